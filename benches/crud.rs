@@ -17,8 +17,8 @@ mod bench {
     use serde_json::Value;
     use sombra::{
         ffi::{
-            Database, DatabaseOptions, MatchSpec, MutationOp, MutationSpec, MutationSummary,
-            PayloadValue, PredicateSpec, ProjectionSpec, QuerySpec,
+            BytesFormat, Database, DatabaseOptions, MatchSpec, MutationOp, MutationSpec,
+            MutationSummary, PayloadValue, PredicateSpec, ProjectionSpec, QuerySpec,
         },
         primitives::pager::Synchronous,
     };
@@ -621,6 +621,21 @@ mod bench {
                 alias: None,
             }],
             distinct: false,
+            operator_timeout_ms: None,
+            include_schema: false,
+            resolve_names: None,
+            reject_cartesian: None,
+            dedup_by: Vec::new(),
+            flatten: false,
+            max_rows: None,
+            nest: None,
+            count_per_start: false,
+            order_by: Vec::new(),
+            tiebreak_desc: None,
+            bytes_format: BytesFormat::default(),
+            limit: None,
+            offset: None,
+            limit_per_group: None,
         }
     }
 
@@ -643,6 +658,21 @@ mod bench {
                 alias: None,
             }],
             distinct: false,
+            operator_timeout_ms: None,
+            include_schema: false,
+            resolve_names: None,
+            reject_cartesian: None,
+            dedup_by: Vec::new(),
+            flatten: false,
+            max_rows: None,
+            nest: None,
+            count_per_start: false,
+            order_by: Vec::new(),
+            tiebreak_desc: None,
+            bytes_format: BytesFormat::default(),
+            limit: None,
+            offset: None,
+            limit_per_group: None,
         }
     }
 
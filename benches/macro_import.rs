@@ -51,6 +51,7 @@ impl ImportHarness {
                 static_labels: Vec::new(),
                 prop_columns: Some(vec!["name".into()]),
                 prop_types: HashMap::new(),
+                id_property: None,
             }),
             edges: Some(EdgeImportConfig {
                 path: self.dataset.edges_csv.clone(),
@@ -63,6 +64,7 @@ impl ImportHarness {
                 exists_cache_capacity: 1024,
                 prop_types: HashMap::new(),
             }),
+            id_offset: 0,
         };
         let summary = run_import(&config, &self.opts).expect("import");
         (summary.nodes_imported, summary.edges_imported)
@@ -24,8 +24,8 @@ mod bench {
         run_import, EdgeImportConfig, ImportConfig, NodeImportConfig,
     };
     use sombra::ffi::{
-        Database, DatabaseOptions, DirectionSpec, EdgeSpec, MatchSpec, PayloadValue, PredicateSpec,
-        ProjectionSpec, QuerySpec,
+        BytesFormat, Database, DatabaseOptions, DirectionSpec, EdgeSpec, MatchSpec, PayloadValue,
+        PredicateSpec, ProjectionSpec, QuerySpec,
     };
 
     const NODE_COUNT: usize = 50_000;
@@ -91,6 +91,7 @@ mod bench {
                 static_labels: Vec::new(),
                 prop_columns: Some(vec!["name".into()]),
                 prop_types: HashMap::new(),
+                id_property: None,
             }),
             edges: Some(EdgeImportConfig {
                 path: dataset.edges_csv.clone(),
@@ -103,6 +104,7 @@ mod bench {
                 exists_cache_capacity: 1024,
                 prop_types: HashMap::new(),
             }),
+            id_offset: 0,
         }
     }
 
@@ -146,6 +148,21 @@ mod bench {
                 },
             ],
             distinct: true,
+            operator_timeout_ms: None,
+            include_schema: false,
+            resolve_names: None,
+            reject_cartesian: None,
+            dedup_by: Vec::new(),
+            flatten: false,
+            max_rows: None,
+            nest: None,
+            count_per_start: false,
+            order_by: Vec::new(),
+            tiebreak_desc: None,
+            bytes_format: BytesFormat::default(),
+            limit: None,
+            offset: None,
+            limit_per_group: None,
         }
     }
 
@@ -187,6 +204,21 @@ mod bench {
                 alias: None,
             }],
             distinct: false,
+            operator_timeout_ms: None,
+            include_schema: false,
+            resolve_names: None,
+            reject_cartesian: None,
+            dedup_by: Vec::new(),
+            flatten: false,
+            max_rows: None,
+            nest: None,
+            count_per_start: false,
+            order_by: Vec::new(),
+            tiebreak_desc: None,
+            bytes_format: BytesFormat::default(),
+            limit: None,
+            offset: None,
+            limit_per_group: None,
         }
     }
 
@@ -220,6 +252,21 @@ mod bench {
                 },
             ],
             distinct: false,
+            operator_timeout_ms: None,
+            include_schema: false,
+            resolve_names: None,
+            reject_cartesian: None,
+            dedup_by: Vec::new(),
+            flatten: false,
+            max_rows: None,
+            nest: None,
+            count_per_start: false,
+            order_by: Vec::new(),
+            tiebreak_desc: None,
+            bytes_format: BytesFormat::default(),
+            limit: None,
+            offset: None,
+            limit_per_group: None,
         }
     }
 }
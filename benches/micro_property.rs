@@ -9,7 +9,9 @@ use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughpu
 use rand::{Rng, SeedableRng};
 use rand_chacha::ChaCha8Rng;
 use sombra::primitives::pager::{PageStore, Pager, PagerOptions};
-use sombra::storage::index::{intersect_sorted, IndexDef, IndexKind, TypeTag, VecPostingStream};
+use sombra::storage::index::{
+    intersect_sorted, IndexDef, IndexKind, IndexState, TypeTag, VecPostingStream,
+};
 use sombra::storage::{Graph, GraphOptions, NodeSpec, PropEntry, PropValue, PropValueOwned};
 use sombra::types::{LabelId, PropId};
 use tempfile::TempDir;
@@ -80,9 +82,12 @@ impl PropertyHarness {
                     &mut write,
                     IndexDef {
                         label,
-                        prop,
+                        props: vec![prop],
                         kind: IndexKind::Chunked,
-                        ty: TypeTag::Int,
+                        types: vec![TypeTag::Int],
+                        max_key_bytes: None,
+                        unique: false,
+                        state: IndexState::Ready,
                     },
                 )
                 .expect("index");
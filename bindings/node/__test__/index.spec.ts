@@ -6,6 +6,7 @@ import path from 'node:path'
 import {
   Database,
   eq,
+  param,
   ErrorCode,
   SombraError,
   AnalyzerError,
@@ -18,6 +19,7 @@ import {
   InvalidArgError,
   NotFoundError,
   ClosedError,
+  DeadlineExceededError,
   wrapNativeError,
 } from '..'
 import { runFluentQueryExample } from '../examples/fluent_query'
@@ -72,6 +74,39 @@ test('streaming query iterates over results', async (t) => {
   t.true(encountered.length >= 3)
 })
 
+test('streamBatched delivers ordered batches no larger than batchSize', async (t) => {
+  const db = Database.open(tempPath())
+  for (let age = 0; age < 10; age++) {
+    db.createNode('User', { name: `user-${age}`, age })
+  }
+  const query = () =>
+    db
+      .query()
+      .match({ var: 'u', label: 'User' })
+      .select([{ var: 'u', prop: 'age' }])
+      .orderBy('u', 'age')
+
+  const batches: Array<Array<number>> = []
+  await query().streamBatched(4, async (batch) => {
+    await new Promise((resolve) => setTimeout(resolve, 1))
+    batches.push(batch.map((row) => row.age as number))
+  })
+  t.deepEqual(batches, [
+    [0, 1, 2, 3],
+    [4, 5, 6, 7],
+    [8, 9],
+  ])
+
+  let calls = 0
+  await query().streamBatched(3, () => {
+    calls += 1
+    return false
+  })
+  t.is(calls, 1)
+
+  await t.throwsAsync(query().streamBatched(0, () => {}), { instanceOf: TypeError })
+})
+
 test('explain produces plan JSON', async (t) => {
   const db = Database.open(tempPath()).seedDemo()
   const plan = await db
@@ -86,6 +121,16 @@ test('explain produces plan JSON', async (t) => {
   t.is(plan.plan[0]?.op, 'Project')
 })
 
+test('direction rejects typos and defaults to out when omitted', async (t) => {
+  const db = Database.open(tempPath()).seedDemo()
+  const err = t.throws(() => db.query().match('User').where('FOLLOWS', 'User').direction('outgong'))
+  t.regex(err?.message ?? '', /invalid direction 'outgong'/)
+
+  const ada = (await db.query().nodes('User').where(eq('name', 'Ada')).execute())[0].n0 as { _id: number }
+  t.deepEqual(db.neighbors(ada._id), db.neighbors(ada._id, { direction: 'out' }))
+  t.throws(() => db.neighbors(ada._id, { direction: 'outgong' }), { message: /invalid direction 'outgong'/ })
+})
+
 test('requestId flows through explain', async (t) => {
   const db = Database.open(tempPath()).seedDemo()
   const plan = await db
@@ -98,6 +143,40 @@ test('requestId flows through explain', async (t) => {
   t.is(plan.request_id, 'req-node')
 })
 
+test('flatten returns bare values for single-column queries', async (t) => {
+  const db = Database.open(tempPath()).seedDemo()
+  const names = await db.query().nodes('User').select('name').flatten().execute()
+  t.true(names.length >= 3)
+  t.true(names.every((name: unknown) => typeof name === 'string'))
+
+  await t.throwsAsync(() => db.query().match('User').where('FOLLOWS', 'User').flatten().execute(), {
+    message: /flatten requires exactly one result column/,
+  })
+})
+
+test('timeoutMs aborts queries past their deadline', async (t) => {
+  const db = Database.open(tempPath()).seedDemo()
+  const names = await db.query().nodes('User').select('name').timeoutMs(60_000).execute()
+  t.true(names.length >= 3)
+
+  const err = await t.throwsAsync(() => db.query().nodes('User').timeoutMs(0).execute())
+  t.true(err instanceof DeadlineExceededError)
+  t.is((err as SombraError).code, ErrorCode.TIMEOUT)
+  t.throws(() => db.query().nodes('User').timeoutMs(-1), { message: /timeoutMs requires a non-negative integer/ })
+})
+
+test('prepared queries bind params on each execute', async (t) => {
+  const db = Database.open(tempPath()).seedDemo()
+  const byName = db.prepare(db.query().nodes('User').where(eq('name', param('name'))).select('name'))
+
+  t.deepEqual(await byName.execute({ name: 'Ada' }), [{ name: 'Ada' }])
+  t.deepEqual(await byName.execute({ name: 'Grace' }), [{ name: 'Grace' }])
+  t.deepEqual(await byName.execute({ name: 'Nobody' }), [])
+  await t.throwsAsync(() => byName.execute({}), {
+    message: /missing value for parameter 'name'/,
+  })
+})
+
 test('mutate supports basic CRUD operations', (t) => {
   const db = Database.open(tempPath()).seedDemo()
   const summary = db.mutate({
@@ -121,6 +200,122 @@ test('mutateMany batches operations', (t) => {
   t.is(summary.createdNodes?.length, 2)
 })
 
+test('getLastNNodesByLabel returns newest ids first', (t) => {
+  const db = Database.open(tempPath())
+  const summary = db.mutateMany(
+    Array.from({ length: 6 }, (_, i) => ({
+      op: 'createNode',
+      labels: [i % 2 ? 'User' : 'Admin'],
+      props: {},
+    })),
+  )
+  const users = summary.createdNodes.filter((_: number, i: number) => i % 2).reverse()
+  t.deepEqual(db.getLastNNodesByLabel('User', 2), users.slice(0, 2))
+  t.deepEqual(db.getLastNNodesByLabel('User', 10), users)
+})
+
+test('getNeighborsByType follows only the requested edge type', (t) => {
+  const db = Database.open(tempPath())
+  const nodes = db.mutateMany(
+    Array.from({ length: 3 }, () => ({ op: 'createNode', labels: ['User'], props: {} })),
+  ).createdNodes
+  const [a, b, c] = nodes
+  db.mutateMany([
+    { op: 'createEdge', src: a, dst: b, ty: 'FOLLOWS', props: {} },
+    { op: 'createEdge', src: a, dst: b, ty: 'FOLLOWS', props: {} },
+    { op: 'createEdge', src: a, dst: c, ty: 'LIKES', props: {} },
+    { op: 'createEdge', src: c, dst: a, ty: 'FOLLOWS', props: {} },
+  ])
+  t.deepEqual(db.getNeighborsByType(a, 'FOLLOWS'), [b])
+  t.deepEqual(db.getNeighborsByType(a, 'FOLLOWS', 'out', false), [b, b])
+  t.deepEqual(db.getNeighborsByType(a, 'LIKES'), [c])
+  t.deepEqual(db.getNeighborsByType(a, 'FOLLOWS', 'in'), [c])
+})
+
+test('getPropertyForNodes matches node records', (t) => {
+  const db = Database.open(tempPath())
+  const ids = db.mutateMany(
+    Array.from({ length: 4 }, (_, i) => ({
+      op: 'createNode',
+      labels: ['User'],
+      props: i % 2 ? { age: i } : { name: `user-${i}`, age: i },
+    })),
+  ).createdNodes.reverse()
+  for (const key of ['name', 'age']) {
+    const expected = ids.map((id: number) => db.getNodeRecord(id)?.properties?.[key] ?? null)
+    t.deepEqual(db.getPropertyForNodes(ids, key), expected)
+  }
+})
+
+test('shortestPathWithEdges reports the parallel edge taken', (t) => {
+  const db = Database.open(tempPath())
+  const [a, b, c] = db.mutateMany(
+    Array.from({ length: 3 }, () => ({ op: 'createNode', labels: ['Stop'], props: {} })),
+  ).createdNodes
+  db.mutateMany([
+    { op: 'createEdge', src: a, dst: b, ty: 'BUS', props: {} },
+    { op: 'createEdge', src: a, dst: b, ty: 'TRAM', props: {} },
+    { op: 'createEdge', src: b, dst: c, ty: 'TRAM', props: {} },
+  ])
+  const hops = db.shortestPathWithEdges(a, c, ['TRAM'])
+  t.truthy(hops)
+  t.deepEqual(
+    hops!.map((hop) => hop.nodeId),
+    [a, b, c],
+  )
+  t.is(hops![0].edgeId, null)
+  for (let i = 1; i < hops!.length; i += 1) {
+    const edge = db.getEdgeRecord(hops![i].edgeId!)
+    t.is(edge?.src, hops![i - 1].nodeId)
+    t.is(edge?.dst, hops![i].nodeId)
+    t.is(edge?.ty, 'TRAM')
+  }
+  t.is(db.shortestPathWithEdges(c, a), null)
+})
+
+test('summary matches individual count calls', (t) => {
+  const db = Database.open(tempPath()).seedDemo()
+  const summary = db.summary()
+  let labelled = 0
+  for (const [label, count] of Object.entries(summary.labels)) {
+    t.is(count, db.countNodesWithLabel(label))
+    labelled += count
+  }
+  t.is(summary.nodes, labelled)
+  let typed = 0
+  for (const [edgeType, count] of Object.entries(summary.edgeTypes)) {
+    t.is(count, db.countEdgesWithType(edgeType))
+    typed += count
+  }
+  t.is(summary.edges, typed)
+  t.true(summary.propertyIndexes >= 1)
+  t.truthy(summary.nodeIdRange)
+})
+
+test('entityNames matches per-record labels and types', (t) => {
+  const db = Database.open(tempPath())
+  const [a, b] = db.mutateMany([
+    { op: 'createNode', labels: ['Person', 'Admin'], props: {} },
+    { op: 'createNode', labels: ['Tag'], props: {} },
+  ]).createdNodes
+  const [e1, e2] = db.mutateMany([
+    { op: 'createEdge', src: a, dst: b, ty: 'TAGGED', props: {} },
+    { op: 'createEdge', src: b, dst: a, ty: 'OWNS', props: {} },
+  ]).createdEdges
+  const missing = 999_999
+  const nodeIds = [b, missing, a]
+  const edgeIds = [e2, e1, missing]
+  const names = db.entityNames(nodeIds, edgeIds)
+  t.deepEqual(
+    names.nodeLabels,
+    nodeIds.map((id) => db.getNodeRecord(id)?.labels ?? null),
+  )
+  t.deepEqual(
+    names.edgeTypes,
+    edgeIds.map((id) => db.getEdgeRecord(id)?.ty ?? null),
+  )
+})
+
 test('mutateBatched chunks large op lists', (t) => {
   const db = Database.open(tempPath())
   const ops = [
@@ -4,8 +4,13 @@ export declare class DatabaseHandle {
 
 }
 
+export declare class PreparedHandle {
+
+}
+
 export declare class StreamHandle {
   next(): NapiResult<any | undefined | null>
+  nextBatch(batchSize: number): NapiResult<Array<any>>
   close(): NapiResult<undefined>
 }
 
@@ -37,11 +42,13 @@ export interface ConnectOptions {
   walSegmentBytes?: number
   walPreallocateSegments?: number
   autocheckpointMs?: number
+  autoRepair?: boolean
 }
 
 export interface BulkLoadOptions {
   nodeChunkSize?: number
   edgeChunkSize?: number
+  deferIndexes?: boolean
 }
 
 
@@ -93,6 +100,8 @@ export declare function databaseBulkLoadNodesTyped(handle: DatabaseHandle, nodes
  */
 export declare function databaseBulkLoadEdgesTyped(handle: DatabaseHandle, edges: Array<TypedEdgeSpec>, options?: BulkLoadOptions | undefined | null): NapiResult<Array<number>>
 
+export declare function databaseEntityNames(handle: DatabaseHandle, nodeIds: Array<number>, edgeIds: Array<number>): NapiResult<any>
+
 export declare function databaseExecute(handle: DatabaseHandle, spec: any): NapiResult<any>
 
 
@@ -102,6 +111,12 @@ export declare function databaseGetEdge(handle: DatabaseHandle, edgeId: number):
 
 export declare function databaseGetNode(handle: DatabaseHandle, nodeId: number): NapiResult<any | undefined | null>
 
+export declare function databaseGetPropertyForNodes(handle: DatabaseHandle, nodeIds: Array<number>, key: string): NapiResult<Array<any>>
+
+export declare function databaseGetNeighborsByType(handle: DatabaseHandle, nodeId: number, edgeType: string, direction?: string | undefined | null, distinct?: boolean | undefined | null): NapiResult<Array<bigint>>
+
+export declare function databaseGetLastNNodesByLabel(handle: DatabaseHandle, label: string, n: number): NapiResult<Array<bigint>>
+
 export declare function databaseIntern(handle: DatabaseHandle, name: string): NapiResult<number>
 
 export declare function databaseListNodesWithLabel(handle: DatabaseHandle, label: string): NapiResult<Array<bigint>>
@@ -110,14 +125,22 @@ export declare function databaseMutate(handle: DatabaseHandle, spec: any): NapiR
 
 export declare function databaseNeighbors(handle: DatabaseHandle, nodeId: number, options?: NeighborOptions | undefined | null): NapiResult<Array<NeighborRecord>>
 
+export declare function databasePageRank(handle: DatabaseHandle, options?: PageRankOptions | undefined | null): NapiResult<Array<PageRankRecord>>
+
 export declare function databasePragmaGet(handle: DatabaseHandle, name: string): NapiResult<any>
 
 export declare function databasePragmaSet(handle: DatabaseHandle, name: string, value: any): NapiResult<any>
 
+export declare function databasePrepare(handle: DatabaseHandle, spec: any): NapiResult<PreparedHandle>
+
 export declare function databaseSeedDemo(handle: DatabaseHandle): NapiResult<undefined>
 
+export declare function databaseShortestPathWithEdges(handle: DatabaseHandle, startId: number, endId: number, edgeTypes?: Array<string> | undefined | null): NapiResult<Array<PathHopRecord> | null>
+
 export declare function databaseStream(handle: DatabaseHandle, spec: any): NapiResult<StreamHandle>
 
+export declare function databaseSummary(handle: DatabaseHandle): NapiResult<any>
+
 export interface NeighborOptions {
   direction?: string
   edgeType?: string
@@ -130,8 +153,27 @@ export interface NeighborRecord {
   typeId: number
 }
 
+export interface PageRankOptions {
+  damping?: number
+  maxIters?: number
+  tolerance?: number
+  edgeType?: string
+}
+
+export interface PageRankRecord {
+  nodeId: number
+  score: number
+}
+
+export interface PathHopRecord {
+  nodeId: number
+  edgeId?: number
+}
+
 export declare function openDatabase(path: string, options?: ConnectOptions | undefined | null): NapiResult<DatabaseHandle>
 
+export declare function preparedExecute(handle: DatabaseHandle, prepared: PreparedHandle, params?: any | undefined | null): NapiResult<any>
+
 /** Result of typed batch creation. */
 export interface TypedBatchResult {
   /** Created node IDs as array of BigInt-compatible values. */
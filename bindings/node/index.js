@@ -573,6 +573,7 @@ if (!nativeBinding) {
 
 module.exports = nativeBinding
 module.exports.DatabaseHandle = nativeBinding.DatabaseHandle
+module.exports.PreparedHandle = nativeBinding.PreparedHandle
 module.exports.StreamHandle = nativeBinding.StreamHandle
 module.exports.databaseBfsTraversal = nativeBinding.databaseBfsTraversal
 module.exports.databaseCancelRequest = nativeBinding.databaseCancelRequest
@@ -581,16 +582,25 @@ module.exports.databaseCountEdgesWithType = nativeBinding.databaseCountEdgesWith
 module.exports.databaseCountNodesWithLabel = nativeBinding.databaseCountNodesWithLabel
 module.exports.databaseCreate = nativeBinding.databaseCreate
 module.exports.databaseCreateTypedBatch = nativeBinding.databaseCreateTypedBatch
+module.exports.databaseEntityNames = nativeBinding.databaseEntityNames
 module.exports.databaseExecute = nativeBinding.databaseExecute
 module.exports.databaseExplain = nativeBinding.databaseExplain
 module.exports.databaseGetEdge = nativeBinding.databaseGetEdge
 module.exports.databaseGetNode = nativeBinding.databaseGetNode
+module.exports.databaseGetPropertyForNodes = nativeBinding.databaseGetPropertyForNodes
+module.exports.databaseGetNeighborsByType = nativeBinding.databaseGetNeighborsByType
+module.exports.databaseGetLastNNodesByLabel = nativeBinding.databaseGetLastNNodesByLabel
 module.exports.databaseIntern = nativeBinding.databaseIntern
 module.exports.databaseListNodesWithLabel = nativeBinding.databaseListNodesWithLabel
 module.exports.databaseMutate = nativeBinding.databaseMutate
 module.exports.databaseNeighbors = nativeBinding.databaseNeighbors
+module.exports.databasePageRank = nativeBinding.databasePageRank
 module.exports.databasePragmaGet = nativeBinding.databasePragmaGet
 module.exports.databasePragmaSet = nativeBinding.databasePragmaSet
+module.exports.databasePrepare = nativeBinding.databasePrepare
 module.exports.databaseSeedDemo = nativeBinding.databaseSeedDemo
+module.exports.databaseShortestPathWithEdges = nativeBinding.databaseShortestPathWithEdges
 module.exports.databaseStream = nativeBinding.databaseStream
+module.exports.databaseSummary = nativeBinding.databaseSummary
 module.exports.openDatabase = nativeBinding.openDatabase
+module.exports.preparedExecute = nativeBinding.preparedExecute
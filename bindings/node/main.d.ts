@@ -32,6 +32,8 @@ export interface ConnectOptions {
   walPreallocateSegments?: number
   /** Auto-checkpoint interval in milliseconds (null to disable) */
   autocheckpointMs?: number | null
+  /** Drop damaged WAL frames, and every commit after them, instead of failing to open */
+  autoRepair?: boolean
   /** Optional runtime schema for validation */
   schema?: NodeSchema
 }
@@ -82,6 +84,10 @@ export const ErrorCode: {
   readonly NOT_FOUND: 'NOT_FOUND'
   /** Database is closed */
   readonly CLOSED: 'CLOSED'
+  /** Operation exceeded its time budget */
+  readonly TIMEOUT: 'TIMEOUT'
+  /** Write would break a schema constraint */
+  readonly CONSTRAINT_VIOLATION: 'CONSTRAINT_VIOLATION'
 }
 
 /** Union type of all error code values */
@@ -158,6 +164,16 @@ export class ClosedError extends SombraError {
   constructor(message: string)
 }
 
+/** Error thrown when an operation exceeds its time budget (e.g. a query operator deadline). */
+export class DeadlineExceededError extends SombraError {
+  constructor(message: string)
+}
+
+/** Error thrown when a write would break a schema constraint (e.g. a unique property index). */
+export class ConstraintViolationError extends SombraError {
+  constructor(message: string)
+}
+
 /** Error thrown when a typed batch operation fails (e.g., duplicate alias, invalid reference). */
 export class BatchError extends SombraError {
   constructor(message: string)
@@ -215,6 +231,30 @@ export interface BfsTraversalOptions {
   maxResults?: number
 }
 
+/**
+ * Options for PageRank scoring.
+ */
+export interface PageRankOptions {
+  /** Probability of following an edge instead of jumping (default 0.85) */
+  damping?: number
+  /** Maximum number of power iterations (default 100) */
+  maxIters?: number
+  /** Convergence threshold on the L1 change between iterations (default 1e-6) */
+  tolerance?: number
+  /** Only follow edges of this type (optional) */
+  edgeType?: string
+}
+
+/**
+ * PageRank score for a single node.
+ */
+export interface PageRankScore {
+  /** The ID of the scored node */
+  nodeId: number
+  /** The node's share of the total rank; scores sum to 1 */
+  score: number
+}
+
 /**
  * A visit record from BFS traversal.
  */
@@ -225,14 +265,119 @@ export interface BfsVisit {
   depth: number
 }
 
+/**
+ * One hop of a path returned by `shortestPathWithEdges`.
+ */
+export interface PathHop {
+  /** The ID of the node reached by this hop */
+  nodeId: number
+  /** The ID of the edge used to reach the node, or null for the start node */
+  edgeId: number | null
+}
+
+/**
+ * Overview returned by `summary`.
+ */
+export interface DatabaseSummary {
+  /** Number of nodes */
+  nodes: number
+  /** Number of edges */
+  edges: number
+  /** Node count per label */
+  labels: Record<string, number>
+  /** Edge count per edge type */
+  edgeTypes: Record<string, number>
+  /** Label indexes on labels that currently have nodes */
+  labelIndexes: number
+  /** Registered property indexes */
+  propertyIndexes: number
+  /** Lowest and highest node ID, or null for an empty graph */
+  nodeIdRange: [number, number] | null
+  /** Lowest and highest edge ID, or null when there are no edges */
+  edgeIdRange: [number, number] | null
+}
+
+/**
+ * Types observed for one property by `inferredSchema`.
+ */
+export interface InferredPropSchema {
+  /** Nodes or edges that carried the property */
+  count: number
+  /** Occurrences per property kind, e.g. `{ int: 3, string: 1 }` */
+  types: Record<string, number>
+  /** Whether more than one non-null kind was observed */
+  conflict: boolean
+}
+
+/**
+ * Properties observed on one label or edge type by `inferredSchema`.
+ */
+export interface InferredOwnerSchema {
+  /** Nodes or edges observed */
+  count: number
+  /** Observed properties keyed by name */
+  props: Record<string, InferredPropSchema>
+}
+
+/**
+ * Overview returned by `inferredSchema`.
+ */
+export interface InferredSchema {
+  /** Observed properties per node label */
+  labels: Record<string, InferredOwnerSchema>
+  /** Observed properties per edge type */
+  edgeTypes: Record<string, InferredOwnerSchema>
+}
+
+/**
+ * Options for `verifyIntegrity`.
+ */
+export interface VerifyIntegrityOptions {
+  /** Depth of the checks; defaults to `'full'` */
+  level?: 'fast' | 'full' | 'catalog'
+  /** Rebuild label and property indexes that disagree with node records before verifying */
+  repairIndexes?: boolean
+}
+
+/**
+ * Result returned by `verifyIntegrity`.
+ */
+export interface IntegrityReport {
+  /** Whether verification found no issues */
+  success: boolean
+  /** Issues found, capped at the first few dozen */
+  errors: string[]
+  /** Nodes scanned */
+  nodes: number
+  /** Edges scanned */
+  edges: number
+  /** Changes made before verifying, such as index rebuilds */
+  repairs: string[]
+}
+
+/**
+ * Names returned by `entityNames`.
+ */
+export interface EntityNames {
+  /** Label names per requested node, or null when the node is missing */
+  nodeLabels: Array<string[] | null>
+  /** Type name per requested edge, or null when the edge is missing */
+  edgeTypes: Array<string | null>
+}
+
 /** Primitive literal values that can be stored in properties */
 export type LiteralValue = string | number | boolean | null
 
 /** Scalar values including binary and date types */
 export type ScalarValue = LiteralValue | Uint8Array | Buffer | Date
 
+/** Placeholder for a value bound when a prepared query executes */
+export interface QueryParam {
+  readonly name: string
+}
+
 /** Values that can be used in predicates */
-export type PredicateLiteral = LiteralValue | Date | Uint8Array | Buffer
+export type PredicateLiteral = LiteralValue | Date | Uint8Array | Buffer | QueryParam
 
 /** Schema definition mapping label names to property definitions */
 export type NodeSchema = Record<string, Record<string, any>>
@@ -279,6 +424,15 @@ type BasePropProjectionField = {
   as?: string | null
 }
 
+/** Aggregate folding every matched row into a single result row. */
+export type AggregateProjectionField = {
+  agg: 'count' | 'sum' | 'avg' | 'min' | 'max'
+  var: string
+  /** Property to aggregate; omit with `count` to count bindings. */
+  prop?: string
+  as: string
+}
+
 /**
  * Options for the between() predicate.
  */
@@ -287,6 +441,26 @@ export interface PredicateBetweenOptions {
   inclusive?: [boolean, boolean]
 }
 
+/**
+ * Callback receiving one batch of streamed rows. Resolve (or return) `false`
+ * to stop the stream.
+ */
+export type BatchCallback<T = Record<string, any>> = (
+  batch: Array<T>,
+) => void | boolean | Promise<void | boolean>
+
+/**
+ * Options for limitPerGroup().
+ */
+export interface LimitPerGroupOptions {
+  /** Variable whose bound node identifies a row's group */
+  groupBy: string
+  /** Maximum rows kept per group */
+  n: number
+  /** Ranking keys within a group; without keys the first rows seen win */
+  orderBy?: Array<{ var: string; prop: string; direction?: 'asc' | 'desc' }>
+}
+
 /**
  * Async iterable stream for query results.
  * Supports early termination via close() and resource disposal.
@@ -302,6 +476,19 @@ export interface QueryStream<T = Record<string, any>> extends AsyncIterable<T> {
   [Symbol.asyncDispose]?(): Promise<void>
 }
 
+/**
+ * Query planned once by `Database.prepare()`.
+ */
+export declare class PreparedQuery {
+  /**
+   * Executes the prepared plan with a value for every `param()` it uses.
+   * @param params - Parameter values keyed by name
+   * @param withMeta - If true, returns full result with metadata
+   */
+  execute(params: Record<string, PredicateLiteral>, withMeta: true): Promise<QueryResultMeta>
+  execute(params?: Record<string, PredicateLiteral>, withMeta?: false): Promise<Array<Record<string, any>>>
+}
+
 /**
  * Query result with metadata.
  * Returned when execute(true) is called.
@@ -457,6 +644,19 @@ export function isNull<K extends string>(prop: K): Expr
  */
 export function isNotNull<K extends string>(prop: K): Expr
 
+/**
+ * Creates a named placeholder for a literal, bound by `PreparedQuery.execute()`.
+ * Parameters cannot appear inside `inList()`.
+ * @param name - Parameter name
+ * @returns Parameter placeholder
+ * @example
+ * ```ts
+ * const byName = db.prepare(db.query().nodes('User').where(eq('name', param('name'))))
+ * await byName.execute({ name: 'Ada' })
+ * ```
+ */
+export function param(name: string): QueryParam
+
 type BindingMap<S extends NodeSchema> = Record<string, TargetLabel<S>>
 
 type BindingLabel<S extends NodeSchema, B extends BindingMap<S>, V extends string> = V extends keyof B
@@ -492,9 +692,12 @@ export type ProjectionField<S extends NodeSchema = DefaultSchema, B extends Bind
   | BaseVarProjectionField
   | BasePropProjectionField
   | TypedPropProjectionField<S, B>
+  | AggregateProjectionField
 
 type ContainsNonPropField<Fields extends ReadonlyArray<ProjectionField>> =
-  Exclude<Fields[number], BasePropProjectionField> extends never ? false : true
+  Exclude<Fields[number], BasePropProjectionField | AggregateProjectionField> extends never
+    ? false
+    : true
 
 type QueryRow<HasVar extends boolean> = Record<string, HasVar extends true ? unknown : ScalarValue>
 
@@ -553,12 +756,21 @@ export interface CreateSummary {
 /** Property input type for mutations */
 export type PropsInput = Record<string, LiteralValue | null>
 
+/**
+ * RFC 6902 JSON Patch operation addressing a top-level property, e.g. `/name`.
+ */
+export type JsonPatchOp =
+  | { op: 'add'; path: string; value: LiteralValue | null }
+  | { op: 'remove'; path: string }
+  | { op: 'replace'; path: string; value: LiteralValue | null }
+
 /**
  * A single mutation operation.
  */
 export type MutationOp =
   | { op: 'createNode'; labels: string[]; props?: PropsInput }
   | { op: 'updateNode'; id: number; set?: PropsInput; unset?: string[] }
+  | { op: 'patchNode'; id: number; patch: JsonPatchOp[] }
   | { op: 'deleteNode'; id: number; cascade?: boolean }
   | { op: 'createEdge'; src: number; dst: number; ty: string; props?: PropsInput }
   | { op: 'updateEdge'; id: number; set?: PropsInput; unset?: string[] }
@@ -965,6 +1177,10 @@ export interface NodeScope<
   bidirectional(flag?: boolean): NodeScope<S, L, HasVar>
   /** Set a request ID for cancellation support */
   requestId(id?: string | null): NodeScope<S, L, HasVar>
+  /** Abort the query once it has run for `ms` milliseconds */
+  timeoutMs(ms?: number | null): NodeScope<S, L, HasVar>
+  /** Return bare values instead of row objects for single-column queries */
+  flatten(flag?: boolean): NodeScope<S, L, HasVar>
   /** Get the query execution plan */
   explain(options?: ExplainOptions): Promise<any>
   /** Execute and return results with metadata */
@@ -973,6 +1189,8 @@ export interface NodeScope<
   execute(withMeta?: false): Promise<Array<QueryRow<HasVar>>>
   /** Execute and return a streaming iterator */
   stream(): QueryStream<QueryRow<HasVar>>
+  /** Stream rows to a callback in batches of at most `batchSize` */
+  streamBatched(batchSize: number, onBatch: BatchCallback<QueryRow<HasVar>>): Promise<void>
 }
 
 type UpdateBindings<S extends NodeSchema, B extends BindingMap<S>, V extends string, L extends TargetLabel<S>> = Omit<
@@ -1144,6 +1362,61 @@ export class QueryBuilder<
    */
   requestId(id?: string | null): QueryBuilder<S, B, HasVar>
 
+  /**
+   * Abort the query once it has run for `ms` milliseconds, failing with a
+   * `DeadlineExceededError`. Streams stop yielding rows past the deadline.
+   * @param ms - Wall-clock budget in milliseconds, or null to clear it
+   * @returns This builder for chaining
+   */
+  timeoutMs(ms?: number | null): QueryBuilder<S, B, HasVar>
+
+  /**
+   * Return each row as its bare value instead of a single-key object.
+   * The query must produce exactly one result column.
+   * @param flag - Whether to flatten rows (default true)
+   * @returns This builder for chaining
+   */
+  flatten(flag?: boolean): QueryBuilder<S, B, HasVar>
+
+  /**
+   * Sort rows by a property before they are returned.
+   * Missing and null values sort last in either direction.
+   * @param varName - Variable whose property is compared
+   * @param prop - Property to sort by
+   * @param direction - 'asc' (default) or 'desc'
+   * @returns This builder for chaining
+   */
+  orderBy(varName: string, prop: string, direction?: 'asc' | 'desc'): QueryBuilder<S, B, HasVar>
+
+  /**
+   * Return at most `count` rows, counted after sorting and `offset`.
+   * @param count - Maximum number of rows
+   * @returns This builder for chaining
+   */
+  limit(count: number): QueryBuilder<S, B, HasVar>
+
+  /**
+   * Skip the first `count` rows, counted after sorting.
+   * @param count - Number of rows to skip
+   * @returns This builder for chaining
+   */
+  offset(count: number): QueryBuilder<S, B, HasVar>
+
+  /**
+   * Keep only the first `n` rows of each group, ranked by `orderBy`, before
+   * the query-wide sort, offset, and limit apply.
+   * @param options - Group variable, per-group cap, and ranking keys
+   * @returns This builder for chaining
+   */
+  limitPerGroup(options: LimitPerGroupOptions): QueryBuilder<S, B, HasVar>
+
+  /**
+   * Choose how bytes values are encoded in result rows.
+   * @param format - 'base64' strings (default) or 'array' of octets
+   * @returns This builder for chaining
+   */
+  bytesFormat(format: 'base64' | 'array'): QueryBuilder<S, B, HasVar>
+
   /**
    * Select which fields to return in results.
    * @param fields - Array of projection fields
@@ -1179,6 +1452,16 @@ export class QueryBuilder<
    * @returns Async iterable stream of rows
    */
   stream(): QueryStream<QueryRow<HasVar>>
+
+  /**
+   * Execute the query and hand rows to `onBatch` in batches of at most
+   * `batchSize`; only the last batch may be smaller.
+   * The next batch is pulled once the callback's returned promise settles.
+   * Returning `false` stops the stream early.
+   * @param batchSize - Maximum rows per batch
+   * @param onBatch - Receives each batch in result order
+   */
+  streamBatched(batchSize: number, onBatch: BatchCallback<QueryRow<HasVar>>): Promise<void>
 }
 
 /**
@@ -1241,6 +1524,15 @@ export class Database<S extends NodeSchema = DefaultSchema> {
    */
   query(): QueryBuilder<S, {}, true>
 
+  /**
+   * Plans a query once for repeated execution with different parameters.
+   * @param query - Query builder or raw query spec using `param()` placeholders
+   * @returns A PreparedQuery handle
+   */
+  prepare(
+    query: QueryBuilder<S, any, any> | NodeScope<S, any, any> | Record<string, any>,
+  ): PreparedQuery
+
   /**
    * Set or update the runtime schema for validation.
    * @param schema - The schema to use, or null to clear
@@ -1380,6 +1672,22 @@ export class Database<S extends NodeSchema = DefaultSchema> {
    */
   getNodeRecord(nodeId: number): Record<string, any> | null
 
+  /**
+   * Fetch one property for many nodes in a single call.
+   * @param ids - Node IDs to read
+   * @param key - Property name
+   * @returns Property values in the order of `ids`, null where missing
+   */
+  getPropertyForNodes(ids: number[], key: string): any[]
+
+  /**
+   * Resolve label names for nodes and type names for edges in a single call.
+   * @param nodeIds - Node IDs whose labels to resolve
+   * @param edgeIds - Edge IDs whose types to resolve
+   * @returns Names in the order of the inputs, null where an entity is missing
+   */
+  entityNames(nodeIds?: number[], edgeIds?: number[]): EntityNames
+
   /**
    * Get an edge's full record.
    * @param edgeId - The edge ID
@@ -1401,6 +1709,29 @@ export class Database<S extends NodeSchema = DefaultSchema> {
    */
   countEdgesWithType(ty: string): number
 
+  /**
+   * Summarize the database: totals, counts per label and edge type, index
+   * counts, and id ranges.
+   * @returns The summary
+   */
+  summary(): DatabaseSummary
+
+  /**
+   * Property kinds observed by typed bulk loads run with `captureSchema`.
+   * Held in memory; starts empty each time the database is opened.
+   * @returns The inferred schema per label and edge type
+   */
+  inferredSchema(): InferredSchema
+
+  /**
+   * Check storage structures for corruption, optionally rebuilding drifted
+   * indexes first. Adjacency and index checks run at the `'full'` level and
+   * above.
+   * @param options - Verification level and repair switches
+   * @returns The verification report
+   */
+  verifyIntegrity(options?: VerifyIntegrityOptions): IntegrityReport
+
   /**
    * List all node IDs with a specific label.
    * @param label - The label to list
@@ -1408,6 +1739,14 @@ export class Database<S extends NodeSchema = DefaultSchema> {
    */
   listNodesWithLabel(label: string): number[]
 
+  /**
+   * List the most recently created node IDs carrying a label.
+   * @param label - The label to scan
+   * @param n - Maximum number of IDs to return
+   * @returns Up to `n` node IDs, highest first
+   */
+  getLastNNodesByLabel(label: string, n: number): number[]
+
   /**
    * Get neighbors of a node.
    * @param nodeId - The node ID
@@ -1434,6 +1773,21 @@ export class Database<S extends NodeSchema = DefaultSchema> {
    */
   getIncomingNeighbors(nodeId: number, edgeType?: string, distinct?: boolean): number[]
 
+  /**
+   * Get neighbor node IDs reached through a single edge type.
+   * @param nodeId - The node ID
+   * @param edgeType - Edge type to follow
+   * @param direction - Traversal direction (default: 'out')
+   * @param distinct - Deduplicate results (default: true)
+   * @returns Array of neighbor node IDs
+   */
+  getNeighborsByType(
+    nodeId: number,
+    edgeType: string,
+    direction?: 'out' | 'in' | 'both',
+    distinct?: boolean,
+  ): number[]
+
   /**
    * Perform a breadth-first search traversal.
    * @param nodeId - Starting node ID
@@ -1442,6 +1796,22 @@ export class Database<S extends NodeSchema = DefaultSchema> {
    * @returns Array of visited nodes with depths
    */
   bfsTraversal(nodeId: number, maxDepth: number, options?: BfsTraversalOptions): BfsVisit[]
+
+  /**
+   * Score every node by PageRank over outgoing edges.
+   * @param options - Damping, iteration and edge type settings
+   * @returns Node ids with their scores, in node id order
+   */
+  pageRank(options?: PageRankOptions): PageRankScore[]
+
+  /**
+   * Find a shortest outgoing path, reporting the edge taken at each hop.
+   * @param startId - Starting node ID
+   * @param endId - Target node ID
+   * @param edgeTypes - Edge types to follow (optional; all types when omitted)
+   * @returns The hops from start to end, or null when end is unreachable
+   */
+  shortestPathWithEdges(startId: number, endId: number, edgeTypes?: string[]): PathHop[] | null
 }
 
 /**
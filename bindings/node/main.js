@@ -3,6 +3,7 @@ const native = require('./index.js')
 
 const CREATE_HANDLE_SYMBOL = Symbol('sombra.createHandle')
 const EXPR_BRAND = Symbol('sombra.expr')
+const PARAM_BRAND = Symbol('sombra.param')
 const NS_PER_MILLISECOND = 1_000_000n
 const I64_MIN = -(1n << 63n)
 const I64_MAX = (1n << 63n) - 1n
@@ -32,6 +33,8 @@ const ErrorCode = Object.freeze({
   INVALID_ARG: 'INVALID_ARG',
   NOT_FOUND: 'NOT_FOUND',
   CLOSED: 'CLOSED',
+  TIMEOUT: 'TIMEOUT',
+  CONSTRAINT_VIOLATION: 'CONSTRAINT_VIOLATION',
 })
 
 /**
@@ -162,6 +165,27 @@ class ClosedError extends SombraError {
   }
 }
 
+/**
+ * Error thrown when an operation exceeds its time budget.
+ */
+class DeadlineExceededError extends SombraError {
+  constructor(message) {
+    super(message, ErrorCode.TIMEOUT)
+    this.name = 'DeadlineExceededError'
+  }
+}
+
+/**
+ * Error thrown when a write would break a schema constraint, such as a
+ * unique property index.
+ */
+class ConstraintViolationError extends SombraError {
+  constructor(message) {
+    super(message, ErrorCode.CONSTRAINT_VIOLATION)
+    this.name = 'ConstraintViolationError'
+  }
+}
+
 /**
  * Map of error code strings to their corresponding error classes.
  */
@@ -178,6 +202,8 @@ const ERROR_CLASS_MAP = {
   [ErrorCode.INVALID_ARG]: InvalidArgError,
   [ErrorCode.NOT_FOUND]: NotFoundError,
   [ErrorCode.CLOSED]: ClosedError,
+  [ErrorCode.TIMEOUT]: DeadlineExceededError,
+  [ErrorCode.CONSTRAINT_VIOLATION]: ConstraintViolationError,
 }
 
 /**
@@ -304,7 +330,21 @@ function encodeBytesLiteral(bytes) {
   throw new TypeError('bytes literals require Buffer or btoa support in this environment')
 }
 
+function paramRef(name) {
+  if (typeof name !== 'string' || name.trim() === '') {
+    throw new TypeError('param() requires a non-empty parameter name')
+  }
+  return Object.freeze({ __param: PARAM_BRAND, name })
+}
+
+function isParam(value) {
+  return Boolean(value && value.__param === PARAM_BRAND)
+}
+
 function literalValue(value) {
+  if (isParam(value)) {
+    return { t: 'Param', v: value.name }
+  }
   if (value === null || value === undefined) {
     return { t: 'Null' }
   }
@@ -705,6 +745,36 @@ class QueryStream {
   }
 }
 
+class PreparedQuery {
+  constructor(db, handle) {
+    this._db = db
+    this._handle = handle
+  }
+
+  /**
+   * Runs the prepared plan with `params` bound in place of each `param()`.
+   * Values are encoded like any other predicate literal.
+   */
+  async execute(params = {}, withMeta = false) {
+    if (params === null || typeof params !== 'object' || Array.isArray(params)) {
+      throw new TypeError('prepared query params must be an object')
+    }
+    const encoded = {}
+    for (const [name, value] of Object.entries(params)) {
+      encoded[name] = literalValue(value)
+    }
+    const payload = this._db._executePrepared(this._handle, encoded)
+    if (withMeta) {
+      return payload
+    }
+    const rows = payload && Array.isArray(payload.rows) ? payload.rows : null
+    if (!rows) {
+      throw new Error('query execution payload missing rows array')
+    }
+    return rows
+  }
+}
+
 class PredicateBuilder {
   constructor(parent, varName, mode = 'and', combinator = 'and', validator = null) {
     if (typeof varName !== 'string' || varName.trim() === '') {
@@ -966,6 +1036,16 @@ class NodeScope {
     return this
   }
 
+  timeoutMs(ms) {
+    this._builder.timeoutMs(ms)
+    return this
+  }
+
+  flatten(flag = true) {
+    this._builder.flatten(flag)
+    return this
+  }
+
   explain(options) {
     return this._builder.explain(options)
   }
@@ -978,6 +1058,10 @@ class NodeScope {
     return this._builder.stream()
   }
 
+  streamBatched(batchSize, onBatch) {
+    return this._builder.streamBatched(batchSize, onBatch)
+  }
+
   _stampExpr(exprOrFn, ctx) {
     let exprValue = exprOrFn
     if (typeof exprValue === 'function') {
@@ -1125,6 +1209,13 @@ class QueryBuilder {
     this._nextVarIdx = 0
     this._pendingDirection = 'out'
     this._requestId = null
+    this._timeoutMs = null
+    this._flatten = false
+    this._orderBy = []
+    this._bytesFormat = null
+    this._limit = null
+    this._offset = null
+    this._limitPerGroup = null
   }
 
   nodes(label) {
@@ -1247,7 +1338,7 @@ class QueryBuilder {
 
   direction(dir) {
     if (dir !== 'out' && dir !== 'in' && dir !== 'both') {
-      throw new Error(`invalid direction: ${dir}`)
+      throw new Error(`invalid direction '${dir}', expected 'out', 'in', or 'both'`)
     }
     this._pendingDirection = dir
     return this
@@ -1278,6 +1369,83 @@ class QueryBuilder {
     return this
   }
 
+  timeoutMs(ms) {
+    if (ms === null || ms === undefined) {
+      this._timeoutMs = null
+      return this
+    }
+    if (!Number.isInteger(ms) || ms < 0) {
+      throw new TypeError('timeoutMs requires a non-negative integer')
+    }
+    this._timeoutMs = ms
+    return this
+  }
+
+  flatten(flag = true) {
+    this._flatten = flag !== false
+    return this
+  }
+
+  orderBy(varName, prop, direction = 'asc') {
+    this._assertMatch(varName)
+    if (typeof prop !== 'string' || prop.trim() === '') {
+      throw new TypeError('orderBy requires a non-empty property name')
+    }
+    if (direction !== 'asc' && direction !== 'desc') {
+      throw new TypeError("orderBy direction must be 'asc' or 'desc'")
+    }
+    this._orderBy.push({ var: varName, prop, desc: direction === 'desc' })
+    return this
+  }
+
+  limit(count) {
+    if (!Number.isInteger(count) || count < 0) {
+      throw new TypeError('limit requires a non-negative integer')
+    }
+    this._limit = count
+    return this
+  }
+
+  offset(count) {
+    if (!Number.isInteger(count) || count < 0) {
+      throw new TypeError('offset requires a non-negative integer')
+    }
+    this._offset = count
+    return this
+  }
+
+  limitPerGroup(options) {
+    const { groupBy, n, orderBy = [] } = options ?? {}
+    this._assertMatch(groupBy)
+    if (!Number.isInteger(n) || n < 0) {
+      throw new TypeError('limitPerGroup requires a non-negative integer n')
+    }
+    if (!Array.isArray(orderBy)) {
+      throw new TypeError('limitPerGroup orderBy must be an array')
+    }
+    const keys = orderBy.map((key) => {
+      const direction = key?.direction ?? 'asc'
+      this._assertMatch(key?.var)
+      if (typeof key.prop !== 'string' || key.prop.trim() === '') {
+        throw new TypeError('limitPerGroup orderBy requires a non-empty property name')
+      }
+      if (direction !== 'asc' && direction !== 'desc') {
+        throw new TypeError("limitPerGroup orderBy direction must be 'asc' or 'desc'")
+      }
+      return { var: key.var, prop: key.prop, desc: direction === 'desc' }
+    })
+    this._limitPerGroup = { groupBy, n, orderBy: keys }
+    return this
+  }
+
+  bytesFormat(format) {
+    if (format !== 'base64' && format !== 'array') {
+      throw new TypeError("bytesFormat must be 'base64' or 'array'")
+    }
+    this._bytesFormat = format
+    return this
+  }
+
   select(fields) {
     const projections = []
     for (const field of fields) {
@@ -1287,6 +1455,25 @@ class QueryBuilder {
         continue
       }
       if (field && typeof field === 'object') {
+        if ('agg' in field) {
+          const varName = field.var
+          if (!['count', 'sum', 'avg', 'min', 'max'].includes(field.agg)) {
+            throw new TypeError("aggregate must be 'count', 'sum', 'avg', 'min', or 'max'")
+          }
+          if (typeof varName !== 'string' || !varName) {
+            throw new TypeError('aggregate projection requires a variable name')
+          }
+          if (typeof field.as !== 'string' || !field.as) {
+            throw new TypeError('aggregate projection requires an alias')
+          }
+          this._assertMatch(varName)
+          let prop = null
+          if (field.prop !== undefined && field.prop !== null) {
+            prop = this._makePropValidator(varName)(field.prop)
+          }
+          projections.push({ kind: 'aggregate', func: field.agg, var: varName, prop, alias: field.as })
+          continue
+        }
         if ('prop' in field) {
           const varName = field.var
           const prop = field.prop
@@ -1348,6 +1535,16 @@ class QueryBuilder {
     return new QueryStream(handle)
   }
 
+  async streamBatched(batchSize, onBatch) {
+    if (!Number.isInteger(batchSize) || batchSize < 1 || batchSize > 0xffffffff) {
+      throw new TypeError('streamBatched() batchSize must be a positive integer')
+    }
+    if (typeof onBatch !== 'function') {
+      throw new TypeError('streamBatched() requires an onBatch callback')
+    }
+    return this._db._streamBatched(this._build(), batchSize, onBatch)
+  }
+
   /** @internal */
   _build() {
     return this._buildSpec()
@@ -1424,6 +1621,28 @@ class QueryBuilder {
     if (this._requestId) {
       spec.request_id = this._requestId
     }
+    if (this._timeoutMs !== null) {
+      spec.timeoutMs = this._timeoutMs
+    }
+    if (this._flatten) {
+      spec.flatten = true
+    }
+    if (this._orderBy.length > 0) {
+      spec.orderBy = this._orderBy.map((key) => ({ ...key }))
+    }
+    if (this._bytesFormat) {
+      spec.bytesFormat = this._bytesFormat
+    }
+    if (this._limit !== null) {
+      spec.limit = this._limit
+    }
+    if (this._offset !== null) {
+      spec.offset = this._offset
+    }
+    if (this._limitPerGroup !== null) {
+      const { groupBy, n, orderBy } = this._limitPerGroup
+      spec.limitPerGroup = { groupBy, n, orderBy: orderBy.map((key) => ({ ...key })) }
+    }
     if (this._predicate) {
       spec.predicate = cloneSpec(this._predicate)
     }
@@ -2365,6 +2584,28 @@ class Database {
     return record ?? null
   }
 
+  getPropertyForNodes(ids, key) {
+    this._assertOpen()
+    if (!Array.isArray(ids)) {
+      throw new TypeError('getPropertyForNodes requires an array of node ids')
+    }
+    const nodeIds = ids.map((id) => assertNodeId(id, 'getPropertyForNodes'))
+    if (typeof key !== 'string' || key.trim() === '') {
+      throw new TypeError('getPropertyForNodes requires a non-empty string key')
+    }
+    return callNative(native.databaseGetPropertyForNodes, this._handle, nodeIds, key)
+  }
+
+  entityNames(nodeIds = [], edgeIds = []) {
+    this._assertOpen()
+    if (!Array.isArray(nodeIds) || !Array.isArray(edgeIds)) {
+      throw new TypeError('entityNames requires arrays of node and edge ids')
+    }
+    const nodes = nodeIds.map((id) => assertNodeId(id, 'entityNames'))
+    const edges = edgeIds.map((id) => assertEdgeId(id, 'entityNames'))
+    return callNative(native.databaseEntityNames, this._handle, nodes, edges)
+  }
+
   getEdgeRecord(edgeId) {
     this._assertOpen()
     const id = assertEdgeId(edgeId, 'getEdgeRecord')
@@ -2390,6 +2631,31 @@ class Database {
     return this._countEdgesWithTypeFallback(normalized)
   }
 
+  summary() {
+    this._assertOpen()
+    return callNative(native.databaseSummary, this._handle)
+  }
+
+  inferredSchema() {
+    this._assertOpen()
+    return callNative(native.databaseInferredSchema, this._handle)
+  }
+
+  verifyIntegrity(options = {}) {
+    this._assertOpen()
+    if (options === null || typeof options !== 'object' || Array.isArray(options)) {
+      throw new TypeError('verifyIntegrity options must be an object')
+    }
+    const { level, repairIndexes } = options
+    if (level !== undefined && !['fast', 'full', 'catalog'].includes(level)) {
+      throw new TypeError("verifyIntegrity level must be 'fast', 'full', or 'catalog'")
+    }
+    if (repairIndexes !== undefined && typeof repairIndexes !== 'boolean') {
+      throw new TypeError('verifyIntegrity repairIndexes must be a boolean')
+    }
+    return callNative(native.databaseVerifyIntegrity, this._handle, { level, repairIndexes })
+  }
+
   listNodesWithLabel(label) {
     this._assertOpen()
     const normalized = assertLabel(label, 'listNodesWithLabel')
@@ -2402,6 +2668,18 @@ class Database {
     return this._listNodesWithLabelFallback(normalized)
   }
 
+  getLastNNodesByLabel(label, n) {
+    this._assertOpen()
+    const normalized = assertLabel(label, 'getLastNNodesByLabel')
+    if (!Number.isInteger(n) || n < 0) {
+      throw new TypeError('getLastNNodesByLabel requires a non-negative integer count')
+    }
+    return normalizeIdList(
+      callNative(native.databaseGetLastNNodesByLabel, this._handle, normalized, n),
+      'getLastNNodesByLabel result',
+    )
+  }
+
   neighbors(nodeId, options) {
     this._assertOpen()
     const id = assertNodeId(nodeId, 'neighbors')
@@ -2441,6 +2719,21 @@ class Database {
     return this.neighbors(nodeId, opts).map((neighbor) => neighbor.nodeId)
   }
 
+  getNeighborsByType(nodeId, edgeType, direction = 'out', distinct = true) {
+    this._assertOpen()
+    const id = assertNodeId(nodeId, 'getNeighborsByType')
+    if (typeof edgeType !== 'string' || edgeType.trim() === '') {
+      throw new TypeError('getNeighborsByType requires a non-empty string edgeType')
+    }
+    if (typeof distinct !== 'boolean') {
+      throw new TypeError('distinct must be a boolean')
+    }
+    return normalizeIdList(
+      callNative(native.databaseGetNeighborsByType, this._handle, id, edgeType, direction, distinct),
+      'getNeighborsByType result',
+    )
+  }
+
   bfsTraversal(nodeId, maxDepth, options) {
     this._assertOpen()
     const id = assertNodeId(nodeId, 'bfsTraversal')
@@ -2453,6 +2746,36 @@ class Database {
     return callNative(native.databaseBfsTraversal, this._handle, id, maxDepth, options ?? undefined)
   }
 
+  pageRank(options) {
+    this._assertOpen()
+    if (options !== undefined && (options === null || typeof options !== 'object')) {
+      throw new TypeError('pageRank options must be an object when provided')
+    }
+    return callNative(native.databasePageRank, this._handle, options ?? undefined)
+  }
+
+  shortestPathWithEdges(startId, endId, edgeTypes) {
+    this._assertOpen()
+    const start = assertNodeId(startId, 'shortestPathWithEdges')
+    const end = assertNodeId(endId, 'shortestPathWithEdges')
+    if (edgeTypes !== undefined && edgeTypes !== null) {
+      if (!Array.isArray(edgeTypes) || edgeTypes.some((ty) => typeof ty !== 'string' || ty.trim() === '')) {
+        throw new TypeError('edgeTypes must be an array of non-empty strings when provided')
+      }
+    }
+    const hops = callNative(
+      native.databaseShortestPathWithEdges,
+      this._handle,
+      start,
+      end,
+      edgeTypes ?? undefined,
+    )
+    if (hops === null || hops === undefined) {
+      return null
+    }
+    return hops.map((hop) => ({ nodeId: hop.nodeId, edgeId: hop.edgeId ?? null }))
+  }
+
   _execute(spec) {
     this._assertOpen()
     return callNative(native.databaseExecute, this._handle, spec)
@@ -2469,6 +2792,47 @@ class Database {
     return callNative(native.databaseStream, this._handle, spec)
   }
 
+  /**
+   * Plans `query` once and returns a handle that executes it with different
+   * parameter values. Use `param(name)` where a literal would go.
+   */
+  prepare(query) {
+    this._assertOpen()
+    const builder = query instanceof NodeScope ? query._builder : query
+    const spec = builder instanceof QueryBuilder ? builder._build() : builder
+    if (spec === null || typeof spec !== 'object') {
+      throw new TypeError('prepare() requires a query builder or query spec')
+    }
+    return new PreparedQuery(this, callNative(native.databasePrepare, this._handle, spec))
+  }
+
+  _executePrepared(handle, params) {
+    this._assertOpen()
+    return callNative(native.preparedExecute, this._handle, handle, params)
+  }
+
+  /**
+   * Pulls `batchSize` rows at a time and hands each batch to `onBatch`. The
+   * next batch is only pulled once the callback (or the promise it returns)
+   * settles; resolving to `false` stops the stream early.
+   */
+  async _streamBatched(spec, batchSize, onBatch) {
+    const handle = this._stream(spec)
+    try {
+      while (true) {
+        const batch = callNative(handle.nextBatch.bind(handle), batchSize)
+        if (batch.length === 0) {
+          return
+        }
+        if ((await onBatch(batch)) === false) {
+          return
+        }
+      }
+    } finally {
+      callNative(handle.close.bind(handle))
+    }
+  }
+
   _listNodesWithLabelFallback(label) {
     const matchVar = '__sombra_list_nodes'
     const builder = new QueryBuilder(this, this._schema)
@@ -2524,6 +2888,7 @@ module.exports = {
   Database,
   PredicateBuilder,
   QueryBuilder,
+  PreparedQuery,
   NodeScope,
   BatchCreateBuilder,
   BatchNodeHandle,
@@ -2546,6 +2911,7 @@ module.exports = {
   exists: existsExpr,
   isNull: isNullExpr,
   isNotNull: isNotNullExpr,
+  param: paramRef,
   // Batch utilities
   estimateBatchSize,
   batchRecords,
@@ -2562,6 +2928,8 @@ module.exports = {
   InvalidArgError,
   NotFoundError,
   ClosedError,
+  DeadlineExceededError,
+  ConstraintViolationError,
   BatchError,
   // Error utilities
   wrapNativeError,
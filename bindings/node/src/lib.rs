@@ -9,9 +9,13 @@ use napi::bindgen_prelude::BigInt;
 use napi::{bindgen_prelude::Result as NapiResult, Error as NapiError, Status};
 use napi_derive::napi;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{Map, Value};
 use sombra::{
-  ffi::{BfsVisitInfo, Database, DatabaseOptions, FfiError, NeighborInfo, QueryStream},
+  admin::{self, GraphHandle, RepairOptions, VerifyLevel},
+  ffi::{
+    BfsVisitInfo, Database, DatabaseOptions, FfiError, NeighborInfo, PageRankScore, PreparedQuery,
+    QueryStream,
+  },
   primitives::pager::{PagerOptions, Synchronous},
   storage::Dir,
 };
@@ -46,6 +50,8 @@ pub struct ConnectOptions {
   pub wal_preallocate_segments: Option<u32>,
   #[napi(js_name = "autocheckpointMs")]
   pub autocheckpoint_ms: Option<u32>,
+  #[napi(js_name = "autoRepair")]
+  pub auto_repair: Option<bool>,
 }
 
 #[napi]
@@ -80,6 +86,11 @@ pub struct StreamHandle {
   inner: Mutex<Option<QueryStream>>,
 }
 
+#[napi]
+pub struct PreparedHandle {
+  inner: PreparedQuery,
+}
+
 #[derive(Debug, Default, Deserialize)]
 #[napi(object)]
 pub struct NeighborOptions {
@@ -89,6 +100,24 @@ pub struct NeighborOptions {
   pub distinct: Option<bool>,
 }
 
+#[derive(Debug, Default, Deserialize)]
+#[napi(object)]
+pub struct VerifyIntegrityOptions {
+  pub level: Option<String>,
+  #[napi(js_name = "repairIndexes")]
+  pub repair_indexes: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct IntegrityReport {
+  success: bool,
+  errors: Vec<String>,
+  nodes: u64,
+  edges: u64,
+  repairs: Vec<String>,
+}
+
 #[derive(Debug, Default, Deserialize)]
 #[napi(object)]
 pub struct BfsTraversalOptions {
@@ -99,6 +128,17 @@ pub struct BfsTraversalOptions {
   pub max_results: Option<u32>,
 }
 
+#[derive(Debug, Default, Deserialize)]
+#[napi(object)]
+pub struct PageRankOptions {
+  pub damping: Option<f64>,
+  #[napi(js_name = "maxIters")]
+  pub max_iters: Option<u32>,
+  pub tolerance: Option<f64>,
+  #[napi(js_name = "edgeType")]
+  pub edge_type: Option<String>,
+}
+
 #[derive(Debug, Clone)]
 #[napi(object)]
 pub struct NeighborRecord {
@@ -118,6 +158,23 @@ pub struct BfsVisitRecord {
   pub depth: u32,
 }
 
+#[derive(Debug, Clone)]
+#[napi(object)]
+pub struct PageRankRecord {
+  #[napi(js_name = "nodeId")]
+  pub node_id: i64,
+  pub score: f64,
+}
+
+#[derive(Debug, Clone)]
+#[napi(object)]
+pub struct PathHopRecord {
+  #[napi(js_name = "nodeId")]
+  pub node_id: i64,
+  #[napi(js_name = "edgeId")]
+  pub edge_id: Option<i64>,
+}
+
 fn js_id_from_u64(value: u64, ctx: &str) -> NapiResult<i64> {
   if value > i64::MAX as u64 {
     Err(NapiError::new(
@@ -183,6 +240,7 @@ pub fn openDatabase(path: String, options: Option<ConnectOptions>) -> NapiResult
     create_if_missing: opts.create_if_missing.unwrap_or(true),
     pager: pager_opts,
     distinct_neighbors_default: opts.distinct_neighbors_default.unwrap_or(false),
+    auto_repair: opts.auto_repair.unwrap_or(false),
     ..DatabaseOptions::default()
   };
 
@@ -213,6 +271,35 @@ pub fn databaseStream(handle: &DatabaseHandle, spec: Value) -> NapiResult<Stream
   })
 }
 
+#[allow(non_snake_case)]
+#[napi]
+pub fn databasePrepare(handle: &DatabaseHandle, spec: Value) -> NapiResult<PreparedHandle> {
+  handle.with_db(|db| {
+    let inner = db.prepare_json(&spec).map_err(to_napi_err)?;
+    Ok(PreparedHandle { inner })
+  })
+}
+
+#[allow(non_snake_case)]
+#[napi]
+pub fn preparedExecute(
+  handle: &DatabaseHandle,
+  prepared: &PreparedHandle,
+  params: Option<Value>,
+) -> NapiResult<Value> {
+  let params = match params {
+    None | Some(Value::Null) => Map::new(),
+    Some(Value::Object(map)) => map,
+    Some(_) => {
+      return Err(NapiError::new(
+        Status::InvalidArg,
+        "prepared query params must be an object",
+      ))
+    }
+  };
+  handle.with_db(|db| prepared.inner.execute(db, &params).map_err(to_napi_err))
+}
+
 #[allow(non_snake_case)]
 #[napi]
 pub fn databaseMutate(handle: &DatabaseHandle, spec: Value) -> NapiResult<Value> {
@@ -265,6 +352,44 @@ pub fn databaseGetNode(handle: &DatabaseHandle, node_id: i64) -> NapiResult<Opti
   })
 }
 
+#[allow(non_snake_case)]
+#[napi]
+pub fn databaseGetPropertyForNodes(
+  handle: &DatabaseHandle,
+  node_ids: Vec<i64>,
+  key: String,
+) -> NapiResult<Vec<Value>> {
+  let ids = node_ids
+    .into_iter()
+    .map(|id| u64_from_js_id(id, "getPropertyForNodes"))
+    .collect::<NapiResult<Vec<_>>>()?;
+  handle.with_db(|db| {
+    db.get_property_for_nodes(&ids, &key)
+      .map_err(to_napi_err)
+  })
+}
+
+#[allow(non_snake_case)]
+#[napi]
+pub fn databaseEntityNames(
+  handle: &DatabaseHandle,
+  node_ids: Vec<i64>,
+  edge_ids: Vec<i64>,
+) -> NapiResult<Value> {
+  let nodes = node_ids
+    .into_iter()
+    .map(|id| u64_from_js_id(id, "entityNames"))
+    .collect::<NapiResult<Vec<_>>>()?;
+  let edges = edge_ids
+    .into_iter()
+    .map(|id| u64_from_js_id(id, "entityNames"))
+    .collect::<NapiResult<Vec<_>>>()?;
+  handle.with_db(|db| {
+    let names = db.entity_names(&nodes, &edges).map_err(to_napi_err)?;
+    to_json_value(names)
+  })
+}
+
 #[allow(non_snake_case)]
 #[napi]
 pub fn databaseGetEdge(handle: &DatabaseHandle, edge_id: i64) -> NapiResult<Option<Value>> {
@@ -287,12 +412,78 @@ pub fn databaseCountEdgesWithType(handle: &DatabaseHandle, ty: String) -> NapiRe
   handle.with_db(|db| db.count_edges_with_type(&ty).map_err(to_napi_err))
 }
 
+#[allow(non_snake_case)]
+#[napi]
+pub fn databaseSummary(handle: &DatabaseHandle) -> NapiResult<Value> {
+  handle.with_db(|db| db.summary_json().map_err(to_napi_err))
+}
+
+#[allow(non_snake_case)]
+#[napi]
+pub fn databaseInferredSchema(handle: &DatabaseHandle) -> NapiResult<Value> {
+  handle.with_db(|db| db.inferred_schema_json().map_err(to_napi_err))
+}
+
+#[allow(non_snake_case)]
+#[napi]
+pub fn databaseVerifyIntegrity(
+  handle: &DatabaseHandle,
+  options: Option<VerifyIntegrityOptions>,
+) -> NapiResult<Value> {
+  let opts = options.unwrap_or_default();
+  let level = parse_verify_level(opts.level.as_deref())?;
+  let repair_indexes = opts.repair_indexes.unwrap_or(false);
+  handle.with_db(|db| {
+    let (pager, graph, dict) = db.storage_parts();
+    let graph = GraphHandle { pager, graph, dict };
+    let report = if repair_indexes {
+      let options = RepairOptions {
+        adjacency: false,
+        indexes: true,
+        freelist: false,
+      };
+      admin::repair_graph(&graph, level, &options)
+    } else {
+      admin::verify_graph(&graph, level)
+    }
+    .map_err(|err| napi::Error::from_reason(err.to_string()))?;
+    to_json_value(IntegrityReport {
+      success: report.success,
+      errors: report
+        .findings
+        .into_iter()
+        .map(|finding| finding.message)
+        .collect(),
+      nodes: report.counts.nodes_found,
+      edges: report.counts.edges_found,
+      repairs: report
+        .repairs
+        .into_iter()
+        .map(|repair| repair.message)
+        .collect(),
+    })
+  })
+}
+
 #[allow(non_snake_case)]
 #[napi]
 pub fn databaseListNodesWithLabel(handle: &DatabaseHandle, label: String) -> NapiResult<Vec<u64>> {
   handle.with_db(|db| db.node_ids_with_label(&label).map_err(to_napi_err))
 }
 
+#[allow(non_snake_case)]
+#[napi]
+pub fn databaseGetLastNNodesByLabel(
+  handle: &DatabaseHandle,
+  label: String,
+  n: u32,
+) -> NapiResult<Vec<u64>> {
+  handle.with_db(|db| {
+    db.get_last_n_nodes_by_label(&label, n as usize)
+      .map_err(to_napi_err)
+  })
+}
+
 #[allow(non_snake_case)]
 #[napi]
 pub fn databaseNeighbors(
@@ -315,6 +506,24 @@ pub fn databaseNeighbors(
   })
 }
 
+#[allow(non_snake_case)]
+#[napi]
+pub fn databaseGetNeighborsByType(
+  handle: &DatabaseHandle,
+  node_id: i64,
+  edge_type: String,
+  direction: Option<String>,
+  distinct: Option<bool>,
+) -> NapiResult<Vec<u64>> {
+  let id = u64_from_js_id(node_id, "getNeighborsByType")?;
+  let dir = parse_direction(direction.as_deref())?;
+  let distinct = distinct.unwrap_or(true);
+  handle.with_db(|db| {
+    db.get_neighbors_by_type(id, &edge_type, dir, distinct)
+      .map_err(to_napi_err)
+  })
+}
+
 #[allow(non_snake_case)]
 #[napi]
 pub fn databaseBfsTraversal(
@@ -344,6 +553,61 @@ pub fn databaseBfsTraversal(
   })
 }
 
+#[allow(non_snake_case)]
+#[napi]
+pub fn databasePageRank(
+  handle: &DatabaseHandle,
+  options: Option<PageRankOptions>,
+) -> NapiResult<Vec<PageRankRecord>> {
+  let opts = options.unwrap_or_default();
+  handle.with_db(|db| {
+    let scores = db
+      .page_rank(
+        opts.damping,
+        opts.max_iters,
+        opts.tolerance,
+        opts.edge_type.as_deref(),
+      )
+      .map_err(to_napi_err)?;
+    scores
+      .into_iter()
+      .map(PageRankRecord::try_from)
+      .collect::<Result<Vec<_>, _>>()
+  })
+}
+
+#[allow(non_snake_case)]
+#[napi]
+pub fn databaseShortestPathWithEdges(
+  handle: &DatabaseHandle,
+  start_id: i64,
+  end_id: i64,
+  edge_types: Option<Vec<String>>,
+) -> NapiResult<Option<Vec<PathHopRecord>>> {
+  let start = u64_from_js_id(start_id, "shortestPathWithEdges")?;
+  let end = u64_from_js_id(end_id, "shortestPathWithEdges")?;
+  handle.with_db(|db| {
+    let Some(hops) = db
+      .shortest_path_with_edges(start, end, edge_types.as_deref())
+      .map_err(to_napi_err)?
+    else {
+      return Ok(None);
+    };
+    hops
+      .into_iter()
+      .map(|(node, edge)| {
+        Ok(PathHopRecord {
+          node_id: js_id_from_u64(node, "shortest path node id")?,
+          edge_id: edge
+            .map(|edge| js_id_from_u64(edge, "shortest path edge id"))
+            .transpose()?,
+        })
+      })
+      .collect::<NapiResult<Vec<_>>>()
+      .map(Some)
+  })
+}
+
 /// Closes the database handle, releasing all resources.
 ///
 /// After calling close(), all subsequent operations on this handle will fail
@@ -378,6 +642,18 @@ impl StreamHandle {
     stream.next().map_err(to_napi_err)
   }
 
+  #[napi(js_name = "nextBatch")]
+  pub fn next_batch(&self, batch_size: u32) -> NapiResult<Vec<Value>> {
+    let mut guard = self
+      .inner
+      .lock()
+      .map_err(|_| NapiError::new(Status::GenericFailure, "[CLOSED] stream handle is poisoned"))?;
+    let stream = guard
+      .as_mut()
+      .ok_or_else(|| NapiError::new(Status::GenericFailure, "[CLOSED] stream is closed"))?;
+    stream.next_batch(batch_size as usize).map_err(to_napi_err)
+  }
+
   #[napi]
   pub fn close(&self) -> NapiResult<()> {
     let mut guard = self
@@ -414,6 +690,17 @@ fn parse_direction(value: Option<&str>) -> NapiResult<Dir> {
   }
 }
 
+fn parse_verify_level(value: Option<&str>) -> NapiResult<VerifyLevel> {
+  match value.unwrap_or("full") {
+    "fast" => Ok(VerifyLevel::Fast),
+    "full" => Ok(VerifyLevel::Full),
+    "catalog" => Ok(VerifyLevel::Catalog),
+    other => Err(napi::Error::from_reason(format!(
+      "invalid verify level '{other}', expected 'fast', 'full', or 'catalog'"
+    ))),
+  }
+}
+
 fn parse_synchronous(value: &str) -> NapiResult<Synchronous> {
   Synchronous::from_str(value).ok_or_else(|| {
     napi::Error::from_reason(format!(
@@ -445,6 +732,17 @@ impl TryFrom<BfsVisitInfo> for BfsVisitRecord {
   }
 }
 
+impl TryFrom<PageRankScore> for PageRankRecord {
+  type Error = NapiError;
+
+  fn try_from(value: PageRankScore) -> Result<Self, Self::Error> {
+    Ok(Self {
+      node_id: js_id_from_u64(value.node_id, "page rank node id")?,
+      score: value.score,
+    })
+  }
+}
+
 // ============================================================================
 // Typed Batch API - Bypasses JSON serialization for high-performance bulk ops
 // ============================================================================
@@ -530,6 +828,10 @@ pub struct BulkLoadOptions {
   pub node_chunk_size: Option<u32>,
   #[napi(js_name = "edgeChunkSize")]
   pub edge_chunk_size: Option<u32>,
+  #[napi(js_name = "deferIndexes")]
+  pub defer_indexes: Option<bool>,
+  #[napi(js_name = "captureSchema")]
+  pub capture_schema: Option<bool>,
 }
 
 // ============================================================================
@@ -742,11 +1044,17 @@ pub fn databaseBulkLoadNodesTyped(
           opts.edge_chunk_size = chunk as usize;
         }
       }
+      if let Some(defer) = o.defer_indexes {
+        opts.defer_indexes = defer;
+      }
+      if let Some(capture) = o.capture_schema {
+        opts.capture_schema = capture;
+      }
     }
 
     let mut bulk = db.begin_bulk_load(opts);
     let result = bulk.load_nodes(&ffi_nodes).map_err(to_napi_err)?;
-    let _stats = bulk.finish();
+    let _stats = bulk.finish().map_err(to_napi_err)?;
 
     let ids: Vec<i64> = result
       .iter()
@@ -796,11 +1104,17 @@ pub fn databaseBulkLoadEdgesTyped(
           opts.edge_chunk_size = chunk as usize;
         }
       }
+      if let Some(defer) = o.defer_indexes {
+        opts.defer_indexes = defer;
+      }
+      if let Some(capture) = o.capture_schema {
+        opts.capture_schema = capture;
+      }
     }
 
     let mut bulk = db.begin_bulk_load(opts);
     let result = bulk.load_edges(&ffi_edges).map_err(to_napi_err)?;
-    let _stats = bulk.finish();
+    let _stats = bulk.finish().map_err(to_napi_err)?;
 
     let ids: Vec<i64> = result
       .iter()
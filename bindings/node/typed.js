@@ -194,7 +194,7 @@ class TypedQueryBuilder {
     }
     edgeTypes.forEach((ty) => this._db._assertEdgeType(ty, 'query.traverse'))
     if (!['out', 'in', 'both'].includes(direction)) {
-      throw new TypeError('direction must be one of out, in, both')
+      throw new TypeError(`invalid direction '${direction}', expected 'out', 'in', or 'both'`)
     }
     if (!Number.isInteger(depth) || depth <= 0) {
       throw new TypeError('depth must be a positive integer')
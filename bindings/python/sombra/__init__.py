@@ -5,8 +5,10 @@ from ._native import version as _native_version
 from .query import (
     CreateBuilder,
     Database,
+    PatternMatch,
     QueryBuilder,
     QueryResult,
+    Transaction,
     open_database,
     # Error types
     ErrorCode,
@@ -21,6 +23,8 @@ from .query import (
     InvalidArgError,
     NotFoundError,
     ClosedError,
+    DeadlineExceededError,
+    ConstraintViolationError,
     wrap_native_error,
 )
 
@@ -29,7 +33,9 @@ __all__ = [
     "Database",
     "QueryBuilder",
     "CreateBuilder",
+    "PatternMatch",
     "QueryResult",
+    "Transaction",
     "open_database",
     "typed",
     # Error types
@@ -45,6 +51,8 @@ __all__ = [
     "InvalidArgError",
     "NotFoundError",
     "ClosedError",
+    "DeadlineExceededError",
+    "ConstraintViolationError",
     "wrap_native_error",
 ]
 
@@ -11,7 +11,7 @@ use pyo3::{
 };
 use serde_json::Value;
 use sombra::{
-    ffi::{Database, DatabaseOptions, FfiError, QueryStream},
+    ffi::{Database, DatabaseOptions, FfiError, NeighborStream, QueryStream},
     primitives::pager::{PagerOptions, Synchronous},
     storage::Dir,
 };
@@ -34,6 +34,7 @@ struct PyConnectOptions {
     wal_segment_size_bytes: Option<u64>,
     wal_preallocate_segments: Option<u32>,
     autocheckpoint_ms: Option<u32>,
+    auto_repair: bool,
 }
 
 impl Default for PyConnectOptions {
@@ -55,6 +56,7 @@ impl Default for PyConnectOptions {
             wal_segment_size_bytes: None,
             wal_preallocate_segments: None,
             autocheckpoint_ms: None,
+            auto_repair: false,
         }
     }
 }
@@ -86,6 +88,11 @@ pub struct StreamHandle {
     inner: Mutex<Option<QueryStream>>,
 }
 
+#[pyclass(module = "sombra._native", unsendable)]
+pub struct NeighborStreamHandle {
+    inner: Mutex<Option<NeighborStream>>,
+}
+
 struct ParsedNeighborOptions {
     direction: Dir,
     edge_type: Option<String>,
@@ -170,6 +177,9 @@ fn parse_connect_options(options: Option<&Bound<'_, PyDict>>) -> PyResult<PyConn
         if let Some(value) = dict.get_item("autocheckpoint_ms")? {
             opts.autocheckpoint_ms = Some(value.extract::<u32>()?);
         }
+        if let Some(value) = dict.get_item("auto_repair")? {
+            opts.auto_repair = value.extract::<bool>()?;
+        }
     }
     Ok(opts)
 }
@@ -217,6 +227,7 @@ fn open_database(path: &str, options: Option<&Bound<'_, PyDict>>) -> PyResult<Da
         create_if_missing: opts.create_if_missing,
         pager,
         distinct_neighbors_default: opts.distinct_neighbors_default,
+        auto_repair: opts.auto_repair,
         ..DatabaseOptions::default()
     };
 
@@ -343,6 +354,33 @@ fn database_get_node(
     })
 }
 
+#[pyfunction]
+fn database_get_property_for_nodes(
+    py: Python<'_>,
+    handle: &DatabaseHandle,
+    node_ids: Vec<u64>,
+    key: &str,
+) -> PyResult<PyObject> {
+    handle.with_db(|db| {
+        let values = db
+            .get_property_for_nodes(&node_ids, key)
+            .map_err(to_py_err)?;
+        value_to_py(py, Value::Array(values))
+    })
+}
+
+#[pyfunction]
+fn database_entity_names(
+    handle: &DatabaseHandle,
+    node_ids: Vec<u64>,
+    edge_ids: Vec<u64>,
+) -> PyResult<(Vec<Option<Vec<String>>>, Vec<Option<String>>)> {
+    handle.with_db(|db| {
+        let names = db.entity_names(&node_ids, &edge_ids).map_err(to_py_err)?;
+        Ok((names.node_labels, names.edge_types))
+    })
+}
+
 #[pyfunction]
 fn database_get_edge(
     py: Python<'_>,
@@ -372,11 +410,28 @@ fn database_count_edges_with_type(handle: &DatabaseHandle, ty: &str) -> PyResult
     handle.with_db(|db| db.count_edges_with_type(ty).map_err(to_py_err))
 }
 
+#[pyfunction]
+fn database_summary(py: Python<'_>, handle: &DatabaseHandle) -> PyResult<PyObject> {
+    handle.with_db(|db| {
+        let summary = db.summary_json().map_err(to_py_err)?;
+        value_to_py(py, summary)
+    })
+}
+
 #[pyfunction]
 fn database_list_nodes_with_label(handle: &DatabaseHandle, label: &str) -> PyResult<Vec<u64>> {
     handle.with_db(|db| db.node_ids_with_label(label).map_err(to_py_err))
 }
 
+#[pyfunction]
+fn database_get_last_n_nodes_by_label(
+    handle: &DatabaseHandle,
+    label: &str,
+    n: usize,
+) -> PyResult<Vec<u64>> {
+    handle.with_db(|db| db.get_last_n_nodes_by_label(label, n).map_err(to_py_err))
+}
+
 #[pyfunction]
 fn database_neighbors(
     py: Python<'_>,
@@ -406,6 +461,43 @@ fn database_neighbors(
     })
 }
 
+#[pyfunction]
+fn database_neighbor_stream(
+    handle: &DatabaseHandle,
+    node_id: u64,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<NeighborStreamHandle> {
+    let parsed = parse_neighbor_options(options)?;
+    handle.with_db(|db| {
+        let stream = db
+            .neighbor_stream(
+                node_id,
+                parsed.direction,
+                parsed.edge_type.as_deref(),
+                parsed.distinct,
+            )
+            .map_err(to_py_err)?;
+        Ok(NeighborStreamHandle {
+            inner: Mutex::new(Some(stream)),
+        })
+    })
+}
+
+#[pyfunction]
+fn database_get_neighbors_by_type(
+    handle: &DatabaseHandle,
+    node_id: u64,
+    edge_type: &str,
+    direction: &str,
+    distinct: bool,
+) -> PyResult<Vec<u64>> {
+    let dir = parse_direction(Some(direction))?;
+    handle.with_db(|db| {
+        db.get_neighbors_by_type(node_id, edge_type, dir, distinct)
+            .map_err(to_py_err)
+    })
+}
+
 #[pyfunction]
 fn database_bfs_traversal(
     py: Python<'_>,
@@ -436,6 +528,32 @@ fn database_bfs_traversal(
     })
 }
 
+#[pyfunction]
+fn database_shortest_path_with_edges(
+    handle: &DatabaseHandle,
+    start_id: u64,
+    end_id: u64,
+    edge_types: Option<Vec<String>>,
+) -> PyResult<Option<Vec<(u64, Option<u64>)>>> {
+    handle.with_db(|db| {
+        db.shortest_path_with_edges(start_id, end_id, edge_types.as_deref())
+            .map_err(to_py_err)
+    })
+}
+
+#[pyfunction]
+fn database_match_pattern(
+    py: Python<'_>,
+    handle: &DatabaseHandle,
+    pattern: &Bound<'_, PyAny>,
+) -> PyResult<PyObject> {
+    let value = any_to_value(pattern)?;
+    handle.with_db(|db| {
+        let matches = db.match_pattern_json(&value).map_err(to_py_err)?;
+        value_to_py(py, matches)
+    })
+}
+
 #[pyfunction]
 fn database_pragma_get(py: Python<'_>, handle: &DatabaseHandle, name: &str) -> PyResult<PyObject> {
     handle.with_db(|db| {
@@ -487,6 +605,46 @@ fn stream_close(handle: &StreamHandle) -> PyResult<()> {
     Ok(())
 }
 
+#[pyfunction]
+fn neighbor_stream_next(
+    py: Python<'_>,
+    handle: &NeighborStreamHandle,
+) -> PyResult<Option<PyObject>> {
+    let mut guard = handle
+        .inner
+        .lock()
+        .map_err(|_| PyRuntimeError::new_err("[CLOSED] neighbor stream lock poisoned"))?;
+    let stream = guard
+        .as_mut()
+        .ok_or_else(|| PyRuntimeError::new_err("[CLOSED] neighbor stream is closed"))?;
+    let entry = match stream.next() {
+        Some(Ok(entry)) => entry,
+        Some(Err(err)) => {
+            guard.take();
+            return Err(to_py_err(err));
+        }
+        None => {
+            guard.take();
+            return Ok(None);
+        }
+    };
+    let row = PyDict::new_bound(py);
+    row.set_item("node_id", entry.node_id)?;
+    row.set_item("edge_id", entry.edge_id)?;
+    row.set_item("type_id", entry.type_id)?;
+    Ok(Some(row.into_py(py)))
+}
+
+#[pyfunction]
+fn neighbor_stream_close(handle: &NeighborStreamHandle) -> PyResult<()> {
+    let mut guard = handle
+        .inner
+        .lock()
+        .map_err(|_| PyRuntimeError::new_err("[CLOSED] neighbor stream lock poisoned"))?;
+    guard.take();
+    Ok(())
+}
+
 #[pyfunction]
 fn version() -> PyResult<&'static str> {
     Ok(env!("CARGO_PKG_VERSION"))
@@ -507,18 +665,32 @@ fn _native(_py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(pyo3::wrap_pyfunction!(database_pragma_set, m)?)?;
     m.add_function(pyo3::wrap_pyfunction!(database_cancel_request, m)?)?;
     m.add_function(pyo3::wrap_pyfunction!(database_get_node, m)?)?;
+    m.add_function(pyo3::wrap_pyfunction!(database_get_property_for_nodes, m)?)?;
+    m.add_function(pyo3::wrap_pyfunction!(database_entity_names, m)?)?;
     m.add_function(pyo3::wrap_pyfunction!(database_get_edge, m)?)?;
     m.add_function(pyo3::wrap_pyfunction!(database_count_nodes_with_label, m)?)?;
     m.add_function(pyo3::wrap_pyfunction!(database_count_edges_with_type, m)?)?;
+    m.add_function(pyo3::wrap_pyfunction!(database_summary, m)?)?;
     m.add_function(pyo3::wrap_pyfunction!(database_list_nodes_with_label, m)?)?;
+    m.add_function(pyo3::wrap_pyfunction!(
+        database_get_last_n_nodes_by_label,
+        m
+    )?)?;
     m.add_function(pyo3::wrap_pyfunction!(database_neighbors, m)?)?;
+    m.add_function(pyo3::wrap_pyfunction!(database_neighbor_stream, m)?)?;
+    m.add_function(pyo3::wrap_pyfunction!(database_get_neighbors_by_type, m)?)?;
     m.add_function(pyo3::wrap_pyfunction!(database_bfs_traversal, m)?)?;
+    m.add_function(pyo3::wrap_pyfunction!(database_shortest_path_with_edges, m)?)?;
+    m.add_function(pyo3::wrap_pyfunction!(database_match_pattern, m)?)?;
     m.add_function(pyo3::wrap_pyfunction!(stream_next, m)?)?;
     m.add_function(pyo3::wrap_pyfunction!(stream_close, m)?)?;
+    m.add_function(pyo3::wrap_pyfunction!(neighbor_stream_next, m)?)?;
+    m.add_function(pyo3::wrap_pyfunction!(neighbor_stream_close, m)?)?;
     m.add_function(pyo3::wrap_pyfunction!(database_seed_demo, m)?)?;
     m.add_function(pyo3::wrap_pyfunction!(version, m)?)?;
     m.add_class::<DatabaseHandle>()?;
     m.add_class::<StreamHandle>()?;
+    m.add_class::<NeighborStreamHandle>()?;
     Ok(())
 }
 
@@ -7,7 +7,7 @@ import copy
 import math
 import re
 from datetime import datetime, timezone
-from typing import Any, AsyncIterator, Callable, Dict, Iterable, List, Mapping, Optional, Sequence, Tuple, Type, Union
+from typing import Any, Callable, Dict, Iterable, List, Mapping, Optional, Sequence, Tuple, Type, Union
 
 from . import _native
 
@@ -30,6 +30,8 @@ class ErrorCode:
     INVALID_ARG = "INVALID_ARG"
     NOT_FOUND = "NOT_FOUND"
     CLOSED = "CLOSED"
+    TIMEOUT = "TIMEOUT"
+    CONSTRAINT_VIOLATION = "CONSTRAINT_VIOLATION"
 
 
 class SombraError(Exception):
@@ -110,6 +112,20 @@ class ClosedError(SombraError):
         super().__init__(message, ErrorCode.CLOSED)
 
 
+class DeadlineExceededError(SombraError):
+    """Error raised when an operation exceeds its time budget."""
+    
+    def __init__(self, message: str):
+        super().__init__(message, ErrorCode.TIMEOUT)
+
+
+class ConstraintViolationError(SombraError):
+    """Error raised when a write would break a schema constraint."""
+    
+    def __init__(self, message: str):
+        super().__init__(message, ErrorCode.CONSTRAINT_VIOLATION)
+
+
 # Map of error code strings to their corresponding exception classes
 _ERROR_CLASS_MAP: Dict[str, Type[SombraError]] = {
     ErrorCode.UNKNOWN: SombraError,
@@ -124,6 +140,8 @@ _ERROR_CLASS_MAP: Dict[str, Type[SombraError]] = {
     ErrorCode.INVALID_ARG: InvalidArgError,
     ErrorCode.NOT_FOUND: NotFoundError,
     ErrorCode.CLOSED: ClosedError,
+    ErrorCode.TIMEOUT: DeadlineExceededError,
+    ErrorCode.CONSTRAINT_VIOLATION: ConstraintViolationError,
 }
 
 
@@ -342,6 +360,12 @@ def _normalize_labels(labels: Union[str, Sequence[str]]) -> List[str]:
     raise ValueError("labels must be a string or sequence of strings")
 
 
+def _normalize_direction(direction: Any) -> str:
+    if direction not in ("out", "in", "both"):
+        raise ValueError(f"invalid direction {direction!r}, expected 'out', 'in', or 'both'")
+    return direction
+
+
 class Expr:
     """Opaque expression tree produced by the helper functions below."""
 
@@ -556,23 +580,102 @@ class _MutationBatch:
         return ops
 
 
+class Transaction(_MutationBatch):
+    """Mutation batch that is applied atomically when committed.
+
+    Usable as a context manager: a clean exit commits the queued operations and
+    an exception discards them. Exiting after an explicit ``commit()`` or
+    ``rollback()`` is a no-op.
+    """
+
+    def __init__(self, db: "Database") -> None:
+        super().__init__()
+        self._db = db
+        self._state = "open"
+        self.summary: Optional[Dict[str, Any]] = None
+
+    def _ensure_mutable(self) -> None:
+        if self._state == "committed":
+            raise RuntimeError("transaction already committed")
+        if self._state == "rolled_back":
+            raise RuntimeError("transaction already rolled back")
+
+    @property
+    def is_active(self) -> bool:
+        """Returns True until the transaction is committed or rolled back."""
+        return self._state == "open"
+
+    def commit(self) -> Dict[str, Any]:
+        """Apply the queued operations in a single write transaction."""
+        self._ensure_mutable()
+        ops = self.drain()
+        self._state = "committed"
+        self.summary = self._db.mutate({"ops": ops}) if ops else _empty_mutation_summary()
+        return self.summary
+
+    def rollback(self) -> None:
+        """Discard the queued operations. Calling it again is a no-op."""
+        if self._state != "open":
+            return
+        self.drain()
+        self._state = "rolled_back"
+
+    def __enter__(self) -> "Transaction":
+        return self
+
+    def __exit__(self, exc_type: Any, exc_val: Any, exc_tb: Any) -> None:
+        if self._state != "open":
+            return
+        if exc_type is None:
+            self.commit()
+        else:
+            self.rollback()
+
+
 class _QueryStream:
+    """Query results pulled from the native stream one row at a time.
+
+    Supports both ``for`` and ``async for`` iteration. The stream is closed once
+    it is exhausted, when the ``with`` block exits, or when it is collected.
+    """
+
     def __init__(self, handle: _native.StreamHandle):
         self._handle = handle
         self._closed = False
 
-    def __aiter__(self) -> "_QueryStream":
-        return self
-
-    async def __anext__(self) -> Any:
+    def _next_row(self) -> Tuple[bool, Any]:
         if self._closed:
-            raise StopAsyncIteration
+            return False, None
         value = _wrap_native_call(_native.stream_next, self._handle)
         if value is None:
             self.close()
+            return False, None
+        return True, value
+
+    def __iter__(self) -> "_QueryStream":
+        return self
+
+    def __next__(self) -> Any:
+        found, value = self._next_row()
+        if not found:
+            raise StopIteration
+        return value
+
+    def __aiter__(self) -> "_QueryStream":
+        return self
+
+    async def __anext__(self) -> Any:
+        found, value = self._next_row()
+        if not found:
             raise StopAsyncIteration
         return value
 
+    def __enter__(self) -> "_QueryStream":
+        return self
+
+    def __exit__(self, exc_type: Any, exc_val: Any, exc_tb: Any) -> None:
+        self.close()
+
     async def __aenter__(self) -> "_QueryStream":
         return self
 
@@ -602,6 +705,38 @@ class _QueryStream:
             self._closed = True
 
 
+class _NeighborStream:
+    """Neighbor entries of one node, read lazily from a snapshot held until close."""
+
+    def __init__(self, handle: _native.NeighborStreamHandle):
+        self._handle = handle
+        self._closed = False
+
+    def __iter__(self) -> "_NeighborStream":
+        return self
+
+    def __next__(self) -> Dict[str, int]:
+        if self._closed:
+            raise StopIteration
+        value = _wrap_native_call(_native.neighbor_stream_next, self._handle)
+        if value is None:
+            self._closed = True
+            raise StopIteration
+        return value
+
+    def __enter__(self) -> "_NeighborStream":
+        return self
+
+    def __exit__(self, exc_type: Any, exc_val: Any, exc_tb: Any) -> None:
+        self.close()
+
+    def close(self) -> None:
+        if self._closed:
+            return
+        self._closed = True
+        _wrap_native_call(_native.neighbor_stream_close, self._handle)
+
+
 class _PredicateBuilder:
     def __init__(
         self,
@@ -818,13 +953,21 @@ class _NodeScope:
         self._builder.request_id(value)
         return self
 
+    def timeout_ms(self, ms: Optional[int]) -> "_NodeScope":
+        self._builder.timeout_ms(ms)
+        return self
+
+    def flatten(self, flag: bool = True) -> "_NodeScope":
+        self._builder.flatten(flag)
+        return self
+
     def explain(self, *, redact_literals: bool = False) -> QueryResult:
         return self._builder.explain(redact_literals=redact_literals)
 
     def execute(self, *, with_meta: bool = False) -> Union[List[Dict[str, Any]], QueryResult]:
         return self._builder.execute(with_meta=with_meta)
 
-    def stream(self) -> AsyncIterator[Any]:
+    def stream(self) -> "_QueryStream":
         return self._builder.stream()
 
     def _as_expr(self, expr: Union[Expr, Callable[["_NodeScope"], Expr]], ctx: str) -> Expr:
@@ -1184,6 +1327,15 @@ class Database:
         summary = self.mutate({"ops": ops})
         return result, summary
 
+    def begin_transaction(self) -> Transaction:
+        """Start a mutation batch that commits atomically.
+
+        Use it as ``with db.begin_transaction() as tx:`` to commit on a clean exit
+        and roll back when the block raises.
+        """
+        self._assert_open()
+        return Transaction(self)
+
     def pragma(self, name: str, value: Any = _PRAGMA_SENTINEL) -> Any:
         self._assert_open()
         if value is _PRAGMA_SENTINEL:
@@ -1207,15 +1359,60 @@ class Database:
         self._assert_open()
         if not isinstance(node_id, int) or node_id < 0:
             raise ValueError("neighbors() requires a non-negative node id")
-        if direction not in ("out", "in", "both"):
-            raise ValueError("direction must be 'out', 'in', or 'both'")
-        options: Dict[str, Any] = {"direction": direction, "distinct": bool(distinct)}
+        options: Dict[str, Any] = {"direction": _normalize_direction(direction), "distinct": bool(distinct)}
         if edge_type is not None:
             if not isinstance(edge_type, str) or not edge_type.strip():
                 raise ValueError("edge_type must be a non-empty string when provided")
             options["edge_type"] = edge_type
         return _wrap_native_call(_native.database_neighbors, self._handle, int(node_id), options)
 
+    def iter_neighbors(
+        self,
+        node_id: int,
+        *,
+        direction: str = "out",
+        edge_type: Optional[str] = None,
+        distinct: bool = True,
+    ) -> "_NeighborStream":
+        """Like ``neighbors()`` but yields entries one at a time instead of returning a list.
+
+        The engine still collects the node's neighbors from a single snapshot
+        when the stream is opened; only the conversion to Python objects is lazy.
+        """
+        self._assert_open()
+        if not isinstance(node_id, int) or node_id < 0:
+            raise ValueError("iter_neighbors() requires a non-negative node id")
+        options: Dict[str, Any] = {"direction": _normalize_direction(direction), "distinct": bool(distinct)}
+        if edge_type is not None:
+            if not isinstance(edge_type, str) or not edge_type.strip():
+                raise ValueError("edge_type must be a non-empty string when provided")
+            options["edge_type"] = edge_type
+        handle = _wrap_native_call(_native.database_neighbor_stream, self._handle, int(node_id), options)
+        return _NeighborStream(handle)
+
+    def get_neighbors_by_type(
+        self,
+        node_id: int,
+        edge_type: str,
+        *,
+        direction: str = "out",
+        distinct: bool = True,
+    ) -> List[int]:
+        self._assert_open()
+        if not isinstance(node_id, int) or node_id < 0:
+            raise ValueError("get_neighbors_by_type requires a non-negative node id")
+        if not isinstance(edge_type, str) or not edge_type.strip():
+            raise ValueError("get_neighbors_by_type requires a non-empty string edge type")
+        values = _wrap_native_call(
+            _native.database_get_neighbors_by_type,
+            self._handle,
+            int(node_id),
+            edge_type,
+            _normalize_direction(direction),
+            bool(distinct),
+        )
+        return [int(value) for value in values]
+
     def bfs_traversal(
         self,
         node_id: int,
@@ -1230,9 +1427,7 @@ class Database:
             raise ValueError("bfs_traversal() requires a non-negative node id")
         if not isinstance(max_depth, int) or max_depth < 0:
             raise ValueError("bfs_traversal() requires a non-negative integer max_depth")
-        if direction not in ("out", "in", "both"):
-            raise ValueError("direction must be 'out', 'in', or 'both'")
-        options: Dict[str, Any] = {"direction": direction}
+        options: Dict[str, Any] = {"direction": _normalize_direction(direction)}
         if edge_types is not None:
             values: List[str] = []
             for ty in edge_types:
@@ -1248,6 +1443,55 @@ class Database:
             _native.database_bfs_traversal, self._handle, int(node_id), int(max_depth), options
         )
 
+    def shortest_path_with_edges(
+        self,
+        start_id: int,
+        end_id: int,
+        edge_types: Optional[Sequence[str]] = None,
+    ) -> Optional[List[Tuple[int, Optional[int]]]]:
+        self._assert_open()
+        for value in (start_id, end_id):
+            if not isinstance(value, int) or value < 0:
+                raise ValueError("shortest_path_with_edges() requires non-negative node ids")
+        types: Optional[List[str]] = None
+        if edge_types is not None:
+            types = []
+            for ty in edge_types:
+                if not isinstance(ty, str) or not ty.strip():
+                    raise ValueError("edge_types entries must be non-empty strings")
+                types.append(ty)
+        hops = _wrap_native_call(
+            _native.database_shortest_path_with_edges,
+            self._handle,
+            int(start_id),
+            int(end_id),
+            types,
+        )
+        if hops is None:
+            return None
+        return [(int(node), None if edge is None else int(edge)) for node, edge in hops]
+
+    def match_pattern(self, pattern: Mapping[str, Any]) -> List[PatternMatch]:
+        """Find every binding of a node/edge pattern.
+
+        ``pattern`` holds ``nodes`` (``var``, one or more ``labels``, optional
+        ``properties`` filters with ``equals``, ``not_equals`` and ``ranges``) and ``edges``
+        (``from``, ``to``, optional ``types`` and ``direction``). The whole pattern is
+        matched natively against one snapshot. Each match lists the bound node ids and,
+        per pattern edge, the id of the edge it bound, so parallel edges yield separate
+        matches.
+        """
+        self._assert_open()
+        spec = _pattern_spec(pattern, self._schema)
+        matches = _wrap_native_call(_native.database_match_pattern, self._handle, spec)
+        return [
+            PatternMatch(
+                {var_name: int(node_id) for var_name, node_id in match["nodeBindings"].items()},
+                [int(edge_id) for edge_id in match["edgeIds"]],
+            )
+            for match in matches
+        ]
+
     def with_schema(self, schema: Optional[Mapping[str, Mapping[str, Any]]]) -> "Database":
         self._assert_open()
         self._schema = _normalize_runtime_schema(schema)
@@ -1262,6 +1506,28 @@ class Database:
             raise TypeError("node record must be a mapping when present")
         return record
 
+    def get_property_for_nodes(self, node_ids: Sequence[int], key: str) -> List[Any]:
+        self._assert_open()
+        ids = [int(node_id) for node_id in node_ids]
+        if any(node_id < 0 for node_id in ids):
+            raise ValueError("get_property_for_nodes requires non-negative node ids")
+        if not isinstance(key, str) or not key.strip():
+            raise ValueError("get_property_for_nodes requires a non-empty string key")
+        return _wrap_native_call(_native.database_get_property_for_nodes, self._handle, ids, key)
+
+    def entity_names(
+        self,
+        node_ids: Sequence[int] = (),
+        edge_ids: Sequence[int] = (),
+    ) -> Tuple[List[Optional[List[str]]], List[Optional[str]]]:
+        self._assert_open()
+        nodes = [int(node_id) for node_id in node_ids]
+        edges = [int(edge_id) for edge_id in edge_ids]
+        if any(value < 0 for value in nodes + edges):
+            raise ValueError("entity_names requires non-negative ids")
+        labels, types = _wrap_native_call(_native.database_entity_names, self._handle, nodes, edges)
+        return list(labels), list(types)
+
     def get_edge_record(self, edge_id: int) -> Optional[Dict[str, Any]]:
         self._assert_open()
         record = _wrap_native_call(_native.database_get_edge, self._handle, int(edge_id))
@@ -1283,6 +1549,10 @@ class Database:
             raise ValueError("count_edges_with_type requires a non-empty edge type string")
         return int(_wrap_native_call(_native.database_count_edges_with_type, self._handle, edge_type))
 
+    def summary(self) -> Dict[str, Any]:
+        self._assert_open()
+        return _wrap_native_call(_native.database_summary, self._handle)
+
     def list_nodes_with_label(self, label: str) -> List[int]:
         self._assert_open()
         if not isinstance(label, str) or not label.strip():
@@ -1290,6 +1560,17 @@ class Database:
         values = _wrap_native_call(_native.database_list_nodes_with_label, self._handle, label)
         return [int(value) for value in values]
 
+    def get_last_n_nodes_by_label(self, label: str, n: int) -> List[int]:
+        self._assert_open()
+        if not isinstance(label, str) or not label.strip():
+            raise ValueError("get_last_n_nodes_by_label requires a non-empty string label")
+        if not isinstance(n, int) or n < 0:
+            raise ValueError("get_last_n_nodes_by_label requires a non-negative integer count")
+        values = _wrap_native_call(
+            _native.database_get_last_n_nodes_by_label, self._handle, label, n
+        )
+        return [int(value) for value in values]
+
     def create_node(
         self,
         labels: Union[str, Sequence[str]],
@@ -1372,6 +1653,126 @@ class Database:
         return _wrap_native_call(_native.database_stream, self._handle, spec)
 
 
+class PatternMatch(dict):
+    """One pattern match: node ids keyed by variable plus one edge id per pattern edge."""
+
+    def __init__(self, node_bindings: Mapping[str, int], edge_ids: Sequence[int]):
+        super().__init__({"node_bindings": dict(node_bindings), "edge_ids": list(edge_ids)})
+
+    @property
+    def node_bindings(self) -> Dict[str, int]:
+        return self["node_bindings"]
+
+    @property
+    def edge_ids(self) -> List[int]:
+        return self["edge_ids"]
+
+
+def _pattern_filters(var_name: str, filters: Any, validate: Callable[[str], str]) -> Dict[str, Any]:
+    if filters is None:
+        return {}
+    if not isinstance(filters, Mapping):
+        raise TypeError(f"properties for '{var_name}' must be a mapping")
+    unknown = set(filters) - {"equals", "not_equals", "ranges"}
+    if unknown:
+        raise ValueError(f"unsupported property filter keys: {sorted(unknown)}")
+    spec: Dict[str, Any] = {
+        "equals": {
+            validate(prop): _literal_value(value)
+            for prop, value in dict(filters.get("equals") or {}).items()
+        },
+        "notEquals": {
+            validate(prop): _literal_value(value)
+            for prop, value in dict(filters.get("not_equals") or {}).items()
+        },
+        "ranges": [],
+    }
+    for entry in filters.get("ranges") or []:
+        if not isinstance(entry, Mapping) or not isinstance(entry.get("key"), str):
+            raise ValueError("range filters require a 'key' property name")
+        range_spec: Dict[str, Any] = {"key": validate(entry["key"])}
+        for side in ("min", "max"):
+            bound = entry.get(side)
+            if bound is None:
+                continue
+            if not isinstance(bound, Mapping) or "value" not in bound:
+                raise ValueError(f"range '{side}' bound requires a 'value'")
+            literal = _literal_value(bound["value"])
+            if literal["t"] == "Null":
+                raise ValueError(f"range '{side}' bound does not accept null literals")
+            range_spec[side] = {"value": literal, "inclusive": bool(bound.get("inclusive", True))}
+        spec["ranges"].append(range_spec)
+    return spec
+
+
+def _pattern_prop_validator(
+    schema: Optional[Mapping[str, Mapping[str, Any]]], labels: Sequence[str]
+) -> Callable[[str], str]:
+    label_schemas = [(label, schema.get(label)) for label in labels] if schema else []
+    label_schemas = [(label, entry) for label, entry in label_schemas if isinstance(entry, Mapping)]
+    if not label_schemas:
+        return _normalize_prop_name
+
+    def validator(prop: str) -> str:
+        normalized = _normalize_prop_name(prop)
+        for label, label_schema in label_schemas:
+            if normalized not in label_schema:
+                raise ValueError(f"Unknown property '{normalized}' on label '{label}'")
+        return normalized
+
+    return validator
+
+
+def _pattern_spec(
+    pattern: Mapping[str, Any], schema: Optional[Mapping[str, Mapping[str, Any]]]
+) -> Dict[str, Any]:
+    if not isinstance(pattern, Mapping):
+        raise TypeError("match_pattern() requires a mapping with 'nodes' and 'edges'")
+    nodes = pattern.get("nodes")
+    if not isinstance(nodes, Sequence) or isinstance(nodes, (str, bytes)) or not nodes:
+        raise ValueError("pattern requires a non-empty 'nodes' list")
+    node_vars: List[str] = []
+    node_specs: List[Dict[str, Any]] = []
+    for node in nodes:
+        if not isinstance(node, Mapping):
+            raise TypeError("pattern nodes must be mappings")
+        var_name = node.get("var")
+        if not isinstance(var_name, str) or not var_name:
+            raise ValueError("pattern nodes require a non-empty 'var'")
+        if var_name in node_vars:
+            raise ValueError(f"pattern variable '{var_name}' is declared twice")
+        labels = _normalize_labels(node.get("labels") or [])
+        if not labels:
+            raise ValueError("pattern nodes require at least one label")
+        validate = _pattern_prop_validator(schema, labels)
+        node_specs.append(
+            {
+                "var": var_name,
+                "labels": labels,
+                "properties": _pattern_filters(var_name, node.get("properties"), validate),
+            }
+        )
+        node_vars.append(var_name)
+
+    edge_specs: List[Dict[str, Any]] = []
+    for edge in pattern.get("edges") or []:
+        if not isinstance(edge, Mapping):
+            raise TypeError("pattern edges must be mappings")
+        src, dst = edge.get("from"), edge.get("to")
+        for var_name in (src, dst):
+            if var_name not in node_vars:
+                raise ValueError(f"pattern edge references unknown variable '{var_name}'")
+        edge_specs.append(
+            {
+                "from": src,
+                "to": dst,
+                "types": _normalize_labels(edge.get("types") or []),
+                "direction": _normalize_direction(edge.get("direction", "out")),
+            }
+        )
+    return {"nodes": node_specs, "edges": edge_specs}
+
+
 def open_database(path: str, **options: Any) -> Database:
     """Convenience helper mirroring Database.open."""
     return Database.open(path, **options)
@@ -1392,6 +1793,13 @@ class QueryBuilder:
         self._next_var_idx = 0
         self._pending_direction = "out"
         self._request_id: Optional[str] = None
+        self._timeout_ms: Optional[int] = None
+        self._flatten = False
+        self._order_by: List[Dict[str, Any]] = []
+        self._bytes_format: Optional[str] = None
+        self._limit: Optional[int] = None
+        self._offset: Optional[int] = None
+        self._limit_per_group: Optional[Dict[str, Any]] = None
 
     def nodes(self, label: str) -> _NodeScope:
         if not isinstance(label, str) or not label:
@@ -1459,9 +1867,7 @@ class QueryBuilder:
         return builder
 
     def direction(self, direction: str) -> "QueryBuilder":
-        if direction not in ("out", "in", "both"):
-            raise ValueError(f"invalid direction: {direction}")
-        self._pending_direction = direction
+        self._pending_direction = _normalize_direction(direction)
         return self
 
     def bidirectional(self) -> "QueryBuilder":
@@ -1483,6 +1889,69 @@ class QueryBuilder:
         self._request_id = trimmed
         return self
 
+    def timeout_ms(self, ms: Optional[int]) -> "QueryBuilder":
+        if ms is None:
+            self._timeout_ms = None
+            return self
+        if not isinstance(ms, int) or isinstance(ms, bool) or ms < 0:
+            raise ValueError("timeout_ms() requires a non-negative integer")
+        self._timeout_ms = ms
+        return self
+
+    def flatten(self, flag: bool = True) -> "QueryBuilder":
+        self._flatten = bool(flag)
+        return self
+
+    def order_by(self, var_name: str, prop: str, direction: str = "asc") -> "QueryBuilder":
+        self._assert_match(var_name)
+        if not isinstance(prop, str) or not prop.strip():
+            raise ValueError("order_by() requires a non-empty property name")
+        if direction not in ("asc", "desc"):
+            raise ValueError("order_by() direction must be 'asc' or 'desc'")
+        self._order_by.append({"var": var_name, "prop": prop, "desc": direction == "desc"})
+        return self
+
+    def limit(self, count: int) -> "QueryBuilder":
+        if not isinstance(count, int) or isinstance(count, bool) or count < 0:
+            raise ValueError("limit() requires a non-negative integer")
+        self._limit = count
+        return self
+
+    def offset(self, count: int) -> "QueryBuilder":
+        if not isinstance(count, int) or isinstance(count, bool) or count < 0:
+            raise ValueError("offset() requires a non-negative integer")
+        self._offset = count
+        return self
+
+    def limit_per_group(
+        self,
+        group_by: str,
+        n: int,
+        order_by: Optional[Sequence[Dict[str, str]]] = None,
+    ) -> "QueryBuilder":
+        self._assert_match(group_by)
+        if not isinstance(n, int) or isinstance(n, bool) or n < 0:
+            raise ValueError("limit_per_group() requires a non-negative integer n")
+        keys: List[Dict[str, Any]] = []
+        for key in order_by or []:
+            var_name = key.get("var")
+            prop = key.get("prop")
+            direction = key.get("direction", "asc")
+            self._assert_match(var_name)
+            if not isinstance(prop, str) or not prop.strip():
+                raise ValueError("limit_per_group() order_by requires a non-empty property name")
+            if direction not in ("asc", "desc"):
+                raise ValueError("limit_per_group() order_by direction must be 'asc' or 'desc'")
+            keys.append({"var": var_name, "prop": prop, "desc": direction == "desc"})
+        self._limit_per_group = {"groupBy": group_by, "n": n, "orderBy": keys}
+        return self
+
+    def bytes_format(self, fmt: str) -> "QueryBuilder":
+        if fmt not in ("base64", "array"):
+            raise ValueError("bytes_format() must be 'base64' or 'array'")
+        self._bytes_format = fmt
+        return self
+
     def select(self, fields: Sequence[ProjectionField]) -> "QueryBuilder":
         projections: List[Dict[str, Any]] = []
         for field in fields:
@@ -1490,7 +1959,24 @@ class QueryBuilder:
                 self._assert_match(field)
                 projections.append({"kind": "var", "var": field, "alias": None})
             elif isinstance(field, dict):
-                if "prop" in field:
+                if "agg" in field:
+                    func = field["agg"]
+                    var_name = field.get("var")
+                    alias = field.get("as")
+                    if func not in ("count", "sum", "avg", "min", "max"):
+                        raise ValueError("aggregate must be 'count', 'sum', 'avg', 'min', or 'max'")
+                    if not isinstance(var_name, str) or not var_name:
+                        raise ValueError("aggregate projection requires a variable name")
+                    if not isinstance(alias, str) or not alias:
+                        raise ValueError("aggregate projection requires an alias")
+                    self._assert_match(var_name)
+                    prop = field.get("prop")
+                    if prop is not None:
+                        prop = self._make_prop_validator(var_name)(prop)
+                    projections.append(
+                        {"kind": "aggregate", "func": func, "var": var_name, "prop": prop, "alias": alias}
+                    )
+                elif "prop" in field:
                     var_name = field.get("var")
                     prop = field["prop"]
                     alias = field.get("as")
@@ -1531,7 +2017,11 @@ class QueryBuilder:
             return payload
         return payload.rows()
 
-    def stream(self) -> AsyncIterator[Any]:
+    def stream(self) -> "_QueryStream":
+        """Stream result rows one at a time instead of materializing them.
+
+        The returned stream works with both ``for`` and ``async for``.
+        """
         handle = self._db._stream(self._build())
         return _QueryStream(handle)
 
@@ -1617,6 +2107,25 @@ class QueryBuilder:
             spec["predicate"] = _clone(self._predicate)
         if self._request_id is not None:
             spec["request_id"] = self._request_id
+        if self._timeout_ms is not None:
+            spec["timeoutMs"] = self._timeout_ms
+        if self._flatten:
+            spec["flatten"] = True
+        if self._order_by:
+            spec["orderBy"] = [dict(key) for key in self._order_by]
+        if self._bytes_format is not None:
+            spec["bytesFormat"] = self._bytes_format
+        if self._limit is not None:
+            spec["limit"] = self._limit
+        if self._offset is not None:
+            spec["offset"] = self._offset
+        if self._limit_per_group is not None:
+            group = self._limit_per_group
+            spec["limitPerGroup"] = {
+                "groupBy": group["groupBy"],
+                "n": group["n"],
+                "orderBy": [dict(key) for key in group["orderBy"]],
+            }
         return spec
 
     def _label_for_var(self, var_name: str) -> Optional[str]:
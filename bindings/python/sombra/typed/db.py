@@ -6,7 +6,7 @@ from typing import Any, Dict, Generic, List, Mapping, Optional, Sequence, Set, T
 
 from typing_extensions import Literal, TypedDict
 
-from ..query import Database
+from ..query import Database, _normalize_direction
 from .schema import (
     EdgeSchema,
     NormalizedGraphSchema,
@@ -271,8 +271,7 @@ class SombraDB(Generic[SchemaT]):
         edge_type: Optional[EdgeLabelT] = None,
         distinct: bool = True,
     ) -> List[int]:
-        if direction not in ("out", "in", "both"):
-            raise ValueError("direction must be 'out', 'in', or 'both'")
+        _normalize_direction(direction)
         normalized_edge = self._maybe_assert_edge(edge_type, "get_neighbors")
         neighbors = self._db.neighbors(
             int(node_id),
@@ -397,8 +396,7 @@ class TypedQueryBuilder(Generic[SchemaT]):
         if not edge_types:
             raise ValueError("traverse requires at least one edge type")
         normalized = [self._db._assert_edge_label(edge, "query.traverse") for edge in edge_types]
-        if direction not in ("out", "in", "both"):
-            raise ValueError("direction must be 'out', 'in', or 'both'")
+        _normalize_direction(direction)
         if not isinstance(depth, int) or depth <= 0:
             raise ValueError("depth must be a positive integer")
         self._edge_types = normalized
@@ -23,6 +23,7 @@ from sombra.query import (
     InvalidArgError,
     NotFoundError,
     ClosedError,
+    DeadlineExceededError,
     wrap_native_error,
 )
 
@@ -44,6 +45,19 @@ def test_execute_query_returns_rows() -> None:
     assert isinstance(record["props"], dict)
 
 
+def test_direction_rejects_typos_and_defaults_to_out() -> None:
+    db = Database.open(temp_db_path())
+    db.seed_demo()
+
+    with pytest.raises(ValueError, match="invalid direction 'outgong'"):
+        db.query().match("User").where("FOLLOWS", "User").direction("outgong")
+
+    ada = db.query().nodes("User").where(eq("name", "Ada")).execute()[0]["n0"]["_id"]
+    assert db.neighbors(ada) == db.neighbors(ada, direction="out")
+    with pytest.raises(ValueError, match="invalid direction 'outgong'"):
+        db.neighbors(ada, direction="outgong")
+
+
 def test_execute_with_meta_returns_envelope() -> None:
     db = Database.open(temp_db_path())
     db.seed_demo()
@@ -90,6 +104,23 @@ def test_stream_close_stops_iteration() -> None:
     asyncio.run(run())
 
 
+def test_stream_supports_sync_iteration() -> None:
+    db = Database.open(temp_db_path())
+    db.seed_demo()
+    expected = db.query().nodes("User").execute()
+
+    with db.query().nodes("User").stream() as stream:
+        rows = [row for row in stream]
+        assert next(stream, None) is None
+    assert len(rows) == len(expected)
+
+    stream = db.query().nodes("User").stream()
+    assert "n0" in next(stream)
+    stream.close()
+    with pytest.raises(StopIteration):
+        next(stream)
+
+
 def test_explain_plan_shape() -> None:
     db = Database.open(temp_db_path())
     db.seed_demo()
@@ -107,6 +138,32 @@ def test_request_id_round_trip() -> None:
     assert plan["request_id"] == "req-py"
 
 
+def test_timeout_ms_aborts_queries_past_deadline() -> None:
+    db = Database.open(temp_db_path())
+    db.seed_demo()
+
+    names = db.query().nodes("User").select("name").timeout_ms(60_000).execute()
+    assert len(names) >= 3
+
+    with pytest.raises(DeadlineExceededError, match="query timed out") as excinfo:
+        db.query().nodes("User").timeout_ms(0).execute()
+    assert excinfo.value.code == ErrorCode.TIMEOUT
+    with pytest.raises(ValueError, match="timeout_ms"):
+        db.query().nodes("User").timeout_ms(-1)
+
+
+def test_flatten_returns_bare_values() -> None:
+    db = Database.open(temp_db_path())
+    db.seed_demo()
+
+    names = db.query().nodes("User").select("name").flatten().execute()
+    assert len(names) >= 3
+    assert all(isinstance(name, str) for name in names)
+
+    with pytest.raises(SombraError, match="flatten requires exactly one result column"):
+        db.query().match("User").where("FOLLOWS", "User").flatten().execute()
+
+
 def test_mutate_crud_helpers() -> None:
     db = Database.open(temp_db_path())
     db.seed_demo()
@@ -130,6 +187,207 @@ def test_mutate_many_batches_ops() -> None:
     assert len(created) == 2
 
 
+def test_get_last_n_nodes_by_label_returns_newest_first() -> None:
+    db = Database.open(temp_db_path())
+    summary = db.mutate_many(
+        [
+            {"op": "createNode", "labels": ["User" if i % 2 else "Admin"], "props": {}}
+            for i in range(6)
+        ]
+    )
+    created = [int(value) for value in summary.get("createdNodes") or []]
+    users = [node for i, node in enumerate(created) if i % 2][::-1]
+    assert db.get_last_n_nodes_by_label("User", 2) == users[:2]
+    assert db.get_last_n_nodes_by_label("User", 10) == users
+
+
+def test_get_neighbors_by_type_follows_only_requested_type() -> None:
+    db = Database.open(temp_db_path())
+    summary = db.mutate_many(
+        [{"op": "createNode", "labels": ["User"], "props": {}} for _ in range(3)]
+    )
+    a, b, c = [int(value) for value in summary.get("createdNodes") or []]
+    db.mutate_many(
+        [
+            {"op": "createEdge", "src": a, "dst": b, "ty": "FOLLOWS", "props": {}},
+            {"op": "createEdge", "src": a, "dst": b, "ty": "FOLLOWS", "props": {}},
+            {"op": "createEdge", "src": a, "dst": c, "ty": "LIKES", "props": {}},
+            {"op": "createEdge", "src": c, "dst": a, "ty": "FOLLOWS", "props": {}},
+        ]
+    )
+    assert db.get_neighbors_by_type(a, "FOLLOWS") == [b]
+    assert db.get_neighbors_by_type(a, "FOLLOWS", distinct=False) == [b, b]
+    assert db.get_neighbors_by_type(a, "LIKES") == [c]
+    assert db.get_neighbors_by_type(a, "FOLLOWS", direction="in") == [c]
+
+
+def test_match_pattern_binds_nodes_and_edges() -> None:
+    db = Database.open(temp_db_path())
+    summary = db.mutate_many(
+        [
+            {"op": "createNode", "labels": ["User"], "props": {"name": "Ada", "age": 36}},
+            {"op": "createNode", "labels": ["User"], "props": {"name": "Bob", "age": 17}},
+            {"op": "createNode", "labels": ["User"], "props": {"name": "Cy", "age": 52}},
+        ]
+    )
+    a, b, c = [int(value) for value in summary.get("createdNodes") or []]
+    edges = db.mutate_many(
+        [
+            {"op": "createEdge", "src": a, "dst": b, "ty": "FOLLOWS", "props": {}},
+            {"op": "createEdge", "src": a, "dst": c, "ty": "FOLLOWS", "props": {}},
+            {"op": "createEdge", "src": a, "dst": c, "ty": "LIKES", "props": {}},
+        ]
+    )
+    follows_c = int((edges.get("createdEdges") or [])[1])
+
+    matches = db.match_pattern(
+        {
+            "nodes": [
+                {"var": "a", "labels": ["User"], "properties": {"equals": {"name": "Ada"}}},
+                {
+                    "var": "b",
+                    "labels": ["User"],
+                    "properties": {"ranges": [{"key": "age", "min": {"value": 18, "inclusive": True}}]},
+                },
+            ],
+            "edges": [{"from": "a", "to": "b", "types": ["FOLLOWS"], "direction": "out"}],
+        }
+    )
+    assert [match.node_bindings for match in matches] == [{"a": a, "b": c}]
+    assert matches[0].edge_ids == [follows_c]
+
+    extra = db.mutate_many([{"op": "createEdge", "src": a, "dst": c, "ty": "FOLLOWS", "props": {}}])
+    second = int((extra.get("createdEdges") or [])[0])
+    parallel = db.match_pattern(
+        {
+            "nodes": [
+                {"var": "a", "labels": ["User"]},
+                {"var": "b", "labels": ["User"], "properties": {"equals": {"name": "Cy"}}},
+            ],
+            "edges": [{"from": "a", "to": "b", "types": ["FOLLOWS"]}],
+        }
+    )
+    assert sorted(edge for match in parallel for edge in match.edge_ids) == sorted([follows_c, second])
+
+    with pytest.raises(ValueError, match="at least one label"):
+        db.match_pattern({"nodes": [{"var": "a"}], "edges": []})
+    with pytest.raises(ValueError, match="unknown variable 'z'"):
+        db.match_pattern({"nodes": [{"var": "a", "labels": "User"}], "edges": [{"from": "a", "to": "z"}]})
+
+
+def test_iter_neighbors_matches_neighbors() -> None:
+    db = Database.open(temp_db_path())
+    summary = db.mutate_many(
+        [{"op": "createNode", "labels": ["User"], "props": {}} for _ in range(3)]
+    )
+    a, b, c = [int(value) for value in summary.get("createdNodes") or []]
+    db.mutate_many(
+        [
+            {"op": "createEdge", "src": a, "dst": b, "ty": "FOLLOWS", "props": {}},
+            {"op": "createEdge", "src": a, "dst": c, "ty": "LIKES", "props": {}},
+        ]
+    )
+    assert list(db.iter_neighbors(a)) == db.neighbors(a)
+    assert [n["node_id"] for n in db.iter_neighbors(a, edge_type="LIKES")] == [c]
+
+    with db.iter_neighbors(a) as stream:
+        assert next(stream)["node_id"] in (b, c)
+    with pytest.raises(StopIteration):
+        next(stream)
+
+
+def test_get_property_for_nodes_matches_node_records() -> None:
+    db = Database.open(temp_db_path())
+    summary = db.mutate_many(
+        [
+            {
+                "op": "createNode",
+                "labels": ["User"],
+                "props": {"age": i} if i % 2 else {"name": f"user-{i}", "age": i},
+            }
+            for i in range(4)
+        ]
+    )
+    ids = [int(value) for value in summary.get("createdNodes") or []][::-1]
+    for key in ("name", "age"):
+        expected = [
+            ((db.get_node_record(node_id) or {}).get("properties") or {}).get(key)
+            for node_id in ids
+        ]
+        assert db.get_property_for_nodes(ids, key) == expected
+
+
+def test_shortest_path_with_edges_reports_parallel_edge_taken() -> None:
+    db = Database.open(temp_db_path())
+    summary = db.mutate_many(
+        [{"op": "createNode", "labels": ["Stop"], "props": {}} for _ in range(3)]
+    )
+    a, b, c = [int(value) for value in summary.get("createdNodes") or []]
+    db.mutate_many(
+        [
+            {"op": "createEdge", "src": a, "dst": b, "ty": "BUS", "props": {}},
+            {"op": "createEdge", "src": a, "dst": b, "ty": "TRAM", "props": {}},
+            {"op": "createEdge", "src": b, "dst": c, "ty": "TRAM", "props": {}},
+        ]
+    )
+    hops = db.shortest_path_with_edges(a, c, ["TRAM"])
+    assert hops is not None
+    assert [node for node, _ in hops] == [a, b, c]
+    assert hops[0][1] is None
+    for (prev, _), (node, edge_id) in zip(hops, hops[1:]):
+        edge = db.get_edge_record(edge_id)
+        assert edge is not None
+        assert (edge["src"], edge["dst"], edge["ty"]) == (prev, node, "TRAM")
+    assert db.shortest_path_with_edges(c, a) is None
+
+
+def test_summary_matches_individual_counts() -> None:
+    db = Database.open(temp_db_path()).seed_demo()
+    summary = db.summary()
+    labels = summary["labels"]
+    for label, count in labels.items():
+        assert count == db.count_nodes_with_label(label)
+    assert summary["nodes"] == sum(labels.values())
+    edge_types = summary["edgeTypes"]
+    for edge_type, count in edge_types.items():
+        assert count == db.count_edges_with_type(edge_type)
+    assert summary["edges"] == sum(edge_types.values())
+    assert summary["propertyIndexes"] >= 1
+    assert summary["nodeIdRange"] is not None
+
+
+def test_entity_names_match_record_lookups() -> None:
+    db = Database.open(temp_db_path())
+    summary = db.mutate_many(
+        [
+            {"op": "createNode", "labels": ["Person", "Admin"], "props": {}},
+            {"op": "createNode", "labels": ["Tag"], "props": {}},
+        ]
+    )
+    a, b = [int(value) for value in summary.get("createdNodes") or []]
+    summary = db.mutate_many(
+        [
+            {"op": "createEdge", "src": a, "dst": b, "ty": "TAGGED", "props": {}},
+            {"op": "createEdge", "src": b, "dst": a, "ty": "OWNS", "props": {}},
+        ]
+    )
+    e1, e2 = [int(value) for value in summary.get("createdEdges") or []]
+    missing = 999_999
+    node_ids = [b, missing, a]
+    edge_ids = [e2, e1, missing]
+    labels, types = db.entity_names(node_ids, edge_ids)
+    expected_labels = []
+    for node_id in node_ids:
+        record = db.get_node_record(node_id)
+        expected_labels.append(None if record is None else record["labels"])
+    expected_types = []
+    for edge_id in edge_ids:
+        record = db.get_edge_record(edge_id)
+        expected_types.append(None if record is None else record["ty"])
+    assert labels == expected_labels
+    assert types == expected_types
+
+
 def test_mutate_batched_chunks_ops() -> None:
     db = Database.open(temp_db_path())
     ops = [
@@ -205,6 +463,52 @@ def test_transaction_collects_ops() -> None:
     assert result == "done"
 
 
+def test_begin_transaction_commits_on_clean_exit() -> None:
+    db = Database.open(temp_db_path())
+    with db.begin_transaction() as tx:
+        tx.create_node("User", {"name": "WithTx"})
+        tx.create_node("User", {"name": "WithTx2"})
+    assert tx.is_active is False
+    assert tx.summary is not None
+    assert len(tx.summary.get("createdNodes") or []) == 2
+    assert db.count_nodes_with_label("User") == 2
+
+
+def test_begin_transaction_rolls_back_on_exception() -> None:
+    db = Database.open(temp_db_path())
+    with pytest.raises(ValueError, match="intentional error"):
+        with db.begin_transaction() as tx:
+            tx.create_node("User", {"name": "Discarded"})
+            raise ValueError("intentional error")
+    assert tx.is_active is False
+    assert tx.summary is None
+    assert db.count_nodes_with_label("User") == 0
+
+
+def test_begin_transaction_exit_after_explicit_commit_is_noop() -> None:
+    db = Database.open(temp_db_path())
+    with db.begin_transaction() as tx:
+        tx.create_node("User", {"name": "Explicit"})
+        summary = tx.commit()
+    assert tx.summary is summary
+    assert db.count_nodes_with_label("User") == 1
+    with pytest.raises(RuntimeError, match="transaction already committed"):
+        tx.commit()
+    with pytest.raises(RuntimeError, match="transaction already committed"):
+        tx.create_node("User", {"name": "Late"})
+
+
+def test_begin_transaction_rollback_is_idempotent() -> None:
+    db = Database.open(temp_db_path())
+    with db.begin_transaction() as tx:
+        tx.create_node("User", {"name": "RolledBack"})
+        tx.rollback()
+        tx.rollback()
+    assert db.count_nodes_with_label("User") == 0
+    with pytest.raises(RuntimeError, match="transaction already rolled back"):
+        tx.commit()
+
+
 def test_pragma_round_trip() -> None:
     db = Database.open(temp_db_path())
     db.pragma("synchronous", "normal")
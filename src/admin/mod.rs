@@ -6,6 +6,7 @@
 //! including checkpoint operations, statistics reporting, vacuum operations, and
 //! verification tools.
 
+mod backup;
 mod checkpoint;
 mod error;
 mod mvcc;
@@ -15,6 +16,11 @@ mod util;
 mod vacuum;
 mod verify;
 
+/// Online backup of an open database into a standalone file.
+///
+/// Copies pages from a pinned read snapshot so writers only pause briefly.
+pub use backup::{backup, BackupReport};
+
 /// Initiates a checkpoint of the write-ahead log into the database.
 ///
 /// A checkpoint transfers committed WAL entries back into the main database file,
@@ -45,12 +51,18 @@ pub use mvcc::{mvcc_status, CommitEntryReport, CommitStatusKind, MvccStatusRepor
 /// Database vacuum (defragmentation) operations.
 ///
 /// Vacuum reclaims unused space in the database file and can optimize data layout.
-pub use vacuum::{promote_vacuumed_copy, vacuum_into, VacuumOptions, VacuumReport};
+pub use vacuum::{
+    incremental_vacuum, promote_vacuumed_copy, vacuum_into, IncrementalVacuumOptions,
+    IncrementalVacuumReport, VacuumOptions, VacuumReport,
+};
 
 /// Database integrity verification.
 ///
 /// Verifies the structural integrity of the database and reports any issues found.
-pub use verify::{verify, VerifyCounts, VerifyFinding, VerifyLevel, VerifyReport, VerifySeverity};
+pub use verify::{
+    repair, repair_graph, verify, verify_graph, RepairOptions, RepairTarget, VerifyCounts,
+    VerifyFinding, VerifyLevel, VerifyRepair, VerifyReport, VerifySeverity,
+};
 
 pub use crate::primitives::pager::{CheckpointMode, PagerOptions};
 
@@ -161,6 +161,12 @@ pub struct StorageSpaceStats {
     pub vstore_extent_segments: u64,
     /// Total number of pages covered by extent allocations.
     pub vstore_extent_pages: u64,
+    /// Property values written inline in their owning record.
+    pub inline_prop_writes: u64,
+    /// Property values written to VStore overflow pages.
+    pub overflow_prop_writes: u64,
+    /// Fraction of property writes that went to overflow (0.0 when none recorded).
+    pub overflow_prop_ratio: f64,
     /// B-tree pages used by the nodes tree.
     pub nodes_tree_pages: u64,
     /// Approximate bytes used in the nodes tree payloads.
@@ -296,6 +302,7 @@ fn collect_storage_space_stats(
     let mvcc = handle.graph.mvcc_status();
     let vstore = handle.graph.vstore_metrics_snapshot();
     let usage = handle.graph.space_usage()?;
+    let placement = handle.graph.prop_placement();
 
     Ok(StorageSpaceStats {
         version_log_bytes: mvcc.version_log_bytes,
@@ -307,6 +314,9 @@ fn collect_storage_space_stats(
         vstore_extent_writes: vstore.extent_writes,
         vstore_extent_segments: vstore.extent_segments,
         vstore_extent_pages: vstore.extent_pages,
+        inline_prop_writes: placement.inline_writes,
+        overflow_prop_writes: placement.overflow_writes,
+        overflow_prop_ratio: placement.overflow_ratio(),
         nodes_tree_pages: usage.nodes_pages,
         nodes_tree_bytes: usage.nodes_bytes,
         edges_tree_pages: usage.edges_pages,
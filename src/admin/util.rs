@@ -1,5 +1,6 @@
 use std::ffi::OsString;
 use std::fs;
+use std::io::ErrorKind;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
@@ -59,6 +60,12 @@ pub fn open_graph(path: &Path, opts: &AdminOpenOptions) -> Result<GraphHandle> {
         .snapshot_pool_max_age_ms(opts.snapshot_pool_max_age_ms);
     let graph = Graph::open(graph_opts)?;
     let dict = Arc::new(Dict::open(store, DictOptions::default())?);
+    if !dict.name_kinds_complete()? {
+        let mut write = pager.begin_write()?;
+        let names = graph.schema_name_ids(&mut write)?;
+        dict.backfill_name_kinds(&mut write, &names)?;
+        pager.commit(write)?;
+    }
     Ok(GraphHandle { pager, graph, dict })
 }
 
@@ -92,3 +99,17 @@ pub(crate) fn lock_path(path: &Path) -> PathBuf {
     output.set_file_name(name);
     output
 }
+
+pub(crate) fn remove_if_exists(path: &Path) -> std::io::Result<()> {
+    match fs::metadata(path) {
+        Ok(meta) => {
+            if meta.is_dir() {
+                fs::remove_dir_all(path)
+            } else {
+                fs::remove_file(path)
+            }
+        }
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(err),
+    }
+}
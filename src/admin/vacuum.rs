@@ -7,13 +7,16 @@ use std::time::Instant;
 use crate::primitives::pager::{
     CheckpointMode, Meta, PageStore, Pager, MVCC_READER_WARN_THRESHOLD_MS,
 };
+use crate::storage::btree::relocate::Relocation;
 use crate::storage::catalog::Dict;
 use crate::storage::{Graph, VacuumTrigger, COMMIT_MAX};
-use crate::types::{NodeId, StrId};
+use crate::types::{NodeId, PageId, StrId};
 use serde::Serialize;
 
 use crate::admin::options::AdminOpenOptions;
-use crate::admin::util::{ensure_parent_dir, lock_path, open_graph, wal_path};
+use crate::admin::util::{
+    ensure_parent_dir, lock_path, open_graph, remove_if_exists, wal_path, GraphHandle,
+};
 use crate::admin::{AdminError, Result};
 use tracing::{info, warn};
 
@@ -59,6 +62,41 @@ pub struct VacuumReport {
     pub mvcc_warning: Option<String>,
 }
 
+/// Options for a single [`incremental_vacuum`] pass.
+#[derive(Clone, Debug)]
+pub struct IncrementalVacuumOptions {
+    /// Maximum number of live pages to relocate in this pass.
+    pub max_pages: u64,
+}
+
+impl Default for IncrementalVacuumOptions {
+    fn default() -> Self {
+        Self { max_pages: 1024 }
+    }
+}
+
+/// Report generated after an [`incremental_vacuum`] pass.
+#[derive(Debug, Clone, Serialize)]
+pub struct IncrementalVacuumReport {
+    /// Duration of the pass in milliseconds.
+    pub duration_ms: f64,
+    /// Live pages moved from the tail into lower free pages.
+    pub pages_relocated: u64,
+    /// Pages trimmed from the end of the database file.
+    pub pages_reclaimed: u64,
+    /// Page count of the database before the pass.
+    pub pages_before: u64,
+    /// Page count of the database after the pass.
+    pub pages_after: u64,
+    /// Whether the pass ran out of budget with pages left to move, so
+    /// another pass could relocate more.
+    pub more_work: bool,
+    /// Highest live page the pass skipped because it cannot be relocated
+    /// (tree roots, overflow and adjacency segment pages stay in place). The
+    /// file cannot shrink below it.
+    pub blocked_at: Option<u64>,
+}
+
 /// Summary of database analysis results from a vacuum operation.
 #[derive(Debug, Clone, Serialize, Default)]
 pub struct AnalyzeSummary {
@@ -229,6 +267,86 @@ pub fn vacuum_into(
     Ok(report)
 }
 
+/// Shrinks an open database in place by relocating at most `max_pages` pages.
+///
+/// Walks backwards from the end of the file, moving each live B+ tree page
+/// into the lowest free page below it, then checkpoints so the freed tail is
+/// truncated. Pages that cannot be moved are skipped and reported through
+/// [`IncrementalVacuumReport::blocked_at`]; pages below them are still
+/// compacted, but the file only shrinks down to them. Each pass commits as a single write transaction, so the
+/// database stays consistent if the caller stops between passes. Call in a
+/// loop while [`IncrementalVacuumReport::more_work`] is set to bound pause
+/// times on large databases.
+///
+/// # Errors
+///
+/// Returns an error if `max_pages` is zero or the relocation transaction,
+/// commit, or checkpoint fails.
+pub fn incremental_vacuum(
+    handle: &GraphHandle,
+    opts: &IncrementalVacuumOptions,
+) -> Result<IncrementalVacuumReport> {
+    if opts.max_pages == 0 {
+        return Err(AdminError::Message(
+            "incremental vacuum max_pages must be greater than zero".into(),
+        ));
+    }
+    let start = Instant::now();
+    let pager = handle.pager.as_ref();
+    pager.checkpoint(CheckpointMode::Force)?;
+    let pages_before = pager.meta()?.next_page.0;
+
+    let mut tx = pager.begin_write()?;
+    let mut cursor = pages_before;
+    let mut pages_relocated = 0u64;
+    let mut blocked_at = None;
+    let mut out_of_space = false;
+    while pages_relocated < opts.max_pages && cursor > 1 {
+        let page = PageId(cursor - 1);
+        if pager.is_page_free(page) {
+            cursor -= 1;
+            continue;
+        }
+        let Some(relocation) = Relocation::plan(pager, &mut tx, page)? else {
+            blocked_at.get_or_insert(page.0);
+            cursor -= 1;
+            continue;
+        };
+        let Some(target) = tx.allocate_page_below(page)? else {
+            out_of_space = true;
+            break;
+        };
+        relocation.apply(pager, &mut tx, target)?;
+        tx.free_page(page)?;
+        pages_relocated += 1;
+        cursor -= 1;
+    }
+    pager.commit(tx)?;
+    pager.checkpoint(CheckpointMode::Force)?;
+    let pages_after = pager.meta()?.next_page.0;
+
+    let more_work = !out_of_space && cursor > 1 && pager.free_page_count() > 0;
+    let report = IncrementalVacuumReport {
+        duration_ms: start.elapsed().as_secs_f64() * 1_000.0,
+        pages_relocated,
+        pages_reclaimed: pages_before.saturating_sub(pages_after),
+        pages_before,
+        pages_after,
+        more_work,
+        blocked_at,
+    };
+    info!(
+        pages_relocated = report.pages_relocated,
+        pages_reclaimed = report.pages_reclaimed,
+        pages_after = report.pages_after,
+        more_work = report.more_work,
+        blocked_at = report.blocked_at,
+        duration_ms = report.duration_ms,
+        "admin.vacuum.incremental.completed"
+    );
+    Ok(report)
+}
+
 /// Promotes a vacuumed database copy by swapping it into place.
 ///
 /// # Arguments
@@ -352,17 +470,3 @@ fn rename_if_exists(src: &Path, dst: &Path) -> std::io::Result<()> {
         Err(err) => Err(err),
     }
 }
-
-fn remove_if_exists(path: &Path) -> std::io::Result<()> {
-    match fs::metadata(path) {
-        Ok(meta) => {
-            if meta.is_dir() {
-                fs::remove_dir_all(path)
-            } else {
-                fs::remove_file(path)
-            }
-        }
-        Err(err) if err.kind() == ErrorKind::NotFound => Ok(()),
-        Err(err) => Err(err),
-    }
-}
@@ -1,14 +1,16 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::hash::{Hash, Hasher};
 use std::path::Path;
 
-use crate::primitives::pager::ReadGuard;
-use crate::storage::Graph;
+use crate::primitives::pager::{CheckpointMode, PageStore, ReadGuard};
+use crate::storage::catalog::Dict;
+use crate::storage::{Graph, IndexDrift};
 use crate::types::{EdgeId, NodeId, TypeId};
 use serde::Serialize;
+use tracing::info;
 
 use crate::admin::options::AdminOpenOptions;
-use crate::admin::util::open_graph;
+use crate::admin::util::{open_graph, GraphHandle};
 use crate::admin::Result;
 
 const MAX_FINDINGS: usize = 32;
@@ -21,6 +23,10 @@ pub enum VerifyLevel {
     Fast,
     /// Comprehensive validation including nodes, edges, and adjacency lists.
     Full,
+    /// Everything `Full` checks, plus the string dictionary: every label,
+    /// type, and property id used by nodes and edges must resolve, and the
+    /// two dictionary mappings must agree entry for entry.
+    Catalog,
 }
 
 /// Indicates the severity level of a verification finding.
@@ -64,6 +70,31 @@ pub struct VerifyCounts {
     pub adjacency_entries: u64,
     /// Number of distinct nodes referenced in adjacency lists.
     pub adjacency_nodes_touched: u64,
+    /// Number of id-to-string entries in the dictionary.
+    pub dictionary_entries: u64,
+}
+
+/// Structure rewritten by [`repair`].
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RepairTarget {
+    /// Forward and reverse adjacency trees.
+    Adjacency,
+    /// A label index.
+    LabelIndex,
+    /// A property index.
+    PropertyIndex,
+    /// The on-disk freelist chain.
+    Freelist,
+}
+
+/// A single change made by [`repair`].
+#[derive(Clone, Debug, Serialize)]
+pub struct VerifyRepair {
+    /// The structure that was changed.
+    pub target: RepairTarget,
+    /// Human-readable description of the change.
+    pub message: String,
 }
 
 /// Complete report of a verification operation.
@@ -77,6 +108,30 @@ pub struct VerifyReport {
     pub findings: Vec<VerifyFinding>,
     /// Statistics about the data structures examined.
     pub counts: VerifyCounts,
+    /// Changes made by [`repair`] before verification ran; empty for a plain
+    /// [`verify`].
+    pub repairs: Vec<VerifyRepair>,
+}
+
+/// Selects the classes of damage [`repair`] may fix.
+#[derive(Clone, Copy, Debug)]
+pub struct RepairOptions {
+    /// Remove adjacency entries whose edge row is gone or disagrees with them.
+    pub adjacency: bool,
+    /// Rebuild label and property indexes that disagree with node rows.
+    pub indexes: bool,
+    /// Rewrite a freelist that lists pages twice or past the end of the file.
+    pub freelist: bool,
+}
+
+impl Default for RepairOptions {
+    fn default() -> Self {
+        Self {
+            adjacency: true,
+            indexes: true,
+            freelist: true,
+        }
+    }
 }
 
 /// Verifies the integrity of a graph database.
@@ -87,6 +142,7 @@ pub struct VerifyReport {
 ///
 /// - `VerifyLevel::Fast`: Quick validation of metadata only
 /// - `VerifyLevel::Full`: Comprehensive scan of all nodes, edges, and adjacency lists
+/// - `VerifyLevel::Catalog`: `Full` plus dictionary consistency and name resolution
 ///
 /// # Arguments
 ///
@@ -108,8 +164,17 @@ pub fn verify(
     level: VerifyLevel,
 ) -> Result<VerifyReport> {
     let handle = open_graph(path.as_ref(), opts)?;
-    let pager = handle.pager.clone();
-    let graph = handle.graph;
+    verify_graph(&handle, level)
+}
+
+/// Verifies an already open graph; see [`verify`] for the checks performed.
+///
+/// # Errors
+///
+/// Returns an error if reading metadata or storage structures fails outright.
+pub fn verify_graph(handle: &GraphHandle, level: VerifyLevel) -> Result<VerifyReport> {
+    let pager = &handle.pager;
+    let graph = &handle.graph;
     let mut findings = Vec::new();
     let mut counts = VerifyCounts::default();
 
@@ -117,25 +182,74 @@ pub fn verify(
     if meta.page_size == 0 {
         push_error(&mut findings, "meta page reports zero page size");
     }
+    match pager.check_freelist() {
+        Ok(check) => {
+            if check.out_of_range > 0 {
+                push_error(
+                    &mut findings,
+                    format!(
+                        "freelist lists {} pages beyond the end of the file",
+                        check.out_of_range
+                    ),
+                );
+            }
+            if check.duplicates > 0 {
+                push_error(
+                    &mut findings,
+                    format!("freelist lists {} pages more than once", check.duplicates),
+                );
+            }
+        }
+        Err(err) => push_error(&mut findings, format!("failed to read freelist: {err}")),
+    }
 
-    if matches!(level, VerifyLevel::Full) {
+    if matches!(level, VerifyLevel::Full | VerifyLevel::Catalog) {
         let read = pager.begin_latest_committed_read()?;
+        let mut str_refs = matches!(level, VerifyLevel::Catalog).then(StrRefs::new);
         let nodes = collect_nodes(
-            &graph,
+            graph,
             &read,
             meta.storage_next_node_id,
             &mut findings,
             &mut counts,
+            str_refs.as_mut(),
         )?;
         let edges = collect_edges(
-            &graph,
+            graph,
             &read,
             meta.storage_next_edge_id,
             &nodes,
             &mut findings,
             &mut counts,
+            str_refs.as_mut(),
         )?;
-        run_adjacency_checks(&graph, &read, &nodes, &edges, &mut findings, &mut counts)?;
+        run_adjacency_checks(graph, &read, &nodes, &edges, &mut findings, &mut counts)?;
+        match graph.index_drift(&read) {
+            Ok(drift) => {
+                for entry in &drift {
+                    push_error(
+                        &mut findings,
+                        format!(
+                            "{} lists {} stale and misses {} nodes",
+                            index_name(entry),
+                            entry.stale.len(),
+                            entry.missing.len()
+                        ),
+                    );
+                }
+            }
+            Err(err) => push_error(&mut findings, format!("failed to scan indexes: {err}")),
+        }
+        if let Some(str_refs) = str_refs {
+            run_catalog_checks(
+                &handle.dict,
+                &read,
+                meta.dict_next_str_id,
+                &str_refs,
+                &mut findings,
+                &mut counts,
+            )?;
+        }
     }
 
     Ok(VerifyReport {
@@ -143,9 +257,163 @@ pub fn verify(
         success: findings.is_empty(),
         findings,
         counts,
+        repairs: Vec::new(),
     })
 }
 
+/// Repairs the damage [`verify`] can safely fix, then verifies again.
+///
+/// Each structure is rebuilt from the records it derives from: adjacency
+/// entries are checked against edge rows, indexes against node rows, and
+/// the freelist is rewritten from the set of free pages loaded at open.
+/// Records themselves are never rewritten, so checksum failures and other
+/// read errors are left for the returned findings to report, and a
+/// structure whose source records cannot be read in full is not repaired.
+/// Adjacency and index repairs need `VerifyLevel::Full` or higher.
+///
+/// # Errors
+///
+/// Returns an error if the database cannot be opened, a repair transaction
+/// fails (for example a unique index no longer holds), or I/O fails.
+pub fn repair(
+    path: impl AsRef<Path>,
+    opts: &AdminOpenOptions,
+    level: VerifyLevel,
+    options: &RepairOptions,
+) -> Result<VerifyReport> {
+    let handle = open_graph(path.as_ref(), opts)?;
+    repair_graph(&handle, level, options)
+}
+
+/// Repairs an already open graph; see [`repair`].
+///
+/// # Errors
+///
+/// Returns an error if a repair transaction or the follow-up checkpoint fails.
+pub fn repair_graph(
+    handle: &GraphHandle,
+    level: VerifyLevel,
+    options: &RepairOptions,
+) -> Result<VerifyReport> {
+    let pager = &handle.pager;
+    let graph = &handle.graph;
+    let mut repairs = Vec::new();
+    let scan_records = matches!(level, VerifyLevel::Full | VerifyLevel::Catalog);
+
+    if options.adjacency && scan_records {
+        // A failed scan leaves the structure alone; the follow-up
+        // verification reports the error.
+        let read = pager.begin_latest_committed_read()?;
+        let candidates = orphaned_adjacency(graph, &read).unwrap_or_default();
+        drop(read);
+        if !candidates.is_empty() {
+            let mut write = pager.begin_write()?;
+            let removed = graph.repair_orphaned_adjacency(&mut write, &candidates)?;
+            pager.commit(write)?;
+            repairs.extend(
+                removed
+                    .into_iter()
+                    .map(|(src, ty, dst, edge)| VerifyRepair {
+                        target: RepairTarget::Adjacency,
+                        message: format!(
+                            "removed adjacency entries for edge {} ({} -> {} type {})",
+                            edge.0, src.0, dst.0, ty.0
+                        ),
+                    }),
+            );
+        }
+    }
+
+    if options.indexes && scan_records {
+        let read = pager.begin_latest_committed_read()?;
+        let drift = graph.index_drift(&read).unwrap_or_default();
+        drop(read);
+        if !drift.is_empty() {
+            let mut write = pager.begin_write()?;
+            for entry in &drift {
+                if graph.rebuild_index(&mut write, entry.label, entry.prop)? {
+                    repairs.push(VerifyRepair {
+                        target: match entry.prop {
+                            Some(_) => RepairTarget::PropertyIndex,
+                            None => RepairTarget::LabelIndex,
+                        },
+                        message: format!(
+                            "rebuilt {}, dropping {} stale and adding {} missing nodes",
+                            index_name(entry),
+                            entry.stale.len(),
+                            entry.missing.len()
+                        ),
+                    });
+                }
+            }
+            pager.commit(write)?;
+        }
+    }
+
+    if options.freelist {
+        if let Ok(check) = pager.check_freelist() {
+            if !check.is_clean() {
+                pager.rewrite_freelist()?;
+                repairs.push(VerifyRepair {
+                    target: RepairTarget::Freelist,
+                    message: format!(
+                        "rewrote freelist, dropping {} duplicate and {} out-of-range entries",
+                        check.duplicates, check.out_of_range
+                    ),
+                });
+            }
+        }
+    }
+
+    if !repairs.is_empty() {
+        pager.checkpoint(CheckpointMode::Force)?;
+    }
+    info!(repairs = repairs.len(), "admin.verify.repaired");
+    let mut report = verify_graph(handle, level)?;
+    report.repairs = repairs;
+    Ok(report)
+}
+
+/// Collects adjacency entries whose edge row is missing or disagrees with
+/// them. Entries whose edge row cannot be read are skipped rather than
+/// treated as orphans.
+fn orphaned_adjacency(
+    graph: &Graph,
+    read: &ReadGuard,
+) -> Result<Vec<(NodeId, TypeId, NodeId, EdgeId)>> {
+    let fwd = graph.debug_collect_adj_fwd(read)?;
+    let rev = graph
+        .debug_collect_adj_rev(read)?
+        .into_iter()
+        .map(|(dst, ty, src, edge)| (src, ty, dst, edge));
+    let mut seen = HashSet::new();
+    let mut orphans = Vec::new();
+    for (src, ty, dst, edge) in fwd.into_iter().chain(rev) {
+        if !seen.insert(EdgeRef::new(src, ty, dst, edge)) {
+            continue;
+        }
+        let orphaned = match graph.get_edge(read, edge) {
+            Ok(Some(data)) => data.src != src || data.dst != dst || data.ty != ty,
+            Ok(None) => true,
+            Err(_) => false,
+        };
+        if orphaned {
+            orphans.push((src, ty, dst, edge));
+        }
+    }
+    Ok(orphans)
+}
+
+fn index_name(drift: &IndexDrift) -> String {
+    match drift.prop {
+        Some(prop) => format!(
+            "property index on label {} property {}",
+            drift.label.0, prop.0
+        ),
+        None => format!("label index on label {}", drift.label.0),
+    }
+}
+
 fn run_adjacency_checks(
     graph: &Graph,
     read: &ReadGuard,
@@ -291,6 +559,7 @@ fn collect_nodes(
     next_node_id: u64,
     findings: &mut Vec<VerifyFinding>,
     counts: &mut VerifyCounts,
+    mut str_refs: Option<&mut StrRefs>,
 ) -> Result<HashSet<u64>> {
     let mut nodes = HashSet::new();
     let max_id = next_node_id.saturating_sub(1);
@@ -298,9 +567,17 @@ fn collect_nodes(
         return Ok(nodes);
     }
     for raw_id in 1..=max_id {
-        if let Some(_data) = graph.get_node(read, NodeId(raw_id))? {
+        if let Some(data) = graph.get_node(read, NodeId(raw_id))? {
             nodes.insert(raw_id);
             counts.nodes_found += 1;
+            if let Some(refs) = str_refs.as_deref_mut() {
+                for label in &data.labels {
+                    refs.note(label.0, || format!("node {raw_id} label"));
+                }
+                for (prop, _) in &data.props {
+                    refs.note(prop.0, || format!("node {raw_id} property"));
+                }
+            }
         }
         if findings.len() >= MAX_FINDINGS {
             break;
@@ -316,6 +593,7 @@ fn collect_edges(
     nodes: &HashSet<u64>,
     findings: &mut Vec<VerifyFinding>,
     counts: &mut VerifyCounts,
+    mut str_refs: Option<&mut StrRefs>,
 ) -> Result<HashSet<u64>> {
     let mut edges_set = HashSet::new();
     let max_id = next_edge_id.saturating_sub(1);
@@ -326,6 +604,12 @@ fn collect_edges(
         if let Some(data) = graph.get_edge(read, EdgeId(raw_id))? {
             edges_set.insert(raw_id);
             counts.edges_found += 1;
+            if let Some(refs) = str_refs.as_deref_mut() {
+                refs.note(data.ty.0, || format!("edge {raw_id} type"));
+                for (prop, _) in &data.props {
+                    refs.note(prop.0, || format!("edge {raw_id} property"));
+                }
+            }
             if !nodes.contains(&data.src.0) {
                 push_error(
                     findings,
@@ -346,6 +630,115 @@ fn collect_edges(
     Ok(edges_set)
 }
 
+/// Dictionary ids referenced by stored records, each with the first record
+/// that used it so findings can point at a culprit.
+struct StrRefs {
+    first_use: BTreeMap<u32, String>,
+}
+
+impl StrRefs {
+    fn new() -> Self {
+        Self {
+            first_use: BTreeMap::new(),
+        }
+    }
+
+    fn note(&mut self, id: u32, context: impl FnOnce() -> String) {
+        self.first_use.entry(id).or_insert_with(context);
+    }
+}
+
+fn run_catalog_checks(
+    dict: &Dict,
+    read: &ReadGuard,
+    next_str_id: u32,
+    str_refs: &StrRefs,
+    findings: &mut Vec<VerifyFinding>,
+    counts: &mut VerifyCounts,
+) -> Result<()> {
+    let str_to_id = dict.debug_collect_str_to_id(read)?;
+    let id_to_str = dict.debug_collect_id_to_str(read)?;
+    counts.dictionary_entries = id_to_str.len() as u64;
+
+    let mut ids_by_str: HashMap<String, u64> = HashMap::with_capacity(str_to_id.len());
+    let mut strs_by_id: HashMap<u64, String> = HashMap::with_capacity(str_to_id.len());
+    for (key, id) in str_to_id {
+        let key = match key {
+            Ok(key) => key,
+            Err(err) => {
+                push_error(
+                    findings,
+                    format!("dictionary string key for id {id} unreadable: {err}"),
+                );
+                continue;
+            }
+        };
+        if let Some(other) = strs_by_id.get(&id) {
+            push_error(
+                findings,
+                format!("dictionary id {id} claimed by both {other:?} and {key:?}"),
+            );
+        } else {
+            strs_by_id.insert(id, key.clone());
+        }
+        ids_by_str.insert(key, id);
+    }
+
+    let mut resolved_ids = HashSet::with_capacity(id_to_str.len());
+    for (id, value) in id_to_str {
+        if id == 0 || id >= u64::from(next_str_id) {
+            push_error(
+                findings,
+                format!("dictionary id {id} outside allocated range 1..{next_str_id}"),
+            );
+        }
+        let value = match value {
+            Ok(value) => value,
+            Err(err) => {
+                push_error(findings, format!("dictionary id {id} unreadable: {err}"));
+                continue;
+            }
+        };
+        resolved_ids.insert(id);
+        match ids_by_str.get(&value) {
+            Some(mapped) if *mapped == id => {}
+            Some(mapped) => push_error(
+                findings,
+                format!("dictionary string {value:?} has duplicate ids {mapped} and {id}"),
+            ),
+            None => push_error(
+                findings,
+                format!("dictionary id {id} ({value:?}) missing from string index"),
+            ),
+        }
+        if findings.len() >= MAX_FINDINGS {
+            return Ok(());
+        }
+    }
+
+    for (id, key) in &strs_by_id {
+        if !resolved_ids.contains(id) {
+            push_error(
+                findings,
+                format!("dictionary string {key:?} maps to orphaned id {id}"),
+            );
+        }
+    }
+
+    for (id, context) in &str_refs.first_use {
+        if !resolved_ids.contains(&u64::from(*id)) {
+            push_error(
+                findings,
+                format!("{context} references dictionary id {id} with no entry"),
+            );
+        }
+        if findings.len() >= MAX_FINDINGS {
+            break;
+        }
+    }
+    Ok(())
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 struct EdgeRef {
     src: u64,
@@ -13,8 +13,8 @@ use clap::{ArgAction, ArgGroup, Args, CommandFactory, Parser, Subcommand, ValueE
 use clap_complete::Shell as CompletionShell;
 use sombra::{
     admin::{
-        checkpoint, mvcc_status, stats, vacuum_into, verify, AdminOpenOptions, CheckpointMode,
-        MvccStatusReport, PagerOptions, VacuumOptions, VerifyLevel,
+        backup, checkpoint, mvcc_status, open_graph, stats, vacuum_into, verify, AdminOpenOptions,
+        CheckpointMode, MvccStatusReport, PagerOptions, VacuumOptions, VerifyLevel,
     },
     cli::import_export::{
         run_export, run_import, CliError, EdgeImportConfig, ExportConfig, ImportConfig,
@@ -322,6 +322,21 @@ struct ImportCmd {
         help = "Rebuild property indexes after import (implies --disable-indexes)"
     )]
     build_indexes: bool,
+
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 0,
+        help = "Add N to the numeric suffix of every CSV node, src, and dst id (not engine ids)"
+    )]
+    id_offset: u64,
+
+    #[arg(
+        long,
+        value_name = "PROP",
+        help = "Store each node's (offset) external id in this property"
+    )]
+    node_id_property: Option<String>,
 }
 
 #[derive(Args, Debug)]
@@ -359,6 +374,21 @@ struct ExportCmd {
         help = "Edge property columns to include"
     )]
     edge_props: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "PREFIX",
+        help = "Namespace prepended to every exported node, src, and dst id"
+    )]
+    id_prefix: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 0,
+        help = "Add N to every exported node id"
+    )]
+    id_offset: u64,
 }
 
 #[derive(Args, Debug)]
@@ -675,6 +705,18 @@ enum Command {
         analyze: bool,
     },
 
+    #[command(about = "Write a consistent backup without stopping writers")]
+    Backup {
+        #[arg(
+            value_name = "DB",
+            help = "Database path (defaults to --database or config)"
+        )]
+        db_path: Option<PathBuf>,
+
+        #[arg(long = "into", value_name = "PATH", required = true)]
+        into: PathBuf,
+    },
+
     #[command(about = "Verify on-disk structures")]
     Verify {
         #[arg(
@@ -829,6 +871,7 @@ impl From<CheckpointModeArg> for CheckpointMode {
 enum VerifyLevelArg {
     Fast,
     Full,
+    Catalog,
 }
 
 impl From<VerifyLevelArg> for VerifyLevel {
@@ -836,6 +879,7 @@ impl From<VerifyLevelArg> for VerifyLevel {
         match level {
             VerifyLevelArg::Fast => VerifyLevel::Fast,
             VerifyLevelArg::Full => VerifyLevel::Full,
+            VerifyLevelArg::Catalog => VerifyLevel::Catalog,
         }
     }
 }
@@ -911,6 +955,20 @@ async fn run() -> Result<(), Box<dyn Error>> {
                 ));
             }
         }
+        Command::Backup { db_path, into } => {
+            let db_path = resolve_db_path(db_path, default_db.as_ref(), "backup")?;
+            let task = ui.task("Backing up database");
+            let handle = open_graph(&db_path, &open_opts)?;
+            let report = backup(&handle, into)?;
+            let elapsed = task.finish();
+            emit(cli.format, &ui, &report, print_backup_text)?;
+            if matches!(cli.format, OutputFormat::Text) {
+                ui.info(&format!(
+                    "Backup completed in {}",
+                    format_duration_pretty(elapsed)
+                ));
+            }
+        }
         Command::Verify { db_path, level } => {
             let db_path = resolve_db_path(db_path, default_db.as_ref(), "verify")?;
             let task = ui.task("Verifying on-disk structures");
@@ -1284,6 +1342,7 @@ fn build_import_config(cmd: &ImportCmd, db_path: PathBuf) -> Result<ImportConfig
         static_labels: parse_labels_list(&cmd.node_labels),
         prop_columns: parse_prop_option(&cmd.node_props),
         prop_types: parse_prop_types(&cmd.node_prop_types)?,
+        id_property: cmd.node_id_property.clone(),
     };
 
     let edge_cfg = if let Some(path) = &cmd.edges {
@@ -1309,6 +1368,7 @@ fn build_import_config(cmd: &ImportCmd, db_path: PathBuf) -> Result<ImportConfig
         build_indexes: cmd.build_indexes,
         nodes: Some(node_cfg),
         edges: edge_cfg,
+        id_offset: cmd.id_offset,
     })
 }
 
@@ -1319,6 +1379,8 @@ fn build_export_config(cmd: &ExportCmd, db_path: PathBuf) -> Result<ExportConfig
         edges_out: cmd.edges.clone(),
         node_props: parse_props_list(&cmd.node_props),
         edge_props: parse_props_list(&cmd.edge_props),
+        id_prefix: cmd.id_prefix.clone(),
+        id_offset: cmd.id_offset,
     })
 }
 
@@ -1542,6 +1604,12 @@ fn print_stats_text_detailed(ui: &Ui, report: &sombra::admin::StatsReport) {
                 ("vstore_extent_writes", format_count(space.vstore_extent_writes)),
                 ("vstore_extent_segments", format_count(space.vstore_extent_segments)),
                 ("vstore_extent_pages", format_count(space.vstore_extent_pages)),
+                ("inline_prop_writes", format_count(space.inline_prop_writes)),
+                ("overflow_prop_writes", format_count(space.overflow_prop_writes)),
+                (
+                    "overflow_prop_ratio",
+                    format!("{:.1}%", space.overflow_prop_ratio * 100.0),
+                ),
                 ("nodes_tree_pages", format_count(space.nodes_tree_pages)),
                 ("nodes_tree_bytes", format_bytes(space.nodes_tree_bytes)),
                 ("edges_tree_pages", format_count(space.edges_tree_pages)),
@@ -1850,6 +1918,19 @@ fn print_vacuum_text(ui: &Ui, report: &sombra::admin::VacuumReport) {
     }
 }
 
+fn print_backup_text(ui: &Ui, report: &sombra::admin::BackupReport) {
+    ui.section(
+        "Backup",
+        [
+            ("duration", format_duration_ms(report.duration_ms)),
+            ("copied", format_bytes(report.copied_bytes)),
+            ("pages", format_count(report.pages_copied)),
+            ("snapshot_lsn", format_count(report.snapshot_lsn)),
+            ("backup_size", format_bytes(report.backup_bytes)),
+        ],
+    );
+}
+
 fn print_verify_text(ui: &Ui, report: &sombra::admin::VerifyReport) {
     ui.section(
         "Verify",
@@ -16,8 +16,8 @@ use std::time::{Duration, Instant};
 
 use serde_json::Value;
 use sombra::ffi::{
-    Database, DatabaseOptions, MatchSpec, PayloadValue, PredicateSpec, ProjectionSpec, QuerySpec,
-    TypedBatchSpec, TypedEdgeSpec, TypedNodeRef, TypedNodeSpec, TypedPropEntry,
+    BytesFormat, Database, DatabaseOptions, MatchSpec, PayloadValue, PredicateSpec, ProjectionSpec,
+    QuerySpec, TypedBatchSpec, TypedEdgeSpec, TypedNodeRef, TypedNodeSpec, TypedPropEntry,
 };
 use sombra::primitives::pager::Synchronous;
 use sombra::storage::Dir;
@@ -93,8 +93,7 @@ fn parse_modes_from_env() -> Vec<Synchronous> {
             Some(mode) => vec![mode],
             None => {
                 eprintln!(
-                    "Unknown SYNC_MODE='{}', expected full|normal|off; defaulting to [Full, Normal]",
-                    val
+                    "Unknown SYNC_MODE='{val}', expected full|normal|off; defaulting to [Full, Normal]"
                 );
                 vec![Synchronous::Full, Synchronous::Normal]
             }
@@ -115,7 +114,7 @@ fn parse_sync_mode(value: &str) -> Option<Synchronous> {
 fn run_for_mode(mode: Synchronous, cfg: &BenchConfig) -> Result<(), Box<dyn std::error::Error>> {
     let tmpdir = TempDir::new()?;
     let db_path = tmpdir.path().join("graph_ops.sombra");
-    println!("DB path: {:?}", db_path);
+    println!("DB path: {db_path:?}");
 
     let mut opts = DatabaseOptions {
         create_if_missing: true,
@@ -133,7 +132,7 @@ fn run_for_mode(mode: Synchronous, cfg: &BenchConfig) -> Result<(), Box<dyn std:
     let (node_ids, names, write_time) = create_users_and_edges(&db, cfg.nodes, cfg.edges)?;
 
     // Ensure indexes on Node.name for realistic lookup performance.
-    let _ = db.ensure_label_indexes(&vec!["Node".to_string()])?;
+    let _ = db.ensure_label_indexes(&["Node".to_string()])?;
     let _ = db.ensure_property_index("Node", "name", "chunked", "string")?;
 
     println!(
@@ -229,6 +228,7 @@ fn make_handle_ref(index: usize) -> TypedNodeRef {
     }
 }
 
+#[allow(clippy::type_complexity)]
 fn create_users_and_edges(
     db: &Database,
     node_count: usize,
@@ -393,11 +393,29 @@ fn user_lookup_spec(name: String) -> QuerySpec {
             prop: "name".to_string(),
             value: PayloadValue::String(name),
         }),
+        filter: None,
         projections: vec![ProjectionSpec::Var {
             var: "n".to_string(),
             alias: None,
         }],
         distinct: false,
+        operator_timeout_ms: None,
+        timeout_ms: None,
+        include_schema: false,
+        include_warnings: false,
+        resolve_names: None,
+        reject_cartesian: None,
+        dedup_by: Vec::new(),
+        flatten: false,
+        max_rows: None,
+        nest: None,
+        count_per_start: false,
+        order_by: Vec::new(),
+        tiebreak_desc: None,
+        bytes_format: BytesFormat::default(),
+        limit: None,
+        offset: None,
+        limit_per_group: None,
     }
 }
 
@@ -396,7 +396,7 @@ fn print_header() {
 
 fn print_row(name: &str, btree: Duration, ifa: Duration, speedup: f64) {
     let speedup_str = if speedup >= 1.0 {
-        format!("{:.2}x faster", speedup)
+        format!("{speedup:.2}x faster")
     } else {
         format!("{:.2}x slower", 1.0 / speedup)
     };
@@ -205,7 +205,7 @@ fn main() {
             .collect();
 
         let _edge_ids = bulk.load_edges(&edges).expect("bulk load edges");
-        let _stats = bulk.finish();
+        let _stats = bulk.finish().expect("bulk load finish");
     } else {
         let chunk_nodes = chunk_nodes.unwrap_or(node_count);
         let chunk_edges = chunk_edges.unwrap_or(edge_count);
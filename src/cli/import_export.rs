@@ -5,31 +5,20 @@ use std::sync::Arc;
 
 use crate::admin::{open_graph, AdminOpenOptions, CheckpointMode, GraphHandle};
 use crate::primitives::pager::{PageStore, Pager, ReadGuard, WriteGuard};
-use crate::storage::catalog::Dict;
+use crate::storage::catalog::NameKind;
 use crate::storage::{
-    index::IndexDef, BulkEdgeValidator, CreateEdgeOptions, EdgeSpec, Graph, GraphWriter, NodeSpec,
-    PropEntry, PropValue, PropValueOwned,
+    index::IndexDef, parse_literal, BulkEdgeValidator, CreateEdgeOptions, EdgeSpec, Graph,
+    GraphWriter, LiteralError, NodeSpec, PropEntry, PropValue, PropValueOwned,
 };
+
+pub use crate::storage::PropertyType;
 use crate::types::{LabelId, NodeId, PropId, SombraError, StrId, TypeId};
 use csv::{ReaderBuilder, StringRecord, WriterBuilder};
 use thiserror::Error;
-use time::format_description::{well_known::Rfc3339, FormatItem};
-use time::macros::format_description;
-use time::{Date, Month, OffsetDateTime, PrimitiveDateTime};
 
 const NODE_BATCH_SIZE: usize = 256;
 const EDGE_BATCH_SIZE: usize = 512;
 
-const DATE_FMT: &[FormatItem<'static>] = format_description!("[year]-[month]-[day]");
-const DATETIME_FMT_T: &[FormatItem<'static>] =
-    format_description!("[year]-[month]-[day]T[hour]:[minute]:[second]");
-const DATETIME_FMT_T_FRAC: &[FormatItem<'static>] =
-    format_description!("[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond]");
-const DATETIME_FMT_SPACE: &[FormatItem<'static>] =
-    format_description!("[year]-[month]-[day] [hour]:[minute]:[second]");
-const DATETIME_FMT_SPACE_FRAC: &[FormatItem<'static>] =
-    format_description!("[year]-[month]-[day] [hour]:[minute]:[second].[subsecond]");
-
 type ExtIdMap = HashMap<String, u64>;
 
 struct NodeInsert {
@@ -51,33 +40,6 @@ struct PropInput {
     value: PropValueOwned,
 }
 
-/// Supported property type coercions for CSV imports.
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
-pub enum PropertyType {
-    /// Let the importer infer the type from literals (default).
-    Auto,
-    /// Boolean literal (`true`/`false`).
-    Bool,
-    /// 64-bit signed integer.
-    Int,
-    /// 64-bit floating point value.
-    Float,
-    /// UTF-8 string literal (disables heuristics).
-    String,
-    /// Calendar date (epoch days or `YYYY-MM-DD`).
-    Date,
-    /// Timestamp (epoch milliseconds or RFC3339).
-    DateTime,
-    /// `0x`-prefixed hex blob.
-    Bytes,
-}
-
-impl Default for PropertyType {
-    fn default() -> Self {
-        Self::Auto
-    }
-}
-
 /// Configuration for importing nodes from a CSV file.
 #[derive(Debug, Clone)]
 pub struct NodeImportConfig {
@@ -94,6 +56,8 @@ pub struct NodeImportConfig {
     pub prop_columns: Option<Vec<String>>,
     /// Explicit property type overrides keyed by column name.
     pub prop_types: HashMap<String, PropertyType>,
+    /// Optional property that records each node's (offset) external id.
+    pub id_property: Option<String>,
 }
 
 /// Configuration for importing edges from a CSV file.
@@ -135,6 +99,13 @@ pub struct ImportConfig {
     pub nodes: Option<NodeImportConfig>,
     /// Optional configuration for edge import.
     pub edges: Option<EdgeImportConfig>,
+    /// Amount added to the numeric suffix of every node, src, and dst id in
+    /// the CSV files.
+    ///
+    /// This only rewrites the external ids: it changes which nodes edges are
+    /// matched to and what [`NodeImportConfig::id_property`] records, while
+    /// the engine still allocates fresh node ids for every imported node.
+    pub id_offset: u64,
 }
 
 /// Summary statistics from an import operation.
@@ -159,6 +130,10 @@ pub struct ExportConfig {
     pub node_props: Vec<String>,
     /// List of property names to include in edge export.
     pub edge_props: Vec<String>,
+    /// Optional namespace prepended to every exported node, src, and dst id.
+    pub id_prefix: Option<String>,
+    /// Amount added to every exported node id before it is written.
+    pub id_offset: u64,
 }
 
 /// Summary statistics from an export operation.
@@ -188,6 +163,9 @@ pub enum CliError {
     /// Storage layer error.
     #[error(transparent)]
     Storage(#[from] SombraError),
+    /// CSV cell that does not parse as its property type.
+    #[error(transparent)]
+    Literal(#[from] LiteralError),
 }
 
 impl From<&str> for CliError {
@@ -245,7 +223,7 @@ pub fn run_import(cfg: &ImportConfig, opts: &AdminOpenOptions) -> Result<ImportS
             dropped_indexes = drop_all_property_indexes(&handle)?;
         }
         let mut id_map: ExtIdMap = ExtIdMap::new();
-        summary.nodes_imported = import_nodes(&handle, nodes_cfg, &mut id_map)?;
+        summary.nodes_imported = import_nodes(&handle, nodes_cfg, cfg.id_offset, &mut id_map)?;
 
         if let Some(edges_cfg) = &cfg.edges {
             if id_map.is_empty() {
@@ -253,7 +231,7 @@ pub fn run_import(cfg: &ImportConfig, opts: &AdminOpenOptions) -> Result<ImportS
                     "cannot import edges without node id mapping".into(),
                 ));
             }
-            summary.edges_imported = import_edges(&handle, edges_cfg, &mut id_map)?;
+            summary.edges_imported = import_edges(&handle, edges_cfg, cfg.id_offset, &mut id_map)?;
         }
 
         handle.pager.checkpoint(CheckpointMode::BestEffort)?;
@@ -288,12 +266,16 @@ pub fn run_export(cfg: &ExportConfig, opts: &AdminOpenOptions) -> Result<ExportS
     let handle = open_graph(&cfg.db_path, opts)?;
     let read = handle.pager.begin_latest_committed_read()?;
     let mut summary = ExportSummary::default();
+    let ids = ExportIds {
+        prefix: cfg.id_prefix.as_deref().unwrap_or_default(),
+        offset: cfg.id_offset,
+    };
 
     if let Some(path) = &cfg.nodes_out {
-        summary.nodes_exported = export_nodes(&handle, &read, path, &cfg.node_props)?;
+        summary.nodes_exported = export_nodes(&handle, &read, path, &cfg.node_props, &ids)?;
     }
     if let Some(path) = &cfg.edges_out {
-        summary.edges_exported = export_edges(&handle, &read, path, &cfg.edge_props)?;
+        summary.edges_exported = export_edges(&handle, &read, path, &cfg.edge_props, &ids)?;
     }
 
     Ok(summary)
@@ -308,6 +290,7 @@ struct ColumnSpec {
 fn import_nodes(
     handle: &GraphHandle,
     cfg: &NodeImportConfig,
+    id_offset: u64,
     id_map: &mut ExtIdMap,
 ) -> Result<u64, CliError> {
     let mut reader = ReaderBuilder::new().flexible(true).from_path(&cfg.path)?;
@@ -339,7 +322,8 @@ fn import_nodes(
                     cfg.id_column
                 ))
             })?;
-        if id_map.contains_key(ext_id) {
+        let ext_id = offset_ext_id(ext_id, id_offset)?;
+        if id_map.contains_key(&ext_id) {
             return Err(CliError::Message(format!(
                 "duplicate node id '{ext_id}' in nodes file"
             )));
@@ -358,9 +342,19 @@ fn import_nodes(
             )));
         }
 
-        let props = build_props(&record, &prop_columns)?;
+        let mut props = build_props(&record, &prop_columns)?;
+        if let Some(name) = &cfg.id_property {
+            let value = match ext_id.parse::<i64>() {
+                Ok(id) => PropValueOwned::Int(id),
+                Err(_) => PropValueOwned::Str(ext_id.clone()),
+            };
+            props.push(PropInput {
+                name: name.clone(),
+                value,
+            });
+        }
         batch.push(NodeInsert {
-            ext_id: ext_id.to_string(),
+            ext_id,
             labels,
             props,
         });
@@ -376,6 +370,7 @@ fn import_nodes(
 fn import_edges(
     handle: &GraphHandle,
     cfg: &EdgeImportConfig,
+    id_offset: u64,
     id_map: &mut ExtIdMap,
 ) -> Result<u64, CliError> {
     if cfg.static_type.is_none() && cfg.type_column.is_none() {
@@ -416,12 +411,18 @@ fn import_edges(
 
     for result in reader.records() {
         let record = result?;
-        let src_ext = get_required(&record, src_index, &cfg.src_column)?;
-        let dst_ext = get_required(&record, dst_index, &cfg.dst_column)?;
-        let src = *id_map.get(src_ext).ok_or_else(|| {
+        let src_ext = offset_ext_id(
+            get_required(&record, src_index, &cfg.src_column)?,
+            id_offset,
+        )?;
+        let dst_ext = offset_ext_id(
+            get_required(&record, dst_index, &cfg.dst_column)?,
+            id_offset,
+        )?;
+        let src = *id_map.get(&src_ext).ok_or_else(|| {
             CliError::Message(format!("edge references unknown src id '{src_ext}'"))
         })?;
-        let dst = *id_map.get(dst_ext).ok_or_else(|| {
+        let dst = *id_map.get(&dst_ext).ok_or_else(|| {
             CliError::Message(format!("edge references unknown dst id '{dst_ext}'"))
         })?;
 
@@ -465,8 +466,8 @@ fn flush_node_batch(
     let mut created = 0u64;
     for node in batch.drain(..) {
         labels_buf.clear();
-        resolve_labels(&handle.dict, &mut write, &node.labels, &mut labels_buf)?;
-        let prop_storage = collect_prop_storage(&handle.dict, &mut write, &node.props)?;
+        resolve_labels(handle, &mut write, &node.labels, &mut labels_buf)?;
+        let prop_storage = collect_prop_storage(handle, &mut write, &node.props)?;
         let prop_entries: Vec<PropEntry<'_>> = prop_storage
             .iter()
             .map(|(prop, owned)| PropEntry::new(*prop, prop_value_ref(owned)))
@@ -502,8 +503,8 @@ fn flush_edge_batch(
     let mut write = handle.pager.begin_write()?;
     let mut created = 0u64;
     for edge in batch.drain(..) {
-        let ty_id = resolve_type(&handle.dict, &mut write, &edge.ty)?;
-        let prop_storage = collect_prop_storage(&handle.dict, &mut write, &edge.props)?;
+        let ty_id = resolve_type(handle, &mut write, &edge.ty)?;
+        let prop_storage = collect_prop_storage(handle, &mut write, &edge.props)?;
         let prop_entries: Vec<PropEntry<'_>> = prop_storage
             .iter()
             .map(|(prop, owned)| PropEntry::new(*prop, prop_value_ref(owned)))
@@ -555,40 +556,50 @@ impl BulkEdgeValidator for SnapshotEdgeValidator {
 }
 
 fn resolve_labels(
-    dict: &Arc<Dict>,
+    handle: &GraphHandle,
     write: &mut WriteGuard<'_>,
     labels: &[String],
     out: &mut Vec<LabelId>,
 ) -> Result<(), CliError> {
     for label in labels {
-        let id = dict.intern(write, label)?;
+        let id = intern_name(handle, write, label, NameKind::Label)?;
         out.push(LabelId(id.0));
     }
     Ok(())
 }
 
 fn resolve_type(
-    dict: &Arc<Dict>,
+    handle: &GraphHandle,
     write: &mut WriteGuard<'_>,
     ty: &str,
 ) -> Result<TypeId, CliError> {
-    let id = dict.intern(write, ty)?;
+    let id = intern_name(handle, write, ty, NameKind::EdgeType)?;
     Ok(TypeId(id.0))
 }
 
 fn collect_prop_storage(
-    dict: &Arc<Dict>,
+    handle: &GraphHandle,
     write: &mut WriteGuard<'_>,
     props: &[PropInput],
 ) -> Result<Vec<(PropId, PropValueOwned)>, CliError> {
     let mut storage = Vec::with_capacity(props.len());
     for prop in props {
-        let id = dict.intern(write, &prop.name)?;
+        let id = intern_name(handle, write, &prop.name, NameKind::PropKey)?;
         storage.push((PropId(id.0), prop.value.clone()));
     }
     Ok(storage)
 }
 
+fn intern_name(
+    handle: &GraphHandle,
+    write: &mut WriteGuard<'_>,
+    name: &str,
+    kind: NameKind,
+) -> Result<StrId, CliError> {
+    let limit = handle.graph.name_limit(kind);
+    Ok(handle.dict.intern_as(write, name, kind, limit)?)
+}
+
 fn drop_all_property_indexes(handle: &GraphHandle) -> Result<Vec<IndexDef>, CliError> {
     let defs = handle.graph.all_property_indexes()?;
     if defs.is_empty() {
@@ -598,7 +609,7 @@ fn drop_all_property_indexes(handle: &GraphHandle) -> Result<Vec<IndexDef>, CliE
     for def in &defs {
         handle
             .graph
-            .drop_property_index(&mut write, def.label, def.prop)?;
+            .drop_property_index(&mut write, def.label, def.prop())?;
     }
     handle.pager.commit(write)?;
     Ok(defs)
@@ -610,7 +621,9 @@ fn rebuild_property_indexes(handle: &GraphHandle, defs: &[IndexDef]) -> Result<(
     }
     let mut write = handle.pager.begin_write()?;
     for def in defs {
-        handle.graph.create_property_index(&mut write, *def)?;
+        handle
+            .graph
+            .create_property_index(&mut write, def.clone())?;
     }
     handle.pager.commit(write)?;
     Ok(())
@@ -670,6 +683,26 @@ fn get_required<'a>(record: &'a StringRecord, idx: usize, name: &str) -> Result<
         .ok_or_else(|| CliError::Message(format!("missing value for column '{name}'")))
 }
 
+/// Shifts the trailing digits of an external id by `offset`, keeping any
+/// namespace prefix (`g1:7` with offset 100 becomes `g1:107`).
+fn offset_ext_id(raw: &str, offset: u64) -> Result<String, CliError> {
+    if offset == 0 {
+        return Ok(raw.to_string());
+    }
+    let split = raw.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+    let (prefix, digits) = raw.split_at(split);
+    let shifted = digits
+        .parse::<u64>()
+        .ok()
+        .and_then(|id| id.checked_add(offset))
+        .ok_or_else(|| {
+            CliError::Message(format!(
+                "cannot apply id offset {offset} to id '{raw}' (needs a numeric suffix)"
+            ))
+        })?;
+    Ok(format!("{prefix}{shifted}"))
+}
+
 fn parse_labels(raw: &str) -> Vec<String> {
     raw.split('|')
         .map(|s| s.trim())
@@ -697,159 +730,6 @@ fn build_props(record: &StringRecord, columns: &[ColumnSpec]) -> Result<Vec<Prop
     Ok(props)
 }
 
-fn parse_literal(raw: &str, ty: PropertyType) -> Result<Option<PropValueOwned>, CliError> {
-    if !matches!(ty, PropertyType::String) && raw.eq_ignore_ascii_case("null") {
-        return Ok(None);
-    }
-    let value = match ty {
-        PropertyType::Auto => return parse_auto_literal(raw),
-        PropertyType::String => PropValueOwned::Str(raw.to_string()),
-        PropertyType::Bool => {
-            let parsed = parse_bool_literal(raw)
-                .ok_or_else(|| CliError::Message(format!("invalid boolean literal '{raw}'")))?;
-            PropValueOwned::Bool(parsed)
-        }
-        PropertyType::Int => {
-            let parsed = raw
-                .parse::<i64>()
-                .map_err(|_| CliError::Message(format!("invalid integer literal '{raw}'")))?;
-            PropValueOwned::Int(parsed)
-        }
-        PropertyType::Float => {
-            let parsed = raw
-                .parse::<f64>()
-                .map_err(|_| CliError::Message(format!("invalid float literal '{raw}'")))?;
-            PropValueOwned::Float(parsed)
-        }
-        PropertyType::Date => {
-            if let Ok(days) = raw.parse::<i64>() {
-                PropValueOwned::Date(days)
-            } else {
-                PropValueOwned::Date(parse_iso_date(raw)?)
-            }
-        }
-        PropertyType::DateTime => {
-            if let Ok(ms) = raw.parse::<i64>() {
-                PropValueOwned::DateTime(ms)
-            } else {
-                PropValueOwned::DateTime(parse_iso_datetime(raw)?)
-            }
-        }
-        PropertyType::Bytes => {
-            let bytes = parse_bytes_literal(raw)?;
-            PropValueOwned::Bytes(bytes)
-        }
-    };
-    Ok(Some(value))
-}
-
-fn parse_auto_literal(raw: &str) -> Result<Option<PropValueOwned>, CliError> {
-    if raw.eq_ignore_ascii_case("null") {
-        return Ok(None);
-    }
-    if let Some(val) = parse_bool_literal(raw) {
-        return Ok(Some(PropValueOwned::Bool(val)));
-    }
-    if let Ok(int_val) = raw.parse::<i64>() {
-        return Ok(Some(PropValueOwned::Int(int_val)));
-    }
-    if let Ok(float_val) = raw.parse::<f64>() {
-        return Ok(Some(PropValueOwned::Float(float_val)));
-    }
-    if raw.starts_with("0x") || raw.starts_with("0X") {
-        let bytes = parse_bytes_literal(raw)?;
-        return Ok(Some(PropValueOwned::Bytes(bytes)));
-    }
-    if looks_like_datetime(raw) {
-        let millis = parse_iso_datetime(raw)?;
-        return Ok(Some(PropValueOwned::DateTime(millis)));
-    }
-    if looks_like_date(raw) {
-        let days = parse_iso_date(raw)?;
-        return Ok(Some(PropValueOwned::Date(days)));
-    }
-    Ok(Some(PropValueOwned::Str(raw.to_string())))
-}
-
-fn parse_bool_literal(raw: &str) -> Option<bool> {
-    match raw.to_ascii_lowercase().as_str() {
-        "true" => Some(true),
-        "false" => Some(false),
-        _ => None,
-    }
-}
-
-fn parse_bytes_literal(raw: &str) -> Result<Vec<u8>, CliError> {
-    let body = raw
-        .strip_prefix("0x")
-        .or_else(|| raw.strip_prefix("0X"))
-        .ok_or_else(|| CliError::Message("byte literal must start with 0x".into()))?;
-    if body.len() % 2 != 0 {
-        return Err(CliError::Message(
-            "byte literal must contain an even number of hex digits".into(),
-        ));
-    }
-    hex::decode(body)
-        .map_err(|_| CliError::Message(format!("byte literal '{raw}' contains non-hex characters")))
-}
-
-fn looks_like_date(raw: &str) -> bool {
-    let bytes = raw.as_bytes();
-    bytes.len() >= 10
-        && bytes[4] == b'-'
-        && bytes[7] == b'-'
-        && bytes[..4].iter().all(u8::is_ascii_digit)
-        && bytes[5..7].iter().all(u8::is_ascii_digit)
-        && bytes[8..10].iter().all(u8::is_ascii_digit)
-}
-
-fn looks_like_datetime(raw: &str) -> bool {
-    let bytes = raw.as_bytes();
-    if bytes.len() < 16 || !looks_like_date(raw) {
-        return false;
-    }
-    matches!(bytes[10], b'T' | b' ')
-}
-
-fn parse_iso_date(raw: &str) -> Result<i64, CliError> {
-    let date = Date::parse(raw, DATE_FMT)
-        .map_err(|_| CliError::Message(format!("invalid date literal '{raw}'")))?;
-    let epoch = Date::from_calendar_date(1970, Month::January, 1)
-        .expect("1970-01-01 is a valid calendar date");
-    Ok((date - epoch).whole_days())
-}
-
-fn parse_iso_datetime(raw: &str) -> Result<i64, CliError> {
-    if let Ok(dt) = OffsetDateTime::parse(raw, &Rfc3339) {
-        return nanos_to_millis(dt.unix_timestamp_nanos());
-    }
-    if let Ok(dt) = PrimitiveDateTime::parse(raw, DATETIME_FMT_T) {
-        return nanos_to_millis(dt.assume_utc().unix_timestamp_nanos());
-    }
-    if let Ok(dt) = PrimitiveDateTime::parse(raw, DATETIME_FMT_T_FRAC) {
-        return nanos_to_millis(dt.assume_utc().unix_timestamp_nanos());
-    }
-    if let Ok(dt) = PrimitiveDateTime::parse(raw, DATETIME_FMT_SPACE) {
-        return nanos_to_millis(dt.assume_utc().unix_timestamp_nanos());
-    }
-    if let Ok(dt) = PrimitiveDateTime::parse(raw, DATETIME_FMT_SPACE_FRAC) {
-        return nanos_to_millis(dt.assume_utc().unix_timestamp_nanos());
-    }
-    Err(CliError::Message(format!(
-        "invalid datetime literal '{raw}', expected RFC3339 or 'YYYY-MM-DD HH:MM:SS'"
-    )))
-}
-
-fn nanos_to_millis(nanos: i128) -> Result<i64, CliError> {
-    let millis = nanos / 1_000_000;
-    if millis < i64::MIN as i128 || millis > i64::MAX as i128 {
-        return Err(CliError::Message(
-            "datetime literal is outside the supported range".into(),
-        ));
-    }
-    Ok(millis as i64)
-}
-
 fn prop_value_ref(value: &PropValueOwned) -> PropValue<'_> {
     match value {
         PropValueOwned::Null => PropValue::Null,
@@ -863,11 +743,30 @@ fn prop_value_ref(value: &PropValueOwned) -> PropValue<'_> {
     }
 }
 
+/// Namespace and offset applied to node ids written by an export.
+struct ExportIds<'a> {
+    prefix: &'a str,
+    offset: u64,
+}
+
+impl ExportIds<'_> {
+    fn format(&self, id: NodeId) -> Result<String, CliError> {
+        let shifted = id.0.checked_add(self.offset).ok_or_else(|| {
+            CliError::Message(format!(
+                "id offset {} overflows node id {}",
+                self.offset, id.0
+            ))
+        })?;
+        Ok(format!("{}{shifted}", self.prefix))
+    }
+}
+
 fn export_nodes(
     handle: &GraphHandle,
     read: &ReadGuard,
     path: &Path,
     props: &[String],
+    ids: &ExportIds<'_>,
 ) -> Result<u64, CliError> {
     let mut writer = WriterBuilder::new().from_path(path)?;
     let mut header = Vec::with_capacity(2 + props.len());
@@ -881,7 +780,7 @@ fn export_nodes(
         let labels = format_labels(handle, read, &data.labels)?;
         let prop_map = materialize_props(handle, read, &data.props)?;
         let mut row = Vec::with_capacity(header.len());
-        row.push(node_id.0.to_string());
+        row.push(ids.format(*node_id)?);
         row.push(labels);
         for prop in props {
             row.push(prop_map.get(prop).cloned().unwrap_or_default());
@@ -897,6 +796,7 @@ fn export_edges(
     read: &ReadGuard,
     path: &Path,
     props: &[String],
+    ids: &ExportIds<'_>,
 ) -> Result<u64, CliError> {
     let mut writer = WriterBuilder::new().from_path(path)?;
     let mut header = Vec::with_capacity(3 + props.len());
@@ -911,8 +811,8 @@ fn export_edges(
         let ty_name = resolve_name(handle, read, data.ty.0, "TYPE")?;
         let prop_map = materialize_props(handle, read, &data.props)?;
         let mut row = Vec::with_capacity(header.len());
-        row.push(data.src.0.to_string());
-        row.push(data.dst.0.to_string());
+        row.push(ids.format(data.src)?);
+        row.push(ids.format(data.dst)?);
         row.push(ty_name);
         for prop in props {
             row.push(prop_map.get(prop).cloned().unwrap_or_default());
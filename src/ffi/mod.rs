@@ -6,45 +6,62 @@
 //! bindings can submit JSON-friendly query specifications without reimplementing
 //! the core logic.
 
+use crate::primitives::io::MemFileIo;
 use crate::primitives::pager::{
-    CheckpointMode, PageStore, Pager, PagerOptions, ReadGuard, Synchronous, WriteGuard,
+    load_meta, CheckpointMode, OpenReport, PageStore, Pager, PagerOptions, ReadGuard,
+    RecoveryOptions, Synchronous, VerifyOnOpen, WriteGuard,
 };
 use crate::query::{
     analyze::{self, MAX_BYTES_LITERAL, MAX_IN_VALUES},
     ast::{
-        BoolExpr, Comparison, EdgeClause, EdgeDirection, MatchClause, Projection, QueryAst, Var,
+        AggFunc, BoolExpr, Comparison, EdgeClause, EdgeDirection, GroupLimit, MatchClause,
+        OrderKey, OrderTarget, Projection, QueryAst, Var,
     },
     errors::{AnalyzerError, SchemaVersionState},
-    executor::{Executor, QueryResult, ResultStream, Row, Value as ExecValue},
+    executor::{
+        node_prop_matches, ExecOptions, Executor, PageCursor, PropComparison, QueryResult,
+        ResultStream, Row, Value as ExecValue, QUERY_TIMED_OUT,
+    },
     metadata::{CatalogMetadata, MetadataProvider},
-    planner::{ExplainNode, PlanExplain, Planner, PlannerConfig, PlannerOutput},
-    profile::profile_snapshot as query_profile_snapshot,
+    physical::{LiteralValue, PhysicalOp, PhysicalPlan, ProjectField},
+    planner::{
+        ExplainNode, PlanExplain, Planner, PlannerConfig, PlannerOutput, ScanCandidate,
+        UnindexedPredicate,
+    },
+    profile::{
+        begin_local_profile as begin_local_query_profile,
+        profile_snapshot as query_profile_snapshot, take_local_profile as take_local_query_profile,
+    },
     Value as QueryValue,
 };
-use crate::storage::catalog::{Dict, DictOptions};
+use crate::storage::catalog::{Dict, DictOptions, NameKind};
 use crate::storage::VersionCodecKind;
 use crate::storage::{
-    profile_timer as storage_profile_timer, record_profile_timer as record_storage_profile_timer,
-    StorageProfileKind,
+    parse_literal, BfsOptions, ComponentMode, DeleteNodeOpts, Dir, EdgeData,
+    EdgeSpec as StorageEdgeSpec, ExpandOpts, Graph, GraphOptions, IndexDef, IndexKind, IndexState,
+    Neighbor, NeighborScan, NodeData, NodeSpec as StorageNodeSpec, PageRankOpts, PropEntry,
+    PropPatch, PropPatchOp, PropValue, PropValueOwned, PropertyType, SpaceReport, SuspendedIndexes,
+    TypeTag,
 };
 use crate::storage::{
-    BfsOptions, DeleteNodeOpts, Dir, EdgeData, EdgeSpec as StorageEdgeSpec, ExpandOpts, Graph,
-    GraphOptions, IndexDef, IndexKind, NodeData, NodeSpec as StorageNodeSpec, PropEntry, PropPatch,
-    PropPatchOp, PropValue, PropValueOwned, TypeTag,
+    profile_timer as storage_profile_timer, record_profile_timer as record_storage_profile_timer,
+    StorageProfileKind,
 };
 use crate::types::{EdgeId, LabelId, NodeId, PropId, SombraError, StrId, TypeId};
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use csv::{ReaderBuilder, StringRecord};
 use serde::{Deserialize, Serialize};
-use serde_json::{Map, Number, Value};
+use serde_json::{json, Map, Number, Value};
 use std::cmp::Ordering;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering as AtomicOrdering};
 use std::{
-    collections::{HashMap, HashSet},
-    fs, mem,
+    cell::RefCell,
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt, fs, mem,
     ops::Bound,
-    path::Path,
-    sync::{Arc, Mutex, OnceLock},
-    time::Instant,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, OnceLock, Weak},
+    time::{Duration, Instant},
 };
 use thiserror::Error;
 
@@ -55,7 +72,7 @@ pub type Result<T> = std::result::Result<T, FfiError>;
 ///
 /// Contains timing and count statistics for various phases of query planning,
 /// execution, and serialization to help identify performance bottlenecks.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Default)]
 pub struct ProfileSnapshot {
     /// Total time spent planning queries (nanoseconds).
     pub plan_ns: u64,
@@ -129,6 +146,26 @@ pub struct NeighborInfo {
     pub type_id: u32,
 }
 
+/// One binding of a [`PatternSpec`] returned by [`Database::match_pattern`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PatternMatch {
+    /// Node id bound to each pattern variable.
+    pub node_bindings: BTreeMap<String, u64>,
+    /// Id of the edge bound to each pattern edge, in pattern order.
+    pub edge_ids: Vec<u64>,
+}
+
+/// Column entry reported when a query asks for its result schema.
+#[derive(Debug, Clone)]
+struct ColumnSchema {
+    /// Key the column is written under in each row.
+    name: String,
+    /// Value type inferred from the projection (`node`, `nodeId`, `int`, `path`,
+    /// an indexed property type, or `any` when it cannot be known up front).
+    ty: &'static str,
+}
+
 /// Visit entry returned by breadth-first traversal helpers.
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -139,6 +176,26 @@ pub struct BfsVisitInfo {
     pub depth: u32,
 }
 
+/// PageRank score computed for a node.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageRankScore {
+    /// Node identifier.
+    pub node_id: u64,
+    /// Rank share of the node; scores across the graph sum to one.
+    pub score: f64,
+}
+
+/// Component count and sizes produced by [`Database::component_summary`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentSummary {
+    /// Number of components found.
+    pub count: u64,
+    /// Node count of each component, largest first.
+    pub sizes: Vec<u64>,
+}
+
 /// Materialized node payload returned by direct lookups.
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -253,6 +310,11 @@ impl LatencyHistogram {
 static PROFILE_ENABLED: OnceLock<bool> = OnceLock::new();
 static PROFILE_COUNTERS: OnceLock<ProfileCounters> = OnceLock::new();
 
+thread_local! {
+    /// Plan/exec/serde totals for the [`LocalProfileScope`] active on this thread.
+    static LOCAL_PROFILE: RefCell<Option<ProfileSnapshot>> = const { RefCell::new(None) };
+}
+
 fn profiling_enabled() -> bool {
     *PROFILE_ENABLED.get_or_init(|| {
         match std::env::var("SOMBRA_PROFILE") {
@@ -270,17 +332,35 @@ fn profile_counters() -> Option<&'static ProfileCounters> {
 }
 
 fn profile_timer() -> Option<Instant> {
-    profiling_enabled().then(Instant::now)
+    let local = LOCAL_PROFILE.with(|local| local.borrow().is_some());
+    (profiling_enabled() || local).then(Instant::now)
 }
 
 fn record_profile_timer(kind: ProfileKind, start: Option<Instant>) {
     let Some(start) = start else {
         return;
     };
+    let nanos = start.elapsed().as_nanos().min(u64::MAX as u128) as u64;
+    let recorded_locally = LOCAL_PROFILE.with(|local| {
+        let mut local = local.borrow_mut();
+        let Some(snapshot) = local.as_mut() else {
+            return false;
+        };
+        let (ns, count) = match kind {
+            ProfileKind::Plan => (&mut snapshot.plan_ns, &mut snapshot.plan_count),
+            ProfileKind::Execute => (&mut snapshot.exec_ns, &mut snapshot.exec_count),
+            ProfileKind::Serialize => (&mut snapshot.serde_ns, &mut snapshot.serde_count),
+        };
+        *ns = ns.saturating_add(nanos);
+        *count = count.saturating_add(1);
+        true
+    });
+    if recorded_locally {
+        return;
+    }
     let Some(counters) = profile_counters() else {
         return;
     };
-    let nanos = start.elapsed().as_nanos().min(u64::MAX as u128) as u64;
     match kind {
         ProfileKind::Plan => {
             counters.plan_ns.fetch_add(nanos, AtomicOrdering::Relaxed);
@@ -400,6 +480,58 @@ enum ProfileKind {
     Serialize,
 }
 
+/// Captures FFI and query timings on the current thread into local counters.
+///
+/// While the scope is alive, timers run regardless of `SOMBRA_PROFILE` and
+/// the global counters read by [`profile_snapshot`] are left untouched.
+/// Dropping the scope without calling [`LocalProfileScope::finish`] simply
+/// discards what it captured.
+struct LocalProfileScope;
+
+impl LocalProfileScope {
+    fn begin() -> Self {
+        LOCAL_PROFILE.with(|local| *local.borrow_mut() = Some(ProfileSnapshot::default()));
+        begin_local_query_profile();
+        Self
+    }
+
+    /// Ends the scope and merges the FFI and query counters it captured.
+    ///
+    /// Storage-level counters (the `query_prop_index_*` lookup, encode and
+    /// stream fields) are process-wide only and stay zero here. The exec
+    /// percentiles collapse to the exact exec time, since a scope normally
+    /// covers a single query.
+    fn finish(self) -> ProfileSnapshot {
+        let mut snapshot = LOCAL_PROFILE
+            .with(|local| local.borrow_mut().take())
+            .unwrap_or_default();
+        let query = take_local_query_profile();
+        snapshot.query_read_guard_ns = query.read_guard_ns;
+        snapshot.query_read_guard_count = query.read_guard_count;
+        snapshot.query_stream_build_ns = query.stream_build_ns;
+        snapshot.query_stream_build_count = query.stream_build_count;
+        snapshot.query_stream_iter_ns = query.stream_iter_ns;
+        snapshot.query_stream_iter_count = query.stream_iter_count;
+        snapshot.query_prop_index_ns = query.prop_index_ns;
+        snapshot.query_prop_index_count = query.prop_index_count;
+        snapshot.query_expand_ns = query.expand_ns;
+        snapshot.query_expand_count = query.expand_count;
+        snapshot.query_filter_ns = query.filter_ns;
+        snapshot.query_filter_count = query.filter_count;
+        snapshot.exec_p50_ns = snapshot.exec_ns;
+        snapshot.exec_p90_ns = snapshot.exec_ns;
+        snapshot.exec_p99_ns = snapshot.exec_ns;
+        snapshot
+    }
+}
+
+impl Drop for LocalProfileScope {
+    fn drop(&mut self) {
+        LOCAL_PROFILE.with(|local| local.borrow_mut().take());
+        take_local_query_profile();
+    }
+}
+
 /// Error codes for FFI operations, useful for programmatic error handling in bindings.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[repr(u32)]
@@ -428,6 +560,10 @@ pub enum ErrorCode {
     NotFound = 16,
     /// Database is closed.
     Closed = 17,
+    /// Operation exceeded its time budget.
+    Timeout = 18,
+    /// Write rejected by a schema constraint such as a unique index.
+    ConstraintViolation = 19,
 }
 
 impl ErrorCode {
@@ -446,6 +582,8 @@ impl ErrorCode {
             ErrorCode::InvalidArg => "INVALID_ARG",
             ErrorCode::NotFound => "NOT_FOUND",
             ErrorCode::Closed => "CLOSED",
+            ErrorCode::Timeout => "TIMEOUT",
+            ErrorCode::ConstraintViolation => "CONSTRAINT_VIOLATION",
         }
     }
 }
@@ -467,6 +605,9 @@ pub enum FfiError {
     /// A JSON serialization/deserialization error.
     #[error(transparent)]
     Json(#[from] serde_json::Error),
+    /// The query ran past the deadline set by [`QuerySpec::timeout_ms`].
+    #[error("query timed out")]
+    QueryTimeout,
 }
 
 impl FfiError {
@@ -476,12 +617,15 @@ impl FfiError {
             FfiError::Message(_) => ErrorCode::Message,
             FfiError::Analyzer(_) => ErrorCode::Analyzer,
             FfiError::Json(_) => ErrorCode::Json,
+            FfiError::QueryTimeout => ErrorCode::Timeout,
             FfiError::Core(err) => match err {
                 SombraError::Io(_) => ErrorCode::Io,
                 SombraError::Corruption(_) => ErrorCode::Corruption,
                 SombraError::Conflict(_) => ErrorCode::Conflict,
                 SombraError::SnapshotTooOld(_) => ErrorCode::SnapshotTooOld,
                 SombraError::Cancelled => ErrorCode::Cancelled,
+                SombraError::Timeout(_) => ErrorCode::Timeout,
+                SombraError::ConstraintViolation(_) => ErrorCode::ConstraintViolation,
                 SombraError::Invalid(_) | SombraError::InvalidOwned(_) => ErrorCode::InvalidArg,
                 SombraError::NotFound => ErrorCode::NotFound,
             },
@@ -499,6 +643,14 @@ impl FfiError {
     }
 }
 
+/// Converts an execution error, recognising the executor's deadline expiry.
+fn exec_error(err: SombraError) -> FfiError {
+    match err {
+        SombraError::Timeout(msg) if msg == QUERY_TIMED_OUT => FfiError::QueryTimeout,
+        other => other.into(),
+    }
+}
+
 /// Configuration options for opening a Sombra database via FFI.
 #[derive(Clone, Debug)]
 pub struct DatabaseOptions {
@@ -522,6 +674,140 @@ pub struct DatabaseOptions {
     pub snapshot_pool_size: usize,
     /// Maximum age in milliseconds for cached snapshots.
     pub snapshot_pool_max_age_ms: u64,
+    /// Page checksum verification to run when opening an existing database.
+    pub verify_on_open: VerifyOnOpen,
+    /// How repeated keys within one typed property list or JSON spec
+    /// property object are resolved.
+    pub duplicate_props: DuplicatePropertyPolicy,
+    /// Discard damaged WAL frames, and every commit after them, instead of
+    /// failing to open. A partially written commit at the end of the WAL is
+    /// always discarded; main-file corruption is never repaired.
+    pub auto_repair: bool,
+    /// Fails the open once WAL recovery has run this long instead of
+    /// blocking until it finishes; `None` leaves recovery unbounded.
+    pub max_recovery_time: Option<Duration>,
+    /// Record equality and range predicates that forced full label scans so
+    /// [`Database::index_advice`] can suggest indexes.
+    pub index_advisor: bool,
+    /// Create a label index the first time a write uses a label. When
+    /// disabled, only [`Database::ensure_label_indexes`] creates them and
+    /// label queries scan node records instead.
+    pub auto_label_index: bool,
+    /// Rejects queries binding more match clauses than this before they
+    /// touch the graph; `None` leaves patterns unbounded.
+    pub max_pattern_nodes: Option<usize>,
+    /// Rejects queries joining more edge clauses than this; `None` leaves
+    /// patterns unbounded.
+    pub max_pattern_edges: Option<usize>,
+    /// Maximum number of distinct label names the database accepts; `None` is unlimited.
+    pub max_distinct_labels: Option<usize>,
+    /// Maximum number of distinct edge type names the database accepts; `None` is unlimited.
+    pub max_distinct_types: Option<usize>,
+    /// Maximum number of distinct property key names the database accepts; `None` is unlimited.
+    pub max_distinct_props: Option<usize>,
+}
+
+/// Visibility of a [`Transaction`]'s own pending writes to its reads.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum IsolationLevel {
+    /// Reads see the database as it was when the transaction began, plus
+    /// every write the transaction has made so far.
+    #[default]
+    Snapshot,
+    /// Reads see only committed data: each read returns the latest committed
+    /// state and never the transaction's own pending writes. A transaction
+    /// holds the writer lock, so no other commit can land while it is open
+    /// and repeated reads stay stable until it commits.
+    ReadCommitted,
+}
+
+/// Resolution applied when an input spec repeats a property key.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DuplicatePropertyPolicy {
+    /// Keep the value of the last occurrence, at the position of the first.
+    #[default]
+    LastWins,
+    /// Reject the spec with an error naming the repeated key.
+    Error,
+}
+
+/// Spec fields whose object value is a property map.
+const PROP_MAP_FIELDS: [&str; 2] = ["props", "set"];
+
+/// Walks JSON text and rejects property maps that repeat a key.
+///
+/// A parsed [`Value`] keeps only the last of repeated keys, so the check has to
+/// run on the text before it is parsed.
+struct DuplicatePropCheck {
+    prop_map: bool,
+}
+
+impl<'de> serde::de::DeserializeSeed<'de> for DuplicatePropCheck {
+    type Value = ();
+
+    fn deserialize<D: serde::Deserializer<'de>>(self, de: D) -> std::result::Result<(), D::Error> {
+        de.deserialize_any(self)
+    }
+}
+
+impl<'de> serde::de::Visitor<'de> for DuplicatePropCheck {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a JSON value")
+    }
+
+    fn visit_bool<E>(self, _: bool) -> std::result::Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_i64<E>(self, _: i64) -> std::result::Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_u64<E>(self, _: u64) -> std::result::Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_f64<E>(self, _: f64) -> std::result::Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_str<E>(self, _: &str) -> std::result::Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_unit<E>(self) -> std::result::Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_seq<A: serde::de::SeqAccess<'de>>(
+        self,
+        mut seq: A,
+    ) -> std::result::Result<(), A::Error> {
+        while seq
+            .next_element_seed(DuplicatePropCheck { prop_map: false })?
+            .is_some()
+        {}
+        Ok(())
+    }
+
+    fn visit_map<A: serde::de::MapAccess<'de>>(
+        self,
+        mut map: A,
+    ) -> std::result::Result<(), A::Error> {
+        let mut seen = HashSet::new();
+        while let Some(key) = map.next_key::<String>()? {
+            let prop_map = PROP_MAP_FIELDS.contains(&key.as_str());
+            map.next_value_seed(DuplicatePropCheck { prop_map })?;
+            if self.prop_map && !seen.insert(key.clone()) {
+                return Err(serde::de::Error::custom(format!(
+                    "duplicate property key '{key}'"
+                )));
+            }
+        }
+        Ok(())
+    }
 }
 
 impl Default for DatabaseOptions {
@@ -537,6 +823,17 @@ impl Default for DatabaseOptions {
             version_codec_min_savings_bytes: 8,
             snapshot_pool_size: 0,
             snapshot_pool_max_age_ms: 200,
+            verify_on_open: VerifyOnOpen::Off,
+            duplicate_props: DuplicatePropertyPolicy::LastWins,
+            auto_repair: false,
+            max_recovery_time: None,
+            index_advisor: false,
+            auto_label_index: true,
+            max_pattern_nodes: None,
+            max_pattern_edges: None,
+            max_distinct_labels: None,
+            max_distinct_types: None,
+            max_distinct_props: None,
         }
     }
 }
@@ -627,6 +924,155 @@ impl Drop for CancellationHandleInner {
     }
 }
 
+/// Outcome of validating a query spec with [`Database::validate_json`].
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationReport {
+    /// Problems that would make execution fail. The analyzer stops at the
+    /// first one, so at most one error is reported.
+    pub errors: Vec<ValidationIssue>,
+    /// Conditions that let the query run but likely make it slow.
+    pub warnings: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    /// Returns `true` when the spec would pass analysis.
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// Single finding in a [`ValidationReport`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationIssue {
+    /// Machine-readable code, matching [`AnalyzerError::code`] for errors.
+    pub code: String,
+    /// Human-readable description.
+    pub message: String,
+}
+
+impl From<&AnalyzerError> for ValidationIssue {
+    fn from(err: &AnalyzerError) -> Self {
+        Self {
+            code: err.code().to_string(),
+            message: err.to_string(),
+        }
+    }
+}
+
+/// One page of rows returned by [`Database::execute_paged_json`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PagedResult {
+    /// Rows on this page, in result order.
+    pub rows: Vec<Value>,
+    /// Opaque token that resumes after the last row, or `None` on the last page.
+    pub next_token: Option<String>,
+}
+
+/// Label and type names for a page of entities, from [`Database::entity_names`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EntityNames {
+    /// Label names per requested node, in request order; `None` when the node
+    /// is missing.
+    pub node_labels: Vec<Option<Vec<String>>>,
+    /// Type name per requested edge, in request order; `None` when the edge
+    /// is missing.
+    pub edge_types: Vec<Option<String>>,
+}
+
+/// Property that nodes carrying a label must set, registered through
+/// [`Database::require_property`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RequiredProperty {
+    /// Label the constraint applies to.
+    pub label: String,
+    /// Property every node with the label must set to a non-null value.
+    pub prop: String,
+}
+
+/// Property index suggested by the index advisor.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexSuggestion {
+    /// Label whose nodes were scanned in full.
+    pub label: String,
+    /// Property the scans filtered on.
+    pub prop: String,
+    /// Number of unindexed predicates on this property evaluated by full scans.
+    pub hits: u64,
+    /// How many of those predicates were equalities; the rest were ranges.
+    pub equality_hits: u64,
+    /// Average estimated fraction of scanned nodes an index would have skipped.
+    pub estimated_benefit: f64,
+}
+
+#[derive(Default)]
+struct AdvisorEntry {
+    hits: u64,
+    equality_hits: u64,
+    benefit_sum: f64,
+}
+
+/// Aggregates unindexed predicates reported by the planner for executed queries.
+#[derive(Default)]
+struct IndexAdvisor {
+    entries: Mutex<HashMap<(String, String), AdvisorEntry>>,
+}
+
+impl IndexAdvisor {
+    fn record(&self, predicates: &[UnindexedPredicate]) {
+        if predicates.is_empty() {
+            return;
+        }
+        let mut entries = self
+            .entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        for pred in predicates {
+            let entry = entries
+                .entry((pred.label.clone(), pred.prop.clone()))
+                .or_default();
+            entry.hits += 1;
+            if pred.equality {
+                entry.equality_hits += 1;
+            }
+            entry.benefit_sum += (1.0 - pred.selectivity).clamp(0.0, 1.0);
+        }
+    }
+
+    /// Returns suggestions ordered by total expected savings (hits times
+    /// benefit), then by hit count.
+    fn suggestions(&self) -> Vec<IndexSuggestion> {
+        // The counters stay consistent even if a recorder panicked mid-update.
+        let entries = self
+            .entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut out: Vec<IndexSuggestion> = entries
+            .iter()
+            .map(|((label, prop), entry)| IndexSuggestion {
+                label: label.clone(),
+                prop: prop.clone(),
+                hits: entry.hits,
+                equality_hits: entry.equality_hits,
+                estimated_benefit: entry.benefit_sum / entry.hits as f64,
+            })
+            .collect();
+        let savings = |s: &IndexSuggestion| s.hits as f64 * s.estimated_benefit;
+        out.sort_by(|a, b| {
+            savings(b)
+                .partial_cmp(&savings(a))
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| b.hits.cmp(&a.hits))
+                .then_with(|| (&a.label, &a.prop).cmp(&(&b.label, &b.prop)))
+        });
+        out
+    }
+}
+
 /// Shared database handle used by language bindings (Node.js, Python, etc.).
 ///
 /// This is the main entry point for FFI clients to interact with the Sombra database.
@@ -640,6 +1086,10 @@ pub struct Database {
     planner: Planner,
     executor: Executor,
     cancellations: Arc<CancellationRegistry>,
+    duplicate_props: DuplicatePropertyPolicy,
+    open_report: OpenReport,
+    index_advisor: Option<IndexAdvisor>,
+    inferred_schema: Mutex<InferredSchema>,
 }
 
 impl Database {
@@ -659,12 +1109,29 @@ impl Database {
         if should_create {
             ensure_parent_dir(path)?;
         }
-        let pager = if should_create {
-            Arc::new(Pager::create(path, opts.pager.clone())?)
+        let (pager, open_report) = if should_create {
+            (
+                Arc::new(Pager::create(path, opts.pager.clone())?),
+                OpenReport::default(),
+            )
         } else {
-            Arc::new(Pager::open(path, opts.pager.clone())?)
+            let recovery = RecoveryOptions {
+                repair: opts.auto_repair,
+                max_time: opts.max_recovery_time,
+            };
+            let (pager, report) = Pager::open_recovering(path, opts.pager.clone(), recovery)?;
+            pager.verify_page_checksums(opts.verify_on_open)?;
+            (Arc::new(pager), report)
         };
+        Self::from_pager(pager, open_report, opts)
+    }
 
+    /// Builds the graph, dictionary, and query engine over an opened pager.
+    fn from_pager(
+        pager: Arc<Pager>,
+        open_report: OpenReport,
+        opts: DatabaseOptions,
+    ) -> Result<Self> {
         let store: Arc<dyn PageStore> = pager.clone();
         let mut graph_opts = GraphOptions::new(Arc::clone(&store));
         graph_opts = graph_opts
@@ -676,12 +1143,33 @@ impl Database {
             .version_codec_min_savings_bytes(opts.version_codec_min_savings_bytes)
             .snapshot_pool_size(opts.snapshot_pool_size)
             .snapshot_pool_max_age_ms(opts.snapshot_pool_max_age_ms)
+            .auto_label_index(opts.auto_label_index)
             // Enable deferred flush for bulk write performance
             .defer_adjacency_flush(true)
             .defer_index_flush(true);
+        graph_opts.max_distinct_labels = opts.max_distinct_labels;
+        graph_opts.max_distinct_types = opts.max_distinct_types;
+        graph_opts.max_distinct_props = opts.max_distinct_props;
         let graph = Graph::open(graph_opts)?;
 
         let dict = Arc::new(Dict::open(Arc::clone(&store), DictOptions::default())?);
+        if !dict.name_kinds_complete()? {
+            let mut write = pager.begin_write()?;
+            let names = graph.schema_name_ids(&mut write)?;
+            dict.backfill_name_kinds(&mut write, &names)?;
+            pager.commit(write)?;
+        }
+        // Indexes a deferred-index load suspended but never restored are
+        // still building; backfill them before anything queries the graph.
+        if graph
+            .all_property_indexes()?
+            .iter()
+            .any(|def| def.state == IndexState::Building)
+        {
+            let mut write = pager.begin_write()?;
+            graph.finish_index_builds(&mut write)?;
+            pager.commit(write)?;
+        }
         let catalog_root = graph.index_catalog_root();
         let metadata: Arc<dyn MetadataProvider> = Arc::new(CatalogMetadata::from_parts(
             Arc::clone(&dict),
@@ -689,7 +1177,15 @@ impl Database {
             catalog_root,
             Arc::clone(&graph),
         )?);
-        let planner = Planner::new(PlannerConfig::default(), Arc::clone(&metadata));
+        let planner = Planner::new(
+            PlannerConfig {
+                adjacency_backend: graph.adjacency_backend(),
+                max_pattern_nodes: opts.max_pattern_nodes,
+                max_pattern_edges: opts.max_pattern_edges,
+                ..PlannerConfig::default()
+            },
+            Arc::clone(&metadata),
+        );
         let executor = Executor::new(
             Arc::clone(&graph),
             Arc::clone(&pager),
@@ -705,9 +1201,65 @@ impl Database {
             planner,
             executor,
             cancellations,
+            duplicate_props: opts.duplicate_props,
+            open_report,
+            index_advisor: opts.index_advisor.then(IndexAdvisor::default),
+            inferred_schema: Mutex::new(InferredSchema::default()),
         })
     }
 
+    /// Opens a byte image produced by [`Database::to_bytes`] as an in-memory
+    /// database.
+    ///
+    /// The main database file stays in memory and is never written to `path`;
+    /// `path` only places the WAL and lock files, so it must not name an
+    /// existing file. `opts.pager.page_size` must match the page size the
+    /// image was written with. Images larger than [`MAX_SNAPSHOT_BYTES`] are
+    /// rejected.
+    pub fn open_from_bytes(
+        path: impl AsRef<Path>,
+        bytes: &[u8],
+        opts: DatabaseOptions,
+    ) -> Result<Self> {
+        let path = path.as_ref();
+        if bytes.len() > MAX_SNAPSHOT_BYTES {
+            return Err(FfiError::Message(format!(
+                "database image of {} bytes exceeds the {MAX_SNAPSHOT_BYTES}-byte limit",
+                bytes.len()
+            )));
+        }
+        if path.exists() {
+            return Err(FfiError::Message(format!(
+                "snapshot destination already exists: {}",
+                path.display()
+            )));
+        }
+        let page_size = opts.pager.page_size;
+        let meta_page = bytes.get(..page_size as usize).unwrap_or(bytes);
+        load_meta(&MemFileIo::new(meta_page.to_vec()), page_size)?;
+        ensure_parent_dir(path)?;
+        let pager = Pager::open_image(path, bytes.to_vec(), opts.pager.clone())?;
+        pager.verify_page_checksums(opts.verify_on_open)?;
+        Self::from_pager(Arc::new(pager), OpenReport::default(), opts)
+    }
+
+    /// Returns what WAL recovery replayed, and what
+    /// [`DatabaseOptions::auto_repair`] discarded, while opening.
+    pub fn open_report(&self) -> &OpenReport {
+        &self.open_report
+    }
+
+    /// Returns the pager, graph and dictionary backing this handle, so
+    /// maintenance tools such as integrity checks can run against the open
+    /// database.
+    pub fn storage_parts(&self) -> (Arc<Pager>, Arc<Graph>, Arc<Dict>) {
+        (
+            Arc::clone(&self.pager),
+            Arc::clone(&self.graph),
+            Arc::clone(&self.dict),
+        )
+    }
+
     /// Closes the database, ensuring all data is persisted.
     ///
     /// This method performs a checkpoint to flush all WAL data to the main
@@ -726,6 +1278,19 @@ impl Database {
         drop(self);
     }
 
+    /// Opens a write transaction whose reads follow `isolation`.
+    ///
+    /// The transaction holds the writer lock until it is committed or
+    /// dropped.
+    pub fn begin_transaction(&self, isolation: IsolationLevel) -> Result<Transaction<'_>> {
+        Ok(Transaction {
+            db: self,
+            write: self.pager.begin_write()?,
+            isolation,
+            aborted: false,
+        })
+    }
+
     /// Begins a non-atomic bulk load session.
     ///
     /// The returned handle can be used to load nodes and edges in
@@ -739,10 +1304,156 @@ impl Database {
             type_cache: HashMap::new(),
             prop_cache: HashMap::new(),
             ensured_label_indexes: HashSet::new(),
+            suspended: SuspendedIndexes::default(),
+            deferred_labels: Vec::new(),
             stats: BulkLoadStats::default(),
         }
     }
 
+    /// Imports nodes and edges from CSV files through a bulk load session.
+    ///
+    /// Every nodes row needs a value in [`CsvImportOptions::id_column`]; edges
+    /// rows refer to those ids in their source and destination columns. The
+    /// remaining columns become properties, coerced per
+    /// [`CsvImportOptions::column_types`] and inferred otherwise. Rows that
+    /// fail to parse, reference unknown nodes or are rejected by storage, for
+    /// example by a unique index, are skipped and reported in
+    /// [`CsvImportSummary::failed_rows`]. A chunk that storage rejects is
+    /// retried one row at a time to find those rows. Only I/O and other
+    /// storage failures abort the import; like [`Database::begin_bulk_load`],
+    /// chunks already loaded stay committed when it does.
+    pub fn import_csv(
+        &self,
+        nodes_csv: &Path,
+        edges_csv: &Path,
+        opts: CsvImportOptions,
+    ) -> Result<CsvImportSummary> {
+        let mut failed_rows = Vec::new();
+        let mut bulk = self.begin_bulk_load(opts.bulk);
+        // External id to node id; `None` until the node's chunk is loaded.
+        let mut ids: HashMap<String, Option<NodeId>> = HashMap::new();
+
+        let mut reader = open_csv(nodes_csv)?;
+        let headers = csv_headers(&mut reader, nodes_csv)?;
+        let id_index = csv_column(&headers, &opts.id_column, nodes_csv)?;
+        let label_index = match &opts.label_column {
+            Some(name) => Some(csv_column(&headers, name, nodes_csv)?),
+            None => None,
+        };
+        let node_columns = csv_prop_columns(&headers, &opts, &[Some(id_index), label_index]);
+        let chunk_size = opts.bulk.node_chunk_size.max(1);
+        let mut pending: Vec<TypedNodeSpec> = Vec::with_capacity(chunk_size);
+        let mut pending_rows: Vec<(String, Option<u64>)> = Vec::with_capacity(chunk_size);
+        for record in reader.records() {
+            let record = match record {
+                Ok(record) => record,
+                Err(err) if err.is_io_error() => return Err(csv_io_error(nodes_csv, err)),
+                Err(err) => {
+                    failed_rows.push(CsvRowError::from_csv(nodes_csv, &err));
+                    continue;
+                }
+            };
+            let row = csv_node_row(&record, id_index, label_index, &node_columns, &opts).and_then(
+                |(ext_id, spec)| {
+                    if ids.contains_key(&ext_id) {
+                        return Err(format!("duplicate node id '{ext_id}'"));
+                    }
+                    Ok((ext_id, spec))
+                },
+            );
+            match row {
+                Ok((ext_id, spec)) => {
+                    ids.insert(ext_id.clone(), None);
+                    pending_rows.push((ext_id, csv_line(&record)));
+                    pending.push(spec);
+                }
+                Err(message) => {
+                    failed_rows.push(CsvRowError::at(nodes_csv, &record, message));
+                    continue;
+                }
+            }
+            if pending.len() >= chunk_size {
+                load_csv_nodes(
+                    &mut bulk,
+                    nodes_csv,
+                    &mut pending,
+                    &mut pending_rows,
+                    &mut ids,
+                    &mut failed_rows,
+                )?;
+            }
+        }
+        load_csv_nodes(
+            &mut bulk,
+            nodes_csv,
+            &mut pending,
+            &mut pending_rows,
+            &mut ids,
+            &mut failed_rows,
+        )?;
+
+        let mut reader = open_csv(edges_csv)?;
+        let headers = csv_headers(&mut reader, edges_csv)?;
+        let src_index = csv_column(&headers, &opts.src_column, edges_csv)?;
+        let dst_index = csv_column(&headers, &opts.dst_column, edges_csv)?;
+        let type_index = match &opts.type_column {
+            Some(name) => Some(csv_column(&headers, name, edges_csv)?),
+            None => None,
+        };
+        let edge_columns = csv_prop_columns(
+            &headers,
+            &opts,
+            &[Some(src_index), Some(dst_index), type_index],
+        );
+        let chunk_size = opts.bulk.edge_chunk_size.max(1);
+        let mut pending: Vec<TypedEdgeSpec> = Vec::with_capacity(chunk_size);
+        let mut pending_lines: Vec<Option<u64>> = Vec::with_capacity(chunk_size);
+        for record in reader.records() {
+            let record = match record {
+                Ok(record) => record,
+                Err(err) if err.is_io_error() => return Err(csv_io_error(edges_csv, err)),
+                Err(err) => {
+                    failed_rows.push(CsvRowError::from_csv(edges_csv, &err));
+                    continue;
+                }
+            };
+            let endpoints = [src_index, dst_index];
+            match csv_edge_row(&record, endpoints, type_index, &edge_columns, &opts, &ids) {
+                Ok(spec) => {
+                    pending_lines.push(csv_line(&record));
+                    pending.push(spec);
+                }
+                Err(message) => {
+                    failed_rows.push(CsvRowError::at(edges_csv, &record, message));
+                    continue;
+                }
+            }
+            if pending.len() >= chunk_size {
+                load_csv_edges(
+                    &mut bulk,
+                    edges_csv,
+                    &mut pending,
+                    &mut pending_lines,
+                    &mut failed_rows,
+                )?;
+            }
+        }
+        load_csv_edges(
+            &mut bulk,
+            edges_csv,
+            &mut pending,
+            &mut pending_lines,
+            &mut failed_rows,
+        )?;
+
+        let stats = bulk.finish()?;
+        Ok(CsvImportSummary {
+            nodes_loaded: stats.nodes_created,
+            edges_loaded: stats.edges_created,
+            failed_rows,
+        })
+    }
+
     /// Forces a checkpoint, flushing WAL data to the main database file.
     ///
     /// This ensures all committed data is persisted to the main database file.
@@ -753,6 +1464,78 @@ impl Database {
             .map_err(FfiError::from)
     }
 
+    /// Checkpoints and serializes the whole database file to bytes.
+    ///
+    /// The image needs no WAL and reopens with [`Database::open_from_bytes`].
+    /// Waits up to [`PagerOptions::checkpoint_lock_wait_ms`] for in-flight
+    /// readers and writers to finish, and fails without reading the file when
+    /// it exceeds [`MAX_SNAPSHOT_BYTES`].
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        match self.pager.checkpoint_image(MAX_SNAPSHOT_BYTES as u64)? {
+            Some(image) => Ok(image),
+            None => Err(FfiError::Message(format!(
+                "database image exceeds the {MAX_SNAPSHOT_BYTES}-byte limit"
+            ))),
+        }
+    }
+
+    /// Reports free versus live pages in the database file.
+    ///
+    /// A rising `fragmentation` ratio signals that a vacuum and checkpoint
+    /// would return space to the allocator or the filesystem.
+    pub fn space_report_json(&self) -> Result<Value> {
+        let summary = SpaceReportSummary::from(self.graph.space_report()?);
+        serde_json::to_value(summary)
+            .map_err(|err| FfiError::Message(format!("failed to encode space report: {err}")))
+    }
+
+    /// Summarizes what the database holds in a single call.
+    ///
+    /// Reports total nodes and edges, counts per label and edge type, index
+    /// counts, and the visible id ranges. No per-label counters are cached, so
+    /// this scans the node and edge trees once each.
+    pub fn summary_json(&self) -> Result<Value> {
+        let read = self.pager.begin_latest_committed_read()?;
+        let graph = self.graph.summary(&read)?;
+        drop(read);
+        let mut str_ids: Vec<StrId> = graph
+            .label_counts
+            .keys()
+            .map(|label| StrId(label.0))
+            .collect();
+        str_ids.extend(graph.type_counts.keys().map(|ty| StrId(ty.0)));
+        let mut names = self.dict.resolve_many(&str_ids)?.into_iter();
+        let mut next_name = || -> Result<String> {
+            names.next().flatten().ok_or_else(|| {
+                SombraError::Corruption("summary name missing from dictionary").into()
+            })
+        };
+        let mut labels = BTreeMap::new();
+        let mut label_indexes = 0u64;
+        for (label, count) in &graph.label_counts {
+            labels.insert(next_name()?, *count);
+            if self.graph.has_label_index(*label)? {
+                label_indexes += 1;
+            }
+        }
+        let mut edge_types = BTreeMap::new();
+        for count in graph.type_counts.values() {
+            edge_types.insert(next_name()?, *count);
+        }
+        let summary = DatabaseSummary {
+            nodes: graph.node_count,
+            edges: graph.edge_count,
+            labels,
+            edge_types,
+            label_indexes,
+            property_indexes: self.graph.all_property_indexes()?.len() as u64,
+            node_id_range: graph.node_id_range.map(|(lo, hi)| [lo.0, hi.0]),
+            edge_id_range: graph.edge_id_range.map(|(lo, hi)| [lo.0, hi.0]),
+        };
+        serde_json::to_value(summary)
+            .map_err(|err| FfiError::Message(format!("failed to encode summary: {err}")))
+    }
+
     /// Executes a JSON-serialized query specification and returns all results.
     ///
     /// Deserializes the JSON query specification and executes it against the database.
@@ -763,6 +1546,26 @@ impl Database {
         self.execute(spec)
     }
 
+    /// Executes a JSON-serialized query and profiles just this call.
+    ///
+    /// Returns the result rows together with the plan, exec and serde
+    /// timings of this query. Timings are captured whether or not
+    /// `SOMBRA_PROFILE` is set and are not added to the process-wide counters
+    /// reported by [`profile_snapshot`].
+    pub fn execute_json_profiled(&self, spec: &Value) -> Result<(Vec<Value>, ProfileSnapshot)> {
+        let scope = LocalProfileScope::begin();
+        let payload = self.execute_json(spec)?;
+        let snapshot = scope.finish();
+        let Value::Object(mut payload) = payload else {
+            return Ok((Vec::new(), snapshot));
+        };
+        let rows = match payload.remove("rows") {
+            Some(Value::Array(rows)) => rows,
+            _ => Vec::new(),
+        };
+        Ok((rows, snapshot))
+    }
+
     /// Explains a JSON-serialized query without executing it.
     ///
     /// Returns the query execution plan for inspection and optimization.
@@ -773,33 +1576,171 @@ impl Database {
         self.explain_with_options(spec.query, spec.redact_literals)
     }
 
-    /// Creates a streaming query from a JSON specification.
+    /// Lists the driving-scan candidates the planner weighed for a JSON query.
     ///
-    /// Returns an iterator-like [`QueryStream`] for processing large result sets.
-    pub fn stream_json(&self, spec: &Value) -> Result<QueryStream> {
+    /// Each entry reports the scan operator, its estimated cost relative to a
+    /// full label scan, and whether the planner selected it.
+    pub fn explain_candidates_json(&self, spec: &Value) -> Result<Value> {
         enforce_payload_size(spec)?;
         let spec: QuerySpec = serde_json::from_value(spec.clone())
             .map_err(|err| FfiError::Message(format!("invalid query spec: {err}")))?;
-        self.stream(spec)
+        let plan = self.plan(spec)?;
+        Ok(candidates_payload(
+            plan.request_id.clone(),
+            plan.plan_hash,
+            &plan.candidates,
+        ))
     }
 
-    /// Samples label IDs from the first `node_limit` nodes and returns the top `max_labels` names.
-    pub fn sample_labels(
-        &self,
-        node_limit: usize,
-        max_labels: usize,
-    ) -> Result<Vec<(String, u64)>> {
-        if node_limit == 0 || max_labels == 0 {
-            return Ok(Vec::new());
-        }
-        let read = self.pager.begin_latest_committed_read()?;
-        let samples = self.graph.sample_node_labels(&read, node_limit)?;
-        drop(read);
-
-        let mut counts: HashMap<u32, u64> = HashMap::new();
-        for label_list in samples {
-            for label in label_list {
-                *counts.entry(label.0).or_insert(0) += 1;
+    /// Checks a JSON query specification without executing it.
+    ///
+    /// Analyzer failures (undefined variables, unknown labels or properties,
+    /// ill-typed literals) are reported as errors rather than returned as
+    /// `Err`; predicates that would fall back to a full label scan are
+    /// reported as warnings. Malformed JSON is still an `Err`.
+    pub fn validate_json(&self, spec: &Value) -> Result<ValidationReport> {
+        enforce_payload_size(spec)?;
+        let spec: QuerySpec = serde_json::from_value(spec.clone())
+            .map_err(|err| FfiError::Message(format!("invalid query spec: {err}")))?;
+        let mut report = ValidationReport::default();
+        let analyzed = match spec.into_ast().and_then(|ast| {
+            self.planner.check_pattern_size(&ast)?;
+            analyze::analyze(&ast, self.metadata.as_ref()).map_err(FfiError::from)
+        }) {
+            Ok(analyzed) => analyzed,
+            Err(FfiError::Analyzer(err)) => {
+                report.errors.push(ValidationIssue::from(&err));
+                return Ok(report);
+            }
+            Err(err) => return Err(err),
+        };
+        if let Some((left, right)) = analyzed.disconnected_vars() {
+            let (left, right) = (&left.0, &right.0);
+            report.warnings.push(ValidationIssue {
+                code: "CartesianProduct".into(),
+                message: format!(
+                    "no edge chain connects '{left}' to '{right}'; \
+                     the query returns the cartesian product of their matches"
+                ),
+            });
+        }
+        let plan = self.planner.plan_analyzed(&analyzed)?;
+        report.warnings.extend(unindexed_warnings(&plan));
+        Ok(report)
+    }
+
+    /// Executes a JSON query and returns one page of at most `page_size` rows.
+    ///
+    /// Pass the previous page's `next_token` to continue; `None` starts from
+    /// the first row. The token records the sort position of the page's last
+    /// row, so the next call skips straight past it instead of counting rows,
+    /// and pages neither repeat nor drop rows when earlier rows are deleted.
+    /// Queries without `orderBy` are paged in bound node id order. Tokens are
+    /// bound to the query's plan hash and are rejected when replayed against
+    /// a different query. `limit`, `offset`, `dedupBy`, and aggregates cannot
+    /// be combined with paging.
+    pub fn execute_paged_json(
+        &self,
+        spec: &Value,
+        page_token: Option<String>,
+        page_size: usize,
+    ) -> Result<PagedResult> {
+        if page_size == 0 {
+            return Err(FfiError::Message(
+                "page_size must be greater than zero".into(),
+            ));
+        }
+        enforce_payload_size(spec)?;
+        let spec: QuerySpec = serde_json::from_value(spec.clone())
+            .map_err(|err| FfiError::Message(format!("invalid query spec: {err}")))?;
+        if spec.limit.is_some() || spec.offset.is_some() {
+            return Err(FfiError::Message(
+                "paged queries cannot set limit or offset".into(),
+            ));
+        }
+        if !spec.dedup_by.is_empty() {
+            return Err(FfiError::Message("paged queries cannot use dedupBy".into()));
+        }
+        let options = spec.exec_options();
+        let bytes_format = spec.bytes_format;
+        let ast = spec.into_ast()?;
+        self.planner.check_pattern_size(&ast)?;
+        let mut analyzed = analyze::analyze(&ast, self.metadata.as_ref())?;
+        analyzed.ensure_total_order();
+        let plan = self.planner.plan_analyzed(&analyzed)?;
+        let after = page_token
+            .as_deref()
+            .map(|token| decode_page_token(token, plan.plan_hash))
+            .transpose()?;
+        self.advise(&plan);
+        let guard = self.register_cancellation(plan.request_id.as_deref())?;
+        let cancel_token = guard.as_ref().map(|handle| handle.token());
+        let page = self
+            .executor
+            .execute_page(&plan.plan, after.as_ref(), page_size, cancel_token, options)
+            .map_err(exec_error)?;
+        let rows = page
+            .rows
+            .iter()
+            .map(|row| row_to_value(row, bytes_format))
+            .collect::<Result<Vec<_>>>()?;
+        let next_token = page
+            .next
+            .map(|cursor| encode_page_token(plan.plan_hash, &cursor));
+        Ok(PagedResult { rows, next_token })
+    }
+
+    /// Creates a streaming query from a JSON specification.
+    ///
+    /// Returns an iterator-like [`QueryStream`] for processing large result sets.
+    pub fn stream_json(&self, spec: &Value) -> Result<QueryStream> {
+        enforce_payload_size(spec)?;
+        let spec: QuerySpec = serde_json::from_value(spec.clone())
+            .map_err(|err| FfiError::Message(format!("invalid query spec: {err}")))?;
+        self.stream(spec)
+    }
+
+    /// Streams a JSON query spec to `on_batch` in batches of at most
+    /// `batch_size` rows.
+    ///
+    /// The next batch is only pulled once `on_batch` returns, so a slow
+    /// consumer holds the producer back. Returning `Ok(false)` stops the
+    /// stream early; errors from the callback are passed through.
+    pub fn stream_batched_json<F>(
+        &self,
+        spec: &Value,
+        batch_size: usize,
+        mut on_batch: F,
+    ) -> Result<()>
+    where
+        F: FnMut(Vec<Value>) -> Result<bool>,
+    {
+        let stream = self.stream_json(spec)?;
+        loop {
+            let batch = stream.next_batch(batch_size)?;
+            if batch.is_empty() || !on_batch(batch)? {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Samples label IDs from the first `node_limit` nodes and returns the top `max_labels` names.
+    pub fn sample_labels(
+        &self,
+        node_limit: usize,
+        max_labels: usize,
+    ) -> Result<Vec<(String, u64)>> {
+        if node_limit == 0 || max_labels == 0 {
+            return Ok(Vec::new());
+        }
+        let read = self.pager.begin_latest_committed_read()?;
+        let samples = self.graph.sample_node_labels(&read, node_limit)?;
+        drop(read);
+
+        let mut counts: HashMap<u32, u64> = HashMap::new();
+        for label_list in samples {
+            for label in label_list {
+                *counts.entry(label.0).or_insert(0) += 1;
             }
         }
 
@@ -871,6 +1812,56 @@ impl Database {
         prop: &str,
         kind: &str,
         ty: &str,
+    ) -> Result<bool> {
+        self.ensure_property_index_with(label, &[prop], kind, &[ty], false)
+    }
+
+    /// Ensures a unique property index exists, taking the same arguments as
+    /// [`Database::ensure_property_index`].
+    ///
+    /// Once created, writes giving two nodes with the label the same non-null
+    /// value fail with [`ErrorCode::ConstraintViolation`]; creation fails the
+    /// same way if existing nodes already share a value. Returns `false`
+    /// without changes if any index on the property already exists.
+    pub fn ensure_unique_property_index(
+        &self,
+        label: &str,
+        prop: &str,
+        kind: &str,
+        ty: &str,
+    ) -> Result<bool> {
+        self.ensure_property_index_with(label, &[prop], kind, &[ty], true)
+    }
+
+    /// Ensures a composite index exists over `props`, in key order, with one
+    /// entry of `types` per property.
+    ///
+    /// Queries with equality predicates on the leading properties, optionally
+    /// followed by a range on the next one, are answered from a single scan.
+    /// The index is identified by its leading property, so this returns
+    /// `false` without changes if any index led by `props[0]` already exists.
+    pub fn ensure_composite_index(
+        &self,
+        label: &str,
+        props: &[&str],
+        kind: &str,
+        types: &[&str],
+    ) -> Result<bool> {
+        if props.len() < 2 {
+            return Err(FfiError::Message(
+                "composite indexes need at least two properties".into(),
+            ));
+        }
+        self.ensure_property_index_with(label, props, kind, types, false)
+    }
+
+    fn ensure_property_index_with(
+        &self,
+        label: &str,
+        props: &[&str],
+        kind: &str,
+        types: &[&str],
+        unique: bool,
     ) -> Result<bool> {
         let label_id = self
             .metadata
@@ -887,40 +1878,90 @@ impl Database {
             }
         };
 
-        let type_tag = match ty.to_lowercase().as_str() {
-            "string" => TypeTag::String,
-            "int" | "integer" => TypeTag::Int,
-            "float" | "double" => TypeTag::Float,
-            "bool" | "boolean" => TypeTag::Bool,
-            "bytes" => TypeTag::Bytes,
-            "date" => TypeTag::Date,
-            "datetime" => TypeTag::DateTime,
-            _ => {
-                return Err(FfiError::Message(format!(
-                    "unknown type '{ty}', expected 'string', 'int', 'float', 'bool', 'bytes', 'date', or 'datetime'"
-                )))
-            }
-        };
+        let mut type_tags = Vec::with_capacity(types.len());
+        for ty in types {
+            type_tags.push(match ty.to_lowercase().as_str() {
+                "string" => TypeTag::String,
+                "int" | "integer" => TypeTag::Int,
+                "float" | "double" => TypeTag::Float,
+                "bool" | "boolean" => TypeTag::Bool,
+                "bytes" => TypeTag::Bytes,
+                "date" => TypeTag::Date,
+                "datetime" => TypeTag::DateTime,
+                _ => {
+                    return Err(FfiError::Message(format!(
+                        "unknown type '{ty}', expected 'string', 'int', 'float', 'bool', 'bytes', 'date', or 'datetime'"
+                    )))
+                }
+            });
+        }
 
         let mut write = self.pager.begin_write()?;
-        let prop_id = PropId(self.dict.intern(&mut write, prop)?.0);
+        let mut prop_ids = Vec::with_capacity(props.len());
+        for prop in props {
+            prop_ids.push(self.resolve_prop(&mut write, prop)?);
+        }
 
-        if self.graph.has_property_index(label_id, prop_id)? {
+        if self.graph.has_property_index(label_id, prop_ids[0])? {
             self.pager.commit(write)?;
             return Ok(false);
         }
 
         let def = IndexDef {
             label: label_id,
-            prop: prop_id,
+            props: prop_ids,
             kind: index_kind,
-            ty: type_tag,
+            types: type_tags,
+            max_key_bytes: None,
+            unique,
+            state: IndexState::Ready,
         };
         self.graph.create_property_index(&mut write, def)?;
         self.pager.commit(write)?;
         Ok(true)
     }
 
+    /// Requires every node carrying `label` to set `prop` to a non-null value.
+    ///
+    /// Fails if an existing node with the label lacks the property. Returns
+    /// `true` if the constraint was created, `false` if it already existed.
+    pub fn require_property(&self, label: &str, prop: &str) -> Result<bool> {
+        let mut write = self.pager.begin_write()?;
+        let label_id = self.resolve_label(&mut write, label)?;
+        let prop_id = self.resolve_prop(&mut write, prop)?;
+        let created = self
+            .graph
+            .create_required_prop(&mut write, label_id, prop_id)?;
+        self.pager.commit(write)?;
+        Ok(created)
+    }
+
+    /// Drops the constraint requiring `prop` on nodes carrying `label`.
+    ///
+    /// Returns `true` if the constraint was removed, `false` if it did not exist.
+    pub fn drop_required_property(&self, label: &str, prop: &str) -> Result<bool> {
+        let mut write = self.pager.begin_write()?;
+        let label_id = self.resolve_label(&mut write, label)?;
+        let prop_id = self.resolve_prop(&mut write, prop)?;
+        let dropped = self
+            .graph
+            .drop_required_prop(&mut write, label_id, prop_id)?;
+        self.pager.commit(write)?;
+        Ok(dropped)
+    }
+
+    /// Lists every required-property constraint.
+    pub fn required_properties(&self) -> Result<Vec<RequiredProperty>> {
+        let mut entries = Vec::new();
+        for (label, prop) in self.graph.required_props()? {
+            entries.push(RequiredProperty {
+                label: self.dict.resolve_str(StrId(label.0))?,
+                prop: self.dict.resolve_str(StrId(prop.0))?,
+            });
+        }
+        Ok(entries)
+    }
+
     /// Applies a JSON mutation specification (create, update, delete operations).
     pub fn mutate_json(&self, spec: &Value) -> Result<Value> {
         let spec: MutationSpec = serde_json::from_value(spec.clone())
@@ -930,6 +1971,16 @@ impl Database {
             .map_err(|err| FfiError::Message(format!("failed to encode mutation result: {err}")))
     }
 
+    /// Applies a mutation specification given as JSON text.
+    ///
+    /// Objects in a parsed [`Value`] cannot hold a key twice, so this is the
+    /// entry point that applies [`DatabaseOptions::duplicate_props`] to the
+    /// `props` and `set` maps of [`Database::mutate_json`] specs.
+    pub fn mutate_json_str(&self, text: &str) -> Result<Value> {
+        let spec = self.parse_spec_text(text, "mutation")?;
+        self.mutate_json(&spec)
+    }
+
     /// Applies a JSON create script (nodes and edges with optional aliases).
     pub fn create_json(&self, spec: &Value) -> Result<Value> {
         let script: CreateScript = serde_json::from_value(spec.clone())
@@ -940,6 +1991,42 @@ impl Database {
             .map_err(|err| FfiError::Message(format!("failed to encode create result: {err}")))
     }
 
+    /// Applies a create script given as JSON text.
+    ///
+    /// Like [`Database::mutate_json_str`], repeated keys in a `props` map are
+    /// resolved per [`DatabaseOptions::duplicate_props`].
+    pub fn create_json_str(&self, text: &str) -> Result<Value> {
+        let spec = self.parse_spec_text(text, "create")?;
+        self.create_json(&spec)
+    }
+
+    /// Parses spec text, rejecting repeated property keys under
+    /// [`DuplicatePropertyPolicy::Error`]. Parsing keeps the last value of a
+    /// repeated key, which is the [`DuplicatePropertyPolicy::LastWins`] result.
+    fn parse_spec_text(&self, text: &str, kind: &str) -> Result<Value> {
+        let invalid =
+            |err: serde_json::Error| FfiError::Message(format!("invalid {kind} spec: {err}"));
+        if self.duplicate_props == DuplicatePropertyPolicy::Error {
+            let mut de = serde_json::Deserializer::from_str(text);
+            serde::de::DeserializeSeed::deserialize(
+                DuplicatePropCheck { prop_map: false },
+                &mut de,
+            )
+            .map_err(invalid)?;
+        }
+        serde_json::from_str(text).map_err(invalid)
+    }
+
+    /// Applies a JSON sync specification (keyed node and edge upserts plus
+    /// deletions) atomically.
+    pub fn sync_json(&self, spec: &Value) -> Result<Value> {
+        let spec: SyncSpec = serde_json::from_value(spec.clone())
+            .map_err(|err| FfiError::Message(format!("invalid sync spec: {err}")))?;
+        let summary = self.sync(spec)?;
+        serde_json::to_value(summary)
+            .map_err(|err| FfiError::Message(format!("failed to encode sync result: {err}")))
+    }
+
     /// Fetches a node by ID and returns its properties and labels.
     pub fn get_node_record(&self, node_id: u64) -> Result<Option<NodeRecord>> {
         let read = self.pager.begin_latest_committed_read()?;
@@ -951,6 +2038,26 @@ impl Database {
         Ok(result)
     }
 
+    /// Fetches the `key` property of each node in `node_ids`, in order.
+    ///
+    /// Missing nodes and nodes without the property map to `null`.
+    pub fn get_property_for_nodes(&self, node_ids: &[u64], key: &str) -> Result<Vec<Value>> {
+        let Some(prop) = self.dict.lookup(key).map_err(FfiError::from)? else {
+            return Ok(vec![Value::Null; node_ids.len()]);
+        };
+        let ids: Vec<NodeId> = node_ids.iter().copied().map(NodeId).collect();
+        let read = self.pager.begin_latest_committed_read()?;
+        let values = self.graph.get_prop_for_nodes(&read, &ids, PropId(prop.0))?;
+        drop(read);
+        values
+            .iter()
+            .map(|value| match value {
+                Some(value) => prop_value_owned_to_json(value),
+                None => Ok(Value::Null),
+            })
+            .collect()
+    }
+
     /// Fetches a node by ID and returns its typed representation without JSON conversion.
     pub fn get_node_data(&self, node_id: u64) -> Result<Option<NodeData>> {
         let read = self.pager.begin_latest_committed_read()?;
@@ -1077,6 +2184,17 @@ impl Database {
         Ok(nodes.into_iter().map(|id| id.0).collect())
     }
 
+    /// Returns up to `n` identifiers of nodes carrying `label`, highest id first.
+    pub fn get_last_n_nodes_by_label(&self, label: &str, n: usize) -> Result<Vec<u64>> {
+        let label_id = self.lookup_label(label)?;
+        let read = self.pager.begin_latest_committed_read()?;
+        let nodes = self
+            .graph
+            .last_nodes_with_label(&read, label_id, n)
+            .map_err(FfiError::from)?;
+        Ok(nodes.into_iter().map(|id| id.0).collect())
+    }
+
     /// Returns neighbors for the provided node using low-level traversal settings.
     pub fn neighbors_with_options(
         &self,
@@ -1085,32 +2203,353 @@ impl Database {
         edge_type: Option<&str>,
         distinct: bool,
     ) -> Result<Vec<NeighborInfo>> {
+        self.neighbor_stream(node_id, direction, edge_type, distinct)?
+            .collect()
+    }
+
+    /// Returns the neighbors of `node_id` as an iterator over
+    /// [`NeighborInfo`] entries.
+    ///
+    /// The stream holds a read snapshot taken here and walks the adjacency
+    /// lists in batches of [`NEIGHBOR_STREAM_BATCH`] entries as it is
+    /// consumed, so every entry comes from the same snapshot without the
+    /// full list being materialized. The snapshot stays pinned until the
+    /// stream is dropped.
+    pub fn neighbor_stream(
+        &self,
+        node_id: u64,
+        direction: Dir,
+        edge_type: Option<&str>,
+        distinct: bool,
+    ) -> Result<NeighborStream> {
         let ty = match edge_type {
             Some(name) => Some(self.lookup_edge_type(name)?),
             None => None,
         };
         let read = self.pager.begin_latest_committed_read()?;
+        let scan = self.graph.neighbor_scan(
+            NodeId(node_id),
+            direction,
+            ty,
+            ExpandOpts {
+                distinct_nodes: distinct,
+            },
+        );
+        Ok(NeighborStream {
+            graph: Arc::clone(&self.graph),
+            read,
+            scan,
+            batch: Vec::new().into_iter(),
+        })
+    }
+
+    /// Returns the ids of neighbors reached through edges of `edge_type`.
+    ///
+    /// The type filter is applied while walking the adjacency lists, so edges
+    /// of other types are never materialized. With `distinct` set, each
+    /// neighbor is reported once even when several matching edges lead to it.
+    pub fn get_neighbors_by_type(
+        &self,
+        node_id: u64,
+        edge_type: &str,
+        direction: Dir,
+        distinct: bool,
+    ) -> Result<Vec<u64>> {
+        let ty = self.lookup_edge_type(edge_type)?;
+        let read = self.pager.begin_latest_committed_read()?;
         let cursor = self.graph.neighbors(
             &read,
             NodeId(node_id),
             direction,
-            ty,
+            Some(ty),
             ExpandOpts {
                 distinct_nodes: distinct,
             },
         )?;
         drop(read);
-        let mut out = Vec::with_capacity(cursor.len());
-        for entry in cursor {
-            out.push(NeighborInfo {
-                node_id: entry.neighbor.0,
-                edge_id: entry.edge.0,
-                type_id: entry.ty.0,
+        Ok(cursor.map(|entry| entry.neighbor.0).collect())
+    }
+
+    /// Finds every binding of a node/edge pattern.
+    ///
+    /// The whole search runs against one snapshot. Each pattern edge binds a
+    /// concrete edge, so parallel edges between the same nodes produce
+    /// separate matches and no edge is bound twice within a match. Property
+    /// filters follow query filter semantics. Labels, types or properties
+    /// that were never interned simply match nothing. Patterns larger than
+    /// [`DatabaseOptions::max_pattern_nodes`] or
+    /// [`DatabaseOptions::max_pattern_edges`] are rejected before matching.
+    pub fn match_pattern(&self, pattern: PatternSpec) -> Result<Vec<PatternMatch>> {
+        self.planner
+            .check_pattern_counts(pattern.nodes.len(), pattern.edges.len())?;
+        let (vars, edges) = self.resolve_pattern(&pattern)?;
+        let read = self.pager.begin_latest_committed_read()?;
+        let mut candidates = Vec::with_capacity(pattern.nodes.len());
+        for node in &pattern.nodes {
+            candidates.push(self.pattern_candidates(&read, node)?);
+        }
+        let matcher = PatternMatcher {
+            graph: &self.graph,
+            read: &read,
+            steps: plan_pattern(&candidates, &edges),
+            candidates,
+            edges,
+        };
+        let mut nodes = vec![None; vars.len()];
+        let mut bound_edges = vec![None; matcher.edges.len()];
+        let mut raw = Vec::new();
+        matcher.search(0, &mut nodes, &mut bound_edges, &mut raw)?;
+        drop(read);
+        Ok(raw
+            .into_iter()
+            .map(|(nodes, edges)| PatternMatch {
+                node_bindings: vars
+                    .iter()
+                    .cloned()
+                    .zip(nodes.into_iter().map(|id| id.0))
+                    .collect(),
+                edge_ids: edges.into_iter().map(|id| id.0).collect(),
+            })
+            .collect())
+    }
+
+    /// JSON wrapper around [`Database::match_pattern`].
+    pub fn match_pattern_json(&self, spec: &Value) -> Result<Value> {
+        let pattern: PatternSpec = serde_json::from_value(spec.clone())
+            .map_err(|err| FfiError::Message(format!("invalid pattern spec: {err}")))?;
+        let matches = self.match_pattern(pattern)?;
+        serde_json::to_value(matches).map_err(|err| FfiError::Message(err.to_string()))
+    }
+
+    fn resolve_pattern(&self, pattern: &PatternSpec) -> Result<(Vec<String>, Vec<PatternEdge>)> {
+        if pattern.nodes.is_empty() {
+            return Err(FfiError::Message(
+                "pattern requires at least one node".into(),
+            ));
+        }
+        let mut vars: Vec<String> = Vec::with_capacity(pattern.nodes.len());
+        for node in &pattern.nodes {
+            if node.var.is_empty() {
+                return Err(FfiError::Message(
+                    "pattern nodes require a non-empty var".into(),
+                ));
+            }
+            if vars.contains(&node.var) {
+                return Err(FfiError::Message(format!(
+                    "pattern variable '{}' is declared twice",
+                    node.var
+                )));
+            }
+            if node.labels.is_empty() {
+                return Err(FfiError::Message(format!(
+                    "pattern node '{}' requires at least one label",
+                    node.var
+                )));
+            }
+            vars.push(node.var.clone());
+        }
+        let var_index = |name: &str| {
+            vars.iter().position(|var| var == name).ok_or_else(|| {
+                FfiError::Message(format!("pattern edge references unknown variable '{name}'"))
+            })
+        };
+        let mut edges = Vec::with_capacity(pattern.edges.len());
+        for edge in &pattern.edges {
+            let from = var_index(&edge.from)?;
+            let to = var_index(&edge.to)?;
+            let dir = match edge.direction.clone().into_direction()? {
+                EdgeDirection::Out => Dir::Out,
+                EdgeDirection::In => Dir::In,
+                EdgeDirection::Both => Dir::Both,
+            };
+            let types = if edge.types.is_empty() {
+                None
+            } else {
+                // A type that was never interned has no edges, so it adds
+                // nothing to the accepted set; when no listed type exists
+                // the empty set makes the edge match nothing.
+                let mut ids = Vec::with_capacity(edge.types.len());
+                for name in &edge.types {
+                    if let Some(id) = self.dict.lookup(name).map_err(FfiError::from)? {
+                        ids.push(TypeId(id.0));
+                    }
+                }
+                Some(ids)
+            };
+            edges.push(PatternEdge {
+                from,
+                to,
+                dir,
+                types,
             });
         }
+        Ok((vars, edges))
+    }
+
+    /// Lists the nodes that satisfy a pattern node's labels and filters,
+    /// sorted by id.
+    fn pattern_candidates(&self, read: &ReadGuard, node: &PatternNodeSpec) -> Result<Vec<NodeId>> {
+        let mut labels = Vec::with_capacity(node.labels.len());
+        for name in &node.labels {
+            match self.dict.lookup(name).map_err(FfiError::from)? {
+                Some(id) => labels.push(LabelId(id.0)),
+                None => return Ok(Vec::new()),
+            }
+        }
+        let filters = &node.properties;
+        let mut checks = Vec::new();
+        let comparisons = filters
+            .equals
+            .iter()
+            .map(|(key, value)| (key, PropComparison::Eq, value))
+            .chain(
+                filters
+                    .not_equals
+                    .iter()
+                    .map(|(key, value)| (key, PropComparison::Ne, value)),
+            );
+        for (key, cmp, value) in comparisons {
+            checks.push(self.pattern_check(key, cmp, value)?);
+        }
+        for range in &filters.ranges {
+            if let Some(bound) = &range.min {
+                let cmp = if bound.inclusive {
+                    PropComparison::Ge
+                } else {
+                    PropComparison::Gt
+                };
+                checks.push(self.pattern_check(&range.key, cmp, &bound.value)?);
+            }
+            if let Some(bound) = &range.max {
+                let cmp = if bound.inclusive {
+                    PropComparison::Le
+                } else {
+                    PropComparison::Lt
+                };
+                checks.push(self.pattern_check(&range.key, cmp, &bound.value)?);
+            }
+        }
+        let mut ids = self.graph.nodes_with_label(read, labels[0])?;
+        ids.sort_unstable_by_key(|id| id.0);
+        ids.dedup();
+        let mut out = Vec::with_capacity(ids.len());
+        for id in ids {
+            let Some(data) = self.graph.get_node(read, id)? else {
+                continue;
+            };
+            if !labels.iter().all(|label| data.labels.contains(label)) {
+                continue;
+            }
+            let mut keep = true;
+            for (prop, cmp, literal) in &checks {
+                let passes = match prop {
+                    Some(prop) => node_prop_matches(&data, *prop, *cmp, literal)?,
+                    // A property that was never interned is missing on every
+                    // node, which only `= null` accepts.
+                    None => *cmp == PropComparison::Eq && matches!(literal, LiteralValue::Null),
+                };
+                if !passes {
+                    keep = false;
+                    break;
+                }
+            }
+            if keep {
+                out.push(id);
+            }
+        }
         Ok(out)
     }
 
+    fn pattern_check(
+        &self,
+        key: &str,
+        cmp: PropComparison,
+        value: &PayloadValue,
+    ) -> Result<(Option<PropId>, PropComparison, LiteralValue)> {
+        let value = value.clone().into_value()?;
+        validate_scalar_value(&value)?;
+        let prop = self
+            .dict
+            .lookup(key)
+            .map_err(FfiError::from)?
+            .map(|id| PropId(id.0));
+        Ok((prop, cmp, LiteralValue::from(&value)))
+    }
+
+    /// Finds a shortest outgoing path from `start_id` to `end_id`.
+    ///
+    /// Each hop pairs a node id with the id of the edge used to reach it, so
+    /// callers can tell which of several parallel edges the path took; the
+    /// start node carries `None`. Only edges of `edge_types` are followed when
+    /// the list is non-empty. Returns `None` when `end_id` is unreachable.
+    pub fn shortest_path_with_edges(
+        &self,
+        start_id: u64,
+        end_id: u64,
+        edge_types: Option<&[String]>,
+    ) -> Result<Option<Vec<(u64, Option<u64>)>>> {
+        let edge_filters = match edge_types {
+            Some(names) if !names.is_empty() => Some(self.lookup_edge_types(names)?),
+            _ => None,
+        };
+        let read = self.pager.begin_latest_committed_read()?;
+        let path = self.graph.shortest_path_with_edges(
+            &read,
+            NodeId(start_id),
+            NodeId(end_id),
+            Dir::Out,
+            edge_filters.as_deref(),
+        )?;
+        drop(read);
+        Ok(path.map(|hops| {
+            hops.into_iter()
+                .map(|(node, edge)| (node.0, edge.map(|edge| edge.0)))
+                .collect()
+        }))
+    }
+
+    /// Resolves label names for `node_ids` and type names for `edge_ids`.
+    ///
+    /// All dictionary lookups go through a single [`Dict::resolve_many`] call,
+    /// so bindings can name every entity of a result page in one round trip.
+    pub fn entity_names(&self, node_ids: &[u64], edge_ids: &[u64]) -> Result<EntityNames> {
+        let nodes: Vec<NodeId> = node_ids.iter().copied().map(NodeId).collect();
+        let edges: Vec<EdgeId> = edge_ids.iter().copied().map(EdgeId).collect();
+        let read = self.pager.begin_latest_committed_read()?;
+        let label_ids = self.graph.node_label_ids(&read, &nodes)?;
+        let type_ids = self.graph.edge_type_ids(&read, &edges)?;
+        drop(read);
+        let mut str_ids = Vec::new();
+        for labels in label_ids.iter().flatten() {
+            str_ids.extend(labels.iter().map(|label| StrId(label.0)));
+        }
+        str_ids.extend(type_ids.iter().flatten().map(|ty| StrId(ty.0)));
+        let mut names = self.dict.resolve_many(&str_ids)?.into_iter();
+        let mut next_name = || -> Result<String> {
+            names.next().flatten().ok_or_else(|| {
+                SombraError::Corruption("entity name missing from dictionary").into()
+            })
+        };
+        let mut node_labels = Vec::with_capacity(label_ids.len());
+        for labels in &label_ids {
+            node_labels.push(match labels {
+                Some(labels) => Some(labels.iter().map(|_| next_name()).collect::<Result<_>>()?),
+                None => None,
+            });
+        }
+        let mut edge_types = Vec::with_capacity(type_ids.len());
+        for ty in &type_ids {
+            edge_types.push(match ty {
+                Some(_) => Some(next_name()?),
+                None => None,
+            });
+        }
+        Ok(EntityNames {
+            node_labels,
+            edge_types,
+        })
+    }
+
     /// Executes a breadth-first traversal starting at `start_id`.
     pub fn bfs_traversal(
         &self,
@@ -1142,35 +2581,286 @@ impl Database {
             .collect())
     }
 
-    /// Handles database pragmas (configuration settings).
+    /// Scores every node by PageRank, optionally following a single edge type.
     ///
-    /// Supported pragmas:
-    /// - `synchronous`: Set write synchronization mode (full, normal, off)
-    /// - `wal_coalesce_ms`: Set WAL coalescing interval
-    /// - `autocheckpoint_ms`: Set automatic checkpoint interval
+    /// Unset options fall back to [`PageRankOpts::default`].
+    pub fn page_rank(
+        &self,
+        damping: Option<f64>,
+        max_iters: Option<u32>,
+        tolerance: Option<f64>,
+        edge_type: Option<&str>,
+    ) -> Result<Vec<PageRankScore>> {
+        let defaults = PageRankOpts::default();
+        let opts = PageRankOpts {
+            damping: damping.unwrap_or(defaults.damping),
+            max_iters: max_iters.unwrap_or(defaults.max_iters),
+            tolerance: tolerance.unwrap_or(defaults.tolerance),
+            edge_type: edge_type
+                .map(|name| self.lookup_edge_type(name))
+                .transpose()?,
+        };
+        let read = self.pager.begin_latest_committed_read()?;
+        let scores = self.graph.page_rank(&read, opts)?;
+        drop(read);
+        Ok(scores
+            .into_iter()
+            .map(|(node, score)| PageRankScore {
+                node_id: node.0,
+                score,
+            })
+            .collect())
+    }
+
+    /// Counts connected components and their sizes without returning the
+    /// per-node labeling.
+    pub fn component_summary(&self, mode: ComponentMode) -> Result<ComponentSummary> {
+        let read = self.pager.begin_latest_committed_read()?;
+        let labels = self.graph.connected_components(&read, mode)?;
+        drop(read);
+        let mut sizes: Vec<u64> = Vec::new();
+        for (_, component) in labels {
+            let component = component as usize;
+            if component >= sizes.len() {
+                sizes.resize(component + 1, 0);
+            }
+            sizes[component] += 1;
+        }
+        sizes.sort_unstable_by(|a, b| b.cmp(a));
+        Ok(ComponentSummary {
+            count: sizes.len() as u64,
+            sizes,
+        })
+    }
+
+    /// Handles database pragmas (configuration settings).
+    ///
+    /// Supported pragmas:
+    /// - `synchronous`: Set write synchronization mode (full, normal, off)
+    /// - `wal_coalesce_ms`: Set WAL coalescing interval
+    /// - `autocheckpoint_ms`: Set automatic checkpoint interval
+    /// - `async_fsync`: Toggle deferred fsync for `synchronous=full` commits
+    /// - `async_fsync_max_wait_ms`: Set the async fsync coalescing window
     pub fn pragma(&self, name: &str, value: Option<Value>) -> Result<Value> {
         match name.to_ascii_lowercase().as_str() {
             "synchronous" => self.handle_synchronous_pragma(value),
             "wal_coalesce_ms" => self.handle_wal_coalesce_pragma(value),
             "autocheckpoint_ms" => self.handle_autocheckpoint_ms_pragma(value),
+            "async_fsync" => self.handle_async_fsync_pragma(value),
+            "async_fsync_max_wait_ms" => self.handle_async_fsync_max_wait_pragma(value),
             other => Err(FfiError::Message(format!("unknown pragma '{other}'"))),
         }
     }
 
     /// Executes a query specification and returns all results.
     pub fn execute(&self, spec: QuerySpec) -> Result<Value> {
+        self.execute_with(spec, |spec| self.plan(spec))
+    }
+
+    /// Parses and plans a JSON query spec once for repeated execution.
+    ///
+    /// Literals written as `{ "t": "Param", "v": "name" }` become named
+    /// parameters, bound on each call to [`PreparedQuery::execute`].
+    pub fn prepare_json(&self, spec: &Value) -> Result<PreparedQuery> {
+        enforce_payload_size(spec)?;
+        let spec: QuerySpec = serde_json::from_value(spec.clone())
+            .map_err(|err| FfiError::Message(format!("invalid query spec: {err}")))?;
+        self.prepare(spec)
+    }
+
+    /// Plans a query specification once for repeated execution.
+    ///
+    /// Parameters may stand in for any comparison literal except those in
+    /// `in()` lists. Nested and `countPerStart` queries cannot be prepared.
+    pub fn prepare(&self, mut spec: QuerySpec) -> Result<PreparedQuery> {
+        if spec.nest.is_some() || spec.count_per_start {
+            return Err(FfiError::Message(
+                "nest and countPerStart queries cannot be prepared".into(),
+            ));
+        }
+        spec.predicate = merged_predicate(spec.predicate.take(), spec.filter.take());
+        let mut params = BTreeMap::new();
+        if let Some(predicate) = &mut spec.predicate {
+            predicate.replace_params(&mut params)?;
+        }
+        let epoch = self.metadata.catalog_epoch();
+        let plan = self.plan(spec.clone())?;
+        Ok(PreparedQuery {
+            spec,
+            params,
+            graph: Arc::downgrade(&self.graph),
+            plan: Mutex::new((epoch, plan)),
+        })
+    }
+
+    /// Runs `spec` with the plan `plan` produces for it.
+    fn execute_with<F>(&self, mut spec: QuerySpec, plan: F) -> Result<Value>
+    where
+        F: FnOnce(QuerySpec) -> Result<PlannerOutput>,
+    {
+        let start_var = spec
+            .count_per_start
+            .then(|| spec.count_start_var())
+            .transpose()?;
+        if start_var.is_some() {
+            // Unprojected rows carry every binding as a node id.
+            spec.projections.clear();
+        }
+        let nest = spec.nest.take();
+        let parent_query = nest
+            .as_ref()
+            .map(|nest| nest.parent_query(&spec))
+            .transpose()?;
+        let options = spec.exec_options();
+        let max_rows = options.max_rows;
+        let schema_vars = spec.include_schema.then(|| {
+            spec.matches
+                .iter()
+                .map(|clause| (clause.var.clone(), clause.label.clone()))
+                .collect::<Vec<_>>()
+        });
+        let flatten = spec.flatten;
+        let bytes_format = spec.bytes_format;
+        let include_warnings = spec.include_warnings;
+        let match_vars = spec
+            .matches
+            .iter()
+            .map(|clause| clause.var.as_str())
+            .collect::<HashSet<_>>()
+            .len();
         let plan_timer = profile_timer();
-        let plan = self.plan(spec)?;
+        let plan = plan(spec)?;
         record_profile_timer(ProfileKind::Plan, plan_timer);
+        let child_columns = nest
+            .as_ref()
+            .map(|nest| nest.child_columns(&plan.plan))
+            .transpose()?;
+        if flatten {
+            let columns = result_column_count(&plan.plan, match_vars);
+            if columns != 1 {
+                return Err(FfiError::Message(format!(
+                    "flatten requires exactly one result column, query produces {columns}"
+                )));
+            }
+        }
+        self.advise(&plan);
         let guard = self.register_cancellation(plan.request_id.as_deref())?;
         let cancel_token = guard.as_ref().map(|handle| handle.token());
         let exec_timer = profile_timer();
-        let result = self.executor.execute(&plan.plan, cancel_token)?;
+        let result = self
+            .executor
+            .execute_with_options(&plan.plan, cancel_token, options)
+            .map_err(exec_error)?;
         record_profile_timer(ProfileKind::Execute, exec_timer);
+        let warnings = include_warnings.then(|| execution_warnings(&plan, &result, max_rows));
+        if let Some(var) = start_var {
+            let mut payload = execution_payload(
+                plan.request_id.clone(),
+                None,
+                Vec::new(),
+                max_rows.map(|_| result.limited),
+                warnings,
+            );
+            if let Value::Object(map) = &mut payload {
+                map.remove("rows");
+                map.insert("counts".into(), counts_per_start(&result, &var)?);
+            }
+            return Ok(payload);
+        }
         let serde_timer = profile_timer();
-        let rows = rows_to_values(&result)?;
+        let rows = if flatten {
+            rows_to_flat_values(&result, bytes_format)?
+        } else {
+            rows_to_values(&result, bytes_format)?
+        };
         record_profile_timer(ProfileKind::Serialize, serde_timer);
-        Ok(execution_payload(plan.request_id.clone(), rows))
+        let rows = match (nest, parent_query, child_columns) {
+            (Some(nest), Some(parent_query), Some(child_columns)) => {
+                let parents = match self.execute(parent_query)? {
+                    Value::Object(mut payload) => match payload.remove("rows") {
+                        Some(Value::Array(rows)) => rows,
+                        _ => Vec::new(),
+                    },
+                    _ => Vec::new(),
+                };
+                let key = nest.key.unwrap_or(nest.child);
+                nest_rows(rows, parents, &child_columns, &key)
+            }
+            _ => rows,
+        };
+        let columns = match schema_vars {
+            Some(vars) => Some(self.result_columns(&plan.plan, &vars)?),
+            None => None,
+        };
+        let truncated = max_rows.map(|_| result.limited);
+        Ok(execution_payload(
+            plan.request_id.clone(),
+            columns,
+            rows,
+            truncated,
+            warnings,
+        ))
+    }
+
+    /// Describes the columns a plan produces without looking at any rows.
+    fn result_columns(
+        &self,
+        plan: &PhysicalPlan,
+        vars: &[(String, Option<String>)],
+    ) -> Result<Vec<ColumnSchema>> {
+        let PhysicalOp::Project { fields } = &plan.root.op else {
+            // Unprojected rows map every bound variable to its node id, keyed
+            // in sorted order.
+            let mut names: Vec<&String> = vars.iter().map(|(var, _)| var).collect();
+            names.sort();
+            names.dedup();
+            return Ok(names
+                .into_iter()
+                .map(|name| ColumnSchema {
+                    name: name.clone(),
+                    ty: "nodeId",
+                })
+                .collect());
+        };
+        fields
+            .iter()
+            .map(|field| {
+                let ty = match field {
+                    ProjectField::Var { .. } => "node",
+                    ProjectField::Prop { var, prop, .. } => {
+                        let label = vars
+                            .iter()
+                            .find(|(name, _)| *name == var.0)
+                            .and_then(|(_, label)| label.as_deref());
+                        self.indexed_prop_type(label, *prop)?
+                    }
+                    ProjectField::RunningCount { .. } => "int",
+                    ProjectField::Path { .. } => "path",
+                    ProjectField::Expr { .. } => "any",
+                    ProjectField::Aggregate { func, .. } => match func {
+                        AggFunc::Count => "int",
+                        AggFunc::Avg => "float",
+                        AggFunc::Sum | AggFunc::Min | AggFunc::Max => "any",
+                    },
+                };
+                Ok(ColumnSchema {
+                    name: field.column_name(),
+                    ty,
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the declared type of a property when `(label, prop)` is indexed.
+    fn indexed_prop_type(&self, label: Option<&str>, prop: PropId) -> Result<&'static str> {
+        let Some(label) = label else {
+            return Ok("any");
+        };
+        let Some(label_id) = self.dict.lookup(label).map_err(FfiError::from)? else {
+            return Ok("any");
+        };
+        let def = self.graph.property_index(LabelId(label_id.0), prop)?;
+        Ok(def.map_or("any", |def| type_tag_name(def.ty())))
     }
 
     /// Returns the query execution plan for a specification.
@@ -1189,12 +2879,65 @@ impl Database {
     }
 
     /// Creates a streaming query result.
+    ///
+    /// The stream reads from the snapshot committed when it was created, so
+    /// nodes deleted or updated by later writers still hydrate with the data
+    /// they had when the query started.
     pub fn stream(&self, spec: QuerySpec) -> Result<QueryStream> {
+        let options = spec.exec_options();
+        let bytes_format = spec.bytes_format;
         let plan = self.plan(spec)?;
+        self.advise(&plan);
         let guard = self.register_cancellation(plan.request_id.as_deref())?;
         let token = guard.as_ref().map(|h| h.token());
-        let stream = self.executor.stream(&plan.plan, token)?;
-        Ok(QueryStream::new(stream, guard))
+        let stream = self
+            .executor
+            .stream_with_options(&plan.plan, token, options)?;
+        Ok(QueryStream::new(stream, guard, bytes_format))
+    }
+
+    /// Ranks the property indexes the advisor would add, most valuable first.
+    ///
+    /// Every executed or streamed query whose driving label scan evaluated an
+    /// unindexed equality or range predicate counts towards `(label, prop)`.
+    /// Returns an empty list unless [`DatabaseOptions::index_advisor`] is set.
+    pub fn index_advice(&self) -> Vec<IndexSuggestion> {
+        self.index_advisor
+            .as_ref()
+            .map(IndexAdvisor::suggestions)
+            .unwrap_or_default()
+    }
+
+    /// Reports the property kinds observed by bulk loads that set
+    /// [`BulkLoadOptions::capture_schema`].
+    ///
+    /// Lists every property per label and edge type with the count of each
+    /// kind seen, flagging properties that arrived with more than one
+    /// non-null kind. The summary lives in memory and starts empty on open.
+    pub fn inferred_schema_json(&self) -> Result<Value> {
+        let schema = self
+            .inferred_schema
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone();
+        serde_json::to_value(schema)
+            .map_err(|err| FfiError::Message(format!("failed to encode inferred schema: {err}")))
+    }
+
+    fn record_inferred_schema(&self, observed: InferredSchema) {
+        if observed.labels.is_empty() && observed.edge_types.is_empty() {
+            return;
+        }
+        self.inferred_schema
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .merge(observed);
+    }
+
+    fn advise(&self, plan: &PlannerOutput) {
+        if let Some(advisor) = &self.index_advisor {
+            advisor.record(&plan.unindexed);
+        }
     }
 
     /// Issues a best-effort cancellation signal for a running query.
@@ -1223,9 +2966,12 @@ impl Database {
         if !self.graph.has_property_index(label_user, prop_name)? {
             let def = IndexDef {
                 label: label_user,
-                prop: prop_name,
+                props: vec![prop_name],
                 kind: IndexKind::Chunked,
-                ty: TypeTag::String,
+                types: vec![TypeTag::String],
+                max_key_bytes: None,
+                unique: false,
+                state: IndexState::Ready,
             };
             self.graph.create_property_index(&mut write, def)?;
         }
@@ -1294,6 +3040,7 @@ impl Database {
     /// Applies a JSON-friendly create script by reusing the fluent builder.
     pub fn create_script(&self, script: CreateScript) -> Result<CreateResult> {
         let mut builder = self.create();
+        builder.defer_indexes(script.defer_indexes);
         let mut handles = Vec::with_capacity(script.nodes.len());
         for node in script.nodes {
             let handle = if let Some(alias) = node.alias {
@@ -1598,10 +3345,27 @@ impl Database {
             }
             return Ok(result);
         }
+        // Repeated keys resolve per the configured policy so storage never
+        // sees the same property id twice.
+        let mut positions: HashMap<PropId, usize> = HashMap::new();
         for entry in props {
             let prop_id = self.resolve_or_cache_prop(write, &entry.key, cache)?;
             let value = entry.to_prop_value_owned()?;
-            result.push((prop_id, value));
+            match positions.get(&prop_id) {
+                Some(&idx) => match self.duplicate_props {
+                    DuplicatePropertyPolicy::LastWins => result[idx].1 = value,
+                    DuplicatePropertyPolicy::Error => {
+                        return Err(FfiError::Message(format!(
+                            "duplicate property key '{}'",
+                            entry.key
+                        )));
+                    }
+                },
+                None => {
+                    positions.insert(prop_id, result.len());
+                    result.push((prop_id, value));
+                }
+            }
         }
         Ok(result)
     }
@@ -1635,6 +3399,23 @@ impl Database {
         }
     }
 
+    fn handle_async_fsync_pragma(&self, value: Option<Value>) -> Result<Value> {
+        if let Some(val) = value {
+            let enabled = parse_bool(&val, "async_fsync")?;
+            self.pager.set_async_fsync(enabled)?;
+        }
+        Ok(Value::Bool(self.pager.async_fsync()))
+    }
+
+    fn handle_async_fsync_max_wait_pragma(&self, value: Option<Value>) -> Result<Value> {
+        if let Some(val) = value {
+            let ms = parse_u64(&val, "async_fsync_max_wait_ms")?;
+            self.pager.set_async_fsync_max_wait_ms(ms);
+        }
+        let current = self.pager.async_fsync_max_wait_ms();
+        Ok(Value::Number(Number::from(current)))
+    }
+
     /// Applies a mutation specification (create, update, delete operations).
     pub fn mutate(&self, spec: MutationSpec) -> Result<MutationSummary> {
         let mut write = self.pager.begin_write()?;
@@ -1648,6 +3429,271 @@ impl Database {
         Ok(summary)
     }
 
+    /// Reconciles nodes and edges identified by key properties in a single
+    /// transaction.
+    ///
+    /// Each upsert merges its properties into the node whose `key` property
+    /// matches, creating the node when none exists. Edge upserts do the same
+    /// for the edge of their type between two keyed nodes. Deletions remove
+    /// the matching edges, then nodes, and are ignored when they are already
+    /// gone. Keys are matched against the transaction's own view, so entities
+    /// created or removed earlier in the sync are seen. Any failure, including
+    /// a key that matches more than one node, rolls back the whole sync.
+    pub fn sync(&self, spec: SyncSpec) -> Result<SyncSummary> {
+        let mut write = self.pager.begin_write()?;
+        let mut summary = SyncSummary::default();
+        let mut keys = SyncKeys::default();
+        for upsert in spec.upserts {
+            let Some(key_value) = upsert.props.get(&upsert.key) else {
+                return Err(FfiError::Message(format!(
+                    "sync upsert for label '{}' is missing key property '{}'",
+                    upsert.label, upsert.key
+                )));
+            };
+            let lookup = sync_key(key_value)?;
+            let nodes =
+                self.sync_lookup(&mut write, &mut keys, &upsert.label, &upsert.key, &lookup)?;
+            match nodes.as_slice() {
+                [node] => {
+                    self.apply_mutation_op(
+                        &mut write,
+                        &mut MutationSummary::default(),
+                        MutationOp::UpdateNode {
+                            id: node.0,
+                            set: upsert.props,
+                            unset: Vec::new(),
+                        },
+                    )?;
+                    self.sync_track(&mut write, &mut keys, *node)?;
+                    summary.updated += 1;
+                }
+                [] => {
+                    let mut created = MutationSummary::default();
+                    self.apply_mutation_op(
+                        &mut write,
+                        &mut created,
+                        MutationOp::CreateNode {
+                            labels: vec![upsert.label],
+                            props: upsert.props,
+                        },
+                    )?;
+                    for id in created.created_nodes {
+                        self.sync_track(&mut write, &mut keys, NodeId(id))?;
+                    }
+                    summary.created += 1;
+                }
+                nodes => {
+                    return Err(FfiError::Message(format!(
+                        "sync key {}.{}={} matches {} nodes",
+                        upsert.label,
+                        upsert.key,
+                        lookup,
+                        nodes.len()
+                    )));
+                }
+            }
+        }
+        for upsert in spec.edge_upserts {
+            let endpoint = |node: Option<NodeId>, at: &SyncNodeRef| {
+                node.ok_or_else(|| {
+                    FfiError::Message(format!(
+                        "sync edge endpoint {}.{}={} matches no node",
+                        at.label, at.key, at.value
+                    ))
+                })
+            };
+            let src = self.sync_node(&mut write, &mut keys, &upsert.src)?;
+            let src = endpoint(src, &upsert.src)?;
+            let dst = self.sync_node(&mut write, &mut keys, &upsert.dst)?;
+            let dst = endpoint(dst, &upsert.dst)?;
+            let ty = self.resolve_type(&mut write, &upsert.ty)?;
+            match self.sync_edges(&mut write, src, ty, dst)?.as_slice() {
+                [edge] => {
+                    self.apply_mutation_op(
+                        &mut write,
+                        &mut MutationSummary::default(),
+                        MutationOp::UpdateEdge {
+                            id: edge.0,
+                            set: upsert.props,
+                            unset: Vec::new(),
+                        },
+                    )?;
+                    summary.edges_updated += 1;
+                }
+                [] => {
+                    self.apply_mutation_op(
+                        &mut write,
+                        &mut MutationSummary::default(),
+                        MutationOp::CreateEdge {
+                            src: src.0,
+                            dst: dst.0,
+                            ty: upsert.ty,
+                            props: upsert.props,
+                        },
+                    )?;
+                    summary.edges_created += 1;
+                }
+                edges => {
+                    return Err(FfiError::Message(format!(
+                        "sync edge {} from node {} to node {} matches {} edges",
+                        upsert.ty,
+                        src.0,
+                        dst.0,
+                        edges.len()
+                    )));
+                }
+            }
+        }
+        for delete in spec.edge_deletes {
+            let Some(src) = self.sync_node(&mut write, &mut keys, &delete.src)? else {
+                continue;
+            };
+            let Some(dst) = self.sync_node(&mut write, &mut keys, &delete.dst)? else {
+                continue;
+            };
+            let Some(ty) = self.dict.lookup_with_write(&mut write, &delete.ty)? else {
+                continue;
+            };
+            for edge in self.sync_edges(&mut write, src, TypeId(ty.0), dst)? {
+                self.apply_mutation_op(
+                    &mut write,
+                    &mut MutationSummary::default(),
+                    MutationOp::DeleteEdge { id: edge.0 },
+                )?;
+                summary.edges_deleted += 1;
+            }
+        }
+        for delete in spec.deletes {
+            let lookup = sync_key(&delete.value)?;
+            let nodes =
+                self.sync_lookup(&mut write, &mut keys, &delete.label, &delete.key, &lookup)?;
+            for node in nodes {
+                self.apply_mutation_op(
+                    &mut write,
+                    &mut MutationSummary::default(),
+                    MutationOp::DeleteNode {
+                        id: node.0,
+                        cascade: delete.cascade,
+                    },
+                )?;
+                self.sync_track(&mut write, &mut keys, node)?;
+                summary.deleted += 1;
+            }
+        }
+        self.graph.flush_deferred_writes(&mut write)?;
+        self.pager.commit(write)?;
+        Ok(summary)
+    }
+
+    /// Returns the nodes of `label` whose `key` property normalizes to
+    /// `lookup`, loading that key's index through `write` on first use.
+    fn sync_lookup(
+        &self,
+        write: &mut WriteGuard<'_>,
+        keys: &mut SyncKeys,
+        label: &str,
+        key: &str,
+        lookup: &str,
+    ) -> Result<Vec<NodeId>> {
+        let slot = (label.to_string(), key.to_string());
+        if !keys.slots.contains_key(&slot) {
+            let mut index = SyncKeyIndex {
+                label: self
+                    .dict
+                    .lookup_with_write(write, label)?
+                    .map(|id| LabelId(id.0)),
+                prop: self
+                    .dict
+                    .lookup_with_write(write, key)?
+                    .map(|id| PropId(id.0)),
+                ..SyncKeyIndex::default()
+            };
+            if let (Some(label), Some(_)) = (index.label, index.prop) {
+                for node in self.graph.nodes_with_label_with_write(write, label)? {
+                    let data = self.graph.get_node_in_write(write, node)?;
+                    index.place(node, data.as_ref())?;
+                }
+            }
+            keys.slots.insert(slot.clone(), index);
+        }
+        Ok(keys.slots[&slot]
+            .nodes
+            .get(lookup)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    /// Resolves a keyed node reference, failing when it matches several nodes.
+    fn sync_node(
+        &self,
+        write: &mut WriteGuard<'_>,
+        keys: &mut SyncKeys,
+        node: &SyncNodeRef,
+    ) -> Result<Option<NodeId>> {
+        let lookup = sync_key(&node.value)?;
+        match self
+            .sync_lookup(write, keys, &node.label, &node.key, &lookup)?
+            .as_slice()
+        {
+            [] => Ok(None),
+            [id] => Ok(Some(*id)),
+            nodes => Err(FfiError::Message(format!(
+                "sync key {}.{}={} matches {} nodes",
+                node.label,
+                node.key,
+                lookup,
+                nodes.len()
+            ))),
+        }
+    }
+
+    /// Re-files `node` in every loaded key index after the sync changed it.
+    fn sync_track(
+        &self,
+        write: &mut WriteGuard<'_>,
+        keys: &mut SyncKeys,
+        node: NodeId,
+    ) -> Result<()> {
+        if keys.slots.is_empty() {
+            return Ok(());
+        }
+        let data = self.graph.get_node_in_write(write, node)?;
+        for ((label, key), index) in keys.slots.iter_mut() {
+            // Names first interned by this sync resolve once it creates them.
+            if index.label.is_none() {
+                index.label = self
+                    .dict
+                    .lookup_with_write(write, label)?
+                    .map(|id| LabelId(id.0));
+            }
+            if index.prop.is_none() {
+                index.prop = self
+                    .dict
+                    .lookup_with_write(write, key)?
+                    .map(|id| PropId(id.0));
+            }
+            index.place(node, data.as_ref())?;
+        }
+        Ok(())
+    }
+
+    /// Lists the live `ty` edges from `src` to `dst` as `write` sees them.
+    fn sync_edges(
+        &self,
+        write: &mut WriteGuard<'_>,
+        src: NodeId,
+        ty: TypeId,
+        dst: NodeId,
+    ) -> Result<Vec<EdgeId>> {
+        Ok(self
+            .graph
+            .incident_edges_with_write(write, src)?
+            .into_iter()
+            .filter(|(_, edge)| edge.src == src && edge.dst == dst && edge.ty == ty)
+            .map(|(id, _)| id)
+            .collect())
+    }
+
     fn apply_mutation_op(
         &self,
         write: &mut WriteGuard<'_>,
@@ -1703,6 +3749,52 @@ impl Database {
                 summary.updated_nodes += 1;
                 Ok(())
             }
+            MutationOp::PatchNode { id, patch } => {
+                let node = NodeId(id);
+                let mut present: HashSet<PropId> = self
+                    .graph
+                    .get_node_in_write(write, node)?
+                    .ok_or_else(|| FfiError::Message(format!("node {id} not found")))?
+                    .props
+                    .into_iter()
+                    .map(|(prop, _)| prop)
+                    .collect();
+                let mut storage: Vec<(PropId, Option<PropValueOwned>)> =
+                    Vec::with_capacity(patch.len());
+                for op in patch {
+                    let (path, value, must_exist) = match op {
+                        JsonPatchOp::Add { path, value } => (path, Some(value), false),
+                        JsonPatchOp::Replace { path, value } => (path, Some(value), true),
+                        JsonPatchOp::Remove { path } => (path, None, true),
+                    };
+                    let prop = self.resolve_prop(write, &json_patch_prop(&path)?)?;
+                    if must_exist && !present.contains(&prop) {
+                        return Err(FfiError::Message(format!(
+                            "patch path '{path}' does not exist on node {id}"
+                        )));
+                    }
+                    match value {
+                        Some(value) => {
+                            present.insert(prop);
+                            storage.push((prop, Some(value_to_prop_value(&value)?)));
+                        }
+                        None => {
+                            present.remove(&prop);
+                            storage.push((prop, None));
+                        }
+                    }
+                }
+                let ops = storage
+                    .iter()
+                    .map(|(prop, owned)| match owned {
+                        Some(owned) => PropPatchOp::Set(*prop, prop_value_ref(owned)),
+                        None => PropPatchOp::Delete(*prop),
+                    })
+                    .collect();
+                self.graph.update_node(write, node, PropPatch::new(ops))?;
+                summary.updated_nodes += 1;
+                Ok(())
+            }
             MutationOp::DeleteNode { id, cascade } => {
                 let opts = if cascade {
                     DeleteNodeOpts::cascade()
@@ -1786,20 +3878,25 @@ impl Database {
     }
 
     fn resolve_label(&self, write: &mut WriteGuard<'_>, name: &str) -> Result<LabelId> {
-        let id = self.dict.intern(write, name)?;
+        let id = self.intern_name(write, name, NameKind::Label)?;
         Ok(LabelId(id.0))
     }
 
     fn resolve_prop(&self, write: &mut WriteGuard<'_>, name: &str) -> Result<PropId> {
-        let id = self.dict.intern(write, name)?;
+        let id = self.intern_name(write, name, NameKind::PropKey)?;
         Ok(PropId(id.0))
     }
 
     fn resolve_type(&self, write: &mut WriteGuard<'_>, name: &str) -> Result<TypeId> {
-        let id = self.dict.intern(write, name)?;
+        let id = self.intern_name(write, name, NameKind::EdgeType)?;
         Ok(TypeId(id.0))
     }
 
+    fn intern_name(&self, write: &mut WriteGuard<'_>, name: &str, kind: NameKind) -> Result<StrId> {
+        let limit = self.graph.name_limit(kind);
+        Ok(self.dict.intern_as(write, name, kind, limit)?)
+    }
+
     fn lookup_edge_type(&self, name: &str) -> Result<TypeId> {
         match self.dict.lookup(name).map_err(FfiError::from)? {
             Some(id) => Ok(TypeId(id.0)),
@@ -1823,27 +3920,7 @@ impl Database {
     }
 
     fn materialize_node(&self, read: &ReadGuard, id: NodeId, node: NodeData) -> Result<NodeRecord> {
-        let mut labels = Vec::with_capacity(node.labels.len());
-        for label in node.labels {
-            let name = self
-                .dict
-                .resolve(read, StrId(label.0))
-                .map_err(FfiError::from)?;
-            labels.push(name);
-        }
-        let mut props = Map::new();
-        for (prop, value) in node.props {
-            let name = self
-                .dict
-                .resolve(read, StrId(prop.0))
-                .map_err(FfiError::from)?;
-            props.insert(name, prop_value_owned_to_json(&value)?);
-        }
-        Ok(NodeRecord {
-            id: id.0,
-            labels,
-            properties: props,
-        })
+        materialize_node_with(id, node, |str_id| self.dict.resolve(read, str_id))
     }
 
     fn materialize_edge(&self, read: &ReadGuard, id: EdgeId, edge: EdgeData) -> Result<EdgeRecord> {
@@ -1868,8 +3945,24 @@ impl Database {
         })
     }
 
+    /// Rebuilds indexes suspended for a deferred-index load and creates the
+    /// label indexes that writers skipped for `labels`.
+    fn restore_deferred_indexes(
+        &self,
+        suspended: SuspendedIndexes,
+        labels: Vec<LabelId>,
+    ) -> Result<()> {
+        let mut write = self.pager.begin_write()?;
+        self.graph.restore_indexes(&mut write, suspended)?;
+        for label in labels {
+            self.ensure_label_index(&mut write, label)?;
+        }
+        self.pager.commit(write)?;
+        Ok(())
+    }
+
     fn ensure_label_index(&self, write: &mut WriteGuard<'_>, label: LabelId) -> Result<()> {
-        if self.graph.has_label_index(label)? {
+        if !self.graph.auto_label_index() || self.graph.has_label_index(label)? {
             return Ok(());
         }
         self.graph
@@ -1879,6 +3972,7 @@ impl Database {
 
     fn plan(&self, spec: QuerySpec) -> Result<PlannerOutput> {
         let ast = spec.into_ast()?;
+        self.planner.check_pattern_size(&ast)?;
         let analyzed = analyze::analyze(&ast, self.metadata.as_ref())?;
         self.planner
             .plan_analyzed(&analyzed)
@@ -1909,6 +4003,82 @@ impl Drop for Database {
 struct StreamInner {
     stream: Mutex<ResultStream>,
     _guard: Option<CancellationHandle>,
+    bytes_format: BytesFormat,
+}
+
+/// Query planned once by [`Database::prepare`] and executed with different
+/// parameter values.
+pub struct PreparedQuery {
+    spec: QuerySpec,
+    /// Parameter names mapped to the comparison that orders their value.
+    params: BTreeMap<String, Option<&'static str>>,
+    graph: Weak<Graph>,
+    /// Plan together with the catalog epoch it was built against.
+    plan: Mutex<(u64, PlannerOutput)>,
+}
+
+impl PreparedQuery {
+    /// Names of the parameters the query expects, in sorted order.
+    pub fn params(&self) -> impl Iterator<Item = &str> + '_ {
+        self.params.keys().map(String::as_str)
+    }
+
+    /// Binds `params` into the prepared plan and executes it against `db`,
+    /// which must be the database that prepared the query.
+    ///
+    /// `params` maps every parameter name to a typed literal such as
+    /// `{ "t": "Int", "v": 30 }`. The planner only runs again when an index
+    /// was created or dropped since the plan was built.
+    pub fn execute(&self, db: &Database, params: &Map<String, Value>) -> Result<Value> {
+        if !Weak::ptr_eq(&self.graph, &Arc::downgrade(&db.graph)) {
+            return Err(FfiError::Message(
+                "prepared query belongs to a different database".into(),
+            ));
+        }
+        let values = self.bind(params)?;
+        let mut plan = {
+            let mut cached = self
+                .plan
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            let epoch = db.metadata.catalog_epoch();
+            if cached.0 != epoch {
+                *cached = (epoch, db.plan(self.spec.clone())?);
+            }
+            cached.1.clone()
+        };
+        plan.plan.rewrite_literals(&mut |literal| {
+            if let LiteralValue::String(text) = literal {
+                if let Some(name) = text.strip_prefix(PARAM_PLACEHOLDER) {
+                    *literal = values[name].clone();
+                }
+            }
+        });
+        db.execute_with(self.spec.clone(), |_| Ok(plan))
+    }
+
+    fn bind(&self, params: &Map<String, Value>) -> Result<HashMap<&str, LiteralValue>> {
+        if let Some(unknown) = params.keys().find(|name| !self.params.contains_key(*name)) {
+            return Err(FfiError::Message(format!("unknown parameter '{unknown}'")));
+        }
+        self.params
+            .iter()
+            .map(|(name, ordered_by)| {
+                let raw = params.get(name).ok_or_else(|| {
+                    FfiError::Message(format!("missing value for parameter '{name}'"))
+                })?;
+                let payload: PayloadValue = serde_json::from_value(raw.clone()).map_err(|err| {
+                    FfiError::Message(format!("invalid value for parameter '{name}': {err}"))
+                })?;
+                let value = payload.into_value()?;
+                validate_scalar_value(&value)?;
+                if let Some(ctx) = ordered_by {
+                    ensure_orderable(&value, ctx)?;
+                }
+                Ok((name.as_str(), LiteralValue::from(&value)))
+            })
+            .collect()
+    }
 }
 
 /// A streaming query result that can be consumed incrementally.
@@ -1919,11 +4089,16 @@ pub struct QueryStream {
 }
 
 impl QueryStream {
-    fn new(stream: ResultStream, guard: Option<CancellationHandle>) -> Self {
+    fn new(
+        stream: ResultStream,
+        guard: Option<CancellationHandle>,
+        bytes_format: BytesFormat,
+    ) -> Self {
         Self {
             inner: Arc::new(StreamInner {
                 stream: Mutex::new(stream),
                 _guard: guard,
+                bytes_format,
             }),
         }
     }
@@ -1939,11 +4114,35 @@ impl QueryStream {
             .lock()
             .map_err(|_| FfiError::Message("stream poisoned".into()))?;
         match guard.next() {
-            Some(Ok(row)) => Ok(Some(row_to_value(&row)?)),
-            Some(Err(err)) => Err(err.into()),
+            Some(Ok(row)) => Ok(Some(row_to_value(&row, self.inner.bytes_format)?)),
+            Some(Err(err)) => Err(exec_error(err)),
             None => Ok(None),
         }
     }
+
+    /// Fetches up to `max_rows` rows in one call.
+    ///
+    /// Returns fewer rows only when the stream is exhausted, and an empty
+    /// batch once nothing is left.
+    pub fn next_batch(&self, max_rows: usize) -> Result<Vec<Value>> {
+        if max_rows == 0 {
+            return Err(FfiError::Message("batch size must be at least 1".into()));
+        }
+        let mut guard = self
+            .inner
+            .stream
+            .lock()
+            .map_err(|_| FfiError::Message("stream poisoned".into()))?;
+        let mut batch = Vec::with_capacity(max_rows.min(1024));
+        while batch.len() < max_rows {
+            match guard.next() {
+                Some(Ok(row)) => batch.push(row_to_value(&row, self.inner.bytes_format)?),
+                Some(Err(err)) => return Err(exec_error(err)),
+                None => break,
+            }
+        }
+        Ok(batch)
+    }
 }
 
 impl Clone for QueryStream {
@@ -1954,13 +4153,54 @@ impl Clone for QueryStream {
     }
 }
 
-fn parse_synchronous_value(value: &Value) -> Result<Synchronous> {
-    let Some(raw) = value.as_str() else {
-        return Err(FfiError::Message(
-            "PRAGMA synchronous expects a string value".into(),
-        ));
-    };
-    Synchronous::from_str(raw).ok_or_else(|| {
+/// Number of adjacency entries a [`NeighborStream`] reads per batch.
+pub const NEIGHBOR_STREAM_BATCH: usize = 256;
+
+/// Neighbor entries of a single node, read lazily from the snapshot taken by
+/// [`Database::neighbor_stream`].
+pub struct NeighborStream {
+    graph: Arc<Graph>,
+    read: ReadGuard,
+    scan: NeighborScan,
+    batch: std::vec::IntoIter<Neighbor>,
+}
+
+impl Iterator for NeighborStream {
+    type Item = Result<NeighborInfo>;
+
+    fn next(&mut self) -> Option<Result<NeighborInfo>> {
+        if self.batch.len() == 0 {
+            if self.scan.is_done() {
+                return None;
+            }
+            match self
+                .graph
+                .next_neighbors(&self.read, &mut self.scan, NEIGHBOR_STREAM_BATCH)
+            {
+                Ok(batch) => self.batch = batch.into_iter(),
+                Err(err) => {
+                    self.scan.stop();
+                    return Some(Err(err.into()));
+                }
+            }
+        }
+        self.batch.next().map(|entry| {
+            Ok(NeighborInfo {
+                node_id: entry.neighbor.0,
+                edge_id: entry.edge.0,
+                type_id: entry.ty.0,
+            })
+        })
+    }
+}
+
+fn parse_synchronous_value(value: &Value) -> Result<Synchronous> {
+    let Some(raw) = value.as_str() else {
+        return Err(FfiError::Message(
+            "PRAGMA synchronous expects a string value".into(),
+        ));
+    };
+    Synchronous::from_str(raw).ok_or_else(|| {
         FfiError::Message(format!(
             "invalid synchronous mode '{raw}', expected 'full', 'normal', or 'off'"
         ))
@@ -1981,6 +4221,29 @@ fn parse_u64(value: &Value, field: &str) -> Result<u64> {
     }
 }
 
+fn parse_bool(value: &Value, field: &str) -> Result<bool> {
+    match value {
+        Value::Bool(flag) => Ok(*flag),
+        Value::Number(num) => match num.as_u64() {
+            Some(0) => Ok(false),
+            Some(1) => Ok(true),
+            _ => Err(FfiError::Message(format!(
+                "PRAGMA {field} requires a boolean value"
+            ))),
+        },
+        Value::String(s) => match s.to_ascii_lowercase().as_str() {
+            "true" | "on" | "1" => Ok(true),
+            "false" | "off" | "0" => Ok(false),
+            _ => Err(FfiError::Message(format!(
+                "PRAGMA {field} requires a boolean value"
+            ))),
+        },
+        _ => Err(FfiError::Message(format!(
+            "PRAGMA {field} requires a boolean value"
+        ))),
+    }
+}
+
 fn parse_optional_u64(value: &Value, field: &str) -> Result<Option<u64>> {
     if value.is_null() {
         return Ok(None);
@@ -2003,7 +4266,7 @@ fn enforce_payload_size(spec: &Value) -> Result<()> {
 /// JSON-deserializable query specification for FFI clients.
 ///
 /// Defines match clauses, edges, predicates, and projections for graph queries.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct QuerySpec {
     /// Schema version for the query payload.
@@ -2021,12 +4284,310 @@ pub struct QuerySpec {
     /// Canonical boolean predicate tree.
     #[serde(default)]
     pub predicate: Option<PredicateSpec>,
+    /// Predicate tree keyed by connective (`{ "and": [...] }`,
+    /// `{ "or": [...] }`, `{ "not": {...} }`) with [`PredicateSpec`] leaves.
+    /// Combined with `predicate` by conjunction when both are given.
+    #[serde(default)]
+    pub filter: Option<FilterSpec>,
     /// Column projections for result output.
     #[serde(default)]
     pub projections: Vec<ProjectionSpec>,
     /// Whether to return distinct results only.
     #[serde(default)]
     pub distinct: bool,
+    /// Per-operator deadline in milliseconds; an operator whose own work
+    /// exceeds it aborts the query with an error naming that operator.
+    #[serde(default, alias = "operator_timeout_ms")]
+    pub operator_timeout_ms: Option<u64>,
+    /// Wall-clock budget in milliseconds for the whole query, counted from
+    /// when it starts; rows are no longer pulled once it elapses and the
+    /// query fails with [`FfiError::QueryTimeout`].
+    #[serde(default, alias = "timeout_ms")]
+    pub timeout_ms: Option<u64>,
+    /// Whether to report the result columns and their inferred types
+    /// alongside the rows, even when no rows match.
+    #[serde(default, alias = "include_schema")]
+    pub include_schema: bool,
+    /// Whether to report conditions that let the query run but are worth
+    /// knowing about, such as full scans or truncated results, alongside
+    /// the rows.
+    #[serde(default, alias = "include_warnings")]
+    pub include_warnings: bool,
+    /// Whether node projections key their properties by dictionary-resolved
+    /// names (the default) or by the interned property ids.
+    #[serde(default, alias = "resolve_names")]
+    pub resolve_names: Option<bool>,
+    /// Fails analysis when match clauses are not all joined by edges instead
+    /// of executing their cartesian product. Defaults to `true`; set `false`
+    /// to opt in to the cross join.
+    #[serde(default, alias = "reject_cartesian")]
+    pub reject_cartesian: Option<bool>,
+    /// Result columns to deduplicate rows on, keeping the first row seen for
+    /// each combination of their values.
+    #[serde(default, alias = "dedup_by")]
+    pub dedup_by: Vec<String>,
+    /// Returns each row as its bare value instead of a single-key object.
+    /// Only valid when the query produces exactly one result column.
+    #[serde(default)]
+    pub flatten: bool,
+    /// Stops after this many rows and reports `truncated` alongside them,
+    /// `true` when the query had more rows to give.
+    #[serde(default, alias = "max_rows")]
+    pub max_rows: Option<usize>,
+    /// Groups rows under their parent var, embedding the child var's columns
+    /// as an array instead of returning one row per pair.
+    #[serde(default)]
+    pub nest: Option<NestSpec>,
+    /// Replaces `rows` with `counts`, mapping each node bound to the first
+    /// match var to the number of result rows it produced.
+    #[serde(default, alias = "count_per_start")]
+    pub count_per_start: bool,
+    /// Sorts rows by these keys, in priority order, before they are
+    /// returned. Missing and null values sort last in either direction.
+    /// Rows tied on every key are ordered by the node ids bound to each
+    /// match variable, the first match first, so pages never overlap.
+    #[serde(default, alias = "order_by")]
+    pub order_by: Vec<OrderSpec>,
+    /// Sorts the node-id tiebreak from largest to smallest. Defaults to the
+    /// direction of the last `orderBy` key.
+    #[serde(default, alias = "tiebreak_desc")]
+    pub tiebreak_desc: Option<bool>,
+    /// How bytes values are written into the result rows.
+    #[serde(default, alias = "bytes_format")]
+    pub bytes_format: BytesFormat,
+    /// Returns at most this many rows, counted after `orderBy` and `offset`.
+    /// Execution stops pulling rows once the limit is reached unless a sort
+    /// has to see every row first.
+    #[serde(default)]
+    pub limit: Option<u64>,
+    /// Skips this many leading rows, counted after `orderBy`.
+    #[serde(default)]
+    pub offset: Option<u64>,
+    /// Keeps only the top rows of each group before `orderBy`, `offset`,
+    /// and `limit` apply to the whole result.
+    #[serde(default, alias = "limit_per_group")]
+    pub limit_per_group: Option<LimitPerGroupSpec>,
+}
+
+/// JSON encoding of bytes values in query results.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BytesFormat {
+    /// Base64-encoded string.
+    #[default]
+    Base64,
+    /// Array of octets, one number per byte.
+    Array,
+}
+
+impl QuerySpec {
+    fn exec_options(&self) -> ExecOptions {
+        ExecOptions {
+            operator_timeout: self.operator_timeout_ms.map(Duration::from_millis),
+            resolve_names: self.resolve_names.unwrap_or(true),
+            dedup_by: self.dedup_by.clone(),
+            max_rows: self.max_rows,
+            deadline: self
+                .timeout_ms
+                .map(|ms| Instant::now() + Duration::from_millis(ms)),
+        }
+    }
+
+    /// Returns the driving var [`QuerySpec::count_per_start`] groups on.
+    fn count_start_var(&self) -> Result<String> {
+        if self.flatten || self.nest.is_some() || !self.dedup_by.is_empty() {
+            return Err(FfiError::Message(
+                "countPerStart cannot be combined with flatten, nest, or dedupBy".into(),
+            ));
+        }
+        self.matches
+            .first()
+            .map(|clause| clause.var.clone())
+            .ok_or_else(|| FfiError::Message("countPerStart requires a match".into()))
+    }
+}
+
+/// Nesting options for [`QuerySpec::nest`].
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NestSpec {
+    /// Match variable whose columns form each output row.
+    pub parent: String,
+    /// Match variable whose columns are collected into the parent's array.
+    pub child: String,
+    /// Key the child array is stored under; defaults to the child var.
+    #[serde(default, rename = "as")]
+    pub key: Option<String>,
+}
+
+impl NestSpec {
+    /// Builds the parent-only query used to find parents without children.
+    ///
+    /// Predicate conjuncts that mention only the parent var carry over, so
+    /// they still filter childless parents; the rest are dropped.
+    fn parent_query(&self, spec: &QuerySpec) -> Result<QuerySpec> {
+        let vars: HashSet<&str> = spec
+            .matches
+            .iter()
+            .map(|clause| clause.var.as_str())
+            .collect();
+        let expected: HashSet<&str> = [self.parent.as_str(), self.child.as_str()].into();
+        if self.parent == self.child || vars != expected {
+            return Err(FfiError::Message(format!(
+                "nest requires matches on exactly the parent '{}' and child '{}' vars",
+                self.parent, self.child
+            )));
+        }
+        if spec.flatten {
+            return Err(FfiError::Message(
+                "nest cannot be combined with flatten".into(),
+            ));
+        }
+        let predicate = merged_predicate(spec.predicate.clone(), spec.filter.clone())
+            .and_then(|predicate| parent_only_predicate(&predicate, &self.parent));
+        let projections = spec
+            .projections
+            .iter()
+            .filter(|projection| match projection {
+                ProjectionSpec::Var { var, .. } | ProjectionSpec::Prop { var, .. } => {
+                    *var == self.parent
+                }
+                _ => false,
+            })
+            .cloned()
+            .collect();
+        Ok(QuerySpec {
+            schema_version: spec.schema_version,
+            request_id: None,
+            matches: spec
+                .matches
+                .iter()
+                .filter(|clause| clause.var == self.parent)
+                .cloned()
+                .collect(),
+            edges: Vec::new(),
+            predicate,
+            filter: None,
+            projections,
+            distinct: true,
+            operator_timeout_ms: spec.operator_timeout_ms,
+            timeout_ms: spec.timeout_ms,
+            include_schema: false,
+            include_warnings: false,
+            resolve_names: spec.resolve_names,
+            reject_cartesian: None,
+            dedup_by: Vec::new(),
+            flatten: false,
+            max_rows: None,
+            nest: None,
+            count_per_start: false,
+            order_by: Vec::new(),
+            tiebreak_desc: None,
+            bytes_format: BytesFormat::default(),
+            limit: None,
+            offset: None,
+            limit_per_group: None,
+        })
+    }
+
+    /// Splits a plan's result columns into parent and child columns.
+    fn child_columns(&self, plan: &PhysicalPlan) -> Result<HashSet<String>> {
+        let PhysicalOp::Project { fields } = &plan.root.op else {
+            return Ok(HashSet::from([self.child.clone()]));
+        };
+        let mut child = HashSet::new();
+        let mut parent = 0usize;
+        for field in fields {
+            let var = match field {
+                ProjectField::Var { var, .. } | ProjectField::Prop { var, .. } => var,
+                _ => {
+                    return Err(FfiError::Message(
+                        "nest supports only var and prop projections".into(),
+                    ))
+                }
+            };
+            if var.0 == self.child {
+                child.insert(field.column_name());
+            } else {
+                parent += 1;
+            }
+        }
+        if parent == 0 || child.is_empty() {
+            return Err(FfiError::Message(
+                "nest requires projecting both the parent and child vars".into(),
+            ));
+        }
+        Ok(child)
+    }
+}
+
+/// Conjoins the flat `predicate` with the keyed `filter` tree.
+fn merged_predicate(
+    predicate: Option<PredicateSpec>,
+    filter: Option<FilterSpec>,
+) -> Option<PredicateSpec> {
+    match (predicate, filter.map(FilterSpec::into_predicate)) {
+        (Some(predicate), Some(filter)) => Some(PredicateSpec::And {
+            args: vec![predicate, filter],
+        }),
+        (predicate, filter) => predicate.or(filter),
+    }
+}
+
+/// Keeps the parts of `predicate` that reference only `var`.
+fn parent_only_predicate(predicate: &PredicateSpec, var: &str) -> Option<PredicateSpec> {
+    if let PredicateSpec::And { args } = predicate {
+        let args: Vec<PredicateSpec> = args
+            .iter()
+            .filter_map(|arg| parent_only_predicate(arg, var))
+            .collect();
+        return match args.len() {
+            0 => None,
+            1 => args.into_iter().next(),
+            _ => Some(PredicateSpec::And { args }),
+        };
+    }
+    let mut vars = HashSet::new();
+    predicate_vars(predicate, &mut vars);
+    (vars.len() == 1 && vars.contains(var)).then(|| predicate.clone())
+}
+
+fn predicate_vars<'a>(predicate: &'a PredicateSpec, out: &mut HashSet<&'a str>) {
+    match predicate {
+        PredicateSpec::And { args } | PredicateSpec::Or { args } | PredicateSpec::Not { args } => {
+            for arg in args {
+                predicate_vars(arg, out);
+            }
+        }
+        PredicateSpec::Eq { var, .. }
+        | PredicateSpec::Ne { var, .. }
+        | PredicateSpec::Lt { var, .. }
+        | PredicateSpec::Le { var, .. }
+        | PredicateSpec::Gt { var, .. }
+        | PredicateSpec::Ge { var, .. }
+        | PredicateSpec::Between { var, .. }
+        | PredicateSpec::In { var, .. }
+        | PredicateSpec::Prefix { var, .. }
+        | PredicateSpec::Exists { var, .. }
+        | PredicateSpec::IsNull { var, .. }
+        | PredicateSpec::IsNotNull { var, .. } => {
+            out.insert(var.as_str());
+        }
+        PredicateSpec::EdgeExists {
+            from_var,
+            target,
+            predicate,
+            ..
+        } => {
+            out.insert(from_var.as_str());
+            if let Some(inner) = predicate {
+                let mut inner_vars = HashSet::new();
+                predicate_vars(inner, &mut inner_vars);
+                inner_vars.remove(target.var.as_str());
+                out.extend(inner_vars);
+            }
+        }
+    }
 }
 
 /// Explain-specific options layered on top of [`QuerySpec`].
@@ -2044,6 +4605,13 @@ pub struct ExplainSpec {
 #[allow(dead_code)]
 const MAX_PAYLOAD_BYTES: usize = 8 * 1024 * 1024;
 
+/// Largest database image accepted by [`Database::to_bytes`] and
+/// [`Database::open_from_bytes`].
+///
+/// Images are held entirely in memory on both sides, so byte snapshots are
+/// meant for small embedded graphs; copy the file for anything larger.
+pub const MAX_SNAPSHOT_BYTES: usize = 64 * 1024 * 1024;
+
 impl QuerySpec {
     fn into_ast(self) -> Result<QueryAst> {
         let schema_version = self
@@ -2077,8 +4645,7 @@ impl QuerySpec {
             .into_iter()
             .map(EdgeSpec::into_clause)
             .collect::<Result<Vec<_>>>()?;
-        let predicate = self
-            .predicate
+        let predicate = merged_predicate(self.predicate, self.filter)
             .map(|spec| spec.into_expr())
             .transpose()?
             .and_then(normalized_predicate);
@@ -2087,6 +4654,15 @@ impl QuerySpec {
             .into_iter()
             .map(ProjectionSpec::into_projection)
             .collect::<Result<Vec<_>>>()?;
+        let order_by = self
+            .order_by
+            .into_iter()
+            .map(OrderSpec::into_key)
+            .collect::<Result<Vec<_>>>()?;
+        let limit_per_group = self
+            .limit_per_group
+            .map(LimitPerGroupSpec::into_group_limit)
+            .transpose()?;
 
         Ok(QueryAst {
             schema_version,
@@ -2096,12 +4672,18 @@ impl QuerySpec {
             predicate,
             distinct: self.distinct,
             projections,
+            order_by,
+            tiebreak_descending: self.tiebreak_desc,
+            limit: self.limit,
+            offset: self.offset,
+            limit_per_group,
+            reject_cartesian: self.reject_cartesian.unwrap_or(true),
         })
     }
 }
 
 /// Specification for a MATCH clause in a query.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MatchSpec {
     /// Variable name to bind matched nodes.
@@ -2124,7 +4706,7 @@ impl MatchSpec {
 }
 
 /// Specification for an edge traversal in a query.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct EdgeSpec {
     /// Source variable name.
@@ -2202,8 +4784,261 @@ impl<'de> Deserialize<'de> for DirectionSpec {
     }
 }
 
+/// Node/edge pattern accepted by [`Database::match_pattern`].
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PatternSpec {
+    /// Node variables, each bound to one node per match.
+    pub nodes: Vec<PatternNodeSpec>,
+    /// Edges that must join the bound nodes.
+    #[serde(default)]
+    pub edges: Vec<PatternEdgeSpec>,
+}
+
+/// Node variable in a [`PatternSpec`].
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PatternNodeSpec {
+    /// Variable name reported in [`PatternMatch::node_bindings`].
+    pub var: String,
+    /// Labels the node must carry; at least one is required.
+    #[serde(default)]
+    pub labels: Vec<String>,
+    /// Property filters the node must pass.
+    #[serde(default)]
+    pub properties: PatternPropertyFilters,
+}
+
+/// Property filters on a [`PatternNodeSpec`]; every filter must hold.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct PatternPropertyFilters {
+    /// Properties that must equal the given values.
+    #[serde(default)]
+    pub equals: BTreeMap<String, PayloadValue>,
+    /// Properties that must be present and differ from the given values.
+    #[serde(default)]
+    pub not_equals: BTreeMap<String, PayloadValue>,
+    /// Bounds on property values.
+    #[serde(default)]
+    pub ranges: Vec<PatternRangeSpec>,
+}
+
+/// Range filter on one property of a pattern node.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PatternRangeSpec {
+    /// Property name.
+    pub key: String,
+    /// Optional lower bound.
+    #[serde(default)]
+    pub min: Option<PatternBoundSpec>,
+    /// Optional upper bound.
+    #[serde(default)]
+    pub max: Option<PatternBoundSpec>,
+}
+
+/// One side of a [`PatternRangeSpec`].
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PatternBoundSpec {
+    /// Bound value.
+    pub value: PayloadValue,
+    /// Whether the bound value itself matches (defaults to true).
+    #[serde(default = "PatternBoundSpec::default_inclusive")]
+    pub inclusive: bool,
+}
+
+impl PatternBoundSpec {
+    fn default_inclusive() -> bool {
+        true
+    }
+}
+
+/// Edge in a [`PatternSpec`].
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PatternEdgeSpec {
+    /// Variable of the edge's source node.
+    pub from: String,
+    /// Variable of the edge's destination node.
+    pub to: String,
+    /// Accepted edge types; empty accepts every type.
+    #[serde(default)]
+    pub types: Vec<String>,
+    /// Direction of the edge relative to `from` (defaults to outgoing).
+    #[serde(default = "DirectionSpec::default_out")]
+    pub direction: DirectionSpec,
+}
+
+/// Pattern edge with its endpoints resolved to variable slots.
+struct PatternEdge {
+    from: usize,
+    to: usize,
+    dir: Dir,
+    /// `None` accepts every type; an empty list accepts none.
+    types: Option<Vec<TypeId>>,
+}
+
+/// Step of a pattern search, run in plan order.
+enum PatternStep {
+    /// Binds a variable to each of its candidates in turn.
+    Scan(usize),
+    /// Follows an edge from the bound `anchor`, binding `other` or, when it
+    /// is already bound, requiring the edge to reach it.
+    Expand {
+        edge: usize,
+        anchor: usize,
+        other: usize,
+        dir: Dir,
+    },
+}
+
+/// Orders the search so each edge is expanded from an already bound
+/// endpoint, starting new components from the variable with the fewest
+/// candidates.
+fn plan_pattern(candidates: &[Vec<NodeId>], edges: &[PatternEdge]) -> Vec<PatternStep> {
+    let mut bound = vec![false; candidates.len()];
+    let mut expanded = vec![false; edges.len()];
+    let mut steps = Vec::with_capacity(candidates.len() + edges.len());
+    loop {
+        let next_edge = (0..edges.len())
+            .find(|&idx| !expanded[idx] && (bound[edges[idx].from] || bound[edges[idx].to]));
+        if let Some(idx) = next_edge {
+            let edge = &edges[idx];
+            let (anchor, other, dir) = if bound[edge.from] {
+                (edge.from, edge.to, edge.dir)
+            } else {
+                let reversed = match edge.dir {
+                    Dir::Out => Dir::In,
+                    Dir::In => Dir::Out,
+                    Dir::Both => Dir::Both,
+                };
+                (edge.to, edge.from, reversed)
+            };
+            expanded[idx] = true;
+            bound[other] = true;
+            steps.push(PatternStep::Expand {
+                edge: idx,
+                anchor,
+                other,
+                dir,
+            });
+            continue;
+        }
+        let next_var = (0..candidates.len())
+            .filter(|&var| !bound[var])
+            .min_by_key(|&var| candidates[var].len());
+        match next_var {
+            Some(var) => {
+                bound[var] = true;
+                steps.push(PatternStep::Scan(var));
+            }
+            None => return steps,
+        }
+    }
+}
+
+/// Backtracking search over a planned pattern on one snapshot.
+struct PatternMatcher<'a> {
+    graph: &'a Graph,
+    read: &'a ReadGuard,
+    steps: Vec<PatternStep>,
+    candidates: Vec<Vec<NodeId>>,
+    edges: Vec<PatternEdge>,
+}
+
+type PatternBinding = (Vec<NodeId>, Vec<EdgeId>);
+
+impl PatternMatcher<'_> {
+    fn search(
+        &self,
+        depth: usize,
+        nodes: &mut [Option<NodeId>],
+        edges: &mut [Option<EdgeId>],
+        out: &mut Vec<PatternBinding>,
+    ) -> Result<()> {
+        let Some(step) = self.steps.get(depth) else {
+            out.push((
+                nodes.iter().flatten().copied().collect(),
+                edges.iter().flatten().copied().collect(),
+            ));
+            return Ok(());
+        };
+        match *step {
+            PatternStep::Scan(var) => {
+                for &id in &self.candidates[var] {
+                    nodes[var] = Some(id);
+                    self.search(depth + 1, nodes, edges, out)?;
+                }
+                nodes[var] = None;
+            }
+            PatternStep::Expand {
+                edge,
+                anchor,
+                other,
+                dir,
+            } => {
+                let Some(from) = nodes[anchor] else {
+                    return Ok(());
+                };
+                let fixed = nodes[other];
+                for (neighbor, edge_id) in self.edges_from(from, dir, &self.edges[edge].types)? {
+                    match fixed {
+                        Some(target) if target != neighbor => continue,
+                        Some(_) => {}
+                        None if self.candidates[other].binary_search(&neighbor).is_err() => {
+                            continue
+                        }
+                        None => nodes[other] = Some(neighbor),
+                    }
+                    if edges.contains(&Some(edge_id)) {
+                        continue;
+                    }
+                    edges[edge] = Some(edge_id);
+                    self.search(depth + 1, nodes, edges, out)?;
+                }
+                edges[edge] = None;
+                nodes[other] = fixed;
+            }
+        }
+        Ok(())
+    }
+
+    /// Lists `(neighbor, edge)` pairs reachable from `node`, one per edge.
+    fn edges_from(
+        &self,
+        node: NodeId,
+        dir: Dir,
+        types: &Option<Vec<TypeId>>,
+    ) -> Result<Vec<(NodeId, EdgeId)>> {
+        let opts = ExpandOpts {
+            distinct_nodes: false,
+        };
+        let mut pairs = Vec::new();
+        match types {
+            None => {
+                let cursor = self.graph.neighbors(self.read, node, dir, None, opts)?;
+                pairs.extend(cursor.map(|entry| (entry.neighbor, entry.edge)));
+            }
+            Some(types) => {
+                for ty in types {
+                    let cursor = self
+                        .graph
+                        .neighbors(self.read, node, dir, Some(*ty), opts)?;
+                    pairs.extend(cursor.map(|entry| (entry.neighbor, entry.edge)));
+                }
+            }
+        }
+        // A self-loop shows up once per direction when walking both ways.
+        pairs.sort_unstable_by_key(|(neighbor, edge)| (neighbor.0, edge.0));
+        pairs.dedup();
+        Ok(pairs)
+    }
+}
+
 /// Literal value emitted by bindings before semantic validation.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 #[serde(tag = "t", content = "v")]
 pub enum PayloadValue {
     /// Null literal.
@@ -2216,10 +5051,22 @@ pub enum PayloadValue {
     Float(f64),
     /// UTF-8 string literal.
     String(String),
-    /// Base64-encoded bytes literal (decoded later).
-    Bytes(String),
+    /// Bytes literal, either base64-encoded or as an array of octets.
+    Bytes(BytesPayload),
     /// Nanoseconds since Unix epoch (UTC).
     DateTime(i128),
+    /// Named parameter bound when a [`PreparedQuery`] executes.
+    Param(String),
+}
+
+/// Bytes literal accepted in either [`BytesFormat`].
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum BytesPayload {
+    /// Base64-encoded string (decoded later).
+    Base64(String),
+    /// Raw octets as an array of numbers.
+    Array(Vec<u8>),
 }
 
 impl PayloadValue {
@@ -2230,19 +5077,25 @@ impl PayloadValue {
             PayloadValue::Int(v) => QueryValue::Int(v),
             PayloadValue::Float(v) => QueryValue::Float(v),
             PayloadValue::String(v) => QueryValue::String(v),
-            PayloadValue::Bytes(raw) => {
+            PayloadValue::Bytes(BytesPayload::Base64(raw)) => {
                 let decoded = BASE64
                     .decode(raw.as_bytes())
                     .map_err(|_| AnalyzerError::BytesEncoding)?;
                 QueryValue::Bytes(decoded)
             }
+            PayloadValue::Bytes(BytesPayload::Array(raw)) => QueryValue::Bytes(raw),
             PayloadValue::DateTime(v) => QueryValue::DateTime(v),
+            PayloadValue::Param(name) => {
+                return Err(FfiError::Message(format!(
+                    "parameter '{name}' is only valid in prepared queries"
+                )))
+            }
         })
     }
 }
 
 /// Boolean predicate specification emitted by bindings.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 #[serde(tag = "op", rename_all = "lowercase")]
 pub enum PredicateSpec {
     /// Logical conjunction.
@@ -2348,6 +5201,19 @@ pub enum PredicateSpec {
         /// Literal set to test membership against.
         values: Vec<PayloadValue>,
     },
+    /// String prefix comparison.
+    #[serde(rename = "prefix")]
+    Prefix {
+        /// Variable binding referenced by the predicate.
+        var: String,
+        /// Property name being compared.
+        prop: String,
+        /// Prefix the string value must start with.
+        prefix: String,
+        /// Requests case-insensitive matching, which is not supported yet.
+        #[serde(default, rename = "caseInsensitive", alias = "case_insensitive")]
+        case_insensitive: bool,
+    },
     /// Property existence test.
     #[serde(rename = "exists")]
     Exists {
@@ -2374,6 +5240,50 @@ pub enum PredicateSpec {
         /// Property name being inspected.
         prop: String,
     },
+    /// Correlated subquery: some neighbor over the variable's edges matches.
+    #[serde(rename = "edgeexists")]
+    #[serde(alias = "edgeExists")]
+    EdgeExists {
+        /// Variable binding whose edges are followed.
+        #[serde(rename = "fromVar", alias = "from_var")]
+        from_var: String,
+        /// Optional edge type filter.
+        #[serde(default, rename = "edgeType", alias = "edge_type")]
+        edge_type: Option<String>,
+        /// Direction of the edges to follow (defaults to outgoing).
+        #[serde(default = "DirectionSpec::default_out")]
+        direction: DirectionSpec,
+        /// Binding for the neighbor, visible only inside `where`.
+        target: MatchSpec,
+        /// Optional predicate the neighbor must satisfy.
+        #[serde(default, rename = "where")]
+        predicate: Option<Box<PredicateSpec>>,
+    },
+}
+
+/// Prefix of the string literal standing in for a parameter while a
+/// [`PreparedQuery`] is planned; the NUL byte keeps it apart from user text.
+const PARAM_PLACEHOLDER: &str = "\0sombra:param:";
+
+/// Swaps a `Param` literal for its placeholder, recording the parameter and
+/// the comparison (if any) that has to order its value.
+fn replace_param(
+    value: &mut PayloadValue,
+    ordered_by: Option<&'static str>,
+    params: &mut BTreeMap<String, Option<&'static str>>,
+) -> Result<()> {
+    let PayloadValue::Param(name) = value else {
+        return Ok(());
+    };
+    if name.is_empty() {
+        return Err(FfiError::Message(
+            "parameter names must be non-empty".into(),
+        ));
+    }
+    let entry = params.entry(name.clone()).or_default();
+    *entry = entry.or(ordered_by);
+    *value = PayloadValue::String(format!("{PARAM_PLACEHOLDER}{name}"));
+    Ok(())
 }
 
 fn validate_scalar_value(value: &QueryValue) -> Result<()> {
@@ -2511,25 +5421,155 @@ fn validate_range_bounds(
     Ok(())
 }
 
+/// Predicate tree keyed by its connective instead of an `op` tag.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum FilterSpec {
+    /// Logical conjunction.
+    And {
+        /// Child filters.
+        and: Vec<FilterSpec>,
+    },
+    /// Logical disjunction.
+    Or {
+        /// Child filters.
+        or: Vec<FilterSpec>,
+    },
+    /// Logical negation.
+    Not {
+        /// Negated filter.
+        not: Box<FilterSpec>,
+    },
+    /// Comparison leaf (or any other [`PredicateSpec`]).
+    Leaf(PredicateSpec),
+}
+
+impl FilterSpec {
+    fn into_predicate(self) -> PredicateSpec {
+        let args = |children: Vec<FilterSpec>| {
+            children
+                .into_iter()
+                .map(FilterSpec::into_predicate)
+                .collect()
+        };
+        match self {
+            FilterSpec::And { and } => PredicateSpec::And { args: args(and) },
+            FilterSpec::Or { or } => PredicateSpec::Or { args: args(or) },
+            FilterSpec::Not { not } => PredicateSpec::Not {
+                args: vec![not.into_predicate()],
+            },
+            FilterSpec::Leaf(predicate) => predicate,
+        }
+    }
+}
+
 impl PredicateSpec {
-    fn into_expr(self) -> Result<BoolExpr> {
+    /// Replaces every parameter in the tree with its placeholder.
+    fn replace_params(
+        &mut self,
+        params: &mut BTreeMap<String, Option<&'static str>>,
+    ) -> Result<()> {
         match self {
-            PredicateSpec::And { args } => Ok(BoolExpr::And(into_expr_vec(args)?)),
-            PredicateSpec::Or { args } => Ok(BoolExpr::Or(into_expr_vec(args)?)),
-            PredicateSpec::Not { args } => {
-                if args.len() != 1 {
-                    return Err(FfiError::Message(
-                        "not() requires exactly one argument".into(),
-                    ));
+            PredicateSpec::And { args }
+            | PredicateSpec::Or { args }
+            | PredicateSpec::Not { args } => {
+                for arg in args {
+                    arg.replace_params(params)?;
                 }
-                let inner = args.into_iter().next().unwrap().into_expr()?;
-                Ok(BoolExpr::Not(Box::new(inner)))
             }
-            PredicateSpec::Eq { var, prop, value } => {
-                let value = value.into_value()?;
-                validate_scalar_value(&value)?;
-                Ok(BoolExpr::Cmp(Comparison::Eq {
-                    var: into_var(var)?,
+            PredicateSpec::Eq { value, .. } | PredicateSpec::Ne { value, .. } => {
+                replace_param(value, None, params)?;
+            }
+            PredicateSpec::Lt { value, .. } => replace_param(value, Some("lt()"), params)?,
+            PredicateSpec::Le { value, .. } => replace_param(value, Some("le()"), params)?,
+            PredicateSpec::Gt { value, .. } => replace_param(value, Some("gt()"), params)?,
+            PredicateSpec::Ge { value, .. } => replace_param(value, Some("ge()"), params)?,
+            PredicateSpec::Between {
+                var,
+                prop,
+                low,
+                high,
+                inclusive,
+            } if [&*low, &*high]
+                .iter()
+                .any(|bound| matches!(bound, PayloadValue::Param(_))) =>
+            {
+                // Bound order is only known once the parameters are bound, so
+                // compare against each bound separately.
+                let [low_inclusive, high_inclusive] = inclusive.unwrap_or([true, true]);
+                let (var, prop) = (mem::take(var), mem::take(prop));
+                let (low, high) = (low.clone(), high.clone());
+                let lower = if low_inclusive {
+                    PredicateSpec::Ge {
+                        var: var.clone(),
+                        prop: prop.clone(),
+                        value: low,
+                    }
+                } else {
+                    PredicateSpec::Gt {
+                        var: var.clone(),
+                        prop: prop.clone(),
+                        value: low,
+                    }
+                };
+                let upper = if high_inclusive {
+                    PredicateSpec::Le {
+                        var,
+                        prop,
+                        value: high,
+                    }
+                } else {
+                    PredicateSpec::Lt {
+                        var,
+                        prop,
+                        value: high,
+                    }
+                };
+                *self = PredicateSpec::And {
+                    args: vec![lower, upper],
+                };
+                self.replace_params(params)?;
+            }
+            PredicateSpec::Between { .. } => {}
+            PredicateSpec::In { values, .. } => {
+                if values
+                    .iter()
+                    .any(|value| matches!(value, PayloadValue::Param(_)))
+                {
+                    return Err(FfiError::Message("in() does not accept parameters".into()));
+                }
+            }
+            PredicateSpec::Prefix { .. }
+            | PredicateSpec::Exists { .. }
+            | PredicateSpec::IsNull { .. }
+            | PredicateSpec::IsNotNull { .. } => {}
+            PredicateSpec::EdgeExists { predicate, .. } => {
+                if let Some(predicate) = predicate {
+                    predicate.replace_params(params)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn into_expr(self) -> Result<BoolExpr> {
+        match self {
+            PredicateSpec::And { args } => Ok(BoolExpr::And(into_expr_vec(args)?)),
+            PredicateSpec::Or { args } => Ok(BoolExpr::Or(into_expr_vec(args)?)),
+            PredicateSpec::Not { args } => {
+                if args.len() != 1 {
+                    return Err(FfiError::Message(
+                        "not() requires exactly one argument".into(),
+                    ));
+                }
+                let inner = args.into_iter().next().unwrap().into_expr()?;
+                Ok(BoolExpr::Not(Box::new(inner)))
+            }
+            PredicateSpec::Eq { var, prop, value } => {
+                let value = value.into_value()?;
+                validate_scalar_value(&value)?;
+                Ok(BoolExpr::Cmp(Comparison::Eq {
+                    var: into_var(var)?,
                     prop: into_prop(prop)?,
                     value,
                 }))
@@ -2613,6 +5653,23 @@ impl PredicateSpec {
                     values: literals,
                 }))
             }
+            PredicateSpec::Prefix {
+                var,
+                prop,
+                prefix,
+                case_insensitive,
+            } => {
+                if case_insensitive {
+                    return Err(FfiError::Message(
+                        "prefix() does not support case-insensitive matching yet".into(),
+                    ));
+                }
+                Ok(BoolExpr::Cmp(Comparison::Prefix {
+                    var: into_var(var)?,
+                    prop: into_prop(prop)?,
+                    prefix,
+                }))
+            }
             PredicateSpec::Exists { var, prop } => Ok(BoolExpr::Cmp(Comparison::Exists {
                 var: into_var(var)?,
                 prop: into_prop(prop)?,
@@ -2625,6 +5682,22 @@ impl PredicateSpec {
                 var: into_var(var)?,
                 prop: into_prop(prop)?,
             })),
+            PredicateSpec::EdgeExists {
+                from_var,
+                edge_type,
+                direction,
+                target,
+                predicate,
+            } => Ok(BoolExpr::Cmp(Comparison::EdgeExists {
+                var: into_var(from_var)?,
+                edge_type,
+                direction: direction.into_direction()?,
+                target: target.into_clause()?,
+                predicate: match predicate {
+                    Some(inner) => Some(Box::new(inner.into_expr()?)),
+                    None => None,
+                },
+            })),
         }
     }
 }
@@ -2733,7 +5806,7 @@ fn between_bounds(
 }
 
 /// Result column projection specification.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 #[serde(tag = "kind", rename_all = "lowercase")]
 pub enum ProjectionSpec {
     /// Project a variable with optional alias.
@@ -2754,6 +5827,48 @@ pub enum ProjectionSpec {
         #[serde(default)]
         alias: Option<String>,
     },
+    /// Project the cumulative number of rows streamed so far.
+    #[serde(rename = "running_count")]
+    RunningCount {
+        /// Optional column alias.
+        #[serde(default)]
+        alias: Option<String>,
+    },
+    /// Project the node ids along the edge chain joining two variables.
+    Path {
+        /// Variable the path starts at.
+        #[serde(alias = "fromVar")]
+        from_var: String,
+        /// Variable the path ends at.
+        #[serde(alias = "toVar")]
+        to_var: String,
+        /// Optional column alias.
+        #[serde(default)]
+        alias: Option<String>,
+    },
+    /// Project a value computed from the row's properties, such as
+    /// `age * 2` or `u.first + ' ' + u.last`.
+    ///
+    /// See [`crate::query::expr`] for the grammar and evaluation rules.
+    Expr {
+        /// Expression source.
+        expr: String,
+        /// Optional column alias; defaults to the expression source.
+        #[serde(default)]
+        alias: Option<String>,
+    },
+    /// Fold every matched row into one row holding the aggregate.
+    Aggregate {
+        /// Aggregate function: `count`, `sum`, `avg`, `min`, or `max`.
+        func: String,
+        /// Variable the aggregate reads from.
+        var: String,
+        /// Property to aggregate; omit with `count` to count bindings.
+        #[serde(default)]
+        prop: Option<String>,
+        /// Column name for the aggregate.
+        alias: String,
+    },
 }
 
 impl ProjectionSpec {
@@ -2787,7 +5902,125 @@ impl ProjectionSpec {
                     alias,
                 })
             }
+            ProjectionSpec::RunningCount { alias } => Ok(Projection::RunningCount { alias }),
+            ProjectionSpec::Path {
+                from_var,
+                to_var,
+                alias,
+            } => {
+                if from_var.trim().is_empty() || to_var.trim().is_empty() {
+                    return Err(FfiError::Message(
+                        "path projection variables cannot be empty".into(),
+                    ));
+                }
+                Ok(Projection::Path {
+                    from_var: Var(from_var),
+                    to_var: Var(to_var),
+                    alias,
+                })
+            }
+            ProjectionSpec::Expr { expr, alias } => {
+                if expr.trim().is_empty() {
+                    return Err(FfiError::Message(
+                        "expression projection cannot be empty".into(),
+                    ));
+                }
+                Ok(Projection::Expr { expr, alias })
+            }
+            ProjectionSpec::Aggregate {
+                func,
+                var,
+                prop,
+                alias,
+            } => {
+                let func = match func.as_str() {
+                    "count" => AggFunc::Count,
+                    "sum" => AggFunc::Sum,
+                    "avg" => AggFunc::Avg,
+                    "min" => AggFunc::Min,
+                    "max" => AggFunc::Max,
+                    other => {
+                        return Err(FfiError::Message(format!(
+                            "unknown aggregate function '{other}'"
+                        )))
+                    }
+                };
+                if var.trim().is_empty() {
+                    return Err(FfiError::Message(
+                        "aggregate projection variable cannot be empty".into(),
+                    ));
+                }
+                Ok(Projection::Aggregate {
+                    func,
+                    var: Var(var),
+                    prop,
+                    alias,
+                })
+            }
+        }
+    }
+}
+
+/// Sort key in a query's `orderBy` list.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderSpec {
+    /// Variable whose property is compared.
+    pub var: String,
+    /// Property to sort by.
+    pub prop: String,
+    /// Sorts from largest to smallest.
+    #[serde(default)]
+    pub desc: bool,
+}
+
+impl OrderSpec {
+    fn into_key(self) -> Result<OrderKey> {
+        if self.var.trim().is_empty() || self.prop.trim().is_empty() {
+            return Err(FfiError::Message(
+                "orderBy entries require a variable and property".into(),
+            ));
         }
+        Ok(OrderKey {
+            target: OrderTarget::Prop {
+                var: Var(self.var),
+                prop: self.prop,
+            },
+            descending: self.desc,
+        })
+    }
+}
+
+/// Top-N-per-group cap in a query's `limitPerGroup` field.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LimitPerGroupSpec {
+    /// Variable whose bound node identifies a row's group.
+    #[serde(alias = "group_by")]
+    pub group_by: String,
+    /// Maximum number of rows kept per group.
+    pub n: u64,
+    /// Ranks rows within a group; without keys the first rows seen win.
+    #[serde(default, alias = "order_by")]
+    pub order_by: Vec<OrderSpec>,
+}
+
+impl LimitPerGroupSpec {
+    fn into_group_limit(self) -> Result<GroupLimit> {
+        if self.group_by.trim().is_empty() {
+            return Err(FfiError::Message(
+                "limitPerGroup requires a groupBy variable".into(),
+            ));
+        }
+        Ok(GroupLimit {
+            group_by: Var(self.group_by),
+            n: self.n,
+            order_by: self
+                .order_by
+                .into_iter()
+                .map(OrderSpec::into_key)
+                .collect::<Result<Vec<_>>>()?,
+        })
     }
 }
 
@@ -2823,6 +6056,16 @@ pub enum MutationOp {
         #[serde(default)]
         unset: Vec<String>,
     },
+    /// Apply an RFC 6902 JSON Patch to an existing node's properties.
+    ///
+    /// Operations run in order; `replace` and `remove` fail when the property
+    /// is absent at that point in the patch.
+    PatchNode {
+        /// Node ID to patch.
+        id: u64,
+        /// Patch operations addressing top-level property keys.
+        patch: Vec<JsonPatchOp>,
+    },
     /// Delete an existing node.
     DeleteNode {
         /// Node ID to delete.
@@ -2861,6 +6104,33 @@ pub enum MutationOp {
     },
 }
 
+/// Single RFC 6902 JSON Patch operation on a node property.
+///
+/// `path` is a JSON Pointer naming one top-level property, e.g. `/name`.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "op", rename_all = "camelCase")]
+pub enum JsonPatchOp {
+    /// Sets the property, creating it if absent.
+    Add {
+        /// Pointer to the property.
+        path: String,
+        /// New property value.
+        value: Value,
+    },
+    /// Removes an existing property.
+    Remove {
+        /// Pointer to the property.
+        path: String,
+    },
+    /// Overwrites an existing property.
+    Replace {
+        /// Pointer to the property.
+        path: String,
+        /// New property value.
+        value: Value,
+    },
+}
+
 /// Specification for batch node and edge creation with aliasing support.
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -2871,6 +6141,10 @@ pub struct CreateScript {
     /// Edges to create.
     #[serde(default)]
     pub edges: Vec<CreateEdgeSpec>,
+    /// Skip index maintenance while creating and rebuild the touched
+    /// labels' indexes once at the end. See [`CreateBuilder::defer_indexes`].
+    #[serde(default)]
+    pub defer_indexes: bool,
 }
 
 /// Specification for creating a node in a create script.
@@ -2941,112 +6215,518 @@ pub struct MutationSummary {
     pub deleted_edges: u64,
 }
 
-fn rows_to_values(result: &QueryResult) -> Result<Vec<Value>> {
-    result
-        .rows
-        .iter()
-        .map(row_to_value)
-        .collect::<Result<Vec<_>>>()
+/// Specification for reconciling nodes by key property in one transaction.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncSpec {
+    /// Nodes to merge into existing matches or create when missing.
+    #[serde(default)]
+    pub upserts: Vec<SyncUpsertSpec>,
+    /// Nodes to delete, identified by key property.
+    #[serde(default)]
+    pub deletes: Vec<SyncDeleteSpec>,
+    /// Edges to merge into existing matches or create when missing.
+    #[serde(default)]
+    pub edge_upserts: Vec<SyncEdgeUpsertSpec>,
+    /// Edges to delete, identified by their keyed endpoints.
+    #[serde(default)]
+    pub edge_deletes: Vec<SyncEdgeDeleteSpec>,
 }
 
-fn execution_payload(request_id: Option<String>, rows: Vec<Value>) -> Value {
-    let mut map = Map::new();
-    map.insert(
-        "request_id".into(),
-        request_id.map(Value::String).unwrap_or(Value::Null),
-    );
-    map.insert("features".into(), Value::Array(Vec::new()));
-    map.insert("rows".into(), Value::Array(rows));
-    Value::Object(map)
+/// Keyed node upsert within a sync specification.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncUpsertSpec {
+    /// Label identifying the node set the key is unique within.
+    pub label: String,
+    /// Name of the property used to match existing nodes; must appear in `props`.
+    pub key: String,
+    /// Properties to merge into the matched node or to create it with.
+    #[serde(default)]
+    pub props: Map<String, Value>,
 }
 
-fn row_to_value(row: &Row) -> Result<Value> {
-    let mut map = Map::new();
-    for (key, value) in row {
-        map.insert(key.clone(), exec_value_to_json(value)?);
-    }
-    Ok(Value::Object(map))
+/// Keyed node deletion within a sync specification.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncDeleteSpec {
+    /// Label identifying the node set the key is unique within.
+    pub label: String,
+    /// Name of the key property.
+    pub key: String,
+    /// Key value of the node to delete.
+    pub value: Value,
+    /// If true, cascade delete connected edges.
+    #[serde(default)]
+    pub cascade: bool,
 }
 
-fn exec_value_to_json(value: &ExecValue) -> Result<Value> {
-    Ok(match value {
-        ExecValue::Null => Value::Null,
-        ExecValue::Bool(v) => Value::Bool(*v),
-        ExecValue::Int(v) => Value::Number((*v).into()),
-        ExecValue::Float(v) => serde_json::Number::from_f64(*v)
-            .map(Value::Number)
-            .ok_or_else(|| FfiError::Message("float value not representable in JSON".into()))?,
-        ExecValue::String(v) => Value::String(v.clone()),
-        ExecValue::Bytes(bytes) => Value::String(BASE64.encode(bytes)),
-        ExecValue::Date(v) => Value::Number((*v).into()),
-        ExecValue::DateTime(v) => Value::Number((*v).into()),
-        ExecValue::Object(map) => {
-            let mut obj = Map::new();
-            for (key, value) in map {
-                obj.insert(key.clone(), exec_value_to_json(value)?);
-            }
-            Value::Object(obj)
-        }
-        ExecValue::NodeId(node) => Value::Number(node.0.into()),
-    })
+/// Node identified by a key property within a sync specification.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncNodeRef {
+    /// Label identifying the node set the key is unique within.
+    pub label: String,
+    /// Name of the key property.
+    pub key: String,
+    /// Key value of the node.
+    pub value: Value,
 }
 
-fn value_to_prop_value(value: &Value) -> Result<PropValueOwned> {
-    match value {
-        Value::Null => Ok(PropValueOwned::Null),
-        Value::Bool(v) => Ok(PropValueOwned::Bool(*v)),
-        Value::Number(num) => {
-            if let Some(i) = num.as_i64() {
-                return Ok(PropValueOwned::Int(i));
-            }
-            if let Some(u) = num.as_u64() {
-                if u <= i64::MAX as u64 {
-                    return Ok(PropValueOwned::Int(u as i64));
-                }
-            }
-            if let Some(f) = num.as_f64() {
-                return Ok(PropValueOwned::Float(f));
-            }
-            Err(FfiError::Message("numeric literal out of range".into()))
-        }
-        Value::String(s) => Ok(PropValueOwned::Str(s.clone())),
-        _ => Err(FfiError::Message(
-            "only bool/int/float/string/null property literals are supported".into(),
-        )),
-    }
+/// Keyed edge upsert within a sync specification.
+///
+/// An edge matches when it has type `ty` and runs from `src` to `dst`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncEdgeUpsertSpec {
+    /// Edge type name.
+    pub ty: String,
+    /// Source node; must already exist or be created earlier in the sync.
+    pub src: SyncNodeRef,
+    /// Destination node; must already exist or be created earlier in the sync.
+    pub dst: SyncNodeRef,
+    /// Properties to merge into the matched edge or to create it with.
+    #[serde(default)]
+    pub props: Map<String, Value>,
 }
 
-fn prop_value_ref(value: &PropValueOwned) -> PropValue<'_> {
-    match value {
-        PropValueOwned::Null => PropValue::Null,
-        PropValueOwned::Bool(v) => PropValue::Bool(*v),
-        PropValueOwned::Int(v) => PropValue::Int(*v),
-        PropValueOwned::Float(v) => PropValue::Float(*v),
-        PropValueOwned::Str(v) => PropValue::Str(v.as_str()),
-        PropValueOwned::Bytes(v) => PropValue::Bytes(v.as_slice()),
-        PropValueOwned::Date(v) => PropValue::Date(*v),
-        PropValueOwned::DateTime(v) => PropValue::DateTime(*v),
-    }
+/// Keyed edge deletion within a sync specification.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncEdgeDeleteSpec {
+    /// Edge type name.
+    pub ty: String,
+    /// Source node.
+    pub src: SyncNodeRef,
+    /// Destination node.
+    pub dst: SyncNodeRef,
 }
 
-fn prop_value_owned_to_json(value: &PropValueOwned) -> Result<Value> {
-    Ok(match value {
-        PropValueOwned::Null => Value::Null,
-        PropValueOwned::Bool(v) => Value::Bool(*v),
-        PropValueOwned::Int(v) => Value::Number((*v).into()),
-        PropValueOwned::Float(v) => serde_json::Number::from_f64(*v)
-            .map(Value::Number)
-            .ok_or_else(|| FfiError::Message("float value not representable in JSON".into()))?,
-        PropValueOwned::Str(v) => Value::String(v.clone()),
-        PropValueOwned::Bytes(v) => Value::String(BASE64.encode(v)),
-        PropValueOwned::Date(v) => Value::Number((*v).into()),
-        PropValueOwned::DateTime(v) => Value::Number((*v).into()),
-    })
+/// Counts of the actions applied by a sync.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncSummary {
+    /// Number of nodes created because no key match existed.
+    pub created: u64,
+    /// Number of existing nodes merged with upsert properties.
+    pub updated: u64,
+    /// Number of nodes deleted.
+    pub deleted: u64,
+    /// Number of edges created because no match existed.
+    pub edges_created: u64,
+    /// Number of existing edges merged with upsert properties.
+    pub edges_updated: u64,
+    /// Number of edges deleted.
+    pub edges_deleted: u64,
 }
 
-fn explain_payload(
-    request_id: Option<String>,
-    plan_hash: u64,
+/// Key indexes for one sync, read through its write transaction and kept
+/// current as the sync changes nodes.
+#[derive(Default)]
+struct SyncKeys {
+    /// Indexes by `(label, key)` name, loaded on first use.
+    slots: HashMap<(String, String), SyncKeyIndex>,
+}
+
+/// Nodes of one label grouped by the normalized value of one key property.
+#[derive(Default)]
+struct SyncKeyIndex {
+    label: Option<LabelId>,
+    prop: Option<PropId>,
+    nodes: HashMap<String, Vec<NodeId>>,
+    key_of: HashMap<NodeId, String>,
+}
+
+impl SyncKeyIndex {
+    /// Files `node` under its current key, or drops it when `data` is gone or
+    /// no longer carries the label and key.
+    fn place(&mut self, node: NodeId, data: Option<&NodeData>) -> Result<()> {
+        if let Some(old) = self.key_of.remove(&node) {
+            if let Some(nodes) = self.nodes.get_mut(&old) {
+                nodes.retain(|id| *id != node);
+                if nodes.is_empty() {
+                    self.nodes.remove(&old);
+                }
+            }
+        }
+        let (Some(label), Some(prop), Some(data)) = (self.label, self.prop, data) else {
+            return Ok(());
+        };
+        if !data.labels.contains(&label) {
+            return Ok(());
+        }
+        let Some((_, value)) = data.props.iter().find(|(id, _)| *id == prop) else {
+            return Ok(());
+        };
+        let key = sync_key(&prop_value_owned_to_json(value)?)?;
+        self.nodes.entry(key.clone()).or_default().push(node);
+        self.key_of.insert(node, key);
+        Ok(())
+    }
+}
+
+/// Extracts the property name from a single-segment JSON Pointer.
+fn json_patch_prop(path: &str) -> Result<String> {
+    match path.strip_prefix('/') {
+        Some(key) if !key.is_empty() && !key.contains('/') => {
+            Ok(key.replace("~1", "/").replace("~0", "~"))
+        }
+        _ => Err(FfiError::Message(format!(
+            "patch path '{path}' must name a single top-level property"
+        ))),
+    }
+}
+
+/// Normalizes a key value so JSON input and stored properties compare equal.
+fn sync_key(value: &Value) -> Result<String> {
+    let normalized = prop_value_owned_to_json(&value_to_prop_value(value)?)?;
+    Ok(normalized.to_string())
+}
+
+fn rows_to_values(result: &QueryResult, bytes: BytesFormat) -> Result<Vec<Value>> {
+    result
+        .rows
+        .iter()
+        .map(|row| row_to_value(row, bytes))
+        .collect::<Result<Vec<_>>>()
+}
+
+/// Tallies result rows per node bound to `var`, keyed by node id.
+fn counts_per_start(result: &QueryResult, var: &str) -> Result<Value> {
+    let mut counts: BTreeMap<u64, u64> = BTreeMap::new();
+    for row in &result.rows {
+        let Some(ExecValue::NodeId(id)) = row.get(var) else {
+            return Err(FfiError::Message(format!(
+                "countPerStart row is missing a node for '{var}'"
+            )));
+        };
+        *counts.entry(id.0).or_default() += 1;
+    }
+    Ok(Value::Object(
+        counts
+            .into_iter()
+            .map(|(id, count)| (id.to_string(), Value::Number(count.into())))
+            .collect(),
+    ))
+}
+
+/// Serializes single-column rows as their bare column values.
+fn rows_to_flat_values(result: &QueryResult, bytes: BytesFormat) -> Result<Vec<Value>> {
+    result
+        .rows
+        .iter()
+        .map(|row| match row.values().next() {
+            Some(value) => exec_value_to_json(value, bytes),
+            None => Ok(Value::Null),
+        })
+        .collect::<Result<Vec<_>>>()
+}
+
+/// Groups `rows` by their parent columns, gathering `child_columns` into an
+/// array stored under `key`.
+///
+/// `parents` lists every parent in output order; those no row mentions get an
+/// empty array. A single child column is collected as its bare value.
+fn nest_rows(
+    rows: Vec<Value>,
+    parents: Vec<Value>,
+    child_columns: &HashSet<String>,
+    key: &str,
+) -> Vec<Value> {
+    let mut groups: Vec<(Map<String, Value>, Vec<Value>)> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    let mut group_of = |parent: Map<String, Value>,
+                        groups: &mut Vec<(Map<String, Value>, Vec<Value>)>| {
+        let id = Value::Object(parent.clone()).to_string();
+        *index.entry(id).or_insert_with(|| {
+            groups.push((parent, Vec::new()));
+            groups.len() - 1
+        })
+    };
+    for parent in parents {
+        if let Value::Object(parent) = parent {
+            group_of(parent, &mut groups);
+        }
+    }
+    for row in rows {
+        let Value::Object(row) = row else {
+            continue;
+        };
+        let (child, parent): (Map<String, Value>, Map<String, Value>) = row
+            .into_iter()
+            .partition(|(column, _)| child_columns.contains(column));
+        let slot = group_of(parent, &mut groups);
+        let child = if child.len() == 1 {
+            child
+                .into_iter()
+                .next()
+                .map(|(_, value)| value)
+                .unwrap_or(Value::Null)
+        } else {
+            Value::Object(child)
+        };
+        groups[slot].1.push(child);
+    }
+    groups
+        .into_iter()
+        .map(|(mut parent, children)| {
+            parent.insert(key.to_string(), Value::Array(children));
+            Value::Object(parent)
+        })
+        .collect()
+}
+
+/// Counts the columns each row of `plan` carries.
+///
+/// Unprojected plans key rows by every distinct match variable, so
+/// `match_vars` supplies that count.
+fn result_column_count(plan: &PhysicalPlan, match_vars: usize) -> usize {
+    match &plan.root.op {
+        PhysicalOp::Project { fields } => fields.len(),
+        _ => match_vars,
+    }
+}
+
+fn execution_payload(
+    request_id: Option<String>,
+    columns: Option<Vec<ColumnSchema>>,
+    rows: Vec<Value>,
+    truncated: Option<bool>,
+    warnings: Option<Vec<ValidationIssue>>,
+) -> Value {
+    let mut map = Map::new();
+    map.insert(
+        "request_id".into(),
+        request_id.map(Value::String).unwrap_or(Value::Null),
+    );
+    map.insert("features".into(), Value::Array(Vec::new()));
+    if let Some(columns) = columns {
+        let columns = columns
+            .into_iter()
+            .map(|column| {
+                let mut entry = Map::new();
+                entry.insert("name".into(), Value::String(column.name));
+                entry.insert("type".into(), Value::String(column.ty.into()));
+                Value::Object(entry)
+            })
+            .collect();
+        map.insert("columns".into(), Value::Array(columns));
+    }
+    map.insert("rows".into(), Value::Array(rows));
+    if let Some(truncated) = truncated {
+        map.insert("truncated".into(), Value::Bool(truncated));
+    }
+    if let Some(warnings) = warnings {
+        let warnings = warnings
+            .into_iter()
+            .map(|warning| {
+                let mut entry = Map::new();
+                entry.insert("code".into(), Value::String(warning.code));
+                entry.insert("message".into(), Value::String(warning.message));
+                Value::Object(entry)
+            })
+            .collect();
+        map.insert("warnings".into(), Value::Array(warnings));
+    }
+    Value::Object(map)
+}
+
+/// Reports each filter on the driving scan that no property index serves.
+fn unindexed_warnings(plan: &PlannerOutput) -> Vec<ValidationIssue> {
+    plan.unindexed
+        .iter()
+        .map(|pred| {
+            let comparison = if pred.equality { "equality" } else { "range" };
+            let (label, prop) = (&pred.label, &pred.prop);
+            ValidationIssue {
+                code: "UnindexedPredicate".into(),
+                message: format!(
+                    "{comparison} predicate on {label}.{prop} scans every {label} node; \
+                     no property index covers it"
+                ),
+            }
+        })
+        .collect()
+}
+
+/// Largest integer magnitude an IEEE double represents exactly.
+const MAX_EXACT_DOUBLE_INT: i64 = 1 << 53;
+
+/// Collects the warnings [`QuerySpec::include_warnings`] reports for a run.
+fn execution_warnings(
+    plan: &PlannerOutput,
+    result: &QueryResult,
+    max_rows: Option<usize>,
+) -> Vec<ValidationIssue> {
+    let mut warnings = unindexed_warnings(plan);
+    if let (Some(max_rows), true) = (max_rows, result.limited) {
+        warnings.push(ValidationIssue {
+            code: "Truncated".into(),
+            message: format!("result stopped at maxRows ({max_rows}); more rows matched"),
+        });
+    }
+    let imprecise: usize = result
+        .rows
+        .iter()
+        .flat_map(|row| row.values())
+        .map(imprecise_ints)
+        .sum();
+    if imprecise > 0 {
+        warnings.push(ValidationIssue {
+            code: "PrecisionLoss".into(),
+            message: format!(
+                "{imprecise} integer value(s) exceed 2^53 and lose precision \
+                 when read as a double"
+            ),
+        });
+    }
+    warnings
+}
+
+/// Counts integers in `value` that a double cannot hold exactly.
+fn imprecise_ints(value: &ExecValue) -> usize {
+    match value {
+        ExecValue::Int(v) => usize::from(v.unsigned_abs() > MAX_EXACT_DOUBLE_INT as u64),
+        ExecValue::Object(map) => map.values().map(imprecise_ints).sum(),
+        ExecValue::List(items) => items.iter().map(imprecise_ints).sum(),
+        _ => 0,
+    }
+}
+
+fn type_tag_name(tag: TypeTag) -> &'static str {
+    match tag {
+        TypeTag::Null => "null",
+        TypeTag::Bool => "bool",
+        TypeTag::Int => "int",
+        TypeTag::Float => "float",
+        TypeTag::String => "string",
+        TypeTag::Bytes => "bytes",
+        TypeTag::Date => "date",
+        TypeTag::DateTime => "datetime",
+    }
+}
+
+fn row_to_value(row: &Row, bytes: BytesFormat) -> Result<Value> {
+    let mut map = Map::new();
+    for (key, value) in row {
+        map.insert(key.clone(), exec_value_to_json(value, bytes)?);
+    }
+    Ok(Value::Object(map))
+}
+
+fn bytes_to_json(raw: &[u8], format: BytesFormat) -> Value {
+    match format {
+        BytesFormat::Base64 => Value::String(BASE64.encode(raw)),
+        BytesFormat::Array => Value::Array(raw.iter().map(|byte| Value::from(*byte)).collect()),
+    }
+}
+
+fn exec_value_to_json(value: &ExecValue, bytes: BytesFormat) -> Result<Value> {
+    Ok(match value {
+        ExecValue::Null => Value::Null,
+        ExecValue::Bool(v) => Value::Bool(*v),
+        ExecValue::Int(v) => Value::Number((*v).into()),
+        ExecValue::Float(v) => serde_json::Number::from_f64(*v)
+            .map(Value::Number)
+            .ok_or_else(|| FfiError::Message("float value not representable in JSON".into()))?,
+        ExecValue::String(v) => Value::String(v.clone()),
+        ExecValue::Bytes(raw) => bytes_to_json(raw, bytes),
+        ExecValue::Date(v) => Value::Number((*v).into()),
+        ExecValue::DateTime(v) => Value::Number((*v).into()),
+        ExecValue::Object(map) => {
+            let mut obj = Map::new();
+            for (key, value) in map {
+                obj.insert(key.clone(), exec_value_to_json(value, bytes)?);
+            }
+            Value::Object(obj)
+        }
+        ExecValue::List(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| exec_value_to_json(item, bytes))
+                .collect::<Result<Vec<_>>>()?,
+        ),
+        ExecValue::NodeId(node) => Value::Number(node.0.into()),
+    })
+}
+
+fn value_to_prop_value(value: &Value) -> Result<PropValueOwned> {
+    match value {
+        Value::Null => Ok(PropValueOwned::Null),
+        Value::Bool(v) => Ok(PropValueOwned::Bool(*v)),
+        Value::Number(num) => {
+            if let Some(i) = num.as_i64() {
+                return Ok(PropValueOwned::Int(i));
+            }
+            if let Some(u) = num.as_u64() {
+                if u <= i64::MAX as u64 {
+                    return Ok(PropValueOwned::Int(u as i64));
+                }
+            }
+            if let Some(f) = num.as_f64() {
+                return Ok(PropValueOwned::Float(f));
+            }
+            Err(FfiError::Message("numeric literal out of range".into()))
+        }
+        Value::String(s) => Ok(PropValueOwned::Str(s.clone())),
+        _ => Err(FfiError::Message(
+            "only bool/int/float/string/null property literals are supported".into(),
+        )),
+    }
+}
+
+fn prop_value_ref(value: &PropValueOwned) -> PropValue<'_> {
+    match value {
+        PropValueOwned::Null => PropValue::Null,
+        PropValueOwned::Bool(v) => PropValue::Bool(*v),
+        PropValueOwned::Int(v) => PropValue::Int(*v),
+        PropValueOwned::Float(v) => PropValue::Float(*v),
+        PropValueOwned::Str(v) => PropValue::Str(v.as_str()),
+        PropValueOwned::Bytes(v) => PropValue::Bytes(v.as_slice()),
+        PropValueOwned::Date(v) => PropValue::Date(*v),
+        PropValueOwned::DateTime(v) => PropValue::DateTime(*v),
+    }
+}
+
+fn materialize_node_with(
+    id: NodeId,
+    node: NodeData,
+    mut resolve: impl FnMut(StrId) -> std::result::Result<String, SombraError>,
+) -> Result<NodeRecord> {
+    let mut labels = Vec::with_capacity(node.labels.len());
+    for label in node.labels {
+        labels.push(resolve(StrId(label.0)).map_err(FfiError::from)?);
+    }
+    let mut props = Map::new();
+    for (prop, value) in node.props {
+        let name = resolve(StrId(prop.0)).map_err(FfiError::from)?;
+        props.insert(name, prop_value_owned_to_json(&value)?);
+    }
+    Ok(NodeRecord {
+        id: id.0,
+        labels,
+        properties: props,
+    })
+}
+
+fn prop_value_owned_to_json(value: &PropValueOwned) -> Result<Value> {
+    Ok(match value {
+        PropValueOwned::Null => Value::Null,
+        PropValueOwned::Bool(v) => Value::Bool(*v),
+        PropValueOwned::Int(v) => Value::Number((*v).into()),
+        PropValueOwned::Float(v) => serde_json::Number::from_f64(*v)
+            .map(Value::Number)
+            .ok_or_else(|| FfiError::Message("float value not representable in JSON".into()))?,
+        PropValueOwned::Str(v) => Value::String(v.clone()),
+        PropValueOwned::Bytes(v) => Value::String(BASE64.encode(v)),
+        PropValueOwned::Date(v) => Value::Number((*v).into()),
+        PropValueOwned::DateTime(v) => Value::Number((*v).into()),
+    })
+}
+
+fn explain_payload(
+    request_id: Option<String>,
+    plan_hash: u64,
     explain: &PlanExplain,
     redact_literals: bool,
 ) -> Value {
@@ -3067,6 +6747,52 @@ fn explain_payload(
     Value::Object(root)
 }
 
+fn candidates_payload(
+    request_id: Option<String>,
+    plan_hash: u64,
+    candidates: &[ScanCandidate],
+) -> Value {
+    let mut root = Map::new();
+    root.insert(
+        "request_id".into(),
+        request_id.map(Value::String).unwrap_or(Value::Null),
+    );
+    root.insert(
+        "plan_hash".into(),
+        Value::String(format_plan_hash(plan_hash)),
+    );
+    let entries = candidates
+        .iter()
+        .map(|candidate| {
+            let mut map = Map::new();
+            map.insert("var".into(), Value::String(candidate.var.0.clone()));
+            map.insert(
+                "label".into(),
+                candidate
+                    .label
+                    .clone()
+                    .map(Value::String)
+                    .unwrap_or(Value::Null),
+            );
+            map.insert("op".into(), Value::String(candidate.op.to_string()));
+            map.insert(
+                "props".into(),
+                Value::Array(candidate.props.iter().cloned().map(Value::String).collect()),
+            );
+            map.insert(
+                "estimated_cost".into(),
+                Number::from_f64(candidate.estimated_cost)
+                    .map(Value::Number)
+                    .unwrap_or(Value::Null),
+            );
+            map.insert("selected".into(), Value::Bool(candidate.selected));
+            Value::Object(map)
+        })
+        .collect();
+    root.insert("candidates".into(), Value::Array(entries));
+    Value::Object(root)
+}
+
 fn explain_node_to_value(node: &ExplainNode, redact_literals: bool) -> Value {
     let mut map = Map::new();
     map.insert("op".into(), Value::String(node.op.clone()));
@@ -3095,6 +6821,62 @@ fn format_plan_hash(hash: u64) -> String {
     format!("0x{hash:016x}")
 }
 
+fn encode_page_token(plan_hash: u64, cursor: &PageCursor) -> String {
+    let after: Vec<Value> = cursor
+        .0
+        .iter()
+        .map(|value| match value {
+            None | Some(PropValueOwned::Null) => Value::Null,
+            Some(PropValueOwned::Bool(v)) => json!({ "bool": v }),
+            Some(PropValueOwned::Int(v)) => json!({ "int": v }),
+            // Bits keep NaN and signed zero exact through JSON.
+            Some(PropValueOwned::Float(v)) => json!({ "float": v.to_bits() }),
+            Some(PropValueOwned::Str(v)) => json!({ "str": v }),
+            Some(PropValueOwned::Bytes(v)) => json!({ "bytes": BASE64.encode(v) }),
+            Some(PropValueOwned::Date(v)) => json!({ "date": v }),
+            Some(PropValueOwned::DateTime(v)) => json!({ "datetime": v }),
+        })
+        .collect();
+    let token = json!({ "plan": format!("{plan_hash:016x}"), "after": after });
+    BASE64.encode(token.to_string())
+}
+
+fn decode_page_token(token: &str, plan_hash: u64) -> Result<PageCursor> {
+    let invalid = || FfiError::Message("invalid page token".into());
+    let raw = BASE64.decode(token).map_err(|_| invalid())?;
+    let token: Value = serde_json::from_slice(&raw).map_err(|_| invalid())?;
+    let hash = token["plan"].as_str().ok_or_else(invalid)?;
+    let hash = u64::from_str_radix(hash, 16).map_err(|_| invalid())?;
+    if hash != plan_hash {
+        return Err(FfiError::Message(
+            "page token was issued for a different query".into(),
+        ));
+    }
+    let after = token["after"].as_array().ok_or_else(invalid)?;
+    let values = after
+        .iter()
+        .map(|value| {
+            if value.is_null() {
+                return Some(None);
+            }
+            let (tag, inner) = value.as_object()?.iter().next()?;
+            let value = match tag.as_str() {
+                "bool" => PropValueOwned::Bool(inner.as_bool()?),
+                "int" => PropValueOwned::Int(inner.as_i64()?),
+                "float" => PropValueOwned::Float(f64::from_bits(inner.as_u64()?)),
+                "str" => PropValueOwned::Str(inner.as_str()?.to_owned()),
+                "bytes" => PropValueOwned::Bytes(BASE64.decode(inner.as_str()?).ok()?),
+                "date" => PropValueOwned::Date(inner.as_i64()?),
+                "datetime" => PropValueOwned::DateTime(inner.as_i64()?),
+                _ => return None,
+            };
+            Some(Some(value))
+        })
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(invalid)?;
+    Ok(PageCursor(values))
+}
+
 /// Result of a batch creation operation with node IDs, edge IDs, and aliases.
 #[derive(Debug, Default, Clone)]
 pub struct CreateResult {
@@ -3132,36 +6914,159 @@ impl From<CreateResult> for CreateSummary {
     }
 }
 
-impl CreateResult {
-    /// Returns node IDs as u64 values.
-    pub fn node_ids_as_u64(&self) -> Vec<u64> {
-        self.node_ids.iter().map(|id| id.0).collect()
-    }
+/// JSON-serializable page-level space report for bindings.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpaceReportSummary {
+    /// Page size in bytes.
+    pub page_size: u32,
+    /// Pages allocated in the database file.
+    pub total_pages: u64,
+    /// Pages awaiting reuse.
+    pub free_pages: u64,
+    /// Pages holding live data or metadata.
+    pub live_pages: u64,
+    /// Fraction of pages that are free.
+    pub fragmentation: f64,
+}
 
-    /// Returns edge IDs as u64 values.
-    pub fn edge_ids_as_u64(&self) -> Vec<u64> {
-        self.edge_ids.iter().map(|id| id.0).collect()
+impl From<SpaceReport> for SpaceReportSummary {
+    fn from(report: SpaceReport) -> Self {
+        Self {
+            page_size: report.page_size,
+            total_pages: report.total_pages,
+            free_pages: report.free_pages,
+            live_pages: report.live_pages,
+            fragmentation: report.fragmentation,
+        }
     }
 }
 
-// ============================================================================
-// Typed Batch API - High-performance bulk operations bypassing JSON/serde
-// ============================================================================
+/// JSON-serializable overview returned by [`Database::summary_json`].
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseSummary {
+    /// Visible nodes.
+    pub nodes: u64,
+    /// Visible edges.
+    pub edges: u64,
+    /// Node count per label name.
+    pub labels: BTreeMap<String, u64>,
+    /// Edge count per edge type name.
+    pub edge_types: BTreeMap<String, u64>,
+    /// Label indexes on labels that currently have nodes.
+    pub label_indexes: u64,
+    /// Registered property indexes.
+    pub property_indexes: u64,
+    /// Lowest and highest node id, or `None` for an empty graph.
+    pub node_id_range: Option<[u64; 2]>,
+    /// Lowest and highest edge id, or `None` when there are no edges.
+    pub edge_id_range: Option<[u64; 2]>,
+}
 
-/// Property entry for typed batch operations.
-///
-/// This struct represents a property without JSON serialization overhead.
-/// The `kind` field indicates the value type, and only the corresponding
-/// value field will be populated.
-#[derive(Debug, Clone)]
-pub struct TypedPropEntry {
-    /// Property key name.
-    pub key: String,
-    /// Property value kind: "null", "bool", "int", "float", "string", "bytes".
+/// Property types observed by [`BulkLoadOptions::capture_schema`] loads,
+/// returned by [`Database::inferred_schema_json`].
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InferredSchema {
+    /// Observed properties per node label.
+    pub labels: BTreeMap<String, InferredOwnerSchema>,
+    /// Observed properties per edge type.
+    pub edge_types: BTreeMap<String, InferredOwnerSchema>,
+}
+
+/// Properties seen on the nodes of one label or the edges of one type.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InferredOwnerSchema {
+    /// Nodes or edges observed.
+    pub count: u64,
+    /// Observed property types keyed by property name.
+    pub props: BTreeMap<String, InferredPropSchema>,
+}
+
+/// Types observed for a single property.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InferredPropSchema {
+    /// Nodes or edges that carried the property.
+    pub count: u64,
+    /// Occurrences per property kind (`"int"`, `"string"`, ...).
+    pub types: BTreeMap<String, u64>,
+    /// Whether more than one non-null kind was observed.
+    pub conflict: bool,
+}
+
+impl InferredSchema {
+    fn observe(
+        owners: &mut BTreeMap<String, InferredOwnerSchema>,
+        owner: &str,
+        props: &[TypedPropEntry],
+    ) {
+        let entry = owners.entry(owner.to_owned()).or_default();
+        entry.count += 1;
+        for prop in props {
+            let observed = entry.props.entry(prop.key.clone()).or_default();
+            observed.count += 1;
+            *observed.types.entry(prop.kind.clone()).or_default() += 1;
+        }
+    }
+
+    fn merge(&mut self, other: InferredSchema) {
+        for (owners, incoming) in [
+            (&mut self.labels, other.labels),
+            (&mut self.edge_types, other.edge_types),
+        ] {
+            for (name, schema) in incoming {
+                let entry = owners.entry(name).or_default();
+                entry.count += schema.count;
+                for (key, prop) in schema.props {
+                    let observed = entry.props.entry(key).or_default();
+                    observed.count += prop.count;
+                    for (kind, count) in prop.types {
+                        *observed.types.entry(kind).or_default() += count;
+                    }
+                    // Nulls only mark a property optional, not mistyped.
+                    observed.conflict =
+                        observed.types.keys().filter(|kind| *kind != "null").count() > 1;
+                }
+            }
+        }
+    }
+}
+
+impl CreateResult {
+    /// Returns node IDs as u64 values.
+    pub fn node_ids_as_u64(&self) -> Vec<u64> {
+        self.node_ids.iter().map(|id| id.0).collect()
+    }
+
+    /// Returns edge IDs as u64 values.
+    pub fn edge_ids_as_u64(&self) -> Vec<u64> {
+        self.edge_ids.iter().map(|id| id.0).collect()
+    }
+}
+
+// ============================================================================
+// Typed Batch API - High-performance bulk operations bypassing JSON/serde
+// ============================================================================
+
+/// Property entry for typed batch operations.
+///
+/// This struct represents a property without JSON serialization overhead.
+/// The `kind` field indicates the value type, and only the corresponding
+/// value field will be populated.
+#[derive(Debug, Clone)]
+pub struct TypedPropEntry {
+    /// Property key name.
+    pub key: String,
+    /// Property value kind: "null", "bool", "int", "float", "string",
+    /// "bytes", "date", "datetime".
     pub kind: String,
     /// Boolean value (when kind is "bool").
     pub bool_value: Option<bool>,
-    /// Integer value (when kind is "int").
+    /// Integer value (when kind is "int"), epoch days (when kind is "date")
+    /// or epoch milliseconds (when kind is "datetime").
     pub int_value: Option<i64>,
     /// Float value (when kind is "float").
     pub float_value: Option<f64>,
@@ -3209,8 +7114,20 @@ impl TypedPropEntry {
                     .map_err(|_| FfiError::Message("invalid base64 encoding for bytes".into()))?;
                 Ok(PropValueOwned::Bytes(decoded))
             }
+            "date" => {
+                let v = self
+                    .int_value
+                    .ok_or_else(|| FfiError::Message("int_value required for date kind".into()))?;
+                Ok(PropValueOwned::Date(v))
+            }
+            "datetime" => {
+                let v = self.int_value.ok_or_else(|| {
+                    FfiError::Message("int_value required for datetime kind".into())
+                })?;
+                Ok(PropValueOwned::DateTime(v))
+            }
             other => Err(FfiError::Message(format!(
-                "unknown property kind '{other}', expected null/bool/int/float/string/bytes"
+                "unknown property kind '{other}', expected null/bool/int/float/string/bytes/date/datetime"
             ))),
         }
     }
@@ -3308,6 +7225,18 @@ pub struct BulkLoadOptions {
     pub node_chunk_size: usize,
     /// Maximum number of edges to insert per transaction.
     pub edge_chunk_size: usize,
+    /// Suspend the property indexes of each label the load touches and
+    /// rebuild them in [`BulkLoadHandle::finish`] instead of maintaining
+    /// them per node. Unique indexes stay live so duplicates are still
+    /// rejected during the load.
+    ///
+    /// Until then the suspended indexes are building, so queries fall back
+    /// to scans. A handle dropped without finishing still tries to rebuild
+    /// them; if that fails they are rebuilt the next time the database opens.
+    pub defer_indexes: bool,
+    /// Record the property kinds seen per label and edge type, merged into
+    /// [`Database::inferred_schema_json`] as each chunk commits.
+    pub capture_schema: bool,
 }
 
 impl Default for BulkLoadOptions {
@@ -3315,6 +7244,8 @@ impl Default for BulkLoadOptions {
         Self {
             node_chunk_size: 10_000,
             edge_chunk_size: 100_000,
+            defer_indexes: false,
+            capture_schema: false,
         }
     }
 }
@@ -3332,6 +7263,447 @@ pub struct BulkLoadStats {
     pub edge_batches: u64,
 }
 
+/// Options for [`Database::import_csv`].
+#[derive(Debug, Clone)]
+pub struct CsvImportOptions {
+    /// Nodes column holding each row's external id, referenced by edges.
+    pub id_column: String,
+    /// Nodes column holding the node label.
+    pub label_column: Option<String>,
+    /// Label for nodes whose label column is missing or empty.
+    pub default_label: Option<String>,
+    /// Property that records each node's external id, if any.
+    pub id_property: Option<String>,
+    /// Edges column holding the source node's external id.
+    pub src_column: String,
+    /// Edges column holding the destination node's external id.
+    pub dst_column: String,
+    /// Edges column holding the edge type.
+    pub type_column: Option<String>,
+    /// Type for edges whose type column is missing or empty.
+    pub default_type: Option<String>,
+    /// Property coercions keyed by column name (case-insensitive). Columns
+    /// without an entry infer their type from each value.
+    pub column_types: HashMap<String, PropertyType>,
+    /// Chunking and index options for the underlying bulk load.
+    pub bulk: BulkLoadOptions,
+}
+
+impl Default for CsvImportOptions {
+    fn default() -> Self {
+        Self {
+            id_column: "id".into(),
+            label_column: Some("label".into()),
+            default_label: None,
+            id_property: None,
+            src_column: "src".into(),
+            dst_column: "dst".into(),
+            type_column: Some("type".into()),
+            default_type: None,
+            column_types: HashMap::new(),
+            bulk: BulkLoadOptions::default(),
+        }
+    }
+}
+
+/// Outcome of [`Database::import_csv`].
+#[derive(Debug, Clone, Default)]
+pub struct CsvImportSummary {
+    /// Nodes created.
+    pub nodes_loaded: u64,
+    /// Edges created.
+    pub edges_loaded: u64,
+    /// Rows skipped because they could not be parsed or resolved.
+    pub failed_rows: Vec<CsvRowError>,
+}
+
+/// A CSV row skipped by [`Database::import_csv`].
+#[derive(Debug, Clone)]
+pub struct CsvRowError {
+    /// File the row came from.
+    pub path: PathBuf,
+    /// 1-based line of the row, when the reader could tell.
+    pub line: Option<u64>,
+    /// Why the row was skipped.
+    pub message: String,
+}
+
+impl CsvRowError {
+    fn new(path: &Path, line: Option<u64>, message: String) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            line,
+            message,
+        }
+    }
+
+    fn at(path: &Path, record: &csv::StringRecord, message: String) -> Self {
+        Self::new(path, csv_line(record), message)
+    }
+
+    fn from_csv(path: &Path, err: &csv::Error) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            line: err.position().map(|pos| pos.line()),
+            message: err.to_string(),
+        }
+    }
+}
+
+/// A CSV column imported as a property.
+struct CsvPropColumn {
+    name: String,
+    index: usize,
+    ty: PropertyType,
+}
+
+fn open_csv(path: &Path) -> Result<csv::Reader<fs::File>> {
+    ReaderBuilder::new()
+        .flexible(true)
+        .from_path(path)
+        .map_err(|err| csv_io_error(path, err))
+}
+
+fn csv_io_error(path: &Path, err: csv::Error) -> FfiError {
+    FfiError::Message(format!("cannot read {}: {err}", path.display()))
+}
+
+fn csv_headers(reader: &mut csv::Reader<fs::File>, path: &Path) -> Result<StringRecord> {
+    reader
+        .headers()
+        .cloned()
+        .map_err(|err| csv_io_error(path, err))
+}
+
+fn csv_column(headers: &StringRecord, name: &str, path: &Path) -> Result<usize> {
+    headers
+        .iter()
+        .position(|header| header.eq_ignore_ascii_case(name))
+        .ok_or_else(|| {
+            FfiError::Message(format!("column '{name}' not found in {}", path.display()))
+        })
+}
+
+fn csv_prop_columns(
+    headers: &StringRecord,
+    opts: &CsvImportOptions,
+    skip: &[Option<usize>],
+) -> Vec<CsvPropColumn> {
+    let types: HashMap<String, PropertyType> = opts
+        .column_types
+        .iter()
+        .map(|(name, ty)| (name.to_ascii_lowercase(), *ty))
+        .collect();
+    headers
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| !skip.contains(&Some(*index)))
+        .map(|(index, header)| CsvPropColumn {
+            name: header.to_string(),
+            index,
+            ty: types
+                .get(&header.to_ascii_lowercase())
+                .copied()
+                .unwrap_or_default(),
+        })
+        .collect()
+}
+
+/// Returns the trimmed cell at `index`, or `None` when it is absent or empty.
+fn csv_cell(record: &StringRecord, index: usize) -> Option<&str> {
+    record
+        .get(index)
+        .map(str::trim)
+        .filter(|cell| !cell.is_empty())
+}
+
+fn csv_props(
+    record: &StringRecord,
+    columns: &[CsvPropColumn],
+) -> std::result::Result<Vec<TypedPropEntry>, String> {
+    let mut props = Vec::with_capacity(columns.len());
+    for column in columns {
+        let Some(raw) = csv_cell(record, column.index) else {
+            continue;
+        };
+        let value = parse_literal(raw, column.ty)
+            .map_err(|err| format!("column '{}': {err}", column.name))?;
+        if let Some(value) = value {
+            props.push(typed_prop_entry(&column.name, value));
+        }
+    }
+    Ok(props)
+}
+
+fn typed_prop_entry(key: &str, value: PropValueOwned) -> TypedPropEntry {
+    let mut entry = TypedPropEntry {
+        key: key.to_string(),
+        kind: String::new(),
+        bool_value: None,
+        int_value: None,
+        float_value: None,
+        string_value: None,
+        bytes_value: None,
+    };
+    entry.kind = match value {
+        PropValueOwned::Bool(v) => {
+            entry.bool_value = Some(v);
+            "bool"
+        }
+        PropValueOwned::Int(v) => {
+            entry.int_value = Some(v);
+            "int"
+        }
+        PropValueOwned::Float(v) => {
+            entry.float_value = Some(v);
+            "float"
+        }
+        PropValueOwned::Str(v) => {
+            entry.string_value = Some(v);
+            "string"
+        }
+        PropValueOwned::Bytes(v) => {
+            entry.bytes_value = Some(BASE64.encode(v));
+            "bytes"
+        }
+        PropValueOwned::Date(v) => {
+            entry.int_value = Some(v);
+            "date"
+        }
+        PropValueOwned::DateTime(v) => {
+            entry.int_value = Some(v);
+            "datetime"
+        }
+        _ => "null",
+    }
+    .to_string();
+    entry
+}
+
+fn csv_node_row(
+    record: &StringRecord,
+    id_index: usize,
+    label_index: Option<usize>,
+    columns: &[CsvPropColumn],
+    opts: &CsvImportOptions,
+) -> std::result::Result<(String, TypedNodeSpec), String> {
+    let ext_id = csv_cell(record, id_index)
+        .ok_or_else(|| format!("missing value for id column '{}'", opts.id_column))?
+        .to_string();
+    let label = label_index
+        .and_then(|index| csv_cell(record, index))
+        .or(opts.default_label.as_deref())
+        .ok_or_else(|| format!("node '{ext_id}' has no label"))?
+        .to_string();
+    let mut props = csv_props(record, columns)?;
+    if let Some(name) = &opts.id_property {
+        let value = match ext_id.parse::<i64>() {
+            Ok(id) => PropValueOwned::Int(id),
+            Err(_) => PropValueOwned::Str(ext_id.clone()),
+        };
+        props.push(typed_prop_entry(name, value));
+    }
+    Ok((
+        ext_id,
+        TypedNodeSpec {
+            label,
+            props,
+            alias: None,
+        },
+    ))
+}
+
+fn csv_edge_row(
+    record: &StringRecord,
+    [src_index, dst_index]: [usize; 2],
+    type_index: Option<usize>,
+    columns: &[CsvPropColumn],
+    opts: &CsvImportOptions,
+    ids: &HashMap<String, Option<NodeId>>,
+) -> std::result::Result<TypedEdgeSpec, String> {
+    let endpoint = |index: usize, column: &str| {
+        let ext_id = csv_cell(record, index)
+            .ok_or_else(|| format!("missing value for column '{column}'"))?;
+        let id = ids
+            .get(ext_id)
+            .copied()
+            .flatten()
+            .ok_or_else(|| format!("edge references unknown node id '{ext_id}'"))?;
+        Ok::<_, String>(TypedNodeRef {
+            kind: "id".into(),
+            alias: None,
+            handle: None,
+            id: Some(id.0),
+        })
+    };
+    let src = endpoint(src_index, &opts.src_column)?;
+    let dst = endpoint(dst_index, &opts.dst_column)?;
+    let ty = type_index
+        .and_then(|index| csv_cell(record, index))
+        .or(opts.default_type.as_deref())
+        .ok_or_else(|| "edge has no type".to_string())?
+        .to_string();
+    Ok(TypedEdgeSpec {
+        ty,
+        src,
+        dst,
+        props: csv_props(record, columns)?,
+    })
+}
+
+/// Returns the 1-based line `record` started on, when the reader knows it.
+fn csv_line(record: &StringRecord) -> Option<u64> {
+    record.position().map(|pos| pos.line())
+}
+
+/// Whether `err` rejects rows being written, such as a unique index or
+/// schema limit violation, rather than failing the write itself.
+fn csv_rejects_rows(err: &FfiError) -> bool {
+    matches!(
+        err.code(),
+        ErrorCode::Message
+            | ErrorCode::Analyzer
+            | ErrorCode::InvalidArg
+            | ErrorCode::ConstraintViolation
+    )
+}
+
+/// Loads `specs` as one chunk through `load`. When storage rejects the chunk
+/// it is retried one row at a time, so each row gets its own id or the
+/// message that rejected it.
+fn load_csv_chunk<S, T>(
+    specs: &[S],
+    mut load: impl FnMut(&[S]) -> Result<Vec<T>>,
+) -> Result<Vec<std::result::Result<T, String>>> {
+    match load(specs) {
+        Ok(created) => return Ok(created.into_iter().map(Ok).collect()),
+        Err(err) if !csv_rejects_rows(&err) => return Err(err),
+        Err(_) => {}
+    }
+    let mut outcomes = Vec::with_capacity(specs.len());
+    for spec in specs {
+        match load(std::slice::from_ref(spec)) {
+            Ok(created) => outcomes.extend(created.into_iter().map(Ok)),
+            Err(err) if csv_rejects_rows(&err) => outcomes.push(Err(err.to_string())),
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(outcomes)
+}
+
+/// Loads the pending nodes as one bulk chunk and records their ids; rows
+/// storage rejects keep no id and land in `failed_rows`.
+fn load_csv_nodes(
+    bulk: &mut BulkLoadHandle<'_>,
+    path: &Path,
+    pending: &mut Vec<TypedNodeSpec>,
+    pending_rows: &mut Vec<(String, Option<u64>)>,
+    ids: &mut HashMap<String, Option<NodeId>>,
+    failed_rows: &mut Vec<CsvRowError>,
+) -> Result<()> {
+    let outcomes = load_csv_chunk(pending, |chunk| bulk.load_nodes(chunk))?;
+    for ((ext_id, line), outcome) in pending_rows.drain(..).zip(outcomes) {
+        match outcome {
+            Ok(id) => {
+                ids.insert(ext_id, Some(id));
+            }
+            Err(message) => failed_rows.push(CsvRowError::new(path, line, message)),
+        }
+    }
+    pending.clear();
+    Ok(())
+}
+
+/// Loads the pending edges as one bulk chunk; rows storage rejects land in
+/// `failed_rows`.
+fn load_csv_edges(
+    bulk: &mut BulkLoadHandle<'_>,
+    path: &Path,
+    pending: &mut Vec<TypedEdgeSpec>,
+    pending_lines: &mut Vec<Option<u64>>,
+    failed_rows: &mut Vec<CsvRowError>,
+) -> Result<()> {
+    let outcomes = load_csv_chunk(pending, |chunk| bulk.load_edges(chunk))?;
+    for (line, outcome) in pending_lines.drain(..).zip(outcomes) {
+        if let Err(message) = outcome {
+            failed_rows.push(CsvRowError::new(path, line, message));
+        }
+    }
+    pending.clear();
+    Ok(())
+}
+
+/// Write transaction opened by [`Database::begin_transaction`].
+///
+/// Mutations accumulate until [`Transaction::commit`]; dropping the handle
+/// without committing rolls them back. The [`IsolationLevel`] chosen at begin
+/// decides whether reads through the handle observe those pending mutations.
+/// A batch that fails partway aborts the transaction: later mutations and the
+/// commit are rejected, so none of its writes can persist.
+pub struct Transaction<'db> {
+    db: &'db Database,
+    write: WriteGuard<'db>,
+    isolation: IsolationLevel,
+    aborted: bool,
+}
+
+impl<'db> Transaction<'db> {
+    /// Returns the isolation level the transaction was opened with.
+    pub fn isolation(&self) -> IsolationLevel {
+        self.isolation
+    }
+
+    /// Applies mutation operations inside the transaction.
+    ///
+    /// If any operation fails, the ops already applied from the batch cannot
+    /// be undone in place, so the whole transaction is aborted.
+    pub fn mutate(&mut self, spec: MutationSpec) -> Result<MutationSummary> {
+        self.ensure_active()?;
+        let mut summary = MutationSummary::default();
+        for op in spec.ops {
+            if let Err(err) = self.db.apply_mutation_op(&mut self.write, &mut summary, op) {
+                self.aborted = true;
+                return Err(err);
+            }
+        }
+        Ok(summary)
+    }
+
+    /// Fetches a node record, honouring the transaction's isolation level.
+    pub fn get_node_record(&mut self, node_id: u64) -> Result<Option<NodeRecord>> {
+        match self.isolation {
+            IsolationLevel::Snapshot => {
+                let id = NodeId(node_id);
+                let Some(node) = self.db.graph.get_node_in_write(&mut self.write, id)? else {
+                    return Ok(None);
+                };
+                let dict = &self.db.dict;
+                let write = &mut self.write;
+                materialize_node_with(id, node, |str_id| dict.resolve_with_write(write, str_id))
+                    .map(Some)
+            }
+            IsolationLevel::ReadCommitted => self.db.get_node_record(node_id),
+        }
+    }
+
+    /// Commits every mutation applied through the transaction.
+    pub fn commit(mut self) -> Result<()> {
+        self.ensure_active()?;
+        self.db.graph.flush_deferred_writes(&mut self.write)?;
+        self.db.pager.commit(self.write)?;
+        Ok(())
+    }
+
+    fn ensure_active(&self) -> Result<()> {
+        if self.aborted {
+            return Err(FfiError::Message(
+                "transaction aborted by a failed mutation".into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
 /// Handle for performing chunked bulk loads.
 pub struct BulkLoadHandle<'db> {
     db: &'db Database,
@@ -3340,6 +7712,8 @@ pub struct BulkLoadHandle<'db> {
     type_cache: HashMap<String, TypeId>,
     prop_cache: HashMap<String, PropId>,
     ensured_label_indexes: HashSet<LabelId>,
+    suspended: SuspendedIndexes,
+    deferred_labels: Vec<LabelId>,
     stats: BulkLoadStats,
 }
 
@@ -3354,57 +7728,77 @@ impl<'db> BulkLoadHandle<'db> {
         }
         let mut created: Vec<NodeId> = Vec::with_capacity(nodes.len());
         for chunk in nodes.chunks(self.options.node_chunk_size.max(1)) {
-            let mut write = self.db.pager.begin_write()?;
-            for node_spec in chunk {
-                // Reject aliases in bulk load for now to keep semantics simple.
-                if node_spec.alias.is_some() {
-                    return Err(FfiError::Message(
-                        "bulk load does not support node aliases".to_string(),
-                    ));
+            match self.load_node_chunk(chunk) {
+                Ok(ids) => created.extend(ids),
+                Err(err) => {
+                    self.forget_uncommitted_names();
+                    return Err(err);
                 }
-                // Resolve label with shared cache.
-                let dict_start = storage_profile_timer();
-                let label_id = self.db.resolve_or_cache_label(
-                    &mut write,
-                    &node_spec.label,
-                    &mut self.label_cache,
-                )?;
-                if self.ensured_label_indexes.insert(label_id) {
-                    self.db.ensure_label_index(&mut write, label_id)?;
-                }
-                record_storage_profile_timer(StorageProfileKind::DictResolve, dict_start);
+            }
+        }
+        Ok(created)
+    }
 
-                // Convert properties once per label schema.
-                let props_start = storage_profile_timer();
-                let prop_storage = self.db.typed_props_to_storage(
-                    &mut write,
-                    &node_spec.props,
-                    &mut self.prop_cache,
-                )?;
-                let mut prop_entries: Vec<PropEntry> =
-                    Vec::with_capacity(prop_storage.len());
-                for (prop_id, owned) in &prop_storage {
-                    prop_entries.push(PropEntry::new(*prop_id, prop_value_ref(owned)));
-                }
-                record_storage_profile_timer(
-                    StorageProfileKind::FfiTypedPropsConvert,
-                    props_start,
-                );
+    /// Loads one chunk of nodes in its own write transaction.
+    fn load_node_chunk(&mut self, chunk: &[TypedNodeSpec]) -> Result<Vec<NodeId>> {
+        if self.options.defer_indexes {
+            self.suspend_chunk_indexes(chunk)?;
+        }
+        let mut created: Vec<NodeId> = Vec::with_capacity(chunk.len());
+        let mut write = self.db.pager.begin_write()?;
+        let mut observed = InferredSchema::default();
+        for node_spec in chunk {
+            // Reject aliases in bulk load for now to keep semantics simple.
+            if node_spec.alias.is_some() {
+                return Err(FfiError::Message(
+                    "bulk load does not support node aliases".to_string(),
+                ));
+            }
+            // Resolve label with shared cache.
+            let dict_start = storage_profile_timer();
+            let label_id = self.db.resolve_or_cache_label(
+                &mut write,
+                &node_spec.label,
+                &mut self.label_cache,
+            )?;
+            if self.ensured_label_indexes.insert(label_id) {
+                self.db.ensure_label_index(&mut write, label_id)?;
+            }
+            record_storage_profile_timer(StorageProfileKind::DictResolve, dict_start);
 
-                let node_id = self.db.graph.create_node(
-                    &mut write,
-                    StorageNodeSpec {
-                        labels: &[label_id],
-                        props: &prop_entries,
-                    },
-                )?;
-                created.push(node_id);
-                self.stats.nodes_created = self.stats.nodes_created.saturating_add(1);
+            // Convert properties once per label schema.
+            let props_start = storage_profile_timer();
+            let prop_storage = self.db.typed_props_to_storage(
+                &mut write,
+                &node_spec.props,
+                &mut self.prop_cache,
+            )?;
+            let mut prop_entries: Vec<PropEntry> = Vec::with_capacity(prop_storage.len());
+            for (prop_id, owned) in &prop_storage {
+                prop_entries.push(PropEntry::new(*prop_id, prop_value_ref(owned)));
+            }
+            record_storage_profile_timer(StorageProfileKind::FfiTypedPropsConvert, props_start);
+
+            let node_id = self.db.graph.create_node(
+                &mut write,
+                StorageNodeSpec {
+                    labels: &[label_id],
+                    props: &prop_entries,
+                },
+            )?;
+            created.push(node_id);
+            if self.options.capture_schema {
+                InferredSchema::observe(&mut observed.labels, &node_spec.label, &node_spec.props);
             }
-            self.db.graph.flush_deferred_writes(&mut write)?;
-            self.db.pager.commit(write)?;
-            self.stats.node_batches = self.stats.node_batches.saturating_add(1);
         }
+        self.db.graph.flush_deferred_writes(&mut write)?;
+        self.db.pager.commit(write)?;
+        self.db.record_inferred_schema(observed);
+        self.stats.nodes_created = self
+            .stats
+            .nodes_created
+            .saturating_add(created.len() as u64);
+        self.stats.node_batches = self.stats.node_batches.saturating_add(1);
         Ok(created)
     }
 
@@ -3418,92 +7812,149 @@ impl<'db> BulkLoadHandle<'db> {
         }
         let mut created: Vec<EdgeId> = Vec::with_capacity(edges.len());
         for chunk in edges.chunks(self.options.edge_chunk_size.max(1)) {
-            let mut write = self.db.pager.begin_write()?;
-            for edge_spec in chunk {
-                // Only allow id-based references in bulk load.
-                let src_id = match edge_spec.src.kind.as_str() {
-                    "id" => edge_spec
-                        .src
-                        .id
-                        .map(NodeId)
-                        .ok_or_else(|| {
-                            FfiError::Message(
-                                "id field required for id kind in bulk load".into(),
-                            )
-                        })?,
-                    other => {
-                        return Err(FfiError::Message(format!(
-                            "bulk load requires id endpoints, got '{other}'",
-                        )));
-                    }
-                };
-                let dst_id = match edge_spec.dst.kind.as_str() {
-                    "id" => edge_spec
-                        .dst
-                        .id
-                        .map(NodeId)
-                        .ok_or_else(|| {
-                            FfiError::Message(
-                                "id field required for id kind in bulk load".into(),
-                            )
-                        })?,
-                    other => {
-                        return Err(FfiError::Message(format!(
-                            "bulk load requires id endpoints, got '{other}'",
-                        )));
-                    }
-                };
-
-                // Resolve edge type with shared cache.
-                let dict_start = storage_profile_timer();
-                let ty_id = self.db.resolve_or_cache_type(
-                    &mut write,
-                    &edge_spec.ty,
-                    &mut self.type_cache,
-                )?;
-                record_storage_profile_timer(StorageProfileKind::DictResolve, dict_start);
-
-                // Convert properties.
-                let props_start = storage_profile_timer();
-                let prop_storage = self.db.typed_props_to_storage(
-                    &mut write,
-                    &edge_spec.props,
-                    &mut self.prop_cache,
-                )?;
-                let mut prop_entries: Vec<PropEntry> =
-                    Vec::with_capacity(prop_storage.len());
-                for (prop_id, owned) in &prop_storage {
-                    prop_entries.push(PropEntry::new(*prop_id, prop_value_ref(owned)));
+            match self.load_edge_chunk(chunk) {
+                Ok(ids) => created.extend(ids),
+                Err(err) => {
+                    self.forget_uncommitted_names();
+                    return Err(err);
                 }
-                record_storage_profile_timer(
-                    StorageProfileKind::FfiTypedPropsConvert,
-                    props_start,
-                );
-
-                let edge_id = self.db.graph.create_edge(
-                    &mut write,
-                    StorageEdgeSpec {
-                        src: src_id,
-                        dst: dst_id,
-                        ty: ty_id,
-                        props: &prop_entries,
-                    },
-                )?;
-                created.push(edge_id);
-                self.stats.edges_created = self.stats.edges_created.saturating_add(1);
             }
-            self.db.graph.flush_deferred_writes(&mut write)?;
-            self.db.pager.commit(write)?;
-            self.stats.edge_batches = self.stats.edge_batches.saturating_add(1);
         }
         Ok(created)
     }
 
-    /// Finishes the bulk load session and returns aggregate statistics.
-    pub fn finish(self) -> BulkLoadStats {
-        self.stats
-    }
-}
+    /// Loads one chunk of edges in its own write transaction.
+    fn load_edge_chunk(&mut self, chunk: &[TypedEdgeSpec]) -> Result<Vec<EdgeId>> {
+        let mut created: Vec<EdgeId> = Vec::with_capacity(chunk.len());
+        let mut write = self.db.pager.begin_write()?;
+        let mut observed = InferredSchema::default();
+        for edge_spec in chunk {
+            // Only allow id-based references in bulk load.
+            let src_id = match edge_spec.src.kind.as_str() {
+                "id" => edge_spec.src.id.map(NodeId).ok_or_else(|| {
+                    FfiError::Message("id field required for id kind in bulk load".into())
+                })?,
+                other => {
+                    return Err(FfiError::Message(format!(
+                        "bulk load requires id endpoints, got '{other}'",
+                    )));
+                }
+            };
+            let dst_id = match edge_spec.dst.kind.as_str() {
+                "id" => edge_spec.dst.id.map(NodeId).ok_or_else(|| {
+                    FfiError::Message("id field required for id kind in bulk load".into())
+                })?,
+                other => {
+                    return Err(FfiError::Message(format!(
+                        "bulk load requires id endpoints, got '{other}'",
+                    )));
+                }
+            };
+
+            // Resolve edge type with shared cache.
+            let dict_start = storage_profile_timer();
+            let ty_id =
+                self.db
+                    .resolve_or_cache_type(&mut write, &edge_spec.ty, &mut self.type_cache)?;
+            record_storage_profile_timer(StorageProfileKind::DictResolve, dict_start);
+
+            // Convert properties.
+            let props_start = storage_profile_timer();
+            let prop_storage = self.db.typed_props_to_storage(
+                &mut write,
+                &edge_spec.props,
+                &mut self.prop_cache,
+            )?;
+            let mut prop_entries: Vec<PropEntry> = Vec::with_capacity(prop_storage.len());
+            for (prop_id, owned) in &prop_storage {
+                prop_entries.push(PropEntry::new(*prop_id, prop_value_ref(owned)));
+            }
+            record_storage_profile_timer(StorageProfileKind::FfiTypedPropsConvert, props_start);
+
+            let edge_id = self.db.graph.create_edge(
+                &mut write,
+                StorageEdgeSpec {
+                    src: src_id,
+                    dst: dst_id,
+                    ty: ty_id,
+                    props: &prop_entries,
+                },
+            )?;
+            created.push(edge_id);
+            if self.options.capture_schema {
+                InferredSchema::observe(&mut observed.edge_types, &edge_spec.ty, &edge_spec.props);
+            }
+        }
+        self.db.graph.flush_deferred_writes(&mut write)?;
+        self.db.pager.commit(write)?;
+        self.db.record_inferred_schema(observed);
+        self.stats.edges_created = self
+            .stats
+            .edges_created
+            .saturating_add(created.len() as u64);
+        self.stats.edge_batches = self.stats.edge_batches.saturating_add(1);
+        Ok(created)
+    }
+
+    /// Finishes the bulk load session and returns aggregate statistics.
+    ///
+    /// With [`BulkLoadOptions::defer_indexes`] this first rebuilds the
+    /// suspended indexes in one write transaction.
+    pub fn finish(mut self) -> Result<BulkLoadStats> {
+        self.restore_deferred_indexes()?;
+        Ok(self.stats)
+    }
+
+    /// Drops cached names and label indexes that a failed chunk resolved in
+    /// its rolled-back transaction, so later chunks intern them again.
+    fn forget_uncommitted_names(&mut self) {
+        self.label_cache.clear();
+        self.type_cache.clear();
+        self.prop_cache.clear();
+        let deferred = &self.deferred_labels;
+        self.ensured_label_indexes
+            .retain(|label| deferred.contains(label));
+    }
+
+    /// Suspends the indexes of labels first seen in `chunk`, committing
+    /// before the chunk's own transaction starts.
+    fn suspend_chunk_indexes(&mut self, chunk: &[TypedNodeSpec]) -> Result<()> {
+        let mut write = self.db.pager.begin_write()?;
+        let mut labels = Vec::new();
+        for node_spec in chunk {
+            let label_id = self.db.resolve_or_cache_label(
+                &mut write,
+                &node_spec.label,
+                &mut self.label_cache,
+            )?;
+            if self.ensured_label_indexes.insert(label_id) {
+                labels.push(label_id);
+            }
+        }
+        let suspended = self.db.graph.suspend_indexes(&mut write, &labels)?;
+        self.db.pager.commit(write)?;
+        self.suspended.extend(suspended);
+        self.deferred_labels.extend(labels);
+        Ok(())
+    }
+
+    fn restore_deferred_indexes(&mut self) -> Result<()> {
+        if self.suspended.is_empty() && self.deferred_labels.is_empty() {
+            return Ok(());
+        }
+        let suspended = std::mem::take(&mut self.suspended);
+        let labels = std::mem::take(&mut self.deferred_labels);
+        self.db.restore_deferred_indexes(suspended, labels)
+    }
+}
+
+impl Drop for BulkLoadHandle<'_> {
+    fn drop(&mut self) {
+        if let Err(err) = self.restore_deferred_indexes() {
+            tracing::warn!(error = %err, "bulk_load.restore_indexes_failed");
+        }
+    }
+}
 
 /// Fluent builder for staging nodes and edges, executing them transactionally.
 ///
@@ -3514,6 +7965,7 @@ pub struct CreateBuilder<'db> {
     nodes: Vec<DraftNode>,
     edges: Vec<DraftEdge>,
     used_aliases: HashSet<String>,
+    defer_indexes: bool,
 }
 
 impl<'db> CreateBuilder<'db> {
@@ -3523,9 +7975,24 @@ impl<'db> CreateBuilder<'db> {
             nodes: Vec::new(),
             edges: Vec::new(),
             used_aliases: HashSet::new(),
+            defer_indexes: false,
         }
     }
 
+    /// Skips index maintenance while executing (default `false`).
+    ///
+    /// Property indexes on every label the builder touches are suspended
+    /// before the create transaction and rebuilt from the node rows after
+    /// it, which beats per-node updates for large loads into indexed labels.
+    /// Unique indexes are not suspended and keep rejecting duplicates.
+    /// The indexes are rebuilt even when the create fails; queries in the
+    /// meantime fall back to scans. If the rebuild itself fails the created
+    /// nodes stay committed and the indexes are rebuilt on the next open.
+    pub fn defer_indexes(&mut self, defer: bool) -> &mut Self {
+        self.defer_indexes = defer;
+        self
+    }
+
     /// Adds a node without an alias and returns a handle that edges can reuse.
     pub fn node<L, S>(&mut self, labels: L, props: Map<String, Value>) -> NodeHandle
     where
@@ -3567,6 +8034,27 @@ impl<'db> CreateBuilder<'db> {
 
     /// Executes all pending nodes + edges within a single write transaction.
     pub fn execute(self) -> Result<CreateResult> {
+        if !self.defer_indexes {
+            return self.execute_nodes_and_edges();
+        }
+        let mut write = self.db.pager.begin_write()?;
+        let mut labels: Vec<LabelId> = Vec::new();
+        for node in &self.nodes {
+            labels.extend(self.db.resolve_labels(&mut write, &node.labels)?);
+        }
+        labels.sort_unstable();
+        labels.dedup();
+        let suspended = self.db.graph.suspend_indexes(&mut write, &labels)?;
+        self.db.pager.commit(write)?;
+
+        let created = self.execute_nodes_and_edges();
+        if let Err(err) = self.db.restore_deferred_indexes(suspended, labels) {
+            tracing::warn!(error = %err, "create.restore_indexes_failed");
+        }
+        created
+    }
+
+    fn execute_nodes_and_edges(&self) -> Result<CreateResult> {
         let mut write = self.db.pager.begin_write()?;
         let mut handle_ids: Vec<Option<NodeId>> = vec![None; self.nodes.len()];
         let mut alias_ids: HashMap<String, NodeId> = HashMap::new();
@@ -3658,7 +8146,7 @@ impl<'db> CreateBuilder<'db> {
         let label_ids = self.db.resolve_labels(write, &node.labels)?;
         let mut ensured_label_indexes: HashSet<LabelId> = HashSet::new();
         for label in &label_ids {
-            if ensured_label_indexes.insert(*label) {
+            if !self.defer_indexes && ensured_label_indexes.insert(*label) {
                 self.db.ensure_label_index(write, *label)?;
             }
         }
@@ -3911,8 +8399,26 @@ mod tests {
             }],
             edges: Vec::new(),
             predicate: Some(PredicateSpec::And { args: vec![] }),
+            filter: None,
             projections: Vec::new(),
             distinct: false,
+            operator_timeout_ms: None,
+            timeout_ms: None,
+            include_schema: false,
+            include_warnings: false,
+            resolve_names: None,
+            reject_cartesian: None,
+            dedup_by: Vec::new(),
+            flatten: false,
+            max_rows: None,
+            nest: None,
+            count_per_start: false,
+            order_by: Vec::new(),
+            tiebreak_desc: None,
+            bytes_format: BytesFormat::default(),
+            limit: None,
+            offset: None,
+            limit_per_group: None,
         };
         let ast = spec.into_ast()?;
         assert!(ast.predicate.is_none());
@@ -3930,8 +8436,26 @@ mod tests {
             }],
             edges: Vec::new(),
             predicate: Some(PredicateSpec::Or { args: vec![] }),
+            filter: None,
             projections: Vec::new(),
             distinct: false,
+            operator_timeout_ms: None,
+            timeout_ms: None,
+            include_schema: false,
+            include_warnings: false,
+            resolve_names: None,
+            reject_cartesian: None,
+            dedup_by: Vec::new(),
+            flatten: false,
+            max_rows: None,
+            nest: None,
+            count_per_start: false,
+            order_by: Vec::new(),
+            tiebreak_desc: None,
+            bytes_format: BytesFormat::default(),
+            limit: None,
+            offset: None,
+            limit_per_group: None,
         };
         let ast = spec.into_ast()?;
         match ast.predicate {
@@ -3988,8 +8512,26 @@ mod tests {
             }],
             edges: Vec::new(),
             predicate: None,
+            filter: None,
             projections: Vec::new(),
             distinct: false,
+            operator_timeout_ms: None,
+            timeout_ms: None,
+            include_schema: false,
+            include_warnings: false,
+            resolve_names: None,
+            reject_cartesian: None,
+            dedup_by: Vec::new(),
+            flatten: false,
+            max_rows: None,
+            nest: None,
+            count_per_start: false,
+            order_by: Vec::new(),
+            tiebreak_desc: None,
+            bytes_format: BytesFormat::default(),
+            limit: None,
+            offset: None,
+            limit_per_group: None,
         };
         let err = spec.into_ast().expect_err("schema version required");
         match err {
@@ -4023,8 +8565,26 @@ mod tests {
                 direction: DirectionSpec("sideways".into()),
             }],
             predicate: None,
+            filter: None,
             projections: Vec::new(),
             distinct: false,
+            operator_timeout_ms: None,
+            timeout_ms: None,
+            include_schema: false,
+            include_warnings: false,
+            resolve_names: None,
+            reject_cartesian: None,
+            dedup_by: Vec::new(),
+            flatten: false,
+            max_rows: None,
+            nest: None,
+            count_per_start: false,
+            order_by: Vec::new(),
+            tiebreak_desc: None,
+            bytes_format: BytesFormat::default(),
+            limit: None,
+            offset: None,
+            limit_per_group: None,
         };
         let err = spec.into_ast().expect_err("direction check");
         match err {
@@ -4035,6 +8595,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn omitted_direction_defaults_out_and_typos_error() -> Result<()> {
+        let edge_spec = |edge: Value| {
+            json!({
+                "$schemaVersion": 1,
+                "matches": [
+                    { "var": "a", "label": "User" },
+                    { "var": "b", "label": "User" }
+                ],
+                "edges": [edge]
+            })
+        };
+        let spec: QuerySpec = serde_json::from_value(edge_spec(json!({ "from": "a", "to": "b" })))?;
+        let ast = spec.into_ast()?;
+        assert_eq!(ast.edges[0].direction, EdgeDirection::Out);
+
+        let spec: QuerySpec = serde_json::from_value(edge_spec(
+            json!({ "from": "a", "to": "b", "direction": "outgong" }),
+        ))?;
+        let err = spec.into_ast().expect_err("typo must not fall back to out");
+        assert_eq!(
+            err.to_string(),
+            "edge direction 'outgong' must be 'out', 'in', or 'both'"
+        );
+        Ok(())
+    }
+
     #[test]
     fn invalid_bytes_literal_returns_bytes_encoding() {
         let spec = json!({
@@ -4092,208 +8679,2692 @@ mod tests {
     }
 
     #[test]
-    fn validate_in_values_rejects_excessive_bytes() {
-        let chunk = QueryValue::Bytes(vec![0u8; MAX_BYTES_LITERAL / 2 + 1]);
-        let err = super::validate_in_values(&[chunk.clone(), chunk]).unwrap_err();
-        match err {
-            FfiError::Message(msg) => {
-                assert!(msg.contains("total bytes in in() literals exceeds"))
-            }
-            other => panic!("unexpected error: {other:?}"),
+    fn running_count_projection_accumulates_while_streaming() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("running_count.db");
+        let db = Database::open(&path, DatabaseOptions::default())?;
+        let ops = (0..7)
+            .map(|i| MutationOp::CreateNode {
+                labels: vec!["Item".into()],
+                props: Map::from_iter([("rank".to_string(), json!(i))]),
+            })
+            .collect();
+        db.mutate(MutationSpec { ops })?;
+        let spec = json!({
+            "$schemaVersion": 1,
+            "matches": [
+                { "var": "a", "label": "Item" }
+            ],
+            "edges": [],
+            "projections": [
+                { "kind": "prop", "var": "a", "prop": "rank" },
+                { "kind": "running_count", "alias": "seen" }
+            ]
+        });
+
+        let response = db.execute_json(&spec)?;
+        let rows = response
+            .get("rows")
+            .and_then(Value::as_array)
+            .expect("rows array");
+        let counts: Vec<i64> = rows
+            .iter()
+            .map(|row| row["seen"].as_i64().expect("running count"))
+            .collect();
+        assert!(counts.windows(2).all(|pair| pair[1] > pair[0]));
+        assert_eq!(counts.last().copied(), Some(rows.len() as i64));
+        assert_eq!(rows.len(), 7);
+
+        let stream = db.stream_json(&spec)?;
+        let mut streamed = Vec::new();
+        while let Some(row) = stream.next()? {
+            streamed.push(row["seen"].as_i64().expect("running count"));
         }
+        assert_eq!(streamed, (1..=7).collect::<Vec<i64>>());
+        Ok(())
     }
 
     #[test]
-    fn validate_in_values_allows_bytes_within_budget() -> Result<()> {
-        let chunk = QueryValue::Bytes(vec![0u8; MAX_BYTES_LITERAL / 2]);
-        super::validate_in_values(&[chunk.clone(), chunk])?;
+    fn stream_batched_delivers_bounded_batches_in_order() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let db = Database::open(dir.path().join("batched.db"), DatabaseOptions::default())?;
+        let nodes: Vec<Value> = (0..10)
+            .map(|i| json!({ "labels": ["User"], "props": { "age": i } }))
+            .collect();
+        db.create_json(&json!({ "nodes": nodes }))?;
+        let spec = json!({
+            "$schemaVersion": 1,
+            "matches": [{ "var": "u", "label": "User" }],
+            "projections": [{ "kind": "prop", "var": "u", "prop": "age" }],
+            "orderBy": [{ "var": "u", "prop": "age" }]
+        });
+        let ages = |batch: &[Value]| -> Vec<i64> {
+            batch
+                .iter()
+                .map(|row| row["age"].as_i64().expect("age"))
+                .collect()
+        };
+
+        let mut batches = Vec::new();
+        db.stream_batched_json(&spec, 4, |batch| {
+            batches.push(ages(&batch));
+            Ok(true)
+        })?;
+        assert_eq!(batches, [vec![0, 1, 2, 3], vec![4, 5, 6, 7], vec![8, 9]]);
+
+        let mut calls = 0;
+        db.stream_batched_json(&spec, 3, |batch| {
+            calls += 1;
+            assert_eq!(ages(&batch), [0, 1, 2]);
+            Ok(false)
+        })?;
+        assert_eq!(calls, 1);
+
+        let stream = db.stream_json(&spec)?;
+        assert_eq!(ages(&stream.next_batch(6)?), [0, 1, 2, 3, 4, 5]);
+        assert_eq!(ages(&stream.next_batch(6)?), [6, 7, 8, 9]);
+        assert!(stream.next_batch(6)?.is_empty());
+        assert!(stream.next_batch(0).is_err());
         Ok(())
     }
 
     #[test]
-    fn create_builder_errors_on_unknown_alias() -> Result<()> {
+    fn stream_sees_nodes_deleted_after_query_started() -> Result<()> {
         let dir = tempdir().unwrap();
-        let path = dir.path().join("builder_unknown_alias.db");
-        let db = Database::open(&path, DatabaseOptions::default())?;
-        let mut builder = db.create();
-        builder.node(["User"], props(&[("name", json!("Alice"))]));
-        builder.edge(
-            NodeRef::alias("$missing"),
-            "LIKES",
-            NodeRef::alias("$also_missing"),
-            Map::new(),
+        let db = Database::open(
+            dir.path().join("snapshot_stream.db"),
+            DatabaseOptions::default(),
         )?;
-        let err = builder.execute().unwrap_err();
-        match err {
-            FfiError::Message(msg) => assert!(msg.contains("unknown node alias")),
-            other => panic!("unexpected error: {other:?}"),
+        let ops = (0..3)
+            .map(|i| MutationOp::CreateNode {
+                labels: vec!["Item".into()],
+                props: Map::from_iter([("rank".to_string(), json!(i))]),
+            })
+            .collect();
+        let created = db.mutate(MutationSpec { ops })?;
+        let spec = json!({
+            "$schemaVersion": 1,
+            "matches": [{ "var": "a", "label": "Item" }],
+            "projections": [
+                { "kind": "var", "var": "a" },
+                { "kind": "prop", "var": "a", "prop": "rank" }
+            ]
+        });
+
+        let stream = db.stream_json(&spec)?;
+        let mut ranks = vec![stream.next()?.expect("first row")["rank"].clone()];
+        for id in &created.created_nodes {
+            db.mutate(MutationSpec {
+                ops: vec![MutationOp::DeleteNode {
+                    id: *id,
+                    cascade: false,
+                }],
+            })?;
+        }
+        while let Some(row) = stream.next()? {
+            assert!(row["a"].is_object());
+            ranks.push(row["rank"].clone());
         }
+        ranks.sort_by_key(|rank| rank.as_i64());
+        assert_eq!(ranks, vec![json!(0), json!(1), json!(2)]);
+        assert_eq!(db.count_nodes_with_label("Item")?, 0);
         Ok(())
     }
 
     #[test]
-    fn create_builder_rejects_duplicate_alias() -> Result<()> {
+    fn byte_snapshot_round_trips_seeded_graph() -> Result<()> {
         let dir = tempdir().unwrap();
-        let path = dir.path().join("builder_duplicate_alias.db");
-        let db = Database::open(&path, DatabaseOptions::default())?;
-        let mut builder = db.create();
-        builder.node_with_alias(["User"], Map::new(), "$dup")?;
-        let err = builder
-            .node_with_alias(["User"], Map::new(), "$dup")
-            .unwrap_err();
-        match err {
-            FfiError::Message(msg) => assert!(msg.contains("duplicate node alias")),
-            other => panic!("unexpected error: {other:?}"),
-        }
+        let db = Database::open(dir.path().join("source.db"), DatabaseOptions::default())?;
+        db.seed_demo()?;
+        let spec = json!({
+            "$schemaVersion": 1,
+            "matches": [
+                { "var": "a", "label": "User" },
+                { "var": "b", "label": "User" }
+            ],
+            "edges": [
+                { "from": "a", "to": "b", "edgeType": "FOLLOWS" }
+            ],
+            "projections": [
+                { "kind": "prop", "var": "a", "prop": "name", "alias": "from" },
+                { "kind": "prop", "var": "b", "prop": "name", "alias": "to" }
+            ]
+        });
+        let rows = |db: &Database| -> Result<Vec<String>> {
+            let response = db.execute_json(&spec)?;
+            let mut rows: Vec<String> = response["rows"]
+                .as_array()
+                .expect("rows array")
+                .iter()
+                .map(|row| format!("{}->{}", row["from"], row["to"]))
+                .collect();
+            rows.sort();
+            Ok(rows)
+        };
+
+        let bytes = db.to_bytes()?;
+        assert!(!bytes.is_empty());
+        assert!(bytes.len() <= MAX_SNAPSHOT_BYTES);
+        let copy_path = dir.path().join("copy.db");
+        let copy = Database::open_from_bytes(&copy_path, &bytes, DatabaseOptions::default())?;
+        assert_eq!(
+            copy.count_nodes_with_label("User")?,
+            db.count_nodes_with_label("User")?
+        );
+        assert_eq!(
+            copy.count_edges_with_type("FOLLOWS")?,
+            db.count_edges_with_type("FOLLOWS")?
+        );
+        assert_eq!(
+            copy.node_ids_with_label("User")?,
+            db.node_ids_with_label("User")?
+        );
+        assert_eq!(rows(&copy)?, rows(&db)?);
+        assert!(!rows(&copy)?.is_empty());
+
+        assert!(!copy_path.exists(), "image stays in memory");
+
+        let err = Database::open_from_bytes(
+            dir.path().join("source.db"),
+            &bytes,
+            DatabaseOptions::default(),
+        )
+        .err()
+        .expect("existing destination rejected");
+        assert!(err.to_string().contains("already exists"), "{err}");
+
+        let garbage_path = dir.path().join("garbage.db");
+        assert!(
+            Database::open_from_bytes(&garbage_path, &[7u8; 64], DatabaseOptions::default())
+                .is_err()
+        );
+        assert!(!garbage_path.exists());
         Ok(())
     }
 
     #[test]
-    fn create_script_supports_structs_and_json() -> Result<()> {
+    fn space_report_json_balances_free_and_live_pages() -> Result<()> {
         let dir = tempdir().unwrap();
-        let path = dir.path().join("builder_script_spec.db");
+        let path = dir.path().join("space_report.db");
         let db = Database::open(&path, DatabaseOptions::default())?;
+        db.seed_demo()?;
+        let report = db.space_report_json()?;
+        let total = report["totalPages"].as_u64().expect("total pages");
+        let free = report["freePages"].as_u64().expect("free pages");
+        let live = report["livePages"].as_u64().expect("live pages");
+        assert!(total > 0);
+        assert_eq!(free + live, total);
+        assert_eq!(report["pageSize"].as_u64(), Some(8192));
+        let ratio = report["fragmentation"].as_f64().expect("fragmentation");
+        assert!((0.0..=1.0).contains(&ratio));
+        Ok(())
+    }
 
-        let script = CreateScript {
-            nodes: vec![
-                CreateNodeSpec {
-                    labels: vec!["User".into()],
-                    props: props(&[("name", json!("Alice"))]),
-                    alias: Some("$alice".into()),
-                },
-                CreateNodeSpec {
-                    labels: vec!["Company".into()],
-                    props: props(&[("name", json!("Acme Inc"))]),
-                    alias: None,
-                },
-            ],
-            edges: vec![CreateEdgeSpec {
-                src: CreateRefSpec::Alias {
-                    alias: "$alice".into(),
-                },
-                ty: "WORKS_AT".into(),
-                dst: CreateRefSpec::Handle { index: 1 },
-                props: props(&[("role", json!("Engineer"))]),
-            }],
-        };
-        let result = db.create_script(script)?;
-        assert_eq!(result.node_ids.len(), 2);
-        assert_eq!(result.edge_ids.len(), 1);
-        assert_eq!(result.aliases.get("$alice"), Some(&result.node_ids[0]));
-
-        let json_spec = json!({
+    #[test]
+    fn path_projection_lists_nodes_along_edge_chain() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("path_projection.db");
+        let db = Database::open(&path, DatabaseOptions::default())?;
+        let created = db.create_json(&json!({
             "nodes": [
-                { "labels": ["User"], "props": { "name": "Bob" }, "alias": "$bob" },
-                { "labels": ["User"], "props": { "name": "Charlie" } }
+                { "labels": ["Stop"], "props": { "name": "origin" }, "alias": "$a" },
+                { "labels": ["Stop"], "props": { "name": "middle" }, "alias": "$b" },
+                { "labels": ["Stop"], "props": { "name": "terminus" }, "alias": "$c" }
             ],
             "edges": [
                 {
-                    "src": { "kind": "alias", "alias": "$bob" },
-                    "ty": "KNOWS",
-                    "dst": { "kind": "handle", "index": 1 },
+                    "src": { "kind": "alias", "alias": "$a" },
+                    "ty": "NEXT",
+                    "dst": { "kind": "alias", "alias": "$b" },
+                    "props": {}
+                },
+                {
+                    "src": { "kind": "alias", "alias": "$b" },
+                    "ty": "NEXT",
+                    "dst": { "kind": "alias", "alias": "$c" },
                     "props": {}
                 }
             ]
+        }))?;
+        let ids: Vec<u64> = created["nodes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|id| id.as_u64().unwrap())
+            .collect();
+        let hops = 2;
+        let spec = json!({
+            "$schemaVersion": 1,
+            "matches": [
+                { "var": "a", "label": "Stop" },
+                { "var": "b", "label": "Stop" },
+                { "var": "c", "label": "Stop" }
+            ],
+            "edges": [
+                { "from": "a", "to": "b", "edgeType": "NEXT" },
+                { "from": "b", "to": "c", "edgeType": "NEXT" }
+            ],
+            "projections": [
+                { "kind": "var", "var": "a" },
+                { "kind": "var", "var": "c" },
+                { "kind": "path", "from_var": "a", "to_var": "c", "alias": "route" }
+            ]
         });
-        let summary = db.create_json(&json_spec)?;
-        assert_eq!(summary["nodes"].as_array().unwrap().len(), 2);
-        assert_eq!(summary["edges"].as_array().unwrap().len(), 1);
-        assert!(summary["aliases"]["$bob"].as_u64().is_some());
-        Ok(())
-    }
 
-    #[test]
-    fn pragma_synchronous_roundtrip() -> Result<()> {
-        let dir = tempdir().unwrap();
-        let path = dir.path().join("pragma_sync.db");
-        let db = Database::open(&path, DatabaseOptions::default())?;
-        let set = db.pragma("synchronous", Some(Value::String("normal".into())))?;
-        assert_eq!(set, Value::String("normal".into()));
-        let current = db.pragma("synchronous", None)?;
-        assert_eq!(current, Value::String("normal".into()));
+        let response = db.execute_json(&spec)?;
+        let rows = response["rows"].as_array().expect("rows array");
+        assert_eq!(rows.len(), 1);
+        let row = &rows[0];
+        let route: Vec<u64> = row["route"]
+            .as_array()
+            .expect("path array")
+            .iter()
+            .map(|id| id.as_u64().expect("node id"))
+            .collect();
+        assert_eq!(route.len(), hops + 1);
+        assert_eq!(route.first().copied(), row["a"]["_id"].as_u64());
+        assert_eq!(route.last().copied(), row["c"]["_id"].as_u64());
+        assert_eq!(route, ids);
+
+        let mut unjoined = spec.clone();
+        unjoined["edges"] = json!([{ "from": "a", "to": "b", "edgeType": "NEXT" }]);
+        let err = db.execute_json(&unjoined).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("no edge chain connects 'a' to 'c'"),
+            "{err}"
+        );
         Ok(())
     }
 
     #[test]
-    fn pragma_wal_coalesce_roundtrip() -> Result<()> {
+    fn include_schema_reports_columns_for_empty_results() -> Result<()> {
         let dir = tempdir().unwrap();
-        let path = dir.path().join("pragma_coalesce.db");
-        let db = Database::open(&path, DatabaseOptions::default())?;
-        let set = db.pragma("wal_coalesce_ms", Some(Value::Number(Number::from(7))))?;
-        assert_eq!(set, Value::Number(Number::from(7)));
-        let current = db.pragma("wal_coalesce_ms", None)?;
-        assert_eq!(current, Value::Number(Number::from(7)));
+        let db = Database::open(dir.path().join("schema.db"), DatabaseOptions::default())?;
+        db.seed_demo()?;
+        let spec = json!({
+            "$schemaVersion": 1,
+            "includeSchema": true,
+            "matches": [
+                { "var": "a", "label": "User" },
+                { "var": "b", "label": "User" }
+            ],
+            "edges": [
+                { "from": "a", "to": "b", "edgeType": "FOLLOWS" }
+            ],
+            "predicate": {
+                "op": "eq",
+                "var": "a",
+                "prop": "name",
+                "value": { "t": "String", "v": "Nobody" }
+            },
+            "projections": [
+                { "kind": "var", "var": "a" },
+                { "kind": "prop", "var": "b", "prop": "name", "alias": "friend" },
+                { "kind": "running_count" },
+                { "kind": "path", "from_var": "a", "to_var": "b" }
+            ]
+        });
+
+        let response = db.execute_json(&spec)?;
+        assert!(response["rows"].as_array().expect("rows array").is_empty());
+        assert_eq!(
+            response["columns"],
+            json!([
+                { "name": "a", "type": "node" },
+                { "name": "friend", "type": "string" },
+                { "name": "running_count", "type": "int" },
+                { "name": "path", "type": "path" }
+            ])
+        );
+
+        let mut unprojected = spec.clone();
+        unprojected["projections"] = json!([]);
+        let response = db.execute_json(&unprojected)?;
+        assert_eq!(
+            response["columns"],
+            json!([
+                { "name": "a", "type": "nodeId" },
+                { "name": "b", "type": "nodeId" }
+            ])
+        );
+
+        let mut without_schema = spec.clone();
+        without_schema["includeSchema"] = json!(false);
+        let response = db.execute_json(&without_schema)?;
+        assert!(response.get("columns").is_none());
         Ok(())
     }
 
     #[test]
-    fn pragma_autocheckpoint_ms_roundtrip() -> Result<()> {
+    fn resolve_names_toggles_interned_prop_ids() -> Result<()> {
         let dir = tempdir().unwrap();
-        let path = dir.path().join("pragma_auto_ms.db");
+        let db = Database::open(dir.path().join("names.db"), DatabaseOptions::default())?;
+        db.seed_demo()?;
+        let name_id = db.dict.lookup("name")?.expect("name interned").0;
+        let mut spec = json!({
+            "$schemaVersion": 1,
+            "matches": [{ "var": "a", "label": "User" }],
+            "predicate": {
+                "op": "eq",
+                "var": "a",
+                "prop": "name",
+                "value": { "t": "String", "v": "Ada" }
+            },
+            "projections": [{ "kind": "var", "var": "a" }]
+        });
+
+        let resolved = db.execute_json(&spec)?;
+        assert_eq!(resolved["rows"][0]["a"]["props"]["name"], json!("Ada"));
+
+        spec["resolveNames"] = json!(false);
+        let raw = db.execute_json(&spec)?;
+        let props = raw["rows"][0]["a"]["props"]
+            .as_object()
+            .expect("props object");
+        assert_eq!(props[&name_id.to_string()], json!("Ada"));
+        assert!(!props.contains_key("name"));
+        assert!(props.keys().all(|key| key.parse::<u32>().is_ok()));
+        assert_eq!(raw["rows"][0]["a"]["_id"], resolved["rows"][0]["a"]["_id"]);
+        Ok(())
+    }
+
+    #[test]
+    fn validate_json_reports_errors_and_scan_warnings() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let db = Database::open(dir.path().join("validate.db"), DatabaseOptions::default())?;
+        db.seed_demo()?;
+        db.mutate(MutationSpec {
+            ops: vec![MutationOp::CreateNode {
+                labels: vec!["User".into()],
+                props: Map::from_iter([("age".to_string(), json!(30))]),
+            }],
+        })?;
+        let spec = |var: &str, prop: &str| {
+            json!({
+                "$schemaVersion": 1,
+                "matches": [{ "var": "a", "label": "User" }],
+                "predicate": {
+                    "op": "eq",
+                    "var": var,
+                    "prop": prop,
+                    "value": { "t": "Int", "v": 30 }
+                },
+                "projections": [{ "kind": "var", "var": "a" }]
+            })
+        };
+
+        let report = db.validate_json(&spec("z", "age"))?;
+        assert!(!report.is_valid());
+        assert_eq!(report.errors.len(), 1);
+        assert!(report.errors[0].message.contains("'z'"), "{report:?}");
+
+        let report = db.validate_json(&spec("a", "name"))?;
+        assert!(report.is_valid(), "{report:?}");
+        assert!(report.warnings.is_empty(), "{report:?}");
+
+        let report = db.validate_json(&spec("a", "age"))?;
+        assert!(report.is_valid(), "{report:?}");
+        assert_eq!(report.warnings.len(), 1);
+        assert_eq!(report.warnings[0].code, "UnindexedPredicate");
+        assert!(report.warnings[0].message.contains("User.age"));
+
+        let mut unknown = spec("a", "age");
+        unknown["matches"][0]["label"] = json!("Ghost");
+        let report = db.validate_json(&unknown)?;
+        assert_eq!(report.errors[0].code, "UnknownLabel");
+        Ok(())
+    }
+
+    #[test]
+    fn reject_cartesian_rejects_disconnected_matches() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let db = Database::open(dir.path().join("cartesian.db"), DatabaseOptions::default())?;
+        db.seed_demo()?;
+        let row_count = |response: Value| response["rows"].as_array().expect("rows").len();
+        let users = row_count(db.execute_json(&json!({
+            "$schemaVersion": 1,
+            "matches": [{ "var": "a", "label": "User" }]
+        }))?);
+        let rejected = json!({
+            "$schemaVersion": 1,
+            "matches": [
+                { "var": "a", "label": "User" },
+                { "var": "b", "label": "User" }
+            ]
+        });
+        let err = db.execute_json(&rejected).unwrap_err();
+        assert!(err.to_string().contains("cartesian product"), "{err}");
+        assert_eq!(
+            db.validate_json(&rejected)?.errors[0].code,
+            "CartesianProduct"
+        );
+
+        let mut allowed = rejected.clone();
+        allowed["rejectCartesian"] = json!(false);
+        assert_eq!(row_count(db.execute_json(&allowed)?), users * users);
+        let report = db.validate_json(&allowed)?;
+        assert!(report.is_valid());
+        assert!(report
+            .warnings
+            .iter()
+            .any(|issue| issue.code == "CartesianProduct"));
+
+        let mut connected = rejected.clone();
+        connected["edges"] = json!([{ "from": "a", "to": "b", "edgeType": "FOLLOWS" }]);
+        assert!(row_count(db.execute_json(&connected)?) > 0);
+        Ok(())
+    }
+
+    #[test]
+    fn index_advice_ranks_most_scanned_unindexed_property() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let db = Database::open(
+            dir.path().join("advisor.db"),
+            DatabaseOptions {
+                index_advisor: true,
+                ..DatabaseOptions::default()
+            },
+        )?;
+        let ops = (0..12)
+            .map(|i| MutationOp::CreateNode {
+                labels: vec!["User".into()],
+                props: Map::from_iter([
+                    ("name".to_string(), json!(format!("user-{i}"))),
+                    ("city".to_string(), json!(format!("city-{}", i % 4))),
+                    ("age".to_string(), json!(20 + i)),
+                ]),
+            })
+            .collect();
+        db.mutate(MutationSpec { ops })?;
+        db.ensure_property_index("User", "name", "chunked", "string")?;
+        let query = |op: &str, prop: &str, value: Value| {
+            json!({
+                "$schemaVersion": 1,
+                "matches": [{ "var": "a", "label": "User" }],
+                "predicate": { "op": op, "var": "a", "prop": prop, "value": value },
+                "projections": [{ "kind": "var", "var": "a" }]
+            })
+        };
+        for city in ["city-0", "city-1", "city-2"] {
+            db.execute_json(&query("eq", "city", json!({ "t": "String", "v": city })))?;
+        }
+        db.execute_json(&query("ge", "age", json!({ "t": "Int", "v": 25 })))?;
+        let indexed = json!({ "t": "String", "v": "user-3" });
+        db.execute_json(&query("eq", "name", indexed))?;
+
+        let advice = db.index_advice();
+        assert_eq!(advice.len(), 2, "{advice:?}");
+        assert_eq!(advice[0].label, "User");
+        assert_eq!(advice[0].prop, "city");
+        assert_eq!(advice[0].hits, 3);
+        assert_eq!(advice[0].equality_hits, 3);
+        assert!(advice[0].estimated_benefit > 0.0);
+        assert_eq!(advice[1].prop, "age");
+        assert_eq!(advice[1].equality_hits, 0);
+        assert!(advice.iter().all(|suggestion| suggestion.prop != "name"));
+
+        let quiet = Database::open(dir.path().join("quiet.db"), DatabaseOptions::default())?;
+        quiet.seed_demo()?;
+        quiet.execute_json(&query("eq", "name", json!({ "t": "String", "v": "Ada" })))?;
+        assert!(quiet.index_advice().is_empty());
+        Ok(())
+    }
+
+    fn int_prop(key: &str, value: i64) -> TypedPropEntry {
+        TypedPropEntry {
+            key: key.into(),
+            kind: "int".into(),
+            bool_value: None,
+            int_value: Some(value),
+            float_value: None,
+            string_value: None,
+            bytes_value: None,
+        }
+    }
+
+    fn duplicate_key_batch() -> TypedBatchSpec {
+        TypedBatchSpec {
+            nodes: vec![TypedNodeSpec {
+                label: "User".into(),
+                props: vec![int_prop("age", 1), int_prop("score", 5), int_prop("age", 2)],
+                alias: None,
+            }],
+            edges: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn duplicate_property_keys_take_last_value_by_default() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("dup_last_wins.db");
         let db = Database::open(&path, DatabaseOptions::default())?;
-        let set = db.pragma("autocheckpoint_ms", Some(Value::Number(Number::from(10))))?;
-        assert_eq!(set, Value::Number(Number::from(10)));
-        let current = db.pragma("autocheckpoint_ms", None)?;
-        assert_eq!(current, Value::Number(Number::from(10)));
-        let cleared = db.pragma("autocheckpoint_ms", Some(Value::Null))?;
-        assert_eq!(cleared, Value::Null);
+        let created = db.create_typed_batch(&duplicate_key_batch())?;
+        let record = db
+            .get_node_record(created.node_ids[0].0)?
+            .expect("node exists");
+        assert_eq!(record.properties.len(), 2);
+        assert_eq!(record.properties.get("age"), Some(&json!(2)));
+        assert_eq!(record.properties.get("score"), Some(&json!(5)));
         Ok(())
     }
 
     #[test]
-    fn explain_json_includes_union_dedup_flag() -> Result<()> {
+    fn duplicate_property_keys_error_under_strict_policy() -> Result<()> {
         let dir = tempdir().unwrap();
-        let path = dir.path().join("explain_union_dedup.db");
+        let path = dir.path().join("dup_error.db");
+        let opts = DatabaseOptions {
+            duplicate_props: DuplicatePropertyPolicy::Error,
+            ..DatabaseOptions::default()
+        };
+        let db = Database::open(&path, opts)?;
+        match db.create_typed_batch(&duplicate_key_batch()) {
+            Err(FfiError::Message(msg)) => assert!(msg.contains("duplicate property key 'age'")),
+            other => panic!("unexpected result: {other:?}"),
+        }
+        Ok(())
+    }
+
+    const DUPLICATE_KEY_SCRIPT: &str = r#"{
+        "nodes": [{ "labels": ["User"], "props": { "age": 1, "score": 5, "age": 2 } }]
+    }"#;
+
+    #[test]
+    fn json_spec_duplicate_keys_take_last_value_by_default() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("json_dup_last_wins.db");
         let db = Database::open(&path, DatabaseOptions::default())?;
-        db.seed_demo()?;
-        let spec = json!({
+        let created = db.create_json_str(DUPLICATE_KEY_SCRIPT)?;
+        let id = created["nodes"][0].as_u64().expect("node id");
+        db.mutate_json_str(&format!(
+            r#"{{ "ops": [{{ "op": "updateNode", "id": {id}, "set": {{ "score": 6, "score": 7 }} }}] }}"#
+        ))?;
+        let record = db.get_node_record(id)?.expect("node exists");
+        assert_eq!(record.properties.len(), 2);
+        assert_eq!(record.properties.get("age"), Some(&json!(2)));
+        assert_eq!(record.properties.get("score"), Some(&json!(7)));
+        Ok(())
+    }
+
+    #[test]
+    fn json_spec_duplicate_keys_error_under_strict_policy() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("json_dup_error.db");
+        let opts = DatabaseOptions {
+            duplicate_props: DuplicatePropertyPolicy::Error,
+            ..DatabaseOptions::default()
+        };
+        let db = Database::open(&path, opts)?;
+        match db.create_json_str(DUPLICATE_KEY_SCRIPT) {
+            Err(FfiError::Message(msg)) => assert!(msg.contains("duplicate property key 'age'")),
+            other => panic!("unexpected result: {other:?}"),
+        }
+        let mutation = r#"{ "ops": [{ "op": "createNode", "labels": ["User"], "props": { "a": 1, "a": 2 } }] }"#;
+        match db.mutate_json_str(mutation) {
+            Err(FfiError::Message(msg)) => assert!(msg.contains("duplicate property key 'a'")),
+            other => panic!("unexpected result: {other:?}"),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn operator_timeout_names_stalled_filter() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("operator_timeout.db");
+        let db = Database::open(&path, DatabaseOptions::default())?;
+        let ops = (0..16)
+            .map(|i| MutationOp::CreateNode {
+                labels: vec!["Doc".into()],
+                props: Map::from_iter([("body".to_string(), json!(format!("doc-{i}")))]),
+            })
+            .collect();
+        db.mutate(MutationSpec { ops })?;
+        let mut spec = json!({
             "$schemaVersion": 1,
-            "request_id": "req-42",
             "matches": [
-                { "var": "a", "label": "User" }
+                { "var": "d", "label": "Doc" }
             ],
+            "edges": [],
             "projections": [
-                { "kind": "var", "var": "a" }
+                { "kind": "var", "var": "d", "alias": null }
             ],
-            "distinct": true,
             "predicate": {
-                "op": "or",
+                "op": "eq",
+                "var": "d",
+                "prop": "body",
+                "value": { "t": "String", "v": "missing" }
+            }
+        });
+        spec["operator_timeout_ms"] = json!(60_000);
+
+        // Within budget the query completes even with the filter stalled.
+        crate::query::executor::stall_operator(Some(("Filter", Duration::from_secs(30))));
+        let within = db.execute_json(&spec);
+        // Pushing the filter's self time past the deadline names it, while
+        // the label scan feeding it stays well inside its own budget.
+        crate::query::executor::stall_operator(Some(("Filter", Duration::from_secs(90))));
+        let stalled = db.execute_json(&spec);
+        crate::query::executor::stall_operator(None);
+
+        assert_eq!(within?["rows"].as_array().map(Vec::len), Some(0));
+        let err = stalled.expect_err("filter should time out");
+        assert_eq!(err.code_name(), "TIMEOUT");
+        let FfiError::Core(SombraError::Timeout(msg)) = err else {
+            panic!("unexpected error: {err:?}");
+        };
+        assert!(msg.starts_with("operator Filter ran for "), "{msg}");
+        assert!(msg.ends_with("exceeding its 60000ms deadline"), "{msg}");
+        Ok(())
+    }
+
+    #[test]
+    fn timeout_ms_aborts_executions_and_streams() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("query_timeout.db");
+        let db = Database::open(&path, DatabaseOptions::default())?;
+        let ops = (0..200)
+            .map(|i| MutationOp::CreateNode {
+                labels: vec!["Doc".into()],
+                props: Map::from_iter([("n".to_string(), json!(i))]),
+            })
+            .collect();
+        db.mutate(MutationSpec { ops })?;
+        let mut spec = json!({
+            "$schemaVersion": 1,
+            "matches": [
+                { "var": "d", "label": "Doc" }
+            ],
+            "projections": [
+                { "kind": "prop", "var": "d", "prop": "n", "alias": null }
+            ],
+            "timeoutMs": 60_000
+        });
+
+        let generous = db.execute_json(&spec)?;
+        assert_eq!(generous["rows"].as_array().map(Vec::len), Some(200));
+
+        spec["timeoutMs"] = json!(0);
+        let err = db.execute_json(&spec).expect_err("query should time out");
+        assert!(matches!(err, FfiError::QueryTimeout), "{err:?}");
+        assert_eq!(err.code_name(), "TIMEOUT");
+        assert_eq!(err.to_string(), "query timed out");
+
+        let stream = db.stream_json(&spec)?;
+        match stream.next() {
+            Err(FfiError::QueryTimeout) => {}
+            other => panic!("expected stream timeout, got {other:?}"),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn unique_index_rejects_conflicting_mutations() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let db = Database::open(dir.path().join("unique.db"), DatabaseOptions::default())?;
+        let user = |email: &str| MutationOp::CreateNode {
+            labels: vec!["User".into()],
+            props: Map::from_iter([("email".to_string(), json!(email))]),
+        };
+        let created = db.mutate(MutationSpec {
+            ops: vec![user("ada@example.com"), user("grace@example.com")],
+        })?;
+        assert!(db.ensure_unique_property_index("User", "email", "btree", "string")?);
+        assert!(!db.ensure_unique_property_index("User", "email", "btree", "string")?);
+
+        // The whole batch rolls back when any operation conflicts.
+        let err = db
+            .mutate(MutationSpec {
+                ops: vec![user("linus@example.com"), user("ada@example.com")],
+            })
+            .expect_err("duplicate email should be rejected");
+        assert_eq!(err.code_name(), "CONSTRAINT_VIOLATION");
+        let err = db
+            .mutate(MutationSpec {
+                ops: vec![MutationOp::UpdateNode {
+                    id: created.created_nodes[1],
+                    set: Map::from_iter([("email".to_string(), json!("ada@example.com"))]),
+                    unset: Vec::new(),
+                }],
+            })
+            .expect_err("duplicate email should be rejected");
+        assert!(
+            matches!(err, FfiError::Core(SombraError::ConstraintViolation(_))),
+            "{err:?}"
+        );
+
+        let spec = json!({
+            "$schemaVersion": 1,
+            "matches": [
+                { "var": "u", "label": "User" }
+            ],
+            "projections": [
+                { "kind": "prop", "var": "u", "prop": "email", "alias": null }
+            ]
+        });
+        let rows = db.execute_json(&spec)?;
+        assert_eq!(rows["rows"].as_array().map(Vec::len), Some(2));
+        db.mutate(MutationSpec {
+            ops: vec![user("linus@example.com")],
+        })?;
+        Ok(())
+    }
+
+    #[test]
+    fn composite_index_answers_prefix_queries() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let db = Database::open(dir.path().join("composite.db"), DatabaseOptions::default())?;
+        let user = |city: &str, age: i64| MutationOp::CreateNode {
+            labels: vec!["User".into()],
+            props: Map::from_iter([
+                ("city".to_string(), json!(city)),
+                ("age".to_string(), json!(age)),
+            ]),
+        };
+        db.mutate(MutationSpec {
+            ops: vec![user("Paris", 25), user("Paris", 35), user("Rome", 35)],
+        })?;
+        assert!(db.ensure_composite_index(
+            "User",
+            &["city", "age"],
+            "chunked",
+            &["string", "int"]
+        )?);
+        assert!(!db.ensure_composite_index(
+            "User",
+            &["city", "age"],
+            "chunked",
+            &["string", "int"]
+        )?);
+        assert!(db
+            .ensure_composite_index("User", &["age"], "chunked", &["int"])
+            .is_err());
+        db.mutate(MutationSpec {
+            ops: vec![user("Paris", 45)],
+        })?;
+
+        let spec = json!({
+            "$schemaVersion": 1,
+            "matches": [{ "var": "u", "label": "User" }],
+            "predicate": {
+                "op": "and",
                 "args": [
-                    {
-                        "op": "eq",
-                        "var": "a",
-                        "prop": "name",
-                        "value": { "t": "String", "v": "Ada" }
-                    },
-                    {
-                        "op": "eq",
-                        "var": "a",
-                        "prop": "name",
-                        "value": { "t": "String", "v": "Grace" }
-                    }
+                    { "op": "eq", "var": "u", "prop": "city", "value": { "t": "String", "v": "Paris" } },
+                    { "op": "gt", "var": "u", "prop": "age", "value": { "t": "Int", "v": 30 } }
+                ]
+            },
+            "projections": [
+                { "kind": "prop", "var": "u", "prop": "age", "alias": null }
+            ]
+        });
+        let explain = db.explain_json(&spec)?.to_string();
+        assert!(explain.contains("CompositeIndexScan"), "{explain}");
+        let rows = db.execute_json(&spec)?;
+        let mut ages: Vec<i64> = rows["rows"]
+            .as_array()
+            .expect("rows array")
+            .iter()
+            .map(|row| row["age"].as_i64().expect("age"))
+            .collect();
+        ages.sort_unstable();
+        assert_eq!(ages, vec![35, 45]);
+        Ok(())
+    }
+
+    #[test]
+    fn prefix_predicate_uses_string_index() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let db = Database::open(dir.path().join("prefix.db"), DatabaseOptions::default())?;
+        let user = |name: &str| MutationOp::CreateNode {
+            labels: vec!["User".into()],
+            props: Map::from_iter([("name".to_string(), json!(name))]),
+        };
+        db.mutate(MutationSpec {
+            ops: vec![user("Ada"), user("Adele"), user("Grace"), user("ad")],
+        })?;
+        let spec = |case_insensitive: bool| {
+            json!({
+                "$schemaVersion": 1,
+                "matches": [{ "var": "u", "label": "User" }],
+                "predicate": {
+                    "op": "prefix",
+                    "var": "u",
+                    "prop": "name",
+                    "prefix": "Ad",
+                    "caseInsensitive": case_insensitive
+                },
+                "projections": [
+                    { "kind": "prop", "var": "u", "prop": "name", "alias": null }
                 ]
+            })
+        };
+        let names = |rows: Value| -> Vec<String> {
+            let mut names: Vec<String> = rows["rows"]
+                .as_array()
+                .expect("rows array")
+                .iter()
+                .map(|row| row["name"].as_str().expect("name").to_owned())
+                .collect();
+            names.sort();
+            names
+        };
+
+        let explain = db.explain_json(&spec(false))?.to_string();
+        assert!(!explain.contains("PropIndexScan"), "{explain}");
+        assert_eq!(names(db.execute_json(&spec(false))?), vec!["Ada", "Adele"]);
+
+        assert!(db.ensure_property_index("User", "name", "btree", "string")?);
+        let explain = db.explain_json(&spec(false))?.to_string();
+        assert!(explain.contains("PropIndexScan"), "{explain}");
+        assert!(explain.contains("STARTS WITH"), "{explain}");
+        assert_eq!(names(db.execute_json(&spec(false))?), vec!["Ada", "Adele"]);
+        assert!(db.execute_json(&spec(true)).is_err());
+        Ok(())
+    }
+
+    fn copy_tree(src: &Path, dst: &Path) {
+        if src.is_dir() {
+            std::fs::create_dir_all(dst).unwrap();
+            for entry in std::fs::read_dir(src).unwrap() {
+                let entry = entry.unwrap();
+                copy_tree(&entry.path(), &dst.join(entry.file_name()));
+            }
+        } else {
+            std::fs::copy(src, dst).unwrap();
+        }
+    }
+
+    fn wal_dir(path: &Path) -> std::path::PathBuf {
+        let mut name = path.as_os_str().to_owned();
+        name.push("-wal");
+        name.into()
+    }
+
+    /// Rewrites the active WAL segment after `edit` has changed its bytes,
+    /// passing the offsets of the frames it holds.
+    fn edit_wal_frames(wal: &Path, page_size: usize, edit: impl FnOnce(&mut [u8], &[usize])) {
+        let segment = std::fs::read_dir(wal.join("active"))
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .max()
+            .expect("active wal segment");
+        let mut bytes = std::fs::read(&segment).unwrap();
+        let frame_len = 32 + page_size;
+        let mut offset = 32;
+        let mut frames = Vec::new();
+        while offset + frame_len <= bytes.len() {
+            let lsn = u64::from_be_bytes(bytes[offset..offset + 8].try_into().unwrap());
+            if lsn == 0 {
+                break;
             }
+            frames.push(offset);
+            offset += frame_len;
+        }
+        assert!(!frames.is_empty(), "wal holds frames");
+        edit(&mut bytes, &frames);
+        std::fs::write(&segment, bytes).unwrap();
+    }
+
+    /// Flips a payload byte of the newest frame in the active WAL segment.
+    fn tear_last_wal_frame(wal: &Path, page_size: usize) {
+        edit_wal_frames(wal, page_size, |bytes, frames| {
+            let last = *frames.last().unwrap();
+            bytes[last + 32 + page_size / 2] ^= 0xFF;
         });
-        let explain = db.explain_json(&spec)?;
-        assert_eq!(
-            explain.get("request_id").and_then(Value::as_str),
-            Some("req-42")
-        );
-        let plan = explain
-            .get("plan")
-            .and_then(Value::as_array)
-            .expect("plan array");
-        dbg!(plan);
-        assert_eq!(plan.len(), 1);
-        let project = plan[0].as_object().expect("plan node");
-        assert_eq!(project.get("op").and_then(Value::as_str), Some("Project"));
-        let inputs = project
-            .get("inputs")
-            .and_then(Value::as_array)
-            .expect("project inputs");
-        assert_eq!(inputs.len(), 1);
-        let union = inputs[0].as_object().expect("union node object");
-        assert_eq!(union.get("op").and_then(Value::as_str), Some("Union"));
+    }
+
+    /// Writes a crashed copy of a database holding `commits` uncheckpointed
+    /// single-node commits to `crashed`.
+    fn crash_after_commits(path: &Path, crashed: &Path, commits: usize) -> Result<()> {
+        let db = Database::open(path, DatabaseOptions::default())?;
+        for _ in 0..commits {
+            db.mutate(MutationSpec {
+                ops: vec![MutationOp::CreateNode {
+                    labels: vec!["Item".into()],
+                    props: Map::new(),
+                }],
+            })?;
+        }
+        // Copy the files while the WAL is still uncheckpointed, as a crash would
+        // leave them.
+        copy_tree(path, crashed);
+        copy_tree(&wal_dir(path), &wal_dir(crashed));
+        Ok(())
+    }
+
+    #[test]
+    fn open_discards_torn_wal_commit() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("torn.db");
+        let crashed = dir.path().join("crashed.db");
+        crash_after_commits(&path, &crashed, 2)?;
+        let page_size = DatabaseOptions::default().pager.page_size as usize;
+        tear_last_wal_frame(&wal_dir(&crashed), page_size);
+
+        // A torn trailing commit never reached its caller as durable, so it is
+        // dropped without auto_repair.
+        let db = Database::open(&crashed, DatabaseOptions::default())?;
+        let repair = db.open_report().wal_tail.expect("torn commit discarded");
+        assert!(repair.discarded_frames > 0);
+        assert_eq!(db.open_report().wal_damage, None);
+        assert_eq!(db.count_nodes_with_label("Item")?, 1);
+        drop(db);
+
+        // Reopening after the repair finds nothing left to discard.
+        let db = Database::open(&crashed, DatabaseOptions::default())?;
+        assert!(!db.open_report().repaired());
+        assert_eq!(db.count_nodes_with_label("Item")?, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn open_discards_commit_with_missing_last_frame_header() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("zeroed.db");
+        let crashed = dir.path().join("crashed.db");
+        crash_after_commits(&path, &crashed, 2)?;
+        let page_size = DatabaseOptions::default().pager.page_size as usize;
+        let mut last_commit_frames = 0;
+        edit_wal_frames(&wal_dir(&crashed), page_size, |bytes, frames| {
+            let lsn_at = |offset: usize| &bytes[offset..offset + 8];
+            let last = *frames.last().unwrap();
+            last_commit_frames = frames
+                .iter()
+                .filter(|offset| lsn_at(**offset) == lsn_at(last))
+                .count();
+            // The commit's last header never reached disk; its earlier frames
+            // are intact.
+            bytes[last..last + 32].fill(0);
+        });
+        assert!(last_commit_frames > 1, "commit spans several pages");
+
+        let db = Database::open(&crashed, DatabaseOptions::default())?;
+        let repair = db.open_report().wal_tail.expect("partial commit discarded");
+        assert_eq!(repair.discarded_frames, last_commit_frames as u64 - 1);
+        assert_eq!(db.count_nodes_with_label("Item")?, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn auto_repair_discards_damage_before_later_commits() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("damaged.db");
+        let crashed = dir.path().join("crashed.db");
+        crash_after_commits(&path, &crashed, 3)?;
+        let page_size = DatabaseOptions::default().pager.page_size as usize;
+        edit_wal_frames(&wal_dir(&crashed), page_size, |bytes, frames| {
+            let lsn_at = |offset: usize| bytes[offset..offset + 8].to_vec();
+            let mut commits: Vec<Vec<usize>> = Vec::new();
+            for offset in frames {
+                match commits.last_mut() {
+                    Some(commit) if lsn_at(commit[0]) == lsn_at(*offset) => commit.push(*offset),
+                    _ => commits.push(vec![*offset]),
+                }
+            }
+            // Garble a page id in the last header of the second node's
+            // commit; the third node's commit follows it intact.
+            let damaged = *commits[commits.len() - 2].last().unwrap();
+            bytes[damaged + 12] ^= 0xFF;
+        });
+
+        match Database::open(&crashed, DatabaseOptions::default()) {
+            Err(FfiError::Core(SombraError::Corruption(msg))) => {
+                assert_eq!(msg, "wal holds damaged frames before later commits");
+            }
+            Err(other) => panic!("unexpected error: {other:?}"),
+            Ok(_) => panic!("mid-log damage must not open without auto_repair"),
+        }
+
+        let opts = DatabaseOptions {
+            auto_repair: true,
+            ..DatabaseOptions::default()
+        };
+        let db = Database::open(&crashed, opts.clone())?;
+        let repair = db.open_report().wal_damage.expect("damage discarded");
+        assert!(repair.discarded_frames > 1);
+        assert_eq!(db.open_report().wal_tail, None);
+        assert_eq!(db.count_nodes_with_label("Item")?, 1);
+        drop(db);
+
+        let db = Database::open(&crashed, DatabaseOptions::default())?;
+        assert!(!db.open_report().repaired());
+        assert_eq!(db.count_nodes_with_label("Item")?, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn max_recovery_time_bounds_wal_replay() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("replay.db");
+        let crashed = dir.path().join("crashed.db");
+        let mut opts = DatabaseOptions::default();
+        opts.pager.autocheckpoint_pages = 0;
+        let db = Database::open(&path, opts.clone())?;
+        for i in 0..200 {
+            db.mutate(MutationSpec {
+                ops: vec![MutationOp::CreateNode {
+                    labels: vec!["Item".into()],
+                    props: Map::from_iter([("n".to_string(), Value::from(i))]),
+                }],
+            })?;
+        }
+        copy_tree(&path, &crashed);
+        copy_tree(&wal_dir(&path), &wal_dir(&crashed));
+        drop(db);
+
+        let bounded = DatabaseOptions {
+            max_recovery_time: Some(Duration::from_nanos(1)),
+            ..opts.clone()
+        };
+        match Database::open(&crashed, bounded) {
+            Err(FfiError::Core(SombraError::Timeout(msg))) => {
+                assert_eq!(msg, "recovery exceeded time budget");
+            }
+            Err(other) => panic!("unexpected error: {other:?}"),
+            Ok(_) => panic!("recovery must not finish within a 1ns budget"),
+        }
+
+        // The abandoned replay left the WAL intact for an unbounded open.
+        let db = Database::open(&crashed, opts)?;
+        let report = db.open_report();
+        assert!(report.frames_replayed >= 200, "{report:?}");
+        assert!(!report.repaired());
+        assert_eq!(db.count_nodes_with_label("Item")?, 200);
+        Ok(())
+    }
+
+    #[test]
+    fn auto_repair_refuses_main_file_corruption() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("meta.db");
+        drop(Database::open(&path, DatabaseOptions::default())?);
+        let mut bytes = std::fs::read(&path).unwrap();
+        bytes[64] ^= 0xFF;
+        std::fs::write(&path, bytes).unwrap();
+
+        let opts = DatabaseOptions {
+            auto_repair: true,
+            ..DatabaseOptions::default()
+        };
+        match Database::open(&path, opts) {
+            Err(FfiError::Core(SombraError::Corruption(_))) => Ok(()),
+            Err(other) => panic!("unexpected error: {other:?}"),
+            Ok(_) => panic!("main-file corruption must not be repaired"),
+        }
+    }
+
+    #[test]
+    fn validate_in_values_rejects_excessive_bytes() {
+        let chunk = QueryValue::Bytes(vec![0u8; MAX_BYTES_LITERAL / 2 + 1]);
+        let err = super::validate_in_values(&[chunk.clone(), chunk]).unwrap_err();
+        match err {
+            FfiError::Message(msg) => {
+                assert!(msg.contains("total bytes in in() literals exceeds"))
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn validate_in_values_allows_bytes_within_budget() -> Result<()> {
+        let chunk = QueryValue::Bytes(vec![0u8; MAX_BYTES_LITERAL / 2]);
+        super::validate_in_values(&[chunk.clone(), chunk])?;
+        Ok(())
+    }
+
+    #[test]
+    fn create_builder_errors_on_unknown_alias() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("builder_unknown_alias.db");
+        let db = Database::open(&path, DatabaseOptions::default())?;
+        let mut builder = db.create();
+        builder.node(["User"], props(&[("name", json!("Alice"))]));
+        builder.edge(
+            NodeRef::alias("$missing"),
+            "LIKES",
+            NodeRef::alias("$also_missing"),
+            Map::new(),
+        )?;
+        let err = builder.execute().unwrap_err();
+        match err {
+            FfiError::Message(msg) => assert!(msg.contains("unknown node alias")),
+            other => panic!("unexpected error: {other:?}"),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn create_builder_rejects_duplicate_alias() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("builder_duplicate_alias.db");
+        let db = Database::open(&path, DatabaseOptions::default())?;
+        let mut builder = db.create();
+        builder.node_with_alias(["User"], Map::new(), "$dup")?;
+        let err = builder
+            .node_with_alias(["User"], Map::new(), "$dup")
+            .unwrap_err();
+        match err {
+            FfiError::Message(msg) => assert!(msg.contains("duplicate node alias")),
+            other => panic!("unexpected error: {other:?}"),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn create_script_supports_structs_and_json() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("builder_script_spec.db");
+        let db = Database::open(&path, DatabaseOptions::default())?;
+
+        let script = CreateScript {
+            nodes: vec![
+                CreateNodeSpec {
+                    labels: vec!["User".into()],
+                    props: props(&[("name", json!("Alice"))]),
+                    alias: Some("$alice".into()),
+                },
+                CreateNodeSpec {
+                    labels: vec!["Company".into()],
+                    props: props(&[("name", json!("Acme Inc"))]),
+                    alias: None,
+                },
+            ],
+            edges: vec![CreateEdgeSpec {
+                src: CreateRefSpec::Alias {
+                    alias: "$alice".into(),
+                },
+                ty: "WORKS_AT".into(),
+                dst: CreateRefSpec::Handle { index: 1 },
+                props: props(&[("role", json!("Engineer"))]),
+            }],
+            defer_indexes: false,
+        };
+        let result = db.create_script(script)?;
+        assert_eq!(result.node_ids.len(), 2);
+        assert_eq!(result.edge_ids.len(), 1);
+        assert_eq!(result.aliases.get("$alice"), Some(&result.node_ids[0]));
+
+        let json_spec = json!({
+            "nodes": [
+                { "labels": ["User"], "props": { "name": "Bob" }, "alias": "$bob" },
+                { "labels": ["User"], "props": { "name": "Charlie" } }
+            ],
+            "edges": [
+                {
+                    "src": { "kind": "alias", "alias": "$bob" },
+                    "ty": "KNOWS",
+                    "dst": { "kind": "handle", "index": 1 },
+                    "props": {}
+                }
+            ]
+        });
+        let summary = db.create_json(&json_spec)?;
+        assert_eq!(summary["nodes"].as_array().unwrap().len(), 2);
+        assert_eq!(summary["edges"].as_array().unwrap().len(), 1);
+        assert!(summary["aliases"]["$bob"].as_u64().is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn pragma_synchronous_roundtrip() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("pragma_sync.db");
+        let db = Database::open(&path, DatabaseOptions::default())?;
+        let set = db.pragma("synchronous", Some(Value::String("normal".into())))?;
+        assert_eq!(set, Value::String("normal".into()));
+        let current = db.pragma("synchronous", None)?;
+        assert_eq!(current, Value::String("normal".into()));
+        Ok(())
+    }
+
+    #[test]
+    fn pragma_wal_coalesce_roundtrip() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("pragma_coalesce.db");
+        let db = Database::open(&path, DatabaseOptions::default())?;
+        let set = db.pragma("wal_coalesce_ms", Some(Value::Number(Number::from(7))))?;
+        assert_eq!(set, Value::Number(Number::from(7)));
+        let current = db.pragma("wal_coalesce_ms", None)?;
+        assert_eq!(current, Value::Number(Number::from(7)));
+        Ok(())
+    }
+
+    #[test]
+    fn pragma_autocheckpoint_ms_roundtrip() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("pragma_auto_ms.db");
+        let db = Database::open(&path, DatabaseOptions::default())?;
+        let set = db.pragma("autocheckpoint_ms", Some(Value::Number(Number::from(10))))?;
+        assert_eq!(set, Value::Number(Number::from(10)));
+        let current = db.pragma("autocheckpoint_ms", None)?;
+        assert_eq!(current, Value::Number(Number::from(10)));
+        let cleared = db.pragma("autocheckpoint_ms", Some(Value::Null))?;
+        assert_eq!(cleared, Value::Null);
+        Ok(())
+    }
+
+    #[test]
+    fn pragma_async_fsync_roundtrip() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("pragma_async_fsync.db");
+        let db = Database::open(&path, DatabaseOptions::default())?;
+        assert_eq!(db.pragma("async_fsync", None)?, Value::Bool(false));
+        assert!(db.pager.async_fsync_backlog().is_none());
+
+        let set = db.pragma("async_fsync", Some(Value::Bool(true)))?;
+        assert_eq!(set, Value::Bool(true));
+        db.seed_demo()?;
+        let backlog = db
+            .pager
+            .async_fsync_backlog()
+            .expect("async fsync should queue commits once enabled");
+        assert!(backlog.pending_lsn.0 > 0);
+
+        let cleared = db.pragma("async_fsync", Some(Value::String("off".into())))?;
+        assert_eq!(cleared, Value::Bool(false));
+        assert!(db.pager.durable_lsn().0 >= backlog.pending_lsn.0);
+        assert!(db
+            .pragma("async_fsync", Some(Value::String("maybe".into())))
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn pragma_async_fsync_max_wait_roundtrip() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("pragma_async_wait.db");
+        let db = Database::open(&path, DatabaseOptions::default())?;
+        let set = db.pragma(
+            "async_fsync_max_wait_ms",
+            Some(Value::Number(Number::from(25))),
+        )?;
+        assert_eq!(set, Value::Number(Number::from(25)));
+        let current = db.pragma("async_fsync_max_wait_ms", None)?;
+        assert_eq!(current, Value::Number(Number::from(25)));
+        Ok(())
+    }
+
+    #[test]
+    fn async_fsync_coalesces_commits_inside_max_wait() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("async_fsync_coalesce.db");
+        let db = Database::open(&path, DatabaseOptions::default())?;
+        let wait = Duration::from_millis(1_000);
+        db.pragma(
+            "async_fsync_max_wait_ms",
+            Some(Value::Number(Number::from(wait.as_millis() as u64))),
+        )?;
+        db.pragma("async_fsync", Some(Value::Bool(true)))?;
+        let durable_before = db.pager.durable_lsn();
+
+        let started = Instant::now();
+        for i in 0..4 {
+            db.mutate(MutationSpec {
+                ops: vec![MutationOp::CreateNode {
+                    labels: vec!["Item".into()],
+                    props: Map::from_iter([("rank".to_string(), json!(i))]),
+                }],
+            })?;
+        }
+        let pending = db
+            .pager
+            .async_fsync_backlog()
+            .expect("async fsync should queue commits once enabled")
+            .pending_lsn;
+        // The worker holds its fsync for the whole window, so nothing the
+        // burst committed is durable yet.
+        if started.elapsed() < wait {
+            assert_eq!(db.pager.durable_lsn(), durable_before);
+        }
+
+        // A single fsync at the end of the window covers every commit in it:
+        // the first durable LSN past the burst start is already the last one.
+        let deadline = started + wait + Duration::from_secs(10);
+        let durable = loop {
+            let durable = db.pager.durable_lsn();
+            if durable.0 > durable_before.0 {
+                break durable;
+            }
+            assert!(
+                Instant::now() < deadline,
+                "async fsync did not land within its {wait:?} window"
+            );
+            std::thread::sleep(Duration::from_millis(5));
+        };
+        assert_eq!(durable, pending);
+        assert!(started.elapsed() >= wait);
+        Ok(())
+    }
+
+    fn sync_user_names(db: &Database) -> Result<Vec<(i64, String)>> {
+        let mut rows = Vec::new();
+        for id in db.node_ids_with_label("User")? {
+            let record = db.get_node_record(id)?.expect("user node");
+            let props = &record.properties;
+            rows.push((
+                props["uid"].as_i64().unwrap(),
+                props["name"].as_str().unwrap().to_string(),
+            ));
+        }
+        rows.sort();
+        Ok(rows)
+    }
+
+    #[test]
+    fn sync_json_creates_updates_and_deletes() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("sync_json.db");
+        let db = Database::open(&path, DatabaseOptions::default())?;
+        db.sync_json(&json!({
+            "upserts": [
+                { "label": "User", "key": "uid", "props": { "uid": 1, "name": "Ada" } },
+                { "label": "User", "key": "uid", "props": { "uid": 2, "name": "Grace" } }
+            ]
+        }))?;
+
+        let summary = db.sync_json(&json!({
+            "upserts": [
+                { "label": "User", "key": "uid", "props": { "uid": 1, "name": "Ada L." } },
+                { "label": "User", "key": "uid", "props": { "uid": 3, "name": "Alan" } }
+            ],
+            "deletes": [
+                { "label": "User", "key": "uid", "value": 2 },
+                { "label": "User", "key": "uid", "value": 99 }
+            ]
+        }))?;
+        assert_eq!(
+            summary,
+            json!({
+                "created": 1, "updated": 1, "deleted": 1,
+                "edgesCreated": 0, "edgesUpdated": 0, "edgesDeleted": 0
+            })
+        );
+        assert_eq!(
+            sync_user_names(&db)?,
+            vec![(1, "Ada L.".to_string()), (3, "Alan".to_string())]
+        );
+        Ok(())
+    }
+
+    /// Lists `(src uid, dst uid, since)` for every `FOLLOWS` edge.
+    fn sync_follows(db: &Database) -> Result<Vec<(i64, i64, i64)>> {
+        let uid = |id: u64| -> Result<i64> {
+            let record = db.get_node_record(id)?.expect("user node");
+            Ok(record.properties["uid"].as_i64().unwrap())
+        };
+        let mut rows = Vec::new();
+        for id in db.node_ids_with_label("User")? {
+            for neighbor in db.neighbors_with_options(id, Dir::Out, Some("FOLLOWS"), false)? {
+                let edge = db.get_edge_record(neighbor.edge_id)?.expect("edge");
+                rows.push((
+                    uid(edge.src)?,
+                    uid(edge.dst)?,
+                    edge.properties["since"].as_i64().unwrap(),
+                ));
+            }
+        }
+        rows.sort();
+        Ok(rows)
+    }
+
+    #[test]
+    fn sync_json_reconciles_edges() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("sync_edges.db");
+        let db = Database::open(&path, DatabaseOptions::default())?;
+        let user = |uid: i64| json!({ "label": "User", "key": "uid", "value": uid });
+        let follows = |src: i64, dst: i64, since: i64| {
+            json!({
+                "ty": "FOLLOWS",
+                "src": user(src),
+                "dst": user(dst),
+                "props": { "since": since }
+            })
+        };
+        // Endpoints created by the same sync are found for its edges.
+        let summary = db.sync_json(&json!({
+            "upserts": [
+                { "label": "User", "key": "uid", "props": { "uid": 1, "name": "Ada" } },
+                { "label": "User", "key": "uid", "props": { "uid": 2, "name": "Grace" } },
+                { "label": "User", "key": "uid", "props": { "uid": 3, "name": "Alan" } }
+            ],
+            "edgeUpserts": [follows(1, 2, 2020), follows(2, 3, 2021)]
+        }))?;
+        assert_eq!(summary["created"], json!(3));
+        assert_eq!(summary["edgesCreated"], json!(2));
+        assert_eq!(sync_follows(&db)?, vec![(1, 2, 2020), (2, 3, 2021)]);
+
+        let summary = db.sync_json(&json!({
+            "edgeUpserts": [follows(1, 2, 2024), follows(3, 1, 2022)],
+            "edgeDeletes": [
+                { "ty": "FOLLOWS", "src": user(2), "dst": user(3) },
+                { "ty": "FOLLOWS", "src": user(9), "dst": user(1) }
+            ]
+        }))?;
+        assert_eq!(summary["edgesCreated"], json!(1));
+        assert_eq!(summary["edgesUpdated"], json!(1));
+        assert_eq!(summary["edgesDeleted"], json!(1));
+        assert_eq!(sync_follows(&db)?, vec![(1, 2, 2024), (3, 1, 2022)]);
+
+        let err = db
+            .sync_json(&json!({ "edgeUpserts": [follows(1, 9, 2025)] }))
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("User.uid=9 matches no node"),
+            "{err}"
+        );
+        assert_eq!(sync_follows(&db)?, vec![(1, 2, 2024), (3, 1, 2022)]);
+        Ok(())
+    }
+
+    #[test]
+    fn sync_json_sees_nodes_created_earlier_in_the_sync() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("sync_same_tx.db");
+        let db = Database::open(&path, DatabaseOptions::default())?;
+        let summary = db.sync_json(&json!({
+            "upserts": [
+                { "label": "User", "key": "uid", "props": { "uid": 1, "email": "ada@x", "name": "Ada" } },
+                { "label": "User", "key": "email", "props": { "email": "ada@x", "name": "Ada L." } },
+                { "label": "User", "key": "uid", "props": { "uid": 2, "email": "grace@x", "name": "Grace" } }
+            ],
+            "deletes": [{ "label": "User", "key": "email", "value": "grace@x" }]
+        }))?;
+        // The email upsert and delete found the nodes the uid upserts created
+        // instead of missing them in the committed snapshot.
+        assert_eq!(summary["created"], json!(2));
+        assert_eq!(summary["updated"], json!(1));
+        assert_eq!(summary["deleted"], json!(1));
+        assert_eq!(sync_user_names(&db)?, vec![(1, "Ada L.".to_string())]);
+        Ok(())
+    }
+
+    #[test]
+    fn sync_json_rolls_back_on_failure() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("sync_json_rollback.db");
+        let db = Database::open(&path, DatabaseOptions::default())?;
+        db.sync_json(&json!({
+            "upserts": [
+                { "label": "User", "key": "uid", "props": { "uid": 1, "name": "Ada" } }
+            ]
+        }))?;
+        let before = sync_user_names(&db)?;
+
+        let err = db
+            .sync_json(&json!({
+                "upserts": [
+                    { "label": "User", "key": "uid", "props": { "uid": 1, "name": "Changed" } },
+                    { "label": "User", "key": "uid", "props": { "uid": 5, "name": "New" } },
+                    { "label": "User", "key": "uid", "props": { "name": "Missing key" } }
+                ],
+                "deletes": [{ "label": "User", "key": "uid", "value": 1 }]
+            }))
+            .unwrap_err();
+        assert!(err.to_string().contains("missing key property"));
+        assert_eq!(sync_user_names(&db)?, before);
+        Ok(())
+    }
+
+    #[test]
+    fn dedup_by_keeps_first_row_per_column_value() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let db = Database::open(dir.path().join("dedup_by.db"), DatabaseOptions::default())?;
+        let edge = |src: &str, dst: &str| {
+            json!({
+                "src": { "kind": "alias", "alias": src },
+                "ty": "TO",
+                "dst": { "kind": "alias", "alias": dst }
+            })
+        };
+        let mut edges = Vec::new();
+        for mid in ["$m1", "$m2", "$m3"] {
+            edges.push(edge("$root", mid));
+            edges.push(edge(mid, "$leaf"));
+        }
+        db.create_json(&json!({
+            "nodes": [
+                { "labels": ["Root"], "props": { "name": "root" }, "alias": "$root" },
+                { "labels": ["Mid"], "props": { "name": "m1" }, "alias": "$m1" },
+                { "labels": ["Mid"], "props": { "name": "m2" }, "alias": "$m2" },
+                { "labels": ["Mid"], "props": { "name": "m3" }, "alias": "$m3" },
+                { "labels": ["Leaf"], "props": { "name": "leaf" }, "alias": "$leaf" }
+            ],
+            "edges": edges
+        }))?;
+        let query = |dedup_by: Value| {
+            db.execute_json(&json!({
+                "$schemaVersion": 1,
+                "matches": [
+                    { "var": "r", "label": "Root" },
+                    { "var": "m", "label": "Mid" },
+                    { "var": "l", "label": "Leaf" }
+                ],
+                "edges": [
+                    { "from": "r", "to": "m", "edgeType": "TO" },
+                    { "from": "m", "to": "l", "edgeType": "TO" }
+                ],
+                "projections": [
+                    { "kind": "var", "var": "l", "alias": "leaf" },
+                    { "kind": "prop", "var": "m", "prop": "name", "alias": "via" }
+                ],
+                "dedupBy": dedup_by
+            }))
+        };
+
+        let all = query(json!([]))?;
+        let all_rows = all["rows"].as_array().expect("rows");
+        assert_eq!(all_rows.len(), 3);
+
+        let deduped = query(json!(["leaf"]))?;
+        let rows = deduped["rows"].as_array().expect("rows");
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0], all_rows[0]);
+
+        let per_path = query(json!(["leaf", "via"]))?;
+        assert_eq!(per_path["rows"], all["rows"]);
+
+        let err = query(json!(["missing"])).unwrap_err();
+        assert!(err.to_string().contains("dedupBy"));
+        Ok(())
+    }
+
+    #[test]
+    fn flatten_returns_bare_values_for_single_column() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let db = Database::open(dir.path().join("flatten.db"), DatabaseOptions::default())?;
+        db.create_json(&json!({
+            "nodes": [
+                { "labels": ["Item"], "props": { "n": 1, "tag": "a" } },
+                { "labels": ["Item"], "props": { "n": 2, "tag": "b" } },
+                { "labels": ["Item"], "props": { "n": 3, "tag": "c" } }
+            ],
+            "edges": []
+        }))?;
+        let query = |projections: Value| {
+            db.execute_json(&json!({
+                "$schemaVersion": 1,
+                "matches": [{ "var": "i", "label": "Item" }],
+                "projections": projections,
+                "flatten": true
+            }))
+        };
+
+        let single = query(json!([{ "kind": "prop", "var": "i", "prop": "n", "alias": "n" }]))?;
+        let mut values: Vec<i64> = single["rows"]
+            .as_array()
+            .expect("rows")
+            .iter()
+            .map(|value| value.as_i64().expect("bare integer"))
+            .collect();
+        values.sort_unstable();
+        assert_eq!(values, vec![1, 2, 3]);
+
+        let err = query(json!([
+            { "kind": "prop", "var": "i", "prop": "n", "alias": "n" },
+            { "kind": "prop", "var": "i", "prop": "tag", "alias": "tag" }
+        ]))
+        .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("flatten requires exactly one result column"));
+        Ok(())
+    }
+
+    #[test]
+    fn count_per_start_tallies_rows_by_driving_node() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let db = Database::open(
+            dir.path().join("count_per_start.db"),
+            DatabaseOptions::default(),
+        )?;
+        let follows = |src: &str, dst: &str| {
+            json!({
+                "src": { "kind": "alias", "alias": src },
+                "ty": "FOLLOWS",
+                "dst": { "kind": "alias", "alias": dst },
+                "props": {}
+            })
+        };
+        let created = db.create_json(&json!({
+            "nodes": [
+                { "labels": ["User"], "props": { "name": "ada" }, "alias": "$ada" },
+                { "labels": ["User"], "props": { "name": "bob" }, "alias": "$bob" },
+                { "labels": ["User"], "props": { "name": "cy" }, "alias": "$cy" },
+                { "labels": ["User"], "props": { "name": "dee" }, "alias": "$dee" }
+            ],
+            "edges": [
+                follows("$ada", "$bob"),
+                follows("$ada", "$cy"),
+                follows("$ada", "$dee"),
+                follows("$bob", "$cy"),
+                follows("$cy", "$ada")
+            ]
+        }))?;
+        let id = |alias: &str| created["aliases"][alias].as_u64().expect("alias id");
+        let spec = json!({
+            "$schemaVersion": 1,
+            "matches": [
+                { "var": "a", "label": "User" },
+                { "var": "b", "label": "User" }
+            ],
+            "edges": [{ "from": "a", "to": "b", "edgeType": "FOLLOWS" }],
+            "projections": [{ "kind": "prop", "var": "b", "prop": "name" }]
+        });
+        let rows = db.execute_json(&spec)?;
+        let total = rows["rows"].as_array().expect("rows").len() as u64;
+
+        let mut counted = spec.clone();
+        counted["countPerStart"] = json!(true);
+        let result = db.execute_json(&counted)?;
+        assert!(result.get("rows").is_none());
+        let counts = result["counts"].as_object().expect("counts");
+        let expected: Map<String, Value> = [("$ada", 3), ("$bob", 1), ("$cy", 1)]
+            .into_iter()
+            .map(|(alias, count)| (id(alias).to_string(), json!(count)))
+            .collect();
+        assert_eq!(counts, &expected);
+        let sum: u64 = counts.values().map(|count| count.as_u64().unwrap()).sum();
+        assert_eq!(sum, total);
+
+        counted["flatten"] = json!(true);
+        assert!(db.execute_json(&counted).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn order_by_sorts_rows_with_nulls_last() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let db = Database::open(dir.path().join("order_by.db"), DatabaseOptions::default())?;
+        db.create_json(&json!({
+            "nodes": [
+                { "labels": ["User"], "props": { "name": "ada", "age": 31, "team": "red" } },
+                { "labels": ["User"], "props": { "name": "bob", "team": "blue" } },
+                { "labels": ["User"], "props": { "name": "cy", "age": 24, "team": "red" } },
+                { "labels": ["User"], "props": { "name": "dee", "age": null, "team": "blue" } },
+                { "labels": ["User"], "props": { "name": "eve", "age": 31, "team": "blue" } }
+            ]
+        }))?;
+        let names = |order_by: Value| -> Result<Vec<String>> {
+            let rows = db.execute_json(&json!({
+                "$schemaVersion": 1,
+                "matches": [{ "var": "u", "label": "User" }],
+                "projections": [{ "kind": "prop", "var": "u", "prop": "name" }],
+                "orderBy": order_by
+            }))?;
+            Ok(rows["rows"]
+                .as_array()
+                .expect("rows")
+                .iter()
+                .map(|row| row["name"].as_str().expect("name").to_owned())
+                .collect())
+        };
+
+        let ascending = names(json!([{ "var": "u", "prop": "age" }]))?;
+        assert_eq!(&ascending[..3], ["cy", "ada", "eve"]);
+        let descending = names(json!([{ "var": "u", "prop": "age", "desc": true }]))?;
+        assert_eq!(&descending[..3], ["eve", "ada", "cy"]);
+        for tail in [&ascending[3..], &descending[3..]] {
+            let mut tail = tail.to_vec();
+            tail.sort();
+            assert_eq!(tail, ["bob", "dee"]);
+        }
+
+        let tie_broken = names(json!([
+            { "var": "u", "prop": "age", "desc": true },
+            { "var": "u", "prop": "team" },
+            { "var": "u", "prop": "name", "desc": true }
+        ]))?;
+        assert_eq!(tie_broken, ["eve", "ada", "cy", "dee", "bob"]);
+
+        let explain = db.explain_json(&json!({
+            "$schemaVersion": 1,
+            "matches": [{ "var": "u", "label": "User" }],
+            "orderBy": [{ "var": "u", "prop": "age", "desc": true }]
+        }))?;
+        assert!(explain.to_string().contains("\"OrderBy\""));
+        assert!(explain.to_string().contains("u.age DESC"));
+        Ok(())
+    }
+
+    #[test]
+    fn order_by_breaks_ties_on_node_id() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let db = Database::open(dir.path().join("tiebreak.db"), DatabaseOptions::default())?;
+        let nodes: Vec<Value> = (0..64)
+            .map(|i| json!({ "labels": ["User"], "props": { "team": "red", "n": i } }))
+            .collect();
+        db.create_json(&json!({ "nodes": nodes }))?;
+        let ids = |extra: Value| -> Result<Vec<u64>> {
+            let mut spec = json!({
+                "$schemaVersion": 1,
+                "matches": [{ "var": "u", "label": "User" }],
+                "projections": [{ "kind": "var", "var": "u" }],
+                "orderBy": [{ "var": "u", "prop": "team", "desc": true }]
+            });
+            if let (Some(spec), Some(extra)) = (spec.as_object_mut(), extra.as_object()) {
+                spec.extend(extra.clone());
+            }
+            let rows = db.execute_json(&spec)?;
+            Ok(rows["rows"]
+                .as_array()
+                .expect("rows")
+                .iter()
+                .map(|row| row["u"]["_id"].as_u64().expect("id"))
+                .collect())
+        };
+
+        let first = ids(json!({}))?;
+        assert_eq!(first.len(), 64);
+        for _ in 0..3 {
+            assert_eq!(ids(json!({}))?, first);
+        }
+        let mut descending = first.clone();
+        descending.sort_unstable_by(|a, b| b.cmp(a));
+        assert_eq!(first, descending, "tiebreak follows the last key");
+
+        let ascending = ids(json!({ "tiebreakDesc": false }))?;
+        let mut expected = first.clone();
+        expected.reverse();
+        assert_eq!(ascending, expected);
+
+        let paged: Vec<u64> = (0..4)
+            .map(|page| ids(json!({ "limit": 16, "offset": page * 16 })))
+            .collect::<Result<Vec<_>>>()?
+            .concat();
+        assert_eq!(paged, first);
+        Ok(())
+    }
+
+    #[test]
+    fn bytes_format_controls_result_encoding() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let db = Database::open(dir.path().join("bytes.db"), DatabaseOptions::default())?;
+        let blob: Vec<u8> = (0..1024).map(|i| (i * 7 % 256) as u8).collect();
+        db.create_typed_batch(&TypedBatchSpec {
+            nodes: vec![TypedNodeSpec {
+                label: "File".into(),
+                props: vec![TypedPropEntry {
+                    key: "data".into(),
+                    kind: "bytes".into(),
+                    bool_value: None,
+                    int_value: None,
+                    float_value: None,
+                    string_value: None,
+                    bytes_value: Some(BASE64.encode(&blob)),
+                }],
+                alias: None,
+            }],
+            edges: Vec::new(),
+        })?;
+        let query = |format: &str, literal: Value| {
+            db.execute_json(&json!({
+                "$schemaVersion": 1,
+                "matches": [{ "var": "f", "label": "File" }],
+                "predicate": {
+                    "op": "eq", "var": "f", "prop": "data",
+                    "value": { "t": "Bytes", "v": literal }
+                },
+                "projections": [{ "kind": "prop", "var": "f", "prop": "data" }],
+                "bytesFormat": format
+            }))
+        };
+
+        let base64 = query("base64", json!(BASE64.encode(&blob)))?;
+        let encoded = base64["rows"][0]["data"].as_str().expect("base64 string");
+        assert_eq!(BASE64.decode(encoded).unwrap(), blob);
+        // Literals decode the same whichever format they arrive in.
+        let array = query("array", json!(blob))?;
+        let octets: Vec<u8> = serde_json::from_value(array["rows"][0]["data"].clone()).unwrap();
+        assert_eq!(octets, blob);
+
+        let base64_len = base64.to_string().len();
+        let array_len = array.to_string().len();
+        assert!(
+            base64_len * 2 < array_len,
+            "base64 payload {base64_len} bytes vs array payload {array_len} bytes"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn limit_and_offset_bound_results_and_streams() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let db = Database::open(dir.path().join("limit.db"), DatabaseOptions::default())?;
+        let nodes: Vec<Value> = (0..20)
+            .map(|i| json!({ "labels": ["User"], "props": { "age": i } }))
+            .collect();
+        db.create_json(&json!({ "nodes": nodes }))?;
+        let spec = |extra: Value| {
+            let mut spec = json!({
+                "$schemaVersion": 1,
+                "matches": [{ "var": "u", "label": "User" }],
+                "projections": [{ "kind": "prop", "var": "u", "prop": "age" }]
+            });
+            for (key, value) in extra.as_object().expect("object") {
+                spec[key] = value.clone();
+            }
+            spec
+        };
+        let ages = |extra: Value| -> Result<Vec<i64>> {
+            let rows = db.execute_json(&spec(extra))?;
+            Ok(rows["rows"]
+                .as_array()
+                .expect("rows")
+                .iter()
+                .map(|row| row["age"].as_i64().expect("age"))
+                .collect())
+        };
+
+        assert_eq!(ages(json!({ "limit": 3 }))?.len(), 3);
+        let sorted = ages(json!({
+            "orderBy": [{ "var": "u", "prop": "age", "desc": true }],
+            "limit": 3,
+            "offset": 2
+        }))?;
+        assert_eq!(sorted, [17, 16, 15]);
+        assert_eq!(ages(json!({ "offset": 18 }))?.len(), 2);
+        assert!(ages(json!({ "offset": 25 }))?.is_empty());
+
+        let stream = db.stream_json(&spec(json!({ "limit": 4 })))?;
+        let mut streamed = 0;
+        while stream.next()?.is_some() {
+            streamed += 1;
+        }
+        assert_eq!(streamed, 4);
+
+        let explain = db.explain_json(&spec(json!({ "limit": 4, "offset": 1 })))?;
+        let explain = explain.to_string();
+        assert!(explain.contains("\"Limit\""), "{explain}");
+        assert!(explain.contains("\"offset\""), "{explain}");
+        Ok(())
+    }
+
+    #[test]
+    fn aggregate_projections_fold_rows_into_one() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let db = Database::open(dir.path().join("agg.db"), DatabaseOptions::default())?;
+        db.create_json(&json!({
+            "nodes": [
+                { "labels": ["User"], "props": { "age": 20, "score": 1.5 } },
+                { "labels": ["User"], "props": { "age": 30, "score": 2.5 } },
+                { "labels": ["User"], "props": { "age": 40 } },
+                { "labels": ["User"], "props": { "name": "ada" } }
+            ]
+        }))?;
+        let aggregate = |func: &str, prop: Option<&str>| {
+            json!({
+                "kind": "aggregate",
+                "func": func,
+                "var": "u",
+                "prop": prop,
+                "alias": func
+            })
+        };
+        let spec = json!({
+            "$schemaVersion": 1,
+            "matches": [{ "var": "u", "label": "User" }],
+            "projections": [
+                aggregate("count", None),
+                aggregate("sum", Some("age")),
+                aggregate("avg", Some("age")),
+                aggregate("min", Some("age")),
+                aggregate("max", Some("score"))
+            ]
+        });
+        let result = db.execute_json(&spec)?;
+        let rows = result["rows"].as_array().expect("rows");
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0]["count"], json!(4));
+        assert_eq!(rows[0]["sum"], json!(90));
+        assert_eq!(rows[0]["avg"], json!(30.0));
+        assert_eq!(rows[0]["min"], json!(20));
+        assert_eq!(rows[0]["max"], json!(2.5));
+
+        let mut empty = spec.clone();
+        empty["predicate"] =
+            json!({ "op": "gt", "var": "u", "prop": "age", "value": { "t": "Int", "v": 100 } });
+        let result = db.execute_json(&empty)?;
+        let rows = result["rows"].as_array().expect("rows");
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0]["count"], json!(0));
+        assert_eq!(rows[0]["sum"], json!(0));
+        assert!(rows[0]["avg"].is_null());
+
+        let mut mixed = spec.clone();
+        mixed["projections"]
+            .as_array_mut()
+            .expect("projections")
+            .push(json!({ "kind": "prop", "var": "u", "prop": "age" }));
+        let err = db.execute_json(&mixed).unwrap_err();
+        assert!(matches!(
+            err,
+            FfiError::Analyzer(AnalyzerError::MixedAggregateProjection { .. })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn expr_projections_compute_typed_values() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let db = Database::open(dir.path().join("expr.db"), DatabaseOptions::default())?;
+        db.create_json(&json!({
+            "nodes": [
+                { "labels": ["User"], "props": { "first": "Ada", "last": "Lovelace", "age": 36 } },
+                { "labels": ["User"], "props": { "first": "Alan", "last": "Turing" } }
+            ]
+        }))?;
+        let spec = |projections: Value| {
+            json!({
+                "$schemaVersion": 1,
+                "matches": [{ "var": "u", "label": "User" }],
+                "projections": projections,
+                "orderBy": [{ "var": "u", "prop": "first" }]
+            })
+        };
+        let result = db.execute_json(&spec(json!([
+            { "kind": "expr", "expr": "age * 2", "alias": "doubled" },
+            { "kind": "expr", "expr": "first + ' ' + last", "alias": "full" },
+            { "kind": "expr", "expr": "u.age / 5.0 >= 7" }
+        ])))?;
+        let rows = result["rows"].as_array().expect("rows");
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["doubled"], json!(72));
+        assert_eq!(rows[0]["full"], json!("Ada Lovelace"));
+        assert_eq!(rows[0]["u.age / 5.0 >= 7"], json!(true));
+        assert!(rows[1]["doubled"].is_null());
+        assert_eq!(rows[1]["full"], json!("Alan Turing"));
+
+        let err = db
+            .execute_json(&spec(json!([
+                { "kind": "expr", "expr": "upper(first)", "alias": "loud" }
+            ])))
+            .unwrap_err();
+        let FfiError::Analyzer(AnalyzerError::InvalidExpression { reason, .. }) = err else {
+            panic!("unexpected error: {err:?}");
+        };
+        assert_eq!(reason, "unknown function 'upper'");
+
+        let err = db
+            .execute_json(&spec(json!([
+                { "kind": "expr", "expr": "first * 2", "alias": "bad" }
+            ])))
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("cannot apply '*' to string and int"),
+            "{err}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn limit_per_group_keeps_top_rows_per_binding() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let db = Database::open(dir.path().join("group.db"), DatabaseOptions::default())?;
+        let posted = |src: &str, dst: String| {
+            json!({
+                "src": { "kind": "alias", "alias": src },
+                "ty": "POSTED",
+                "dst": { "kind": "alias", "alias": dst },
+                "props": {}
+            })
+        };
+        let mut nodes = Vec::new();
+        let mut edges = Vec::new();
+        for (user, posts) in [("ada", 0), ("bob", 1), ("cy", 2), ("di", 5)] {
+            let alias = format!("${user}");
+            nodes.push(json!({ "labels": ["User"], "props": { "name": user }, "alias": alias }));
+            for score in 0..posts {
+                let post = format!("${user}{score}");
+                let props = json!({ "score": score });
+                nodes.push(json!({ "labels": ["Post"], "props": props, "alias": post }));
+                edges.push(posted(&alias, post));
+            }
+        }
+        db.create_json(&json!({ "nodes": nodes, "edges": edges }))?;
+        let spec = json!({
+            "$schemaVersion": 1,
+            "matches": [
+                { "var": "u", "label": "User" },
+                { "var": "p", "label": "Post" }
+            ],
+            "edges": [{ "from": "u", "to": "p", "edgeType": "POSTED" }],
+            "projections": [
+                { "kind": "prop", "var": "u", "prop": "name" },
+                { "kind": "prop", "var": "p", "prop": "score" }
+            ],
+            "limitPerGroup": {
+                "groupBy": "u",
+                "n": 3,
+                "orderBy": [{ "var": "p", "prop": "score", "desc": true }]
+            }
+        });
+        let result = db.execute_json(&spec)?;
+        let mut per_user: BTreeMap<String, Vec<i64>> = BTreeMap::new();
+        for row in result["rows"].as_array().expect("rows") {
+            per_user
+                .entry(row["name"].as_str().expect("name").to_string())
+                .or_default()
+                .push(row["score"].as_i64().expect("score"));
+        }
+        let expected: BTreeMap<String, Vec<i64>> = [
+            ("bob".to_string(), vec![0]),
+            ("cy".to_string(), vec![1, 0]),
+            ("di".to_string(), vec![4, 3, 2]),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(per_user, expected);
+
+        let mut capped = spec.clone();
+        capped["limit"] = json!(2);
+        let rows = db.execute_json(&capped)?;
+        assert_eq!(rows["rows"].as_array().expect("rows").len(), 2);
+
+        let mut blank = spec;
+        blank["limitPerGroup"]["groupBy"] = json!("");
+        assert!(db.execute_json(&blank).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn in_list_uses_property_index_and_matches_scan() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let db = Database::open(dir.path().join("in.db"), DatabaseOptions::default())?;
+        let statuses = ["active", "pending", "closed", "banned"];
+        let nodes: Vec<Value> = (0..40)
+            .map(|i| {
+                let props = json!({ "seq": i, "status": statuses[i % statuses.len()] });
+                json!({ "labels": ["User"], "props": props })
+            })
+            .collect();
+        db.create_json(&json!({ "nodes": nodes }))?;
+        // Longer than the union fan-out so the list becomes one index probe.
+        let mut wanted: Vec<Value> = vec![json!({ "t": "String", "v": "active" })];
+        wanted.extend((0..11).map(|i| json!({ "t": "String", "v": format!("pending{i}") })));
+        wanted.push(json!({ "t": "String", "v": "pending" }));
+        let spec = json!({
+            "$schemaVersion": 1,
+            "matches": [{ "var": "u", "label": "User" }],
+            "predicate": { "op": "in", "var": "u", "prop": "status", "values": wanted },
+            "projections": [{ "kind": "prop", "var": "u", "prop": "seq" }]
+        });
+        let seqs = |spec: &Value| -> Result<Vec<i64>> {
+            let mut seqs: Vec<i64> = db.execute_json(spec)?["rows"]
+                .as_array()
+                .expect("rows")
+                .iter()
+                .map(|row| row["seq"].as_i64().expect("seq"))
+                .collect();
+            seqs.sort_unstable();
+            Ok(seqs)
+        };
+        let expected: Vec<i64> = (0..40).filter(|i| i % 4 < 2).collect();
+
+        let scanned = seqs(&spec)?;
+        assert_eq!(scanned, expected);
+        let explain = db.explain_json(&spec)?.to_string();
+        assert!(!explain.contains("PropIndexScan"), "{explain}");
+
+        db.ensure_property_index("User", "status", "btree", "string")?;
+        assert_eq!(seqs(&spec)?, expected);
+        let explain = db.explain_json(&spec)?.to_string();
+        assert!(explain.contains("PropIndexScan"), "{explain}");
+        assert!(explain.contains("u.status IN"), "{explain}");
+        Ok(())
+    }
+
+    #[test]
+    fn filter_tree_matches_predicate_and_pushes_index_leaves() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let db = Database::open(dir.path().join("filter.db"), DatabaseOptions::default())?;
+        let cities = ["NYC", "SF", "LA", "BOS"];
+        let nodes: Vec<Value> = (0..40)
+            .map(|i| {
+                let props = json!({ "age": i, "city": cities[i % cities.len()] });
+                json!({ "labels": ["User"], "props": props })
+            })
+            .collect();
+        db.create_json(&json!({ "nodes": nodes }))?;
+        db.ensure_property_index("User", "city", "btree", "string")?;
+        let int = |v: i64| json!({ "t": "Int", "v": v });
+        let city = |v: &str| {
+            let value = json!({ "t": "String", "v": v });
+            json!({ "op": "eq", "var": "u", "prop": "city", "value": value })
+        };
+        let query = |extra: Value| {
+            let mut spec = json!({
+                "$schemaVersion": 1,
+                "matches": [{ "var": "u", "label": "User" }],
+                "projections": [{ "kind": "prop", "var": "u", "prop": "age" }]
+            });
+            spec.as_object_mut()
+                .unwrap()
+                .extend(extra.as_object().unwrap().clone());
+            spec
+        };
+        let ages = |spec: &Value| -> Result<Vec<i64>> {
+            let mut ages: Vec<i64> = db.execute_json(spec)?["rows"]
+                .as_array()
+                .expect("rows")
+                .iter()
+                .map(|row| row["age"].as_i64().expect("age"))
+                .collect();
+            ages.sort_unstable();
+            Ok(ages)
+        };
+
+        let older = json!({ "op": "gt", "var": "u", "prop": "age", "value": int(30) });
+        let either = query(json!({ "filter": { "or": [older, city("NYC")] } }));
+        let expected: Vec<i64> = (0..40).filter(|i| *i > 30 || i % 4 == 0).collect();
+        assert_eq!(ages(&either)?, expected);
+        let tagged = query(json!({ "predicate": { "op": "or", "args": [older, city("NYC")] } }));
+        assert_eq!(ages(&tagged)?, expected);
+
+        let young = json!({ "op": "lt", "var": "u", "prop": "age", "value": int(20) });
+        let sf_adults = query(json!({ "filter": { "and": [city("SF"), { "not": young }] } }));
+        let expected: Vec<i64> = (20..40).filter(|i| i % 4 == 1).collect();
+        assert_eq!(ages(&sf_adults)?, expected);
+        let explain = db.explain_json(&sf_adults)?.to_string();
+        assert!(explain.contains("PropIndexScan"), "{explain}");
+
+        // A flat predicate and a filter tree are conjoined.
+        let adults = json!({ "op": "ge", "var": "u", "prop": "age", "value": int(10) });
+        let both = query(json!({ "predicate": city("LA"), "filter": { "not": adults } }));
+        assert_eq!(ages(&both)?, vec![2, 6]);
+        Ok(())
+    }
+
+    #[test]
+    fn prepared_query_binds_params_into_cached_plan() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let db = Database::open(dir.path().join("prepared.db"), DatabaseOptions::default())?;
+        let nodes: Vec<Value> = (0..20)
+            .map(|i| {
+                let props = json!({ "name": format!("u{}", i % 5), "age": i });
+                json!({ "labels": ["User"], "props": props })
+            })
+            .collect();
+        db.create_json(&json!({ "nodes": nodes }))?;
+        db.ensure_property_index("User", "name", "btree", "string")?;
+        let param = |name: &str| json!({ "t": "Param", "v": name });
+        let spec = json!({
+            "$schemaVersion": 1,
+            "matches": [{ "var": "u", "label": "User" }],
+            "filter": { "and": [
+                { "op": "eq", "var": "u", "prop": "name", "value": param("name") },
+                { "op": "between", "var": "u", "prop": "age", "low": param("lo"), "high": param("hi") }
+            ] },
+            "projections": [{ "kind": "prop", "var": "u", "prop": "age" }]
+        });
+        let prepared = db.prepare_json(&spec)?;
+        assert_eq!(
+            prepared.params().collect::<Vec<_>>(),
+            vec!["hi", "lo", "name"]
+        );
+        let ages = |params: Value| -> Result<Vec<i64>> {
+            let params = params.as_object().expect("params object").clone();
+            let mut ages: Vec<i64> = prepared.execute(&db, &params)?["rows"]
+                .as_array()
+                .expect("rows")
+                .iter()
+                .map(|row| row["age"].as_i64().expect("age"))
+                .collect();
+            ages.sort_unstable();
+            Ok(ages)
+        };
+        let int = |v: i64| json!({ "t": "Int", "v": v });
+        let name = |v: &str| json!({ "t": "String", "v": v });
+
+        assert_eq!(
+            ages(json!({ "name": name("u2"), "lo": int(0), "hi": int(19) }))?,
+            vec![2, 7, 12, 17]
+        );
+        assert_eq!(
+            ages(json!({ "name": name("u3"), "lo": int(5), "hi": int(13) }))?,
+            vec![8, 13]
+        );
+        // A new index bumps the catalog epoch, so the plan is rebuilt.
+        db.ensure_property_index("User", "age", "btree", "int")?;
+        assert_eq!(
+            ages(json!({ "name": name("u3"), "lo": int(5), "hi": int(13) }))?,
+            vec![8, 13]
+        );
+
+        let err = ages(json!({ "name": name("u3"), "lo": int(0) })).expect_err("missing hi");
+        assert!(
+            err.to_string().contains("missing value for parameter 'hi'"),
+            "{err}"
+        );
+        let err = ages(json!({ "name": name("u3"), "lo": int(0), "hi": int(1), "x": int(1) }))
+            .expect_err("unknown parameter");
+        assert!(err.to_string().contains("unknown parameter 'x'"), "{err}");
+        let err = ages(
+            json!({ "name": name("u3"), "lo": json!({ "t": "Bool", "v": true }), "hi": int(1) }),
+        )
+        .expect_err("unorderable bound");
+        assert!(err.to_string().contains("ge()"), "{err}");
+        let err = db.execute_json(&spec).expect_err("params need prepare");
+        assert!(
+            err.to_string().contains("only valid in prepared queries"),
+            "{err}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn include_warnings_reports_full_scans_and_truncation() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let db = Database::open(dir.path().join("warn.db"), DatabaseOptions::default())?;
+        let nodes: Vec<Value> = (0..10)
+            .map(|i| {
+                let props = json!({ "name": format!("u{i}"), "age": i });
+                json!({ "labels": ["User"], "props": props })
+            })
+            .collect();
+        db.create_json(&json!({ "nodes": nodes }))?;
+        db.ensure_property_index("User", "name", "btree", "string")?;
+        let query = |predicate: Value| {
+            json!({
+                "$schemaVersion": 1,
+                "matches": [{ "var": "u", "label": "User" }],
+                "predicate": predicate,
+                "projections": [{ "kind": "prop", "var": "u", "prop": "name" }],
+                "includeWarnings": true
+            })
+        };
+        let codes = |result: &Value| -> Vec<String> {
+            result["warnings"]
+                .as_array()
+                .expect("warnings")
+                .iter()
+                .map(|warning| warning["code"].as_str().expect("code").to_string())
+                .collect()
+        };
+
+        let age = json!({ "op": "gt", "var": "u", "prop": "age", "value": { "t": "Int", "v": 6 } });
+        let scanned = db.execute_json(&query(age))?;
+        assert_eq!(scanned["rows"].as_array().expect("rows").len(), 3);
+        assert_eq!(codes(&scanned), vec!["UnindexedPredicate"]);
+        let message = scanned["warnings"][0]["message"].as_str().expect("message");
+        assert!(message.contains("scans every User node"), "{message}");
+
+        let name = json!({ "op": "eq", "var": "u", "prop": "name", "value": { "t": "String", "v": "u3" } });
+        let probed = db.execute_json(&query(name.clone()))?;
+        assert_eq!(probed["rows"].as_array().expect("rows").len(), 1);
+        assert!(codes(&probed).is_empty());
+
+        let mut capped = query(Value::Null);
+        capped["maxRows"] = json!(2);
+        assert_eq!(codes(&db.execute_json(&capped)?), vec!["Truncated"]);
+
+        let mut quiet = query(name);
+        quiet["includeWarnings"] = json!(false);
+        assert!(db.execute_json(&quiet)?.get("warnings").is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn pattern_size_limit_rejects_oversized_queries() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let opts = DatabaseOptions {
+            max_pattern_edges: Some(1),
+            ..DatabaseOptions::default()
+        };
+        let db = Database::open(dir.path().join("limit.db"), opts)?;
+        db.create_json(&json!({
+            "nodes": [
+                { "labels": ["User"], "props": { "name": "ada" }, "alias": "$ada" },
+                { "labels": ["User"], "props": { "name": "bob" }, "alias": "$bob" }
+            ],
+            "edges": [{
+                "src": { "kind": "alias", "alias": "$ada" },
+                "ty": "FOLLOWS",
+                "dst": { "kind": "alias", "alias": "$bob" },
+                "props": {}
+            }]
+        }))?;
+        let one_hop = json!({
+            "$schemaVersion": 1,
+            "matches": [
+                { "var": "a", "label": "User" },
+                { "var": "b", "label": "User" }
+            ],
+            "edges": [{ "from": "a", "to": "b", "edgeType": "FOLLOWS" }],
+            "projections": [{ "kind": "prop", "var": "b", "prop": "name" }]
+        });
+        let rows = db.execute_json(&one_hop)?;
+        assert_eq!(rows["rows"].as_array().expect("rows").len(), 1);
+
+        // The unknown label would fail resolution; the size check runs first.
+        let two_hop = json!({
+            "$schemaVersion": 1,
+            "matches": [
+                { "var": "a", "label": "User" },
+                { "var": "b", "label": "User" },
+                { "var": "c", "label": "Missing" }
+            ],
+            "edges": [
+                { "from": "a", "to": "b", "edgeType": "FOLLOWS" },
+                { "from": "b", "to": "c", "edgeType": "FOLLOWS" }
+            ],
+            "projections": [{ "kind": "var", "var": "c" }]
+        });
+        let err = db.execute_json(&two_hop).expect_err("too many edges");
+        assert!(
+            matches!(
+                err,
+                FfiError::Analyzer(AnalyzerError::PatternTooLarge {
+                    clause: "edge",
+                    count: 2,
+                    max: 1
+                })
+            ),
+            "{err:?}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn nest_groups_children_under_parents() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let db = Database::open(dir.path().join("nest.db"), DatabaseOptions::default())?;
+        let contains = |src: &str, dst: &str| {
+            json!({
+                "src": { "kind": "alias", "alias": src },
+                "ty": "CONTAINS",
+                "dst": { "kind": "alias", "alias": dst },
+                "props": {}
+            })
+        };
+        db.create_json(&json!({
+            "nodes": [
+                { "labels": ["Folder"], "props": { "name": "docs" }, "alias": "$docs" },
+                { "labels": ["Folder"], "props": { "name": "src" }, "alias": "$src" },
+                { "labels": ["Folder"], "props": { "name": "empty" }, "alias": "$empty" },
+                { "labels": ["File"], "props": { "name": "guide.md" }, "alias": "$guide" },
+                { "labels": ["File"], "props": { "name": "faq.md" }, "alias": "$faq" },
+                { "labels": ["File"], "props": { "name": "lib.rs" }, "alias": "$lib" }
+            ],
+            "edges": [
+                contains("$docs", "$guide"),
+                contains("$docs", "$faq"),
+                contains("$src", "$lib")
+            ]
+        }))?;
+        let query = |predicate: Value| {
+            db.execute_json(&json!({
+                "$schemaVersion": 1,
+                "matches": [
+                    { "var": "d", "label": "Folder" },
+                    { "var": "f", "label": "File" }
+                ],
+                "edges": [{ "from": "d", "to": "f", "edgeType": "CONTAINS" }],
+                "predicate": predicate,
+                "projections": [
+                    { "kind": "prop", "var": "d", "prop": "name", "alias": "folder" },
+                    { "kind": "prop", "var": "f", "prop": "name", "alias": "file" }
+                ],
+                "nest": { "parent": "d", "child": "f", "as": "files" }
+            }))
+        };
+        let grouped = |result: &Value| {
+            let mut groups: Vec<(String, Vec<String>)> = result["rows"]
+                .as_array()
+                .expect("rows")
+                .iter()
+                .map(|row| {
+                    let mut files: Vec<String> = row["files"]
+                        .as_array()
+                        .expect("files array")
+                        .iter()
+                        .map(|file| file.as_str().expect("file name").to_string())
+                        .collect();
+                    files.sort();
+                    (row["folder"].as_str().expect("folder").to_string(), files)
+                })
+                .collect();
+            groups.sort();
+            groups
+        };
+
+        let all = query(Value::Null)?;
+        assert_eq!(
+            grouped(&all),
+            vec![
+                (
+                    "docs".to_string(),
+                    vec!["faq.md".to_string(), "guide.md".to_string()]
+                ),
+                ("empty".to_string(), Vec::new()),
+                ("src".to_string(), vec!["lib.rs".to_string()]),
+            ]
+        );
+
+        // Parent-only conjuncts also filter the childless parents.
+        let filtered = query(json!({
+            "op": "and",
+            "args": [
+                { "op": "ne", "var": "d", "prop": "name", "value": { "t": "String", "v": "empty" } },
+                { "op": "ne", "var": "f", "prop": "name", "value": { "t": "String", "v": "faq.md" } }
+            ]
+        }))?;
+        assert_eq!(
+            grouped(&filtered),
+            vec![
+                ("docs".to_string(), vec!["guide.md".to_string()]),
+                ("src".to_string(), vec!["lib.rs".to_string()]),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn edge_exists_keeps_users_with_qualifying_post() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let db = Database::open(
+            dir.path().join("edge_exists.db"),
+            DatabaseOptions::default(),
+        )?;
+        let authored = |src: &str, dst: &str| {
+            json!({
+                "src": { "kind": "alias", "alias": src },
+                "ty": "AUTHORED",
+                "dst": { "kind": "alias", "alias": dst },
+                "props": {}
+            })
+        };
+        db.create_json(&json!({
+            "nodes": [
+                { "labels": ["User"], "props": { "name": "ada" }, "alias": "$ada" },
+                { "labels": ["User"], "props": { "name": "bob" }, "alias": "$bob" },
+                { "labels": ["User"], "props": { "name": "cy" }, "alias": "$cy" },
+                { "labels": ["User"], "props": { "name": "dee" }, "alias": "$dee" },
+                { "labels": ["Post"], "props": { "likes": 150 }, "alias": "$p1" },
+                { "labels": ["Post"], "props": { "likes": 5 }, "alias": "$p2" },
+                { "labels": ["Post"], "props": { "likes": 50 }, "alias": "$p3" },
+                { "labels": ["Post"], "props": { "likes": 101 }, "alias": "$p4" },
+                { "labels": ["Comment"], "props": { "likes": 500 }, "alias": "$c1" }
+            ],
+            "edges": [
+                authored("$ada", "$p1"),
+                authored("$ada", "$p2"),
+                authored("$bob", "$p3"),
+                authored("$bob", "$c1"),
+                authored("$cy", "$p4")
+            ]
+        }))?;
+        let popular = json!({
+            "op": "edgeExists",
+            "fromVar": "u",
+            "edgeType": "AUTHORED",
+            "direction": "out",
+            "target": { "var": "p", "label": "Post" },
+            "where": { "op": "gt", "var": "p", "prop": "likes", "value": { "t": "Int", "v": 100 } }
+        });
+        let names = |predicate: Value| -> Result<Vec<String>> {
+            let result = db.execute_json(&json!({
+                "$schemaVersion": 1,
+                "matches": [{ "var": "u", "label": "User" }],
+                "predicate": predicate,
+                "projections": [{ "kind": "prop", "var": "u", "prop": "name", "alias": "name" }]
+            }))?;
+            let mut names: Vec<String> = result["rows"]
+                .as_array()
+                .expect("rows")
+                .iter()
+                .map(|row| row["name"].as_str().expect("name").to_string())
+                .collect();
+            names.sort();
+            Ok(names)
+        };
+
+        // bob's only popular item is a comment, which the target label excludes.
+        assert_eq!(names(popular.clone())?, vec!["ada", "cy"]);
+        assert_eq!(
+            names(json!({ "op": "not", "args": [popular] }))?,
+            vec!["bob", "dee"]
+        );
+        // Without a nested predicate any labelled neighbor qualifies.
+        assert_eq!(
+            names(json!({
+                "op": "edgeExists",
+                "fromVar": "u",
+                "edgeType": "AUTHORED",
+                "target": { "var": "p", "label": "Post" }
+            }))?,
+            vec!["ada", "bob", "cy"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn edge_exists_target_is_scoped_to_its_predicate() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let db = Database::open(
+            dir.path().join("edge_exists_scope.db"),
+            DatabaseOptions::default(),
+        )?;
+        db.create_json(&json!({
+            "nodes": [
+                { "labels": ["User"], "props": { "name": "ada" } },
+                { "labels": ["Post"], "props": { "likes": 1 } }
+            ],
+            "edges": []
+        }))?;
+        let query = |predicate: Value| {
+            db.execute_json(&json!({
+                "$schemaVersion": 1,
+                "matches": [{ "var": "u", "label": "User" }],
+                "predicate": predicate,
+                "projections": [{ "kind": "var", "var": "u" }]
+            }))
+        };
+        let leaked = query(json!({
+            "op": "and",
+            "args": [
+                {
+                    "op": "edgeExists",
+                    "fromVar": "u",
+                    "target": { "var": "p", "label": "Post" }
+                },
+                { "op": "gt", "var": "p", "prop": "likes", "value": { "t": "Int", "v": 0 } }
+            ]
+        }));
+        assert!(leaked.is_err(), "target var must not escape its predicate");
+        let shadowed = query(json!({
+            "op": "edgeExists",
+            "fromVar": "u",
+            "target": { "var": "u", "label": "Post" }
+        }));
+        assert!(shadowed.is_err(), "target var must not shadow a match var");
+        Ok(())
+    }
+
+    #[test]
+    fn max_rows_truncates_and_flags_overflow() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let db = Database::open(dir.path().join("max_rows.db"), DatabaseOptions::default())?;
+        db.seed_demo()?;
+        let query = |max_rows: usize| {
+            db.execute_json(&json!({
+                "$schemaVersion": 1,
+                "matches": [{ "var": "u", "label": "User" }],
+                "maxRows": max_rows
+            }))
+        };
+        let users = db.execute_json(&json!({
+            "$schemaVersion": 1,
+            "matches": [{ "var": "u", "label": "User" }]
+        }))?;
+        assert!(users.get("truncated").is_none());
+        let total = users["rows"].as_array().expect("rows").len();
+        assert!(total > 2);
+
+        let capped = query(2)?;
+        assert_eq!(capped["rows"].as_array().expect("rows").len(), 2);
+        assert_eq!(capped["truncated"], json!(true));
+
+        let exact = query(total)?;
+        assert_eq!(exact["rows"], users["rows"]);
+        assert_eq!(exact["truncated"], json!(false));
+        Ok(())
+    }
+
+    #[test]
+    fn explain_json_includes_union_dedup_flag() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("explain_union_dedup.db");
+        let db = Database::open(&path, DatabaseOptions::default())?;
+        db.seed_demo()?;
+        let spec = json!({
+            "$schemaVersion": 1,
+            "request_id": "req-42",
+            "matches": [
+                { "var": "a", "label": "User" }
+            ],
+            "projections": [
+                { "kind": "var", "var": "a" }
+            ],
+            "distinct": true,
+            "predicate": {
+                "op": "or",
+                "args": [
+                    {
+                        "op": "eq",
+                        "var": "a",
+                        "prop": "name",
+                        "value": { "t": "String", "v": "Ada" }
+                    },
+                    {
+                        "op": "eq",
+                        "var": "a",
+                        "prop": "name",
+                        "value": { "t": "String", "v": "Grace" }
+                    }
+                ]
+            }
+        });
+        let explain = db.explain_json(&spec)?;
+        assert_eq!(
+            explain.get("request_id").and_then(Value::as_str),
+            Some("req-42")
+        );
+        let plan = explain
+            .get("plan")
+            .and_then(Value::as_array)
+            .expect("plan array");
+        dbg!(plan);
+        assert_eq!(plan.len(), 1);
+        let project = plan[0].as_object().expect("plan node");
+        assert_eq!(project.get("op").and_then(Value::as_str), Some("Project"));
+        let inputs = project
+            .get("inputs")
+            .and_then(Value::as_array)
+            .expect("project inputs");
+        assert_eq!(inputs.len(), 1);
+        let union = inputs[0].as_object().expect("union node object");
+        assert_eq!(union.get("op").and_then(Value::as_str), Some("Union"));
         let dedup = union
             .get("props")
             .and_then(Value::as_object)
@@ -4313,6 +11384,1577 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn explain_candidates_json_reports_rejected_index_scans() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("explain_candidates.db");
+        let db = Database::open(&path, DatabaseOptions::default())?;
+        db.seed_demo()?;
+        db.ensure_property_index("User", "age", "btree", "int")?;
+        let spec = json!({
+            "$schemaVersion": 1,
+            "request_id": "req-candidates",
+            "matches": [
+                { "var": "a", "label": "User" },
+                { "var": "b", "label": "User" }
+            ],
+            "edges": [
+                { "from": "a", "to": "b", "edgeType": "FOLLOWS" }
+            ],
+            "predicate": {
+                "op": "and",
+                "args": [
+                    {
+                        "op": "eq",
+                        "var": "a",
+                        "prop": "name",
+                        "value": { "t": "String", "v": "Ada" }
+                    },
+                    {
+                        "op": "ge",
+                        "var": "b",
+                        "prop": "age",
+                        "value": { "t": "Int", "v": 21 }
+                    }
+                ]
+            },
+            "projections": [
+                { "kind": "var", "var": "a" }
+            ]
+        });
+        let explain = db.explain_candidates_json(&spec)?;
+        assert_eq!(
+            explain.get("request_id").and_then(Value::as_str),
+            Some("req-candidates")
+        );
+        let candidates = explain
+            .get("candidates")
+            .and_then(Value::as_array)
+            .expect("candidates array");
+        let index_scans: Vec<&Value> = candidates
+            .iter()
+            .filter(|candidate| candidate["op"] == "PropIndexScan")
+            .collect();
+        assert_eq!(index_scans.len(), 2);
+        for candidate in &index_scans {
+            assert!(candidate["estimated_cost"].as_f64().is_some());
+        }
+        let selected: Vec<&Value> = candidates
+            .iter()
+            .filter(|candidate| candidate["selected"] == true)
+            .collect();
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0]["var"], "a");
+        assert_eq!(selected[0]["props"], json!(["name"]));
+        Ok(())
+    }
+
+    /// Builds a checkpointed database and flips a payload byte in its last
+    /// `corrupt_pages` pages, returning the database path.
+    fn corrupted_database(dir: &Path, corrupt_pages: u64) -> Result<std::path::PathBuf> {
+        use std::io::{Seek, SeekFrom, Write};
+
+        let path = dir.join("verify_on_open.db");
+        {
+            let db = Database::open(&path, DatabaseOptions::default())?;
+            let ops = (0..2_000)
+                .map(|i| MutationOp::CreateNode {
+                    labels: vec!["User".into()],
+                    props: json!({ "name": format!("user-{i:05}"), "bio": "x".repeat(64) })
+                        .as_object()
+                        .cloned()
+                        .unwrap(),
+                })
+                .collect();
+            db.mutate(MutationSpec { ops })?;
+            db.checkpoint()?;
+        }
+        let page_size = PagerOptions::default().page_size as u64;
+        let mut file = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(&path)
+            .unwrap();
+        let pages = file.metadata().unwrap().len() / page_size;
+        assert!(pages > corrupt_pages + 1, "database too small to corrupt");
+        for page in pages - corrupt_pages..pages {
+            file.seek(SeekFrom::Start(page * page_size + page_size / 2))
+                .unwrap();
+            file.write_all(&[0xA5, 0x5A, 0xA5, 0x5A]).unwrap();
+        }
+        file.flush().unwrap();
+        Ok(path)
+    }
+
+    fn open_with_verify(path: &Path, verify_on_open: VerifyOnOpen) -> Result<Database> {
+        Database::open(
+            path,
+            DatabaseOptions {
+                verify_on_open,
+                ..DatabaseOptions::default()
+            },
+        )
+    }
+
+    #[test]
+    fn verify_on_open_full_detects_corrupted_page() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = corrupted_database(dir.path(), 1)?;
+        match open_with_verify(&path, VerifyOnOpen::Full) {
+            Err(FfiError::Core(SombraError::Corruption(_))) => {}
+            Err(other) => panic!("expected corruption error, got {other:?}"),
+            Ok(_) => panic!("expected corruption error, database opened"),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn verify_on_open_off_skips_checksums() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = corrupted_database(dir.path(), 1)?;
+        open_with_verify(&path, VerifyOnOpen::Off)?;
+        Ok(())
+    }
+
+    #[test]
+    fn verify_on_open_sample_detects_corruption() -> Result<()> {
+        let dir = tempdir().unwrap();
+        // With eight bad pages, a 0.9 sample misses all of them with
+        // probability 1e-8.
+        let path = corrupted_database(dir.path(), 8)?;
+        assert!(matches!(
+            open_with_verify(&path, VerifyOnOpen::Sample(0.9)),
+            Err(FfiError::Core(SombraError::Corruption(_)))
+        ));
+        assert!(matches!(
+            open_with_verify(&path, VerifyOnOpen::Sample(1.5)),
+            Err(FfiError::Core(SombraError::Invalid(_)))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn get_last_n_nodes_by_label_returns_highest_ids() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("last_n_by_label.db");
+        let db = Database::open(&path, DatabaseOptions::default())?;
+        let ops = (0..10)
+            .map(|i| MutationOp::CreateNode {
+                labels: vec![if i % 3 == 0 { "Admin" } else { "User" }.into()],
+                props: Map::new(),
+            })
+            .collect();
+        let summary = db.mutate(MutationSpec { ops })?;
+        let created = summary.created_nodes;
+        let mut admins: Vec<u64> = created.iter().step_by(3).copied().collect();
+        admins.reverse();
+        let mut users: Vec<u64> = created
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| i % 3 != 0)
+            .map(|(_, id)| *id)
+            .collect();
+        users.reverse();
+
+        assert_eq!(
+            db.get_last_n_nodes_by_label("User", 3)?,
+            users[..3].to_vec()
+        );
+        assert_eq!(db.get_last_n_nodes_by_label("Admin", 10)?, admins);
+        assert!(db.get_last_n_nodes_by_label("Admin", 0)?.is_empty());
+
+        db.ensure_label_indexes(&["User".to_string()])?;
+        assert_eq!(
+            db.get_last_n_nodes_by_label("User", 2)?,
+            users[..2].to_vec()
+        );
+        assert_eq!(db.get_last_n_nodes_by_label("User", 100)?, users);
+        Ok(())
+    }
+
+    fn create_named(name: &str) -> MutationSpec {
+        MutationSpec {
+            ops: vec![MutationOp::CreateNode {
+                labels: vec!["User".into()],
+                props: Map::from_iter([("name".to_string(), json!(name))]),
+            }],
+        }
+    }
+
+    fn rename(id: u64, name: &str) -> MutationSpec {
+        MutationSpec {
+            ops: vec![MutationOp::UpdateNode {
+                id,
+                set: Map::from_iter([("name".to_string(), json!(name))]),
+                unset: Vec::new(),
+            }],
+        }
+    }
+
+    #[test]
+    fn patch_node_applies_json_patch_ops_in_order() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let db = Database::open(dir.path().join("patch.db"), DatabaseOptions::default())?;
+        let created = db.mutate(MutationSpec {
+            ops: vec![MutationOp::CreateNode {
+                labels: vec!["User".into()],
+                props: Map::from_iter([
+                    ("name".to_string(), json!("Ada")),
+                    ("role".to_string(), json!("analyst")),
+                    ("temp".to_string(), json!(true)),
+                ]),
+            }],
+        })?;
+        let id = created.created_nodes[0];
+
+        db.mutate_json(&json!({
+            "ops": [{
+                "op": "patchNode",
+                "id": id,
+                "patch": [
+                    { "op": "add", "path": "/age", "value": 36 },
+                    { "op": "replace", "path": "/role", "value": "engineer" },
+                    { "op": "remove", "path": "/temp" }
+                ]
+            }]
+        }))?;
+        let record = db.get_node_record(id)?.expect("patched node");
+        assert_eq!(
+            Value::Object(record.properties),
+            json!({ "name": "Ada", "role": "engineer", "age": 36 })
+        );
+
+        let missing = db.mutate_json(&json!({
+            "ops": [{
+                "op": "patchNode",
+                "id": id,
+                "patch": [
+                    { "op": "add", "path": "/nickname", "value": "A" },
+                    { "op": "remove", "path": "/temp" }
+                ]
+            }]
+        }));
+        assert!(missing.is_err());
+        let record = db.get_node_record(id)?.expect("node unchanged");
+        assert!(!record.properties.contains_key("nickname"));
+        Ok(())
+    }
+
+    #[test]
+    fn snapshot_transaction_reads_its_own_writes() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("snapshot_tx.db");
+        let db = Database::open(&path, DatabaseOptions::default())?;
+        let mut tx = db.begin_transaction(IsolationLevel::Snapshot)?;
+        assert_eq!(tx.isolation(), IsolationLevel::Snapshot);
+        let id = tx.mutate(create_named("Ada"))?.created_nodes[0];
+        let record = tx.get_node_record(id)?.expect("pending node visible");
+        assert_eq!(record.labels, vec!["User".to_string()]);
+        assert_eq!(record.properties["name"], json!("Ada"));
+        tx.mutate(rename(id, "Grace"))?;
+        let record = tx.get_node_record(id)?.expect("pending node visible");
+        assert_eq!(record.properties["name"], json!("Grace"));
+        tx.commit()?;
+        let committed = db.get_node_record(id)?.expect("committed node");
+        assert_eq!(committed.properties["name"], json!("Grace"));
+
+        let mut tx = db.begin_transaction(IsolationLevel::default())?;
+        let dropped = tx.mutate(create_named("Alan"))?.created_nodes[0];
+        assert!(tx.get_node_record(dropped)?.is_some());
+        drop(tx);
+        assert!(db.get_node_record(dropped)?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn read_committed_transaction_hides_pending_writes() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("read_committed_tx.db");
+        let db = Database::open(&path, DatabaseOptions::default())?;
+        let existing = db.mutate(create_named("before"))?.created_nodes[0];
+
+        let mut tx = db.begin_transaction(IsolationLevel::ReadCommitted)?;
+        tx.mutate(rename(existing, "after"))?;
+        let created = tx.mutate(create_named("new"))?.created_nodes[0];
+        let record = tx.get_node_record(existing)?.expect("committed node");
+        assert_eq!(record.properties["name"], json!("before"));
+        assert!(tx.get_node_record(created)?.is_none());
+        tx.commit()?;
+
+        let record = db.get_node_record(existing)?.expect("committed node");
+        assert_eq!(record.properties["name"], json!("after"));
+        assert!(db.get_node_record(created)?.is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn failed_batch_aborts_the_transaction() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("aborted_tx.db");
+        let db = Database::open(&path, DatabaseOptions::default())?;
+        let existing = db.mutate(create_named("before"))?.created_nodes[0];
+        let mut tx = db.begin_transaction(IsolationLevel::Snapshot)?;
+        let mut ops = create_named("Ada").ops;
+        ops.extend(rename(u64::MAX, "ghost").ops);
+        assert!(tx.mutate(MutationSpec { ops }).is_err());
+        assert!(tx.mutate(create_named("Grace")).is_err());
+        assert!(tx.commit().is_err());
+        assert_eq!(db.node_ids_with_label("User")?, vec![existing]);
+
+        // The aborted transaction released the writer lock.
+        db.mutate(create_named("Alan"))?;
+        assert_eq!(db.node_ids_with_label("User")?.len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn get_property_for_nodes_matches_node_records() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("prop_column.db");
+        let db = Database::open(&path, DatabaseOptions::default())?;
+        let ops = (0..6)
+            .map(|i| {
+                let mut props = Map::new();
+                props.insert("age".into(), json!(30 + i));
+                if i % 2 == 0 {
+                    props.insert("name".into(), json!(format!("user-{i}")));
+                }
+                MutationOp::CreateNode {
+                    labels: vec!["User".into()],
+                    props,
+                }
+            })
+            .collect();
+        let mut ids = db.mutate(MutationSpec { ops })?.created_nodes;
+        ids.reverse();
+        ids.push(u64::MAX);
+
+        for key in ["name", "age"] {
+            let column = db.get_property_for_nodes(&ids, key)?;
+            let expected: Vec<Value> = ids
+                .iter()
+                .map(|id| {
+                    Ok(db
+                        .get_node_record(*id)?
+                        .and_then(|node| node.properties.get(key).cloned())
+                        .unwrap_or(Value::Null))
+                })
+                .collect::<Result<_>>()?;
+            assert_eq!(column, expected, "{key}");
+        }
+        assert_eq!(
+            db.get_property_for_nodes(&ids[..2], "missing")?,
+            vec![Value::Null, Value::Null]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn get_neighbors_by_type_skips_other_edge_types() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("neighbors_by_type.db");
+        let db = Database::open(&path, DatabaseOptions::default())?;
+        let ops = (0..4)
+            .map(|_| MutationOp::CreateNode {
+                labels: vec!["User".into()],
+                props: Map::new(),
+            })
+            .collect();
+        let nodes = db.mutate(MutationSpec { ops })?.created_nodes;
+        let (a, b, c, d) = (nodes[0], nodes[1], nodes[2], nodes[3]);
+        let edge = |src: u64, dst: u64, ty: &str| MutationOp::CreateEdge {
+            src,
+            dst,
+            ty: ty.into(),
+            props: Map::new(),
+        };
+        db.mutate(MutationSpec {
+            ops: vec![
+                edge(a, b, "FOLLOWS"),
+                edge(a, b, "FOLLOWS"),
+                edge(a, c, "LIKES"),
+                edge(a, d, "BLOCKS"),
+                edge(d, a, "FOLLOWS"),
+            ],
+        })?;
+
+        assert_eq!(
+            db.get_neighbors_by_type(a, "FOLLOWS", Dir::Out, true)?,
+            vec![b]
+        );
+        assert_eq!(
+            db.get_neighbors_by_type(a, "FOLLOWS", Dir::Out, false)?,
+            vec![b, b]
+        );
+        assert_eq!(
+            db.get_neighbors_by_type(a, "LIKES", Dir::Out, true)?,
+            vec![c]
+        );
+        assert_eq!(
+            db.get_neighbors_by_type(a, "FOLLOWS", Dir::In, true)?,
+            vec![d]
+        );
+        let mut both = db.get_neighbors_by_type(a, "FOLLOWS", Dir::Both, true)?;
+        both.sort_unstable();
+        assert_eq!(both, vec![b, d]);
+        assert!(db
+            .get_neighbors_by_type(a, "LIKES", Dir::In, true)?
+            .is_empty());
+        assert!(db
+            .get_neighbors_by_type(a, "MISSING", Dir::Out, true)
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn neighbor_stream_reads_a_single_snapshot() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("neighbor_stream.db");
+        let db = Database::open(&path, DatabaseOptions::default())?;
+        let ops = (0..3)
+            .map(|_| MutationOp::CreateNode {
+                labels: vec!["User".into()],
+                props: Map::new(),
+            })
+            .collect();
+        let nodes = db.mutate(MutationSpec { ops })?.created_nodes;
+        let (a, b, c) = (nodes[0], nodes[1], nodes[2]);
+        let edge = |src: u64, dst: u64, ty: &str| MutationOp::CreateEdge {
+            src,
+            dst,
+            ty: ty.into(),
+            props: Map::new(),
+        };
+        db.mutate(MutationSpec {
+            ops: vec![edge(a, b, "FOLLOWS"), edge(a, c, "LIKES")],
+        })?;
+
+        let mut stream = db.neighbor_stream(a, Dir::Out, None, true)?;
+        let first = stream.next().expect("first neighbor")?;
+        // Writes after the stream was opened do not change what it yields.
+        db.mutate(MutationSpec {
+            ops: vec![edge(a, a, "FOLLOWS")],
+        })?;
+        let rest = stream
+            .map(|entry| entry.map(|entry| entry.node_id))
+            .collect::<Result<Vec<u64>>>()?;
+        let mut seen = vec![first.node_id];
+        seen.extend(rest);
+        seen.sort_unstable();
+        assert_eq!(seen, vec![b, c]);
+
+        let mut follows = db
+            .neighbor_stream(a, Dir::Out, Some("FOLLOWS"), true)?
+            .map(|entry| entry.map(|entry| entry.node_id))
+            .collect::<Result<Vec<u64>>>()?;
+        follows.sort_unstable();
+        assert_eq!(follows, vec![a, b]);
+        assert!(db
+            .neighbor_stream(a, Dir::Out, Some("MISSING"), true)
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn neighbor_stream_reads_adjacency_in_batches() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("neighbor_batches.db");
+        let db = Database::open(&path, DatabaseOptions::default())?;
+        let count = NEIGHBOR_STREAM_BATCH * 2 + 10;
+        let ops = (0..=count)
+            .map(|_| MutationOp::CreateNode {
+                labels: vec!["User".into()],
+                props: Map::new(),
+            })
+            .collect();
+        let nodes = db.mutate(MutationSpec { ops })?.created_nodes;
+        let hub = nodes[0];
+        let ops = nodes[1..]
+            .iter()
+            .map(|&dst| MutationOp::CreateEdge {
+                src: hub,
+                dst,
+                ty: "FOLLOWS".into(),
+                props: Map::new(),
+            })
+            .collect();
+        db.mutate(MutationSpec { ops })?;
+
+        let mut stream = db.neighbor_stream(hub, Dir::Out, None, false)?;
+        stream.next().expect("first neighbor")?;
+        // Only the first batch has been read from the adjacency tree.
+        assert_eq!(stream.batch.len(), NEIGHBOR_STREAM_BATCH - 1);
+        assert!(!stream.scan.is_done());
+        for _ in 0..NEIGHBOR_STREAM_BATCH {
+            stream.next().expect("neighbor")?;
+        }
+        assert_eq!(stream.batch.len(), NEIGHBOR_STREAM_BATCH - 1);
+        assert!(!stream.scan.is_done());
+
+        let mut seen = vec![];
+        for entry in db.neighbor_stream(hub, Dir::Out, None, false)? {
+            seen.push(entry?.node_id);
+        }
+        seen.sort_unstable();
+        assert_eq!(seen, nodes[1..].to_vec());
+        Ok(())
+    }
+
+    #[test]
+    fn match_pattern_applies_pattern_size_limits() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let opts = DatabaseOptions {
+            max_pattern_nodes: Some(2),
+            max_pattern_edges: Some(1),
+            ..DatabaseOptions::default()
+        };
+        let db = Database::open(dir.path().join("pattern_limit.db"), opts)?;
+        let node = |var: &str| json!({"var": var, "labels": ["User"]});
+        let edge = |from: &str, to: &str| json!({"from": from, "to": to});
+
+        let fits = json!({"nodes": [node("a"), node("b")], "edges": [edge("a", "b")]});
+        assert_eq!(db.match_pattern_json(&fits)?, json!([]));
+
+        let too_many_nodes = json!({"nodes": [node("a"), node("b"), node("c")]});
+        let err = db
+            .match_pattern_json(&too_many_nodes)
+            .expect_err("too many nodes");
+        assert!(
+            matches!(
+                err,
+                FfiError::Analyzer(AnalyzerError::PatternTooLarge {
+                    clause: "match",
+                    count: 3,
+                    max: 2
+                })
+            ),
+            "{err:?}"
+        );
+        let too_many_edges = json!({
+            "nodes": [node("a"), node("b")],
+            "edges": [edge("a", "b"), edge("b", "a")]
+        });
+        let err = db
+            .match_pattern_json(&too_many_edges)
+            .expect_err("too many edges");
+        assert!(
+            matches!(
+                err,
+                FfiError::Analyzer(AnalyzerError::PatternTooLarge {
+                    clause: "edge",
+                    count: 2,
+                    max: 1
+                })
+            ),
+            "{err:?}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn match_pattern_binds_each_parallel_edge() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("match_pattern.db");
+        let db = Database::open(&path, DatabaseOptions::default())?;
+        let ops = [("Ada", 36), ("Bob", 17), ("Cy", 52)]
+            .into_iter()
+            .map(|(name, age)| MutationOp::CreateNode {
+                labels: vec!["User".into()],
+                props: Map::from_iter([
+                    ("name".to_string(), json!(name)),
+                    ("age".to_string(), json!(age)),
+                ]),
+            })
+            .collect();
+        let nodes = db.mutate(MutationSpec { ops })?.created_nodes;
+        let (a, b, c) = (nodes[0], nodes[1], nodes[2]);
+        let edge = |src: u64, dst: u64, ty: &str| MutationOp::CreateEdge {
+            src,
+            dst,
+            ty: ty.into(),
+            props: Map::new(),
+        };
+        let edges = db
+            .mutate(MutationSpec {
+                ops: vec![
+                    edge(a, b, "FOLLOWS"),
+                    edge(a, c, "FOLLOWS"),
+                    edge(a, c, "FOLLOWS"),
+                    edge(a, c, "LIKES"),
+                ],
+            })?
+            .created_edges;
+
+        let adults = json!({
+            "nodes": [
+                {"var": "a", "labels": ["User"], "properties": {
+                    "equals": {"name": {"t": "String", "v": "Ada"}}
+                }},
+                {"var": "b", "labels": ["User"], "properties": {
+                    "ranges": [{"key": "age", "min": {"value": {"t": "Int", "v": 18}}}]
+                }}
+            ],
+            "edges": [{"from": "a", "to": "b", "types": ["FOLLOWS"]}]
+        });
+        let matches = db.match_pattern_json(&adults)?;
+        let matches = matches.as_array().expect("match list");
+        assert_eq!(matches.len(), 2);
+        let mut bound: Vec<u64> = matches
+            .iter()
+            .map(|m| {
+                assert_eq!(m["nodeBindings"], json!({"a": a, "b": c}));
+                m["edgeIds"][0].as_u64().unwrap()
+            })
+            .collect();
+        bound.sort_unstable();
+        assert_eq!(bound, vec![edges[1], edges[2]]);
+
+        // Two pattern edges never bind the same graph edge.
+        let spec: PatternSpec = serde_json::from_value(json!({
+            "nodes": [
+                {"var": "x", "labels": ["User"]},
+                {"var": "y", "labels": ["User"]}
+            ],
+            "edges": [
+                {"from": "x", "to": "y", "types": ["FOLLOWS"]},
+                {"from": "y", "to": "x", "types": ["FOLLOWS"], "direction": "in"}
+            ]
+        }))
+        .unwrap();
+        let pairs = db.match_pattern(spec)?;
+        assert_eq!(pairs.len(), 2);
+        for m in &pairs {
+            assert_eq!(m.node_bindings["y"], c);
+            assert_ne!(m.edge_ids[0], m.edge_ids[1]);
+        }
+
+        let missing = json!({"nodes": [{"var": "a", "labels": ["Nobody"]}]});
+        assert_eq!(db.match_pattern_json(&missing)?, json!([]));
+        let unknown_type = json!({
+            "nodes": [
+                {"var": "a", "labels": ["User"]},
+                {"var": "b", "labels": ["User"]}
+            ],
+            "edges": [{"from": "a", "to": "b", "types": ["BLOCKS"]}]
+        });
+        assert_eq!(db.match_pattern_json(&unknown_type)?, json!([]));
+        let bad = json!({
+            "nodes": [{"var": "a", "labels": ["User"]}],
+            "edges": [{"from": "a", "to": "z"}]
+        });
+        assert!(db.match_pattern_json(&bad).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn page_rank_scores_nodes_by_incoming_links() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("page_rank.db");
+        let db = Database::open(&path, DatabaseOptions::default())?;
+        let ops = (0..3)
+            .map(|_| MutationOp::CreateNode {
+                labels: vec!["Page".into()],
+                props: Map::new(),
+            })
+            .collect();
+        let nodes = db.mutate(MutationSpec { ops })?.created_nodes;
+        let (a, b, c) = (nodes[0], nodes[1], nodes[2]);
+        let edge = |src: u64, dst: u64, ty: &str| MutationOp::CreateEdge {
+            src,
+            dst,
+            ty: ty.into(),
+            props: Map::new(),
+        };
+        db.mutate(MutationSpec {
+            ops: vec![
+                edge(a, c, "LINKS"),
+                edge(b, c, "LINKS"),
+                edge(c, a, "CITES"),
+            ],
+        })?;
+
+        let scores = db.page_rank(None, None, None, None)?;
+        assert_eq!(
+            scores.iter().map(|s| s.node_id).collect::<Vec<_>>(),
+            vec![a, b, c]
+        );
+        assert!(scores[2].score > scores[0].score);
+        assert!(scores[0].score > scores[1].score);
+
+        let links = db.page_rank(Some(0.85), Some(50), Some(1e-9), Some("LINKS"))?;
+        assert!((links[0].score - links[1].score).abs() < 1e-9);
+        assert!(db.page_rank(None, None, None, Some("MISSING")).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn component_summary_counts_weak_and_strong_components() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("components.db");
+        let db = Database::open(&path, DatabaseOptions::default())?;
+        let ops = (0..6)
+            .map(|_| MutationOp::CreateNode {
+                labels: vec!["User".into()],
+                props: Map::new(),
+            })
+            .collect();
+        let n = db.mutate(MutationSpec { ops })?.created_nodes;
+        let edge = |src: u64, dst: u64| MutationOp::CreateEdge {
+            src,
+            dst,
+            ty: "FOLLOWS".into(),
+            props: Map::new(),
+        };
+        db.mutate(MutationSpec {
+            ops: vec![
+                edge(n[0], n[1]),
+                edge(n[1], n[2]),
+                edge(n[2], n[0]),
+                edge(n[2], n[3]),
+                edge(n[4], n[5]),
+            ],
+        })?;
+
+        let weak = db.component_summary(ComponentMode::Weak)?;
+        assert_eq!(weak.count, 2);
+        assert_eq!(weak.sizes, vec![4, 2]);
+        let strong = db.component_summary(ComponentMode::Strong)?;
+        assert_eq!(strong.count, 4);
+        assert_eq!(strong.sizes, vec![3, 1, 1, 1]);
+        Ok(())
+    }
+
+    #[test]
+    fn shortest_path_with_edges_names_parallel_edge_taken() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("shortest_path.db");
+        let db = Database::open(&path, DatabaseOptions::default())?;
+        let ops = (0..3)
+            .map(|_| MutationOp::CreateNode {
+                labels: vec!["Stop".into()],
+                props: Map::new(),
+            })
+            .collect();
+        let nodes = db.mutate(MutationSpec { ops })?.created_nodes;
+        let (a, b, c) = (nodes[0], nodes[1], nodes[2]);
+        let edge = |src: u64, dst: u64, ty: &str| MutationOp::CreateEdge {
+            src,
+            dst,
+            ty: ty.into(),
+            props: Map::new(),
+        };
+        db.mutate(MutationSpec {
+            ops: vec![
+                edge(a, b, "BUS"),
+                edge(a, b, "TRAM"),
+                edge(b, c, "TRAM"),
+                edge(b, c, "BUS"),
+            ],
+        })?;
+
+        let tram = ["TRAM".to_string()];
+        let hops = db
+            .shortest_path_with_edges(a, c, Some(&tram))?
+            .expect("tram path");
+        assert_eq!(hops.len(), 3);
+        assert_eq!(hops[0], (a, None));
+        for pair in hops.windows(2) {
+            let edge_id = pair[1].1.expect("edge id");
+            let record = db.get_edge_record(edge_id)?.expect("edge record");
+            assert_eq!((record.src, record.dst), (pair[0].0, pair[1].0));
+            assert_eq!(record.ty, "TRAM");
+        }
+
+        assert!(db.shortest_path_with_edges(c, a, None)?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn execute_paged_json_concatenates_to_unpaged_rows() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let db = Database::open(dir.path().join("paged.db"), DatabaseOptions::default())?;
+        let ops = (0..23)
+            .map(|rank| MutationOp::CreateNode {
+                labels: vec!["User".into()],
+                props: Map::from_iter([("rank".to_string(), json!(rank))]),
+            })
+            .collect();
+        db.mutate(MutationSpec { ops })?;
+        let spec = json!({
+            "$schemaVersion": 1,
+            "matches": [{ "var": "a", "label": "User" }],
+            "projections": [{ "kind": "var", "var": "a" }]
+        });
+        let unpaged = db.execute_json(&spec)?["rows"]
+            .as_array()
+            .expect("rows array")
+            .clone();
+        assert_eq!(unpaged.len(), 23);
+
+        let mut paged = Vec::new();
+        let mut token = None;
+        let mut pages = 0;
+        loop {
+            let page = db.execute_paged_json(&spec, token.take(), 5)?;
+            assert!(page.rows.len() <= 5);
+            paged.extend(page.rows);
+            pages += 1;
+            match page.next_token {
+                Some(next) => token = Some(next),
+                None => break,
+            }
+        }
+        assert_eq!(pages, 5);
+        assert_eq!(paged, unpaged);
+        let ids: HashSet<_> = paged.iter().map(|row| row["a"]["_id"].clone()).collect();
+        assert_eq!(ids.len(), paged.len());
+
+        let first = db.execute_paged_json(&spec, None, 5)?;
+        let other = json!({
+            "$schemaVersion": 1,
+            "matches": [{ "var": "b", "label": "User" }]
+        });
+        assert!(db.execute_paged_json(&other, first.next_token, 5).is_err());
+        assert!(db.execute_paged_json(&spec, None, 0).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn execute_paged_json_resumes_after_the_last_sort_key() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let db = Database::open(dir.path().join("keyset.db"), DatabaseOptions::default())?;
+        let ops = (0..30)
+            .map(|rank| MutationOp::CreateNode {
+                labels: vec!["User".into()],
+                props: Map::from_iter([("bucket".to_string(), json!(rank % 4))]),
+            })
+            .collect();
+        db.mutate(MutationSpec { ops })?;
+        let spec = json!({
+            "$schemaVersion": 1,
+            "matches": [{ "var": "a", "label": "User" }],
+            "projections": [{ "kind": "var", "var": "a" }],
+            "orderBy": [{ "var": "a", "prop": "bucket", "desc": true }]
+        });
+        let unpaged = db.execute_json(&spec)?["rows"]
+            .as_array()
+            .expect("rows array")
+            .clone();
+        assert_eq!(unpaged.len(), 30);
+
+        let mut paged = Vec::new();
+        let mut token = None;
+        loop {
+            let page = db.execute_paged_json(&spec, token.take(), 4)?;
+            paged.extend(page.rows);
+            match page.next_token {
+                Some(next) => token = Some(next),
+                None => break,
+            }
+        }
+        assert_eq!(paged, unpaged);
+
+        // Deleting a row already returned must not shift later pages.
+        let first = db.execute_paged_json(&spec, None, 4)?;
+        let seen = first.rows[0]["a"]["_id"].as_u64().expect("id");
+        db.mutate(MutationSpec {
+            ops: vec![MutationOp::DeleteNode {
+                id: seen,
+                cascade: false,
+            }],
+        })?;
+        let second = db.execute_paged_json(&spec, first.next_token, 4)?;
+        assert_eq!(second.rows, unpaged[4..8]);
+
+        let limited = json!({
+            "$schemaVersion": 1,
+            "matches": [{ "var": "a", "label": "User" }],
+            "limit": 10
+        });
+        assert!(db.execute_paged_json(&limited, None, 4).is_err());
+        let garbage = Some("not a token".to_string());
+        assert!(db.execute_paged_json(&spec, garbage, 4).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn summary_json_matches_individual_counts() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let db = Database::open(dir.path().join("summary.db"), DatabaseOptions::default())?;
+        db.seed_demo()?;
+        let created = db.mutate(MutationSpec {
+            ops: vec![
+                MutationOp::CreateNode {
+                    labels: vec!["Team".into(), "User".into()],
+                    props: Map::new(),
+                },
+                MutationOp::CreateNode {
+                    labels: vec!["Team".into()],
+                    props: Map::new(),
+                },
+            ],
+        })?;
+        let (a, b) = (created.created_nodes[0], created.created_nodes[1]);
+        db.mutate(MutationSpec {
+            ops: vec![MutationOp::CreateEdge {
+                src: a,
+                dst: b,
+                ty: "MEMBER_OF".into(),
+                props: Map::new(),
+            }],
+        })?;
+
+        let summary = db.summary_json()?;
+        let read = db.pager.begin_latest_committed_read()?;
+        let nodes = db.graph.scan_all_nodes(&read)?;
+        let edges = db.graph.scan_all_edges(&read)?;
+        drop(read);
+        assert_eq!(summary["nodes"], json!(nodes.len()));
+        assert_eq!(summary["edges"], json!(edges.len()));
+        let labels = summary["labels"].as_object().expect("labels");
+        assert_eq!(labels.len(), 2);
+        let mut label_indexes = 0;
+        for (label, count) in labels {
+            assert_eq!(count, &json!(db.count_nodes_with_label(label)?));
+            if db.graph.has_label_index(db.lookup_label(label)?)? {
+                label_indexes += 1;
+            }
+        }
+        let types = summary["edgeTypes"].as_object().expect("edge types");
+        assert_eq!(types.len(), 2);
+        for (ty, count) in types {
+            assert_eq!(count, &json!(db.count_edges_with_type(ty)?));
+        }
+        assert_eq!(summary["labelIndexes"], json!(label_indexes));
+        assert_eq!(summary["propertyIndexes"], json!(1));
+        let node_ids: Vec<u64> = nodes.iter().map(|(id, _)| id.0).collect();
+        let expected_range = [node_ids.iter().min(), node_ids.iter().max()];
+        assert_eq!(summary["nodeIdRange"], json!(expected_range));
+        let edge_ids: Vec<u64> = edges.iter().map(|(id, _)| id.0).collect();
+        let expected_range = [edge_ids.iter().min(), edge_ids.iter().max()];
+        assert_eq!(summary["edgeIdRange"], json!(expected_range));
+        Ok(())
+    }
+
+    #[test]
+    fn auto_label_index_disabled_scans_records() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let opts = DatabaseOptions {
+            auto_label_index: false,
+            ..DatabaseOptions::default()
+        };
+        let db = Database::open(dir.path().join("no_auto_index.db"), opts)?;
+        db.mutate(MutationSpec {
+            ops: vec![
+                MutationOp::CreateNode {
+                    labels: vec!["Person".into()],
+                    props: Map::from_iter([("name".into(), json!("ada"))]),
+                },
+                MutationOp::CreateNode {
+                    labels: vec!["Person".into()],
+                    props: Map::from_iter([("name".into(), json!("grace"))]),
+                },
+            ],
+        })?;
+        let person = db.lookup_label("Person")?;
+        assert!(!db.graph.has_label_index(person)?);
+
+        let response = db.execute_json(&json!({
+            "$schemaVersion": 1,
+            "matches": [{ "var": "p", "label": "Person" }]
+        }))?;
+        assert_eq!(response["rows"].as_array().expect("rows").len(), 2);
+        assert_eq!(db.count_nodes_with_label("Person")?, 2);
+
+        assert_eq!(db.ensure_label_indexes(&["Person".into()])?, 1);
+        assert!(db.graph.has_label_index(person)?);
+        Ok(())
+    }
+
+    #[test]
+    fn create_script_defer_indexes_matches_immediate_load() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let load = |name: &str, defer: bool| -> Result<Database> {
+            let db = Database::open(dir.path().join(name), DatabaseOptions::default())?;
+            db.create_json(&json!({
+                "nodes": [{ "labels": ["User"], "props": { "age": 3, "name": "seed" } }]
+            }))?;
+            db.ensure_property_index("User", "age", "btree", "int")?;
+            let nodes: Vec<Value> = (1..40)
+                .map(|i| {
+                    json!({ "labels": ["User", "Member"], "props": { "age": i % 7, "name": format!("u{i}") } })
+                })
+                .collect();
+            db.create_json(&json!({ "nodes": nodes, "deferIndexes": defer }))?;
+            Ok(db)
+        };
+        let immediate = load("immediate.db", false)?;
+        let deferred = load("deferred.db", true)?;
+
+        let by_age = json!({
+            "$schemaVersion": 1,
+            "matches": [{ "var": "u", "label": "User" }],
+            "predicate": {
+                "op": "eq",
+                "var": "u",
+                "prop": "age",
+                "value": { "t": "Int", "v": 3 }
+            }
+        });
+        let members = json!({
+            "$schemaVersion": 1,
+            "matches": [{ "var": "m", "label": "Member" }]
+        });
+        for spec in [&by_age, &members] {
+            let expected = immediate.execute_json(spec)?;
+            let actual = deferred.execute_json(spec)?;
+            assert_eq!(actual["rows"], expected["rows"]);
+        }
+        assert_eq!(
+            deferred.execute_json(&by_age)?["rows"]
+                .as_array()
+                .expect("rows")
+                .len(),
+            7
+        );
+
+        let user = deferred.lookup_label("User")?;
+        let member = deferred.lookup_label("Member")?;
+        let age = PropId(deferred.dict.lookup("age")?.expect("age interned").0);
+        assert!(deferred.graph.has_label_index(user)?);
+        assert!(deferred.graph.has_label_index(member)?);
+        assert!(deferred.graph.has_property_index(user, age)?);
+        Ok(())
+    }
+
+    #[test]
+    fn create_script_defer_indexes_failure_rebuilds_indexes() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let db = Database::open(dir.path().join("defer_fail.db"), DatabaseOptions::default())?;
+        db.create_json(&json!({
+            "nodes": [{ "labels": ["User"], "props": { "age": 3 } }]
+        }))?;
+        db.ensure_property_index("User", "age", "btree", "int")?;
+        let err = db.create_json(&json!({
+            "nodes": [{ "labels": ["User"], "props": { "age": 3 } }],
+            "edges": [{
+                "src": { "kind": "handle", "index": 0 },
+                "ty": "KNOWS",
+                "dst": { "kind": "id", "id": 999_999 }
+            }],
+            "deferIndexes": true
+        }));
+        assert!(err.is_err());
+
+        let user = db.lookup_label("User")?;
+        let age = PropId(db.dict.lookup("age")?.expect("age interned").0);
+        assert!(db.graph.has_label_index(user)?);
+        assert!(db.graph.has_property_index(user, age)?);
+        let rows = db.execute_json(&json!({
+            "$schemaVersion": 1,
+            "matches": [{ "var": "u", "label": "User" }],
+            "predicate": {
+                "op": "eq",
+                "var": "u",
+                "prop": "age",
+                "value": { "t": "Int", "v": 3 }
+            }
+        }))?;
+        assert_eq!(rows["rows"].as_array().expect("rows").len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn building_index_degrades_to_scan_without_missing_nodes() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let db = Database::open(dir.path().join("mid_build.db"), DatabaseOptions::default())?;
+        let users = |people: &[(&str, i64)]| {
+            let nodes = people
+                .iter()
+                .map(|(name, age)| json!({ "labels": ["User"], "props": { "name": name, "age": age } }))
+                .collect::<Vec<_>>();
+            db.create_json(&json!({ "nodes": nodes }))
+        };
+        users(&[("ada", 1), ("bob", 3), ("cy", 3), ("dee", 7)])?;
+
+        let user = db.lookup_label("User")?;
+        let mut write = db.pager.begin_write()?;
+        let age = PropId(db.dict.intern(&mut write, "age")?.0);
+        db.graph.begin_property_index_build(
+            &mut write,
+            IndexDef {
+                label: user,
+                props: vec![age],
+                kind: IndexKind::BTree,
+                types: vec![TypeTag::Int],
+                max_key_bytes: None,
+                unique: false,
+                state: IndexState::Ready,
+            },
+        )?;
+        db.pager.commit(write)?;
+        // Writes skip the building index, so it stays empty until finished.
+        users(&[("eve", 3), ("fay", 9)])?;
+        let def = db
+            .graph
+            .property_index(user, age)?
+            .expect("index registered");
+        assert_eq!(def.state, IndexState::Building);
+
+        let specs = [
+            json!({ "op": "eq", "var": "u", "prop": "age", "value": { "t": "Int", "v": 3 } }),
+            json!({ "op": "ge", "var": "u", "prop": "age", "value": { "t": "Int", "v": 3 } }),
+        ]
+        .map(|predicate| {
+            json!({
+                "$schemaVersion": 1,
+                "matches": [{ "var": "u", "label": "User" }],
+                "predicate": predicate,
+                "projections": [{ "kind": "prop", "var": "u", "prop": "name" }]
+            })
+        });
+        let names = |spec: &Value| -> Result<Vec<String>> {
+            let rows = db.execute_json(spec)?;
+            let mut names = rows["rows"]
+                .as_array()
+                .expect("rows")
+                .iter()
+                .map(|row| row["name"].as_str().expect("name").to_owned())
+                .collect::<Vec<_>>();
+            names.sort();
+            Ok(names)
+        };
+        let uses_index = |spec: &Value| -> Result<bool> {
+            Ok(db.explain_json(spec)?.to_string().contains("PropIndexScan"))
+        };
+
+        let mut building = Vec::new();
+        for spec in &specs {
+            assert!(!uses_index(spec)?);
+            building.push(names(spec)?);
+        }
+        assert_eq!(building[0], ["bob", "cy", "eve"]);
+        assert_eq!(building[1], ["bob", "cy", "dee", "eve", "fay"]);
+
+        let mut write = db.pager.begin_write()?;
+        let finished = db
+            .graph
+            .finish_property_index_build(&mut write, user, age)?;
+        assert!(finished);
+        db.pager.commit(write)?;
+        for (spec, expected) in specs.iter().zip(&building) {
+            assert!(uses_index(spec)?);
+            assert_eq!(&names(spec)?, expected);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn bulk_load_defer_indexes_rebuilds_on_finish() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let db = Database::open(dir.path().join("bulk_defer.db"), DatabaseOptions::default())?;
+        db.create_json(&json!({
+            "nodes": [{ "labels": ["User"], "props": { "age": 1 } }]
+        }))?;
+        db.ensure_property_index("User", "age", "btree", "int")?;
+        let user = db.lookup_label("User")?;
+        let age = PropId(db.dict.lookup("age")?.expect("age interned").0);
+
+        let nodes: Vec<TypedNodeSpec> = (0..25)
+            .map(|i| TypedNodeSpec {
+                label: "User".into(),
+                props: vec![TypedPropEntry {
+                    key: "age".into(),
+                    kind: "int".into(),
+                    bool_value: None,
+                    int_value: Some(i % 5),
+                    float_value: None,
+                    string_value: None,
+                    bytes_value: None,
+                }],
+                alias: None,
+            })
+            .collect();
+        let mut bulk = db.begin_bulk_load(BulkLoadOptions {
+            node_chunk_size: 10,
+            defer_indexes: true,
+            ..BulkLoadOptions::default()
+        });
+        bulk.load_nodes(&nodes)?;
+        let def = db.graph.property_index(user, age)?.expect("index kept");
+        assert_eq!(def.state, IndexState::Building);
+        let stats = bulk.finish()?;
+        assert_eq!(stats.nodes_created, 25);
+        assert!(db.graph.has_label_index(user)?);
+        assert!(db.graph.has_property_index(user, age)?);
+
+        let rows = db.execute_json(&json!({
+            "$schemaVersion": 1,
+            "matches": [{ "var": "u", "label": "User" }],
+            "predicate": {
+                "op": "eq",
+                "var": "u",
+                "prop": "age",
+                "value": { "t": "Int", "v": 1 }
+            }
+        }))?;
+        assert_eq!(rows["rows"].as_array().expect("rows").len(), 6);
+        Ok(())
+    }
+
+    #[test]
+    fn unrestored_deferred_indexes_rebuild_on_open() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("bulk_abandoned.db");
+        let spec = json!({
+            "$schemaVersion": 1,
+            "matches": [{ "var": "u", "label": "User" }],
+            "predicate": {
+                "op": "eq",
+                "var": "u",
+                "prop": "age",
+                "value": { "t": "Int", "v": 2 }
+            }
+        });
+        {
+            let db = Database::open(&path, DatabaseOptions::default())?;
+            db.create_json(&json!({
+                "nodes": [{ "labels": ["User"], "props": { "age": 0 } }]
+            }))?;
+            db.ensure_property_index("User", "age", "btree", "int")?;
+            let nodes: Vec<TypedNodeSpec> = (0..9)
+                .map(|i| TypedNodeSpec {
+                    label: "User".into(),
+                    props: vec![TypedPropEntry {
+                        key: "age".into(),
+                        kind: "int".into(),
+                        bool_value: None,
+                        int_value: Some(i % 3),
+                        float_value: None,
+                        string_value: None,
+                        bytes_value: None,
+                    }],
+                    alias: None,
+                })
+                .collect();
+            let mut bulk = db.begin_bulk_load(BulkLoadOptions {
+                defer_indexes: true,
+                ..BulkLoadOptions::default()
+            });
+            bulk.load_nodes(&nodes)?;
+            // Skip finish and the drop-time rebuild, as a crash would.
+            std::mem::forget(bulk);
+            let plan = db.explain_json(&spec)?.to_string();
+            assert!(!plan.contains("PropIndexScan"));
+        }
+
+        let db = Database::open(&path, DatabaseOptions::default())?;
+        let user = db.lookup_label("User")?;
+        let age = PropId(db.dict.lookup("age")?.expect("age interned").0);
+        let def = db.graph.property_index(user, age)?.expect("index kept");
+        assert_eq!(def.state, IndexState::Ready);
+        let plan = db.explain_json(&spec)?.to_string();
+        assert!(plan.contains("PropIndexScan"));
+        let rows = db.execute_json(&spec)?;
+        assert_eq!(rows["rows"].as_array().expect("rows").len(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn bulk_load_captures_inferred_schema() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let db = Database::open(
+            dir.path().join("bulk_schema.db"),
+            DatabaseOptions::default(),
+        )?;
+        let prop = |key: &str, kind: &str| TypedPropEntry {
+            key: key.into(),
+            kind: kind.into(),
+            bool_value: (kind == "bool").then_some(true),
+            int_value: (kind == "int").then_some(7),
+            float_value: (kind == "float").then_some(1.5),
+            string_value: (kind == "string").then(|| "x".to_string()),
+            bytes_value: None,
+        };
+        let node = |label: &str, props: Vec<TypedPropEntry>| TypedNodeSpec {
+            label: label.into(),
+            props,
+            alias: None,
+        };
+        let nodes = vec![
+            node("User", vec![prop("name", "string"), prop("age", "int")]),
+            node("User", vec![prop("name", "string"), prop("age", "string")]),
+            node("User", vec![prop("name", "null"), prop("active", "bool")]),
+            node("Post", vec![prop("score", "float")]),
+        ];
+        let mut bulk = db.begin_bulk_load(BulkLoadOptions {
+            node_chunk_size: 2,
+            capture_schema: true,
+            ..BulkLoadOptions::default()
+        });
+        let ids = bulk.load_nodes(&nodes)?;
+        let edge = TypedEdgeSpec {
+            ty: "WROTE".into(),
+            src: TypedNodeRef {
+                kind: "id".into(),
+                alias: None,
+                handle: None,
+                id: Some(ids[0].0),
+            },
+            dst: TypedNodeRef {
+                kind: "id".into(),
+                alias: None,
+                handle: None,
+                id: Some(ids[3].0),
+            },
+            props: vec![prop("weight", "float")],
+        };
+        bulk.load_edges(&[edge])?;
+        bulk.finish()?;
+
+        let schema = db.inferred_schema_json()?;
+        let user = &schema["labels"]["User"];
+        assert_eq!(user["count"], 3);
+        assert_eq!(
+            user["props"]["age"]["types"],
+            json!({ "int": 1, "string": 1 })
+        );
+        assert_eq!(user["props"]["age"]["conflict"], true);
+        // A null alongside one concrete kind only marks the property optional.
+        assert_eq!(
+            user["props"]["name"]["types"],
+            json!({ "null": 1, "string": 2 })
+        );
+        assert_eq!(user["props"]["name"]["conflict"], false);
+        assert_eq!(user["props"]["active"]["count"], 1);
+        assert_eq!(
+            schema["labels"]["Post"]["props"]["score"]["types"],
+            json!({ "float": 1 })
+        );
+        assert_eq!(
+            schema["edgeTypes"]["WROTE"]["props"]["weight"]["conflict"],
+            false
+        );
+
+        // Loads without the option leave the summary untouched.
+        let mut plain = db.begin_bulk_load(BulkLoadOptions::default());
+        plain.load_nodes(&[node("Tag", vec![prop("name", "string")])])?;
+        plain.finish()?;
+        assert!(db.inferred_schema_json()?["labels"].get("Tag").is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn import_csv_loads_rows_and_reports_failures() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let nodes_csv = dir.path().join("nodes.csv");
+        let edges_csv = dir.path().join("edges.csv");
+        fs::write(
+            &nodes_csv,
+            "id,label,name,age,joined\n\
+             u1,User,Ada,36,2020-01-02\n\
+             u2,User,Bob,old,2021-03-04\n\
+             u3,,Cy,52,\n\
+             ,User,NoId,1,\n\
+             u1,User,Dup,2,\n\
+             p1,Post,Hello,,\n",
+        )
+        .unwrap();
+        fs::write(
+            &edges_csv,
+            "src,dst,type,weight\n\
+             u1,p1,WROTE,0.5\n\
+             u3,u1,FOLLOWS,\n\
+             u1,u2,FOLLOWS,\n\
+             u1,p1,,\n",
+        )
+        .unwrap();
+
+        let db = Database::open(dir.path().join("import.db"), DatabaseOptions::default())?;
+        let summary = db.import_csv(
+            &nodes_csv,
+            &edges_csv,
+            CsvImportOptions {
+                default_label: Some("Member".into()),
+                id_property: Some("ext_id".into()),
+                column_types: HashMap::from([
+                    ("AGE".to_string(), PropertyType::Int),
+                    ("joined".to_string(), PropertyType::Date),
+                ]),
+                bulk: BulkLoadOptions {
+                    node_chunk_size: 2,
+                    edge_chunk_size: 1,
+                    ..BulkLoadOptions::default()
+                },
+                ..CsvImportOptions::default()
+            },
+        )?;
+
+        assert_eq!(summary.nodes_loaded, 3);
+        assert_eq!(summary.edges_loaded, 2);
+        let failures: Vec<(Option<u64>, &str)> = summary
+            .failed_rows
+            .iter()
+            .map(|row| (row.line, row.message.as_str()))
+            .collect();
+        assert_eq!(
+            failures,
+            vec![
+                (Some(3), "column 'age': invalid integer literal 'old'"),
+                (Some(5), "missing value for id column 'id'"),
+                (Some(6), "duplicate node id 'u1'"),
+                (Some(4), "edge references unknown node id 'u2'"),
+                (Some(5), "edge has no type"),
+            ]
+        );
+        assert_eq!(summary.failed_rows[0].path, nodes_csv);
+        assert_eq!(summary.failed_rows[3].path, edges_csv);
+
+        assert_eq!(db.count_nodes_with_label("User")?, 1);
+        assert_eq!(db.count_nodes_with_label("Member")?, 1);
+        assert_eq!(db.count_edges_with_type("FOLLOWS")?, 1);
+        let ada = db
+            .get_node_record(db.node_ids_with_label("User")?[0])?
+            .expect("ada");
+        assert_eq!(ada.properties["name"], json!("Ada"));
+        assert_eq!(ada.properties["age"], json!(36));
+        assert_eq!(ada.properties["joined"], json!(18263));
+        assert_eq!(ada.properties["ext_id"], json!("u1"));
+        Ok(())
+    }
+
+    #[test]
+    fn import_csv_retries_rejected_chunks_row_by_row() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let nodes_csv = dir.path().join("nodes.csv");
+        let edges_csv = dir.path().join("edges.csv");
+        fs::write(
+            &nodes_csv,
+            "id,label,email\n\
+             u1,User,grace@example.com\n\
+             u2,User,ada@example.com\n\
+             u3,User,linus@example.com\n\
+             u4,User,grace@example.com\n",
+        )
+        .unwrap();
+        fs::write(
+            &edges_csv,
+            "src,dst,type\n\
+             u1,u3,FOLLOWS\n\
+             u2,u3,FOLLOWS\n",
+        )
+        .unwrap();
+
+        let db = Database::open(dir.path().join("import.db"), DatabaseOptions::default())?;
+        db.mutate(MutationSpec {
+            ops: vec![MutationOp::CreateNode {
+                labels: vec!["User".into()],
+                props: Map::from_iter([("email".to_string(), json!("ada@example.com"))]),
+            }],
+        })?;
+        assert!(db.ensure_unique_property_index("User", "email", "btree", "string")?);
+
+        let summary = db.import_csv(
+            &nodes_csv,
+            &edges_csv,
+            CsvImportOptions {
+                bulk: BulkLoadOptions {
+                    node_chunk_size: 4,
+                    ..BulkLoadOptions::default()
+                },
+                ..CsvImportOptions::default()
+            },
+        )?;
+
+        // Only the conflicting rows are dropped from the rejected chunk.
+        assert_eq!(summary.nodes_loaded, 2);
+        assert_eq!(summary.edges_loaded, 1);
+        let failures: Vec<(Option<u64>, &str)> = summary
+            .failed_rows
+            .iter()
+            .map(|row| (row.line, row.message.as_str()))
+            .collect();
+        assert_eq!(failures.len(), 3);
+        assert_eq!(failures[0].0, Some(3));
+        assert!(failures[0].1.starts_with("constraint violation"));
+        assert_eq!(failures[1].0, Some(5));
+        assert!(failures[1].1.starts_with("constraint violation"));
+        assert_eq!(
+            failures[2],
+            (Some(3), "edge references unknown node id 'u2'")
+        );
+        assert_eq!(db.count_nodes_with_label("User")?, 3);
+        assert_eq!(db.count_edges_with_type("FOLLOWS")?, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn entity_names_match_individual_resolution() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("entity_names.db");
+        let db = Database::open(&path, DatabaseOptions::default())?;
+        let node = |labels: &[&str]| MutationOp::CreateNode {
+            labels: labels.iter().map(|label| label.to_string()).collect(),
+            props: Map::new(),
+        };
+        let nodes = db
+            .mutate(MutationSpec {
+                ops: vec![
+                    node(&["Person", "Admin"]),
+                    node(&["Tag"]),
+                    node(&["Person"]),
+                ],
+            })?
+            .created_nodes;
+        let edge = |src: u64, dst: u64, ty: &str| MutationOp::CreateEdge {
+            src,
+            dst,
+            ty: ty.into(),
+            props: Map::new(),
+        };
+        let edges = db
+            .mutate(MutationSpec {
+                ops: vec![
+                    edge(nodes[0], nodes[1], "TAGGED"),
+                    edge(nodes[0], nodes[2], "KNOWS"),
+                    edge(nodes[2], nodes[1], "TAGGED"),
+                ],
+            })?
+            .created_edges;
+
+        let missing = u64::MAX;
+        let node_ids = [nodes[2], missing, nodes[0], nodes[1], nodes[0]];
+        let edge_ids = [edges[2], edges[1], missing, edges[0]];
+        let names = db.entity_names(&node_ids, &edge_ids)?;
+        let expected_labels = node_ids
+            .iter()
+            .map(|&id| Ok(db.get_node_record(id)?.map(|record| record.labels)))
+            .collect::<Result<Vec<_>>>()?;
+        let expected_types = edge_ids
+            .iter()
+            .map(|&id| Ok(db.get_edge_record(id)?.map(|record| record.ty)))
+            .collect::<Result<Vec<_>>>()?;
+        assert_eq!(names.node_labels, expected_labels);
+        assert_eq!(names.edge_types, expected_types);
+        assert_eq!(names.node_labels[1], None);
+        assert_eq!(names.edge_types[2], None);
+
+        let mut str_ids: Vec<StrId> = (1..=6).rev().map(StrId).collect();
+        str_ids.push(StrId(2));
+        str_ids.push(StrId(u32::MAX));
+        let batched = db.dict.resolve_many(&str_ids)?;
+        assert_eq!(batched.len(), str_ids.len());
+        for (id, name) in str_ids.iter().zip(&batched) {
+            match db.dict.resolve_str(*id) {
+                Ok(single) => assert_eq!(name.as_deref(), Some(single.as_str())),
+                Err(SombraError::NotFound) => assert_eq!(name, &None),
+                Err(err) => return Err(err.into()),
+            }
+        }
+        assert_eq!(batched.last(), Some(&None));
+        Ok(())
+    }
+
     #[test]
     fn explain_json_can_redact_literals() -> Result<()> {
         let dir = tempdir().unwrap();
@@ -10,6 +10,8 @@ use std::{
     sync::Arc,
 };
 
+use parking_lot::RwLock;
+
 use crate::types::{Result, SombraError};
 
 #[cfg(test)]
@@ -334,6 +336,81 @@ impl FileIo for StdFileIo {
     }
 }
 
+/// In-memory file backed by a growable byte buffer.
+///
+/// Reads past the end fail with `UnexpectedEof` and writes past the end
+/// zero-fill the gap, matching [`StdFileIo`]. Syncs are no-ops.
+#[derive(Default)]
+pub struct MemFileIo {
+    bytes: RwLock<Vec<u8>>,
+}
+
+impl MemFileIo {
+    /// Wraps an existing byte buffer.
+    pub fn new(bytes: Vec<u8>) -> Self {
+        Self {
+            bytes: RwLock::new(bytes),
+        }
+    }
+
+    /// Returns a copy of the current contents.
+    pub fn to_vec(&self) -> Vec<u8> {
+        self.bytes.read().clone()
+    }
+
+    /// Consumes the file and returns its contents.
+    pub fn into_inner(self) -> Vec<u8> {
+        self.bytes.into_inner()
+    }
+}
+
+impl FileIo for MemFileIo {
+    fn read_at(&self, off: u64, dst: &mut [u8]) -> Result<()> {
+        let bytes = self.bytes.read();
+        let start = usize::try_from(off).map_err(|_| SombraError::Invalid("offset overflow"))?;
+        match start
+            .checked_add(dst.len())
+            .and_then(|end| bytes.get(start..end))
+        {
+            Some(src) => {
+                dst.copy_from_slice(src);
+                Ok(())
+            }
+            None => Err(SombraError::Io(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "read past end of in-memory file",
+            ))),
+        }
+    }
+
+    fn write_at(&self, off: u64, src: &[u8]) -> Result<()> {
+        let mut bytes = self.bytes.write();
+        let start = usize::try_from(off).map_err(|_| SombraError::Invalid("offset overflow"))?;
+        let end = start
+            .checked_add(src.len())
+            .ok_or(SombraError::Invalid("offset overflow"))?;
+        if bytes.len() < end {
+            bytes.resize(end, 0);
+        }
+        bytes[start..end].copy_from_slice(src);
+        Ok(())
+    }
+
+    fn sync_all(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn len(&self) -> Result<u64> {
+        Ok(self.bytes.read().len() as u64)
+    }
+
+    fn truncate(&self, len: u64) -> Result<()> {
+        let len = usize::try_from(len).map_err(|_| SombraError::Invalid("length overflow"))?;
+        self.bytes.write().resize(len, 0);
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -391,4 +468,18 @@ mod tests {
         reopen.read_at(0, &mut buf).unwrap();
         assert!(buf.iter().all(|&b| b == 42));
     }
+
+    #[test]
+    fn mem_file_grows_and_truncates() {
+        let io = MemFileIo::default();
+        io.write_at(4, b"abc").unwrap();
+        assert_eq!(io.len().unwrap(), 7);
+        let mut buf = [0xffu8; 7];
+        io.read_at(0, &mut buf).unwrap();
+        assert_eq!(&buf, b"\0\0\0\0abc");
+        let err = io.read_at(5, &mut [0u8; 4]).unwrap_err();
+        assert!(matches!(err, SombraError::Io(inner) if inner.kind() == ErrorKind::UnexpectedEof));
+        io.truncate(2).unwrap();
+        assert_eq!(io.into_inner(), vec![0, 0]);
+    }
 }
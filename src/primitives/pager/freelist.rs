@@ -112,6 +112,34 @@ impl FreeCache {
         Some(page)
     }
 
+    /// Removes the lowest cached page if it sits below `limit`.
+    pub fn pop_lowest_below(&mut self, limit: PageId) -> Option<PageId> {
+        let first = self.extents.first()?;
+        if first.start.0 >= limit.0 {
+            return None;
+        }
+        let page = first.start;
+        if first.len > 1 {
+            self.extents[0].start.0 += 1;
+            self.extents[0].len -= 1;
+        } else {
+            self.extents.remove(0);
+        }
+        self.rebuild();
+        Some(page)
+    }
+
+    /// Returns true when `page` falls inside a cached extent.
+    pub fn contains(&self, page: PageId) -> bool {
+        let idx = self
+            .extents
+            .partition_point(|extent| extent.start.0 <= page.0);
+        idx > 0 && {
+            let extent = &self.extents[idx - 1];
+            page.0 < extent.start.0 + extent.len as u64
+        }
+    }
+
     /// Removes up to `len` contiguous pages from the cache, returning the extent.
     pub fn pop_extent(&mut self, len: u32) -> Option<Extent> {
         if len == 0 {
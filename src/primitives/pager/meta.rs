@@ -43,7 +43,9 @@ const META_STORAGE_NEXT_VERSION_PTR: Range<usize> = PAGE_HDR_LEN + 192..PAGE_HDR
 const META_IFA_ADJ_OUT_ROOT: Range<usize> = PAGE_HDR_LEN + 200..PAGE_HDR_LEN + 208;
 const META_IFA_ADJ_IN_ROOT: Range<usize> = PAGE_HDR_LEN + 208..PAGE_HDR_LEN + 216;
 const META_IFA_OVERFLOW_ROOT: Range<usize> = PAGE_HDR_LEN + 216..PAGE_HDR_LEN + 224;
-const META_RESERVED_3: Range<usize> = PAGE_HDR_LEN + 224..PAGE_HDR_LEN + 232;
+const META_DICT_NAME_KIND_ROOT: Range<usize> = PAGE_HDR_LEN + 224..PAGE_HDR_LEN + 232;
+const META_STORAGE_NODE_COUNT: Range<usize> = PAGE_HDR_LEN + 232..PAGE_HDR_LEN + 240;
+const META_STORAGE_EDGE_COUNT: Range<usize> = PAGE_HDR_LEN + 240..PAGE_HDR_LEN + 248;
 
 /// Database metadata stored in page 0 containing configuration and root pointers.
 ///
@@ -113,6 +115,12 @@ pub struct Meta {
     pub ifa_adj_in_root: PageId,
     /// Root page ID for IFA overflow blocks B-tree.
     pub ifa_overflow_root: PageId,
+    /// Root page ID for the B-tree recording the schema role of each dictionary name.
+    pub dict_name_kind_root: PageId,
+    /// Number of live (non-deleted) nodes.
+    pub storage_node_count: u64,
+    /// Number of live (non-deleted) edges.
+    pub storage_edge_count: u64,
 }
 
 /// Creates a new database metadata page with default values and writes it to page 0.
@@ -158,6 +166,9 @@ pub fn create_meta(io: &dyn FileIo, page_size: u32) -> Result<Meta> {
         ifa_adj_out_root: PageId(0),
         ifa_adj_in_root: PageId(0),
         ifa_overflow_root: PageId(0),
+        dict_name_kind_root: PageId(0),
+        storage_node_count: 0,
+        storage_edge_count: 0,
     };
     let mut buf = vec![0u8; page_size as usize];
     write_meta_page(&mut buf, &meta)?;
@@ -238,7 +249,9 @@ pub fn write_meta_page(buf: &mut [u8], meta: &Meta) -> Result<()> {
     buf[META_IFA_ADJ_OUT_ROOT].copy_from_slice(&meta.ifa_adj_out_root.0.to_be_bytes());
     buf[META_IFA_ADJ_IN_ROOT].copy_from_slice(&meta.ifa_adj_in_root.0.to_be_bytes());
     buf[META_IFA_OVERFLOW_ROOT].copy_from_slice(&meta.ifa_overflow_root.0.to_be_bytes());
-    buf[META_RESERVED_3].fill(0);
+    buf[META_DICT_NAME_KIND_ROOT].copy_from_slice(&meta.dict_name_kind_root.0.to_be_bytes());
+    buf[META_STORAGE_NODE_COUNT].copy_from_slice(&meta.storage_node_count.to_be_bytes());
+    buf[META_STORAGE_EDGE_COUNT].copy_from_slice(&meta.storage_edge_count.to_be_bytes());
     page::clear_crc32(&mut buf[..PAGE_HDR_LEN])?;
     let crc = page_crc32(PageId(0).0, meta.salt, &buf[..page_size]);
     buf[page::header::CRC32].copy_from_slice(&crc.to_be_bytes());
@@ -343,9 +356,11 @@ pub fn read_meta_page(buf: &[u8]) -> Result<Meta> {
     let ifa_overflow_root = PageId(u64::from_be_bytes(
         buf[META_IFA_OVERFLOW_ROOT].try_into().unwrap(),
     ));
-    if buf[META_RESERVED_3].iter().any(|b| *b != 0) {
-        return Err(SombraError::Corruption("meta reserved3 field non-zero"));
-    }
+    let dict_name_kind_root = PageId(u64::from_be_bytes(
+        buf[META_DICT_NAME_KIND_ROOT].try_into().unwrap(),
+    ));
+    let storage_node_count = u64::from_be_bytes(buf[META_STORAGE_NODE_COUNT].try_into().unwrap());
+    let storage_edge_count = u64::from_be_bytes(buf[META_STORAGE_EDGE_COUNT].try_into().unwrap());
     Ok(Meta {
         page_size,
         salt,
@@ -378,6 +393,9 @@ pub fn read_meta_page(buf: &[u8]) -> Result<Meta> {
         ifa_adj_out_root,
         ifa_adj_in_root,
         ifa_overflow_root,
+        dict_name_kind_root,
+        storage_node_count,
+        storage_edge_count,
     })
 }
 
@@ -385,7 +403,7 @@ impl fmt::Display for Meta {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "Meta(page_size={}, salt={}, format_version={}, free_head={}, next_page={}, last_checkpoint_lsn={}, wal_salt={}, wal_policy_flags={}, dict_str_to_id_root={}, dict_id_to_str_root={}, dict_next_str_id={}, storage_flags={}, storage_nodes_root={}, storage_edges_root={}, storage_adj_fwd_root={}, storage_adj_rev_root={}, storage_degree_root={}, storage_index_catalog_root={}, storage_label_index_root={}, storage_prop_chunk_root={}, storage_prop_btree_root={}, storage_version_log_root={}, storage_next_node_id={}, storage_next_edge_id={}, storage_next_version_ptr={}, storage_inline_prop_blob={}, storage_inline_prop_value={}, storage_ddl_epoch={}, ifa_adj_out_root={}, ifa_adj_in_root={}, ifa_overflow_root={})",
+            "Meta(page_size={}, salt={}, format_version={}, free_head={}, next_page={}, last_checkpoint_lsn={}, wal_salt={}, wal_policy_flags={}, dict_str_to_id_root={}, dict_id_to_str_root={}, dict_next_str_id={}, storage_flags={}, storage_nodes_root={}, storage_edges_root={}, storage_adj_fwd_root={}, storage_adj_rev_root={}, storage_degree_root={}, storage_index_catalog_root={}, storage_label_index_root={}, storage_prop_chunk_root={}, storage_prop_btree_root={}, storage_version_log_root={}, storage_next_node_id={}, storage_next_edge_id={}, storage_next_version_ptr={}, storage_inline_prop_blob={}, storage_inline_prop_value={}, storage_ddl_epoch={}, ifa_adj_out_root={}, ifa_adj_in_root={}, ifa_overflow_root={}, dict_name_kind_root={}, storage_node_count={}, storage_edge_count={})",
             self.page_size,
             self.salt,
             self.format_version,
@@ -417,6 +435,9 @@ impl fmt::Display for Meta {
             self.ifa_adj_out_root.0,
             self.ifa_adj_in_root.0,
             self.ifa_overflow_root.0,
+            self.dict_name_kind_root.0,
+            self.storage_node_count,
+            self.storage_edge_count,
         )
     }
 }
@@ -4,10 +4,12 @@ mod frame;
 mod freelist;
 mod meta;
 mod pager;
+mod retained;
 
-pub use meta::{load_meta, Meta};
+pub use meta::{load_meta, write_meta_page, Meta};
 pub use pager::{
-    AsyncFsyncBacklog, AutockptContext, BackgroundMaintainer, CheckpointMode, PageMut, PageRef,
-    PageStore, Pager, PagerOptions, PagerStats, ReadGuard, Synchronous, WriteGuard,
-    MVCC_READER_WARN_THRESHOLD_MS,
+    AsyncFsyncBacklog, AutockptContext, BackgroundMaintainer, CheckpointMode, FreelistCheck,
+    OpenReport, PageMut, PageRef, PageStore, Pager, PagerOptions, PagerStats, ReadGuard,
+    RecoveryOptions, Synchronous, VerifyOnOpen, WalTailRepair, WriteGuard,
+    CHECKPOINT_LOCK_TIMED_OUT, MVCC_READER_WARN_THRESHOLD_MS, RECOVERY_TIMED_OUT,
 };
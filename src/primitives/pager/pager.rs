@@ -19,12 +19,17 @@ use parking_lot::{
 use super::frame::{Frame, FrameState};
 use super::freelist::{free_page_capacity, read_free_page, write_free_page, Extent, FreeCache};
 use super::meta::{create_meta, load_meta, write_meta_page, Meta};
+use super::retained::RetainedPages;
 use crate::primitives::{
-    concurrency::{ReaderGuard as LockReaderGuard, SingleWriter, WriterGuard as LockWriterGuard},
-    io::{FileIo, StdFileIo},
+    concurrency::{
+        CheckpointGuard, ReaderGuard as LockReaderGuard, SingleWriter,
+        WriterGuard as LockWriterGuard,
+    },
+    io::{FileIo, MemFileIo, StdFileIo},
     wal::{
-        Wal, WalAllocatorStats, WalCommitBacklog, WalCommitConfig, WalCommitter, WalFrame,
-        WalFrameOwned, WalFramePtr, WalOptions, WalSyncMode,
+        DiscardedFrames, GroupCommitStats, Wal, WalAllocatorStats, WalCommitBacklog,
+        WalCommitConfig, WalCommitter, WalFrame, WalFrameOwned, WalFramePtr, WalOptions,
+        WalSyncMode, DEFAULT_SYNC_RETRIES,
     },
 };
 use crate::storage::{
@@ -34,8 +39,10 @@ use crate::storage::{
 };
 use crate::types::{
     page::{self, PageHeader, PAGE_HDR_LEN},
-    page_crc32, Lsn, PageId, Result, SombraError,
+    page_crc32, ChecksumKind, Lsn, PageId, Result, SombraError,
 };
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 use tracing::{debug, info, warn};
 
 #[cfg(test)]
@@ -86,6 +93,14 @@ pub struct PagerOptions {
     pub wal_segment_size_bytes: u64,
     /// Number of WAL segments to preallocate ahead of time.
     pub wal_preallocate_segments: u32,
+    /// Algorithm checksumming WAL frames; see [`WalOptions::checksum`].
+    pub wal_checksum: ChecksumKind,
+    /// Whether WAL open and replay check frame payload checksums; see
+    /// [`WalOptions::verify_payloads`].
+    pub wal_verify_payloads: bool,
+    /// Times a WAL fsync failing with a transient error is retried before the
+    /// commit fails; see [`WalOptions::sync_retries`].
+    pub wal_sync_retries: u32,
     /// Enable direct commit path when no contention detected.
     pub direct_commit_enabled: bool,
     /// Delay window in microseconds for coalescing fsyncs in direct commit path.
@@ -97,6 +112,20 @@ pub struct PagerOptions {
     /// When false on macOS, uses regular fsync which is ~10x faster but doesn't
     /// guarantee durability on power failure. Has no effect on other platforms.
     pub fullfsync: bool,
+    /// Expose page pre-images through [`WriteGuard::changed_pages`].
+    ///
+    /// Pre-images are always held for rollback (one page-sized buffer per page
+    /// touched by the transaction), so enabling this adds no steady-state cost;
+    /// each `changed_pages` call copies a pre- and post-image per modified page.
+    pub retain_page_preimages: bool,
+    /// Pages to load ahead of a cache miss once reads are detected as sequential.
+    ///
+    /// Zero disables read-ahead. Random access resets the detector, so only
+    /// ascending page runs (such as full B-tree scans) trigger prefetching.
+    pub readahead_pages: usize,
+    /// Longest [`Pager::checkpoint_image`] waits for readers and the writer to
+    /// drain before failing with [`CHECKPOINT_LOCK_TIMED_OUT`].
+    pub checkpoint_lock_wait_ms: u64,
 }
 
 struct PendingWalFrame {
@@ -224,9 +253,15 @@ impl Default for PagerOptions {
             async_fsync_max_wait_ms: 0,
             wal_segment_size_bytes: 64 * 1024 * 1024,
             wal_preallocate_segments: 0,
+            wal_checksum: ChecksumKind::Crc32,
+            wal_verify_payloads: true,
+            wal_sync_retries: DEFAULT_SYNC_RETRIES,
             direct_commit_enabled: true,
             direct_fsync_delay_us: 0, // No delay for single-threaded performance
             fullfsync: true,          // Default to true for maximum durability
+            retain_page_preimages: false,
+            readahead_pages: 0,
+            checkpoint_lock_wait_ms: 30_000,
         }
     }
 }
@@ -275,6 +310,111 @@ pub enum CheckpointMode {
     BestEffort,
 }
 
+/// Page checksum verification performed when a database is opened.
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum VerifyOnOpen {
+    /// Open without checking page checksums.
+    #[default]
+    Off,
+    /// Check a random fraction (`0.0..=1.0`) of pages.
+    ///
+    /// Pages are chosen by an RNG seeded from the database salt, so the same
+    /// file is always sampled the same way.
+    Sample(f64),
+    /// Check every allocated page.
+    Full,
+}
+
+/// Work done by WAL recovery to bring a database online.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct OpenReport {
+    /// Partially written commit cut off the end of the WAL, if any.
+    pub wal_tail: Option<WalTailRepair>,
+    /// Damaged frames, and every commit after them, dropped from the WAL
+    /// because [`RecoveryOptions::repair`] was set.
+    pub wal_damage: Option<WalTailRepair>,
+    /// WAL frames written back into the database file.
+    pub frames_replayed: u64,
+    /// Wall-clock time spent reading and replaying the WAL.
+    pub recovery_time: Duration,
+}
+
+impl OpenReport {
+    /// Returns whether anything was discarded while opening.
+    pub fn repaired(&self) -> bool {
+        self.wal_tail.is_some() || self.wal_damage.is_some()
+    }
+}
+
+/// WAL frames discarded during recovery.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct WalTailRepair {
+    /// LSN of the first discarded frame.
+    pub lsn: Lsn,
+    /// Frames dropped, torn or not.
+    pub discarded_frames: u64,
+    /// WAL offset replay stopped at; every frame before it was replayed.
+    pub valid_up_to: u64,
+}
+
+impl From<DiscardedFrames> for WalTailRepair {
+    fn from(dropped: DiscardedFrames) -> Self {
+        Self {
+            lsn: dropped.lsn,
+            discarded_frames: dropped.frames,
+            valid_up_to: dropped.valid_up_to,
+        }
+    }
+}
+
+/// On-disk freelist problems found by [`Pager::check_freelist`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct FreelistCheck {
+    /// Freelist pages walked from the meta page.
+    pub chain_pages: u64,
+    /// Listed free pages at or beyond the end of the file.
+    pub out_of_range: u64,
+    /// Pages listed more than once, by overlapping extents, by a revisited
+    /// chain page, or as both a chain page and a free extent.
+    pub duplicates: u64,
+}
+
+impl FreelistCheck {
+    /// Returns whether the freelist lists every page at most once and only
+    /// pages inside the file.
+    pub fn is_clean(&self) -> bool {
+        self.out_of_range == 0 && self.duplicates == 0
+    }
+}
+
+/// Error returned when recovery finds damaged WAL frames followed by later
+/// commits.
+const DAMAGED_WAL: &str = "wal holds damaged frames before later commits";
+
+/// Error returned when WAL recovery runs past [`RecoveryOptions::max_time`].
+pub const RECOVERY_TIMED_OUT: &str = "recovery exceeded time budget";
+
+/// Error returned when the exclusive checkpoint lock stays busy past
+/// [`PagerOptions::checkpoint_lock_wait_ms`].
+pub const CHECKPOINT_LOCK_TIMED_OUT: &str = "timed out waiting for the checkpoint lock";
+
+/// How [`Pager::open_recovering`] treats the WAL left by a previous process.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct RecoveryOptions {
+    /// Drop damaged WAL frames, and every commit after them, instead of
+    /// failing.
+    ///
+    /// A partially written commit at the very end of the WAL is always
+    /// dropped; this only matters when damage is followed by later commits.
+    pub repair: bool,
+    /// Abandon recovery once it has run this long; `None` waits for it to
+    /// finish.
+    ///
+    /// Nothing is removed from the WAL until replay completes, so an
+    /// abandoned recovery is simply redone on the next open.
+    pub max_time: Option<Duration>,
+}
+
 /// Desired read snapshot semantics.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum ReadConsistency {
@@ -351,17 +491,69 @@ impl WalDurableCookie {
     }
 }
 
+/// Replays committed WAL frames into the database file and resets the WAL.
+///
+/// The WAL only yields frames of complete commits; a partially written
+/// commit at its end is dropped and reported. Damaged frames followed by
+/// later commits are an error unless `repair` is set, in which case they are
+/// dropped along with everything after them. Replay fails with
+/// [`RECOVERY_TIMED_OUT`] once it runs past `max_time`.
 fn recover_database(
     wal: &Wal,
     db_io: &dyn FileIo,
     meta: &mut Meta,
     page_size: usize,
     replay_limit: Option<Lsn>,
-) -> Result<Lsn> {
+    recovery: RecoveryOptions,
+) -> Result<(Lsn, OpenReport)> {
+    let started = Instant::now();
+    let check_deadline = |frames: usize| match recovery.max_time {
+        Some(budget) if started.elapsed() >= budget => {
+            warn!(
+                budget_ms = budget.as_millis() as u64,
+                frames, "pager.recover.time_budget_exceeded"
+            );
+            Err(SombraError::Timeout(RECOVERY_TIMED_OUT.to_string()))
+        }
+        _ => Ok(()),
+    };
+    let mut report = OpenReport::default();
+    if let Some(damage) = wal.damaged_frames() {
+        if !recovery.repair {
+            return Err(SombraError::Corruption(DAMAGED_WAL));
+        }
+        warn!(
+            lsn = damage.lsn.0,
+            discarded_frames = damage.frames,
+            valid_up_to = damage.valid_up_to,
+            "pager.recover.wal_damage_dropped"
+        );
+        report.wal_damage = Some(damage.into());
+    }
+    if let Some(torn) = wal.torn_tail() {
+        warn!(
+            lsn = torn.lsn.0,
+            discarded_frames = torn.frames,
+            valid_up_to = torn.valid_up_to,
+            "pager.recover.wal_tail_dropped"
+        );
+        report.wal_tail = Some(torn.into());
+    }
+    // Dropped frames stay on disk in recycled segments; start the next log
+    // past them so they are never mistaken for frames of the new one.
+    let dropped_max = [wal.damaged_frames(), wal.torn_tail()]
+        .into_iter()
+        .flatten()
+        .map(|dropped| dropped.max_lsn)
+        .max();
     let mut iter = wal.iter()?;
     let mut frames = Vec::new();
     let mut max_lsn = meta.last_checkpoint_lsn;
-    while let Some(frame) = iter.next_frame()? {
+    loop {
+        check_deadline(frames.len())?;
+        let Some(frame) = iter.next_frame()? else {
+            break;
+        };
         if let Some(limit) = replay_limit {
             if frame.lsn.0 > limit.0 {
                 break;
@@ -378,16 +570,26 @@ fn recover_database(
         }
         frames.push(frame);
     }
+    let next_lsn =
+        |checkpoint: Lsn| Lsn(dropped_max.map_or(checkpoint, |lsn| lsn.max(checkpoint)).0 + 1);
     if frames.is_empty() {
         let _ = wal.recycle_active_segments()?;
-        wal.reset(Lsn(meta.last_checkpoint_lsn.0 + 1))?;
-        return Ok(Lsn(meta.last_checkpoint_lsn.0 + 1));
+        let next = next_lsn(meta.last_checkpoint_lsn);
+        wal.reset(next)?;
+        report.recovery_time = started.elapsed();
+        return Ok((next, report));
     }
-    for frame in &frames {
+    for (replayed, frame) in frames.iter().enumerate() {
+        check_deadline(replayed)?;
         let offset = page_offset(frame.page_id, page_size);
         db_io.write_at(offset, frame.payload.as_slice())?;
     }
     db_io.sync_all()?;
+    // Replayed commits carry their own meta page; stamp the checkpoint LSN onto
+    // that copy instead of the one loaded before replay.
+    if frames.iter().any(|frame| frame.page_id == PageId(0)) {
+        *meta = load_meta(db_io, meta.page_size)?;
+    }
     meta.last_checkpoint_lsn = max_lsn;
     let mut meta_buf = vec![0u8; page_size];
     write_meta_page(&mut meta_buf, meta)?;
@@ -396,8 +598,11 @@ fn recover_database(
     let refreshed = load_meta(db_io, meta.page_size)?;
     *meta = refreshed;
     let _ = wal.recycle_active_segments()?;
-    wal.reset(Lsn(meta.last_checkpoint_lsn.0 + 1))?;
-    Ok(Lsn(meta.last_checkpoint_lsn.0 + 1))
+    let next = next_lsn(meta.last_checkpoint_lsn);
+    wal.reset(next)?;
+    report.frames_replayed = frames.len() as u64;
+    report.recovery_time = started.elapsed();
+    Ok((next, report))
 }
 
 /// Reader age threshold (ms) that triggers MVCC lag warnings.
@@ -414,6 +619,8 @@ pub struct PagerStats {
     pub evictions: u64,
     /// Number of dirty pages written back.
     pub dirty_writebacks: u64,
+    /// Number of pages loaded into the cache by sequential read-ahead.
+    pub readahead_pages: u64,
     /// Total number of MVCC page versions retained.
     pub mvcc_page_versions_total: u64,
     /// Number of pages currently tracking historical versions.
@@ -442,6 +649,8 @@ pub struct PagerStats {
     pub lock_writer: bool,
     /// Whether checkpoint lock is held.
     pub lock_checkpoint: bool,
+    /// Batch size distribution of the WAL group-commit worker.
+    pub wal_group_commit: GroupCommitStats,
 }
 
 /// Context provided to background maintenance hooks after auto-checkpoints.
@@ -466,6 +675,13 @@ pub trait PageStore: 'static {
     fn page_size(&self) -> u32;
     /// Retrieves a page within a read transaction.
     fn get_page(&self, guard: &ReadGuard, id: PageId) -> Result<PageRef>;
+    /// Reads a page within a read transaction into `buf`, reusing its allocation.
+    fn read_page_into(&self, guard: &ReadGuard, id: PageId, buf: &mut Vec<u8>) -> Result<()> {
+        let page = self.get_page(guard, id)?;
+        buf.clear();
+        buf.extend_from_slice(page.data());
+        Ok(())
+    }
     /// Retrieves a page while holding a write transaction.
     fn get_page_with_write(&self, guard: &mut WriteGuard<'_>, id: PageId) -> Result<PageRef>;
     /// Begins a read transaction.
@@ -507,11 +723,26 @@ pub trait PageStore: 'static {
         None
     }
 
+    /// Returns the number of pages available for reuse when tracked by the store.
+    fn free_page_count(&self) -> Option<u64> {
+        None
+    }
+
     /// Returns async fsync backlog details, including pending cookie LSN.
     fn async_fsync_backlog(&self) -> Option<AsyncFsyncBacklog> {
         None
     }
 
+    /// Returns the distinct page ids written by WAL frames with an LSN greater than `lsn`.
+    ///
+    /// Returns `Ok(None)` when the WAL no longer retains every frame after `lsn`
+    /// (for example because a checkpoint has since truncated it) or when the
+    /// store has no WAL.
+    fn wal_pages_since(&self, lsn: Lsn) -> Result<Option<Vec<PageId>>> {
+        let _ = lsn;
+        Ok(None)
+    }
+
     /// Enables or disables checksum verification on page reads.
     fn set_checksum_verification(&self, enabled: bool) {
         let _ = enabled;
@@ -593,6 +824,7 @@ pub struct ReadGuard {
     commit_reader: CommitReader,
     _metrics: ReaderMetricsHandle,
     evicted: Arc<AtomicBool>,
+    retained: RetainedPages,
 }
 
 /// Guard for a write transaction, tracking modifications and state for rollback.
@@ -669,6 +901,28 @@ impl ReadGuard {
         Ok(())
     }
 
+    /// Returns `id`'s bytes as a slice borrowed from this guard.
+    ///
+    /// The guard keeps the last few sliced pages and reads each new page into
+    /// the buffer of the least recently sliced one, so a scan allocates a
+    /// fixed handful of buffers however many pages it visits, where
+    /// [`PageStore::get_page`] allocates for every page. Pages still retained
+    /// are handed back without touching `store`.
+    pub fn page_slice(&mut self, store: &dyn PageStore, id: PageId) -> Result<&[u8]> {
+        self.validate()?;
+        if !self.retained.touch(id) {
+            let mut buf = self.retained.spare();
+            store.read_page_into(self, id, &mut buf)?;
+            self.retained.insert(id, buf);
+        }
+        Ok(self.retained.latest())
+    }
+
+    /// Number of pages retained for [`ReadGuard::page_slice`].
+    pub fn retained_pages(&self) -> usize {
+        self.retained.len()
+    }
+
     /// Marks this reader as evicted.
     ///
     /// This is typically called by the vacuum process when a reader exceeds
@@ -703,6 +957,14 @@ impl<'a> WriteGuard<'a> {
         self.pager.allocate_page_in_txn(self)
     }
 
+    /// Allocates the lowest free page below `limit`, if the free cache holds one.
+    ///
+    /// Unlike [`WriteGuard::allocate_page`] this never grows the file, which
+    /// lets callers move pages toward the front of the database.
+    pub fn allocate_page_below(&mut self, limit: PageId) -> Result<Option<PageId>> {
+        self.pager.allocate_page_below_in_txn(self, limit)
+    }
+
     /// Marks a page as free within this write transaction.
     pub fn free_page(&mut self, id: PageId) -> Result<()> {
         self.pager.free_page_in_txn(self, id)
@@ -758,6 +1020,65 @@ impl<'a> WriteGuard<'a> {
     pub fn take_extension<T: Any>(&mut self) -> Option<T> {
         self.extensions.remove::<T>()
     }
+
+    /// Returns `(page, before, after)` images for every page modified so far.
+    ///
+    /// Entries are ordered by page id. `before` is the page as it was when the
+    /// transaction first touched it and `after` is its current contents. Pages
+    /// allocated by the transaction are included whether or not they have been
+    /// written yet, with an all-zero `before`; pages no longer cached are read
+    /// back from the database file. The set is always empty unless
+    /// [`PagerOptions::retain_page_preimages`] is enabled, and it is discarded
+    /// when the transaction commits or rolls back.
+    pub fn changed_pages(&self) -> Result<Vec<(PageId, Vec<u8>, Vec<u8>)>> {
+        if !self.pager.options.lock().retain_page_preimages {
+            return Ok(Vec::new());
+        }
+        let page_size = self.pager.page_size;
+        let mut pages: Vec<PageId> = self
+            .original_pages
+            .keys()
+            .chain(self.allocated_pages.iter())
+            .copied()
+            .filter(|page| !self.freed_pages.contains(page))
+            .collect();
+        pages.sort_by_key(|page| page.0);
+        pages.dedup();
+        let inner = self.pager.inner.lock();
+        let mut changed = Vec::with_capacity(pages.len());
+        for page in pages {
+            let before = if self.allocated_pages.contains(&page) {
+                vec![0u8; page_size]
+            } else {
+                match self.original_pages.get(&page) {
+                    Some(before) => before.clone(),
+                    None => continue,
+                }
+            };
+            let after = match inner.page_table.get(&page) {
+                Some(&idx) => inner.frames[idx].buf.read().to_vec(),
+                None => {
+                    let mut buf = vec![0u8; page_size];
+                    match self
+                        .pager
+                        .db_io
+                        .read_at(page_offset(page, page_size), &mut buf)
+                    {
+                        Ok(()) => {}
+                        // Allocated past the end of the file and never written back.
+                        Err(SombraError::Io(err)) if err.kind() == ErrorKind::UnexpectedEof => {
+                            buf.fill(0);
+                        }
+                        Err(err) => return Err(err),
+                    }
+                    buf
+                }
+            };
+            changed.push((page, before, after));
+        }
+        Ok(changed)
+    }
+
     fn release_writer_lock(&mut self) {
         if let Some(lock) = self.lock.take() {
             drop(lock);
@@ -798,6 +1119,8 @@ impl Drop for WriteGuard<'_> {
 }
 
 const NORMAL_SYNC_DELAY_MS: u64 = 10;
+/// Consecutive ascending page reads required before read-ahead kicks in.
+const READAHEAD_MIN_RUN: u32 = 2;
 
 struct WalSyncState {
     scheduled: bool,
@@ -919,6 +1242,8 @@ struct PagerInner {
     cold_count: usize,
     test_pages: Vec<PageId>,
     test_lookup: HashSet<PageId>,
+    last_read_page: Option<PageId>,
+    sequential_reads: u32,
 }
 
 impl PagerInner {
@@ -945,9 +1270,25 @@ impl PagerInner {
             cold_count: 0,
             test_pages: Vec::new(),
             test_lookup: HashSet::new(),
+            last_read_page: None,
+            sequential_reads: 0,
         }
     }
 
+    /// Records a snapshot page read and reports whether reads look sequential.
+    fn observe_read(&mut self, id: PageId) -> bool {
+        let ascending = self
+            .last_read_page
+            .is_some_and(|last| last.0.checked_add(1) == Some(id.0));
+        self.sequential_reads = if ascending {
+            self.sequential_reads.saturating_add(1)
+        } else {
+            0
+        };
+        self.last_read_page = Some(id);
+        self.sequential_reads >= READAHEAD_MIN_RUN
+    }
+
     fn set_frame_state(&mut self, idx: usize, new_state: FrameState) {
         let frame = &mut self.frames[idx];
         if frame.state == new_state {
@@ -1006,7 +1347,7 @@ pub struct Pager {
     last_autocheckpoint: Mutex<Option<Instant>>,
     wal_sync_state: Arc<Mutex<WalSyncState>>,
     wal_cookie: Option<Arc<WalDurableCookie>>,
-    async_fsync_state: Option<Arc<Mutex<AsyncFsyncState>>>,
+    async_fsync_state: Mutex<Option<Arc<Mutex<AsyncFsyncState>>>>,
     checksum_verify_on_read: AtomicBool,
     latest_visible_lsn: AtomicU64,
     durable_lsn: Arc<AtomicU64>,
@@ -1027,17 +1368,137 @@ impl Pager {
         let path = path.as_ref();
         let db = Arc::new(StdFileIo::open(path)?);
         let mut meta = create_meta(db.as_ref(), options.page_size)?;
-        Self::open_internal(path, db, &mut meta, options, true)
+        Self::open_internal(
+            path,
+            db,
+            &mut meta,
+            options,
+            true,
+            RecoveryOptions::default(),
+        )
+        .map(|(pager, _)| pager)
     }
 
     /// Opens an existing pager database at the specified path.
     ///
-    /// This loads metadata and performs WAL recovery if needed.
+    /// This loads metadata and performs WAL recovery if needed. A partially
+    /// written commit at the end of the WAL is dropped; recovery fails with a
+    /// corruption error if damaged frames are followed by later commits; see
+    /// [`Pager::open_with_repair`].
     pub fn open(path: impl AsRef<Path>, options: PagerOptions) -> Result<Self> {
+        Self::open_recovering(path, options, RecoveryOptions::default()).map(|(pager, _)| pager)
+    }
+
+    /// Opens an existing pager database, discarding damaged WAL frames and
+    /// every commit after them instead of failing.
+    ///
+    /// Only commits before the damage are replayed, and the returned report
+    /// describes what was dropped. Damage to the main file,
+    /// such as an unreadable meta page, is never repaired and still fails.
+    pub fn open_with_repair(
+        path: impl AsRef<Path>,
+        options: PagerOptions,
+    ) -> Result<(Self, OpenReport)> {
+        let recovery = RecoveryOptions {
+            repair: true,
+            ..RecoveryOptions::default()
+        };
+        Self::open_recovering(path, options, recovery)
+    }
+
+    /// Opens an existing pager database, replaying the WAL as `recovery`
+    /// directs and reporting what the replay did.
+    ///
+    /// Fails with a [`SombraError::Timeout`] carrying [`RECOVERY_TIMED_OUT`]
+    /// when replay outlasts [`RecoveryOptions::max_time`].
+    pub fn open_recovering(
+        path: impl AsRef<Path>,
+        options: PagerOptions,
+        recovery: RecoveryOptions,
+    ) -> Result<(Self, OpenReport)> {
         let path = path.as_ref();
         let db = Arc::new(StdFileIo::open(path)?);
         let mut meta = load_meta(db.as_ref(), options.page_size)?;
-        Self::open_internal(path, db, &mut meta, options, false)
+        Self::open_internal(path, db, &mut meta, options, false, recovery)
+    }
+
+    /// Opens a database whose main file lives in memory, starting from `image`.
+    ///
+    /// `image` is a copy of a checkpointed database file such as
+    /// [`Pager::checkpoint_image`] returns. Nothing is written to `path`
+    /// itself; it only places the WAL and lock files, and checkpoints fold the
+    /// WAL back into the in-memory file.
+    pub fn open_image(
+        path: impl AsRef<Path>,
+        image: Vec<u8>,
+        options: PagerOptions,
+    ) -> Result<Self> {
+        let path = path.as_ref();
+        let db = Arc::new(MemFileIo::new(image));
+        let mut meta = load_meta(db.as_ref(), options.page_size)?;
+        Self::open_internal(
+            path,
+            db,
+            &mut meta,
+            options,
+            false,
+            RecoveryOptions::default(),
+        )
+        .map(|(pager, _)| pager)
+    }
+
+    /// Verifies on-disk page checksums according to `mode`.
+    ///
+    /// Pages are read straight from the database file, bypassing the cache and
+    /// WAL overlays. Pages that were never written are skipped. Returns the
+    /// number of pages checked, or a corruption error on the first mismatch.
+    pub fn verify_page_checksums(&self, mode: VerifyOnOpen) -> Result<u64> {
+        let fraction = match mode {
+            VerifyOnOpen::Off => return Ok(0),
+            VerifyOnOpen::Full => 1.0,
+            VerifyOnOpen::Sample(fraction) => {
+                if !(0.0..=1.0).contains(&fraction) {
+                    return Err(SombraError::Invalid(
+                        "verify_on_open sample fraction must be within 0.0..=1.0",
+                    ));
+                }
+                fraction
+            }
+        };
+        let (next_page, salt, page_size) = {
+            let inner = self.inner.lock();
+            (inner.meta.next_page, inner.meta.salt, inner.meta.page_size)
+        };
+        let mut rng = ChaCha8Rng::seed_from_u64(salt);
+        let mut buf = vec![0u8; self.page_size];
+        let mut checked = 0u64;
+        for page_no in 1..next_page.0 {
+            if fraction < 1.0 && !rng.gen_bool(fraction) {
+                continue;
+            }
+            let page_id = PageId(page_no);
+            match self
+                .db_io
+                .read_at(page_offset(page_id, self.page_size), &mut buf)
+            {
+                Ok(()) => {}
+                Err(SombraError::Io(err)) if err.kind() == ErrorKind::UnexpectedEof => break,
+                Err(err) => return Err(err),
+            }
+            if buf.iter().all(|byte| *byte == 0) {
+                continue;
+            }
+            let header = PageHeader::decode(&buf[..PAGE_HDR_LEN])?;
+            if header.page_no != page_id || header.page_size != page_size {
+                return Err(SombraError::Corruption("page header mismatch"));
+            }
+            page::clear_crc32(&mut buf[..PAGE_HDR_LEN])?;
+            if page_crc32(page_no, salt, &buf) != header.crc32 {
+                return Err(SombraError::Corruption("page crc mismatch"));
+            }
+            checked += 1;
+        }
+        Ok(checked)
     }
 
     /// Sets the synchronous mode at runtime.
@@ -1052,6 +1513,48 @@ impl Pager {
         options.synchronous
     }
 
+    /// Enables or disables async fsync for `Synchronous::Full` commits at runtime.
+    ///
+    /// Disabling drains the pending async fsync backlog so every commit that
+    /// already returned is durable once this call completes.
+    pub fn set_async_fsync(&self, enabled: bool) -> Result<()> {
+        if enabled {
+            // Install the worker state before flipping the option so a racing
+            // commit never observes `async_fsync` without somewhere to queue.
+            let mut slot = self.async_fsync_state.lock();
+            if slot.is_none() {
+                let durable = Lsn(self.durable_lsn.load(AtomicOrdering::Acquire));
+                *slot = Some(Arc::new(Mutex::new(AsyncFsyncState::new(durable))));
+            }
+        }
+        self.options.lock().async_fsync = enabled;
+        if enabled {
+            Ok(())
+        } else {
+            self.drain_async_fsync()
+        }
+    }
+
+    /// Returns whether async fsync is currently enabled.
+    pub fn async_fsync(&self) -> bool {
+        let options = self.options.lock();
+        options.async_fsync
+    }
+
+    /// Sets the maximum time async fsync waits to coalesce commits at runtime.
+    ///
+    /// The new window applies from the next time the async fsync worker starts.
+    pub fn set_async_fsync_max_wait_ms(&self, ms: u64) {
+        let mut options = self.options.lock();
+        options.async_fsync_max_wait_ms = ms;
+    }
+
+    /// Returns the current async fsync coalesce window in milliseconds.
+    pub fn async_fsync_max_wait_ms(&self) -> u64 {
+        let options = self.options.lock();
+        options.async_fsync_max_wait_ms
+    }
+
     /// Sets the WAL commit coalesce time in milliseconds at runtime.
     pub fn set_wal_coalesce_ms(&self, ms: u64) {
         let config = {
@@ -1091,7 +1594,8 @@ impl Pager {
         meta: &mut Meta,
         options: PagerOptions,
         is_create: bool,
-    ) -> Result<Self> {
+        recovery: RecoveryOptions,
+    ) -> Result<(Self, OpenReport)> {
         let wal_dir = wal_path(path);
         let mut wal_options = WalOptions::new(
             meta.page_size,
@@ -1101,6 +1605,9 @@ impl Pager {
         wal_options.segment_size_bytes = options.wal_segment_size_bytes;
         wal_options.preallocate_segments = options.wal_preallocate_segments;
         wal_options.fullfsync = options.fullfsync;
+        wal_options.checksum = options.wal_checksum;
+        wal_options.verify_payloads = options.wal_verify_payloads;
+        wal_options.sync_retries = options.wal_sync_retries;
         let wal = Wal::open(&wal_dir, wal_options)?;
         let wal_cookie = if options.async_fsync {
             Some(Arc::new(WalDurableCookie::new(wal_cookie_path(path))))
@@ -1112,9 +1619,9 @@ impl Pager {
         } else {
             meta.last_checkpoint_lsn
         };
-        let next_lsn = if is_create {
+        let (next_lsn, report) = if is_create {
             wal.reset(Lsn(meta.last_checkpoint_lsn.0 + 1))?;
-            Lsn(meta.last_checkpoint_lsn.0 + 1)
+            (Lsn(meta.last_checkpoint_lsn.0 + 1), OpenReport::default())
         } else {
             recover_database(
                 wal.as_ref(),
@@ -1126,6 +1633,7 @@ impl Pager {
                 } else {
                     None
                 },
+                recovery,
             )?
         };
         if let Some(cookie) = wal_cookie.as_ref() {
@@ -1157,7 +1665,7 @@ impl Pager {
             last_autocheckpoint: Mutex::new(None),
             wal_sync_state: Arc::new(Mutex::new(WalSyncState::new())),
             wal_cookie,
-            async_fsync_state,
+            async_fsync_state: Mutex::new(async_fsync_state),
             checksum_verify_on_read: AtomicBool::new(true),
             latest_visible_lsn: AtomicU64::new(meta.last_checkpoint_lsn.0),
             durable_lsn,
@@ -1170,7 +1678,7 @@ impl Pager {
             background_hooks: Mutex::new(Vec::new()),
         };
         pager.load_freelist()?;
-        Ok(pager)
+        Ok((pager, report))
     }
 
     fn load_freelist(&self) -> Result<()> {
@@ -1251,6 +1759,110 @@ impl Pager {
         self.wal.allocator_stats()
     }
 
+    /// Returns the number of pages available for reuse by the allocator.
+    ///
+    /// Counts free-cache extents plus pages released since the freelist was
+    /// last rebuilt; pages holding the freelist itself are not included.
+    pub fn free_page_count(&self) -> u64 {
+        let inner = self.inner.lock();
+        let cached: u64 = inner
+            .free_cache
+            .extents()
+            .iter()
+            .map(|extent| u64::from(extent.len))
+            .sum();
+        cached + inner.pending_free.len() as u64
+    }
+
+    /// Returns true if `id` is free: cached for reuse, released since the last
+    /// freelist rebuild, or holding the freelist itself.
+    pub fn is_page_free(&self, id: PageId) -> bool {
+        let inner = self.inner.lock();
+        inner.free_cache.contains(id)
+            || inner.pending_free.contains(&id)
+            || inner.freelist_pages.contains(&id)
+    }
+
+    /// Walks the freelist chain as last written to the database file.
+    ///
+    /// Opening a pager drops out-of-range extents from its in-memory free
+    /// set but keeps overlapping ones, which could hand the same page to two
+    /// writers; [`Pager::rewrite_freelist`] replaces the chain with the
+    /// deduplicated set.
+    pub fn check_freelist(&self) -> Result<FreelistCheck> {
+        let meta = self.meta()?;
+        let mut check = FreelistCheck::default();
+        let mut listed: HashSet<u64> = HashSet::new();
+        let mut next = meta.free_head;
+        let mut buf = vec![0u8; self.page_size];
+        while next.0 != 0 {
+            if !listed.insert(next.0) {
+                check.duplicates += 1;
+                break;
+            }
+            if next.0 >= meta.next_page.0 {
+                check.out_of_range += 1;
+                break;
+            }
+            check.chain_pages += 1;
+            self.db_io
+                .read_at(page_offset(next, self.page_size), &mut buf)?;
+            let free_page = read_free_page(&buf, self.page_size, &meta)?;
+            for page in free_page
+                .extents
+                .iter()
+                .flat_map(|extent| extent.iter_pages())
+            {
+                if page.0 >= meta.next_page.0 {
+                    check.out_of_range += 1;
+                } else if !listed.insert(page.0) {
+                    check.duplicates += 1;
+                }
+            }
+            next = free_page.next;
+        }
+        Ok(check)
+    }
+
+    /// Checkpoints, then rewrites the freelist chain from the in-memory free
+    /// set, which drops duplicate and out-of-range entries. Waits up to
+    /// [`PagerOptions::checkpoint_lock_wait_ms`] for readers and the writer
+    /// to drain.
+    pub fn rewrite_freelist(&self) -> Result<()> {
+        let checkpoint_guard = self.acquire_checkpoint_lock()?;
+        self.perform_checkpoint()?;
+        let mut inner = self.inner.lock();
+        self.rebuild_freelist(&mut inner)?;
+        self.db_io.sync_all()?;
+        let mut meta_buf = vec![0u8; self.page_size];
+        write_meta_page(&mut meta_buf, &inner.meta)?;
+        self.db_io.write_at(0, &meta_buf)?;
+        self.db_io.sync_all()?;
+        inner.meta_dirty = false;
+        drop(inner);
+        drop(checkpoint_guard);
+        info!("pager.freelist.rewritten");
+        Ok(())
+    }
+
+    /// Returns every free page in ascending order, using the same definition
+    /// as [`Pager::is_page_free`].
+    pub fn free_pages(&self) -> Vec<PageId> {
+        let inner = self.inner.lock();
+        let mut pages: Vec<PageId> = inner
+            .free_cache
+            .extents()
+            .iter()
+            .flat_map(|extent| extent.iter_pages())
+            .chain(inner.pending_free.iter().copied())
+            .chain(inner.freelist_pages.iter().copied())
+            .collect();
+        drop(inner);
+        pages.sort_by_key(|page| page.0);
+        pages.dedup();
+        pages
+    }
+
     /// Returns a snapshot of current pager statistics.
     pub fn stats(&self) -> PagerStats {
         let (active_total, begin_total, end_total) = self.reader_metrics.snapshot();
@@ -1294,6 +1906,7 @@ impl Pager {
         stats.lock_readers = lock_snapshot.readers;
         stats.lock_writer = lock_snapshot.writer;
         stats.lock_checkpoint = lock_snapshot.checkpoint;
+        stats.wal_group_commit = self.wal_committer.group_commit_stats();
         stats
     }
 
@@ -1331,6 +1944,31 @@ impl Pager {
         Ok((idx, false))
     }
 
+    /// Loads up to `count` pages following `after` into the cache.
+    ///
+    /// Best effort: stops at the end of the allocated range or when no clean
+    /// frame can be reclaimed, leaving the remaining pages to be read on demand.
+    fn read_ahead(&self, after: PageId, count: usize) -> Result<()> {
+        let mut inner = self.inner.lock();
+        let end = after
+            .0
+            .saturating_add(count as u64)
+            .min(inner.meta.next_page.0.saturating_sub(1));
+        for page_no in after.0 + 1..=end {
+            let page_id = PageId(page_no);
+            if inner.page_table.contains_key(&page_id) {
+                continue;
+            }
+            let Ok(idx) = self.obtain_available_frame(&mut inner) else {
+                break;
+            };
+            self.load_page_into_frame(&mut inner, idx, page_id)?;
+            inner.page_table.insert(page_id, idx);
+            inner.stats.readahead_pages += 1;
+        }
+        Ok(())
+    }
+
     fn obtain_available_frame(&self, inner: &mut PagerInner) -> Result<usize> {
         if let Some(idx) = inner.frames.iter().enumerate().find_map(|(idx, frame)| {
             if frame.id.is_none() && frame.pin_count == 0 {
@@ -1761,6 +2399,28 @@ impl Pager {
         Ok(page)
     }
 
+    fn allocate_page_below_in_txn(
+        &self,
+        guard: &mut WriteGuard<'_>,
+        limit: PageId,
+    ) -> Result<Option<PageId>> {
+        let wal_frames = self.wal.stats().frames_appended;
+        let mut inner = self.inner.lock();
+        let should_reload = inner.free_cache.extents().is_empty()
+            && !inner.meta_dirty
+            && wal_frames == 0
+            && (inner.meta.free_head.0 != 0 || !inner.freelist_pages.is_empty());
+        if should_reload {
+            self.load_freelist_locked(&mut inner)?;
+        }
+        let Some(page) = inner.free_cache.pop_lowest_below(limit) else {
+            return Ok(None);
+        };
+        inner.meta_dirty = true;
+        guard.allocated_pages.push(page);
+        Ok(Some(page))
+    }
+
     fn allocate_extent_in_txn(&self, guard: &mut WriteGuard<'_>, len: u32) -> Result<Extent> {
         if len == 0 {
             return Err(SombraError::Invalid("extent length must be non-zero"));
@@ -1846,6 +2506,9 @@ impl Pager {
     }
 
     fn rollback_transaction(&self, guard: &mut WriteGuard<'_>) -> Result<()> {
+        if let Some(lsn) = guard.commit_lsn {
+            self.commit_table.lock().abort(lsn.0);
+        }
         let mut inner = self.inner.lock();
         inner.meta = guard.meta_snapshot.clone();
         inner.free_cache = guard.free_cache_snapshot.clone();
@@ -2353,6 +3016,49 @@ impl Pager {
         self.wal_committer.write_borrowed_frames(&refs, sync_mode)
     }
 
+    /// Checkpoints and returns a copy of the database file.
+    ///
+    /// The copy is taken while the exclusive checkpoint lock is still held, so
+    /// it captures exactly the state this checkpoint folded in and opens
+    /// without any WAL. Waits up to [`PagerOptions::checkpoint_lock_wait_ms`]
+    /// for readers and the writer to drain. Returns `Ok(None)` without reading
+    /// the file when it is longer than `max_len` bytes.
+    pub fn checkpoint_image(&self, max_len: u64) -> Result<Option<Vec<u8>>> {
+        let checkpoint_guard = self.acquire_checkpoint_lock()?;
+        self.perform_checkpoint()?;
+        let len = self.db_io.len()?;
+        if len > max_len {
+            return Ok(None);
+        }
+        let len = usize::try_from(len)
+            .map_err(|_| SombraError::Invalid("database file too large to copy"))?;
+        let mut image = vec![0u8; len];
+        self.db_io.read_at(0, &mut image)?;
+        drop(checkpoint_guard);
+        Ok(Some(image))
+    }
+
+    /// Takes the exclusive checkpoint lock, failing with
+    /// [`CHECKPOINT_LOCK_TIMED_OUT`] once [`PagerOptions::checkpoint_lock_wait_ms`]
+    /// passes without readers and the writer draining.
+    fn acquire_checkpoint_lock(&self) -> Result<CheckpointGuard> {
+        let wait = Duration::from_millis(self.options.lock().checkpoint_lock_wait_ms);
+        let deadline = Instant::now() + wait;
+        loop {
+            if let Some(guard) = self.locks.try_acquire_checkpoint()? {
+                return Ok(guard);
+            }
+            if Instant::now() >= deadline {
+                warn!(
+                    wait_ms = wait.as_millis() as u64,
+                    "pager.checkpoint_lock.timed_out"
+                );
+                return Err(SombraError::Timeout(CHECKPOINT_LOCK_TIMED_OUT.to_string()));
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+
     fn run_checkpoint(&self, mode: CheckpointMode) -> Result<()> {
         pager_test_log!("[pager.checkpoint] start mode={:?}", mode);
         debug!(mode = ?mode, "pager.run_checkpoint.start");
@@ -2593,7 +3299,8 @@ impl Pager {
     }
 
     fn schedule_async_fsync(&self, target: Lsn) -> Result<()> {
-        let Some(state_arc) = self.async_fsync_state.as_ref() else {
+        let state_arc = self.async_fsync_state.lock().clone();
+        let Some(state_arc) = state_arc.as_ref() else {
             self.durable_lsn
                 .fetch_max(target.0, AtomicOrdering::Release);
             if let Some(cookie) = &self.wal_cookie {
@@ -2639,6 +3346,36 @@ impl Pager {
         Ok(())
     }
 
+    /// Synchronously fsyncs any commits still queued for the async fsync worker.
+    fn drain_async_fsync(&self) -> Result<()> {
+        let state_arc = self.async_fsync_state.lock().clone();
+        let Some(state_arc) = state_arc else {
+            return Ok(());
+        };
+        let target = {
+            let mut state = state_arc.lock();
+            if let Some(err) = state.last_error.take() {
+                return Err(err);
+            }
+            if state.pending_lsn.0 <= state.durable_lsn.0 {
+                return Ok(());
+            }
+            state.pending_lsn
+        };
+        self.wal.sync()?;
+        if let Some(cookie) = &self.wal_cookie {
+            cookie.persist(target)?;
+        }
+        self.commit_table.lock().mark_durable_up_to(target.0)?;
+        self.durable_lsn
+            .fetch_max(target.0, AtomicOrdering::Release);
+        let mut state = state_arc.lock();
+        if target.0 > state.durable_lsn.0 {
+            state.durable_lsn = target;
+        }
+        Ok(())
+    }
+
     fn maybe_autocheckpoint(&self) -> Result<()> {
         let (autocheckpoint_pages, autocheckpoint_ms) = {
             let options = self.options.lock();
@@ -3060,93 +3797,28 @@ impl Pager {
         Ok(overlay.map(Arc::<[u8]>::from))
     }
 
-    fn begin_read_consistency(&self, consistency: ReadConsistency) -> Result<ReadGuard> {
-        let lock = self.locks.acquire_reader()?;
-        let snapshot_lsn = match consistency {
-            ReadConsistency::Checkpoint => {
-                let inner = self.inner.lock();
-                inner.meta.last_checkpoint_lsn
-            }
-            ReadConsistency::LatestCommitted => self.latest_committed_lsn(),
-        };
-        let evicted = Arc::new(AtomicBool::new(false));
-        let commit_reader = {
-            let mut table = self.commit_table.lock();
-            match table.register_reader(
-                snapshot_lsn.0,
-                Instant::now(),
-                thread::current().id(),
-                Arc::downgrade(&evicted),
-            ) {
-                Ok(reader) => reader,
-                Err(err) => {
-                    drop(lock);
-                    return Err(err);
-                }
-            }
-        };
-        let metrics_handle = ReaderMetricsHandle::new(Arc::clone(&self.reader_metrics));
-        Ok(ReadGuard {
-            _lock: lock,
-            snapshot_lsn,
-            consistency,
-            commit_table: Arc::clone(&self.commit_table),
-            commit_reader,
-            _metrics: metrics_handle,
-            evicted,
-        })
-    }
-
-    /// Begins a read transaction targeting the latest committed snapshot.
-    pub fn begin_latest_committed_read(&self) -> Result<ReadGuard> {
-        self.begin_read_consistency(ReadConsistency::LatestCommitted)
-    }
-
-    /// Begins a read transaction restricted to checkpoint durability.
-    pub fn begin_checkpoint_read(&self) -> Result<ReadGuard> {
-        self.begin_read_consistency(ReadConsistency::Checkpoint)
-    }
-
-    /// Returns the current metadata.
-    pub fn meta(&self) -> Result<Meta> {
-        let inner = self.inner.lock();
-        Ok(inner.meta.clone())
-    }
-
-    #[cfg(test)]
-    fn drop_version_payloads_for_test(&self) {
-        let mut chains = self.version_chains.lock();
-        for entries in chains.values_mut() {
-            for entry in entries.iter_mut() {
-                if entry.wal_offset.is_some() {
-                    entry.data = None;
-                }
-            }
-        }
-    }
-
-    #[cfg(test)]
-    fn test_version_page_for_snapshot(
+    /// Reads `id` as of `guard`'s snapshot.
+    ///
+    /// Pages served from the version chain or the WAL overlay come back as the
+    /// shared buffer they already live in; every other page is copied into
+    /// `buf`, which is resized to the page size without reallocating once it
+    /// has held a page.
+    fn read_snapshot_page(
         &self,
-        page_id: PageId,
-        snapshot_lsn: Lsn,
-    ) -> Option<Arc<[u8]>> {
-        self.version_page_for_snapshot(page_id, snapshot_lsn)
-    }
-}
-
-impl PageStore for Pager {
-    fn page_size(&self) -> u32 {
-        self.page_size as u32
-    }
-
-    fn get_page(&self, guard: &ReadGuard, id: PageId) -> Result<PageRef> {
-        let mut cached: Option<Arc<[u8]>> = None;
+        guard: &ReadGuard,
+        id: PageId,
+        buf: &mut Vec<u8>,
+    ) -> Result<Option<Arc<[u8]>>> {
+        buf.resize(self.page_size, 0);
+        let mut cached = false;
         let mut refresh_idx: Option<usize> = None;
         let mut has_uncommitted = false;
         let snapshot_lsn = guard.snapshot_lsn;
+        let readahead_pages = self.options.lock().readahead_pages;
+        let mut read_ahead = false;
         let (salt, page_size, last_checkpoint_lsn) = {
             let mut inner = self.inner.lock();
+            let sequential = readahead_pages > 0 && inner.observe_read(id);
             if let Some(&idx) = inner.page_table.get(&id) {
                 let frame = &inner.frames[idx];
                 has_uncommitted = frame.dirty && !frame.newly_allocated;
@@ -3162,13 +3834,12 @@ impl PageStore for Pager {
                     inner.stats.misses += 1;
                 } else {
                     inner.stats.hits += 1;
-                    let buf = inner.frames[idx].buf.read();
-                    let mut copy = vec![0u8; self.page_size];
-                    copy.copy_from_slice(&buf[..]);
-                    cached = Some(Arc::<[u8]>::from(copy));
+                    buf.copy_from_slice(&inner.frames[idx].buf.read()[..]);
+                    cached = true;
                 }
             } else {
                 inner.stats.misses += 1;
+                read_ahead = sequential;
             }
             (
                 inner.meta.salt,
@@ -3186,18 +3857,20 @@ impl PageStore for Pager {
         // to see the pre-modification state while a write transaction is in progress.
         if has_uncommitted {
             if let Some(version_data) = self.version_page_for_snapshot(id, snapshot_lsn) {
-                return Ok(PageRef {
-                    id,
-                    data: version_data,
-                });
+                return Ok(Some(version_data));
             }
         }
+        // A clean cached frame already holds the committed bytes; only the
+        // overlay of a newer snapshot can supersede them.
+        if cached {
+            if snapshot_lsn.0 > last_checkpoint_lsn.0 {
+                return self.overlay_page_for_snapshot(id, last_checkpoint_lsn, snapshot_lsn);
+            }
+            return Ok(None);
+        }
 
-        let mut buf = vec![0u8; self.page_size];
         let verify_crc = self.checksum_verify_on_read.load(AtomicOrdering::Relaxed);
-        let read_result = self
-            .db_io
-            .read_at(page_offset(id, self.page_size), &mut buf);
+        let read_result = self.db_io.read_at(page_offset(id, self.page_size), buf);
         let fresh = match read_result {
             Ok(()) => false,
             Err(SombraError::Io(err)) if err.kind() == ErrorKind::UnexpectedEof => {
@@ -3210,7 +3883,7 @@ impl PageStore for Pager {
             let mut inner = self.inner.lock();
             if let Some(frame) = inner.frames.get_mut(idx) {
                 let mut guard_buf = frame.buf.write();
-                guard_buf.copy_from_slice(&buf);
+                guard_buf.copy_from_slice(buf);
                 frame.dirty = false;
                 frame.pending_checkpoint = false;
                 frame.newly_allocated = false;
@@ -3226,9 +3899,12 @@ impl PageStore for Pager {
                 return Err(SombraError::Corruption("page size mismatch"));
             }
             if verify_crc {
-                let mut scratch = buf.clone();
-                page::clear_crc32(&mut scratch[..PAGE_HDR_LEN])?;
-                let crc = page_crc32(id.0, salt, &scratch);
+                // Checksum with the CRC field zeroed, then put the header back.
+                let mut stored = [0u8; PAGE_HDR_LEN];
+                stored.copy_from_slice(&buf[..PAGE_HDR_LEN]);
+                page::clear_crc32(&mut buf[..PAGE_HDR_LEN])?;
+                let crc = page_crc32(id.0, salt, buf);
+                buf[..PAGE_HDR_LEN].copy_from_slice(&stored);
                 if crc != header.crc32 {
                     return Err(SombraError::Corruption("page crc mismatch"));
                 }
@@ -3242,18 +3918,112 @@ impl PageStore for Pager {
             if let Some(overlay) =
                 self.overlay_page_for_snapshot(id, last_checkpoint_lsn, snapshot_lsn)?
             {
-                return Ok(PageRef { id, data: overlay });
+                return Ok(Some(overlay));
             }
         }
-        if let Some(data) = cached {
-            return Ok(PageRef { id, data });
+        if read_ahead && !fresh {
+            self.read_ahead(id, readahead_pages)?;
         }
-        Ok(PageRef {
-            id,
-            data: Arc::from(buf),
+        Ok(None)
+    }
+
+    fn begin_read_consistency(&self, consistency: ReadConsistency) -> Result<ReadGuard> {
+        let lock = self.locks.acquire_reader()?;
+        let snapshot_lsn = match consistency {
+            ReadConsistency::Checkpoint => {
+                let inner = self.inner.lock();
+                inner.meta.last_checkpoint_lsn
+            }
+            ReadConsistency::LatestCommitted => self.latest_committed_lsn(),
+        };
+        let evicted = Arc::new(AtomicBool::new(false));
+        let commit_reader = {
+            let mut table = self.commit_table.lock();
+            match table.register_reader(
+                snapshot_lsn.0,
+                Instant::now(),
+                thread::current().id(),
+                Arc::downgrade(&evicted),
+            ) {
+                Ok(reader) => reader,
+                Err(err) => {
+                    drop(lock);
+                    return Err(err);
+                }
+            }
+        };
+        let metrics_handle = ReaderMetricsHandle::new(Arc::clone(&self.reader_metrics));
+        Ok(ReadGuard {
+            _lock: lock,
+            snapshot_lsn,
+            consistency,
+            commit_table: Arc::clone(&self.commit_table),
+            commit_reader,
+            _metrics: metrics_handle,
+            evicted,
+            retained: RetainedPages::default(),
         })
     }
 
+    /// Begins a read transaction targeting the latest committed snapshot.
+    pub fn begin_latest_committed_read(&self) -> Result<ReadGuard> {
+        self.begin_read_consistency(ReadConsistency::LatestCommitted)
+    }
+
+    /// Begins a read transaction restricted to checkpoint durability.
+    pub fn begin_checkpoint_read(&self) -> Result<ReadGuard> {
+        self.begin_read_consistency(ReadConsistency::Checkpoint)
+    }
+
+    /// Returns the current metadata.
+    pub fn meta(&self) -> Result<Meta> {
+        let inner = self.inner.lock();
+        Ok(inner.meta.clone())
+    }
+
+    #[cfg(test)]
+    fn drop_version_payloads_for_test(&self) {
+        let mut chains = self.version_chains.lock();
+        for entries in chains.values_mut() {
+            for entry in entries.iter_mut() {
+                if entry.wal_offset.is_some() {
+                    entry.data = None;
+                }
+            }
+        }
+    }
+
+    #[cfg(test)]
+    fn test_version_page_for_snapshot(
+        &self,
+        page_id: PageId,
+        snapshot_lsn: Lsn,
+    ) -> Option<Arc<[u8]>> {
+        self.version_page_for_snapshot(page_id, snapshot_lsn)
+    }
+}
+
+impl PageStore for Pager {
+    fn page_size(&self) -> u32 {
+        self.page_size as u32
+    }
+
+    fn get_page(&self, guard: &ReadGuard, id: PageId) -> Result<PageRef> {
+        let mut buf = Vec::new();
+        let data = match self.read_snapshot_page(guard, id, &mut buf)? {
+            Some(shared) => shared,
+            None => Arc::from(buf),
+        };
+        Ok(PageRef { id, data })
+    }
+
+    fn read_page_into(&self, guard: &ReadGuard, id: PageId, buf: &mut Vec<u8>) -> Result<()> {
+        if let Some(shared) = self.read_snapshot_page(guard, id, buf)? {
+            buf.copy_from_slice(&shared);
+        }
+        Ok(())
+    }
+
     fn get_page_with_write(&self, _guard: &mut WriteGuard<'_>, id: PageId) -> Result<PageRef> {
         let data = {
             let mut inner = self.inner.lock();
@@ -3345,8 +4115,12 @@ impl PageStore for Pager {
         Some(Pager::wal_allocator_stats(self))
     }
 
+    fn free_page_count(&self) -> Option<u64> {
+        Some(Pager::free_page_count(self))
+    }
+
     fn async_fsync_backlog(&self) -> Option<AsyncFsyncBacklog> {
-        self.async_fsync_state.as_ref().map(|state| {
+        self.async_fsync_state.lock().as_ref().map(|state| {
             let guard = state.lock();
             let pending_lag = guard.pending_lsn.0.saturating_sub(guard.durable_lsn.0);
             AsyncFsyncBacklog {
@@ -3358,6 +4132,26 @@ impl PageStore for Pager {
         })
     }
 
+    fn wal_pages_since(&self, lsn: Lsn) -> Result<Option<Vec<PageId>>> {
+        if lsn.0 < PageStore::last_checkpoint_lsn(self).0 {
+            return Ok(None);
+        }
+        let mut pages = HashSet::new();
+        let mut iter = self.wal.iter()?;
+        while let Some(frame) = iter.next_frame()? {
+            if frame.lsn.0 > lsn.0 {
+                pages.insert(frame.page_id);
+            }
+        }
+        // A checkpoint racing with the scan may have recycled frames we needed.
+        if lsn.0 < PageStore::last_checkpoint_lsn(self).0 {
+            return Ok(None);
+        }
+        let mut pages: Vec<PageId> = pages.into_iter().collect();
+        pages.sort_unstable();
+        Ok(Some(pages))
+    }
+
     fn set_checksum_verification(&self, enabled: bool) {
         self.checksum_verify_on_read
             .store(enabled, AtomicOrdering::Relaxed);
@@ -3420,6 +4214,8 @@ impl fmt::Display for PagerStats {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::primitives::pager::retained::RETAINED_PAGES;
+    use crate::primitives::wal::FlakySyncIo;
     use crate::types::page::PageKind;
     use rand::Rng;
     use std::collections::HashSet;
@@ -3690,6 +4486,52 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn async_fsync_toggles_at_runtime() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let options = PagerOptions {
+            cache_pages: 8,
+            autocheckpoint_pages: usize::MAX,
+            autocheckpoint_ms: None,
+            synchronous: Synchronous::Full,
+            ..PagerOptions::default()
+        };
+        let pager = Pager::create(dir.path().join("async_toggle.db"), options)?;
+        assert!(!pager.async_fsync());
+        assert!(pager.async_fsync_backlog().is_none());
+
+        let commit_one = |pager: &Pager| -> Result<Lsn> {
+            let mut write = pager.begin_write()?;
+            let _ = write.allocate_page()?;
+            pager.commit(write)
+        };
+
+        commit_one(&pager)?;
+        assert_eq!(pager.wal.stats().syncs, 1);
+
+        pager.set_async_fsync_max_wait_ms(200);
+        assert_eq!(pager.async_fsync_max_wait_ms(), 200);
+        pager.set_async_fsync(true)?;
+        assert!(pager.async_fsync());
+        let mut last = Lsn(0);
+        for _ in 0..4 {
+            last = commit_one(&pager)?;
+        }
+        // Commits return before the coalesced fsync lands.
+        assert!(pager.wal.stats().syncs < 5);
+        let backlog = pager.async_fsync_backlog().expect("async backlog");
+        assert_eq!(backlog.pending_lsn, last);
+
+        // Disabling drains the backlog before returning.
+        pager.set_async_fsync(false)?;
+        assert!(!pager.async_fsync());
+        assert!(pager.durable_lsn().0 >= last.0);
+        let syncs_before = pager.wal.stats().syncs;
+        commit_one(&pager)?;
+        assert_eq!(pager.wal.stats().syncs, syncs_before + 1);
+        Ok(())
+    }
+
     #[test]
     fn autocheckpoint_ms_triggers_checkpoint() -> Result<()> {
         init_tracing();
@@ -3799,6 +4641,147 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn rewrite_freelist_drops_overlapping_and_out_of_range_extents() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("freelist_repair.db");
+        let options = PagerOptions {
+            page_size: 4096,
+            cache_pages: 32,
+            prefetch_on_miss: false,
+            synchronous: Synchronous::Full,
+            autocheckpoint_pages: 64,
+            autocheckpoint_ms: None,
+            ..PagerOptions::default()
+        };
+        let pager = Pager::create(&path, options.clone())?;
+        let pages = {
+            let mut write = pager.begin_write()?;
+            let pages = (0..8)
+                .map(|_| write.allocate_page())
+                .collect::<Result<Vec<_>>>()?;
+            pager.commit(write)?;
+            pages
+        };
+        {
+            let mut write = pager.begin_write()?;
+            for page in &pages[2..5] {
+                write.free_page(*page)?;
+            }
+            pager.commit(write)?;
+        }
+        pager.checkpoint(CheckpointMode::Force)?;
+        assert!(pager.check_freelist()?.is_clean());
+        let meta = pager.meta()?;
+        drop(pager);
+
+        // Overlapping extents plus one past the end of the file.
+        let extents = [
+            Extent::new(pages[2], 2),
+            Extent::new(pages[3], 1),
+            Extent::new(PageId(meta.next_page.0 + 4), 1),
+        ];
+        let mut buf = vec![0u8; meta.page_size as usize];
+        write_free_page(&mut buf, meta.free_head, &meta, PageId(0), &extents)?;
+        let mut file = OpenOptions::new().write(true).open(&path)?;
+        std::io::Seek::seek(
+            &mut file,
+            std::io::SeekFrom::Start(page_offset(meta.free_head, meta.page_size as usize)),
+        )?;
+        file.write_all(&buf)?;
+        file.sync_all()?;
+        drop(file);
+
+        let pager = Pager::open(&path, options)?;
+        let check = pager.check_freelist()?;
+        assert_eq!(check.duplicates, 1);
+        assert_eq!(check.out_of_range, 1);
+        pager.rewrite_freelist()?;
+        assert!(pager.check_freelist()?.is_clean());
+        let free = pager.free_pages();
+        for page in &pages[2..5] {
+            assert!(free.contains(page), "page {} should stay free", page.0);
+        }
+        for page in pages[..2].iter().chain(&pages[5..]) {
+            assert!(
+                !pager.is_page_free(*page),
+                "page {} should stay live",
+                page.0
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn rewrite_freelist_gives_up_while_readers_hold_the_lock() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let options = PagerOptions {
+            checkpoint_lock_wait_ms: 20,
+            ..PagerOptions::default()
+        };
+        let pager = Pager::create(dir.path().join("freelist_wait.db"), options)?;
+        let read = pager.begin_read()?;
+        match pager.rewrite_freelist() {
+            Err(SombraError::Timeout(msg)) => assert_eq!(msg, CHECKPOINT_LOCK_TIMED_OUT),
+            other => panic!("expected checkpoint lock timeout, got {other:?}"),
+        }
+        drop(read);
+        pager.rewrite_freelist()?;
+        assert!(pager.check_freelist()?.is_clean());
+        Ok(())
+    }
+
+    #[test]
+    fn checkpoint_image_gives_up_while_readers_hold_the_lock() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let options = PagerOptions {
+            checkpoint_lock_wait_ms: 20,
+            ..PagerOptions::default()
+        };
+        let pager = Pager::create(dir.path().join("image_wait.db"), options)?;
+        let read = pager.begin_read()?;
+        match pager.checkpoint_image(u64::MAX) {
+            Err(SombraError::Timeout(msg)) => assert_eq!(msg, CHECKPOINT_LOCK_TIMED_OUT),
+            other => panic!("expected checkpoint lock timeout, got {other:?}"),
+        }
+        drop(read);
+        let image = pager
+            .checkpoint_image(u64::MAX)?
+            .expect("image within limit");
+        assert_eq!(image.len() as u64, pager.db_io.len()?);
+        assert!(pager.checkpoint_image(1)?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn commit_retries_transient_wal_sync_failures() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let options = PagerOptions {
+            synchronous: Synchronous::Full,
+            wal_sync_retries: 2,
+            ..PagerOptions::default()
+        };
+        let pager = Pager::create(dir.path().join("sync_retry.db"), options)?;
+        let commit_page = || -> Result<Lsn> {
+            let mut write = pager.begin_write()?;
+            let page = write.allocate_page()?;
+            write_test_payload(&pager, &mut write, page)?;
+            pager.commit(write)
+        };
+
+        let interrupted = std::io::ErrorKind::Interrupted;
+        let flaky = Arc::new(FlakySyncIo::new(2, interrupted));
+        pager.wal.override_sync_io(flaky.clone());
+        commit_page()?;
+        assert_eq!(flaky.attempts.load(AtomicOrdering::SeqCst), 3);
+
+        let failing = Arc::new(FlakySyncIo::new(usize::MAX, interrupted));
+        pager.wal.override_sync_io(failing.clone());
+        assert!(commit_page().is_err());
+        assert_eq!(failing.attempts.load(AtomicOrdering::SeqCst), 3);
+        Ok(())
+    }
+
     #[test]
     fn pager_random_workload() -> Result<()> {
         init_tracing();
@@ -3998,4 +4981,67 @@ mod tests {
             "write arc should succeed after lease drop"
         );
     }
+
+    #[test]
+    fn page_slices_match_copied_pages_and_recycle_buffers() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("page_slice.db");
+        let options = PagerOptions {
+            page_size: 4096,
+            ..PagerOptions::default()
+        };
+        let pager = Pager::create(&path, options)?;
+        let mut pages = Vec::new();
+        let mut write = pager.begin_write()?;
+        // Enough pages to cycle through the retained buffers several times.
+        for i in 0..40u8 {
+            let page = write.allocate_page()?;
+            write_test_payload(&pager, &mut write, page)?;
+            write.page_mut(page)?.data_mut()[PAGE_HDR_LEN + 4] = i;
+            pages.push(page);
+        }
+        pager.commit(write)?;
+
+        let decode = |bytes: &[u8]| -> Result<(PageHeader, Vec<u8>)> {
+            let header = PageHeader::decode(&bytes[..PAGE_HDR_LEN])?;
+            Ok((header, bytes[PAGE_HDR_LEN..PAGE_HDR_LEN + 5].to_vec()))
+        };
+        let fetches = |pager: &Pager| {
+            let stats = pager.stats();
+            stats.hits + stats.misses
+        };
+        const PASSES: u64 = 3;
+
+        let mut read = pager.begin_latest_committed_read()?;
+        let before = fetches(&pager);
+        let mut copied = Vec::new();
+        for &id in &pages {
+            for _ in 0..PASSES {
+                copied.push(decode(pager.get_page(&read, id)?.data())?);
+            }
+        }
+        let copy_fetches = fetches(&pager) - before;
+
+        let before = fetches(&pager);
+        let mut sliced = Vec::new();
+        for &id in &pages {
+            for _ in 0..PASSES {
+                sliced.push(decode(read.page_slice(&pager, id)?)?);
+            }
+        }
+        let slice_fetches = fetches(&pager) - before;
+
+        assert_eq!(copied, sliced);
+        assert_eq!(copy_fetches, PASSES * pages.len() as u64);
+        assert_eq!(slice_fetches, pages.len() as u64);
+        assert_eq!(read.retained_pages(), RETAINED_PAGES);
+
+        // Going back past the retained window fetches the page again.
+        let before = fetches(&pager);
+        let first = decode(read.page_slice(&pager, pages[0])?)?;
+        assert_eq!(first, copied[0]);
+        assert_eq!(fetches(&pager) - before, 1);
+        assert_eq!(read.retained_pages(), RETAINED_PAGES);
+        Ok(())
+    }
 }
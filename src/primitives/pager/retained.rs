@@ -0,0 +1,52 @@
+use crate::types::PageId;
+
+/// Most pages a read guard keeps for [`super::ReadGuard::page_slice`].
+pub(crate) const RETAINED_PAGES: usize = 8;
+
+/// Page buffers a read guard reuses so scans can parse pages in place.
+///
+/// Holds at most [`RETAINED_PAGES`] pages, least recently sliced first. A page
+/// that falls out hands its buffer to the next one, so a long scan settles on
+/// a fixed set of allocations instead of growing with the pages it visits.
+#[derive(Default)]
+pub(crate) struct RetainedPages {
+    pages: Vec<(PageId, Vec<u8>)>,
+}
+
+impl RetainedPages {
+    /// Marks `id` as the most recently sliced page if it is still retained.
+    pub(crate) fn touch(&mut self, id: PageId) -> bool {
+        let Some(pos) = self.pages.iter().position(|(page, _)| *page == id) else {
+            return false;
+        };
+        let entry = self.pages.remove(pos);
+        self.pages.push(entry);
+        true
+    }
+
+    /// Returns a buffer for the next page, recycling the oldest one once full.
+    pub(crate) fn spare(&mut self) -> Vec<u8> {
+        if self.pages.len() < RETAINED_PAGES {
+            return Vec::new();
+        }
+        self.pages.remove(0).1
+    }
+
+    /// Retains `buf` as the bytes of `id`.
+    pub(crate) fn insert(&mut self, id: PageId, buf: Vec<u8>) {
+        self.pages.push((id, buf));
+    }
+
+    /// Bytes of the most recently sliced page.
+    pub(crate) fn latest(&self) -> &[u8] {
+        self.pages
+            .last()
+            .map(|(_, buf)| &buf[..])
+            .unwrap_or_default()
+    }
+
+    /// Number of pages currently retained.
+    pub(crate) fn len(&self) -> usize {
+        self.pages.len()
+    }
+}
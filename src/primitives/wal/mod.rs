@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::fmt;
 use std::fs::{self, File, OpenOptions};
-use std::io::{self, IoSlice, Read, Write};
+use std::io::{self, Read, Write};
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering as AtomicOrdering};
 use std::sync::Arc;
@@ -18,11 +18,16 @@ use crate::storage::{
     record_wal_commit_direct_contention, record_wal_commit_group, record_wal_io_group_sample,
     record_wal_reused_segments, record_wal_sync_coalesced,
 };
-use crate::types::{Checksum, Crc32Fast, Lsn, PageId, Result, SombraError};
+use crate::types::{Checksum, ChecksumKind, Crc32Fast, Lsn, PageId, Result, SombraError};
 use parking_lot::{Condvar, Mutex};
 
 const WAL_MAGIC: [u8; 4] = *b"SOMW";
-const WAL_FORMAT_VERSION: u16 = 1;
+const WAL_FORMAT_VERSION: u16 = 2;
+/// Oldest segment format still readable. Version 1 frames carry no commit
+/// marker, so every intact frame of such a segment counts as committed.
+const MIN_WAL_FORMAT_VERSION: u16 = 1;
+/// Bit of the encoded page id that marks the last frame of a commit.
+const FRAME_COMMIT_FLAG: u64 = 1 << 63;
 const FILE_HEADER_LEN: usize = 32;
 const FRAME_HEADER_LEN: usize = 32;
 const WAL_MAX_IO_SLICES: usize = 512;
@@ -31,6 +36,8 @@ const WAL_LAYOUT_VERSION: u32 = 1;
 const WAL_LAYOUT_KIND: &str = "segmented_v1";
 /// Test-only delay for WAL batch application to surface backlogs.
 static APPLY_BATCH_DELAY_MS: AtomicU64 = AtomicU64::new(0);
+pub(crate) const DEFAULT_SYNC_RETRIES: u32 = 3;
+const SYNC_RETRY_BASE_BACKOFF: Duration = Duration::from_millis(1);
 /// Configuration options for opening a write-ahead log.
 #[derive(Clone, Debug)]
 pub struct WalOptions {
@@ -48,6 +55,31 @@ pub struct WalOptions {
     /// When false on macOS, uses regular fsync which is ~100x faster but doesn't
     /// guarantee durability on power failure.
     pub fullfsync: bool,
+    /// Accept a WAL whose salt differs from `wal_salt`, logging a warning.
+    ///
+    /// Meant for recovery and forensic tooling only: the WAL is opened
+    /// read-only, so it can be iterated but never appended to or reset.
+    pub ignore_salt_mismatch: bool,
+    /// Verify each frame's payload CRC in [`WalIterator::next_frame`]
+    /// (default `true`).
+    ///
+    /// Disabling this speeds up open and replay on trusted storage. Frame
+    /// headers and the CRC chain are still checked, but a corrupted payload,
+    /// including a torn final frame, is replayed as-is; the chain only stops
+    /// at the frame after it.
+    pub verify_payloads: bool,
+    /// Times [`Wal::sync`] retries an fsync that failed with a transient
+    /// error (`EINTR`/`EAGAIN`) before giving up (default 3).
+    ///
+    /// Retries back off exponentially from 1ms. Other IO errors are
+    /// returned immediately.
+    pub sync_retries: u32,
+    /// Algorithm checksumming frames and chaining them together (default
+    /// CRC32).
+    ///
+    /// Recorded in each segment header. An existing WAL keeps the algorithm
+    /// it was written with until [`Wal::reset`] starts it over.
+    pub checksum: ChecksumKind,
 }
 
 impl WalOptions {
@@ -60,6 +92,10 @@ impl WalOptions {
             segment_size_bytes: 64 * 1024 * 1024,
             preallocate_segments: 0,
             fullfsync: true,
+            ignore_salt_mismatch: false,
+            verify_payloads: true,
+            sync_retries: DEFAULT_SYNC_RETRIES,
+            checksum: ChecksumKind::Crc32,
         }
     }
 }
@@ -73,6 +109,10 @@ impl Default for WalOptions {
             segment_size_bytes: 64 * 1024 * 1024,
             preallocate_segments: 0,
             fullfsync: true,
+            ignore_salt_mismatch: false,
+            verify_payloads: true,
+            sync_retries: DEFAULT_SYNC_RETRIES,
+            checksum: ChecksumKind::Crc32,
         }
     }
 }
@@ -120,27 +160,49 @@ pub struct WalAllocatorStats {
     pub allocation_error: Option<String>,
 }
 
+/// Frames left out of the log at open because they cannot be replayed.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct DiscardedFrames {
+    /// LSN of the first commit left out.
+    pub lsn: Lsn,
+    /// Highest LSN among the frames left out.
+    pub max_lsn: Lsn,
+    /// Left-out frames whose headers were intact.
+    pub frames: u64,
+    /// Log offset where the replayable frames end.
+    pub valid_up_to: u64,
+}
+
 #[derive(Clone, Debug)]
 struct FileHeader {
+    version: u16,
     page_size: u32,
     wal_salt: u64,
     start_lsn: Lsn,
+    checksum: ChecksumKind,
 }
 
 impl FileHeader {
-    fn new(page_size: u32, wal_salt: u64, start_lsn: Lsn) -> Self {
+    fn new(page_size: u32, wal_salt: u64, start_lsn: Lsn, checksum: ChecksumKind) -> Self {
         Self {
+            version: WAL_FORMAT_VERSION,
             page_size,
             wal_salt,
             start_lsn,
+            checksum,
         }
     }
 
+    /// Returns whether frames under this header mark the end of each commit.
+    fn marks_commits(&self) -> bool {
+        self.version >= 2
+    }
+
     fn encode(&self) -> [u8; FILE_HEADER_LEN] {
         let mut buf = [0u8; FILE_HEADER_LEN];
         buf[0..4].copy_from_slice(&WAL_MAGIC);
-        buf[4..6].copy_from_slice(&WAL_FORMAT_VERSION.to_be_bytes());
-        buf[6..8].fill(0);
+        buf[4..6].copy_from_slice(&self.version.to_be_bytes());
+        buf[6..8].copy_from_slice(&self.checksum.code().to_be_bytes());
         buf[8..12].copy_from_slice(&self.page_size.to_be_bytes());
         buf[12..20].copy_from_slice(&self.wal_salt.to_be_bytes());
         buf[20..28].copy_from_slice(&self.start_lsn.0.to_be_bytes());
@@ -161,27 +223,27 @@ impl FileHeader {
             return Err(SombraError::Corruption("wal magic mismatch"));
         }
         let version = u16::from_be_bytes(header[4..6].try_into().unwrap());
-        if version != WAL_FORMAT_VERSION {
+        if !(MIN_WAL_FORMAT_VERSION..=WAL_FORMAT_VERSION).contains(&version) {
             return Err(SombraError::Corruption("wal format version mismatch"));
         }
-        if header[6..8] != [0, 0] {
-            return Err(SombraError::Corruption(
-                "wal reserved header bytes non-zero",
-            ));
-        }
         let stored_crc = u32::from_be_bytes(header[28..32].try_into().unwrap());
         header[28..32].fill(0);
         let crc = compute_crc32(&[&header]);
         if crc != stored_crc {
             return Err(SombraError::Corruption("wal header crc mismatch"));
         }
+        let checksum = ChecksumKind::from_code(u16::from_be_bytes([header[6], header[7]])).ok_or(
+            SombraError::Invalid("wal checksum algorithm is not supported by this build"),
+        )?;
         let page_size = u32::from_be_bytes(src[8..12].try_into().unwrap());
         let wal_salt = u64::from_be_bytes(src[12..20].try_into().unwrap());
         let start_lsn = Lsn(u64::from_be_bytes(src[20..28].try_into().unwrap()));
         Ok(Self {
+            version,
             page_size,
             wal_salt,
             start_lsn,
+            checksum,
         })
     }
 }
@@ -190,16 +252,26 @@ impl FileHeader {
 struct FrameHeader {
     frame_lsn: Lsn,
     page_id: PageId,
+    /// Whether this is the last frame of its commit; stored in the top bit of
+    /// the encoded page id.
+    commit: bool,
     prev_crc32_chain: u64,
     payload_crc32: u32,
     header_crc32: u32,
 }
 
 impl FrameHeader {
-    fn new(frame_lsn: Lsn, page_id: PageId, prev_crc32_chain: u64, payload_crc32: u32) -> Self {
+    fn new(
+        frame_lsn: Lsn,
+        page_id: PageId,
+        commit: bool,
+        prev_crc32_chain: u64,
+        payload_crc32: u32,
+    ) -> Self {
         Self {
             frame_lsn,
             page_id,
+            commit,
             prev_crc32_chain,
             payload_crc32,
             header_crc32: 0,
@@ -208,24 +280,28 @@ impl FrameHeader {
 
     fn encode(&self) -> [u8; FRAME_HEADER_LEN] {
         let mut buf = [0u8; FRAME_HEADER_LEN];
+        let mut page_word = self.page_id.0;
+        if self.commit {
+            page_word |= FRAME_COMMIT_FLAG;
+        }
         buf[0..8].copy_from_slice(&self.frame_lsn.0.to_be_bytes());
-        buf[8..16].copy_from_slice(&self.page_id.0.to_be_bytes());
+        buf[8..16].copy_from_slice(&page_word.to_be_bytes());
         buf[16..24].copy_from_slice(&self.prev_crc32_chain.to_be_bytes());
         buf[24..28].copy_from_slice(&self.payload_crc32.to_be_bytes());
         buf[28..32].copy_from_slice(&self.header_crc32.to_be_bytes());
         buf
     }
 
-    fn encode_with_crc(&self) -> [u8; FRAME_HEADER_LEN] {
+    fn encode_with_crc(&self, checksum: ChecksumKind) -> [u8; FRAME_HEADER_LEN] {
         let mut buf = self.encode();
         let mut crc_buf = buf;
         crc_buf[28..32].fill(0);
-        let crc = compute_crc32(&[&crc_buf]);
+        let crc = checksum.checksum(&[&crc_buf]);
         buf[28..32].copy_from_slice(&crc.to_be_bytes());
         buf
     }
 
-    fn decode(src: &[u8]) -> Result<Self> {
+    fn decode(src: &[u8], checksum: ChecksumKind) -> Result<Self> {
         if src.len() < FRAME_HEADER_LEN {
             return Err(SombraError::Corruption("wal frame header truncated"));
         }
@@ -233,17 +309,18 @@ impl FrameHeader {
         header.copy_from_slice(&src[..FRAME_HEADER_LEN]);
         let stored_crc = u32::from_be_bytes(header[28..32].try_into().unwrap());
         header[28..32].fill(0);
-        let crc = compute_crc32(&[&header]);
+        let crc = checksum.checksum(&[&header]);
         if crc != stored_crc {
             return Err(SombraError::Corruption("wal frame header crc mismatch"));
         }
         let frame_lsn = Lsn(u64::from_be_bytes(src[0..8].try_into().unwrap()));
-        let page_id = PageId(u64::from_be_bytes(src[8..16].try_into().unwrap()));
+        let page_word = u64::from_be_bytes(src[8..16].try_into().unwrap());
         let prev_crc32_chain = u64::from_be_bytes(src[16..24].try_into().unwrap());
         let payload_crc32 = u32::from_be_bytes(src[24..28].try_into().unwrap());
         Ok(Self {
             frame_lsn,
-            page_id,
+            page_id: PageId(page_word & !FRAME_COMMIT_FLAG),
+            commit: page_word & FRAME_COMMIT_FLAG != 0,
             prev_crc32_chain,
             payload_crc32,
             header_crc32: stored_crc,
@@ -381,9 +458,7 @@ fn list_segments(dir: &Path) -> Result<Vec<u64>> {
 fn create_segment_file(
     dir: &Path,
     id: u64,
-    page_size: u32,
-    wal_salt: u64,
-    start_lsn: Lsn,
+    header: &FileHeader,
     capacity: u64,
 ) -> Result<Arc<StdFileIo>> {
     let path = segment_path(dir, id);
@@ -393,8 +468,7 @@ fn create_segment_file(
         capacity_bytes = capacity,
         "wal.segment.create"
     );
-    let header = FileHeader::new(page_size, wal_salt, start_lsn);
-    let io = initialize_segment_file(&path, &header, capacity)?;
+    let io = initialize_segment_file(&path, header, capacity)?;
     Ok(Arc::new(io))
 }
 
@@ -419,17 +493,35 @@ fn list_recycle_segments(dir: &Path) -> Result<Vec<u64>> {
     Ok(ids)
 }
 
-fn open_segment_file(dir: &Path, id: u64, page_size: u32, wal_salt: u64) -> Result<Arc<StdFileIo>> {
-    let path = segment_path(dir, id);
-    let io = Arc::new(StdFileIo::open(&path)?);
+fn read_file_header(io: &StdFileIo) -> Result<FileHeader> {
     let mut buf = [0u8; FILE_HEADER_LEN];
     io.read_at(0, &mut buf)?;
-    let header = FileHeader::decode(&buf)?;
+    FileHeader::decode(&buf)
+}
+
+fn open_segment_file(
+    dir: &Path,
+    id: u64,
+    page_size: u32,
+    wal_salt: u64,
+    ignore_salt_mismatch: bool,
+) -> Result<Arc<StdFileIo>> {
+    let path = segment_path(dir, id);
+    let io = Arc::new(StdFileIo::open(&path)?);
+    let header = read_file_header(&io)?;
     if header.page_size != page_size {
         return Err(SombraError::Corruption("wal segment page size mismatch"));
     }
     if header.wal_salt != wal_salt {
-        return Err(SombraError::Corruption("wal segment salt mismatch"));
+        if !ignore_salt_mismatch {
+            return Err(SombraError::Corruption("wal segment salt mismatch"));
+        }
+        warn!(
+            segment_id = id,
+            expected = wal_salt,
+            found = header.wal_salt,
+            "wal.segment.salt_mismatch_ignored"
+        );
     }
     Ok(io)
 }
@@ -526,8 +618,25 @@ pub struct Wal {
     prealloc_thread: Mutex<Option<thread::JoinHandle<()>>>,
     prealloc_target: u32,
     pending_recycle: Mutex<Option<Vec<u64>>>,
+    /// Trailing commit left out at open because its last frame is missing.
+    torn_tail: Mutex<Option<DiscardedFrames>>,
+    /// Frames left out at open because damaged frames precede them.
+    damaged: Mutex<Option<DiscardedFrames>>,
     /// Whether to use F_FULLFSYNC on macOS (true) or regular fsync (false)
     fullfsync: bool,
+    /// Set when opened with [`WalOptions::ignore_salt_mismatch`].
+    read_only: bool,
+    /// Mirrors [`WalOptions::verify_payloads`] for iterators.
+    verify_payloads: bool,
+    /// Mirrors [`WalOptions::sync_retries`].
+    sync_retries: u32,
+    /// Mirrors [`WalOptions::checksum`]; applied to segments written after a
+    /// reset.
+    checksum: ChecksumKind,
+    /// File [`Wal::sync`] flushes in place of the active segment, so tests can
+    /// fail fsyncs underneath a full commit.
+    #[cfg(test)]
+    sync_override: Mutex<Option<Arc<dyn FileIo>>>,
 }
 
 impl Wal {
@@ -749,8 +858,20 @@ impl Wal {
         if manifest.page_size != options.page_size {
             return Err(SombraError::Corruption("wal manifest page size mismatch"));
         }
+        let read_only = options.ignore_salt_mismatch;
         if manifest.wal_salt != options.wal_salt {
-            return Err(SombraError::Corruption("wal manifest salt mismatch"));
+            if !read_only {
+                return Err(SombraError::Corruption("wal manifest salt mismatch"));
+            }
+            warn!(
+                wal_dir = ?dir,
+                expected = options.wal_salt,
+                found = manifest.wal_salt,
+                "wal.open.salt_mismatch_ignored"
+            );
+        }
+        if read_only {
+            return Self::open_read_only(dir, options, manifest);
         }
         let mut manifest_state = manifest;
         let mut manifest_dirty = false;
@@ -769,17 +890,16 @@ impl Wal {
         if manifest_dirty {
             manifest_state.persist(&dir)?;
         }
+        let mut header = FileHeader::new(
+            options.page_size,
+            options.wal_salt,
+            options.start_lsn,
+            options.checksum,
+        );
         let mut segment_ids = list_segments(&dir)?;
         if segment_ids.is_empty() {
             let id = manifest_state.next_segment_id;
-            create_segment_file(
-                &dir,
-                id,
-                options.page_size,
-                options.wal_salt,
-                options.start_lsn,
-                options.segment_size_bytes,
-            )?;
+            create_segment_file(&dir, id, &header, options.segment_size_bytes)?;
             manifest_state.next_segment_id += 1;
             manifest_state.persist(&dir)?;
             segment_ids.push(id);
@@ -797,25 +917,41 @@ impl Wal {
             manifest_state.persist(&dir)?;
         }
         let active_id = *segment_ids.last().expect("at least one segment");
-        let active_io = open_segment_file(&dir, active_id, options.page_size, options.wal_salt)?;
-        let header = FileHeader::new(options.page_size, options.wal_salt, options.start_lsn);
+        let active_io =
+            open_segment_file(&dir, active_id, options.page_size, options.wal_salt, false)?;
+        // Frames already on disk stay readable under the format and algorithm
+        // they were written with; the configured ones take over at the next
+        // reset.
+        let active_header = read_file_header(&active_io)?;
+        header.checksum = active_header.checksum;
+        header.version = active_header.version;
         let mut segment_cache = HashMap::new();
         segment_cache.insert(active_id, Arc::clone(&active_io));
-        let mut metadata = BTreeMap::new();
+        let mut scanned = Vec::with_capacity(segment_ids.len());
         for id in &segment_ids {
             let path = segment_path(&dir, *id);
             let raw_len = fs::metadata(&path)?.len().max(FILE_HEADER_LEN as u64);
             let io = if *id == active_id {
                 Arc::clone(&active_io)
             } else {
-                open_segment_file(&dir, *id, options.page_size, options.wal_salt)?
+                open_segment_file(&dir, *id, options.page_size, options.wal_salt, false)?
             };
-            let valid_len = detect_valid_prefix(&io, raw_len, options.page_size as usize, &header)?;
-            metadata.insert(*id, SegmentMeta { len: valid_len });
             if *id != active_id {
                 segment_cache.insert(*id, Arc::clone(&io));
             }
+            scanned.push((io, raw_len));
         }
+        let scan = scan_log(
+            &scanned,
+            options.page_size as usize,
+            &header,
+            options.verify_payloads,
+        )?;
+        let metadata: BTreeMap<u64, SegmentMeta> = segment_ids
+            .iter()
+            .zip(&scan.lens)
+            .map(|(id, len)| (*id, SegmentMeta { len: *len }))
+            .collect();
         let active_len = metadata
             .get(&active_id)
             .map(|meta| meta.len)
@@ -839,15 +975,128 @@ impl Wal {
             prealloc_thread: Mutex::new(None),
             prealloc_target: options.preallocate_segments,
             pending_recycle: Mutex::new(None),
+            torn_tail: Mutex::new(scan.torn_tail),
+            damaged: Mutex::new(scan.damaged),
             fullfsync: options.fullfsync,
+            read_only: false,
+            verify_payloads: options.verify_payloads,
+            sync_retries: options.sync_retries,
+            checksum: options.checksum,
+            #[cfg(test)]
+            sync_override: Mutex::new(None),
         });
         wal.initialize_ready_segments()?;
         wal.start_preallocator();
         Ok(wal)
     }
 
+    /// Opens an existing WAL for iteration only, trusting the salt recorded
+    /// on disk rather than the caller's.
+    ///
+    /// Nothing is written: the manifest is left as found, no segments are
+    /// created or preallocated, and mutating calls fail.
+    fn open_read_only(
+        dir: PathBuf,
+        options: WalOptions,
+        manifest: WalDirectoryManifest,
+    ) -> Result<Arc<Self>> {
+        let segment_ids = list_segments(&dir)?;
+        let Some(&active_id) = segment_ids.last() else {
+            return Err(SombraError::Invalid("wal has no segments to read"));
+        };
+        let active_header = {
+            let io =
+                open_segment_file(&dir, active_id, manifest.page_size, manifest.wal_salt, true)?;
+            read_file_header(&io)?
+        };
+        let mut header = FileHeader::new(
+            manifest.page_size,
+            manifest.wal_salt,
+            Lsn(manifest.start_lsn),
+            active_header.checksum,
+        );
+        header.version = active_header.version;
+        let mut segment_cache = HashMap::new();
+        let mut scanned = Vec::with_capacity(segment_ids.len());
+        for id in &segment_ids {
+            let path = segment_path(&dir, *id);
+            let raw_len = fs::metadata(&path)?.len().max(FILE_HEADER_LEN as u64);
+            let io = open_segment_file(&dir, *id, manifest.page_size, manifest.wal_salt, true)?;
+            segment_cache.insert(*id, Arc::clone(&io));
+            scanned.push((io, raw_len));
+        }
+        let scan = scan_log(
+            &scanned,
+            manifest.page_size as usize,
+            &header,
+            options.verify_payloads,
+        )?;
+        let metadata: BTreeMap<u64, SegmentMeta> = segment_ids
+            .iter()
+            .zip(&scan.lens)
+            .map(|(id, len)| (*id, SegmentMeta { len: *len }))
+            .collect();
+        let active_io = Arc::clone(&segment_cache[&active_id]);
+        let active_len = metadata[&active_id].len;
+        let writer = SegmentWriter::new(active_id, active_io, active_len, active_len);
+        let state = WalState::new(header, writer, manifest.segment_size_bytes);
+        Ok(Arc::new(Self {
+            dir,
+            page_size: manifest.page_size as usize,
+            state: Mutex::new(state),
+            segments: Mutex::new(metadata),
+            segment_cache: Mutex::new(segment_cache),
+            manifest: Mutex::new(manifest),
+            prealloc: Arc::new(PreallocQueues::new()),
+            prealloc_thread: Mutex::new(None),
+            prealloc_target: 0,
+            pending_recycle: Mutex::new(None),
+            torn_tail: Mutex::new(scan.torn_tail),
+            damaged: Mutex::new(scan.damaged),
+            fullfsync: options.fullfsync,
+            read_only: true,
+            verify_payloads: options.verify_payloads,
+            sync_retries: options.sync_retries,
+            checksum: options.checksum,
+            #[cfg(test)]
+            sync_override: Mutex::new(None),
+        }))
+    }
+
+    fn ensure_writable(&self) -> Result<()> {
+        if self.read_only {
+            return Err(SombraError::Invalid("wal opened read-only"));
+        }
+        Ok(())
+    }
+
+    /// Returns the trailing commit left out when the WAL was opened.
+    ///
+    /// Every frame of a commit is written before the frame that marks its
+    /// end, so a log whose last commit lacks that frame was cut short by a
+    /// crash mid-append. Such a commit was never acknowledged and is dropped
+    /// however its later frames were left: torn, zeroed or missing. Cleared
+    /// by [`Wal::reset`].
+    pub fn torn_tail(&self) -> Option<DiscardedFrames> {
+        *self.torn_tail.lock()
+    }
+
+    /// Returns the frames left out when the WAL was opened because damaged
+    /// frames precede them.
+    ///
+    /// Unlike a torn tail, intact frames still follow the damage, so commits
+    /// that may have been acknowledged are among the ones left out. Cleared
+    /// by [`Wal::reset`].
+    pub fn damaged_frames(&self) -> Option<DiscardedFrames> {
+        *self.damaged.lock()
+    }
+
     /// Resets the WAL to a new starting LSN, truncating all existing frames.
+    ///
+    /// Retired segments are kept for reuse up to the preallocation target;
+    /// the rest are deleted so the directory does not grow across checkpoints.
     pub fn reset(&self, start_lsn: Lsn) -> Result<()> {
+        self.ensure_writable()?;
         debug!(start_lsn = start_lsn.0, "wal.reset.start");
         let mut state = self.state.lock();
         let recycled_ids = {
@@ -857,8 +1106,15 @@ impl Wal {
                 None => self.recycle_segments_internal()?,
             }
         };
-        state.header = FileHeader::new(state.header.page_size, state.header.wal_salt, start_lsn);
+        state.header = FileHeader::new(
+            state.header.page_size,
+            state.header.wal_salt,
+            start_lsn,
+            self.checksum,
+        );
         state.prev_chain = 0;
+        *self.torn_tail.lock() = None;
+        *self.damaged.lock() = None;
         state.stats = WalStats::default();
         let new_id = self.take_ready_segment(&state.header, state.segment_capacity)?;
         let segment_io =
@@ -893,6 +1149,7 @@ impl Wal {
     /// ultimately invoked. Callers must ensure no further WAL appends occur
     /// before the reset completes.
     pub fn recycle_active_segments(&self) -> Result<usize> {
+        self.ensure_writable()?;
         {
             let pending = self.pending_recycle.lock();
             if let Some(ids) = pending.as_ref() {
@@ -923,10 +1180,32 @@ impl Wal {
                 cache.remove(id);
             }
         }
-        for id in &old_ids {
+        // Keep enough segments to replace the active one and cover the
+        // preallocation target; anything beyond that is obsolete and would
+        // otherwise sit in the recycle directory indefinitely.
+        let keep = self.recycle_retention().min(old_ids.len());
+        let (kept, obsolete) = old_ids.split_at(keep);
+        for id in kept {
             let _ = self.enqueue_recycle(*id);
         }
-        Ok(old_ids)
+        for id in obsolete {
+            let path = segment_path(&self.dir, *id);
+            debug!(segment_id = *id, ?path, "wal.segment.delete");
+            match fs::remove_file(&path) {
+                Ok(()) => {}
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                Err(err) => return Err(err.into()),
+            }
+        }
+        Ok(kept.to_vec())
+    }
+
+    /// Number of retired segments worth keeping for reuse, counting those
+    /// already queued or prepared.
+    fn recycle_retention(&self) -> usize {
+        let target = self.prealloc_target.max(1) as usize + 1;
+        let guard = self.prealloc.state.lock();
+        target.saturating_sub(guard.ready.len() + guard.recycle.len())
     }
 
     /// Appends a single frame to the WAL.
@@ -940,6 +1219,18 @@ impl Wal {
 
     /// Appends a batch of frames, coalescing writes when possible.
     pub fn append_frame_batch(&self, frames: &[WalFrame<'_>]) -> Result<Vec<WalFramePtr>> {
+        self.append_frames(frames)
+    }
+
+    /// Appends frames under a single state lock.
+    ///
+    /// Frames must arrive as whole commits: the last frame of each run of
+    /// equal LSNs is marked as the end of its commit, and recovery drops a
+    /// trailing commit whose marked frame never reached the disk. Each chunk of up to `WAL_MAX_IO_SLICES / 2` frames is encoded into one
+    /// contiguous buffer and written with a single `write_at`, so a group
+    /// commit costs one syscall per chunk rather than two per frame.
+    pub fn append_frames(&self, frames: &[WalFrame<'_>]) -> Result<Vec<WalFramePtr>> {
+        self.ensure_writable()?;
         if frames.is_empty() {
             return Ok(Vec::new());
         }
@@ -951,6 +1242,9 @@ impl Wal {
             if frame.lsn.0 < state.header.start_lsn.0 {
                 return Err(SombraError::Invalid("wal frame lsn below start_lsn"));
             }
+            if frame.page_id.0 & FRAME_COMMIT_FLAG != 0 {
+                return Err(SombraError::Invalid("wal frame page id out of range"));
+            }
         }
         if state.stats.frames_appended == 0 && frames[0].lsn.0 > state.header.start_lsn.0 {
             state.header.start_lsn = frames[0].lsn;
@@ -961,36 +1255,44 @@ impl Wal {
         }
         let frame_size = FRAME_HEADER_LEN + self.page_size;
         let mut offsets = Vec::with_capacity(frames.len());
+        let mut buf: Vec<u8> = Vec::new();
         let mut index = 0usize;
         while index < frames.len() {
             let remaining = frames.len() - index;
             let chunk_frames = remaining.min(WAL_MAX_IO_SLICES / 2).max(1);
             let slice_end = index + chunk_frames;
             let chunk = &frames[index..slice_end];
-            let mut header_bufs: Vec<[u8; FRAME_HEADER_LEN]> = Vec::with_capacity(chunk.len());
-            for frame in chunk {
-                let payload_crc32 = compute_crc32(&[frame.payload]);
-                let header =
-                    FrameHeader::new(frame.lsn, frame.page_id, state.prev_chain, payload_crc32);
-                let encoded_header = header.encode_with_crc();
-                let mut chain_hasher = Crc32Fast::default();
+            buf.clear();
+            buf.reserve(chunk.len() * frame_size);
+            for (pos, frame) in chunk.iter().enumerate() {
+                let checksum = state.header.checksum;
+                let payload_crc32 = checksum.checksum(&[frame.payload]);
+                let commit = state.header.marks_commits()
+                    && frames
+                        .get(index + pos + 1)
+                        .is_none_or(|next| next.lsn != frame.lsn);
+                let header = FrameHeader::new(
+                    frame.lsn,
+                    frame.page_id,
+                    commit,
+                    state.prev_chain,
+                    payload_crc32,
+                );
+                let encoded_header = header.encode_with_crc(checksum);
+                let mut chain_hasher = checksum.hasher();
                 chain_hasher.update(&state.prev_chain.to_be_bytes());
                 chain_hasher.update(&encoded_header);
                 chain_hasher.update(frame.payload);
                 let chain_crc = chain_hasher.finalize();
                 state.prev_chain = ((frame_size as u64) << 32) | u64::from(chain_crc);
-                header_bufs.push(encoded_header);
-            }
-            let mut slices: Vec<IoSlice<'_>> = Vec::with_capacity(chunk.len() * 2);
-            for (idx, frame) in chunk.iter().enumerate() {
-                slices.push(IoSlice::new(&header_bufs[idx]));
-                slices.push(IoSlice::new(frame.payload));
+                buf.extend_from_slice(&encoded_header);
+                buf.extend_from_slice(frame.payload);
             }
             let chunk_bytes = chunk.len() * frame_size;
             let chunk_bytes_u64 = chunk_bytes as u64;
             self.ensure_segment_capacity(&mut state, chunk_bytes_u64)?;
             let chunk_start = state.segment_writer.offset;
-            state.segment_writer.io.writev_at(chunk_start, &slices)?;
+            state.segment_writer.io.write_at(chunk_start, &buf)?;
             state.segment_writer.offset += chunk_bytes_u64;
             state.stats.frames_appended += chunk.len() as u64;
             state.stats.bytes_appended += chunk_bytes_u64;
@@ -1011,21 +1313,29 @@ impl Wal {
 
     /// Syncs all pending writes to persistent storage.
     pub fn sync(&self) -> Result<()> {
-        let io = {
-            let state = self.state.lock();
-            Arc::clone(&state.segment_writer.io)
-        };
-        if self.fullfsync {
-            io.sync_all()?;
-        } else {
-            io.sync_fast()?;
-        }
+        let io = self.sync_target();
+        sync_with_retry(io.as_ref(), self.fullfsync, self.sync_retries)?;
         record_pager_fsync();
         let mut state = self.state.lock();
         state.stats.syncs += 1;
         Ok(())
     }
 
+    fn sync_target(&self) -> Arc<dyn FileIo> {
+        #[cfg(test)]
+        if let Some(io) = self.sync_override.lock().clone() {
+            return io;
+        }
+        let state = self.state.lock();
+        Arc::clone(&state.segment_writer.io) as Arc<dyn FileIo>
+    }
+
+    /// Routes later [`Wal::sync`] calls to `io` instead of the active segment.
+    #[cfg(test)]
+    pub(crate) fn override_sync_io(&self, io: Arc<dyn FileIo>) {
+        *self.sync_override.lock() = Some(io);
+    }
+
     /// Creates an iterator to read frames from the WAL.
     pub fn iter(&self) -> Result<WalIterator> {
         let header = {
@@ -1052,6 +1362,7 @@ impl Wal {
             prev_chain: 0,
             valid_up_to: FILE_HEADER_LEN as u64,
             header,
+            verify_payloads: self.verify_payloads,
         })
     }
 
@@ -1075,12 +1386,13 @@ impl Wal {
         let io = self.open_segment_cached(ptr.segment_id)?;
         let mut header_buf = [0u8; FRAME_HEADER_LEN];
         io.read_at(ptr.offset, &mut header_buf)?;
-        let header = FrameHeader::decode(&header_buf)?;
-        {
+        let (checksum, start_lsn) = {
             let state = self.state.lock();
-            if header.frame_lsn.0 < state.header.start_lsn.0 {
-                return Err(SombraError::Corruption("wal frame lsn below start_lsn"));
-            }
+            (state.header.checksum, state.header.start_lsn)
+        };
+        let header = FrameHeader::decode(&header_buf, checksum)?;
+        if header.frame_lsn.0 < start_lsn.0 {
+            return Err(SombraError::Corruption("wal frame lsn below start_lsn"));
         }
         let payload_off = ptr.offset + FRAME_HEADER_LEN as u64;
         if payload_off + self.page_size as u64 > segment_len {
@@ -1088,7 +1400,7 @@ impl Wal {
         }
         let mut payload = vec![0u8; self.page_size];
         io.read_at(payload_off, &mut payload)?;
-        let payload_crc = compute_crc32(&[&payload]);
+        let payload_crc = checksum.checksum(&[&payload]);
         if payload_crc != header.payload_crc32 {
             return Err(SombraError::Corruption("wal frame payload crc mismatch"));
         }
@@ -1233,7 +1545,7 @@ impl Wal {
             let state = self.state.lock();
             (state.header.page_size, state.header.wal_salt)
         };
-        let io = open_segment_file(&self.dir, id, page_size, wal_salt)?;
+        let io = open_segment_file(&self.dir, id, page_size, wal_salt, self.read_only)?;
         let mut cache = self.segment_cache.lock();
         Ok(Arc::clone(cache.entry(id).or_insert(io)))
     }
@@ -1358,6 +1670,85 @@ pub struct WalCommitBacklog {
     pub pending_syncs: usize,
 }
 
+/// Number of buckets in each [`GroupCommitStats`] histogram.
+pub const GROUP_COMMIT_BUCKETS: usize = 8;
+
+/// Distribution of the batches flushed by the [`WalCommitter`] worker.
+///
+/// Histogram bucket `i` counts batches whose size falls in `2^i..2^(i+1)`;
+/// the last bucket also takes everything larger. Direct commits bypass the
+/// worker and are not counted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct GroupCommitStats {
+    /// Batches flushed by the worker.
+    pub batches: u64,
+    /// Commit requests across all batches.
+    pub commits: u64,
+    /// WAL frames across all batches.
+    pub frames: u64,
+    /// Histogram of commit requests per batch.
+    pub commits_per_batch: [u64; GROUP_COMMIT_BUCKETS],
+    /// Histogram of frames written per flush.
+    pub frames_per_batch: [u64; GROUP_COMMIT_BUCKETS],
+}
+
+impl GroupCommitStats {
+    /// Mean commit requests per batch, or 0 before the first batch.
+    pub fn avg_commits_per_batch(&self) -> f64 {
+        if self.batches == 0 {
+            return 0.0;
+        }
+        self.commits as f64 / self.batches as f64
+    }
+
+    /// Mean frames per flush, or 0 before the first batch.
+    pub fn avg_frames_per_batch(&self) -> f64 {
+        if self.batches == 0 {
+            return 0.0;
+        }
+        self.frames as f64 / self.batches as f64
+    }
+}
+
+#[derive(Default)]
+struct GroupCommitRecorder {
+    batches: AtomicU64,
+    commits: AtomicU64,
+    frames: AtomicU64,
+    commits_per_batch: [AtomicU64; GROUP_COMMIT_BUCKETS],
+    frames_per_batch: [AtomicU64; GROUP_COMMIT_BUCKETS],
+}
+
+impl GroupCommitRecorder {
+    fn bucket(size: usize) -> usize {
+        let log2 = usize::BITS - 1 - size.max(1).leading_zeros();
+        (log2 as usize).min(GROUP_COMMIT_BUCKETS - 1)
+    }
+
+    fn record(&self, commits: usize, frames: usize) {
+        self.batches.fetch_add(1, AtomicOrdering::Relaxed);
+        self.commits
+            .fetch_add(commits as u64, AtomicOrdering::Relaxed);
+        self.frames
+            .fetch_add(frames as u64, AtomicOrdering::Relaxed);
+        self.commits_per_batch[Self::bucket(commits)].fetch_add(1, AtomicOrdering::Relaxed);
+        self.frames_per_batch[Self::bucket(frames)].fetch_add(1, AtomicOrdering::Relaxed);
+    }
+
+    fn snapshot(&self) -> GroupCommitStats {
+        let load = |counters: &[AtomicU64; GROUP_COMMIT_BUCKETS]| {
+            std::array::from_fn(|i| counters[i].load(AtomicOrdering::Relaxed))
+        };
+        GroupCommitStats {
+            batches: self.batches.load(AtomicOrdering::Relaxed),
+            commits: self.commits.load(AtomicOrdering::Relaxed),
+            frames: self.frames.load(AtomicOrdering::Relaxed),
+            commits_per_batch: load(&self.commits_per_batch),
+            frames_per_batch: load(&self.frames_per_batch),
+        }
+    }
+}
+
 /// Asynchronous WAL committer that batches writes for improved throughput.
 ///
 /// Spawns a background worker thread that coalesces multiple commit requests
@@ -1374,6 +1765,8 @@ pub struct WalCommitter {
     direct_commit_active: Arc<AtomicBool>,
     /// State for fsync coalescing in direct commit path
     direct_sync_state: Arc<Mutex<DirectSyncState>>,
+    /// Batch size distribution recorded by the worker
+    group_stats: Arc<GroupCommitRecorder>,
 }
 
 /// Ticket representing a pending commit operation.
@@ -1404,6 +1797,7 @@ impl WalCommitter {
             pending_count: Arc::new(AtomicUsize::new(0)),
             direct_commit_active: Arc::new(AtomicBool::new(false)),
             direct_sync_state: Arc::new(Mutex::new(DirectSyncState::default())),
+            group_stats: Arc::new(GroupCommitRecorder::default()),
         }
     }
 
@@ -1440,6 +1834,7 @@ impl WalCommitter {
                     Arc::clone(&self.wakeup),
                     Arc::clone(&self.config),
                     Arc::clone(&self.pending_count),
+                    Arc::clone(&self.group_stats),
                 );
             } else {
                 self.wakeup.notify_one();
@@ -1484,14 +1879,22 @@ impl WalCommitter {
         }
     }
 
+    /// Returns the batch size distribution recorded by the worker so far.
+    pub fn group_commit_stats(&self) -> GroupCommitStats {
+        self.group_stats.snapshot()
+    }
+
     fn spawn_worker(
         wal: Arc<Wal>,
         state: Arc<Mutex<CommitState>>,
         wakeup: Arc<Condvar>,
         config: Arc<Mutex<WalCommitConfig>>,
         pending_count: Arc<AtomicUsize>,
+        group_stats: Arc<GroupCommitRecorder>,
     ) {
-        thread::spawn(move || Self::worker_loop(wal, state, wakeup, config, pending_count));
+        thread::spawn(move || {
+            Self::worker_loop(wal, state, wakeup, config, pending_count, group_stats)
+        });
     }
 
     fn worker_loop(
@@ -1500,6 +1903,7 @@ impl WalCommitter {
         wakeup: Arc<Condvar>,
         config: Arc<Mutex<WalCommitConfig>>,
         pending_count: Arc<AtomicUsize>,
+        group_stats: Arc<GroupCommitRecorder>,
     ) {
         let mut batch = Vec::new();
         loop {
@@ -1526,6 +1930,7 @@ impl WalCommitter {
                 batch_commits = batch.len(),
                 total_frames, "wal.committer.worker_batch_ready"
             );
+            group_stats.record(batch.len(), total_frames);
             if let Err(err) = Self::apply_batch(&wal, &mut batch) {
                 Self::fail_batch(&batch, &err);
                 Self::fail_pending(&state, &pending_count, &err);
@@ -1592,7 +1997,7 @@ impl WalCommitter {
                     });
                 }
             }
-            offsets_all = wal.append_frame_batch(&flat)?;
+            offsets_all = wal.append_frames(&flat)?;
             debug!(
                 frames = flat.len(),
                 "wal.committer.apply_batch.appended_frames"
@@ -1982,6 +2387,7 @@ pub struct WalIterator {
     prev_chain: u64,
     valid_up_to: u64,
     header: FileHeader,
+    verify_payloads: bool,
 }
 
 struct SegmentIterState {
@@ -2012,7 +2418,7 @@ impl WalIterator {
                 }
                 return Err(err);
             }
-            let header = match FrameHeader::decode(&header_buf) {
+            let header = match FrameHeader::decode(&header_buf, self.header.checksum) {
                 Ok(header) => header,
                 Err(_) => {
                     debug!(
@@ -2064,7 +2470,11 @@ impl WalIterator {
                 }
                 return Err(err);
             }
-            let payload_crc = compute_crc32(&[&payload]);
+            let payload_crc = if self.verify_payloads {
+                self.header.checksum.checksum(&[&payload])
+            } else {
+                header.payload_crc32
+            };
             if payload_crc != header.payload_crc32 {
                 debug!(
                     expected_crc = header.payload_crc32,
@@ -2083,7 +2493,7 @@ impl WalIterator {
             let mut encoded_header = header.encode();
             encoded_header[28..32].copy_from_slice(&header.header_crc32.to_be_bytes());
             let frame_size = FRAME_HEADER_LEN + self.page_size;
-            let mut chain_hasher = Crc32Fast::default();
+            let mut chain_hasher = self.header.checksum.hasher();
             chain_hasher.update(&self.prev_chain.to_be_bytes());
             chain_hasher.update(&encoded_header);
             chain_hasher.update(&payload);
@@ -2107,61 +2517,183 @@ impl WalIterator {
     }
 }
 
-fn detect_valid_prefix(
-    io: &Arc<StdFileIo>,
-    segment_len: u64,
+/// Outcome of [`scan_log`] over the segments present at open.
+struct LogScan {
+    /// Length to keep of each segment, in segment order, covering only the
+    /// frames of complete commits.
+    lens: Vec<u64>,
+    torn_tail: Option<DiscardedFrames>,
+    damaged: Option<DiscardedFrames>,
+}
+
+/// A run of frames counted while scanning the log.
+#[derive(Default)]
+struct FrameRun {
+    lsn: Option<Lsn>,
+    max_lsn: u64,
+    frames: u64,
+}
+
+impl FrameRun {
+    fn note(&mut self, frame: &FrameHeader) {
+        self.lsn.get_or_insert(frame.frame_lsn);
+        self.max_lsn = self.max_lsn.max(frame.frame_lsn.0);
+        self.frames += 1;
+    }
+}
+
+/// What a frame slot holds.
+enum Slot {
+    /// No frame of this log starts here: the segment ends, its unwritten
+    /// tail begins, or a stale frame from before `start_lsn` is left over.
+    End,
+    /// Bytes that do not decode as a frame header.
+    Damaged,
+    Frame(FrameHeader),
+}
+
+fn read_slot(io: &StdFileIo, offset: u64, segment_len: u64, header: &FileHeader) -> Result<Slot> {
+    if offset + FRAME_HEADER_LEN as u64 > segment_len {
+        return Ok(Slot::End);
+    }
+    let mut header_buf = [0u8; FRAME_HEADER_LEN];
+    if let Err(err) = io.read_at(offset, &mut header_buf) {
+        if matches!(err, SombraError::Io(ref io_err) if io_err.kind() == std::io::ErrorKind::UnexpectedEof)
+        {
+            return Ok(Slot::End);
+        }
+        return Err(err);
+    }
+    if header_buf.iter().all(|byte| *byte == 0) {
+        return Ok(Slot::End);
+    }
+    match FrameHeader::decode(&header_buf, header.checksum) {
+        Ok(frame) if frame.frame_lsn.0 < header.start_lsn.0 => Ok(Slot::End),
+        Ok(frame) => Ok(Slot::Frame(frame)),
+        Err(_) => Ok(Slot::Damaged),
+    }
+}
+
+/// Scans every segment in order and keeps the frames of complete commits.
+///
+/// Frames after the last commit frame belong to a commit that never finished
+/// reaching disk, whatever state their headers are in, and are always
+/// dropped; they are reported through `torn_tail`. When frames of a later
+/// commit still follow the first bad frame, the damage sits in the middle of
+/// the log rather than at its tail, and everything from that point is
+/// reported through `damaged` instead.
+///
+/// Payload checksums are only compared when `verify_payloads` is set; see
+/// [`WalOptions::verify_payloads`].
+fn scan_log(
+    segments: &[(Arc<StdFileIo>, u64)],
     page_size: usize,
     header: &FileHeader,
-) -> Result<u64> {
-    let mut offset = FILE_HEADER_LEN as u64;
+    verify_payloads: bool,
+) -> Result<LogScan> {
     let frame_size = FRAME_HEADER_LEN as u64 + page_size as u64;
+    let mut ends = vec![FILE_HEADER_LEN as u64; segments.len()];
+    let mut committed = (0usize, FILE_HEADER_LEN as u64);
+    let mut pending = FrameRun::default();
     let mut prev_chain = 0u64;
-    while offset + FRAME_HEADER_LEN as u64 <= segment_len {
-        let mut header_buf = [0u8; FRAME_HEADER_LEN];
-        if let Err(err) = io.read_at(offset, &mut header_buf) {
-            if matches!(err, SombraError::Io(ref io_err) if io_err.kind() == std::io::ErrorKind::UnexpectedEof)
-            {
-                break;
+    // Where to start looking for frames past the first bad one.
+    let mut resume = None;
+    'segments: for (idx, (io, segment_len)) in segments.iter().enumerate() {
+        let mut offset = FILE_HEADER_LEN as u64;
+        loop {
+            let frame = match read_slot(io, offset, *segment_len, header)? {
+                Slot::End => break,
+                Slot::Damaged => {
+                    resume = Some((idx, offset + frame_size));
+                    break 'segments;
+                }
+                Slot::Frame(frame) => frame,
+            };
+            if frame.prev_crc32_chain != prev_chain {
+                resume = Some((idx, offset));
+                break 'segments;
+            }
+            let payload_off = offset + FRAME_HEADER_LEN as u64;
+            let mut payload = vec![0u8; page_size];
+            let intact = if payload_off + page_size as u64 > *segment_len {
+                false
+            } else {
+                match io.read_at(payload_off, &mut payload) {
+                    Ok(()) => {
+                        !verify_payloads
+                            || header.checksum.checksum(&[&payload]) == frame.payload_crc32
+                    }
+                    Err(SombraError::Io(ref io_err))
+                        if io_err.kind() == std::io::ErrorKind::UnexpectedEof =>
+                    {
+                        false
+                    }
+                    Err(err) => return Err(err),
+                }
+            };
+            pending.note(&frame);
+            if !intact {
+                resume = Some((idx, offset + frame_size));
+                break 'segments;
+            }
+            let mut encoded_header = frame.encode();
+            encoded_header[28..32].copy_from_slice(&frame.header_crc32.to_be_bytes());
+            let mut chain_hasher = header.checksum.hasher();
+            chain_hasher.update(&prev_chain.to_be_bytes());
+            chain_hasher.update(&encoded_header);
+            chain_hasher.update(&payload);
+            prev_chain = (frame_size << 32) | u64::from(chain_hasher.finalize());
+            offset += frame_size;
+            ends[idx] = offset;
+            if frame.commit || !header.marks_commits() {
+                committed = (idx, offset);
+                pending = FrameRun::default();
             }
-            return Err(err);
         }
-        let frame_header = match FrameHeader::decode(&header_buf) {
-            Ok(hdr) => hdr,
-            Err(_) => break,
+    }
+    let resume = resume.unwrap_or_else(|| {
+        let last = segments.len().saturating_sub(1);
+        (last, ends.get(last).copied().unwrap_or(0) + frame_size)
+    });
+    // Frames of the trailing commit may still follow a bad one; only frames
+    // of another commit mean the damage is not at the tail.
+    let mut tail_lsn = pending.lsn;
+    let mut later = FrameRun::default();
+    let mut beyond_tail = false;
+    for (idx, (io, segment_len)) in segments.iter().enumerate().skip(resume.0) {
+        let mut offset = if idx == resume.0 {
+            resume.1
+        } else {
+            FILE_HEADER_LEN as u64
         };
-        if frame_header.frame_lsn.0 < header.start_lsn.0 {
-            break;
-        }
-        if frame_header.prev_crc32_chain != prev_chain {
-            break;
-        }
-        let payload_off = offset + FRAME_HEADER_LEN as u64;
-        if payload_off + page_size as u64 > segment_len {
-            break;
-        }
-        let mut payload = vec![0u8; page_size];
-        if let Err(err) = io.read_at(payload_off, &mut payload) {
-            if matches!(err, SombraError::Io(ref io_err) if io_err.kind() == std::io::ErrorKind::UnexpectedEof)
-            {
-                break;
+        while offset + FRAME_HEADER_LEN as u64 <= *segment_len {
+            if let Slot::Frame(frame) = read_slot(io, offset, *segment_len, header)? {
+                later.note(&frame);
+                beyond_tail |= *tail_lsn.get_or_insert(frame.frame_lsn) != frame.frame_lsn;
             }
-            return Err(err);
-        }
-        let payload_crc = compute_crc32(&[&payload]);
-        if payload_crc != frame_header.payload_crc32 {
-            break;
+            offset += frame_size;
         }
-        let mut encoded_header = frame_header.encode();
-        encoded_header[28..32].copy_from_slice(&frame_header.header_crc32.to_be_bytes());
-        let mut chain_hasher = Crc32Fast::default();
-        chain_hasher.update(&prev_chain.to_be_bytes());
-        chain_hasher.update(&encoded_header);
-        chain_hasher.update(&payload);
-        let chain_crc = chain_hasher.finalize();
-        prev_chain = (frame_size << 32) | u64::from(chain_crc);
-        offset += frame_size;
     }
-    Ok(offset)
+    let lens: Vec<u64> = ends
+        .iter()
+        .enumerate()
+        .map(|(idx, end)| match idx.cmp(&committed.0) {
+            std::cmp::Ordering::Less => *end,
+            std::cmp::Ordering::Equal => committed.1,
+            std::cmp::Ordering::Greater => FILE_HEADER_LEN as u64,
+        })
+        .collect();
+    let dropped = DiscardedFrames {
+        lsn: pending.lsn.or(later.lsn).unwrap_or(Lsn(0)),
+        max_lsn: Lsn(pending.max_lsn.max(later.max_lsn)),
+        frames: pending.frames + later.frames,
+        valid_up_to: lens[..committed.0].iter().sum::<u64>() + committed.1,
+    };
+    Ok(LogScan {
+        lens,
+        torn_tail: (!beyond_tail && dropped.frames > 0).then_some(dropped),
+        damaged: beyond_tail.then_some(dropped),
+    })
 }
 
 fn clone_error(err: &SombraError) -> SombraError {
@@ -2178,6 +2710,8 @@ fn clone_error(err: &SombraError) -> SombraError {
         SombraError::Cancelled => SombraError::Cancelled,
         SombraError::SnapshotTooOld(msg) => SombraError::SnapshotTooOld(msg.clone()),
         SombraError::Conflict(msg) => SombraError::Conflict(msg.clone()),
+        SombraError::Timeout(msg) => SombraError::Timeout(msg.clone()),
+        SombraError::ConstraintViolation(msg) => SombraError::ConstraintViolation(msg.clone()),
     }
 }
 
@@ -2188,6 +2722,36 @@ fn compute_crc32(chunks: &[&[u8]]) -> u32 {
     }
     hasher.finalize()
 }
+/// Syncs `io`, retrying up to `retries` times when the failure is transient.
+fn sync_with_retry(io: &dyn FileIo, fullfsync: bool, retries: u32) -> Result<()> {
+    let mut backoff = SYNC_RETRY_BASE_BACKOFF;
+    let mut attempt = 0;
+    loop {
+        let result = if fullfsync {
+            io.sync_all()
+        } else {
+            io.sync_fast()
+        };
+        match result {
+            Err(err) if attempt < retries && is_transient_sync_error(&err) => {
+                attempt += 1;
+                warn!(attempt, error = %err, "wal.sync.retry");
+                thread::sleep(backoff);
+                backoff = backoff.saturating_mul(2);
+            }
+            other => return other,
+        }
+    }
+}
+
+fn is_transient_sync_error(err: &SombraError) -> bool {
+    matches!(
+        err,
+        SombraError::Io(io_err)
+            if matches!(io_err.kind(), io::ErrorKind::Interrupted | io::ErrorKind::WouldBlock)
+    )
+}
+
 fn initialize_segment_file(path: &Path, header: &FileHeader, capacity: u64) -> Result<StdFileIo> {
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent)?;
@@ -2204,6 +2768,52 @@ fn initialize_segment_file(path: &Path, header: &FileHeader, capacity: u64) -> R
     Ok(io)
 }
 
+/// [`FileIo`] whose `sync_all` fails with `kind` for its first `failures`
+/// calls, for exercising [`sync_with_retry`].
+#[cfg(test)]
+pub(crate) struct FlakySyncIo {
+    failures: AtomicUsize,
+    kind: io::ErrorKind,
+    /// Number of `sync_all` calls seen so far.
+    pub(crate) attempts: AtomicUsize,
+}
+
+#[cfg(test)]
+impl FlakySyncIo {
+    pub(crate) fn new(failures: usize, kind: io::ErrorKind) -> Self {
+        Self {
+            failures: AtomicUsize::new(failures),
+            kind,
+            attempts: AtomicUsize::new(0),
+        }
+    }
+}
+
+#[cfg(test)]
+impl FileIo for FlakySyncIo {
+    fn read_at(&self, _off: u64, _dst: &mut [u8]) -> Result<()> {
+        Ok(())
+    }
+    fn write_at(&self, _off: u64, _src: &[u8]) -> Result<()> {
+        Ok(())
+    }
+    fn sync_all(&self) -> Result<()> {
+        self.attempts.fetch_add(1, AtomicOrdering::SeqCst);
+        let remaining = self.failures.load(AtomicOrdering::SeqCst);
+        if remaining > 0 {
+            self.failures.store(remaining - 1, AtomicOrdering::SeqCst);
+            return Err(io::Error::from(self.kind).into());
+        }
+        Ok(())
+    }
+    fn len(&self) -> Result<u64> {
+        Ok(0)
+    }
+    fn truncate(&self, _len: u64) -> Result<()> {
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2241,6 +2851,53 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn wal_salt_mismatch_requires_read_only_bypass() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("wal_salt_bypass");
+        {
+            let wal = Wal::open(&path, WalOptions::new(4096, 42, Lsn(1)))?;
+            let payload = vec![9u8; 4096];
+            let _ = wal.append_frame(WalFrame {
+                lsn: Lsn(1),
+                page_id: PageId(4),
+                payload: &payload,
+            })?;
+            wal.sync()?;
+        }
+
+        let err = Wal::open(&path, WalOptions::new(4096, 43, Lsn(1)))
+            .err()
+            .expect("salt mismatch rejected");
+        assert!(matches!(
+            err,
+            SombraError::Corruption("wal manifest salt mismatch")
+        ));
+
+        let mut options = WalOptions::new(4096, 43, Lsn(1));
+        options.ignore_salt_mismatch = true;
+        let wal = Wal::open(&path, options)?;
+        let mut iter = wal.iter()?;
+        let frame = iter.next_frame()?.expect("frame");
+        assert_eq!(frame.lsn, Lsn(1));
+        assert_eq!(frame.page_id, PageId(4));
+        assert_eq!(frame.payload, vec![9u8; 4096]);
+        assert!(iter.next_frame()?.is_none());
+
+        let payload = vec![0u8; 4096];
+        let append = wal.append_frame(WalFrame {
+            lsn: Lsn(2),
+            page_id: PageId(5),
+            payload: &payload,
+        });
+        assert!(matches!(
+            append,
+            Err(SombraError::Invalid("wal opened read-only"))
+        ));
+        assert!(wal.reset(Lsn(2)).is_err());
+        Ok(())
+    }
+
     #[test]
     fn wal_detects_corruption() -> Result<()> {
         let dir = tempdir().unwrap();
@@ -2268,6 +2925,138 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn wal_iterator_can_skip_payload_verification() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let open_with_frames = |name: &str, verify_payloads: bool| -> Result<Arc<Wal>> {
+            let mut options = WalOptions::new(4096, 888, Lsn(1));
+            options.verify_payloads = verify_payloads;
+            let wal = Wal::open(dir.path().join(name), options)?;
+            for lsn in 1..=3u8 {
+                let payload = vec![lsn; 4096];
+                let _ = wal.append_frame(WalFrame {
+                    lsn: Lsn(u64::from(lsn)),
+                    page_id: PageId(u64::from(lsn) + 10),
+                    payload: &payload,
+                })?;
+            }
+            wal.sync()?;
+            Ok(wal)
+        };
+        let corrupt_second_frame = |name: &str| -> Result<()> {
+            let segment = dir.path().join(name).join("active").join("wal-000001");
+            let io = StdFileIo::open(&segment)?;
+            let offset = (FILE_HEADER_LEN + FRAME_HEADER_LEN + 4096 + FRAME_HEADER_LEN) as u64;
+            let mut byte = [0u8; 1];
+            io.read_at(offset + 10, &mut byte)?;
+            byte[0] ^= 0xFF;
+            io.write_at(offset + 10, &byte)?;
+            Ok(())
+        };
+
+        let fast = open_with_frames("fast", false)?;
+        let mut iter = fast.iter()?;
+        for lsn in 1..=3u8 {
+            let frame = iter.next_frame()?.expect("frame");
+            assert_eq!(frame.lsn, Lsn(u64::from(lsn)));
+            assert_eq!(frame.page_id, PageId(u64::from(lsn) + 10));
+            assert_eq!(frame.payload, vec![lsn; 4096]);
+        }
+        assert!(iter.next_frame()?.is_none());
+
+        // Without payload checks the corrupted frame is replayed, and the CRC
+        // chain stops iteration at the frame after it.
+        corrupt_second_frame("fast")?;
+        let mut iter = fast.iter()?;
+        assert_eq!(iter.next_frame()?.expect("first frame").lsn, Lsn(1));
+        let corrupted = iter.next_frame()?.expect("second frame");
+        assert_eq!(corrupted.lsn, Lsn(2));
+        assert_ne!(corrupted.payload, vec![2u8; 4096]);
+        assert!(iter.next_frame()?.is_none());
+
+        let verified = open_with_frames("verified", true)?;
+        corrupt_second_frame("verified")?;
+        let mut iter = verified.iter()?;
+        assert_eq!(iter.next_frame()?.expect("first frame").lsn, Lsn(1));
+        assert!(iter.next_frame()?.is_none());
+
+        // Reopening applies the same setting to damage detection. Unverified,
+        // only the chain break at the last frame shows, so it is dropped as a
+        // torn tail; verified, the later commit makes the second frame damage
+        // in the middle of the log.
+        drop((fast, verified));
+        let reopen = |name: &str, verify_payloads: bool| -> Result<Arc<Wal>> {
+            let mut options = WalOptions::new(4096, 888, Lsn(1));
+            options.verify_payloads = verify_payloads;
+            Wal::open(dir.path().join(name), options)
+        };
+        let fast = reopen("fast", false)?;
+        assert_eq!(fast.torn_tail().map(|torn| torn.lsn), Some(Lsn(3)));
+        assert_eq!(fast.damaged_frames(), None);
+        let verified = reopen("verified", true)?;
+        assert_eq!(verified.torn_tail(), None);
+        assert_eq!(verified.damaged_frames().map(|dropped| dropped.lsn), Some(Lsn(2)));
+        Ok(())
+    }
+
+    #[test]
+    fn wal_append_frames_matches_per_frame_accounting() -> Result<()> {
+        const FRAMES: usize = 512;
+        let dir = tempdir().unwrap();
+        let payloads: Vec<Vec<u8>> = (0..FRAMES).map(|i| vec![i as u8; 4096]).collect();
+        let frames: Vec<WalFrame<'_>> = payloads
+            .iter()
+            .enumerate()
+            .map(|(i, payload)| WalFrame {
+                lsn: Lsn(1 + i as u64),
+                page_id: PageId(i as u64),
+                payload,
+            })
+            .collect();
+
+        let single = Wal::open(
+            dir.path().join("wal_single"),
+            WalOptions::new(4096, 7, Lsn(1)),
+        )?;
+        for frame in &frames {
+            let _ = single.append_frame(WalFrame {
+                lsn: frame.lsn,
+                page_id: frame.page_id,
+                payload: frame.payload,
+            })?;
+        }
+        let batched = Wal::open(
+            dir.path().join("wal_batched"),
+            WalOptions::new(4096, 7, Lsn(1)),
+        )?;
+        let ptrs = batched.append_frames(&frames)?;
+        assert_eq!(ptrs.len(), FRAMES);
+
+        let single_stats = single.stats();
+        let batched_stats = batched.stats();
+        assert_eq!(batched_stats.frames_appended, FRAMES as u64);
+        assert_eq!(batched_stats.frames_appended, single_stats.frames_appended);
+        assert_eq!(batched_stats.bytes_appended, single_stats.bytes_appended);
+        assert_eq!(single_stats.coalesced_writes, FRAMES as u64);
+        assert_eq!(
+            batched_stats.coalesced_writes,
+            (FRAMES / (WAL_MAX_IO_SLICES / 2)) as u64
+        );
+
+        let mut single_iter = single.iter()?;
+        let mut batched_iter = batched.iter()?;
+        for frame in &frames {
+            let a = single_iter.next_frame()?.expect("per-frame entry");
+            let b = batched_iter.next_frame()?.expect("batched entry");
+            assert_eq!(a.lsn, frame.lsn);
+            assert_eq!(b.lsn, frame.lsn);
+            assert_eq!(b.page_id, a.page_id);
+            assert_eq!(b.payload, a.payload);
+        }
+        assert!(batched_iter.next_frame()?.is_none());
+        Ok(())
+    }
+
     #[test]
     fn wal_committer_appends_and_syncs() -> Result<()> {
         let dir = tempdir().unwrap();
@@ -2292,6 +3081,45 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn wal_committer_records_group_commit_batches() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("wal_committer_group_stats");
+        let wal = Wal::open(&path, WalOptions::new(4096, 333, Lsn(1)))?;
+        let committer = WalCommitter::new(
+            Arc::clone(&wal),
+            WalCommitConfig {
+                max_batch_wait: Duration::from_millis(50),
+                ..WalCommitConfig::default()
+            },
+        );
+        let tickets: Vec<WalCommitTicket> = (1..=16u64)
+            .filter_map(|lsn| {
+                committer.enqueue(
+                    vec![WalFrameOwned {
+                        lsn: Lsn(lsn),
+                        page_id: PageId(lsn),
+                        payload: vec![lsn as u8; 4096],
+                    }],
+                    WalSyncMode::Immediate,
+                )
+            })
+            .collect();
+        for ticket in tickets {
+            ticket.wait()?;
+        }
+        let stats = committer.group_commit_stats();
+        assert_eq!(stats.commits, 16);
+        assert_eq!(stats.frames, 16);
+        assert_eq!(stats.commits_per_batch.iter().sum::<u64>(), stats.batches);
+        assert_eq!(stats.frames_per_batch.iter().sum::<u64>(), stats.batches);
+        assert!(
+            stats.avg_commits_per_batch() > 1.0,
+            "expected coalescing, got {stats:?}"
+        );
+        Ok(())
+    }
+
     #[test]
     fn wal_committer_empty_batch_syncs() -> Result<()> {
         let dir = tempdir().unwrap();
@@ -2413,6 +3241,153 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn wal_reset_deletes_obsolete_segments() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("wal_reset_delete");
+        let mut opts = WalOptions::new(512, 6, Lsn(1));
+        opts.segment_size_bytes = (FILE_HEADER_LEN + FRAME_HEADER_LEN + 512) as u64 + 1;
+        opts.preallocate_segments = 0;
+        let wal = Wal::open(&path, opts)?;
+        let payload = vec![5u8; 512];
+        for lsn in 1..=8 {
+            wal.append_frame(WalFrame {
+                lsn: Lsn(lsn),
+                page_id: PageId(lsn),
+                payload: &payload,
+            })?;
+        }
+        assert_eq!(list_segments(&path)?.len(), 8);
+        wal.reset(Lsn(9))?;
+        let active = list_segments(&path)?;
+        let recycled = list_recycle_segments(&path)?;
+        assert_eq!(active.len(), 1);
+        assert!(
+            recycled.len() <= 1,
+            "expected obsolete segments to be deleted, found {recycled:?}"
+        );
+        wal.append_frame(WalFrame {
+            lsn: Lsn(9),
+            page_id: PageId(9),
+            payload: &payload,
+        })?;
+        let mut iter = wal.iter()?;
+        assert_eq!(iter.next_frame()?.expect("frame after reset").lsn, Lsn(9));
+        assert!(iter.next_frame()?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn wal_checksum_kinds_roundtrip_and_detect_corruption() -> Result<()> {
+        for kind in [
+            ChecksumKind::Crc32,
+            ChecksumKind::Crc32c,
+            ChecksumKind::XxHash64,
+        ] {
+            let dir = tempdir().unwrap();
+            let path = dir.path().join("wal_checksum");
+            let mut opts = WalOptions::new(512, 21, Lsn(1));
+            opts.checksum = kind;
+            {
+                let wal = Wal::open(&path, opts.clone())?;
+                for lsn in 1..=3 {
+                    let payload = vec![lsn as u8; 512];
+                    wal.append_frame(WalFrame {
+                        lsn: Lsn(lsn),
+                        page_id: PageId(lsn),
+                        payload: &payload,
+                    })?;
+                }
+                wal.sync()?;
+            }
+            let segment = segment_path(&path, list_segments(&path)?[0]);
+            let mut bytes = fs::read(&segment)?;
+            assert_eq!(u16::from_be_bytes([bytes[6], bytes[7]]), kind.code());
+
+            let wal = Wal::open(&path, opts.clone())?;
+            let mut iter = wal.iter()?;
+            for lsn in 1..=3 {
+                assert_eq!(iter.next_frame()?.expect("frame").lsn, Lsn(lsn), "{kind:?}");
+            }
+            assert!(iter.next_frame()?.is_none());
+            drop(iter);
+            drop(wal);
+
+            // Flip a payload byte in the last frame; the prefix before it survives.
+            let last = FILE_HEADER_LEN + 2 * (FRAME_HEADER_LEN + 512);
+            bytes[last + FRAME_HEADER_LEN + 7] ^= 0xFF;
+            fs::write(&segment, &bytes)?;
+            let wal = Wal::open(&path, opts)?;
+            assert_eq!(wal.torn_tail().map(|torn| torn.lsn), Some(Lsn(3)), "{kind:?}");
+            let mut iter = wal.iter()?;
+            assert_eq!(iter.next_frame()?.expect("frame").lsn, Lsn(1));
+            assert_eq!(iter.next_frame()?.expect("frame").lsn, Lsn(2));
+            assert!(iter.next_frame()?.is_none());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn wal_keeps_recorded_checksum_until_reset() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("wal_checksum_switch");
+        let payload = vec![9u8; 512];
+        {
+            let wal = Wal::open(&path, WalOptions::new(512, 22, Lsn(1)))?;
+            wal.append_frame(WalFrame {
+                lsn: Lsn(1),
+                page_id: PageId(1),
+                payload: &payload,
+            })?;
+            wal.sync()?;
+        }
+        let mut opts = WalOptions::new(512, 22, Lsn(1));
+        opts.checksum = ChecksumKind::Crc32c;
+        let wal = Wal::open(&path, opts)?;
+        let mut iter = wal.iter()?;
+        assert_eq!(iter.next_frame()?.expect("crc32 frame").lsn, Lsn(1));
+        drop(iter);
+        wal.reset(Lsn(2))?;
+        wal.append_frame(WalFrame {
+            lsn: Lsn(2),
+            page_id: PageId(2),
+            payload: &payload,
+        })?;
+        let active = segment_path(&path, *list_segments(&path)?.last().unwrap());
+        let bytes = fs::read(active)?;
+        assert_eq!(
+            u16::from_be_bytes([bytes[6], bytes[7]]),
+            ChecksumKind::Crc32c.code()
+        );
+        let mut iter = wal.iter()?;
+        assert_eq!(iter.next_frame()?.expect("crc32c frame").lsn, Lsn(2));
+        assert!(iter.next_frame()?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn wal_rejects_unknown_checksum_algorithm() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("wal_checksum_unknown");
+        let opts = WalOptions::new(512, 23, Lsn(1));
+        drop(Wal::open(&path, opts.clone())?);
+        let segment = segment_path(&path, list_segments(&path)?[0]);
+        let mut bytes = fs::read(&segment)?;
+        bytes[6..8].copy_from_slice(&0x00FFu16.to_be_bytes());
+        bytes[28..32].fill(0);
+        let crc = compute_crc32(&[&bytes[..FILE_HEADER_LEN]]);
+        bytes[28..32].copy_from_slice(&crc.to_be_bytes());
+        fs::write(&segment, &bytes)?;
+        match Wal::open(&path, opts) {
+            Err(SombraError::Invalid(msg)) => {
+                assert_eq!(msg, "wal checksum algorithm is not supported by this build");
+            }
+            Err(other) => panic!("unexpected error: {other:?}"),
+            Ok(_) => panic!("unknown checksum algorithm must be rejected"),
+        }
+        Ok(())
+    }
+
     #[test]
     fn direct_commit_succeeds_when_queue_empty() -> Result<()> {
         let dir = tempdir().unwrap();
@@ -2566,4 +3541,31 @@ mod tests {
         assert_eq!(backlog.pending_syncs, 0);
         Ok(())
     }
+
+    #[test]
+    fn sync_retries_transient_errors_until_success() -> Result<()> {
+        let io = FlakySyncIo::new(2, io::ErrorKind::Interrupted);
+        sync_with_retry(&io, true, 3)?;
+        assert_eq!(io.attempts.load(AtomicOrdering::SeqCst), 3);
+
+        let io = FlakySyncIo::new(2, io::ErrorKind::WouldBlock);
+        sync_with_retry(&io, false, 3)?;
+        assert_eq!(io.attempts.load(AtomicOrdering::SeqCst), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn sync_retry_is_bounded() {
+        let io = FlakySyncIo::new(usize::MAX, io::ErrorKind::Interrupted);
+        let err = sync_with_retry(&io, true, 3).expect_err("retries exhausted");
+        assert!(matches!(err, SombraError::Io(ref e) if e.kind() == io::ErrorKind::Interrupted));
+        assert_eq!(io.attempts.load(AtomicOrdering::SeqCst), 4);
+    }
+
+    #[test]
+    fn sync_does_not_retry_fatal_errors() {
+        let io = FlakySyncIo::new(1, io::ErrorKind::PermissionDenied);
+        assert!(sync_with_retry(&io, true, 3).is_err());
+        assert_eq!(io.attempts.load(AtomicOrdering::SeqCst), 1);
+    }
 }
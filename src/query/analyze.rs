@@ -10,9 +10,11 @@
 
 use crate::query::{
     ast::{
-        BoolExpr, Comparison, EdgeClause, EdgeDirection, MatchClause, Projection, QueryAst, Var,
+        AggFunc, BoolExpr, Comparison, EdgeClause, EdgeDirection, GroupLimit, MatchClause,
+        OrderKey, OrderTarget, Projection, QueryAst, Var,
     },
     errors::AnalyzerError,
+    expr::{self, PropPath, ScalarExpr},
     metadata::MetadataProvider,
     Value,
 };
@@ -21,7 +23,7 @@ use crate::types::{LabelId, PropId, TypeId};
 use base64::engine::general_purpose::STANDARD as BASE64_ENGINE;
 use base64::Engine;
 use std::cmp::Ordering;
-use std::collections::{HashMap, HashSet};
+use std::collections::{hash_map::Entry, HashMap, HashSet};
 use std::ops::Bound;
 
 /// Maximum supported number of MATCH clauses per query.
@@ -116,6 +118,67 @@ pub enum AnalyzedProjection {
         /// Optional alias.
         alias: Option<String>,
     },
+    /// Running row count (`kind: "running_count"`).
+    RunningCount {
+        /// Optional alias.
+        alias: Option<String>,
+    },
+    /// Traversed path (`kind: "path"`).
+    Path {
+        /// Bindings visited from the start variable to the end variable.
+        vars: Vec<VarId>,
+        /// Optional alias.
+        alias: Option<String>,
+    },
+    /// Computed scalar expression (`kind: "expr"`).
+    Expr {
+        /// Expression with its property operands resolved.
+        expr: ScalarExpr<(VarId, PropRef)>,
+        /// Source text the expression was parsed from.
+        source: String,
+        /// Optional alias.
+        alias: Option<String>,
+    },
+    /// Aggregate over every matched row (`kind: "aggregate"`).
+    Aggregate {
+        /// Aggregate function.
+        func: AggFunc,
+        /// Binding identifier.
+        var: VarId,
+        /// Resolved property metadata; `None` counts bindings.
+        prop: Option<PropRef>,
+        /// Column name.
+        alias: String,
+    },
+}
+
+impl AnalyzedProjection {
+    /// Returns true when the projection folds all rows into one.
+    pub fn is_aggregate(&self) -> bool {
+        matches!(self, AnalyzedProjection::Aggregate { .. })
+    }
+}
+
+/// Sort key after alias and property resolution.
+#[derive(Clone, Debug)]
+pub struct AnalyzedOrderKey {
+    /// Binding whose node supplies the sort value.
+    pub var: VarId,
+    /// Property compared between rows; `None` orders by node id.
+    pub prop: Option<PropRef>,
+    /// Sorts from largest to smallest.
+    pub descending: bool,
+}
+
+/// Per-group row cap after variable and sort key resolution.
+#[derive(Clone, Debug)]
+pub struct AnalyzedGroupLimit {
+    /// Binding whose node identifies a row's group.
+    pub group_by: VarId,
+    /// Maximum number of rows kept per group.
+    pub n: u64,
+    /// Sort keys ranking rows within a group.
+    pub order_by: Vec<AnalyzedOrderKey>,
 }
 
 /// Typed boolean predicate tree.
@@ -175,6 +238,11 @@ pub enum AnalyzedComparison {
         prop: PropRef,
         values: Vec<Value>,
     },
+    Prefix {
+        var: VarId,
+        prop: PropRef,
+        prefix: String,
+    },
     Exists {
         var: VarId,
         prop: PropRef,
@@ -187,6 +255,28 @@ pub enum AnalyzedComparison {
         var: VarId,
         prop: PropRef,
     },
+    EdgeExists {
+        var: VarId,
+        edge_type: EdgeTypeRef,
+        direction: EdgeDirection,
+        target: VarBinding,
+        predicate: Option<Box<AnalyzedExpr>>,
+    },
+}
+
+/// Appends node-id keys for every match variable, in match order, so rows
+/// tied on the requested keys still come back in one fixed order.
+fn push_tiebreak_keys(vars: &[VarBinding], keys: &mut Vec<AnalyzedOrderKey>, descending: bool) {
+    for binding in vars {
+        let ordered = |key: &AnalyzedOrderKey| key.var == binding.id && key.prop.is_none();
+        if !keys.iter().any(ordered) {
+            keys.push(AnalyzedOrderKey {
+                var: binding.id,
+                prop: None,
+                descending,
+            });
+        }
+    }
 }
 
 /// Fully analyzed query passed into the planner.
@@ -198,6 +288,7 @@ pub struct AnalyzedQuery {
     pub request_id: Option<String>,
     vars: Vec<VarBinding>,
     var_index: HashMap<String, VarId>,
+    scoped_vars: Vec<VarBinding>,
     /// Match edges after variable/type resolution.
     pub edges: Vec<AnalyzedEdge>,
     /// Normalized predicate referencing property identifiers.
@@ -206,14 +297,37 @@ pub struct AnalyzedQuery {
     pub distinct: bool,
     /// Projection list referencing analyzed bindings.
     pub projections: Vec<AnalyzedProjection>,
+    /// Sort keys in priority order.
+    pub order_by: Vec<AnalyzedOrderKey>,
+    /// Maximum number of rows to return, if bounded.
+    pub limit: Option<u64>,
+    /// Per-group row cap applied before the global sort.
+    pub limit_per_group: Option<AnalyzedGroupLimit>,
+    /// Number of leading rows to skip.
+    pub offset: u64,
 }
 
 impl AnalyzedQuery {
+    /// Sorts by bound node ids, ascending, when the query has no sort keys,
+    /// giving every row a fixed position a keyset page can resume after.
+    pub fn ensure_total_order(&mut self) {
+        if self.order_by.is_empty() {
+            push_tiebreak_keys(&self.vars, &mut self.order_by, false);
+        }
+    }
+
     /// Returns the ordered list of variable bindings.
     pub fn vars(&self) -> &[VarBinding] {
         &self.vars
     }
 
+    /// Returns bindings introduced by nested edge-existence predicates.
+    ///
+    /// These are only visible inside their predicate and never drive a scan.
+    pub fn scoped_vars(&self) -> &[VarBinding] {
+        &self.scoped_vars
+    }
+
     /// Looks up the binding metadata for the provided identifier.
     pub fn var_binding(&self, id: VarId) -> Option<&VarBinding> {
         self.vars.iter().find(|binding| binding.id == id)
@@ -235,6 +349,15 @@ impl AnalyzedQuery {
     pub fn schema_version(&self) -> u32 {
         self.schema_version
     }
+
+    /// Returns the first pair of match variables that no edge chain joins.
+    ///
+    /// `Some` means the plan cross-joins disconnected components, producing
+    /// the cartesian product of their rows.
+    pub fn disconnected_vars(&self) -> Option<(&Var, &Var)> {
+        let (left, right) = disconnected_pair(&self.vars, &self.edges)?;
+        Some((&self.vars[left].var, &self.vars[right].var))
+    }
 }
 
 /// Runs normalization + semantic analysis, producing an [`AnalyzedQuery`].
@@ -245,6 +368,10 @@ pub fn analyze(ast: &QueryAst, metadata: &dyn MetadataProvider) -> AnalyzeResult
 
 /// Normalizes projections and predicates to their canonical encodings.
 pub fn normalize(ast: &QueryAst) -> AnalyzeResult<QueryAst> {
+    if let Some(expr) = &ast.predicate {
+        // Reject oversized trees before the recursive rewrite passes touch them.
+        validate_predicate_limits(expr)?;
+    }
     let mut normalized = ast.clone();
     normalized.predicate = match normalized.predicate.take() {
         Some(expr) => normalize_expr(expr)?.or_else(|| Some(BoolExpr::And(Vec::new()))),
@@ -256,17 +383,40 @@ pub fn normalize(ast: &QueryAst) -> AnalyzeResult<QueryAst> {
 
 fn normalize_projections(projections: &mut [Projection]) -> AnalyzeResult<()> {
     for proj in projections {
-        if let Projection::Prop { alias, .. } = proj {
+        if let Projection::Prop { alias, .. }
+        | Projection::RunningCount { alias }
+        | Projection::Path { alias, .. }
+        | Projection::Expr { alias, .. } = proj
+        {
             if let Some(alias) = alias {
                 if alias.trim().is_empty() {
                     return Err(AnalyzerError::EmptyProjectionAlias);
                 }
             }
         }
+        if let Projection::Aggregate { alias, .. } = proj {
+            if alias.trim().is_empty() {
+                return Err(AnalyzerError::EmptyProjectionAlias);
+            }
+        }
     }
     Ok(())
 }
 
+/// Returns the result column an AST projection writes.
+fn projection_column(projection: &Projection) -> String {
+    match projection {
+        Projection::Var { var, alias } => alias.clone().unwrap_or_else(|| var.0.clone()),
+        Projection::Prop { prop, alias, .. } => alias.clone().unwrap_or_else(|| prop.clone()),
+        Projection::RunningCount { alias } => {
+            alias.clone().unwrap_or_else(|| "running_count".into())
+        }
+        Projection::Path { alias, .. } => alias.clone().unwrap_or_else(|| "path".into()),
+        Projection::Expr { expr, alias } => alias.clone().unwrap_or_else(|| expr.trim().into()),
+        Projection::Aggregate { alias, .. } => alias.clone(),
+    }
+}
+
 fn normalize_expr(expr: BoolExpr) -> AnalyzeResult<Option<BoolExpr>> {
     match simplify(expr)? {
         Simplified::True => Ok(None),
@@ -357,6 +507,28 @@ fn canonicalize_comparison(cmp: Comparison) -> AnalyzeResult<Comparison> {
             canonicalize_in_values(&mut values)?;
             Ok(Comparison::In { var, prop, values })
         }
+        Comparison::EdgeExists {
+            var,
+            edge_type,
+            direction,
+            target,
+            predicate,
+        } => {
+            let predicate = match predicate {
+                Some(expr) => {
+                    validate_predicate_limits(&expr)?;
+                    normalize_expr(*expr)?.map(Box::new)
+                }
+                None => None,
+            };
+            Ok(Comparison::EdgeExists {
+                var,
+                edge_type,
+                direction,
+                target,
+                predicate,
+            })
+        }
         other => Ok(other),
     }
 }
@@ -497,9 +669,27 @@ fn comparison_sort_key(cmp: &Comparison) -> String {
             value_keys.sort();
             format!("in:{}:{}:{}", var.0, prop, value_keys.join(","))
         }
+        Comparison::Prefix { var, prop, prefix } => {
+            format!("prefix:{}:{}:{:?}", var.0, prop, prefix)
+        }
         Comparison::Exists { var, prop } => format!("exists:{}:{}", var.0, prop),
         Comparison::IsNull { var, prop } => format!("isnull:{}:{}", var.0, prop),
         Comparison::IsNotNull { var, prop } => format!("isnotnull:{}:{}", var.0, prop),
+        Comparison::EdgeExists {
+            var,
+            edge_type,
+            direction,
+            target,
+            predicate,
+        } => format!(
+            "edgeexists:{}:{}:{:?}:{}:{}:{}",
+            var.0,
+            edge_type.as_deref().unwrap_or(""),
+            direction,
+            target.var.0,
+            target.label.as_deref().unwrap_or(""),
+            predicate.as_deref().map(expr_sort_key).unwrap_or_default()
+        ),
     }
 }
 
@@ -582,6 +772,7 @@ struct Analyzer<'m> {
     metadata: &'m dyn MetadataProvider,
     vars: Vec<VarBinding>,
     var_index: HashMap<String, VarId>,
+    scoped_vars: Vec<VarBinding>,
     prop_cache: HashMap<String, PropRef>,
 }
 
@@ -591,6 +782,7 @@ impl<'m> Analyzer<'m> {
             metadata,
             vars: Vec::new(),
             var_index: HashMap::new(),
+            scoped_vars: Vec::new(),
             prop_cache: HashMap::new(),
         }
     }
@@ -600,26 +792,56 @@ impl<'m> Analyzer<'m> {
         let schema_version = ast.schema_version;
         self.process_matches(&ast.matches)?;
         let edges = self.process_edges(&ast.edges)?;
+        if ast.reject_cartesian {
+            if let Some((left, right)) = disconnected_pair(&self.vars, &edges) {
+                return Err(AnalyzerError::CartesianProduct {
+                    left: self.vars[left].var.0.clone(),
+                    right: self.vars[right].var.0.clone(),
+                });
+            }
+        }
         let predicate = match ast.predicate {
             Some(expr) => {
-                self.validate_predicate_limits(&expr)?;
+                validate_predicate_limits(&expr)?;
                 Some(self.analyze_expr(expr)?)
             }
             None => None,
         };
-        let projections = self.process_projections(&ast.projections)?;
+        let projections = self.process_projections(&ast.projections, &edges)?;
+        let mut order_by = self.process_order_by(&ast.order_by, &projections)?;
+        if let Some(last) = order_by.last() {
+            let descending = ast.tiebreak_descending.unwrap_or(last.descending);
+            push_tiebreak_keys(&self.vars, &mut order_by, descending);
+        }
+        let limit_per_group = match &ast.limit_per_group {
+            Some(group) => Some(self.process_group_limit(group, &projections)?),
+            None => None,
+        };
+        if projections.iter().any(AnalyzedProjection::is_aggregate)
+            && (ast.limit.is_some() || ast.offset.is_some() || limit_per_group.is_some())
+        {
+            return Err(AnalyzerError::AggregateWithLimit);
+        }
         let Analyzer {
-            vars, var_index, ..
+            vars,
+            var_index,
+            scoped_vars,
+            ..
         } = self;
         Ok(AnalyzedQuery {
             schema_version,
             request_id,
             vars,
             var_index,
+            scoped_vars,
             edges,
             predicate,
             distinct: ast.distinct,
             projections,
+            order_by,
+            limit: ast.limit,
+            limit_per_group,
+            offset: ast.offset.unwrap_or(0),
         })
     }
 
@@ -638,18 +860,7 @@ impl<'m> Analyzer<'m> {
             if self.var_index.contains_key(&name) {
                 return Err(AnalyzerError::DuplicateVariable { var: name });
             }
-            let label = clause
-                .label
-                .clone()
-                .ok_or_else(|| AnalyzerError::MatchMissingLabel {
-                    var: clause.var.0.clone(),
-                })?;
-            let label_id =
-                self.metadata
-                    .resolve_label(&label)
-                    .map_err(|_| AnalyzerError::UnknownLabel {
-                        label: label.clone(),
-                    })?;
+            let (label, label_id) = self.resolve_match_label(clause)?;
             let id = VarId(self.vars.len() as u32);
             let binding = VarBinding {
                 id,
@@ -663,6 +874,36 @@ impl<'m> Analyzer<'m> {
         Ok(())
     }
 
+    fn resolve_match_label(&self, clause: &MatchClause) -> AnalyzeResult<(String, LabelId)> {
+        let label = clause
+            .label
+            .clone()
+            .ok_or_else(|| AnalyzerError::MatchMissingLabel {
+                var: clause.var.0.clone(),
+            })?;
+        let label_id =
+            self.metadata
+                .resolve_label(&label)
+                .map_err(|_| AnalyzerError::UnknownLabel {
+                    label: label.clone(),
+                })?;
+        Ok((label, label_id))
+    }
+
+    fn resolve_edge_type(&self, edge_type: &Option<String>) -> AnalyzeResult<EdgeTypeRef> {
+        Ok(match edge_type {
+            Some(name) => EdgeTypeRef {
+                name: Some(name.clone()),
+                id: Some(self.metadata.resolve_edge_type(name).map_err(|_| {
+                    AnalyzerError::UnknownEdgeType {
+                        edge_type: name.clone(),
+                    }
+                })?),
+            },
+            None => EdgeTypeRef::default(),
+        })
+    }
+
     fn process_edges(&self, edges: &[EdgeClause]) -> AnalyzeResult<Vec<AnalyzedEdge>> {
         let mut out = Vec::with_capacity(edges.len());
         for edge in edges {
@@ -673,17 +914,7 @@ impl<'m> Analyzer<'m> {
                     var: edge.from.0.clone(),
                 });
             }
-            let edge_type = match &edge.edge_type {
-                Some(name) => EdgeTypeRef {
-                    name: Some(name.clone()),
-                    id: Some(self.metadata.resolve_edge_type(name).map_err(|_| {
-                        AnalyzerError::UnknownEdgeType {
-                            edge_type: name.clone(),
-                        }
-                    })?),
-                },
-                None => EdgeTypeRef::default(),
-            };
+            let edge_type = self.resolve_edge_type(&edge.edge_type)?;
             out.push(AnalyzedEdge {
                 from,
                 to,
@@ -697,6 +928,7 @@ impl<'m> Analyzer<'m> {
     fn process_projections(
         &mut self,
         projections: &[Projection],
+        edges: &[AnalyzedEdge],
     ) -> AnalyzeResult<Vec<AnalyzedProjection>> {
         let mut out = Vec::with_capacity(projections.len());
         for projection in projections {
@@ -717,11 +949,170 @@ impl<'m> Analyzer<'m> {
                         alias: alias.clone(),
                     });
                 }
+                Projection::RunningCount { alias } => {
+                    out.push(AnalyzedProjection::RunningCount {
+                        alias: alias.clone(),
+                    });
+                }
+                Projection::Path {
+                    from_var,
+                    to_var,
+                    alias,
+                } => {
+                    let from = self.require_var(from_var, "path projection")?;
+                    let to = self.require_var(to_var, "path projection")?;
+                    let vars = edge_chain(edges, from, to).ok_or_else(|| {
+                        AnalyzerError::PathNotConnected {
+                            from: from_var.0.clone(),
+                            to: to_var.0.clone(),
+                        }
+                    })?;
+                    out.push(AnalyzedProjection::Path {
+                        vars,
+                        alias: alias.clone(),
+                    });
+                }
+                Projection::Expr { expr, alias } => {
+                    out.push(AnalyzedProjection::Expr {
+                        expr: self.resolve_expr(expr)?,
+                        source: expr.trim().to_owned(),
+                        alias: alias.clone(),
+                    });
+                }
+                Projection::Aggregate {
+                    func,
+                    var,
+                    prop,
+                    alias,
+                } => {
+                    let var_id = self.require_var(var, "aggregate")?;
+                    let prop = match prop {
+                        Some(prop) => Some(self.property(prop)?),
+                        None if *func == AggFunc::Count => None,
+                        None => {
+                            return Err(AnalyzerError::AggregateMissingProperty {
+                                func: func.name(),
+                            })
+                        }
+                    };
+                    out.push(AnalyzedProjection::Aggregate {
+                        func: *func,
+                        var: var_id,
+                        prop,
+                        alias: alias.clone(),
+                    });
+                }
+            }
+        }
+        if out.iter().any(AnalyzedProjection::is_aggregate) {
+            if let Some(index) = out.iter().position(|proj| !proj.is_aggregate()) {
+                return Err(AnalyzerError::MixedAggregateProjection {
+                    column: projection_column(&projections[index]),
+                });
             }
         }
         Ok(out)
     }
 
+    fn process_order_by(
+        &mut self,
+        keys: &[OrderKey],
+        projections: &[AnalyzedProjection],
+    ) -> AnalyzeResult<Vec<AnalyzedOrderKey>> {
+        let mut out = Vec::with_capacity(keys.len());
+        for key in keys {
+            let (var, prop) = match &key.target {
+                OrderTarget::Prop { var, prop } => {
+                    let (var_id, prop_ref) = self.resolve_var_prop(var, prop, "order by")?;
+                    (var_id, Some(prop_ref))
+                }
+                OrderTarget::Alias(alias) => self.resolve_order_alias(alias, projections)?,
+            };
+            out.push(AnalyzedOrderKey {
+                var,
+                prop,
+                descending: key.descending,
+            });
+        }
+        Ok(out)
+    }
+
+    fn process_group_limit(
+        &mut self,
+        group: &GroupLimit,
+        projections: &[AnalyzedProjection],
+    ) -> AnalyzeResult<AnalyzedGroupLimit> {
+        let group_by = self.require_var(&group.group_by, "limit per group")?;
+        let order_by = self.process_order_by(&group.order_by, projections)?;
+        Ok(AnalyzedGroupLimit {
+            group_by,
+            n: group.n,
+            order_by,
+        })
+    }
+
+    /// Maps an order alias onto the projection writing that column.
+    fn resolve_order_alias(
+        &self,
+        alias: &str,
+        projections: &[AnalyzedProjection],
+    ) -> AnalyzeResult<(VarId, Option<PropRef>)> {
+        for projection in projections {
+            match projection {
+                AnalyzedProjection::Var { var, alias: named } => {
+                    let default = self.binding_for_id(*var).map(|binding| &binding.var.0);
+                    if named.as_ref().or(default).map(String::as_str) == Some(alias) {
+                        return Ok((*var, None));
+                    }
+                }
+                AnalyzedProjection::Prop {
+                    var,
+                    prop,
+                    alias: named,
+                } => {
+                    if named.as_deref().unwrap_or(&prop.name) == alias {
+                        return Ok((*var, Some(prop.clone())));
+                    }
+                }
+                AnalyzedProjection::RunningCount { alias: named } => {
+                    if named.as_deref().unwrap_or("running_count") == alias {
+                        return Err(AnalyzerError::UnsortableOrderAlias {
+                            alias: alias.to_owned(),
+                        });
+                    }
+                }
+                AnalyzedProjection::Expr {
+                    source,
+                    alias: named,
+                    ..
+                } => {
+                    if named.as_deref().unwrap_or(source) == alias {
+                        return Err(AnalyzerError::UnsortableOrderAlias {
+                            alias: alias.to_owned(),
+                        });
+                    }
+                }
+                AnalyzedProjection::Path { alias: named, .. } => {
+                    if named.as_deref().unwrap_or("path") == alias {
+                        return Err(AnalyzerError::UnsortableOrderAlias {
+                            alias: alias.to_owned(),
+                        });
+                    }
+                }
+                AnalyzedProjection::Aggregate { alias: named, .. } => {
+                    if named == alias {
+                        return Err(AnalyzerError::UnsortableOrderAlias {
+                            alias: alias.to_owned(),
+                        });
+                    }
+                }
+            }
+        }
+        Err(AnalyzerError::UnknownOrderAlias {
+            alias: alias.to_owned(),
+        })
+    }
+
     fn analyze_expr(&mut self, expr: BoolExpr) -> AnalyzeResult<AnalyzedExpr> {
         Ok(match expr {
             BoolExpr::Cmp(cmp) => AnalyzedExpr::Cmp(self.analyze_comparison(cmp)?),
@@ -830,6 +1221,14 @@ impl<'m> Analyzer<'m> {
                     values,
                 })
             }
+            Comparison::Prefix { var, prop, prefix } => {
+                let (var_id, prop_ref) = self.resolve_var_prop(&var, &prop, "predicate")?;
+                Ok(AnalyzedComparison::Prefix {
+                    var: var_id,
+                    prop: prop_ref,
+                    prefix,
+                })
+            }
             Comparison::Exists { var, prop } => {
                 let (var_id, prop_ref) = self.resolve_var_prop(&var, &prop, "predicate")?;
                 Ok(AnalyzedComparison::Exists {
@@ -851,26 +1250,42 @@ impl<'m> Analyzer<'m> {
                     prop: prop_ref,
                 })
             }
+            Comparison::EdgeExists {
+                var,
+                edge_type,
+                direction,
+                target,
+                predicate,
+            } => {
+                let var_id = self.require_var(&var, "predicate")?;
+                let edge_type = self.resolve_edge_type(&edge_type)?;
+                let name = target.var.0.clone();
+                if self.var_index.contains_key(&name) {
+                    return Err(AnalyzerError::DuplicateVariable { var: name });
+                }
+                let (label, label_id) = self.resolve_match_label(&target)?;
+                let target = VarBinding {
+                    id: VarId((self.vars.len() + self.scoped_vars.len()) as u32),
+                    var: target.var,
+                    label: Some(label),
+                    label_id,
+                };
+                self.scoped_vars.push(target.clone());
+                // The target is only visible while its own predicate is analyzed.
+                self.var_index.insert(name.clone(), target.id);
+                let predicate = predicate.map(|expr| self.analyze_expr(*expr));
+                self.var_index.remove(&name);
+                Ok(AnalyzedComparison::EdgeExists {
+                    var: var_id,
+                    edge_type,
+                    direction,
+                    target,
+                    predicate: predicate.transpose()?.map(Box::new),
+                })
+            }
         }
     }
 
-    fn validate_predicate_limits(&self, expr: &BoolExpr) -> AnalyzeResult<()> {
-        let stats = predicate_stats(expr);
-        if stats.nodes > MAX_PREDICATE_NODES {
-            return Err(AnalyzerError::PredicateTooLarge {
-                nodes: stats.nodes,
-                max: MAX_PREDICATE_NODES,
-            });
-        }
-        if stats.depth > MAX_PREDICATE_DEPTH {
-            return Err(AnalyzerError::PredicateTooDeep {
-                depth: stats.depth,
-                max: MAX_PREDICATE_DEPTH,
-            });
-        }
-        Ok(())
-    }
-
     fn validate_scalar(&self, value: &Value) -> AnalyzeResult<()> {
         validate_scalar_value(value)
     }
@@ -942,7 +1357,10 @@ impl<'m> Analyzer<'m> {
     }
 
     fn binding_for_id(&self, id: VarId) -> Option<&VarBinding> {
-        self.vars.iter().find(|binding| binding.id == id)
+        self.vars
+            .iter()
+            .chain(&self.scoped_vars)
+            .find(|binding| binding.id == id)
     }
 
     fn ensure_property_visible(&self, var_id: VarId, prop: &PropRef) -> AnalyzeResult<()> {
@@ -967,6 +1385,39 @@ impl<'m> Analyzer<'m> {
         Ok(())
     }
 
+    /// Parses a computed projection and resolves its property operands.
+    ///
+    /// Bare property names refer to the only match variable, so they are
+    /// rejected when the query binds more than one.
+    fn resolve_expr(&mut self, source: &str) -> AnalyzeResult<ScalarExpr<(VarId, PropRef)>> {
+        let invalid = |reason: String| AnalyzerError::InvalidExpression {
+            expr: source.trim().to_owned(),
+            reason,
+        };
+        let parsed = expr::parse(source).map_err(invalid)?;
+        parsed.try_map_props(&mut |path: PropPath| {
+            let var = match path.var {
+                Some(var) => self.require_var(&Var(var), "expression")?,
+                None if self.var_index.contains_key(&path.prop) => {
+                    return Err(invalid(format!(
+                        "variable '{}' is not a value; reference one of its properties",
+                        path.prop
+                    )));
+                }
+                None => match self.vars.as_slice() {
+                    [binding] => binding.id,
+                    _ => {
+                        return Err(invalid(format!(
+                            "property '{}' must be qualified with a variable",
+                            path.prop
+                        )))
+                    }
+                },
+            };
+            Ok((var, self.property(&path.prop)?))
+        })
+    }
+
     fn resolve_var_prop(
         &mut self,
         var: &Var,
@@ -985,30 +1436,102 @@ struct PredicateStats {
     depth: usize,
 }
 
-fn predicate_stats(expr: &BoolExpr) -> PredicateStats {
-    match expr {
-        BoolExpr::Cmp(_) => PredicateStats { nodes: 1, depth: 1 },
-        BoolExpr::And(children) | BoolExpr::Or(children) => {
-            let mut nodes = 1;
-            let mut max_depth = 0;
-            for child in children {
-                let stats = predicate_stats(child);
-                nodes += stats.nodes;
-                max_depth = max_depth.max(stats.depth);
+/// Finds the first binding unreachable from the first one over `edges`,
+/// returning both positions in `vars`.
+fn disconnected_pair(vars: &[VarBinding], edges: &[AnalyzedEdge]) -> Option<(usize, usize)> {
+    let first = vars.first()?;
+    let mut reached: HashSet<VarId> = HashSet::from([first.id]);
+    let mut frontier = vec![first.id];
+    while let Some(var) = frontier.pop() {
+        for edge in edges {
+            let neighbor = if edge.from == var {
+                edge.to
+            } else if edge.to == var {
+                edge.from
+            } else {
+                continue;
+            };
+            if reached.insert(neighbor) {
+                frontier.push(neighbor);
             }
-            PredicateStats {
-                nodes,
-                depth: max_depth + 1,
+        }
+    }
+    let right = vars
+        .iter()
+        .position(|binding| !reached.contains(&binding.id))?;
+    Some((0, right))
+}
+
+/// Returns the bindings on the shortest edge-clause chain from `from` to `to`,
+/// ignoring clause direction.
+fn edge_chain(edges: &[AnalyzedEdge], from: VarId, to: VarId) -> Option<Vec<VarId>> {
+    let mut parent: HashMap<VarId, VarId> = HashMap::new();
+    let mut frontier = vec![from];
+    parent.insert(from, from);
+    while !frontier.is_empty() && !parent.contains_key(&to) {
+        let mut next = Vec::new();
+        for var in frontier {
+            for edge in edges {
+                let neighbor = if edge.from == var {
+                    edge.to
+                } else if edge.to == var {
+                    edge.from
+                } else {
+                    continue;
+                };
+                if let Entry::Vacant(slot) = parent.entry(neighbor) {
+                    slot.insert(var);
+                    next.push(neighbor);
+                }
             }
         }
-        BoolExpr::Not(child) => {
-            let stats = predicate_stats(child);
-            PredicateStats {
-                nodes: stats.nodes + 1,
-                depth: stats.depth + 1,
+        frontier = next;
+    }
+    parent.get(&to)?;
+    let mut chain = vec![to];
+    let mut current = to;
+    while current != from {
+        current = parent[&current];
+        chain.push(current);
+    }
+    chain.reverse();
+    Some(chain)
+}
+
+fn validate_predicate_limits(expr: &BoolExpr) -> AnalyzeResult<()> {
+    let stats = predicate_stats(expr);
+    if stats.nodes > MAX_PREDICATE_NODES {
+        return Err(AnalyzerError::PredicateTooLarge {
+            nodes: stats.nodes,
+            max: MAX_PREDICATE_NODES,
+        });
+    }
+    if stats.depth > MAX_PREDICATE_DEPTH {
+        return Err(AnalyzerError::PredicateTooDeep {
+            depth: stats.depth,
+            max: MAX_PREDICATE_DEPTH,
+        });
+    }
+    Ok(())
+}
+
+/// Counts nodes and measures depth with an explicit stack so that hostile
+/// nesting cannot overflow the call stack before the limits are enforced.
+fn predicate_stats(expr: &BoolExpr) -> PredicateStats {
+    let mut stats = PredicateStats { nodes: 0, depth: 0 };
+    let mut stack = vec![(expr, 1usize)];
+    while let Some((node, depth)) = stack.pop() {
+        stats.nodes += 1;
+        stats.depth = stats.depth.max(depth);
+        match node {
+            BoolExpr::Cmp(_) => {}
+            BoolExpr::And(children) | BoolExpr::Or(children) => {
+                stack.extend(children.iter().map(|child| (child, depth + 1)));
             }
+            BoolExpr::Not(child) => stack.push((child, depth + 1)),
         }
     }
+    stats
 }
 
 fn validate_scalar_value(value: &Value) -> AnalyzeResult<()> {
@@ -1075,6 +1598,12 @@ mod tests {
             predicate: Some(expr),
             distinct: false,
             projections: vec![],
+            order_by: vec![],
+            tiebreak_descending: None,
+            limit: None,
+            offset: None,
+            limit_per_group: None,
+            reject_cartesian: false,
         };
         let normalized = normalize(&ast).expect("normalize succeeds");
         assert!(matches!(
@@ -1103,6 +1632,12 @@ mod tests {
             predicate: Some(expr),
             distinct: false,
             projections: vec![],
+            order_by: vec![],
+            tiebreak_descending: None,
+            limit: None,
+            offset: None,
+            limit_per_group: None,
+            reject_cartesian: false,
         };
         let normalized = normalize(&ast).expect("normalize succeeds");
         match normalized.predicate.unwrap() {
@@ -1130,6 +1665,12 @@ mod tests {
             predicate: Some(expr),
             distinct: false,
             projections: vec![],
+            order_by: vec![],
+            tiebreak_descending: None,
+            limit: None,
+            offset: None,
+            limit_per_group: None,
+            reject_cartesian: false,
         };
         let err = normalize(&ast).expect_err("normalize should fail");
         assert!(matches!(err, AnalyzerError::InListEmpty));
@@ -1151,6 +1692,12 @@ mod tests {
             predicate: Some(expr),
             distinct: false,
             projections: vec![],
+            order_by: vec![],
+            tiebreak_descending: None,
+            limit: None,
+            offset: None,
+            limit_per_group: None,
+            reject_cartesian: false,
         };
         assert!(matches!(
             normalize(&ast).unwrap_err(),
@@ -1171,6 +1718,47 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn rejects_nesting_past_depth_cap_before_simplifying() {
+        // An even chain of negations would simplify away entirely, so the
+        // depth cap must be applied to the tree as submitted.
+        let mut expr = BoolExpr::Cmp(Comparison::Exists {
+            var: var("a"),
+            prop: "name".into(),
+        });
+        for _ in 0..MAX_PREDICATE_DEPTH + 1 {
+            expr = BoolExpr::Not(Box::new(expr));
+        }
+        let ast = QueryAst {
+            predicate: Some(expr),
+            ..Default::default()
+        };
+        let err = normalize(&ast).expect_err("over-nested predicate rejected");
+        assert!(matches!(
+            err,
+            AnalyzerError::PredicateTooDeep { depth, max }
+                if depth == MAX_PREDICATE_DEPTH + 2 && max == MAX_PREDICATE_DEPTH
+        ));
+    }
+
+    #[test]
+    fn rejects_predicates_past_node_cap() {
+        let children = (0..MAX_PREDICATE_NODES)
+            .map(|_| {
+                BoolExpr::Cmp(Comparison::Exists {
+                    var: var("a"),
+                    prop: "name".into(),
+                })
+            })
+            .collect();
+        let ast = QueryAst {
+            predicate: Some(BoolExpr::Or(children)),
+            ..Default::default()
+        };
+        let err = analyze(&ast, &metadata()).expect_err("oversized predicate rejected");
+        assert_eq!(err.code(), "PredicateTooLarge");
+    }
+
     #[test]
     fn empty_or_represents_false() {
         let ast = QueryAst {
@@ -135,6 +135,15 @@ pub enum Comparison {
         /// Literal values to test membership against.
         values: Vec<Value>,
     },
+    /// String property starts with a literal prefix.
+    Prefix {
+        /// Variable binding referenced by the predicate.
+        var: Var,
+        /// Property name on the variable.
+        prop: String,
+        /// Prefix the string value must start with.
+        prefix: String,
+    },
     /// Checks if a property key exists (value may still be null).
     Exists {
         /// Variable binding referenced by the predicate.
@@ -156,6 +165,20 @@ pub enum Comparison {
         /// Property name on the variable.
         prop: String,
     },
+    /// Correlated existence test: holds when some neighbor reached over the
+    /// variable's edges binds `target` and satisfies `predicate`.
+    EdgeExists {
+        /// Variable binding whose edges are inspected.
+        var: Var,
+        /// Optional edge type filter.
+        edge_type: Option<String>,
+        /// Direction of the edges to follow.
+        direction: EdgeDirection,
+        /// Binding for the neighbor, scoped to the nested predicate.
+        target: MatchClause,
+        /// Optional predicate the neighbor must satisfy.
+        predicate: Option<Box<BoolExpr>>,
+    },
 }
 
 /// Projection item included in the final result.
@@ -177,6 +200,102 @@ pub enum Projection {
         /// Optional alias for the projected column.
         alias: Option<String>,
     },
+    /// Cumulative count of rows emitted so far, starting at 1.
+    RunningCount {
+        /// Optional alias for the projected column.
+        alias: Option<String>,
+    },
+    /// Node ids along the chain of edge clauses joining two variables.
+    Path {
+        /// Variable the path starts at.
+        from_var: Var,
+        /// Variable the path ends at.
+        to_var: Var,
+        /// Optional alias for the projected column.
+        alias: Option<String>,
+    },
+    /// Scalar expression computed from the row's properties.
+    ///
+    /// The source follows the grammar documented in [`crate::query::expr`].
+    Expr {
+        /// Expression source, such as `age * 2` or `u.first + ' ' + u.last`.
+        expr: String,
+        /// Optional alias for the projected column; defaults to the source.
+        alias: Option<String>,
+    },
+    /// Aggregate folded over every matched row into a single output row.
+    Aggregate {
+        /// Function folding the values together.
+        func: AggFunc,
+        /// Variable the aggregate reads from.
+        var: Var,
+        /// Property aggregated; `None` counts the bindings themselves.
+        prop: Option<String>,
+        /// Name of the projected column.
+        alias: String,
+    },
+}
+
+/// Aggregate function applied by [`Projection::Aggregate`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum AggFunc {
+    /// Number of bindings, or of non-null property values.
+    Count,
+    /// Sum of numeric property values.
+    Sum,
+    /// Arithmetic mean of numeric property values.
+    Avg,
+    /// Smallest property value.
+    Min,
+    /// Largest property value.
+    Max,
+}
+
+impl AggFunc {
+    /// Returns the lowercase name used in explain output and bindings.
+    pub fn name(self) -> &'static str {
+        match self {
+            AggFunc::Count => "count",
+            AggFunc::Sum => "sum",
+            AggFunc::Avg => "avg",
+            AggFunc::Min => "min",
+            AggFunc::Max => "max",
+        }
+    }
+}
+
+/// Value an [`OrderKey`] sorts rows by.
+#[derive(Clone, Debug)]
+pub enum OrderTarget {
+    /// Column written by the projection with this alias (or default name).
+    Alias(String),
+    /// Property read from a bound variable.
+    Prop {
+        /// Variable exposing the property.
+        var: Var,
+        /// Property name to sort by.
+        prop: String,
+    },
+}
+
+/// Sort key applied to the result rows.
+#[derive(Clone, Debug)]
+pub struct OrderKey {
+    /// Value compared between rows.
+    pub target: OrderTarget,
+    /// Sorts from largest to smallest instead of smallest to largest.
+    pub descending: bool,
+}
+
+/// Keeps only the first `n` rows of each group, ranked by `order_by`.
+#[derive(Clone, Debug)]
+pub struct GroupLimit {
+    /// Variable whose bound node identifies a row's group.
+    pub group_by: Var,
+    /// Maximum number of rows kept per group.
+    pub n: u64,
+    /// Sort keys ranking rows within a group; ties keep pipeline order.
+    pub order_by: Vec<OrderKey>,
 }
 
 /// Top-level AST produced by the query builder.
@@ -196,6 +315,20 @@ pub struct QueryAst {
     pub distinct: bool,
     /// Projection items defining the output columns.
     pub projections: Vec<Projection>,
+    /// Sort keys in priority order. Rows tied on every key are ordered by
+    /// the node ids bound to each match variable, the first match first.
+    pub order_by: Vec<OrderKey>,
+    /// Direction of the node-id tiebreak; `None` follows the last sort key.
+    pub tiebreak_descending: Option<bool>,
+    /// Maximum number of rows to return after sorting and skipping.
+    pub limit: Option<u64>,
+    /// Number of leading rows to skip before any are returned.
+    pub offset: Option<u64>,
+    /// Per-group row cap applied before the global sort and limit.
+    pub limit_per_group: Option<GroupLimit>,
+    /// Rejects match clauses that no edge chain connects instead of
+    /// cross-joining them.
+    pub reject_cartesian: bool,
 }
 
 impl Default for QueryAst {
@@ -208,6 +341,12 @@ impl Default for QueryAst {
             predicate: None,
             distinct: false,
             projections: Vec::new(),
+            order_by: Vec::new(),
+            tiebreak_descending: None,
+            limit: None,
+            offset: None,
+            limit_per_group: None,
+            reject_cartesian: false,
         }
     }
 }
@@ -2,7 +2,8 @@
 
 use crate::query::{
     ast::{
-        BoolExpr, Comparison, EdgeClause, EdgeDirection, MatchClause, Projection, QueryAst, Var,
+        AggFunc, BoolExpr, Comparison, EdgeClause, EdgeDirection, GroupLimit, MatchClause,
+        OrderKey, OrderTarget, Projection, QueryAst, Var,
     },
     executor::{Executor, QueryResult},
     planner::{PlanExplain, Planner, PlannerOutput},
@@ -159,6 +160,68 @@ impl QueryBuilder {
         self
     }
 
+    /// Appends a sort key ordering rows by `var.prop`.
+    pub fn order_by(
+        mut self,
+        var: impl Into<String>,
+        prop: impl Into<String>,
+        descending: bool,
+    ) -> Self {
+        self.ast.order_by.push(OrderKey {
+            target: OrderTarget::Prop {
+                var: Var(var.into()),
+                prop: prop.into(),
+            },
+            descending,
+        });
+        self
+    }
+
+    /// Overrides the direction of the node-id tiebreak applied after the
+    /// sort keys, which otherwise follows the last key.
+    pub fn tiebreak(mut self, descending: bool) -> Self {
+        self.ast.tiebreak_descending = Some(descending);
+        self
+    }
+
+    /// Caps the number of rows returned, applied after any sort.
+    pub fn limit(mut self, limit: u64) -> Self {
+        self.ast.limit = Some(limit);
+        self
+    }
+
+    /// Skips this many leading rows, applied after any sort.
+    pub fn offset(mut self, offset: u64) -> Self {
+        self.ast.offset = Some(offset);
+        self
+    }
+
+    /// Keeps at most `n` rows per distinct `group_by` binding, ranked by the
+    /// `(var, prop, descending)` keys, before the global sort and limit.
+    pub fn limit_per_group<I, V, P>(mut self, group_by: impl Into<String>, n: u64, keys: I) -> Self
+    where
+        I: IntoIterator<Item = (V, P, bool)>,
+        V: Into<String>,
+        P: Into<String>,
+    {
+        let order_by = keys
+            .into_iter()
+            .map(|(var, prop, descending)| OrderKey {
+                target: OrderTarget::Prop {
+                    var: Var(var.into()),
+                    prop: prop.into(),
+                },
+                descending,
+            })
+            .collect();
+        self.ast.limit_per_group = Some(GroupLimit {
+            group_by: Var(group_by.into()),
+            n,
+            order_by,
+        });
+        self
+    }
+
     /// Builds the AST without planning.
     pub fn build(self) -> Result<QueryAst> {
         if let Some(err) = self.error {
@@ -494,6 +557,19 @@ impl PredicateBuilder {
         })
     }
 
+    /// Adds a predicate matching string values that start with `prefix`.
+    pub fn starts_with<P, S>(&mut self, prop: P, prefix: S) -> &mut Self
+    where
+        P: Into<String>,
+        S: Into<String>,
+    {
+        self.push_cmp(Comparison::Prefix {
+            var: self.var.clone(),
+            prop: prop.into(),
+            prefix: prefix.into(),
+        })
+    }
+
     /// Asserts that the property key is present on the entity.
     pub fn exists<P>(&mut self, prop: P) -> &mut Self
     where
@@ -576,6 +652,24 @@ pub struct ProjectionSpec {
 }
 
 impl ProjectionSpec {
+    /// Projects `func` over `var.prop`, or over `var` itself when `prop` is
+    /// `None`, as the column `alias`.
+    pub fn aggregate(
+        func: AggFunc,
+        var: impl Into<String>,
+        prop: Option<&str>,
+        alias: impl Into<String>,
+    ) -> Self {
+        Self {
+            projection: Projection::Aggregate {
+                func,
+                var: Var(var.into()),
+                prop: prop.map(str::to_owned),
+                alias: alias.into(),
+            },
+        }
+    }
+
     fn into_projection(self) -> Projection {
         self.projection
     }
@@ -82,6 +82,37 @@ pub enum AnalyzerError {
     /// Projection alias cannot be blank or whitespace-only.
     #[error("projection alias cannot be empty")]
     EmptyProjectionAlias,
+    /// Path projection endpoints are not joined by edge clauses.
+    #[error("no edge chain connects '{from}' to '{to}'")]
+    PathNotConnected { from: String, to: String },
+    /// Order key names a column no projection writes.
+    #[error("order key '{alias}' does not name a projected column")]
+    UnknownOrderAlias { alias: String },
+    /// Order key names a column computed while rows are projected.
+    #[error("cannot order by computed column '{alias}'")]
+    UnsortableOrderAlias { alias: String },
+    /// Computed projection uses syntax or names the expression language lacks.
+    #[error("invalid expression '{expr}': {reason}")]
+    InvalidExpression { expr: String, reason: String },
+    /// Aggregate other than count was given no property to fold.
+    #[error("{func}() aggregate requires a property")]
+    AggregateMissingProperty { func: &'static str },
+    /// Aggregates fold every row, so plain columns have no single value.
+    #[error("projection '{column}' cannot be combined with aggregate projections")]
+    MixedAggregateProjection { column: String },
+    /// Aggregates emit a single row, so paging the input is ambiguous.
+    #[error("limit, offset, and limit per group cannot be combined with aggregate projections")]
+    AggregateWithLimit,
+    /// Match clauses are not joined by edges and `rejectCartesian` is set.
+    #[error("no edge chain connects '{left}' to '{right}'; refusing cartesian product")]
+    CartesianProduct { left: String, right: String },
+    /// Pattern holds more match or edge clauses than the planner allows.
+    #[error("pattern has {count} {clause} clauses, exceeding the limit of {max}")]
+    PatternTooLarge {
+        clause: &'static str,
+        count: usize,
+        max: usize,
+    },
     /// Predicate tree exceeds the node budget.
     #[error("predicate tree exceeds {max} nodes (got {nodes})")]
     PredicateTooLarge { nodes: usize, max: usize },
@@ -167,6 +198,15 @@ impl AnalyzerError {
             AnalyzerError::DirectionInvalid { .. } => "DirectionInvalid",
             AnalyzerError::EdgeReflexiveNotAllowed { .. } => "EdgeReflexiveNotAllowed",
             AnalyzerError::EmptyProjectionAlias => "EmptyProjectionAlias",
+            AnalyzerError::PathNotConnected { .. } => "PathNotConnected",
+            AnalyzerError::UnknownOrderAlias { .. } => "UnknownOrderAlias",
+            AnalyzerError::UnsortableOrderAlias { .. } => "UnsortableOrderAlias",
+            AnalyzerError::InvalidExpression { .. } => "InvalidExpression",
+            AnalyzerError::AggregateMissingProperty { .. } => "AggregateMissingProperty",
+            AnalyzerError::MixedAggregateProjection { .. } => "MixedAggregateProjection",
+            AnalyzerError::AggregateWithLimit => "AggregateWithLimit",
+            AnalyzerError::CartesianProduct { .. } => "CartesianProduct",
+            AnalyzerError::PatternTooLarge { .. } => "PatternTooLarge",
             AnalyzerError::PredicateTooLarge { .. } => "PredicateTooLarge",
             AnalyzerError::PredicateTooDeep { .. } => "PredicateTooDeep",
             AnalyzerError::InListEmpty => "InListEmpty",
@@ -1,9 +1,10 @@
 //! Query executor scaffolding.
 
-use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::ops::Bound;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use crate::primitives::pager::{Pager, ReadGuard};
 use crate::storage::index::{collect_all, PostingStream};
@@ -12,12 +13,13 @@ use crate::storage::{
 };
 use crate::types::{LabelId, NodeId, PropId, Result, SombraError, TypeId};
 
-use crate::query::ast::Var;
+use crate::query::ast::{AggFunc, Var};
 use crate::query::metadata::MetadataProvider;
 use crate::query::physical::{
     InLookup, LiteralValue, PhysicalBoolExpr, PhysicalComparison, PhysicalNode, PhysicalOp,
-    PhysicalPlan, ProjectField, PropPredicate as PhysicalPredicate, ValueKey,
+    PhysicalPlan, ProjectField, PropPredicate as PhysicalPredicate, SortKey, ValueKey,
 };
+use crate::query::planner::op_name;
 use crate::query::profile::{
     profile_timer as query_profile_timer, record_profile_timer as record_query_profile_timer,
     QueryProfileKind,
@@ -28,8 +30,24 @@ use crate::query::profile::{
 pub struct QueryResult {
     /// The rows returned by the query.
     pub rows: Vec<Row>,
+    /// Whether [`ExecOptions::max_rows`] cut the result short.
+    pub limited: bool,
 }
 
+/// One page of rows returned by [`Executor::execute_page`].
+#[derive(Debug, Default)]
+pub struct QueryPage {
+    /// Rows on this page, in sort order.
+    pub rows: Vec<Row>,
+    /// Position of the page's last row when more rows follow it.
+    pub next: Option<PageCursor>,
+}
+
+/// Sort position of a row: its value for every `OrderBy` key, with `None`
+/// marking a missing or null property.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PageCursor(pub Vec<Option<PropValueOwned>>);
+
 /// Single output row represented as a mapping from alias to value.
 pub type Row = BTreeMap<String, Value>;
 
@@ -56,6 +74,8 @@ pub enum Value {
     NodeId(NodeId),
     /// Nested object value (used for var projections).
     Object(BTreeMap<String, Value>),
+    /// Ordered list value (used for path projections).
+    List(Vec<Value>),
 }
 
 type NodeCache = Arc<Mutex<HashMap<NodeId, NodeData>>>;
@@ -69,11 +89,12 @@ type BoxBindingStream = Box<dyn BindingStream>;
 
 struct ReadContext {
     guard: ReadGuard,
+    clock: Option<OperatorClock>,
 }
 
 impl ReadContext {
-    fn new(guard: ReadGuard) -> Self {
-        Self { guard }
+    fn new(guard: ReadGuard, clock: Option<OperatorClock>) -> Self {
+        Self { guard, clock }
     }
 
     fn guard(&self) -> &ReadGuard {
@@ -81,6 +102,134 @@ impl ReadContext {
     }
 }
 
+type OperatorClock = Arc<Mutex<OperatorTimes>>;
+
+#[cfg(any(test, feature = "test-hooks"))]
+thread_local! {
+    /// Extra self time charged to a named operator on every pull, so
+    /// operator timeouts can be exercised without depending on wall time.
+    static OPERATOR_STALL: std::cell::Cell<Option<(&'static str, Duration)>> =
+        const { std::cell::Cell::new(None) };
+}
+
+/// Charges `stall` to every pull of the operator called `name` in queries run
+/// on this thread, or clears the stall when `None`.
+///
+/// Only available to tests and to harnesses built with `test-hooks`.
+#[cfg(any(test, feature = "test-hooks"))]
+pub fn stall_operator(stall: Option<(&'static str, Duration)>) {
+    OPERATOR_STALL.with(|cell| cell.set(stall));
+}
+
+#[cfg(any(test, feature = "test-hooks"))]
+fn injected_stall(name: &str) -> Duration {
+    match OPERATOR_STALL.with(std::cell::Cell::get) {
+        Some((stalled, by)) if stalled == name => by,
+        _ => Duration::ZERO,
+    }
+}
+
+#[cfg(not(any(test, feature = "test-hooks")))]
+fn injected_stall(_name: &str) -> Duration {
+    Duration::ZERO
+}
+
+/// Self time spent by each operator of one query, checked against a shared budget.
+///
+/// Time is charged to the innermost running operator only, so a parent that
+/// waits on a slow child is not blamed for the child's work.
+struct OperatorTimes {
+    budget: Duration,
+    names: Vec<&'static str>,
+    spent: Vec<Duration>,
+    active: Vec<(usize, Instant)>,
+}
+
+impl OperatorTimes {
+    fn new(budget: Duration) -> Self {
+        Self {
+            budget,
+            names: Vec::new(),
+            spent: Vec::new(),
+            active: Vec::new(),
+        }
+    }
+
+    fn register(&mut self, name: &'static str) -> usize {
+        self.names.push(name);
+        self.spent.push(Duration::ZERO);
+        self.names.len() - 1
+    }
+
+    /// Pauses the calling operator and starts charging `slot`.
+    fn enter(&mut self, slot: usize) -> Result<()> {
+        let now = Instant::now();
+        if let Some(&(parent, started)) = self.active.last() {
+            self.spent[parent] += now - started;
+            self.check(parent)?;
+        }
+        self.active.push((slot, now));
+        Ok(())
+    }
+
+    /// Stops charging the current operator and resumes its caller.
+    fn exit(&mut self) -> Result<()> {
+        let now = Instant::now();
+        let Some((slot, started)) = self.active.pop() else {
+            return Ok(());
+        };
+        self.spent[slot] += now - started + injected_stall(self.names[slot]);
+        if let Some(parent) = self.active.last_mut() {
+            parent.1 = now;
+        }
+        self.check(slot)
+    }
+
+    fn check(&self, slot: usize) -> Result<()> {
+        let spent = self.spent[slot];
+        if spent <= self.budget {
+            return Ok(());
+        }
+        Err(SombraError::Timeout(format!(
+            "operator {} ran for {}ms, exceeding its {}ms deadline",
+            self.names[slot],
+            spent.as_millis(),
+            self.budget.as_millis()
+        )))
+    }
+}
+
+/// Charges the time spent pulling from `inner` to one operator slot.
+struct TimedStream {
+    inner: BoxBindingStream,
+    clock: OperatorClock,
+    slot: usize,
+}
+
+impl TimedStream {
+    fn wrap(inner: BoxBindingStream, clock: OperatorClock, name: &'static str) -> Result<Self> {
+        let slot = lock_clock(&clock)?.register(name);
+        Ok(Self { inner, clock, slot })
+    }
+}
+
+impl BindingStream for TimedStream {
+    fn try_next(&mut self) -> Result<Option<BindingRow>> {
+        lock_clock(&self.clock)?.enter(self.slot)?;
+        let row = self.inner.try_next();
+        let exited = lock_clock(&self.clock)?.exit();
+        let row = row?;
+        exited?;
+        Ok(row)
+    }
+}
+
+fn lock_clock(clock: &OperatorClock) -> Result<std::sync::MutexGuard<'_, OperatorTimes>> {
+    clock
+        .lock()
+        .map_err(|_| SombraError::Invalid("operator clock lock poisoned"))
+}
+
 #[derive(Clone, Debug, Default)]
 struct BindingRow {
     nodes: BTreeMap<String, NodeId>,
@@ -112,11 +261,18 @@ enum RowMapper {
         cache: NodeCache,
         metadata: Arc<dyn MetadataProvider>,
         prop_names: PropNameCache,
+        resolve_names: bool,
+    },
+    Aggregate {
+        fields: Vec<ProjectField>,
+        graph: Arc<Graph>,
+        context: Arc<ReadContext>,
+        cache: NodeCache,
     },
 }
 
 impl RowMapper {
-    fn map(&self, binding: &BindingRow) -> Result<Row> {
+    fn map(&self, binding: &BindingRow, ordinal: u64) -> Result<Row> {
         match self {
             RowMapper::All => project_all(binding),
             RowMapper::Project {
@@ -126,7 +282,21 @@ impl RowMapper {
                 cache,
                 metadata,
                 prop_names,
-            } => apply_projection(binding, fields, graph, context, cache, metadata, prop_names),
+                resolve_names,
+            } => apply_projection(
+                binding,
+                ordinal,
+                fields,
+                graph,
+                context,
+                cache,
+                metadata,
+                prop_names,
+                *resolve_names,
+            ),
+            RowMapper::Aggregate { .. } => Err(SombraError::Invalid(
+                "aggregate rows are folded, not mapped",
+            )),
         }
     }
 }
@@ -137,6 +307,10 @@ pub struct ResultStream {
     mapper: RowMapper,
     _context: Arc<ReadContext>,
     cancel_token: Option<Arc<AtomicBool>>,
+    deadline: Option<Instant>,
+    emitted: u64,
+    dedup: Option<RowDedup>,
+    exhausted: bool,
 }
 
 impl ResultStream {
@@ -145,13 +319,69 @@ impl ResultStream {
         mapper: RowMapper,
         context: Arc<ReadContext>,
         cancel_token: Option<Arc<AtomicBool>>,
+        deadline: Option<Instant>,
+        dedup: Option<RowDedup>,
     ) -> Self {
         Self {
             bindings,
             mapper,
             _context: context,
             cancel_token,
+            deadline,
+            emitted: 0,
+            dedup,
+            exhausted: false,
+        }
+    }
+
+    /// Drains every binding into the aggregate fields and builds the single
+    /// row they produce.
+    fn fold_aggregates(&mut self) -> Result<Row> {
+        let RowMapper::Aggregate {
+            fields,
+            graph,
+            context,
+            cache,
+        } = &self.mapper
+        else {
+            return Err(SombraError::Invalid(
+                "aggregate fold without aggregate fields",
+            ));
+        };
+        let mut accumulators: Vec<AggAccumulator> = fields
+            .iter()
+            .map(|field| match field {
+                ProjectField::Aggregate { func, .. } => Ok(AggAccumulator::new(*func)),
+                _ => Err(SombraError::Invalid(
+                    "aggregate projection mixes plain fields",
+                )),
+            })
+            .collect::<Result<_>>()?;
+        while let Some(binding) = self.bindings.try_next()? {
+            self.check_cancel()?;
+            for (field, acc) in fields.iter().zip(accumulators.iter_mut()) {
+                let ProjectField::Aggregate { var, prop, .. } = field else {
+                    continue;
+                };
+                let node = binding
+                    .get(&var.0)
+                    .ok_or(SombraError::Invalid("aggregate variable missing"))?;
+                match prop {
+                    None => acc.count += 1,
+                    Some(prop) => {
+                        let data = fetch_node_data(graph, context, cache, node)?;
+                        if let Some(value) = find_prop(&data, *prop) {
+                            acc.update(value)?;
+                        }
+                    }
+                }
+            }
+        }
+        let mut row = Row::new();
+        for (field, acc) in fields.iter().zip(accumulators) {
+            row.insert(field.column_name(), acc.finish()?);
         }
+        Ok(row)
     }
 
     fn check_cancel(&self) -> Result<()> {
@@ -160,6 +390,12 @@ impl ResultStream {
                 return Err(SombraError::Cancelled);
             }
         }
+        if self
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            return Err(SombraError::Timeout(QUERY_TIMED_OUT.into()));
+        }
         Ok(())
     }
 }
@@ -168,13 +404,171 @@ impl Iterator for ResultStream {
     type Item = Result<Row>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if let Err(err) = self.check_cancel() {
-            return Some(Err(err));
+        if matches!(self.mapper, RowMapper::Aggregate { .. }) {
+            if self.exhausted {
+                return None;
+            }
+            if let Err(err) = self.check_cancel() {
+                return Some(Err(err));
+            }
+            self.exhausted = true;
+            self.emitted += 1;
+            return Some(self.fold_aggregates());
         }
-        match self.bindings.try_next() {
-            Ok(Some(binding)) => Some(self.mapper.map(&binding)),
-            Ok(None) => None,
-            Err(err) => Some(Err(err)),
+        loop {
+            if let Err(err) = self.check_cancel() {
+                return Some(Err(err));
+            }
+            let binding = match self.bindings.try_next() {
+                Ok(Some(binding)) => binding,
+                Ok(None) => return None,
+                Err(err) => return Some(Err(err)),
+            };
+            let row = match self.mapper.map(&binding, self.emitted + 1) {
+                Ok(row) => row,
+                Err(err) => return Some(Err(err)),
+            };
+            if let Some(dedup) = self.dedup.as_mut() {
+                match dedup.first_occurrence(&row) {
+                    Ok(true) => {}
+                    Ok(false) => continue,
+                    Err(err) => return Some(Err(err)),
+                }
+            }
+            self.emitted += 1;
+            return Some(Ok(row));
+        }
+    }
+}
+
+/// Drops rows whose `columns` repeat those of an earlier row, keeping the
+/// first occurrence.
+struct RowDedup {
+    columns: Vec<String>,
+    seen: HashSet<Vec<u8>>,
+}
+
+impl RowDedup {
+    fn new(columns: Vec<String>) -> Option<Self> {
+        if columns.is_empty() {
+            return None;
+        }
+        Some(Self {
+            columns,
+            seen: HashSet::new(),
+        })
+    }
+
+    fn first_occurrence(&mut self, row: &Row) -> Result<bool> {
+        let mut key = Vec::new();
+        for column in &self.columns {
+            let value = row.get(column).ok_or(SombraError::Invalid(
+                "dedupBy names an unknown result column",
+            ))?;
+            encode_dedup_key(value, &mut key);
+        }
+        Ok(self.seen.insert(key))
+    }
+}
+
+/// Appends a self-delimiting encoding of `value` so that equal values, and
+/// only equal values, produce equal byte strings.
+fn encode_dedup_key(value: &Value, out: &mut Vec<u8>) {
+    let bytes = |out: &mut Vec<u8>, data: &[u8]| {
+        out.extend_from_slice(&(data.len() as u64).to_be_bytes());
+        out.extend_from_slice(data);
+    };
+    match value {
+        Value::Null => out.push(0),
+        Value::Bool(v) => out.extend_from_slice(&[1, u8::from(*v)]),
+        Value::Int(v) => {
+            out.push(2);
+            out.extend_from_slice(&v.to_be_bytes());
+        }
+        Value::Float(v) => {
+            out.push(3);
+            out.extend_from_slice(&v.to_bits().to_be_bytes());
+        }
+        Value::String(v) => {
+            out.push(4);
+            bytes(out, v.as_bytes());
+        }
+        Value::Bytes(v) => {
+            out.push(5);
+            bytes(out, v);
+        }
+        Value::Date(v) => {
+            out.push(6);
+            out.extend_from_slice(&v.to_be_bytes());
+        }
+        Value::DateTime(v) => {
+            out.push(7);
+            out.extend_from_slice(&v.to_be_bytes());
+        }
+        Value::NodeId(id) => {
+            out.push(8);
+            out.extend_from_slice(&id.0.to_be_bytes());
+        }
+        Value::Object(map) => {
+            out.push(9);
+            out.extend_from_slice(&(map.len() as u64).to_be_bytes());
+            for (key, value) in map {
+                bytes(out, key.as_bytes());
+                encode_dedup_key(value, out);
+            }
+        }
+        Value::List(items) => {
+            out.push(10);
+            out.extend_from_slice(&(items.len() as u64).to_be_bytes());
+            for item in items {
+                encode_dedup_key(item, out);
+            }
+        }
+    }
+}
+
+/// Per-call options for [`Executor::execute_with_options`] and
+/// [`Executor::stream_with_options`].
+#[derive(Clone, Debug)]
+pub struct ExecOptions {
+    /// Budget for the work of any single operator; `None` disables the check.
+    pub operator_timeout: Option<Duration>,
+    /// Whether projected property keys are resolved through the dictionary.
+    ///
+    /// When `false`, node projections key their properties by the interned
+    /// property id rendered as a decimal string.
+    pub resolve_names: bool,
+    /// Result columns to deduplicate rows on; empty disables deduplication.
+    ///
+    /// A row is dropped when the values of all these columns equal those of
+    /// an earlier row, so the first occurrence's other columns are kept.
+    /// Unlike `distinct`, the remaining columns need not match.
+    pub dedup_by: Vec<String>,
+    /// Most rows [`Executor::execute_with_options`] materializes; `None`
+    /// keeps every row.
+    ///
+    /// Execution stops at the first row past the cap and the result is
+    /// marked [`QueryResult::limited`]. Streams ignore this setting.
+    pub max_rows: Option<usize>,
+    /// Instant past which the query aborts; `None` lets it run to completion.
+    ///
+    /// Checked before each row is pulled, so streams honour it too. Expiry
+    /// surfaces as a [`SombraError::Timeout`] carrying [`QUERY_TIMED_OUT`].
+    pub deadline: Option<Instant>,
+}
+
+/// Message of the [`SombraError::Timeout`] raised once [`ExecOptions::deadline`]
+/// passes.
+pub const QUERY_TIMED_OUT: &str = "query timed out";
+
+impl Default for ExecOptions {
+    fn default() -> Self {
+        Self {
+            operator_timeout: None,
+            resolve_names: true,
+            dedup_by: Vec::new(),
+            max_rows: None,
+            deadline: None,
         }
     }
 }
@@ -202,11 +596,49 @@ impl Executor {
         plan: &PhysicalPlan,
         cancel: Option<Arc<AtomicBool>>,
     ) -> Result<QueryResult> {
-        let mut stream = self.stream_with_token(plan, cancel)?;
+        self.execute_with_operator_timeout(plan, cancel, None)
+    }
+
+    /// Executes a physical plan, aborting once any single operator spends
+    /// longer than `operator_timeout` in its own work.
+    ///
+    /// The resulting [`SombraError::Timeout`] names the stalled operator and
+    /// the time it consumed.
+    pub fn execute_with_operator_timeout(
+        &self,
+        plan: &PhysicalPlan,
+        cancel: Option<Arc<AtomicBool>>,
+        operator_timeout: Option<Duration>,
+    ) -> Result<QueryResult> {
+        let options = ExecOptions {
+            operator_timeout,
+            ..ExecOptions::default()
+        };
+        self.execute_with_options(plan, cancel, options)
+    }
+
+    /// Executes a physical plan under the given [`ExecOptions`].
+    pub fn execute_with_options(
+        &self,
+        plan: &PhysicalPlan,
+        cancel: Option<Arc<AtomicBool>>,
+        options: ExecOptions,
+    ) -> Result<QueryResult> {
+        let max_rows = options.max_rows;
+        let mut stream = self.stream_with_token(plan, cancel, options)?;
         let iter_timer = query_profile_timer();
-        let rows: Vec<Row> = stream.by_ref().collect::<Result<_>>()?;
+        let mut rows: Vec<Row> = Vec::new();
+        let mut limited = false;
+        for row in stream.by_ref() {
+            let row = row?;
+            if max_rows.is_some_and(|max| rows.len() >= max) {
+                limited = true;
+                break;
+            }
+            rows.push(row);
+        }
         record_query_profile_timer(QueryProfileKind::StreamIter, iter_timer);
-        Ok(QueryResult { rows })
+        Ok(QueryResult { rows, limited })
     }
 
     /// Executes a physical plan and returns a streaming iterator over results.
@@ -215,44 +647,165 @@ impl Executor {
         plan: &PhysicalPlan,
         cancel: Option<Arc<AtomicBool>>,
     ) -> Result<ResultStream> {
-        self.stream_with_token(plan, cancel)
+        self.stream_with_token(plan, cancel, ExecOptions::default())
     }
 
-    fn stream_with_token(
+    /// Streams a physical plan under a per-operator time budget.
+    ///
+    /// See [`Executor::execute_with_operator_timeout`].
+    pub fn stream_with_operator_timeout(
         &self,
         plan: &PhysicalPlan,
         cancel: Option<Arc<AtomicBool>>,
+        operator_timeout: Option<Duration>,
     ) -> Result<ResultStream> {
-        let guard_timer = query_profile_timer();
-        let context = Arc::new(ReadContext::new(self.pager.begin_latest_committed_read()?));
-        record_query_profile_timer(QueryProfileKind::ReadGuard, guard_timer);
-        let cache: NodeCache = Arc::new(Mutex::new(HashMap::new()));
-        let mut project_fields = None;
-        let root = match &plan.root.op {
-            PhysicalOp::Project { fields } => {
-                if plan.root.inputs.len() != 1 {
-                    return Err(SombraError::Invalid("project expects single input"));
-                }
-                project_fields = Some(fields.clone());
-                &plan.root.inputs[0]
+        let options = ExecOptions {
+            operator_timeout,
+            ..ExecOptions::default()
+        };
+        self.stream_with_token(plan, cancel, options)
+    }
+
+    /// Streams a physical plan under the given [`ExecOptions`].
+    pub fn stream_with_options(
+        &self,
+        plan: &PhysicalPlan,
+        cancel: Option<Arc<AtomicBool>>,
+        options: ExecOptions,
+    ) -> Result<ResultStream> {
+        self.stream_with_token(plan, cancel, options)
+    }
+
+    /// Executes one keyset page of a plan whose sort sits directly under the
+    /// projection, returning at most `page_size` rows that sort after `after`.
+    ///
+    /// Only the page being assembled is buffered, so memory stays bounded by
+    /// `page_size` however many rows precede the cursor. Rows equal on every
+    /// sort key share one position; the analyzer's node-id tiebreak keys leave
+    /// only identical bindings tied.
+    pub fn execute_page(
+        &self,
+        plan: &PhysicalPlan,
+        after: Option<&PageCursor>,
+        page_size: usize,
+        cancel: Option<Arc<AtomicBool>>,
+        options: ExecOptions,
+    ) -> Result<QueryPage> {
+        if page_size == 0 {
+            return Err(SombraError::Invalid("page size must be greater than zero"));
+        }
+        let (context, cache) = self.read_context(&options)?;
+        let (project_fields, root) = split_projection(plan)?;
+        if project_fields
+            .as_ref()
+            .is_some_and(|fields| fields.iter().any(ProjectField::is_aggregate))
+        {
+            return Err(SombraError::Invalid("paged queries cannot aggregate"));
+        }
+        let PhysicalOp::OrderBy { keys } = &root.op else {
+            return Err(SombraError::Invalid(
+                "paged queries require an order by below the projection",
+            ));
+        };
+        if root.inputs.len() != 1 {
+            return Err(SombraError::Invalid("order by expects single input child"));
+        }
+        let mut input = self.build_stream(&root.inputs[0], Arc::clone(&context), cache.clone())?;
+        let cap = page_size.saturating_add(1);
+        let mut kept: Vec<(Vec<Option<PropValueOwned>>, BindingRow)> = Vec::new();
+        while let Some(row) = input.try_next()? {
+            let values = sort_values(&row, keys, &self.graph, &context, &cache)?;
+            if after.is_some_and(|cursor| compare_sort_keys(&values, &cursor.0, keys).is_le()) {
+                continue;
             }
-            _ => &plan.root,
+            let pos = kept.partition_point(|(existing, _)| {
+                compare_sort_keys(existing, &values, keys).is_le()
+            });
+            if pos < cap {
+                kept.insert(pos, (values, row));
+                kept.truncate(cap);
+            }
+        }
+        let next = if kept.len() > page_size {
+            kept.truncate(page_size);
+            kept.last().map(|(values, _)| PageCursor(values.clone()))
+        } else {
+            None
         };
+        let bindings = VecBindingStream::new(kept.into_iter().map(|(_, row)| row).collect());
+        let mapper = self.row_mapper(project_fields, &context, cache, &options);
+        let stream = ResultStream::new(
+            Box::new(bindings),
+            mapper,
+            context,
+            cancel,
+            options.deadline,
+            RowDedup::new(options.dedup_by),
+        );
+        let rows = stream.collect::<Result<Vec<_>>>()?;
+        Ok(QueryPage { rows, next })
+    }
+
+    fn stream_with_token(
+        &self,
+        plan: &PhysicalPlan,
+        cancel: Option<Arc<AtomicBool>>,
+        options: ExecOptions,
+    ) -> Result<ResultStream> {
+        let (context, cache) = self.read_context(&options)?;
+        let (project_fields, root) = split_projection(plan)?;
         let build_timer = query_profile_timer();
         let bindings = self.build_stream(root, Arc::clone(&context), Arc::clone(&cache))?;
         record_query_profile_timer(QueryProfileKind::StreamBuild, build_timer);
-        let mapper = match project_fields {
+        let mapper = self.row_mapper(project_fields, &context, cache, &options);
+        Ok(ResultStream::new(
+            bindings,
+            mapper,
+            context,
+            cancel,
+            options.deadline,
+            RowDedup::new(options.dedup_by),
+        ))
+    }
+
+    fn read_context(&self, options: &ExecOptions) -> Result<(Arc<ReadContext>, NodeCache)> {
+        let guard_timer = query_profile_timer();
+        let clock = options
+            .operator_timeout
+            .map(|budget| Arc::new(Mutex::new(OperatorTimes::new(budget))));
+        let context = Arc::new(ReadContext::new(
+            self.pager.begin_latest_committed_read()?,
+            clock,
+        ));
+        record_query_profile_timer(QueryProfileKind::ReadGuard, guard_timer);
+        Ok((context, Arc::new(Mutex::new(HashMap::new()))))
+    }
+
+    fn row_mapper(
+        &self,
+        project_fields: Option<Vec<ProjectField>>,
+        context: &Arc<ReadContext>,
+        cache: NodeCache,
+        options: &ExecOptions,
+    ) -> RowMapper {
+        match project_fields {
+            Some(fields) if fields.iter().any(ProjectField::is_aggregate) => RowMapper::Aggregate {
+                fields,
+                graph: Arc::clone(&self.graph),
+                context: Arc::clone(context),
+                cache,
+            },
             Some(fields) => RowMapper::Project {
                 fields,
                 graph: Arc::clone(&self.graph),
-                context: Arc::clone(&context),
+                context: Arc::clone(context),
                 cache,
                 metadata: Arc::clone(&self.metadata),
                 prop_names: Arc::new(Mutex::new(HashMap::new())),
+                resolve_names: options.resolve_names,
             },
             None => RowMapper::All,
-        };
-        Ok(ResultStream::new(bindings, mapper, context, cancel))
+        }
     }
 
     fn build_stream(
@@ -260,6 +813,24 @@ impl Executor {
         node: &PhysicalNode,
         context: Arc<ReadContext>,
         cache: NodeCache,
+    ) -> Result<BoxBindingStream> {
+        let clock = context.clock.clone();
+        let stream = self.build_operator(node, context, cache)?;
+        match clock {
+            Some(clock) => Ok(Box::new(TimedStream::wrap(
+                stream,
+                clock,
+                op_name(&node.op),
+            )?)),
+            None => Ok(stream),
+        }
+    }
+
+    fn build_operator(
+        &self,
+        node: &PhysicalNode,
+        context: Arc<ReadContext>,
+        cache: NodeCache,
     ) -> Result<BoxBindingStream> {
         match &node.op {
             PhysicalOp::LabelScan { label, as_var, .. } => {
@@ -276,6 +847,19 @@ impl Executor {
                 as_var,
                 ..
             } => self.build_prop_index_stream(*label, *prop, pred, &as_var.0, Arc::clone(&context)),
+            PhysicalOp::CompositeIndexScan {
+                label,
+                props,
+                preds,
+                as_var,
+                ..
+            } => self.build_composite_index_stream(
+                *label,
+                props[0],
+                preds,
+                &as_var.0,
+                Arc::clone(&context),
+            ),
             PhysicalOp::Expand {
                 from,
                 to,
@@ -368,6 +952,61 @@ impl Executor {
                     right.0.clone(),
                 )?))
             }
+            PhysicalOp::CrossJoin => {
+                if node.inputs.len() != 2 {
+                    return Err(SombraError::Invalid(
+                        "cross join expects exactly two input children",
+                    ));
+                }
+                let left_stream =
+                    self.build_stream(&node.inputs[0], Arc::clone(&context), cache.clone())?;
+                let mut right_stream =
+                    self.build_stream(&node.inputs[1], Arc::clone(&context), cache)?;
+                Ok(Box::new(CrossJoinStream::new(
+                    left_stream,
+                    collect_bindings(&mut *right_stream)?,
+                )))
+            }
+            PhysicalOp::OrderBy { keys } => {
+                if node.inputs.len() != 1 {
+                    return Err(SombraError::Invalid("order by expects single input child"));
+                }
+                let mut input =
+                    self.build_stream(&node.inputs[0], Arc::clone(&context), cache.clone())?;
+                let rows = collect_bindings(&mut *input)?;
+                Ok(Box::new(VecBindingStream::new(sort_bindings(
+                    rows,
+                    keys,
+                    &self.graph,
+                    &context,
+                    &cache,
+                )?)))
+            }
+            PhysicalOp::LimitPerGroup { group_by, n, keys } => {
+                if node.inputs.len() != 1 {
+                    return Err(SombraError::Invalid(
+                        "limit per group expects single input child",
+                    ));
+                }
+                let mut input =
+                    self.build_stream(&node.inputs[0], Arc::clone(&context), cache.clone())?;
+                Ok(Box::new(VecBindingStream::new(limit_per_group(
+                    &mut *input,
+                    group_by,
+                    *n,
+                    keys,
+                    &self.graph,
+                    &context,
+                    &cache,
+                )?)))
+            }
+            PhysicalOp::Limit { limit, offset } => {
+                if node.inputs.len() != 1 {
+                    return Err(SombraError::Invalid("limit expects single input child"));
+                }
+                let input = self.build_stream(&node.inputs[0], context, cache)?;
+                Ok(Box::new(LimitStream::new(input, *limit, *offset)))
+            }
             PhysicalOp::Distinct => {
                 if node.inputs.len() != 1 {
                     return Err(SombraError::Invalid("distinct expects single input child"));
@@ -424,7 +1063,92 @@ impl Executor {
                     stream,
                 )?))
             }
+            PhysicalPredicate::In { values, .. } => {
+                let mut nodes = Vec::new();
+                let prop_timer = query_profile_timer();
+                for value in values {
+                    // Null never equals a stored value, matching `eval_in`.
+                    if matches!(value, LiteralValue::Null) {
+                        continue;
+                    }
+                    let owned = literal_to_prop_value(value)?;
+                    let mut stream =
+                        self.graph
+                            .property_scan_eq_stream(context.guard(), label, prop, &owned)?;
+                    collect_all(&mut *stream, &mut nodes)?;
+                }
+                record_query_profile_timer(QueryProfileKind::PropIndex, prop_timer);
+                Ok(Box::new(PostingBindingStream::from_nodes(
+                    var.to_owned(),
+                    nodes,
+                )))
+            }
+            PhysicalPredicate::Prefix { prefix, .. } => {
+                let prop_timer = query_profile_timer();
+                let stream =
+                    self.graph
+                        .property_scan_prefix_stream(context.guard(), label, prop, prefix)?;
+                record_query_profile_timer(QueryProfileKind::PropIndex, prop_timer);
+                Ok(Box::new(PostingBindingStream::from_stream(
+                    var.to_owned(),
+                    stream,
+                )?))
+            }
+        }
+    }
+
+    fn build_composite_index_stream(
+        &self,
+        label: LabelId,
+        leading: PropId,
+        preds: &[PhysicalPredicate],
+        var: &str,
+        context: Arc<ReadContext>,
+    ) -> Result<BoxBindingStream> {
+        let mut eq = Vec::with_capacity(preds.len());
+        let mut range = None;
+        for pred in preds {
+            if range.is_some() {
+                return Err(SombraError::Invalid(
+                    "composite index scans allow a range only on the last property",
+                ));
+            }
+            match pred {
+                PhysicalPredicate::Eq { value, .. } => eq.push(literal_to_prop_value(value)?),
+                PhysicalPredicate::Range { lower, upper, .. } => {
+                    range = Some((bound_owned(lower)?, bound_owned(upper)?));
+                }
+                PhysicalPredicate::In { .. } => {
+                    return Err(SombraError::Invalid(
+                        "composite index scans do not support IN predicates",
+                    ))
+                }
+                PhysicalPredicate::Prefix { .. } => {
+                    return Err(SombraError::Invalid(
+                        "composite index scans do not support prefix predicates",
+                    ))
+                }
+            }
         }
+        let bounds = match (&range, preds.last()) {
+            (
+                Some((lower_owned, upper_owned)),
+                Some(PhysicalPredicate::Range { lower, upper, .. }),
+            ) => Some((
+                bound_ref(lower, lower_owned.as_ref()),
+                bound_ref(upper, upper_owned.as_ref()),
+            )),
+            _ => None,
+        };
+        let prop_timer = query_profile_timer();
+        let stream =
+            self.graph
+                .composite_scan_stream(context.guard(), label, leading, &eq, bounds)?;
+        record_query_profile_timer(QueryProfileKind::PropIndex, prop_timer);
+        Ok(Box::new(PostingBindingStream::from_stream(
+            var.to_owned(),
+            stream,
+        )?))
     }
 }
 
@@ -443,13 +1167,17 @@ impl PostingBindingStream {
     fn from_stream(var: String, mut stream: Box<dyn PostingStream + '_>) -> Result<Self> {
         let mut nodes = Vec::new();
         collect_all(&mut *stream, &mut nodes)?;
+        Ok(Self::from_nodes(var, nodes))
+    }
+
+    fn from_nodes(var: String, mut nodes: Vec<NodeId>) -> Self {
         nodes.sort_by_key(|node| node.0);
         nodes.dedup();
-        Ok(Self {
+        Self {
             var,
             nodes,
             index: 0,
-        })
+        }
     }
 }
 
@@ -511,6 +1239,44 @@ impl BindingStream for DistinctStream {
     }
 }
 
+/// Drops the first `offset` rows, then stops pulling its input once
+/// `remaining` rows have been returned.
+struct LimitStream {
+    input: BoxBindingStream,
+    remaining: Option<u64>,
+    offset: u64,
+}
+
+impl LimitStream {
+    fn new(input: BoxBindingStream, limit: Option<u64>, offset: u64) -> Self {
+        Self {
+            input,
+            remaining: limit,
+            offset,
+        }
+    }
+}
+
+impl BindingStream for LimitStream {
+    fn try_next(&mut self) -> Result<Option<BindingRow>> {
+        if self.remaining == Some(0) {
+            return Ok(None);
+        }
+        while self.offset > 0 {
+            if self.input.try_next()?.is_none() {
+                self.remaining = Some(0);
+                return Ok(None);
+            }
+            self.offset -= 1;
+        }
+        let row = self.input.try_next()?;
+        if let (Some(remaining), Some(_)) = (self.remaining.as_mut(), row.as_ref()) {
+            *remaining -= 1;
+        }
+        Ok(row)
+    }
+}
+
 struct UnionStream {
     inputs: Vec<BoxBindingStream>,
     current: usize,
@@ -682,7 +1448,10 @@ impl FilterStream {
             };
             let matches = match &self.eval {
                 FilterEval::Physical(pred) => match pred {
-                    PhysicalPredicate::Eq { var, .. } | PhysicalPredicate::Range { var, .. } => {
+                    PhysicalPredicate::Eq { var, .. }
+                    | PhysicalPredicate::Range { var, .. }
+                    | PhysicalPredicate::In { var, .. }
+                    | PhysicalPredicate::Prefix { var, .. } => {
                         let node_id = row
                             .get(&var.0)
                             .ok_or(SombraError::Invalid("filter variable missing from binding"))?;
@@ -710,10 +1479,17 @@ impl FilterStream {
 
 trait BoolNodeResolver {
     fn resolve(&mut self, var: &Var) -> Result<NodeData>;
+    /// Lists the distinct neighbors of `var` over edges matching `dir` and `ty`.
+    fn neighbors(&mut self, var: &Var, dir: StorageDir, ty: Option<TypeId>) -> Result<Vec<NodeId>>;
+    /// Binds a subquery variable, shadowing any earlier binding for it.
+    fn bind(&mut self, var: &Var, node: NodeId);
+    /// Drops a binding made through [`BoolNodeResolver::bind`].
+    fn unbind(&mut self, var: &Var);
 }
 
 struct ExecutorBoolResolver<'a> {
     row: &'a BindingRow,
+    scoped: BindingRow,
     graph: Arc<Graph>,
     context: Arc<ReadContext>,
     cache: NodeCache,
@@ -729,12 +1505,22 @@ impl<'a> ExecutorBoolResolver<'a> {
     ) -> Self {
         Self {
             row,
+            scoped: BindingRow::default(),
             graph,
             context,
             cache,
             loaded: HashMap::new(),
         }
     }
+
+    fn node_id(&self, var: &Var) -> Result<NodeId> {
+        self.scoped
+            .get(&var.0)
+            .or_else(|| self.row.get(&var.0))
+            .ok_or(SombraError::Invalid(
+                "predicate variable missing from binding",
+            ))
+    }
 }
 
 impl BoolNodeResolver for ExecutorBoolResolver<'_> {
@@ -742,13 +1528,35 @@ impl BoolNodeResolver for ExecutorBoolResolver<'_> {
         if let Some(existing) = self.loaded.get(&var.0) {
             return Ok(existing.clone());
         }
-        let node_id = self.row.get(&var.0).ok_or(SombraError::Invalid(
-            "predicate variable missing from binding",
-        ))?;
+        let node_id = self.node_id(var)?;
         let data = fetch_node_data(&self.graph, &self.context, &self.cache, node_id)?;
         self.loaded.insert(var.0.clone(), data.clone());
         Ok(data)
     }
+
+    fn neighbors(&mut self, var: &Var, dir: StorageDir, ty: Option<TypeId>) -> Result<Vec<NodeId>> {
+        let node_id = self.node_id(var)?;
+        let cursor = self.graph.neighbors(
+            self.context.guard(),
+            node_id,
+            dir,
+            ty,
+            ExpandOpts {
+                distinct_nodes: true,
+            },
+        )?;
+        Ok(cursor.map(|neighbor| neighbor.neighbor).collect())
+    }
+
+    fn bind(&mut self, var: &Var, node: NodeId) {
+        self.scoped.insert(&var.0, node);
+        self.loaded.remove(&var.0);
+    }
+
+    fn unbind(&mut self, var: &Var) {
+        self.scoped.nodes.remove(&var.0);
+        self.loaded.remove(&var.0);
+    }
 }
 
 fn evaluate_bool_expr<R: BoolNodeResolver>(
@@ -838,6 +1646,12 @@ fn evaluate_comparison<R: BoolNodeResolver>(
             let node = resolver.resolve(var)?;
             eval_in(&node, *prop, values, lookup)
         }
+        PhysicalComparison::Prefix {
+            var, prop, prefix, ..
+        } => {
+            let node = resolver.resolve(var)?;
+            Ok(eval_prefix(&node, *prop, prefix))
+        }
         PhysicalComparison::Exists { var, prop, .. } => {
             let node = resolver.resolve(var)?;
             Ok(find_prop(&node, *prop).is_some())
@@ -854,9 +1668,47 @@ fn evaluate_comparison<R: BoolNodeResolver>(
                 .map(|value| !matches!(value, PropValueOwned::Null))
                 .unwrap_or(false))
         }
+        PhysicalComparison::EdgeExists {
+            var,
+            dir,
+            ty,
+            target,
+            label,
+            predicate,
+            ..
+        } => {
+            let neighbors = resolver.neighbors(var, storage_dir(*dir), *ty)?;
+            let result = eval_edge_exists(&neighbors, target, *label, predicate, resolver);
+            resolver.unbind(target);
+            result
+        }
     }
 }
 
+/// Binds each neighbor in turn and stops at the first one that qualifies.
+fn eval_edge_exists<R: BoolNodeResolver>(
+    neighbors: &[NodeId],
+    target: &Var,
+    label: LabelId,
+    predicate: &Option<Box<PhysicalBoolExpr>>,
+    resolver: &mut R,
+) -> Result<bool> {
+    for &neighbor in neighbors {
+        resolver.bind(target, neighbor);
+        if !resolver.resolve(target)?.labels.contains(&label) {
+            continue;
+        }
+        let matches = match predicate {
+            Some(expr) => evaluate_bool_expr(expr, resolver)?,
+            None => true,
+        };
+        if matches {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
 fn eval_eq(node: &NodeData, prop: PropId, literal: &LiteralValue) -> Result<bool> {
     if matches!(literal, LiteralValue::Null) {
         return Ok(find_prop(node, prop)
@@ -909,6 +1761,36 @@ where
     Ok(predicate(ord))
 }
 
+/// Comparison applied by [`node_prop_matches`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum PropComparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+/// Tests one property of `node` against `literal` the way query filters do,
+/// so matchers outside a physical plan agree with the executor on nulls,
+/// missing properties and mixed numeric types.
+pub(crate) fn node_prop_matches(
+    node: &NodeData,
+    prop: PropId,
+    cmp: PropComparison,
+    literal: &LiteralValue,
+) -> Result<bool> {
+    match cmp {
+        PropComparison::Eq => eval_eq(node, prop, literal),
+        PropComparison::Ne => eval_ne(node, prop, literal),
+        PropComparison::Lt => compare_with(node, prop, literal, CompareOrdering::is_lt),
+        PropComparison::Le => compare_with(node, prop, literal, CompareOrdering::is_le),
+        PropComparison::Gt => compare_with(node, prop, literal, CompareOrdering::is_gt),
+        PropComparison::Ge => compare_with(node, prop, literal, CompareOrdering::is_ge),
+    }
+}
+
 fn eval_between(
     node: &NodeData,
     prop: PropId,
@@ -1050,6 +1932,61 @@ impl BindingStream for HashJoinStream {
     }
 }
 
+struct CrossJoinStream {
+    left: BoxBindingStream,
+    right: Vec<BindingRow>,
+    current: Option<BindingRow>,
+    right_idx: usize,
+}
+
+impl CrossJoinStream {
+    fn new(left: BoxBindingStream, right: Vec<BindingRow>) -> Self {
+        Self {
+            left,
+            right,
+            current: None,
+            right_idx: 0,
+        }
+    }
+}
+
+impl BindingStream for CrossJoinStream {
+    fn try_next(&mut self) -> Result<Option<BindingRow>> {
+        if self.right.is_empty() {
+            return Ok(None);
+        }
+        loop {
+            if let Some(left) = &self.current {
+                while self.right_idx < self.right.len() {
+                    let right = &self.right[self.right_idx];
+                    self.right_idx += 1;
+                    if let Some(merged) = merge_rows(left, right) {
+                        return Ok(Some(merged));
+                    }
+                }
+            }
+            let Some(left) = self.left.try_next()? else {
+                return Ok(None);
+            };
+            self.current = Some(left);
+            self.right_idx = 0;
+        }
+    }
+}
+
+/// Splits a top-level projection from the operator tree beneath it.
+fn split_projection(plan: &PhysicalPlan) -> Result<(Option<Vec<ProjectField>>, &PhysicalNode)> {
+    match &plan.root.op {
+        PhysicalOp::Project { fields } => {
+            if plan.root.inputs.len() != 1 {
+                return Err(SombraError::Invalid("project expects single input"));
+            }
+            Ok((Some(fields.clone()), &plan.root.inputs[0]))
+        }
+        _ => Ok((None, &plan.root)),
+    }
+}
+
 fn collect_bindings(stream: &mut dyn BindingStream) -> Result<Vec<BindingRow>> {
     let mut rows = Vec::new();
     while let Some(row) = stream.try_next()? {
@@ -1058,6 +1995,156 @@ fn collect_bindings(stream: &mut dyn BindingStream) -> Result<Vec<BindingRow>> {
     Ok(rows)
 }
 
+/// Stable-sorts `rows` by `keys`, placing missing and null values last
+/// regardless of direction.
+fn sort_bindings(
+    rows: Vec<BindingRow>,
+    keys: &[SortKey],
+    graph: &Arc<Graph>,
+    context: &Arc<ReadContext>,
+    cache: &NodeCache,
+) -> Result<Vec<BindingRow>> {
+    let mut keyed = Vec::with_capacity(rows.len());
+    for row in rows {
+        let values = sort_values(&row, keys, graph, context, cache)?;
+        keyed.push((values, row));
+    }
+    keyed.sort_by(|(left, _), (right, _)| compare_sort_keys(left, right, keys));
+    Ok(keyed.into_iter().map(|(_, row)| row).collect())
+}
+
+/// Keeps the first `n` rows of each `group_by` group as ranked by `keys`,
+/// emitting groups in the order they were first seen.
+///
+/// Each group buffers at most `n` rows, so memory stays proportional to
+/// `groups * n` rather than to the input size.
+fn limit_per_group(
+    input: &mut dyn BindingStream,
+    group_by: &Var,
+    n: u64,
+    keys: &[SortKey],
+    graph: &Arc<Graph>,
+    context: &Arc<ReadContext>,
+    cache: &NodeCache,
+) -> Result<Vec<BindingRow>> {
+    let cap = usize::try_from(n).unwrap_or(usize::MAX);
+    let mut order: Vec<NodeId> = Vec::new();
+    let mut groups: HashMap<NodeId, Vec<(Vec<Option<PropValueOwned>>, BindingRow)>> =
+        HashMap::new();
+    while let Some(row) = input.try_next()? {
+        let group = row.get(&group_by.0).ok_or(SombraError::Invalid(
+            "limit per group variable missing from binding",
+        ))?;
+        let kept = groups.entry(group).or_insert_with(|| {
+            order.push(group);
+            Vec::new()
+        });
+        if cap == 0 {
+            continue;
+        }
+        let values = sort_values(&row, keys, graph, context, cache)?;
+        // Ties go after rows already kept, matching a stable sort.
+        let pos = kept
+            .partition_point(|(existing, _)| compare_sort_keys(existing, &values, keys).is_le());
+        if pos < cap {
+            kept.insert(pos, (values, row));
+            kept.truncate(cap);
+        }
+    }
+    let mut rows = Vec::new();
+    for group in order {
+        if let Some(kept) = groups.remove(&group) {
+            rows.extend(kept.into_iter().map(|(_, row)| row));
+        }
+    }
+    Ok(rows)
+}
+
+/// Reads the value each sort key compares for `row`; `None` marks a missing
+/// or null property.
+fn sort_values(
+    row: &BindingRow,
+    keys: &[SortKey],
+    graph: &Arc<Graph>,
+    context: &Arc<ReadContext>,
+    cache: &NodeCache,
+) -> Result<Vec<Option<PropValueOwned>>> {
+    let mut values = Vec::with_capacity(keys.len());
+    for key in keys {
+        let node_id = row.get(&key.var.0).ok_or(SombraError::Invalid(
+            "order by variable missing from binding",
+        ))?;
+        let value = match key.prop {
+            Some(prop) => {
+                let node = fetch_node_data(graph, context, cache, node_id)?;
+                find_prop(&node, prop)
+                    .filter(|value| !matches!(value, PropValueOwned::Null))
+                    .cloned()
+            }
+            None => Some(PropValueOwned::Int(node_id.0 as i64)),
+        };
+        values.push(value);
+    }
+    Ok(values)
+}
+
+/// Compares two rows' [`sort_values`] key by key.
+fn compare_sort_keys(
+    left: &[Option<PropValueOwned>],
+    right: &[Option<PropValueOwned>],
+    keys: &[SortKey],
+) -> std::cmp::Ordering {
+    for ((a, b), key) in left.iter().zip(right).zip(keys) {
+        let ordering = match (a, b) {
+            (None, None) => std::cmp::Ordering::Equal,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (Some(a), Some(b)) if key.descending => compare_sort_values(a, b).reverse(),
+            (Some(a), Some(b)) => compare_sort_values(a, b),
+        };
+        if ordering.is_ne() {
+            return ordering;
+        }
+    }
+    std::cmp::Ordering::Equal
+}
+
+/// Total order over non-null property values: booleans, then numbers and
+/// temporal values, then strings, then bytes.
+fn compare_sort_values(a: &PropValueOwned, b: &PropValueOwned) -> std::cmp::Ordering {
+    fn rank(value: &PropValueOwned) -> u8 {
+        match value {
+            PropValueOwned::Bool(_) => 0,
+            PropValueOwned::Int(_)
+            | PropValueOwned::Float(_)
+            | PropValueOwned::Date(_)
+            | PropValueOwned::DateTime(_) => 1,
+            PropValueOwned::Str(_) => 2,
+            PropValueOwned::Bytes(_) => 3,
+            PropValueOwned::Null => 4,
+        }
+    }
+    fn number(value: &PropValueOwned) -> Option<f64> {
+        match value {
+            PropValueOwned::Int(v) | PropValueOwned::Date(v) | PropValueOwned::DateTime(v) => {
+                Some(*v as f64)
+            }
+            PropValueOwned::Float(v) => Some(*v),
+            _ => None,
+        }
+    }
+    match (a, b) {
+        (PropValueOwned::Bool(x), PropValueOwned::Bool(y)) => x.cmp(y),
+        (PropValueOwned::Int(x), PropValueOwned::Int(y)) => x.cmp(y),
+        (PropValueOwned::Str(x), PropValueOwned::Str(y)) => x.cmp(y),
+        (PropValueOwned::Bytes(x), PropValueOwned::Bytes(y)) => x.cmp(y),
+        _ => match (number(a), number(b)) {
+            (Some(x), Some(y)) => x.total_cmp(&y),
+            _ => rank(a).cmp(&rank(b)),
+        },
+    }
+}
+
 fn intersect_rows(vars: &[Var], sets: Vec<Vec<BindingRow>>) -> Result<Vec<BindingRow>> {
     if vars.is_empty() {
         return Err(SombraError::Invalid(
@@ -1122,40 +2209,42 @@ fn fetch_node_data(
     Ok(data)
 }
 
+#[allow(clippy::too_many_arguments)]
 fn apply_projection(
     binding: &BindingRow,
+    ordinal: u64,
     fields: &[ProjectField],
     graph: &Arc<Graph>,
     context: &Arc<ReadContext>,
     cache: &NodeCache,
     metadata: &Arc<dyn MetadataProvider>,
     prop_names: &PropNameCache,
+    resolve_names: bool,
 ) -> Result<Row> {
     let mut row = Row::new();
     for field in fields {
+        let key = field.column_name();
         match field {
-            ProjectField::Var { var, alias } => {
+            ProjectField::Var { var, .. } => {
                 let Some(node) = binding.get(&var.0) else {
                     return Err(SombraError::Invalid("projection variable missing"));
                 };
                 let data = fetch_node_data(graph, context, cache, node)?;
                 let mut props = BTreeMap::new();
                 for (prop_id, prop_value) in &data.props {
-                    let name = resolve_prop_name(metadata, prop_names, *prop_id)?;
+                    let name = if resolve_names {
+                        resolve_prop_name(metadata, prop_names, *prop_id)?
+                    } else {
+                        prop_id.0.to_string()
+                    };
                     props.insert(name, prop_value_to_exec_value(prop_value));
                 }
-                let key = alias.clone().unwrap_or_else(|| var.0.clone());
                 let mut node_obj = BTreeMap::new();
                 node_obj.insert("_id".into(), Value::NodeId(node));
                 node_obj.insert("props".into(), Value::Object(props));
                 row.insert(key, Value::Object(node_obj));
             }
-            ProjectField::Prop {
-                var,
-                prop,
-                prop_name,
-                alias,
-            } => {
+            ProjectField::Prop { var, prop, .. } => {
                 let node_id = binding
                     .get(&var.0)
                     .ok_or(SombraError::Invalid("projection variable missing"))?;
@@ -1163,14 +2252,131 @@ fn apply_projection(
                 let value = find_prop(&data, *prop)
                     .map(prop_value_to_exec_value)
                     .unwrap_or(Value::Null);
-                let key = alias.clone().unwrap_or_else(|| prop_name.clone());
                 row.insert(key, value);
             }
+            ProjectField::RunningCount { .. } => {
+                let count = i64::try_from(ordinal)
+                    .map_err(|_| SombraError::Invalid("running count overflow"))?;
+                row.insert(key, Value::Int(count));
+            }
+            ProjectField::Path { vars, .. } => {
+                let ids = vars
+                    .iter()
+                    .map(|var| {
+                        binding
+                            .get(&var.0)
+                            .map(Value::NodeId)
+                            .ok_or(SombraError::Invalid("projection variable missing"))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                row.insert(key, Value::List(ids));
+            }
+            ProjectField::Expr { expr, .. } => {
+                let value = expr.evaluate(&mut |operand| {
+                    let node_id = binding
+                        .get(&operand.var.0)
+                        .ok_or(SombraError::Invalid("projection variable missing"))?;
+                    let data = fetch_node_data(graph, context, cache, node_id)?;
+                    Ok(find_prop(&data, operand.prop)
+                        .map(prop_value_to_exec_value)
+                        .unwrap_or(Value::Null))
+                })?;
+                row.insert(key, value);
+            }
+            ProjectField::Aggregate { .. } => {
+                return Err(SombraError::Invalid(
+                    "aggregate projection evaluated per row",
+                ));
+            }
         }
     }
     Ok(row)
 }
 
+/// Running state for one aggregate column.
+struct AggAccumulator {
+    func: AggFunc,
+    count: i64,
+    int_sum: i64,
+    float_sum: f64,
+    saw_float: bool,
+    best: Option<PropValueOwned>,
+}
+
+impl AggAccumulator {
+    fn new(func: AggFunc) -> Self {
+        Self {
+            func,
+            count: 0,
+            int_sum: 0,
+            float_sum: 0.0,
+            saw_float: false,
+            best: None,
+        }
+    }
+
+    /// Folds one property value in; nulls are ignored like missing values.
+    fn update(&mut self, value: &PropValueOwned) -> Result<()> {
+        if matches!(value, PropValueOwned::Null) {
+            return Ok(());
+        }
+        self.count += 1;
+        match self.func {
+            AggFunc::Count => {}
+            AggFunc::Sum | AggFunc::Avg => match value {
+                PropValueOwned::Int(v) => {
+                    self.int_sum = self
+                        .int_sum
+                        .checked_add(*v)
+                        .ok_or(SombraError::Invalid("aggregate sum overflow"))?;
+                }
+                PropValueOwned::Float(v) => {
+                    self.saw_float = true;
+                    self.float_sum += v;
+                }
+                _ => {
+                    return Err(SombraError::InvalidOwned(format!(
+                        "{}() requires numeric property values",
+                        self.func.name()
+                    )))
+                }
+            },
+            AggFunc::Min | AggFunc::Max => {
+                let wanted = if self.func == AggFunc::Min {
+                    std::cmp::Ordering::Less
+                } else {
+                    std::cmp::Ordering::Greater
+                };
+                let replace = match &self.best {
+                    Some(best) => compare_sort_values(value, best) == wanted,
+                    None => true,
+                };
+                if replace {
+                    self.best = Some(value.clone());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Value> {
+        Ok(match self.func {
+            AggFunc::Count => Value::Int(self.count),
+            AggFunc::Sum if self.saw_float => Value::Float(self.int_sum as f64 + self.float_sum),
+            AggFunc::Sum => Value::Int(self.int_sum),
+            AggFunc::Avg if self.count == 0 => Value::Null,
+            AggFunc::Avg => {
+                Value::Float((self.int_sum as f64 + self.float_sum) / self.count as f64)
+            }
+            AggFunc::Min | AggFunc::Max => self
+                .best
+                .as_ref()
+                .map(prop_value_to_exec_value)
+                .unwrap_or(Value::Null),
+        })
+    }
+}
+
 fn resolve_prop_name(
     metadata: &Arc<dyn MetadataProvider>,
     cache: &PropNameCache,
@@ -1267,9 +2473,23 @@ fn evaluate_predicate(predicate: &PhysicalPredicate, node: &NodeData) -> Result<
             };
             Ok(lower_cmp && upper_cmp)
         }
+        PhysicalPredicate::In {
+            prop,
+            values,
+            lookup,
+            ..
+        } => eval_in(node, *prop, values, lookup),
+        PhysicalPredicate::Prefix { prop, prefix, .. } => Ok(eval_prefix(node, *prop, prefix)),
     }
 }
 
+/// Checks whether the node holds a string property starting with `prefix`.
+///
+/// Matching is case-sensitive, like the index prefix scans it backs up.
+fn eval_prefix(node: &NodeData, prop: PropId, prefix: &str) -> bool {
+    matches!(find_prop(node, prop), Some(PropValueOwned::Str(s)) if s.starts_with(prefix))
+}
+
 fn find_prop(node: &NodeData, prop: PropId) -> Option<&PropValueOwned> {
     node.props
         .iter()
@@ -1443,6 +2663,7 @@ mod tests {
     use crate::types::{LabelId, PropId, TypeId};
     use std::collections::HashMap;
     use std::ops::Bound;
+    use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
     use std::sync::Arc;
     use tempfile::{tempdir, TempDir};
 
@@ -1694,6 +2915,38 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn limit_stream_skips_offset_and_stops_pulling() -> Result<()> {
+        let rows = (1..=10)
+            .map(|id| BindingRow::from_binding("a", NodeId(id)))
+            .collect();
+        let pulled = Arc::new(AtomicUsize::new(0));
+        let input = CountingStream {
+            inner: MockStream::new(rows),
+            pulled: Arc::clone(&pulled),
+        };
+        let mut stream = LimitStream::new(Box::new(input), Some(3), 2);
+        let mut seen = Vec::new();
+        while let Some(row) = stream.try_next()? {
+            seen.push(row.get("a").expect("binding present"));
+        }
+        assert_eq!(seen, vec![NodeId(3), NodeId(4), NodeId(5)]);
+        assert_eq!(pulled.load(AtomicOrdering::Relaxed), 5);
+        Ok(())
+    }
+
+    struct CountingStream {
+        inner: MockStream,
+        pulled: Arc<AtomicUsize>,
+    }
+
+    impl BindingStream for CountingStream {
+        fn try_next(&mut self) -> Result<Option<BindingRow>> {
+            self.pulled.fetch_add(1, AtomicOrdering::Relaxed);
+            self.inner.try_next()
+        }
+    }
+
     struct MockStream {
         rows: Vec<BindingRow>,
         idx: usize,
@@ -1744,6 +2997,19 @@ mod tests {
                 .cloned()
                 .ok_or(SombraError::Invalid("missing test binding"))
         }
+
+        fn neighbors(
+            &mut self,
+            _var: &Var,
+            _dir: StorageDir,
+            _ty: Option<TypeId>,
+        ) -> Result<Vec<NodeId>> {
+            Err(SombraError::Invalid("test resolver has no edges"))
+        }
+
+        fn bind(&mut self, _var: &Var, _node: NodeId) {}
+
+        fn unbind(&mut self, _var: &Var) {}
     }
 
     fn eval_cmp_with_props(cmp: PhysicalComparison, props: Vec<(PropId, PropValueOwned)>) -> bool {
@@ -0,0 +1,658 @@
+//! Scalar expressions computed per row by `expr` projections.
+//!
+//! The language is deliberately small: literals, property references,
+//! arithmetic, string concatenation, and comparisons. There are no function
+//! calls, so evaluating an expression can neither touch storage beyond the
+//! referenced properties nor run for longer than its size.
+//!
+//! ```text
+//! expr       := comparison
+//! comparison := additive (("=" | "==" | "!=" | "<>" | "<" | "<=" | ">" | ">=") additive)?
+//! additive   := term (("+" | "-") term)*
+//! term       := unary (("*" | "/" | "%") unary)*
+//! unary      := "-" unary | primary
+//! primary    := INT | FLOAT | STRING | "true" | "false" | "null"
+//!             | IDENT ("." IDENT)? | "(" expr ")"
+//! STRING     := '...' | "..."   (backslash escapes the quote and itself)
+//! ```
+//!
+//! A bare `IDENT` names a property of the query's only match variable;
+//! `var.prop` names a property of a specific variable. Evaluation follows
+//! these rules:
+//!
+//! - Null operands, including missing properties, make the result null.
+//! - `+`, `-`, `*`, `/`, and `%` on two ints yield an int (division
+//!   truncates and overflow is an error); any float operand yields a float.
+//!   Dividing by zero yields null.
+//! - `+` on two strings concatenates them.
+//! - Comparisons yield a bool and require both sides to have the same kind,
+//!   except that ints and floats compare numerically.
+//!
+//! Any other operand combination fails the query with a type error.
+
+use std::cmp::Ordering;
+use std::fmt;
+
+use crate::query::executor::Value;
+use crate::types::{Result, SombraError};
+
+/// Longest expression source accepted, in bytes.
+pub const MAX_EXPR_LEN: usize = 1024;
+/// Deepest nesting of operators and parentheses accepted.
+pub const MAX_EXPR_DEPTH: usize = 32;
+
+/// Literal operand written in an expression.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ExprLiteral {
+    /// `null`.
+    Null,
+    /// `true` or `false`.
+    Bool(bool),
+    /// Integer literal.
+    Int(i64),
+    /// Floating-point literal.
+    Float(f64),
+    /// Quoted string literal.
+    String(String),
+}
+
+/// Binary operator joining two operands.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum BinaryOp {
+    /// Addition or string concatenation.
+    Add,
+    /// Subtraction.
+    Sub,
+    /// Multiplication.
+    Mul,
+    /// Division.
+    Div,
+    /// Remainder.
+    Rem,
+    /// Equality.
+    Eq,
+    /// Inequality.
+    Ne,
+    /// Less than.
+    Lt,
+    /// Less than or equal.
+    Le,
+    /// Greater than.
+    Gt,
+    /// Greater than or equal.
+    Ge,
+}
+
+impl BinaryOp {
+    /// Returns the operator as written in expression source.
+    pub fn symbol(self) -> &'static str {
+        match self {
+            BinaryOp::Add => "+",
+            BinaryOp::Sub => "-",
+            BinaryOp::Mul => "*",
+            BinaryOp::Div => "/",
+            BinaryOp::Rem => "%",
+            BinaryOp::Eq => "=",
+            BinaryOp::Ne => "!=",
+            BinaryOp::Lt => "<",
+            BinaryOp::Le => "<=",
+            BinaryOp::Gt => ">",
+            BinaryOp::Ge => ">=",
+        }
+    }
+}
+
+/// Property reference as written in expression source.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PropPath {
+    /// Variable qualifying the property; `None` for a bare name.
+    pub var: Option<String>,
+    /// Property name.
+    pub prop: String,
+}
+
+/// Expression tree whose property operands are of type `P`.
+///
+/// Parsing yields [`PropPath`] operands; later stages swap them for resolved
+/// references with [`ScalarExpr::try_map_props`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum ScalarExpr<P> {
+    /// Literal operand.
+    Literal(ExprLiteral),
+    /// Property of a bound variable.
+    Prop(P),
+    /// Arithmetic negation.
+    Neg(Box<ScalarExpr<P>>),
+    /// Binary operation.
+    Binary {
+        /// Operator applied.
+        op: BinaryOp,
+        /// Left operand.
+        lhs: Box<ScalarExpr<P>>,
+        /// Right operand.
+        rhs: Box<ScalarExpr<P>>,
+    },
+}
+
+impl<P> ScalarExpr<P> {
+    /// Rebuilds the tree with every property operand converted by `f`.
+    pub fn try_map_props<Q, E>(
+        self,
+        f: &mut impl FnMut(P) -> std::result::Result<Q, E>,
+    ) -> std::result::Result<ScalarExpr<Q>, E> {
+        Ok(match self {
+            ScalarExpr::Literal(lit) => ScalarExpr::Literal(lit),
+            ScalarExpr::Prop(prop) => ScalarExpr::Prop(f(prop)?),
+            ScalarExpr::Neg(inner) => ScalarExpr::Neg(Box::new(inner.try_map_props(f)?)),
+            ScalarExpr::Binary { op, lhs, rhs } => ScalarExpr::Binary {
+                op,
+                lhs: Box::new(lhs.try_map_props(f)?),
+                rhs: Box::new(rhs.try_map_props(f)?),
+            },
+        })
+    }
+
+    /// Evaluates the expression, reading property operands through `resolve`.
+    pub fn evaluate(&self, resolve: &mut impl FnMut(&P) -> Result<Value>) -> Result<Value> {
+        match self {
+            ScalarExpr::Literal(lit) => Ok(literal_value(lit)),
+            ScalarExpr::Prop(prop) => resolve(prop),
+            ScalarExpr::Neg(inner) => match inner.evaluate(resolve)? {
+                Value::Null => Ok(Value::Null),
+                Value::Int(v) => v
+                    .checked_neg()
+                    .map(Value::Int)
+                    .ok_or(SombraError::Invalid("expression integer overflow")),
+                Value::Float(v) => Ok(Value::Float(-v)),
+                other => Err(type_error("negate", &other, None)),
+            },
+            ScalarExpr::Binary { op, lhs, rhs } => {
+                let lhs = lhs.evaluate(resolve)?;
+                let rhs = rhs.evaluate(resolve)?;
+                apply_binary(*op, lhs, rhs)
+            }
+        }
+    }
+}
+
+impl<P: fmt::Display> fmt::Display for ScalarExpr<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScalarExpr::Literal(ExprLiteral::Null) => write!(f, "null"),
+            ScalarExpr::Literal(ExprLiteral::Bool(v)) => write!(f, "{v}"),
+            ScalarExpr::Literal(ExprLiteral::Int(v)) => write!(f, "{v}"),
+            ScalarExpr::Literal(ExprLiteral::Float(v)) => write!(f, "{v:?}"),
+            ScalarExpr::Literal(ExprLiteral::String(v)) => write!(f, "{v:?}"),
+            ScalarExpr::Prop(prop) => write!(f, "{prop}"),
+            ScalarExpr::Neg(inner) => write!(f, "-{inner}"),
+            ScalarExpr::Binary { op, lhs, rhs } => write!(f, "({lhs} {} {rhs})", op.symbol()),
+        }
+    }
+}
+
+fn literal_value(lit: &ExprLiteral) -> Value {
+    match lit {
+        ExprLiteral::Null => Value::Null,
+        ExprLiteral::Bool(v) => Value::Bool(*v),
+        ExprLiteral::Int(v) => Value::Int(*v),
+        ExprLiteral::Float(v) => Value::Float(*v),
+        ExprLiteral::String(v) => Value::String(v.clone()),
+    }
+}
+
+fn kind_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "bool",
+        Value::Int(_) => "int",
+        Value::Float(_) => "float",
+        Value::String(_) => "string",
+        Value::Bytes(_) => "bytes",
+        Value::Date(_) => "date",
+        Value::DateTime(_) => "datetime",
+        Value::NodeId(_) => "node",
+        Value::Object(_) => "object",
+        Value::List(_) => "list",
+    }
+}
+
+fn type_error(op: &str, lhs: &Value, rhs: Option<&Value>) -> SombraError {
+    let message = match rhs {
+        Some(rhs) => format!(
+            "cannot apply '{op}' to {} and {}",
+            kind_name(lhs),
+            kind_name(rhs)
+        ),
+        None => format!("cannot {op} {}", kind_name(lhs)),
+    };
+    SombraError::InvalidOwned(message)
+}
+
+fn apply_binary(op: BinaryOp, lhs: Value, rhs: Value) -> Result<Value> {
+    if matches!(lhs, Value::Null) || matches!(rhs, Value::Null) {
+        return Ok(Value::Null);
+    }
+    match op {
+        BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div | BinaryOp::Rem => {
+            arithmetic(op, lhs, rhs)
+        }
+        BinaryOp::Eq | BinaryOp::Ne | BinaryOp::Lt | BinaryOp::Le | BinaryOp::Gt | BinaryOp::Ge => {
+            let ordering =
+                compare(&lhs, &rhs).ok_or_else(|| type_error(op.symbol(), &lhs, Some(&rhs)))?;
+            let holds = match op {
+                BinaryOp::Eq => ordering == Ordering::Equal,
+                BinaryOp::Ne => ordering != Ordering::Equal,
+                BinaryOp::Lt => ordering == Ordering::Less,
+                BinaryOp::Le => ordering != Ordering::Greater,
+                BinaryOp::Gt => ordering == Ordering::Greater,
+                _ => ordering != Ordering::Less,
+            };
+            Ok(Value::Bool(holds))
+        }
+    }
+}
+
+fn arithmetic(op: BinaryOp, lhs: Value, rhs: Value) -> Result<Value> {
+    match (&lhs, &rhs) {
+        (Value::String(a), Value::String(b)) if op == BinaryOp::Add => {
+            Ok(Value::String(format!("{a}{b}")))
+        }
+        (Value::Int(a), Value::Int(b)) => {
+            let (a, b) = (*a, *b);
+            let result = match op {
+                BinaryOp::Add => a.checked_add(b),
+                BinaryOp::Sub => a.checked_sub(b),
+                BinaryOp::Mul => a.checked_mul(b),
+                BinaryOp::Div | BinaryOp::Rem if b == 0 => return Ok(Value::Null),
+                BinaryOp::Div => a.checked_div(b),
+                _ => a.checked_rem(b),
+            };
+            result
+                .map(Value::Int)
+                .ok_or(SombraError::Invalid("expression integer overflow"))
+        }
+        _ => {
+            let (Some(a), Some(b)) = (as_float(&lhs), as_float(&rhs)) else {
+                return Err(type_error(op.symbol(), &lhs, Some(&rhs)));
+            };
+            if matches!(op, BinaryOp::Div | BinaryOp::Rem) && b == 0.0 {
+                return Ok(Value::Null);
+            }
+            Ok(Value::Float(match op {
+                BinaryOp::Add => a + b,
+                BinaryOp::Sub => a - b,
+                BinaryOp::Mul => a * b,
+                BinaryOp::Div => a / b,
+                _ => a % b,
+            }))
+        }
+    }
+}
+
+fn as_float(value: &Value) -> Option<f64> {
+    match value {
+        Value::Int(v) => Some(*v as f64),
+        Value::Float(v) => Some(*v),
+        _ => None,
+    }
+}
+
+fn compare(lhs: &Value, rhs: &Value) -> Option<Ordering> {
+    match (lhs, rhs) {
+        (Value::Int(a), Value::Int(b)) => Some(a.cmp(b)),
+        (Value::Bool(a), Value::Bool(b)) => Some(a.cmp(b)),
+        (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+        (Value::Bytes(a), Value::Bytes(b)) => Some(a.cmp(b)),
+        (Value::Date(a), Value::Date(b)) | (Value::DateTime(a), Value::DateTime(b)) => {
+            Some(a.cmp(b))
+        }
+        _ => as_float(lhs)?.partial_cmp(&as_float(rhs)?),
+    }
+}
+
+/// Parses expression source into a tree of unresolved property references.
+///
+/// Errors describe the first unsupported construct, such as a function call
+/// or a stray token.
+pub fn parse(source: &str) -> std::result::Result<ScalarExpr<PropPath>, String> {
+    if source.len() > MAX_EXPR_LEN {
+        return Err(format!("expression exceeds {MAX_EXPR_LEN} bytes"));
+    }
+    let tokens = tokenize(source)?;
+    let mut parser = Parser { tokens, pos: 0 };
+    let expr = parser.expr(0)?;
+    match parser.peek() {
+        None => Ok(expr),
+        Some(token) => Err(format!("unexpected {token}")),
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Int(i64),
+    Float(f64),
+    Str(String),
+    Ident(String),
+    Op(&'static str),
+    LParen,
+    RParen,
+    Dot,
+    Comma,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Int(v) => write!(f, "number {v}"),
+            Token::Float(v) => write!(f, "number {v}"),
+            Token::Str(v) => write!(f, "string {v:?}"),
+            Token::Ident(v) => write!(f, "identifier '{v}'"),
+            Token::Op(op) => write!(f, "'{op}'"),
+            Token::LParen => write!(f, "'('"),
+            Token::RParen => write!(f, "')'"),
+            Token::Dot => write!(f, "'.'"),
+            Token::Comma => write!(f, "','"),
+        }
+    }
+}
+
+fn tokenize(source: &str) -> std::result::Result<Vec<Token>, String> {
+    const OPERATORS: [&str; 13] = [
+        "==", "!=", "<>", "<=", ">=", "<", ">", "=", "+", "-", "*", "/", "%",
+    ];
+    let mut tokens = Vec::new();
+    let mut chars = source.char_indices().peekable();
+    while let Some(&(start, ch)) = chars.peek() {
+        if ch.is_whitespace() {
+            chars.next();
+        } else if ch.is_ascii_digit() {
+            let mut end = start;
+            let mut is_float = false;
+            while let Some(&(idx, c)) = chars.peek() {
+                let exponent_sign = matches!(c, '+' | '-')
+                    && matches!(source[..idx].chars().last(), Some('e' | 'E'));
+                if c.is_ascii_digit() || exponent_sign {
+                    end = idx + c.len_utf8();
+                } else if c == '.' || c == 'e' || c == 'E' {
+                    is_float = true;
+                    end = idx + 1;
+                } else {
+                    break;
+                }
+                chars.next();
+            }
+            let text = &source[start..end];
+            tokens.push(if is_float {
+                Token::Float(
+                    text.parse()
+                        .map_err(|_| format!("invalid number '{text}'"))?,
+                )
+            } else {
+                Token::Int(
+                    text.parse()
+                        .map_err(|_| format!("integer '{text}' is out of range"))?,
+                )
+            });
+        } else if ch == '\'' || ch == '"' {
+            chars.next();
+            let mut text = String::new();
+            let mut closed = false;
+            while let Some((_, c)) = chars.next() {
+                match c {
+                    '\\' => match chars.next() {
+                        Some((_, escaped)) => text.push(escaped),
+                        None => break,
+                    },
+                    c if c == ch => {
+                        closed = true;
+                        break;
+                    }
+                    c => text.push(c),
+                }
+            }
+            if !closed {
+                return Err("unterminated string literal".into());
+            }
+            tokens.push(Token::Str(text));
+        } else if ch.is_alphabetic() || ch == '_' {
+            let mut end = start;
+            while let Some(&(idx, c)) = chars.peek() {
+                if !(c.is_alphanumeric() || c == '_') {
+                    break;
+                }
+                end = idx + c.len_utf8();
+                chars.next();
+            }
+            tokens.push(Token::Ident(source[start..end].to_owned()));
+        } else {
+            let rest = &source[start..];
+            let single = match ch {
+                '(' => Some(Token::LParen),
+                ')' => Some(Token::RParen),
+                '.' => Some(Token::Dot),
+                ',' => Some(Token::Comma),
+                _ => None,
+            };
+            let token = match single {
+                Some(token) => token,
+                None => {
+                    let op = OPERATORS
+                        .iter()
+                        .find(|op| rest.starts_with(**op))
+                        .ok_or_else(|| format!("unsupported character '{ch}'"))?;
+                    Token::Op(op)
+                }
+            };
+            let len = match &token {
+                Token::Op(op) => op.len(),
+                _ => 1,
+            };
+            for _ in 0..len {
+                chars.next();
+            }
+            tokens.push(token);
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat_op(&mut self, ops: &[&str]) -> Option<&'static str> {
+        match self.peek() {
+            Some(Token::Op(op)) if ops.contains(op) => {
+                let op = *op;
+                self.pos += 1;
+                Some(op)
+            }
+            _ => None,
+        }
+    }
+
+    fn expr(&mut self, depth: usize) -> std::result::Result<ScalarExpr<PropPath>, String> {
+        if depth > MAX_EXPR_DEPTH {
+            return Err(format!(
+                "expression nests deeper than {MAX_EXPR_DEPTH} levels"
+            ));
+        }
+        let lhs = self.additive(depth)?;
+        let Some(op) = self.eat_op(&["==", "!=", "<>", "<=", ">=", "<", ">", "="]) else {
+            return Ok(lhs);
+        };
+        let op = match op {
+            "=" | "==" => BinaryOp::Eq,
+            "!=" | "<>" => BinaryOp::Ne,
+            "<" => BinaryOp::Lt,
+            "<=" => BinaryOp::Le,
+            ">" => BinaryOp::Gt,
+            _ => BinaryOp::Ge,
+        };
+        let rhs = self.additive(depth)?;
+        Ok(binary(op, lhs, rhs))
+    }
+
+    fn additive(&mut self, depth: usize) -> std::result::Result<ScalarExpr<PropPath>, String> {
+        let mut lhs = self.term(depth)?;
+        while let Some(op) = self.eat_op(&["+", "-"]) {
+            let op = if op == "+" {
+                BinaryOp::Add
+            } else {
+                BinaryOp::Sub
+            };
+            let rhs = self.term(depth)?;
+            lhs = binary(op, lhs, rhs);
+        }
+        Ok(lhs)
+    }
+
+    fn term(&mut self, depth: usize) -> std::result::Result<ScalarExpr<PropPath>, String> {
+        let mut lhs = self.unary(depth)?;
+        while let Some(op) = self.eat_op(&["*", "/", "%"]) {
+            let op = match op {
+                "*" => BinaryOp::Mul,
+                "/" => BinaryOp::Div,
+                _ => BinaryOp::Rem,
+            };
+            let rhs = self.unary(depth)?;
+            lhs = binary(op, lhs, rhs);
+        }
+        Ok(lhs)
+    }
+
+    fn unary(&mut self, depth: usize) -> std::result::Result<ScalarExpr<PropPath>, String> {
+        if self.eat_op(&["-"]).is_some() {
+            if depth >= MAX_EXPR_DEPTH {
+                return Err(format!(
+                    "expression nests deeper than {MAX_EXPR_DEPTH} levels"
+                ));
+            }
+            return Ok(ScalarExpr::Neg(Box::new(self.unary(depth + 1)?)));
+        }
+        self.primary(depth)
+    }
+
+    fn primary(&mut self, depth: usize) -> std::result::Result<ScalarExpr<PropPath>, String> {
+        match self.next() {
+            Some(Token::Int(v)) => Ok(ScalarExpr::Literal(ExprLiteral::Int(v))),
+            Some(Token::Float(v)) => Ok(ScalarExpr::Literal(ExprLiteral::Float(v))),
+            Some(Token::Str(v)) => Ok(ScalarExpr::Literal(ExprLiteral::String(v))),
+            Some(Token::LParen) => {
+                let inner = self.expr(depth + 1)?;
+                match self.next() {
+                    Some(Token::RParen) => Ok(inner),
+                    Some(token) => Err(format!("expected ')' but found {token}")),
+                    None => Err("expected ')' but the expression ended".into()),
+                }
+            }
+            Some(Token::Ident(name)) => match name.as_str() {
+                "true" => Ok(ScalarExpr::Literal(ExprLiteral::Bool(true))),
+                "false" => Ok(ScalarExpr::Literal(ExprLiteral::Bool(false))),
+                "null" => Ok(ScalarExpr::Literal(ExprLiteral::Null)),
+                _ => match self.peek() {
+                    Some(Token::LParen) => Err(format!("unknown function '{name}'")),
+                    Some(Token::Dot) => {
+                        self.pos += 1;
+                        match self.next() {
+                            Some(Token::Ident(prop)) => Ok(ScalarExpr::Prop(PropPath {
+                                var: Some(name),
+                                prop,
+                            })),
+                            Some(token) => Err(format!(
+                                "expected a property name after '{name}.' but found {token}"
+                            )),
+                            None => Err(format!("expected a property name after '{name}.'")),
+                        }
+                    }
+                    _ => Ok(ScalarExpr::Prop(PropPath {
+                        var: None,
+                        prop: name,
+                    })),
+                },
+            },
+            Some(token) => Err(format!("unexpected {token}")),
+            None => Err("expression ended unexpectedly".into()),
+        }
+    }
+}
+
+fn binary(
+    op: BinaryOp,
+    lhs: ScalarExpr<PropPath>,
+    rhs: ScalarExpr<PropPath>,
+) -> ScalarExpr<PropPath> {
+    ScalarExpr::Binary {
+        op,
+        lhs: Box::new(lhs),
+        rhs: Box::new(rhs),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(source: &str) -> Result<Value> {
+        let expr = parse(source).expect("parse");
+        expr.evaluate(&mut |path: &PropPath| {
+            Ok(match path.prop.as_str() {
+                "age" => Value::Int(36),
+                "name" => Value::String("ada".into()),
+                _ => Value::Null,
+            })
+        })
+    }
+
+    #[test]
+    fn parses_with_arithmetic_precedence() {
+        let expr = parse("1 + u.age * -2 = (3)").unwrap();
+        let rendered = expr
+            .try_map_props(&mut |path: PropPath| {
+                Ok::<_, ()>(format!("{}.{}", path.var.unwrap_or_default(), path.prop))
+            })
+            .unwrap()
+            .to_string();
+        assert_eq!(rendered, "((1 + (u.age * -2)) = 3)");
+    }
+
+    #[test]
+    fn rejects_unsupported_syntax() {
+        assert_eq!(
+            parse("lower(name)").unwrap_err(),
+            "unknown function 'lower'"
+        );
+        assert_eq!(parse("age +").unwrap_err(), "expression ended unexpectedly");
+        assert_eq!(parse("age ; 1").unwrap_err(), "unsupported character ';'");
+        assert_eq!(parse("'open").unwrap_err(), "unterminated string literal");
+        assert_eq!(parse("age 1").unwrap_err(), "unexpected number 1");
+        let nested = format!("{}1{}", "(".repeat(40), ")".repeat(40));
+        assert!(parse(&nested).unwrap_err().contains("nests deeper"));
+    }
+
+    #[test]
+    fn evaluates_with_null_and_numeric_rules() {
+        assert!(matches!(eval("age * 2").unwrap(), Value::Int(72)));
+        assert!(matches!(eval("age / 8").unwrap(), Value::Int(4)));
+        assert!(matches!(eval("age / 8.0").unwrap(), Value::Float(v) if v == 4.5));
+        assert!(matches!(eval("age % 0").unwrap(), Value::Null));
+        assert!(matches!(eval("missing + 1").unwrap(), Value::Null));
+        assert!(matches!(eval("name + '!'").unwrap(), Value::String(v) if v == "ada!"));
+        assert!(matches!(eval("age > 35.5").unwrap(), Value::Bool(true)));
+        assert!(matches!(eval("name <> 'bob'").unwrap(), Value::Bool(true)));
+        assert!(eval("name - 1").is_err());
+        assert!(eval("9223372036854775807 + 1").is_err());
+    }
+}
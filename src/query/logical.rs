@@ -2,7 +2,7 @@
 //! optimisation.
 
 use crate::query::{
-    analyze::{AnalyzedExpr, AnalyzedProjection, EdgeTypeRef, PropRef},
+    analyze::{AnalyzedExpr, AnalyzedOrderKey, AnalyzedProjection, EdgeTypeRef, PropRef, VarId},
     ast::{EdgeDirection, Var},
     value::Value,
 };
@@ -74,6 +74,22 @@ pub enum LogicalOp {
         /// Variable name to bind matched nodes.
         as_var: Var,
     },
+    /// Scans nodes using a composite index over several properties.
+    CompositeIndexScan {
+        /// Optional label to scan within.
+        label: Option<String>,
+        /// Resolved label identifier.
+        label_id: LabelId,
+        /// Covered properties in index key order.
+        props: Vec<PropRef>,
+        /// One predicate per covered property: equalities, then at most one
+        /// trailing range.
+        predicates: Vec<PropPredicate>,
+        /// Estimated combined selectivity.
+        selectivity: f64,
+        /// Variable name to bind matched nodes.
+        as_var: Var,
+    },
     /// Expands from nodes to their neighbors.
     Expand {
         /// Variable representing source nodes.
@@ -113,6 +129,8 @@ pub enum LogicalOp {
         /// Variable from the right stream to join on.
         right: Var,
     },
+    /// Pairs every row of the first input with every row of the second.
+    CrossJoin,
     /// Projects specific fields into the output.
     Project {
         /// Fields to include in the projection.
@@ -120,6 +138,27 @@ pub enum LogicalOp {
     },
     /// Removes duplicate rows from the result stream.
     Distinct,
+    /// Sorts rows by the given keys, keeping ties in input order.
+    OrderBy {
+        /// Sort keys in priority order.
+        keys: Vec<AnalyzedOrderKey>,
+    },
+    /// Keeps the first `n` rows of each `group_by` group, ranked by `keys`.
+    LimitPerGroup {
+        /// Binding whose node identifies a row's group.
+        group_by: VarId,
+        /// Maximum number of rows kept per group.
+        n: u64,
+        /// Sort keys ranking rows within a group.
+        keys: Vec<AnalyzedOrderKey>,
+    },
+    /// Skips the first `offset` rows and stops after `limit` more.
+    Limit {
+        /// Maximum number of rows to pass through, if bounded.
+        limit: Option<u64>,
+        /// Number of leading rows to drop.
+        offset: u64,
+    },
     /// Filters rows using a boolean predicate tree.
     BoolFilter {
         /// Predicate to evaluate.
@@ -150,4 +189,22 @@ pub enum PropPredicate {
         /// Upper bound for the range (inclusive or exclusive).
         upper: Bound<Value>,
     },
+    /// Membership predicate answered with one point lookup per value.
+    In {
+        /// Variable to test the property on.
+        var: Var,
+        /// Property name to check.
+        prop: PropRef,
+        /// Values the property may equal.
+        values: Vec<Value>,
+    },
+    /// String prefix predicate answered with an ordered index range.
+    Prefix {
+        /// Variable to test the property on.
+        var: Var,
+        /// Property name to check.
+        prop: PropRef,
+        /// Prefix the string value must start with.
+        prefix: String,
+    },
 }
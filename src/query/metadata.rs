@@ -9,7 +9,7 @@ use std::sync::{Arc, Mutex};
 
 use crate::primitives::pager::PageStore;
 use crate::storage::catalog::{Dict, DictOptions};
-use crate::storage::index::{IndexCatalog, IndexDef, IndexKind, TypeTag};
+use crate::storage::index::{IndexCatalog, IndexDef, IndexKind, IndexState, TypeTag};
 use crate::storage::{Graph, PropStats};
 use crate::types::{LabelId, PageId, PropId, Result, SombraError, StrId, TypeId};
 
@@ -23,6 +23,10 @@ pub trait MetadataProvider {
     fn resolve_edge_type(&self, name: &str) -> Result<TypeId>;
     /// Looks up an index definition for the given label and property.
     fn property_index(&self, label: LabelId, prop: PropId) -> Result<Option<IndexDef>>;
+    /// Lists the composite indexes defined on the given label.
+    fn composite_indexes(&self, _label: LabelId) -> Result<Vec<IndexDef>> {
+        Ok(Vec::new())
+    }
     /// Resolves a property identifier back to its canonical name.
     fn property_name(&self, id: PropId) -> Result<String>;
     /// Returns statistics for the given (label, property) pair when available.
@@ -106,6 +110,13 @@ impl MetadataProvider for CatalogMetadata {
         self.catalog.get(&read, label, prop)
     }
 
+    fn composite_indexes(&self, label: LabelId) -> Result<Vec<IndexDef>> {
+        let read = self.catalog.store().begin_latest_committed_read()?;
+        let mut defs = self.catalog.iter_label(&read, label)?;
+        defs.retain(IndexDef::is_composite);
+        Ok(defs)
+    }
+
     fn property_name(&self, id: PropId) -> Result<String> {
         self.dict.resolve_str(StrId(id.0))
     }
@@ -192,9 +203,12 @@ impl InMemoryMetadata {
             (label, prop),
             IndexDef {
                 label,
-                prop,
+                props: vec![prop],
                 kind: IndexKind::Chunked,
-                ty: TypeTag::Null,
+                types: vec![TypeTag::Null],
+                max_key_bytes: None,
+                unique: false,
+                state: IndexState::Ready,
             },
         );
         self
@@ -202,7 +216,7 @@ impl InMemoryMetadata {
 
     /// Registers a custom index definition for the given label and property.
     pub fn with_property_index_def(mut self, def: IndexDef) -> Self {
-        self.prop_indexes.insert((def.label, def.prop), def);
+        self.prop_indexes.insert((def.label, def.prop()), def);
         self
     }
 
@@ -252,7 +266,18 @@ impl MetadataProvider for InMemoryMetadata {
     }
 
     fn property_index(&self, label: LabelId, prop: PropId) -> Result<Option<IndexDef>> {
-        Ok(self.prop_indexes.get(&(label, prop)).copied())
+        Ok(self.prop_indexes.get(&(label, prop)).cloned())
+    }
+
+    fn composite_indexes(&self, label: LabelId) -> Result<Vec<IndexDef>> {
+        let mut defs: Vec<IndexDef> = self
+            .prop_indexes
+            .values()
+            .filter(|def| def.label == label && def.is_composite())
+            .cloned()
+            .collect();
+        defs.sort_by_key(IndexDef::prop);
+        Ok(defs)
     }
 
     fn property_name(&self, id: PropId) -> Result<String> {
@@ -24,6 +24,9 @@ pub mod errors;
 /// Canonical scalar value representation shared across the query stack.
 pub mod value;
 
+/// Scalar expression language evaluated by computed projections.
+pub mod expr;
+
 /// Query execution engine.
 ///
 /// Executes physical plans and streams result rows back to clients.
@@ -58,4 +61,4 @@ pub use builder::QueryBuilder;
 pub use value::Value;
 
 /// Execution plan output with explanation capabilities.
-pub use planner::{PlanExplain, PlannerOutput};
+pub use planner::{PlanExplain, PlannerOutput, ScanCandidate, UnindexedPredicate};
@@ -1,11 +1,13 @@
 //! Physical operator tree selected by the rule-based planner.
 
-use crate::query::ast::Var;
+use crate::query::ast::{AggFunc, Var};
+use crate::query::expr::ScalarExpr;
 use crate::query::Value;
 use crate::storage::PropValueOwned;
 use crate::types::{LabelId, PropId, TypeId};
 use rustc_hash::FxHashSet;
 use std::convert::TryInto;
+use std::fmt;
 use std::ops::Bound;
 use std::sync::Arc;
 
@@ -21,6 +23,13 @@ impl PhysicalPlan {
     pub fn new(root: PhysicalNode) -> Self {
         Self { root }
     }
+
+    /// Applies `f` to every literal the plan's predicates compare against.
+    ///
+    /// IN lookups are rebuilt afterwards so they match the rewritten values.
+    pub fn rewrite_literals(&mut self, f: &mut impl FnMut(&mut LiteralValue)) {
+        self.root.rewrite_literals(f);
+    }
 }
 
 /// Node within the physical plan tree.
@@ -45,6 +54,22 @@ impl PhysicalNode {
     pub fn with_inputs(op: PhysicalOp, inputs: Vec<PhysicalNode>) -> Self {
         Self { op, inputs }
     }
+
+    fn rewrite_literals(&mut self, f: &mut impl FnMut(&mut LiteralValue)) {
+        match &mut self.op {
+            PhysicalOp::PropIndexScan { pred, .. } | PhysicalOp::Filter { pred, .. } => {
+                pred.rewrite_literals(f)
+            }
+            PhysicalOp::CompositeIndexScan { preds, .. } => {
+                preds.iter_mut().for_each(|pred| pred.rewrite_literals(f))
+            }
+            PhysicalOp::BoolFilter { expr } => expr.rewrite_literals(f),
+            _ => {}
+        }
+        for input in &mut self.inputs {
+            input.rewrite_literals(f);
+        }
+    }
 }
 
 /// Physical operators supported by Stage 8.
@@ -76,6 +101,24 @@ pub enum PhysicalOp {
         /// Variable name to bind matched nodes.
         as_var: Var,
     },
+    /// Scans nodes using a composite index over several properties.
+    CompositeIndexScan {
+        /// The label of nodes to scan.
+        label: LabelId,
+        /// Optional human-readable label.
+        label_name: Option<String>,
+        /// Covered properties in index key order.
+        props: Vec<PropId>,
+        /// Property names retained for explain output.
+        prop_names: Vec<String>,
+        /// One predicate per covered property: equalities, then at most one
+        /// trailing range.
+        preds: Vec<PropPredicate>,
+        /// Estimated combined selectivity.
+        selectivity: f64,
+        /// Variable name to bind matched nodes.
+        as_var: Var,
+    },
     /// Expands from one node to its neighbors via edges.
     Expand {
         /// Variable representing the source node.
@@ -115,8 +158,33 @@ pub enum PhysicalOp {
         /// Variable from the right stream to join on.
         right: Var,
     },
+    /// Pairs every row of the first input with every row of the second.
+    CrossJoin,
     /// Removes duplicate rows from the result stream.
     Distinct,
+    /// Sorts rows by the given keys, keeping ties in input order.
+    OrderBy {
+        /// Sort keys in priority order.
+        keys: Vec<SortKey>,
+    },
+    /// Keeps the first `n` rows of each group, ranked by `keys`; buffers at
+    /// most `n` rows per group and emits groups in first-seen order.
+    LimitPerGroup {
+        /// Variable whose bound node identifies a row's group.
+        group_by: Var,
+        /// Maximum number of rows kept per group.
+        n: u64,
+        /// Sort keys ranking rows within a group.
+        keys: Vec<SortKey>,
+    },
+    /// Skips the first `offset` rows and stops pulling its input once
+    /// `limit` more have been returned.
+    Limit {
+        /// Maximum number of rows to pass through, if bounded.
+        limit: Option<u64>,
+        /// Number of leading rows to drop.
+        offset: u64,
+    },
     /// Projects specific fields into the output.
     Project {
         /// Fields to include in the projection.
@@ -129,6 +197,21 @@ pub enum PhysicalOp {
     },
 }
 
+/// Key compared by [`PhysicalOp::OrderBy`].
+///
+/// Missing and null values sort after every other value in either direction.
+#[derive(Clone, Debug)]
+pub struct SortKey {
+    /// Variable whose node supplies the value.
+    pub var: Var,
+    /// Property compared between rows; `None` compares node ids.
+    pub prop: Option<PropId>,
+    /// Property name preserved for explain output.
+    pub prop_name: Option<String>,
+    /// Sorts from largest to smallest.
+    pub descending: bool,
+}
+
 /// Edge traversal direction for physical expansion.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum Dir {
@@ -167,6 +250,53 @@ pub enum PropPredicate {
         /// Upper bound of the range (inclusive or exclusive).
         upper: Bound<LiteralValue>,
     },
+    /// Membership predicate on a property.
+    In {
+        /// Variable whose property to check.
+        var: Var,
+        /// Property ID to check.
+        prop: PropId,
+        /// Property name for explain output.
+        prop_name: String,
+        /// Values the property may equal.
+        values: Vec<LiteralValue>,
+        /// Precomputed lookup used when filtering rows.
+        lookup: InLookup,
+    },
+    /// String prefix predicate on a property.
+    Prefix {
+        /// Variable whose property to check.
+        var: Var,
+        /// Property ID to check.
+        prop: PropId,
+        /// Property name for explain output.
+        prop_name: String,
+        /// Prefix the string value must start with.
+        prefix: String,
+    },
+}
+
+impl PropPredicate {
+    fn rewrite_literals(&mut self, f: &mut impl FnMut(&mut LiteralValue)) {
+        match self {
+            PropPredicate::Eq { value, .. } => f(value),
+            PropPredicate::Range { lower, upper, .. } => {
+                rewrite_bound(lower, f);
+                rewrite_bound(upper, f);
+            }
+            PropPredicate::In { values, lookup, .. } => {
+                values.iter_mut().for_each(&mut *f);
+                *lookup = InLookup::from_literals(values);
+            }
+            PropPredicate::Prefix { .. } => {}
+        }
+    }
+}
+
+fn rewrite_bound(bound: &mut Bound<LiteralValue>, f: &mut impl FnMut(&mut LiteralValue)) {
+    if let Bound::Included(value) | Bound::Excluded(value) = bound {
+        f(value);
+    }
 }
 
 /// Boolean predicate tree resolved to physical identifiers.
@@ -182,6 +312,20 @@ pub enum PhysicalBoolExpr {
     Not(Box<PhysicalBoolExpr>),
 }
 
+impl PhysicalBoolExpr {
+    fn rewrite_literals(&mut self, f: &mut impl FnMut(&mut LiteralValue)) {
+        match self {
+            PhysicalBoolExpr::Cmp(cmp) => cmp.rewrite_literals(f),
+            PhysicalBoolExpr::And(children) | PhysicalBoolExpr::Or(children) => {
+                for child in children {
+                    child.rewrite_literals(f);
+                }
+            }
+            PhysicalBoolExpr::Not(child) => child.rewrite_literals(f),
+        }
+    }
+}
+
 /// Comparison operator referencing resolved property identifiers.
 #[derive(Clone, Debug)]
 pub enum PhysicalComparison {
@@ -277,6 +421,17 @@ pub enum PhysicalComparison {
         /// Lookup strategy for membership evaluation.
         lookup: InLookup,
     },
+    /// String prefix predicate on a resolved property id.
+    Prefix {
+        /// Variable whose property is inspected.
+        var: Var,
+        /// Resolved property identifier.
+        prop: PropId,
+        /// Property name used for explain output.
+        prop_name: String,
+        /// Prefix the string value must start with.
+        prefix: String,
+    },
     /// Checks whether the property key exists on the node.
     Exists {
         /// Variable whose property is inspected.
@@ -304,6 +459,55 @@ pub enum PhysicalComparison {
         /// Property name used for explain output.
         prop_name: String,
     },
+    /// Semi-join probing the variable's neighbors until one qualifies.
+    EdgeExists {
+        /// Variable whose edges are followed.
+        var: Var,
+        /// Direction of the edges to follow.
+        dir: Dir,
+        /// Optional edge type filter.
+        ty: Option<TypeId>,
+        /// Edge type name used for explain output.
+        edge_type: Option<String>,
+        /// Variable bound to each neighbor while the predicate runs.
+        target: Var,
+        /// Label the neighbor must carry.
+        label: LabelId,
+        /// Label name used for explain output.
+        label_name: Option<String>,
+        /// Optional predicate the neighbor must satisfy.
+        predicate: Option<Box<PhysicalBoolExpr>>,
+    },
+}
+
+impl PhysicalComparison {
+    fn rewrite_literals(&mut self, f: &mut impl FnMut(&mut LiteralValue)) {
+        match self {
+            PhysicalComparison::Eq { value, .. }
+            | PhysicalComparison::Ne { value, .. }
+            | PhysicalComparison::Lt { value, .. }
+            | PhysicalComparison::Le { value, .. }
+            | PhysicalComparison::Gt { value, .. }
+            | PhysicalComparison::Ge { value, .. } => f(value),
+            PhysicalComparison::Between { low, high, .. } => {
+                rewrite_bound(low, f);
+                rewrite_bound(high, f);
+            }
+            PhysicalComparison::In { values, lookup, .. } => {
+                values.iter_mut().for_each(&mut *f);
+                *lookup = InLookup::from_literals(values);
+            }
+            PhysicalComparison::EdgeExists { predicate, .. } => {
+                if let Some(predicate) = predicate {
+                    predicate.rewrite_literals(f);
+                }
+            }
+            PhysicalComparison::Prefix { .. }
+            | PhysicalComparison::Exists { .. }
+            | PhysicalComparison::IsNull { .. }
+            | PhysicalComparison::IsNotNull { .. } => {}
+        }
+    }
 }
 
 /// Projected field in the output stream.
@@ -327,6 +531,82 @@ pub enum ProjectField {
         /// Optional alias for the output field.
         alias: Option<String>,
     },
+    /// Projects the number of rows emitted so far, including the current one.
+    RunningCount {
+        /// Optional alias for the output field.
+        alias: Option<String>,
+    },
+    /// Projects the node ids bound to each variable along a path, in order.
+    Path {
+        /// Variables visited from the start of the path to its end.
+        vars: Vec<Var>,
+        /// Optional alias for the output field.
+        alias: Option<String>,
+    },
+    /// Evaluates a scalar expression over the row's properties.
+    Expr {
+        /// Expression with resolved property operands.
+        expr: ScalarExpr<ExprProp>,
+        /// Source text, used as the column name when no alias is given.
+        source: String,
+        /// Optional alias for the output field.
+        alias: Option<String>,
+    },
+    /// Folds a value from every row into the single output row.
+    Aggregate {
+        /// Aggregate function.
+        func: AggFunc,
+        /// Variable the aggregate reads from.
+        var: Var,
+        /// Property folded; `None` counts rows.
+        prop: Option<PropId>,
+        /// Property name preserved for explain output.
+        prop_name: Option<String>,
+        /// Name of the output field.
+        alias: String,
+    },
+}
+
+impl ProjectField {
+    /// Returns true when the field folds every row into one value.
+    pub fn is_aggregate(&self) -> bool {
+        matches!(self, ProjectField::Aggregate { .. })
+    }
+
+    /// Returns the key this field is written under in result rows.
+    pub fn column_name(&self) -> String {
+        match self {
+            ProjectField::Var { var, alias } => alias.clone().unwrap_or_else(|| var.0.clone()),
+            ProjectField::Prop {
+                prop_name, alias, ..
+            } => alias.clone().unwrap_or_else(|| prop_name.clone()),
+            ProjectField::RunningCount { alias } => {
+                alias.clone().unwrap_or_else(|| "running_count".into())
+            }
+            ProjectField::Path { alias, .. } => alias.clone().unwrap_or_else(|| "path".into()),
+            ProjectField::Expr { source, alias, .. } => {
+                alias.clone().unwrap_or_else(|| source.clone())
+            }
+            ProjectField::Aggregate { alias, .. } => alias.clone(),
+        }
+    }
+}
+
+/// Property operand of a [`ProjectField::Expr`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExprProp {
+    /// Variable exposing the property.
+    pub var: Var,
+    /// Resolved property identifier.
+    pub prop: PropId,
+    /// Property name preserved for explain output.
+    pub prop_name: String,
+}
+
+impl fmt::Display for ExprProp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.var.0, self.prop_name)
+    }
 }
 
 /// Literal surfaced in the physical plan.
@@ -2,21 +2,21 @@
 
 use crate::query::{
     analyze::{
-        self, AnalyzedComparison, AnalyzedExpr, AnalyzedProjection, AnalyzedQuery, Collation,
-        PropRef, VarBinding, VarId,
+        self, AnalyzeResult, AnalyzedComparison, AnalyzedExpr, AnalyzedOrderKey,
+        AnalyzedProjection, AnalyzedQuery, Collation, PropRef, VarBinding, VarId,
     },
     ast::{EdgeDirection, QueryAst, Var},
-    errors::AnalyzerErrorWithCode,
+    errors::{AnalyzerError, AnalyzerErrorWithCode},
     logical::{LogicalOp, LogicalPlan, PlanNode, PropPredicate as AstPredicate},
     metadata::MetadataProvider,
     physical::{
-        Dir, InLookup, LiteralValue, PhysicalBoolExpr, PhysicalComparison, PhysicalNode,
-        PhysicalOp, PhysicalPlan, ProjectField, PropPredicate as PhysicalPredicate,
+        Dir, ExprProp, InLookup, LiteralValue, PhysicalBoolExpr, PhysicalComparison, PhysicalNode,
+        PhysicalOp, PhysicalPlan, ProjectField, PropPredicate as PhysicalPredicate, SortKey,
     },
     Value,
 };
-use crate::storage::index::{IndexDef, TypeTag};
-use crate::storage::{PropStats, PropValueOwned};
+use crate::storage::index::{IndexDef, IndexState, TypeTag};
+use crate::storage::{AdjacencyBackend, PropStats, PropValueOwned};
 use crate::types::{LabelId, PropId, Result, SombraError};
 use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
@@ -31,6 +31,12 @@ use xxhash_rust::xxh64::Xxh64;
 pub struct PlannerConfig {
     /// Whether to enable hash join optimization
     pub enable_hash_join: bool,
+    /// Adjacency storage that expand operators read, reported by explain.
+    pub adjacency_backend: AdjacencyBackend,
+    /// Largest number of match clauses a pattern may bind; `None` is unlimited.
+    pub max_pattern_nodes: Option<usize>,
+    /// Largest number of edge clauses a pattern may join; `None` is unlimited.
+    pub max_pattern_edges: Option<usize>,
 }
 
 /// Planner output containing the chosen physical plan and explain tree.
@@ -44,6 +50,55 @@ pub struct PlannerOutput {
     pub explain: PlanExplain,
     /// Deterministic plan hash for explain/caching.
     pub plan_hash: u64,
+    /// Driving-scan candidates considered for each match variable.
+    pub candidates: Vec<ScanCandidate>,
+    /// Equality and range predicates the driving label scan had to evaluate
+    /// row by row because their property is not indexed.
+    pub unindexed: Vec<UnindexedPredicate>,
+}
+
+/// Predicate on the driving variable that an index on `(label, prop)` could serve.
+#[derive(Clone, Debug)]
+pub struct UnindexedPredicate {
+    /// Label read by the full scan.
+    pub label: String,
+    /// Property the predicate compares.
+    pub prop: String,
+    /// Whether the predicate is an equality rather than a range comparison.
+    pub equality: bool,
+    /// Estimated fraction of the label's nodes the predicate keeps.
+    pub selectivity: f64,
+}
+
+/// Driving-scan alternative considered while choosing the plan anchor.
+#[derive(Clone, Debug)]
+pub struct ScanCandidate {
+    /// Variable the scan would bind.
+    pub var: Var,
+    /// Label the scan reads, when the match names one.
+    pub label: Option<String>,
+    /// Scan operator name (`LabelScan`, `PropIndexScan`, `CompositeIndexScan`,
+    /// `Intersect`, or `Union`).
+    pub op: &'static str,
+    /// Indexed properties read by the scan.
+    pub props: Vec<String>,
+    /// Estimated fraction of the label's nodes visited; a label scan costs `1.0`.
+    pub estimated_cost: f64,
+    /// Whether the planner chose this candidate to drive the query.
+    pub selected: bool,
+}
+
+impl ScanCandidate {
+    fn new(binding: &VarBinding, op: &'static str, props: Vec<String>, cost: f64) -> Self {
+        Self {
+            var: binding.var.clone(),
+            label: binding.label.clone(),
+            op,
+            props,
+            estimated_cost: cost,
+            selected: false,
+        }
+    }
 }
 
 /// Human-readable explain tree.
@@ -109,33 +164,59 @@ impl ExplainProp {
 /// Query planner that converts AST to physical execution plans.
 pub struct Planner {
     metadata: Arc<dyn MetadataProvider>,
-    _config: PlannerConfig,
+    config: PlannerConfig,
 }
 
 impl Planner {
     /// Creates a new planner with the given configuration and metadata provider.
     pub fn new(config: PlannerConfig, metadata: Arc<dyn MetadataProvider>) -> Self {
-        Self {
-            metadata,
-            _config: config,
-        }
+        Self { metadata, config }
     }
 
     /// Converts an AST into a physical plan.
     pub fn plan(&self, ast: &QueryAst) -> Result<PlannerOutput> {
-        let analyzed = analyze::analyze(ast, self.metadata.as_ref())
+        let analyzed = self
+            .check_pattern_size(ast)
+            .and_then(|()| analyze::analyze(ast, self.metadata.as_ref()))
             .map_err(|err| SombraError::InvalidOwned(AnalyzerErrorWithCode(&err).to_string()))?;
         self.plan_analyzed(&analyzed)
     }
 
+    /// Rejects patterns with more match or edge clauses than the configured
+    /// limits. Only the AST is inspected, so oversized patterns fail before
+    /// any catalog or graph access.
+    pub fn check_pattern_size(&self, ast: &QueryAst) -> AnalyzeResult<()> {
+        self.check_pattern_counts(ast.matches.len(), ast.edges.len())
+    }
+
+    /// Applies the same limits as [`Planner::check_pattern_size`] to a
+    /// pattern given as node and edge counts, for matchers that do not go
+    /// through a query AST.
+    pub fn check_pattern_counts(&self, nodes: usize, edges: usize) -> AnalyzeResult<()> {
+        let limits = [
+            ("match", nodes, self.config.max_pattern_nodes),
+            ("edge", edges, self.config.max_pattern_edges),
+        ];
+        for (clause, count, max) in limits {
+            if let Some(max) = max {
+                if count > max {
+                    return Err(AnalyzerError::PatternTooLarge { clause, count, max });
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Converts an analyzed query into a physical plan.
     pub fn plan_analyzed(&self, analyzed: &AnalyzedQuery) -> Result<PlannerOutput> {
         let mut ctx = PlanContext::new(self.metadata.as_ref());
         let logical = self.build_logical_plan(analyzed, &mut ctx)?;
         let physical = self.lower_to_physical(&logical, &mut ctx)?;
+        let candidates = std::mem::take(&mut ctx.candidates);
+        let unindexed = std::mem::take(&mut ctx.unindexed);
         let plan_hash = compute_plan_hash(analyzed, &physical, self.metadata.catalog_epoch());
         let explain = PlanExplain {
-            root: build_explain_tree(&physical.root),
+            root: build_explain_tree(&physical.root, &self.config),
             plan_hash,
         };
         Ok(PlannerOutput {
@@ -143,6 +224,8 @@ impl Planner {
             plan: physical,
             explain,
             plan_hash,
+            candidates,
+            unindexed,
         })
     }
 
@@ -199,72 +282,87 @@ impl Planner {
 
         let bindings = analyzed.vars();
         ctx.register_bindings(bindings);
+        ctx.register_bindings(analyzed.scoped_vars());
         let anchor_idx = self.select_anchor(bindings, &preds_by_var, ctx)?;
         let anchor_binding = &bindings[anchor_idx];
         let anchor_label = anchor_binding.label_id;
         let mut indexed = self.take_indexed_predicates(anchor_binding, &mut preds_by_var, ctx)?;
+        let candidates =
+            self.scan_candidates(bindings, anchor_idx, &indexed, &preds_by_var, ctx)?;
+        ctx.candidates = candidates;
+        if indexed.union.is_none() && indexed.scan_count() == 0 {
+            ctx.unindexed = unindexed_predicates(anchor_binding, &preds_by_var);
+        }
         if let Some(expr) = indexed.union_fallback.take() {
             residual_predicate = merge_residual(residual_predicate, expr);
         }
         let mut current = if let Some(union_pred) = indexed.union {
             self.build_union_scan(analyzed, anchor_binding, union_pred, analyzed.distinct)?
         } else {
-            match indexed.scans.len() {
-                0 => PlanNode::new(LogicalOp::LabelScan {
+            let mut children = Vec::with_capacity(indexed.scan_count());
+            if let Some(composite) = indexed.composite {
+                children.push(PlanNode::new(LogicalOp::CompositeIndexScan {
                     label: anchor_binding.label.clone(),
                     label_id: anchor_label,
-                    as_var: anchor_binding.var.clone(),
-                }),
-                1 => {
-                    let pred = indexed.scans.into_iter().next().unwrap();
-                    match pred.kind {
-                        VarPredicateKind::Comparison(cmp) => {
-                            PlanNode::new(LogicalOp::PropIndexScan {
-                                label: anchor_binding.label.clone(),
-                                label_id: anchor_label,
-                                prop: prop_from_cmp(&cmp),
-                                predicate: cmp_to_prop_predicate(analyzed, &cmp)?,
-                                selectivity: pred.selectivity,
-                                as_var: anchor_binding.var.clone(),
-                            })
-                        }
-                        VarPredicateKind::Union { .. } => {
-                            return Err(SombraError::Invalid(
-                                "unexpected union predicate in indexed scans",
-                            ))
-                        }
-                    }
-                }
-                _ => {
-                    let children = indexed
-                        .scans
-                        .into_iter()
-                        .map(|var_pred| -> Result<PlanNode> {
-                            match var_pred.kind {
-                                VarPredicateKind::Comparison(cmp) => {
-                                    Ok(PlanNode::new(LogicalOp::PropIndexScan {
-                                        label: anchor_binding.label.clone(),
-                                        label_id: anchor_label,
-                                        prop: prop_from_cmp(&cmp),
-                                        predicate: cmp_to_prop_predicate(analyzed, &cmp)?,
-                                        selectivity: var_pred.selectivity,
-                                        as_var: anchor_binding.var.clone(),
-                                    }))
-                                }
-                                VarPredicateKind::Union { .. } => Err(SombraError::Invalid(
-                                    "unexpected union predicate in indexed scans",
-                                )),
+                    props: composite
+                        .preds
+                        .iter()
+                        .map(|pred| match &pred.kind {
+                            VarPredicateKind::Comparison(cmp) => Ok(prop_from_cmp(cmp)),
+                            VarPredicateKind::Union { .. } => Err(SombraError::Invalid(
+                                "unexpected union predicate in composite scan",
+                            )),
+                        })
+                        .collect::<Result<Vec<_>>>()?,
+                    predicates: composite
+                        .preds
+                        .iter()
+                        .map(|pred| match &pred.kind {
+                            VarPredicateKind::Comparison(cmp) => {
+                                cmp_to_prop_predicate(analyzed, cmp)
                             }
+                            VarPredicateKind::Union { .. } => Err(SombraError::Invalid(
+                                "unexpected union predicate in composite scan",
+                            )),
                         })
-                        .collect::<Result<Vec<_>>>()?;
-                    PlanNode::with_inputs(
-                        LogicalOp::Intersect {
-                            vars: vec![anchor_binding.var.clone()],
-                        },
-                        children,
-                    )
+                        .collect::<Result<Vec<_>>>()?,
+                    selectivity: composite.selectivity,
+                    as_var: anchor_binding.var.clone(),
+                }));
+            }
+            for var_pred in indexed.scans {
+                match var_pred.kind {
+                    VarPredicateKind::Comparison(cmp) => {
+                        children.push(PlanNode::new(LogicalOp::PropIndexScan {
+                            label: anchor_binding.label.clone(),
+                            label_id: anchor_label,
+                            prop: prop_from_cmp(&cmp),
+                            predicate: cmp_to_prop_predicate(analyzed, &cmp)?,
+                            selectivity: var_pred.selectivity,
+                            as_var: anchor_binding.var.clone(),
+                        }))
+                    }
+                    VarPredicateKind::Union { .. } => {
+                        return Err(SombraError::Invalid(
+                            "unexpected union predicate in indexed scans",
+                        ))
+                    }
                 }
             }
+            match children.len() {
+                0 => PlanNode::new(LogicalOp::LabelScan {
+                    label: anchor_binding.label.clone(),
+                    label_id: anchor_label,
+                    as_var: anchor_binding.var.clone(),
+                }),
+                1 => children.pop().unwrap(),
+                _ => PlanNode::with_inputs(
+                    LogicalOp::Intersect {
+                        vars: vec![anchor_binding.var.clone()],
+                    },
+                    children,
+                ),
+            }
         };
 
         current =
@@ -292,9 +390,22 @@ impl Planner {
                     }
                 })
             else {
-                return Err(SombraError::Invalid(
-                    "query pattern is disconnected; cannot plan edges",
-                ));
+                // No edge reaches the remaining bindings: scan the next
+                // disconnected component on its own and cross-join it.
+                let binding = bindings
+                    .iter()
+                    .find(|binding| !bound_vars.contains(&binding.var))
+                    .expect("unbound binding remains");
+                let scan = PlanNode::new(LogicalOp::LabelScan {
+                    label: binding.label.clone(),
+                    label_id: binding.label_id,
+                    as_var: binding.var.clone(),
+                });
+                let scan =
+                    self.apply_var_predicates(analyzed, scan, binding.id, &mut preds_by_var)?;
+                current = PlanNode::with_inputs(LogicalOp::CrossJoin, vec![current, scan]);
+                bound_vars.insert(binding.var.clone());
+                continue;
             };
 
             let edge = remaining_edges.remove(edge_idx);
@@ -374,6 +485,38 @@ impl Planner {
             current = PlanNode::with_inputs(LogicalOp::Distinct, vec![current]);
         }
 
+        if let Some(group) = &analyzed.limit_per_group {
+            current = PlanNode::with_inputs(
+                LogicalOp::LimitPerGroup {
+                    group_by: group.group_by,
+                    n: group.n,
+                    keys: group.order_by.clone(),
+                },
+                vec![current],
+            );
+        }
+
+        if !analyzed.order_by.is_empty() {
+            current = PlanNode::with_inputs(
+                LogicalOp::OrderBy {
+                    keys: analyzed.order_by.clone(),
+                },
+                vec![current],
+            );
+        }
+
+        // Applied above OrderBy and Distinct so the bound counts final rows;
+        // without a sort the stream stops pulling once the limit is reached.
+        if analyzed.limit.is_some() || analyzed.offset > 0 {
+            current = PlanNode::with_inputs(
+                LogicalOp::Limit {
+                    limit: analyzed.limit,
+                    offset: analyzed.offset,
+                },
+                vec![current],
+            );
+        }
+
         if !analyzed.projections.is_empty() {
             current = PlanNode::with_inputs(
                 LogicalOp::Project {
@@ -465,24 +608,24 @@ impl Planner {
         for pred in preds {
             match &pred.kind {
                 VarPredicateKind::Comparison(cmp) => {
-                    if let Some((prop, score_candidate)) = cmp_anchor_class(cmp) {
-                        if ctx.property_index(binding.label_id, prop.id)?.is_some() {
-                            if score_candidate == AnchorScore::Eq {
-                                return Ok(AnchorScore::Eq);
-                            }
-                            best = AnchorScore::Range;
+                    if let Some((_, score_candidate)) =
+                        indexed_anchor_class(ctx, binding.label_id, cmp)?
+                    {
+                        if score_candidate == AnchorScore::Eq {
+                            return Ok(AnchorScore::Eq);
                         }
+                        best = AnchorScore::Range;
                     }
                 }
                 VarPredicateKind::Union { terms, .. } => {
                     for term in terms {
-                        if let Some((prop, score_candidate)) = cmp_anchor_class(&term.cmp) {
-                            if ctx.property_index(binding.label_id, prop.id)?.is_some() {
-                                if score_candidate == AnchorScore::Eq {
-                                    return Ok(AnchorScore::Eq);
-                                }
-                                best = AnchorScore::Range;
+                        if let Some((_, score_candidate)) =
+                            indexed_anchor_class(ctx, binding.label_id, &term.cmp)?
+                        {
+                            if score_candidate == AnchorScore::Eq {
+                                return Ok(AnchorScore::Eq);
                             }
+                            best = AnchorScore::Range;
                         }
                     }
                 }
@@ -519,15 +662,13 @@ impl Planner {
                         selectivity,
                         kind: VarPredicateKind::Comparison(cmp.clone()),
                     };
-                    if let Some((prop, class)) = cmp_anchor_class(&cmp) {
-                        if ctx.property_index(binding.label_id, prop.id)?.is_some() {
-                            match class {
-                                AnchorScore::Eq => indexed_eq.push(rebuilt),
-                                AnchorScore::Range => indexed_range.push(rebuilt),
-                                AnchorScore::Label => unreachable!("label score not used here"),
-                            }
-                            continue;
+                    if let Some((_, class)) = indexed_anchor_class(ctx, binding.label_id, &cmp)? {
+                        match class {
+                            AnchorScore::Eq => indexed_eq.push(rebuilt),
+                            AnchorScore::Range => indexed_range.push(rebuilt),
+                            AnchorScore::Label => unreachable!("label score not used here"),
                         }
+                        continue;
                     }
                     remaining.push(rebuilt);
                 }
@@ -551,6 +692,11 @@ impl Planner {
             }
         }
 
+        selection.composite = take_composite_predicates(
+            ctx.composite_indexes(binding.label_id)?,
+            [&mut indexed_eq, &mut indexed_range, &mut remaining],
+        );
+
         if !remaining.is_empty() {
             preds_by_var.insert(binding.id, remaining);
         }
@@ -567,6 +713,115 @@ impl Planner {
         Ok(selection)
     }
 
+    /// Lists the driving scans available to every binding, marking the one the
+    /// planner picked. Rejected anchors keep their indexed alternatives so callers
+    /// can compare them against the chosen scan.
+    fn scan_candidates(
+        &self,
+        bindings: &[VarBinding],
+        anchor_idx: usize,
+        indexed: &IndexedSelection,
+        preds_by_var: &HashMap<VarId, Vec<VarPredicate>>,
+        ctx: &mut PlanContext<'_>,
+    ) -> Result<Vec<ScanCandidate>> {
+        let mut candidates = Vec::new();
+        for (idx, binding) in bindings.iter().enumerate() {
+            let mut label_scan = ScanCandidate::new(binding, "LabelScan", Vec::new(), 1.0);
+            if idx != anchor_idx {
+                candidates.push(label_scan);
+                for pred in preds_by_var.get(&binding.id).into_iter().flatten() {
+                    match &pred.kind {
+                        VarPredicateKind::Comparison(cmp) => {
+                            let Some((prop, _)) = indexed_anchor_class(ctx, binding.label_id, cmp)?
+                            else {
+                                continue;
+                            };
+                            candidates.push(ScanCandidate::new(
+                                binding,
+                                "PropIndexScan",
+                                vec![prop.name],
+                                pred.selectivity,
+                            ));
+                        }
+                        VarPredicateKind::Union { terms, .. } => {
+                            if union_terms_indexed(binding, ctx, terms)? {
+                                candidates.push(ScanCandidate::new(
+                                    binding,
+                                    "Union",
+                                    union_term_props(terms),
+                                    pred.selectivity,
+                                ));
+                            }
+                        }
+                    }
+                }
+                continue;
+            }
+
+            label_scan.selected = indexed.union.is_none() && indexed.scan_count() == 0;
+            candidates.push(label_scan);
+            let single_scan = indexed.union.is_none() && indexed.scan_count() == 1;
+            if let Some(composite) = &indexed.composite {
+                let mut candidate = ScanCandidate::new(
+                    binding,
+                    "CompositeIndexScan",
+                    composite.prop_names(),
+                    composite.selectivity,
+                );
+                candidate.selected = single_scan;
+                candidates.push(candidate);
+            }
+            for pred in &indexed.scans {
+                if let VarPredicateKind::Comparison(cmp) = &pred.kind {
+                    let mut candidate = ScanCandidate::new(
+                        binding,
+                        "PropIndexScan",
+                        vec![prop_from_cmp(cmp).name],
+                        pred.selectivity,
+                    );
+                    candidate.selected = single_scan;
+                    candidates.push(candidate);
+                }
+            }
+            if let Some(pred) = &indexed.union {
+                if let VarPredicateKind::Union { terms, .. } = &pred.kind {
+                    let mut candidate = ScanCandidate::new(
+                        binding,
+                        "Union",
+                        union_term_props(terms),
+                        pred.selectivity,
+                    );
+                    candidate.selected = true;
+                    candidates.push(candidate);
+                }
+            } else if indexed.scan_count() > 1 {
+                let mut props = indexed
+                    .composite
+                    .as_ref()
+                    .map(CompositeSelection::prop_names)
+                    .unwrap_or_default();
+                props.extend(indexed.scans.iter().filter_map(|pred| match &pred.kind {
+                    VarPredicateKind::Comparison(cmp) => Some(prop_from_cmp(cmp).name),
+                    VarPredicateKind::Union { .. } => None,
+                }));
+                // Every input of an intersection is read in full.
+                let cost = indexed
+                    .scans
+                    .iter()
+                    .map(|pred| pred.selectivity)
+                    .sum::<f64>()
+                    + indexed
+                        .composite
+                        .as_ref()
+                        .map_or(0.0, |composite| composite.selectivity);
+                let mut candidate = ScanCandidate::new(binding, "Intersect", props, cost);
+                candidate.selected = true;
+                candidates.push(candidate);
+            }
+        }
+        Ok(candidates)
+    }
+
     fn build_union_scan(
         &self,
         analyzed: &AnalyzedQuery,
@@ -654,6 +909,36 @@ impl Planner {
                     as_var: as_var.clone(),
                 }
             }
+            LogicalOp::CompositeIndexScan {
+                label,
+                label_id,
+                props: _,
+                predicates,
+                selectivity,
+                as_var,
+            } => {
+                let preds = predicates
+                    .iter()
+                    .map(|predicate| self.convert_prop_predicate(predicate, ctx))
+                    .collect::<Result<Vec<_>>>()?;
+                let props = preds
+                    .iter()
+                    .map(|pred| {
+                        prop_from_predicate(pred).ok_or(SombraError::Invalid(
+                            "composite index scans require concrete predicates",
+                        ))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                PhysicalOp::CompositeIndexScan {
+                    label: *label_id,
+                    label_name: label.clone(),
+                    props,
+                    prop_names: preds.iter().map(prop_name_from_predicate).collect(),
+                    preds,
+                    selectivity: *selectivity,
+                    as_var: as_var.clone(),
+                }
+            }
             LogicalOp::Expand {
                 from,
                 to,
@@ -683,6 +968,7 @@ impl Planner {
                 left: left.clone(),
                 right: right.clone(),
             },
+            LogicalOp::CrossJoin => PhysicalOp::CrossJoin,
             LogicalOp::Project { fields } => {
                 let projections = fields
                     .iter()
@@ -694,6 +980,18 @@ impl Planner {
                 }
             }
             LogicalOp::Distinct => PhysicalOp::Distinct,
+            LogicalOp::OrderBy { keys } => PhysicalOp::OrderBy {
+                keys: convert_sort_keys(keys, ctx),
+            },
+            LogicalOp::LimitPerGroup { group_by, n, keys } => PhysicalOp::LimitPerGroup {
+                group_by: ctx.var_for_id(*group_by),
+                n: *n,
+                keys: convert_sort_keys(keys, ctx),
+            },
+            LogicalOp::Limit { limit, offset } => PhysicalOp::Limit {
+                limit: *limit,
+                offset: *offset,
+            },
             LogicalOp::BoolFilter { expr } => PhysicalOp::BoolFilter {
                 expr: self.convert_bool_expr(expr, ctx)?,
             },
@@ -726,6 +1024,23 @@ impl Planner {
                 lower: convert_bound(lower),
                 upper: convert_bound(upper),
             }),
+            AstPredicate::In { var, prop, values } => {
+                let literals: Vec<LiteralValue> = values.iter().map(LiteralValue::from).collect();
+                let lookup = InLookup::from_literals(&literals);
+                Ok(PhysicalPredicate::In {
+                    var: var.clone(),
+                    prop: prop.id,
+                    prop_name: prop.name.clone(),
+                    values: literals,
+                    lookup,
+                })
+            }
+            AstPredicate::Prefix { var, prop, prefix } => Ok(PhysicalPredicate::Prefix {
+                var: var.clone(),
+                prop: prop.id,
+                prop_name: prop.name.clone(),
+                prefix: prefix.clone(),
+            }),
         }
     }
 
@@ -770,7 +1085,7 @@ impl Planner {
     fn convert_comparison(
         &self,
         cmp: &AnalyzedComparison,
-        ctx: &PlanContext<'_>,
+        ctx: &mut PlanContext<'_>,
     ) -> Result<PhysicalComparison> {
         Ok(match cmp {
             AnalyzedComparison::Eq { var, prop, value } => PhysicalComparison::Eq {
@@ -832,6 +1147,12 @@ impl Planner {
                     lookup,
                 }
             }
+            AnalyzedComparison::Prefix { var, prop, prefix } => PhysicalComparison::Prefix {
+                var: ctx.var_for_id(*var),
+                prop: prop.id,
+                prop_name: prop.name.clone(),
+                prefix: prefix.clone(),
+            },
             AnalyzedComparison::Exists { var, prop } => PhysicalComparison::Exists {
                 var: ctx.var_for_id(*var),
                 prop: prop.id,
@@ -847,6 +1168,25 @@ impl Planner {
                 prop: prop.id,
                 prop_name: prop.name.clone(),
             },
+            AnalyzedComparison::EdgeExists {
+                var,
+                edge_type,
+                direction,
+                target,
+                predicate,
+            } => PhysicalComparison::EdgeExists {
+                var: ctx.var_for_id(*var),
+                dir: convert_direction(*direction),
+                ty: edge_type.id,
+                edge_type: edge_type.name.clone(),
+                target: target.var.clone(),
+                label: target.label_id,
+                label_name: target.label.clone(),
+                predicate: match predicate {
+                    Some(expr) => Some(Box::new(self.convert_bool_expr(expr, ctx)?)),
+                    None => None,
+                },
+            },
         })
     }
 }
@@ -887,9 +1227,52 @@ fn convert_projection(proj: AnalyzedProjection, ctx: &PlanContext<'_>) -> Result
             prop_name: prop.name.clone(),
             alias,
         }),
+        AnalyzedProjection::RunningCount { alias } => Ok(ProjectField::RunningCount { alias }),
+        AnalyzedProjection::Path { vars, alias } => Ok(ProjectField::Path {
+            vars: vars.into_iter().map(|var| ctx.var_for_id(var)).collect(),
+            alias,
+        }),
+        AnalyzedProjection::Expr {
+            expr,
+            source,
+            alias,
+        } => Ok(ProjectField::Expr {
+            expr: expr.try_map_props(&mut |(var, prop): (VarId, PropRef)| {
+                Ok::<_, SombraError>(ExprProp {
+                    var: ctx.var_for_id(var),
+                    prop: prop.id,
+                    prop_name: prop.name,
+                })
+            })?,
+            source,
+            alias,
+        }),
+        AnalyzedProjection::Aggregate {
+            func,
+            var,
+            prop,
+            alias,
+        } => Ok(ProjectField::Aggregate {
+            func,
+            var: ctx.var_for_id(var),
+            prop: prop.as_ref().map(|prop| prop.id),
+            prop_name: prop.map(|prop| prop.name),
+            alias,
+        }),
     }
 }
 
+fn convert_sort_keys(keys: &[AnalyzedOrderKey], ctx: &PlanContext<'_>) -> Vec<SortKey> {
+    keys.iter()
+        .map(|key| SortKey {
+            var: ctx.var_for_id(key.var),
+            prop: key.prop.as_ref().map(|prop| prop.id),
+            prop_name: key.prop.as_ref().map(|prop| prop.name.clone()),
+            descending: key.descending,
+        })
+        .collect()
+}
+
 fn extract_pushdown_predicates(
     query: &AnalyzedQuery,
     expr: AnalyzedExpr,
@@ -907,6 +1290,12 @@ fn extract_pushdown_predicates(
                 });
                 None
             }
+            // Longer lists become a single multi-point index probe instead of
+            // one union branch per value.
+            AnalyzedComparison::In { .. } => {
+                out.push(PushdownCandidate::Comparison(cmp));
+                None
+            }
             _ if is_pushdown_comparison(&cmp) => {
                 out.push(PushdownCandidate::Comparison(cmp));
                 None
@@ -1034,7 +1423,8 @@ fn is_pushdown_comparison(cmp: &AnalyzedComparison) -> bool {
         | AnalyzedComparison::Le { .. }
         | AnalyzedComparison::Gt { .. }
         | AnalyzedComparison::Ge { .. }
-        | AnalyzedComparison::Between { .. } => true,
+        | AnalyzedComparison::Between { .. }
+        | AnalyzedComparison::Prefix { .. } => true,
         _ => false,
     }
 }
@@ -1049,9 +1439,11 @@ fn predicate_var(cmp: &AnalyzedComparison) -> VarId {
         | AnalyzedComparison::Ge { var, .. }
         | AnalyzedComparison::Between { var, .. }
         | AnalyzedComparison::In { var, .. }
+        | AnalyzedComparison::Prefix { var, .. }
         | AnalyzedComparison::Exists { var, .. }
         | AnalyzedComparison::IsNull { var, .. }
-        | AnalyzedComparison::IsNotNull { var, .. } => *var,
+        | AnalyzedComparison::IsNotNull { var, .. }
+        | AnalyzedComparison::EdgeExists { var, .. } => *var,
     }
 }
 
@@ -1065,9 +1457,13 @@ fn prop_from_cmp(cmp: &AnalyzedComparison) -> PropRef {
         | AnalyzedComparison::Ge { prop, .. }
         | AnalyzedComparison::Between { prop, .. }
         | AnalyzedComparison::In { prop, .. }
+        | AnalyzedComparison::Prefix { prop, .. }
         | AnalyzedComparison::Exists { prop, .. }
         | AnalyzedComparison::IsNull { prop, .. }
         | AnalyzedComparison::IsNotNull { prop, .. } => prop.clone(),
+        AnalyzedComparison::EdgeExists { .. } => {
+            unreachable!("edge existence tests are never pushed down")
+        }
     }
 }
 
@@ -1118,6 +1514,16 @@ fn cmp_to_prop_predicate(
             lower: low.clone(),
             upper: high.clone(),
         }),
+        AnalyzedComparison::In { prop, values, .. } => Ok(AstPredicate::In {
+            var,
+            prop: prop.clone(),
+            values: values.clone(),
+        }),
+        AnalyzedComparison::Prefix { prop, prefix, .. } => Ok(AstPredicate::Prefix {
+            var,
+            prop: prop.clone(),
+            prefix: prefix.clone(),
+        }),
         _ => Err(SombraError::Invalid(
             "cannot convert comparison into property predicate",
         )),
@@ -1323,6 +1729,8 @@ struct PlanContext<'a> {
     metadata: &'a dyn MetadataProvider,
     prop_stats: HashMap<(LabelId, PropId), Arc<PropStats>>,
     var_names: HashMap<VarId, Var>,
+    candidates: Vec<ScanCandidate>,
+    unindexed: Vec<UnindexedPredicate>,
 }
 
 impl<'a> PlanContext<'a> {
@@ -1331,6 +1739,8 @@ impl<'a> PlanContext<'a> {
             metadata,
             prop_stats: HashMap::new(),
             var_names: HashMap::new(),
+            candidates: Vec::new(),
+            unindexed: Vec::new(),
         }
     }
 
@@ -1347,8 +1757,21 @@ impl<'a> PlanContext<'a> {
             .clone()
     }
 
+    /// Returns the index on `(label, prop)` when it can answer queries.
+    ///
+    /// An index still [`IndexState::Building`] may be missing nodes, so it is
+    /// reported absent and predicates on it degrade to a filtered scan.
     fn property_index(&self, label: LabelId, prop: PropId) -> Result<Option<IndexDef>> {
-        self.metadata.property_index(label, prop)
+        Ok(self
+            .metadata
+            .property_index(label, prop)?
+            .filter(|def| def.state == IndexState::Ready))
+    }
+
+    fn composite_indexes(&self, label: LabelId) -> Result<Vec<IndexDef>> {
+        let mut defs = self.metadata.composite_indexes(label)?;
+        defs.retain(|def| def.state == IndexState::Ready);
+        Ok(defs)
     }
 
     fn property_stats_by_id(
@@ -1402,17 +1825,62 @@ struct UnionTerm {
 #[derive(Clone, Default)]
 struct IndexedSelection {
     scans: Vec<VarPredicate>,
+    composite: Option<CompositeSelection>,
     union: Option<VarPredicate>,
     union_fallback: Option<AnalyzedExpr>,
 }
 
-fn build_explain_tree(node: &PhysicalNode) -> ExplainNode {
+impl IndexedSelection {
+    /// Counts the index scans the anchor intersects, excluding unions.
+    fn scan_count(&self) -> usize {
+        self.scans.len() + usize::from(self.composite.is_some())
+    }
+}
+
+/// Predicates answered together by one composite index, in key order.
+#[derive(Clone)]
+struct CompositeSelection {
+    preds: Vec<VarPredicate>,
+    selectivity: f64,
+}
+
+impl CompositeSelection {
+    fn prop_names(&self) -> Vec<String> {
+        self.preds
+            .iter()
+            .filter_map(|pred| match &pred.kind {
+                VarPredicateKind::Comparison(cmp) => Some(prop_from_cmp(cmp).name),
+                VarPredicateKind::Union { .. } => None,
+            })
+            .collect()
+    }
+}
+
+fn build_explain_tree(node: &PhysicalNode, config: &PlannerConfig) -> ExplainNode {
     let mut explain = ExplainNode::new(op_name(&node.op));
     explain.props = op_props(&node.op);
-    explain.inputs = node.inputs.iter().map(build_explain_tree).collect();
+    if matches!(node.op, PhysicalOp::Expand { .. }) {
+        explain.props.push(ExplainProp::plain(
+            "traversal_strategy",
+            traversal_strategy(config.adjacency_backend),
+        ));
+    }
+    explain.inputs = node
+        .inputs
+        .iter()
+        .map(|input| build_explain_tree(input, config))
+        .collect();
     explain
 }
 
+/// Names the adjacency path expands take; shadow-write IFA still reads the B-tree.
+fn traversal_strategy(backend: AdjacencyBackend) -> &'static str {
+    match backend {
+        AdjacencyBackend::BTree | AdjacencyBackend::Ifa => "btree",
+        AdjacencyBackend::IfaOnly => "ifa",
+    }
+}
+
 fn compute_plan_hash(analyzed: &AnalyzedQuery, plan: &PhysicalPlan, catalog_epoch: u64) -> u64 {
     let logical = hash_analyzed_query(analyzed, catalog_epoch);
     let physical = hash_physical_plan(plan);
@@ -1478,9 +1946,35 @@ fn hash_analyzed_query(analyzed: &AnalyzedQuery, catalog_epoch: u64) -> u64 {
     for projection in &analyzed.projections {
         hash_projection(projection, &mut hasher);
     }
+    hash_order_keys(&analyzed.order_by, &mut hasher);
+    match &analyzed.limit_per_group {
+        Some(group) => {
+            hasher.write_u32(group.group_by.0);
+            hasher.write_u64(group.n);
+            hash_order_keys(&group.order_by, &mut hasher);
+        }
+        None => hasher.write_u8(0xff),
+    }
+    match analyzed.limit {
+        Some(limit) => hasher.write_u64(limit),
+        None => hasher.write_u8(0xff),
+    }
+    hasher.write_u64(analyzed.offset);
     hasher.finish()
 }
 
+fn hash_order_keys(keys: &[AnalyzedOrderKey], hasher: &mut Xxh64) {
+    hasher.write_u64(keys.len() as u64);
+    for key in keys {
+        hasher.write_u32(key.var.0);
+        match &key.prop {
+            Some(prop) => hash_prop(prop, hasher),
+            None => hasher.write_u8(0xff),
+        }
+        hasher.write_u8(key.descending as u8);
+    }
+}
+
 fn hash_projection(projection: &AnalyzedProjection, hasher: &mut Xxh64) {
     match projection {
         AnalyzedProjection::Var { var, alias } => {
@@ -1502,6 +1996,43 @@ fn hash_projection(projection: &AnalyzedProjection, hasher: &mut Xxh64) {
                 hasher.write(alias.as_bytes());
             }
         }
+        AnalyzedProjection::RunningCount { alias } => {
+            hasher.write_u8(2);
+            if let Some(alias) = alias {
+                hasher.write(alias.as_bytes());
+            }
+        }
+        AnalyzedProjection::Path { vars, alias } => {
+            hasher.write_u8(3);
+            for var in vars {
+                hasher.write_u32(var.0);
+            }
+            if let Some(alias) = alias {
+                hasher.write(alias.as_bytes());
+            }
+        }
+        AnalyzedProjection::Expr { source, alias, .. } => {
+            hasher.write_u8(5);
+            hasher.write(source.as_bytes());
+            if let Some(alias) = alias {
+                hasher.write(alias.as_bytes());
+            }
+        }
+        AnalyzedProjection::Aggregate {
+            func,
+            var,
+            prop,
+            alias,
+        } => {
+            hasher.write_u8(4);
+            hasher.write(func.name().as_bytes());
+            hasher.write_u32(var.0);
+            if let Some(prop) = prop {
+                hasher.write(prop.name.as_bytes());
+                hasher.write_u32(prop.id.0);
+            }
+            hasher.write(alias.as_bytes());
+        }
     }
 }
 
@@ -1591,6 +2122,13 @@ fn hash_analyzed_comparison(cmp: &AnalyzedComparison, hasher: &mut Xxh64) {
                 hash_value(value, hasher);
             }
         }
+        AnalyzedComparison::Prefix { var, prop, prefix } => {
+            hasher.write_u8(12);
+            hasher.write_u32(var.0);
+            hash_prop(prop, hasher);
+            hasher.write_u64(prefix.len() as u64);
+            hasher.write(prefix.as_bytes());
+        }
         AnalyzedComparison::Exists { var, prop } => {
             hasher.write_u8(8);
             hasher.write_u32(var.0);
@@ -1606,6 +2144,37 @@ fn hash_analyzed_comparison(cmp: &AnalyzedComparison, hasher: &mut Xxh64) {
             hasher.write_u32(var.0);
             hash_prop(prop, hasher);
         }
+        AnalyzedComparison::EdgeExists {
+            var,
+            edge_type,
+            direction,
+            target,
+            predicate,
+        } => {
+            hasher.write_u8(11);
+            hasher.write_u32(var.0);
+            hasher.write_u8(match direction {
+                EdgeDirection::Out => 0,
+                EdgeDirection::In => 1,
+                EdgeDirection::Both => 2,
+            });
+            if let Some(id) = edge_type.id {
+                hasher.write_u32(id.0);
+            }
+            if let Some(name) = &edge_type.name {
+                hasher.write(name.as_bytes());
+            }
+            hasher.write_u32(target.id.0);
+            hasher.write(target.var.0.as_bytes());
+            hasher.write_u32(target.label_id.0);
+            match predicate {
+                Some(expr) => {
+                    hasher.write_u8(1);
+                    hash_analyzed_expr(expr, hasher);
+                }
+                None => hasher.write_u8(0),
+            }
+        }
     }
 }
 
@@ -1682,17 +2251,22 @@ fn hash_bound_value(bound: &Bound<Value>, hasher: &mut Xxh64) {
     }
 }
 
-fn op_name(op: &PhysicalOp) -> &'static str {
+pub(crate) fn op_name(op: &PhysicalOp) -> &'static str {
     match op {
         PhysicalOp::LabelScan { .. } => "LabelScan",
         PhysicalOp::PropIndexScan { .. } => "PropIndexScan",
+        PhysicalOp::CompositeIndexScan { .. } => "CompositeIndexScan",
         PhysicalOp::Expand { .. } => "Expand",
         PhysicalOp::Filter { .. } => "Filter",
         PhysicalOp::BoolFilter { .. } => "BoolFilter",
         PhysicalOp::Union { .. } => "Union",
         PhysicalOp::Intersect { .. } => "Intersect",
         PhysicalOp::HashJoin { .. } => "HashJoin",
+        PhysicalOp::CrossJoin => "CrossJoin",
         PhysicalOp::Distinct => "Distinct",
+        PhysicalOp::OrderBy { .. } => "OrderBy",
+        PhysicalOp::LimitPerGroup { .. } => "LimitPerGroup",
+        PhysicalOp::Limit { .. } => "Limit",
         PhysicalOp::Project { .. } => "Project",
     }
 }
@@ -1738,6 +2312,45 @@ fn op_props(op: &PhysicalOp) -> Vec<ExplainProp> {
             ));
             props
         }
+        PhysicalOp::CompositeIndexScan {
+            label,
+            label_name,
+            props: prop_ids,
+            prop_names,
+            preds,
+            selectivity,
+            as_var,
+        } => {
+            let mut props = vec![
+                ExplainProp::plain("label_id", label.0.to_string()),
+                ExplainProp::plain(
+                    "prop_ids",
+                    prop_ids
+                        .iter()
+                        .map(|prop| prop.0.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                ),
+                ExplainProp::plain("props", prop_names.join(", ")),
+                ExplainProp::plain("as", as_var.0.clone()),
+                ExplainProp::literal(
+                    "predicate",
+                    preds
+                        .iter()
+                        .map(describe_predicate)
+                        .collect::<Vec<_>>()
+                        .join(" AND "),
+                ),
+            ];
+            if let Some(name) = label_name {
+                props.insert(0, ExplainProp::plain("label", name.clone()));
+            }
+            props.push(ExplainProp::plain(
+                "selectivity",
+                fmt_selectivity(*selectivity),
+            ));
+            props
+        }
         PhysicalOp::Expand {
             from,
             to,
@@ -1785,18 +2398,51 @@ fn op_props(op: &PhysicalOp) -> Vec<ExplainProp> {
             ExplainProp::plain("left", left.0.clone()),
             ExplainProp::plain("right", right.0.clone()),
         ],
-        PhysicalOp::Distinct => Vec::new(),
-        PhysicalOp::Project { fields } => vec![ExplainProp::plain(
-            "fields",
-            fields
-                .iter()
-                .map(describe_field)
+        PhysicalOp::CrossJoin | PhysicalOp::Distinct => Vec::new(),
+        PhysicalOp::OrderBy { keys } => vec![ExplainProp::plain(
+            "keys",
+            keys.iter()
+                .map(describe_sort_key)
                 .collect::<Vec<_>>()
                 .join(", "),
         )],
-    }
-}
-
+        PhysicalOp::LimitPerGroup { group_by, n, keys } => vec![
+            ExplainProp::plain("group_by", group_by.0.clone()),
+            ExplainProp::plain("n", n.to_string()),
+            ExplainProp::plain(
+                "keys",
+                keys.iter()
+                    .map(describe_sort_key)
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ),
+        ],
+        PhysicalOp::Limit { limit, offset } => vec![
+            ExplainProp::plain(
+                "limit",
+                limit.map_or_else(|| "none".to_string(), |limit| limit.to_string()),
+            ),
+            ExplainProp::plain("offset", offset.to_string()),
+        ],
+        PhysicalOp::Project { fields } => vec![ExplainProp::plain(
+            "fields",
+            fields
+                .iter()
+                .map(describe_field)
+                .collect::<Vec<_>>()
+                .join(", "),
+        )],
+    }
+}
+
+fn describe_sort_key(key: &SortKey) -> String {
+    let direction = if key.descending { "DESC" } else { "ASC" };
+    match &key.prop_name {
+        Some(prop) => format!("{}.{} {direction}", key.var.0, prop),
+        None => format!("{} {direction}", key.var.0),
+    }
+}
+
 fn describe_predicate(pred: &PhysicalPredicate) -> String {
     match pred {
         PhysicalPredicate::Eq {
@@ -1818,6 +2464,27 @@ fn describe_predicate(pred: &PhysicalPredicate) -> String {
             bound_to_string(lower),
             bound_to_string(upper)
         ),
+        PhysicalPredicate::In {
+            var,
+            prop_name,
+            values,
+            ..
+        } => format!(
+            "{}.{} IN [{}]",
+            var.0,
+            prop_name,
+            values
+                .iter()
+                .map(literal_to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        PhysicalPredicate::Prefix {
+            var,
+            prop_name,
+            prefix,
+            ..
+        } => format!("{}.{} STARTS WITH {:?}", var.0, prop_name, prefix),
     }
 }
 
@@ -1911,6 +2578,12 @@ fn describe_comparison(cmp: &PhysicalComparison) -> String {
                 .collect::<Vec<_>>()
                 .join(", ")
         ),
+        PhysicalComparison::Prefix {
+            var,
+            prop_name,
+            prefix,
+            ..
+        } => format!("{}.{} STARTS WITH {:?}", var.0, prop_name, prefix),
         PhysicalComparison::Exists { var, prop_name, .. } => {
             format!("EXISTS({}.{})", var.0, prop_name)
         }
@@ -1920,6 +2593,37 @@ fn describe_comparison(cmp: &PhysicalComparison) -> String {
         PhysicalComparison::IsNotNull { var, prop_name, .. } => {
             format!("{}.{} IS NOT NULL", var.0, prop_name)
         }
+        PhysicalComparison::EdgeExists {
+            var,
+            dir,
+            edge_type,
+            target,
+            label_name,
+            predicate,
+            ..
+        } => {
+            let ty = edge_type
+                .as_deref()
+                .map(|ty| format!(":{ty}"))
+                .unwrap_or_default();
+            let edge = match dir {
+                Dir::Out => format!("-[{ty}]->"),
+                Dir::In => format!("<-[{ty}]-"),
+                Dir::Both => format!("-[{ty}]-"),
+            };
+            let label = label_name.as_deref().unwrap_or_default();
+            match predicate {
+                Some(expr) => format!(
+                    "EXISTS(({}){}({}:{}) WHERE {})",
+                    var.0,
+                    edge,
+                    target.0,
+                    label,
+                    describe_bool_expr(expr)
+                ),
+                None => format!("EXISTS(({}){}({}:{}))", var.0, edge, target.0, label),
+            }
+        }
     }
 }
 
@@ -1958,6 +2662,31 @@ fn describe_field(field: &ProjectField) -> String {
             Some(alias) => format!("{}.{} as {}", var.0, prop_name, alias),
             None => format!("{}.{}", var.0, prop_name),
         },
+        ProjectField::RunningCount { alias } => match alias {
+            Some(alias) => format!("running_count() as {alias}"),
+            None => "running_count()".into(),
+        },
+        ProjectField::Path { vars, alias } => {
+            let names: Vec<&str> = vars.iter().map(|var| var.0.as_str()).collect();
+            match alias {
+                Some(alias) => format!("path({}) as {alias}", names.join(", ")),
+                None => format!("path({})", names.join(", ")),
+            }
+        }
+        ProjectField::Expr { expr, alias, .. } => match alias {
+            Some(alias) => format!("{expr} as {alias}"),
+            None => expr.to_string(),
+        },
+        ProjectField::Aggregate {
+            func,
+            var,
+            prop_name,
+            alias,
+            ..
+        } => match prop_name {
+            Some(prop) => format!("{}({}.{}) as {alias}", func.name(), var.0, prop),
+            None => format!("{}({}) as {alias}", func.name(), var.0),
+        },
     }
 }
 
@@ -1965,45 +2694,176 @@ fn prop_from_predicate(pred: &PhysicalPredicate) -> Option<PropId> {
     match pred {
         PhysicalPredicate::Eq { prop, .. } => Some(*prop),
         PhysicalPredicate::Range { prop, .. } => Some(*prop),
+        PhysicalPredicate::In { prop, .. } => Some(*prop),
+        PhysicalPredicate::Prefix { prop, .. } => Some(*prop),
     }
 }
 
 fn prop_name_from_predicate(pred: &PhysicalPredicate) -> String {
     match pred {
-        PhysicalPredicate::Eq { prop_name, .. } | PhysicalPredicate::Range { prop_name, .. } => {
-            prop_name.clone()
-        }
+        PhysicalPredicate::Eq { prop_name, .. }
+        | PhysicalPredicate::Range { prop_name, .. }
+        | PhysicalPredicate::In { prop_name, .. }
+        | PhysicalPredicate::Prefix { prop_name, .. } => prop_name.clone(),
     }
 }
 
 fn cmp_anchor_class(cmp: &AnalyzedComparison) -> Option<(PropRef, AnchorScore)> {
     match cmp {
-        AnalyzedComparison::Eq { prop, .. } => Some((prop.clone(), AnchorScore::Eq)),
+        AnalyzedComparison::Eq { prop, .. } | AnalyzedComparison::In { prop, .. } => {
+            Some((prop.clone(), AnchorScore::Eq))
+        }
         AnalyzedComparison::Lt { prop, .. }
         | AnalyzedComparison::Le { prop, .. }
         | AnalyzedComparison::Gt { prop, .. }
         | AnalyzedComparison::Ge { prop, .. }
-        | AnalyzedComparison::Between { prop, .. } => Some((prop.clone(), AnchorScore::Range)),
+        | AnalyzedComparison::Between { prop, .. }
+        | AnalyzedComparison::Prefix { prop, .. } => Some((prop.clone(), AnchorScore::Range)),
+        _ => None,
+    }
+}
+
+/// Classifies `cmp` like [`cmp_anchor_class`] when a ready index on its
+/// property can answer it. Prefix predicates also need the index to hold
+/// string keys.
+fn indexed_anchor_class(
+    ctx: &PlanContext<'_>,
+    label: LabelId,
+    cmp: &AnalyzedComparison,
+) -> Result<Option<(PropRef, AnchorScore)>> {
+    let Some((prop, class)) = cmp_anchor_class(cmp) else {
+        return Ok(None);
+    };
+    let Some(def) = ctx.property_index(label, prop.id)? else {
+        return Ok(None);
+    };
+    if matches!(cmp, AnalyzedComparison::Prefix { .. }) && def.ty() != TypeTag::String {
+        return Ok(None);
+    }
+    Ok(Some((prop, class)))
+}
+
+/// Picks the composite index whose leading properties are covered by the most
+/// equality predicates, optionally followed by one range predicate on the next
+/// property, and takes the predicates it answers out of `pools`.
+///
+/// Indexes covering a single property are left to `PropIndexScan`.
+fn take_composite_predicates(
+    defs: Vec<IndexDef>,
+    pools: [&mut Vec<VarPredicate>; 3],
+) -> Option<CompositeSelection> {
+    let mut best: Vec<(usize, usize)> = Vec::new();
+    for def in &defs {
+        let mut covered = Vec::new();
+        for prop in &def.props {
+            let find = |equality: bool| {
+                pools.iter().enumerate().find_map(|(pool_idx, pool)| {
+                    pool.iter()
+                        .position(|pred| composite_class(pred, *prop) == Some(equality))
+                        .map(|idx| (pool_idx, idx))
+                })
+            };
+            if let Some(found) = find(true) {
+                covered.push(found);
+                continue;
+            }
+            if let Some(found) = find(false) {
+                covered.push(found);
+            }
+            break;
+        }
+        if covered.len() > best.len() {
+            best = covered;
+        }
+    }
+    if best.len() < 2 {
+        return None;
+    }
+    let mut order: Vec<(usize, (usize, usize))> = best.into_iter().enumerate().collect();
+    order.sort_by(|(_, a), (_, b)| b.cmp(a));
+    let mut slots: Vec<Option<VarPredicate>> = vec![None; order.len()];
+    for (slot, (pool_idx, idx)) in order {
+        slots[slot] = Some(pools[pool_idx].remove(idx));
+    }
+    let preds: Vec<VarPredicate> = slots.into_iter().flatten().collect();
+    let selectivity = preds
+        .iter()
+        .map(|pred| pred.selectivity)
+        .product::<f64>()
+        .max(MIN_SELECTIVITY);
+    Some(CompositeSelection { preds, selectivity })
+}
+
+/// Classifies `pred` as an equality (`true`) or range (`false`) a composite
+/// index can answer on `prop`.
+fn composite_class(pred: &VarPredicate, prop: PropId) -> Option<bool> {
+    let VarPredicateKind::Comparison(cmp) = &pred.kind else {
+        return None;
+    };
+    match cmp {
+        AnalyzedComparison::Eq { prop: p, .. } if p.id == prop => Some(true),
+        AnalyzedComparison::Lt { prop: p, .. }
+        | AnalyzedComparison::Le { prop: p, .. }
+        | AnalyzedComparison::Gt { prop: p, .. }
+        | AnalyzedComparison::Ge { prop: p, .. }
+        | AnalyzedComparison::Between { prop: p, .. }
+            if p.id == prop =>
+        {
+            Some(false)
+        }
         _ => None,
     }
 }
 
+/// Lists the anchor's equality and range predicates left for a label scan to
+/// evaluate; indexed ones were already taken by `take_indexed_predicates`.
+fn unindexed_predicates(
+    binding: &VarBinding,
+    preds_by_var: &HashMap<VarId, Vec<VarPredicate>>,
+) -> Vec<UnindexedPredicate> {
+    let Some(label) = binding.label.as_ref() else {
+        return Vec::new();
+    };
+    preds_by_var
+        .get(&binding.id)
+        .into_iter()
+        .flatten()
+        .filter_map(|pred| match &pred.kind {
+            VarPredicateKind::Comparison(cmp) => {
+                cmp_anchor_class(cmp).map(|(prop, class)| UnindexedPredicate {
+                    label: label.clone(),
+                    prop: prop.name,
+                    equality: class == AnchorScore::Eq,
+                    selectivity: pred.selectivity,
+                })
+            }
+            VarPredicateKind::Union { .. } => None,
+        })
+        .collect()
+}
+
 fn union_terms_indexed(
     binding: &VarBinding,
     ctx: &mut PlanContext<'_>,
     terms: &[UnionTerm],
 ) -> Result<bool> {
     for term in terms {
-        let Some((prop, _)) = cmp_anchor_class(&term.cmp) else {
-            return Ok(false);
-        };
-        if ctx.property_index(binding.label_id, prop.id)?.is_none() {
+        if indexed_anchor_class(ctx, binding.label_id, &term.cmp)?.is_none() {
             return Ok(false);
         }
     }
     Ok(true)
 }
 
+fn union_term_props(terms: &[UnionTerm]) -> Vec<String> {
+    let mut props: Vec<String> = terms
+        .iter()
+        .map(|term| prop_from_cmp(&term.cmp).name)
+        .collect();
+    props.dedup();
+    props
+}
+
 fn merge_residual(existing: Option<AnalyzedExpr>, extra: AnalyzedExpr) -> Option<AnalyzedExpr> {
     match existing {
         None => Some(extra),
@@ -2048,13 +2908,14 @@ fn comparison_selectivity(cmp: &PhysicalComparison) -> f64 {
         PhysicalComparison::Ne { .. } => 0.95,
         PhysicalComparison::Lt { .. } | PhysicalComparison::Le { .. } => 0.3,
         PhysicalComparison::Gt { .. } | PhysicalComparison::Ge { .. } => 0.3,
-        PhysicalComparison::Between { .. } => 0.2,
+        PhysicalComparison::Between { .. } | PhysicalComparison::Prefix { .. } => 0.2,
         PhysicalComparison::In {
             values, lookup: _, ..
         } => (values.len() as f64 * 0.05).clamp(0.05, 1.0),
         PhysicalComparison::Exists { .. } => 0.5,
         PhysicalComparison::IsNull { .. } => 0.1,
         PhysicalComparison::IsNotNull { .. } => 0.9,
+        PhysicalComparison::EdgeExists { .. } => 0.5,
     }
 }
 
@@ -2065,8 +2926,10 @@ fn fmt_selectivity(value: f64) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::query::builder::QueryBuilder;
+    use crate::query::ast::{AggFunc, OrderKey, OrderTarget, Projection};
+    use crate::query::builder::{ProjectionSpec, QueryBuilder};
     use crate::query::metadata::InMemoryMetadata;
+    use crate::storage::index::IndexKind;
     use crate::types::{LabelId, PropId, TypeId};
 
     fn planner_with_metadata() -> Planner {
@@ -2195,6 +3058,138 @@ mod tests {
         }
     }
 
+    #[test]
+    fn planner_uses_composite_index_for_equality_prefix_and_range() {
+        let metadata = InMemoryMetadata::new()
+            .with_label("User", LabelId(1))
+            .with_property("city", PropId(4))
+            .with_property("age", PropId(6))
+            .with_property("name", PropId(7))
+            .with_property_index_def(IndexDef {
+                label: LabelId(1),
+                props: vec![PropId(4), PropId(6)],
+                kind: IndexKind::BTree,
+                types: vec![TypeTag::String, TypeTag::Int],
+                max_key_bytes: None,
+                unique: false,
+                state: IndexState::Ready,
+            });
+        let planner = Planner::new(PlannerConfig::default(), Arc::new(metadata));
+        let ast = QueryBuilder::new()
+            .r#match("User")
+            .where_var("a", |pred| {
+                pred.gt("age", 30_i64);
+            })
+            .where_var("a", |pred| {
+                pred.eq("city", "Paris");
+            })
+            .where_var("a", |pred| {
+                pred.eq("name", "Ada");
+            })
+            .select(["a"])
+            .build()
+            .expect("builder succeeds");
+        let output = planner.plan(&ast).expect("plan succeeds");
+        let project_input = output.plan.root.inputs.first().expect("project input");
+        let PhysicalOp::Filter { pred, .. } = &project_input.op else {
+            panic!("expected residual filter, found {:?}", project_input.op);
+        };
+        assert_eq!(prop_from_predicate(pred), Some(PropId(7)));
+        match &project_input.inputs[0].op {
+            PhysicalOp::CompositeIndexScan { props, preds, .. } => {
+                assert_eq!(props, &vec![PropId(4), PropId(6)]);
+                assert!(matches!(preds[0], PhysicalPredicate::Eq { .. }));
+                assert!(matches!(preds[1], PhysicalPredicate::Range { .. }));
+            }
+            other => panic!("expected CompositeIndexScan, found {other:?}"),
+        }
+        assert_eq!(
+            output.explain.root.inputs[0].inputs[0].op,
+            "CompositeIndexScan"
+        );
+
+        // A lone predicate on the leading property uses the index as a single
+        // property scan; one on a trailing property cannot use it.
+        let leading = QueryBuilder::new()
+            .r#match("User")
+            .where_var("a", |pred| {
+                pred.eq("city", "Paris");
+            })
+            .select(["a"])
+            .build()
+            .expect("builder succeeds");
+        let output = planner.plan(&leading).expect("plan succeeds");
+        assert!(matches!(
+            output.plan.root.inputs[0].op,
+            PhysicalOp::PropIndexScan { .. }
+        ));
+        let trailing = QueryBuilder::new()
+            .r#match("User")
+            .where_var("a", |pred| {
+                pred.eq("age", 30_i64);
+            })
+            .select(["a"])
+            .build()
+            .expect("builder succeeds");
+        let output = planner.plan(&trailing).expect("plan succeeds");
+        assert!(matches!(
+            output.plan.root.inputs[0].op,
+            PhysicalOp::Filter { .. }
+        ));
+    }
+
+    #[test]
+    fn planner_uses_string_index_for_prefix_predicates() {
+        let metadata = InMemoryMetadata::new()
+            .with_label("User", LabelId(1))
+            .with_property("name", PropId(4))
+            .with_property("age", PropId(6))
+            .with_property_index_def(IndexDef {
+                label: LabelId(1),
+                props: vec![PropId(4)],
+                kind: IndexKind::BTree,
+                types: vec![TypeTag::String],
+                max_key_bytes: None,
+                unique: false,
+                state: IndexState::Ready,
+            })
+            .with_property_index(LabelId(1), PropId(6));
+        let planner = Planner::new(PlannerConfig::default(), Arc::new(metadata));
+        let prefix_on = |prop: &str| {
+            QueryBuilder::new()
+                .r#match("User")
+                .where_var("a", |pred| {
+                    pred.starts_with(prop, "Ad");
+                })
+                .select(["a"])
+                .build()
+                .expect("builder succeeds")
+        };
+
+        let output = planner.plan(&prefix_on("name")).expect("plan succeeds");
+        match &output.plan.root.inputs[0].op {
+            PhysicalOp::PropIndexScan { pred, .. } => {
+                assert!(matches!(pred, PhysicalPredicate::Prefix { prefix, .. } if prefix == "Ad"));
+            }
+            other => panic!("expected PropIndexScan, found {other:?}"),
+        }
+        assert_eq!(output.explain.root.inputs[0].op, "PropIndexScan");
+
+        // The index on `age` does not hold string keys, so the prefix is
+        // evaluated by a filter.
+        let output = planner.plan(&prefix_on("age")).expect("plan succeeds");
+        let PhysicalOp::BoolFilter { expr } = &output.plan.root.inputs[0].op else {
+            panic!(
+                "expected BoolFilter, found {:?}",
+                output.plan.root.inputs[0].op
+            );
+        };
+        assert!(matches!(
+            expr,
+            PhysicalBoolExpr::Cmp(PhysicalComparison::Prefix { .. })
+        ));
+    }
+
     #[test]
     fn planner_pushes_down_in_list_as_union() {
         let metadata = InMemoryMetadata::new()
@@ -2241,6 +3236,52 @@ mod tests {
         }
     }
 
+    #[test]
+    fn planner_probes_index_once_for_long_in_list() {
+        let names: Vec<String> = (0..12).map(|i| format!("user{i}")).collect();
+        let build = || {
+            QueryBuilder::new()
+                .r#match("User")
+                .where_var("a", |pred| {
+                    pred.in_list("name", names.clone());
+                })
+                .select(["a"])
+                .build()
+                .expect("builder succeeds")
+        };
+        let metadata = InMemoryMetadata::new()
+            .with_label("User", LabelId(1))
+            .with_property("name", PropId(4))
+            .with_property_index(LabelId(1), PropId(4));
+        let planner = Planner::new(PlannerConfig::default(), Arc::new(metadata));
+        let output = planner.plan(&build()).expect("plan succeeds");
+        let project_input = output.plan.root.inputs.first().expect("project input");
+        match &project_input.op {
+            PhysicalOp::PropIndexScan { pred, .. } => match pred {
+                PhysicalPredicate::In { values, prop, .. } => {
+                    assert_eq!(prop.0, 4);
+                    assert_eq!(values.len(), names.len());
+                }
+                other => panic!("expected in predicate, found {other:?}"),
+            },
+            other => panic!("expected PropIndexScan, found {other:?}"),
+        }
+        let explain = &output.explain.root.inputs[0];
+        assert!(explain
+            .props
+            .iter()
+            .any(|prop| prop.value.starts_with("a.name IN [\"user0\"")));
+
+        let unindexed = planner_with_metadata();
+        let output = unindexed.plan(&build()).expect("plan succeeds");
+        let project_input = output.plan.root.inputs.first().expect("project input");
+        assert!(matches!(project_input.op, PhysicalOp::BoolFilter { .. }));
+        assert!(matches!(
+            project_input.inputs[0].op,
+            PhysicalOp::LabelScan { .. }
+        ));
+    }
+
     #[test]
     fn planner_lowers_or_expression_to_union() {
         let metadata = InMemoryMetadata::new()
@@ -2387,6 +3428,233 @@ mod tests {
         }
     }
 
+    #[test]
+    fn planner_sorts_below_projection() {
+        let planner = planner_with_metadata();
+        let mut ast = QueryBuilder::new()
+            .r#match(("a", "User"))
+            .select([("a", "who")])
+            .order_by("a", "age", true)
+            .build()
+            .expect("builder succeeds");
+        ast.order_by.push(OrderKey {
+            target: OrderTarget::Alias("who".into()),
+            descending: false,
+        });
+        let output = planner.plan(&ast).expect("plan succeeds");
+        let order_by = output.plan.root.inputs.first().expect("project child");
+        match &order_by.op {
+            PhysicalOp::OrderBy { keys } => {
+                assert_eq!(keys.len(), 2);
+                assert_eq!(keys[0].prop, Some(PropId(3)));
+                assert!(keys[0].descending);
+                assert_eq!(keys[1].var.0, "a");
+                assert_eq!(keys[1].prop, None);
+            }
+            other => panic!("expected OrderBy below Project, found {other:?}"),
+        }
+        let explain = &output.explain.root.inputs[0];
+        assert_eq!(explain.op, "OrderBy");
+        assert_eq!(explain.props[0].value, "a.age DESC, a ASC");
+
+        ast.order_by[1].target = OrderTarget::Alias("missing".into());
+        let err = planner.plan(&ast).expect_err("unknown alias rejected");
+        assert!(err.to_string().contains("UnknownOrderAlias"), "{err}");
+    }
+
+    #[test]
+    fn planner_projects_aggregates() {
+        let planner = planner_with_metadata();
+        let ast = QueryBuilder::new()
+            .r#match(("a", "User"))
+            .select([
+                ProjectionSpec::aggregate(AggFunc::Count, "a", None, "users"),
+                ProjectionSpec::aggregate(AggFunc::Avg, "a", Some("age"), "mean_age"),
+            ])
+            .build()
+            .expect("builder succeeds");
+        let output = planner.plan(&ast).expect("plan succeeds");
+        match &output.plan.root.op {
+            PhysicalOp::Project { fields } => {
+                assert!(fields.iter().all(ProjectField::is_aggregate));
+                assert_eq!(fields[1].column_name(), "mean_age");
+            }
+            other => panic!("unexpected root op: {other:?}"),
+        }
+        assert_eq!(
+            output.explain.root.props[0].value,
+            "count(a) as users, avg(a.age) as mean_age"
+        );
+
+        let mut mixed = ast.clone();
+        mixed.projections.push(Projection::Var {
+            var: Var("a".into()),
+            alias: None,
+        });
+        let err = planner.plan(&mixed).expect_err("mixed projection rejected");
+        assert!(
+            err.to_string().contains("MixedAggregateProjection"),
+            "{err}"
+        );
+
+        let mut limited = ast.clone();
+        limited.limit = Some(1);
+        let err = planner.plan(&limited).expect_err("limit rejected");
+        assert!(err.to_string().contains("AggregateWithLimit"), "{err}");
+
+        let mut missing = ast;
+        missing.projections[1] = Projection::Aggregate {
+            func: AggFunc::Sum,
+            var: Var("a".into()),
+            prop: None,
+            alias: "total".into(),
+        };
+        let err = planner.plan(&missing).expect_err("sum needs a property");
+        assert!(
+            err.to_string().contains("AggregateMissingProperty"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn planner_limits_after_sorting() {
+        let planner = planner_with_metadata();
+        let ast = QueryBuilder::new()
+            .r#match(("a", "User"))
+            .select([("a", "who")])
+            .order_by("a", "age", false)
+            .limit(10)
+            .offset(5)
+            .build()
+            .expect("builder succeeds");
+        let output = planner.plan(&ast).expect("plan succeeds");
+        let limit = output.plan.root.inputs.first().expect("project child");
+        match &limit.op {
+            PhysicalOp::Limit { limit, offset } => {
+                assert_eq!(*limit, Some(10));
+                assert_eq!(*offset, 5);
+            }
+            other => panic!("expected Limit below Project, found {other:?}"),
+        }
+        assert!(matches!(limit.inputs[0].op, PhysicalOp::OrderBy { .. }));
+        let explain = &output.explain.root.inputs[0];
+        assert_eq!(explain.op, "Limit");
+        let props: Vec<(&str, &str)> = explain
+            .props
+            .iter()
+            .map(|prop| (prop.key.as_str(), prop.value.as_str()))
+            .collect();
+        assert_eq!(props, [("limit", "10"), ("offset", "5")]);
+
+        let unbounded = QueryBuilder::new()
+            .r#match(("a", "User"))
+            .select([("a", "who")])
+            .build()
+            .expect("builder succeeds");
+        let unbounded = planner.plan(&unbounded).expect("plan succeeds");
+        assert_ne!(unbounded.plan_hash, output.plan_hash);
+        assert!(!matches!(
+            unbounded.plan.root.inputs[0].op,
+            PhysicalOp::Limit { .. }
+        ));
+    }
+
+    #[test]
+    fn planner_limits_per_group_before_sorting() {
+        let planner = planner_with_metadata();
+        let ast = QueryBuilder::new()
+            .r#match(("a", "User"))
+            .where_edge("FOLLOWS", ("b", "User"))
+            .select(["a", "b"])
+            .order_by("a", "name", false)
+            .limit_per_group("a", 3, [("b", "age", true)])
+            .build()
+            .expect("builder succeeds");
+        let output = planner.plan(&ast).expect("plan succeeds");
+        let order = output.plan.root.inputs.first().expect("project child");
+        assert!(matches!(order.op, PhysicalOp::OrderBy { .. }));
+        match &order.inputs[0].op {
+            PhysicalOp::LimitPerGroup { group_by, n, keys } => {
+                assert_eq!(group_by.0, "a");
+                assert_eq!(*n, 3);
+                assert_eq!(keys.len(), 1);
+                assert!(keys[0].descending);
+            }
+            other => panic!("expected LimitPerGroup below OrderBy, found {other:?}"),
+        }
+        let explain = &output.explain.root.inputs[0].inputs[0];
+        assert_eq!(explain.op, "LimitPerGroup");
+        let props: Vec<(&str, &str)> = explain
+            .props
+            .iter()
+            .map(|prop| (prop.key.as_str(), prop.value.as_str()))
+            .collect();
+        assert_eq!(
+            props,
+            [("group_by", "a"), ("n", "3"), ("keys", "b.age DESC")]
+        );
+
+        let mut wider = ast.clone();
+        wider.limit_per_group.as_mut().expect("group limit").n = 5;
+        let wider = planner.plan(&wider).expect("plan succeeds");
+        assert_ne!(wider.plan_hash, output.plan_hash);
+
+        let mut unknown = ast;
+        unknown
+            .limit_per_group
+            .as_mut()
+            .expect("group limit")
+            .group_by = Var("z".into());
+        let err = planner
+            .plan(&unknown)
+            .expect_err("unknown group var rejected");
+        assert!(err.to_string().contains("VarNotMatched"), "{err}");
+    }
+
+    #[test]
+    fn planner_rejects_patterns_over_size_limits() {
+        let metadata = InMemoryMetadata::new()
+            .with_label("User", LabelId(1))
+            .with_edge_type("FOLLOWS", TypeId(5));
+        let config = PlannerConfig {
+            max_pattern_nodes: Some(3),
+            max_pattern_edges: Some(2),
+            ..PlannerConfig::default()
+        };
+        let planner = Planner::new(config, Arc::new(metadata));
+        let chain = |hops: usize| {
+            let mut builder = QueryBuilder::new().r#match(("a0", "User"));
+            for hop in 1..=hops {
+                builder = builder.where_edge("FOLLOWS", (format!("a{hop}").as_str(), "User"));
+            }
+            builder.select(["a0"]).build().expect("builder succeeds")
+        };
+        planner
+            .plan(&chain(2))
+            .expect("pattern within limits plans");
+
+        let err = planner.plan(&chain(3)).expect_err("too many match clauses");
+        assert!(err.to_string().contains("PatternTooLarge"), "{err}");
+
+        // Size is checked before labels resolve, so the unknown label is
+        // never looked up.
+        let mut unknown = chain(3);
+        unknown.matches[0].label = Some("Missing".into());
+        let err = planner.plan(&unknown).expect_err("size checked first");
+        assert!(err.to_string().contains("PatternTooLarge"), "{err}");
+
+        let mut edges = chain(2);
+        edges.edges.push(edges.edges[0].clone());
+        assert!(matches!(
+            planner.check_pattern_size(&edges),
+            Err(AnalyzerError::PatternTooLarge {
+                clause: "edge",
+                count: 3,
+                max: 2
+            })
+        ));
+    }
+
     #[test]
     fn planner_can_reanchor_mid_chain_using_index() {
         let metadata = InMemoryMetadata::new()
@@ -2420,6 +3688,125 @@ mod tests {
             other => panic!("expected Expand, found {other:?}"),
         }
     }
+
+    #[test]
+    fn explain_reports_expand_traversal_strategy() {
+        fn expand_strategy(backend: AdjacencyBackend) -> String {
+            let metadata = InMemoryMetadata::new()
+                .with_label("User", LabelId(1))
+                .with_edge_type("FOLLOWS", TypeId(5));
+            let config = PlannerConfig {
+                adjacency_backend: backend,
+                ..PlannerConfig::default()
+            };
+            let planner = Planner::new(config, Arc::new(metadata));
+            let ast = QueryBuilder::new()
+                .r#match(("a", "User"))
+                .where_edge("FOLLOWS", ("b", "User"))
+                .select(["a", "b"])
+                .build()
+                .expect("builder succeeds");
+            let output = planner.plan(&ast).expect("plan succeeds");
+            let expand = &output.explain.root.inputs[0];
+            assert_eq!(expand.op, "Expand");
+            expand
+                .props
+                .iter()
+                .find(|prop| prop.key == "traversal_strategy")
+                .map(|prop| prop.value.clone())
+                .expect("traversal_strategy prop")
+        }
+
+        assert_eq!(expand_strategy(AdjacencyBackend::BTree), "btree");
+        assert_eq!(expand_strategy(AdjacencyBackend::Ifa), "btree");
+        assert_eq!(expand_strategy(AdjacencyBackend::IfaOnly), "ifa");
+    }
+
+    #[test]
+    fn planner_retains_rejected_scan_candidates() {
+        let metadata = InMemoryMetadata::new()
+            .with_label("User", LabelId(1))
+            .with_property("name", PropId(4))
+            .with_property("age", PropId(3))
+            .with_edge_type("FOLLOWS", TypeId(5))
+            .with_property_index(LabelId(1), PropId(4))
+            .with_property_index(LabelId(1), PropId(3));
+        let planner = Planner::new(PlannerConfig::default(), Arc::new(metadata));
+        let ast = QueryBuilder::new()
+            .r#match(("a", "User"))
+            .where_edge("FOLLOWS", ("b", "User"))
+            .where_var("a", |pred| {
+                pred.ge("age", 21_i64);
+            })
+            .where_var("b", |pred| {
+                pred.eq("name", "Ada");
+            })
+            .select(["a", "b"])
+            .build()
+            .expect("builder succeeds");
+        let output = planner.plan(&ast).expect("plan succeeds");
+        let index_scans: Vec<&ScanCandidate> = output
+            .candidates
+            .iter()
+            .filter(|candidate| candidate.op == "PropIndexScan")
+            .collect();
+        assert_eq!(index_scans.len(), 2);
+        assert!(index_scans
+            .iter()
+            .all(|candidate| candidate.estimated_cost.is_finite()));
+        let selected: Vec<&ScanCandidate> = output
+            .candidates
+            .iter()
+            .filter(|candidate| candidate.selected)
+            .collect();
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].var.0, "b");
+        assert_eq!(selected[0].op, "PropIndexScan");
+        assert_eq!(selected[0].props, vec!["name".to_string()]);
+        let rejected = index_scans
+            .iter()
+            .find(|candidate| candidate.var.0 == "a")
+            .expect("age index candidate retained");
+        assert_eq!(rejected.props, vec!["age".to_string()]);
+        assert!(!rejected.selected);
+    }
+
+    #[test]
+    fn planner_reports_predicates_left_to_label_scan() {
+        let metadata = || {
+            InMemoryMetadata::new()
+                .with_label("User", LabelId(1))
+                .with_property("name", PropId(4))
+                .with_property("age", PropId(3))
+        };
+        let planner = Planner::new(PlannerConfig::default(), Arc::new(metadata()));
+        let ast = QueryBuilder::new()
+            .r#match(("a", "User"))
+            .where_var("a", |pred| {
+                pred.eq("name", "Ada").ge("age", 21_i64);
+            })
+            .select(["a"])
+            .build()
+            .expect("builder succeeds");
+        let output = planner.plan(&ast).expect("plan succeeds");
+        let mut unindexed: Vec<(&str, &str, bool)> = output
+            .unindexed
+            .iter()
+            .map(|pred| (pred.label.as_str(), pred.prop.as_str(), pred.equality))
+            .collect();
+        unindexed.sort();
+        assert_eq!(
+            unindexed,
+            vec![("User", "age", false), ("User", "name", true)]
+        );
+
+        let indexed = Planner::new(
+            PlannerConfig::default(),
+            Arc::new(metadata().with_property_index(LabelId(1), PropId(4))),
+        );
+        let output = indexed.plan(&ast).expect("plan succeeds");
+        assert!(output.unindexed.is_empty());
+    }
 }
 #[derive(Clone)]
 enum PushdownCandidate {
@@ -1,3 +1,4 @@
+use std::cell::RefCell;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::OnceLock;
 use std::time::Instant;
@@ -72,16 +73,41 @@ struct QueryProfileCounters {
 static PROFILE_ENABLED: OnceLock<bool> = OnceLock::new();
 static PROFILE_COUNTERS: OnceLock<QueryProfileCounters> = OnceLock::new();
 
+thread_local! {
+    /// Per-thread counters that take over from the globals while a local
+    /// profile is active on this thread.
+    static LOCAL_COUNTERS: RefCell<Option<QueryProfileSnapshot>> = const { RefCell::new(None) };
+}
+
 fn profiling_enabled() -> bool {
     *PROFILE_ENABLED.get_or_init(|| std::env::var_os("SOMBRA_PROFILE").is_some())
 }
 
+fn local_profile_active() -> bool {
+    LOCAL_COUNTERS.with(|local| local.borrow().is_some())
+}
+
+/// Starts routing this thread's query timings into a fresh local counter set.
+///
+/// Timers run regardless of `SOMBRA_PROFILE`, and nothing is recorded into
+/// the global counters until [`take_local_profile`] is called.
+pub(crate) fn begin_local_profile() {
+    LOCAL_COUNTERS.with(|local| *local.borrow_mut() = Some(QueryProfileSnapshot::default()));
+}
+
+/// Stops local profiling on this thread and returns what it captured.
+pub(crate) fn take_local_profile() -> QueryProfileSnapshot {
+    LOCAL_COUNTERS
+        .with(|local| local.borrow_mut().take())
+        .unwrap_or_default()
+}
+
 fn counters() -> Option<&'static QueryProfileCounters> {
     profiling_enabled().then(|| PROFILE_COUNTERS.get_or_init(QueryProfileCounters::default))
 }
 
 pub(crate) fn profile_timer() -> Option<Instant> {
-    profiling_enabled().then(Instant::now)
+    (profiling_enabled() || local_profile_active()).then(Instant::now)
 }
 
 pub(crate) enum QueryProfileKind {
@@ -103,10 +129,40 @@ pub(crate) fn record_profile_timer(kind: QueryProfileKind, start: Option<Instant
     let Some(start) = start else {
         return;
     };
+    let nanos = start.elapsed().as_nanos().min(u64::MAX as u128) as u64;
+    let recorded_locally = LOCAL_COUNTERS.with(|local| {
+        let mut local = local.borrow_mut();
+        let Some(snapshot) = local.as_mut() else {
+            return false;
+        };
+        let (ns, count) = match kind {
+            QueryProfileKind::ReadGuard => {
+                (&mut snapshot.read_guard_ns, &mut snapshot.read_guard_count)
+            }
+            QueryProfileKind::StreamBuild => (
+                &mut snapshot.stream_build_ns,
+                &mut snapshot.stream_build_count,
+            ),
+            QueryProfileKind::StreamIter => (
+                &mut snapshot.stream_iter_ns,
+                &mut snapshot.stream_iter_count,
+            ),
+            QueryProfileKind::PropIndex => {
+                (&mut snapshot.prop_index_ns, &mut snapshot.prop_index_count)
+            }
+            QueryProfileKind::Expand => (&mut snapshot.expand_ns, &mut snapshot.expand_count),
+            QueryProfileKind::Filter => (&mut snapshot.filter_ns, &mut snapshot.filter_count),
+        };
+        *ns = ns.saturating_add(nanos);
+        *count = count.saturating_add(1);
+        true
+    });
+    if recorded_locally {
+        return;
+    }
     let Some(counters) = counters() else {
         return;
     };
-    let nanos = start.elapsed().as_nanos().min(u64::MAX as u128) as u64;
     match kind {
         QueryProfileKind::ReadGuard => {
             counters.read_guard_ns.fetch_add(nanos, Ordering::Relaxed);
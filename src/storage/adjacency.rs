@@ -1,3 +1,4 @@
+use std::collections::HashSet;
 use std::convert::TryInto;
 
 use crate::types::{EdgeId, NodeId, TypeId};
@@ -162,6 +163,38 @@ pub struct Neighbor {
     pub ty: TypeId,
 }
 
+/// Resumable walk over the adjacency entries of a single node.
+///
+/// Created by [`Graph::neighbor_scan`] and advanced with
+/// [`Graph::next_neighbors`], which reads a bounded number of entries per call.
+/// The scan only records where it stopped, so every call must be made with a
+/// read guard on the same snapshot for the walk to be consistent.
+///
+/// [`Graph::neighbor_scan`]: crate::storage::Graph::neighbor_scan
+/// [`Graph::next_neighbors`]: crate::storage::Graph::next_neighbors
+pub struct NeighborScan {
+    pub(crate) node: NodeId,
+    pub(crate) ty: Option<TypeId>,
+    pub(crate) out_pending: bool,
+    pub(crate) in_pending: bool,
+    pub(crate) resume: Option<Vec<u8>>,
+    pub(crate) seen: Option<HashSet<NodeId>>,
+}
+
+impl NeighborScan {
+    /// Returns true once every adjacency entry has been read.
+    pub fn is_done(&self) -> bool {
+        !self.out_pending && !self.in_pending
+    }
+
+    /// Ends the walk early, e.g. after a read error.
+    pub(crate) fn stop(&mut self) {
+        self.out_pending = false;
+        self.in_pending = false;
+        self.resume = None;
+    }
+}
+
 /// Cursor for iterating through neighbors of a node.
 pub struct NeighborCursor {
     neighbors: Vec<Neighbor>,
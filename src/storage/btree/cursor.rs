@@ -203,31 +203,136 @@ impl<'a, K: KeyCodec, V: ValCodec> Cursor<'a, K, V> {
     }
 
     fn lower_allows(&self, key: &[u8]) -> bool {
-        match &self.lower {
-            EncodedBound::Unbounded => true,
-            EncodedBound::Included(bound) => {
-                matches!(
-                    K::compare_encoded(key, bound),
-                    Ordering::Equal | Ordering::Greater
-                )
-            }
-            EncodedBound::Excluded(bound) => {
-                matches!(K::compare_encoded(key, bound), Ordering::Greater)
-            }
-        }
+        self.lower.allows_from_below::<K>(key)
     }
 
     fn is_past_upper(&self, key: &[u8]) -> bool {
-        match &self.upper {
-            EncodedBound::Unbounded => false,
-            EncodedBound::Included(bound) => {
-                matches!(K::compare_encoded(key, bound), Ordering::Greater)
+        self.upper.exceeded_by::<K>(key)
+    }
+}
+
+/// A cursor that walks a key range of a B+ tree from the highest key down.
+///
+/// Leaves are followed through their left-sibling links, so stopping after a
+/// few entries only touches the leaves at the top of the range.
+pub struct RevCursor<'a, K: KeyCodec, V: ValCodec> {
+    tree: &'a BTree<K, V>,
+    tx: &'a ReadGuard,
+    lower: EncodedBound,
+    upper: EncodedBound,
+    current_page: Option<PageRef>,
+    current_header: Option<page::Header>,
+    slot_extents: Option<page::SlotExtents>,
+    /// Number of slots in the current leaf not yet visited; the next
+    /// candidate is the slot just below this count.
+    remaining: usize,
+    done: bool,
+}
+
+impl<'a, K: KeyCodec, V: ValCodec> RevCursor<'a, K, V> {
+    pub(crate) fn new(
+        tree: &'a BTree<K, V>,
+        tx: &'a ReadGuard,
+        lo: Bound<K>,
+        hi: Bound<K>,
+    ) -> Result<Self> {
+        let lower = EncodedBound::from_bound::<K>(lo);
+        let upper = EncodedBound::from_bound::<K>(hi);
+        let done = EncodedBound::range_is_empty::<K>(&lower, &upper);
+        let mut cursor = Self {
+            tree,
+            tx,
+            lower,
+            upper,
+            current_page: None,
+            current_header: None,
+            slot_extents: None,
+            remaining: 0,
+            done,
+        };
+        if !cursor.done {
+            let (page, header) = match cursor.upper.key_bytes() {
+                Some(key) => tree.find_leaf(tx, key)?,
+                None => tree.find_rightmost_leaf(tx)?,
+            };
+            cursor.set_current_page(page, header)?;
+        }
+        Ok(cursor)
+    }
+
+    /// Steps the cursor back and returns the next lower key-value pair, if any.
+    pub fn next(&mut self) -> Result<Option<(K, V)>> {
+        if self.done {
+            return Ok(None);
+        }
+        loop {
+            if self.remaining == 0 {
+                if !self.retreat_to_prev_leaf()? {
+                    return Ok(None);
+                }
+                continue;
             }
-            EncodedBound::Excluded(bound) => {
-                !matches!(K::compare_encoded(key, bound), Ordering::Less)
+            let (page, extents) = match (&self.current_page, &self.slot_extents) {
+                (Some(page), Some(extents)) => (page, extents),
+                _ => {
+                    self.finish();
+                    return Ok(None);
+                }
+            };
+            let payload = page::payload(page.data())?;
+            let rec_slice = extents.record_slice(payload, self.remaining - 1)?;
+            record_btree_leaf_key_decodes(1);
+            let record = page::decode_leaf_record(rec_slice)?;
+            if self.upper.exceeded_by::<K>(record.key) {
+                self.remaining -= 1;
+                continue;
             }
+            if !self.lower.allows_from_below::<K>(record.key) {
+                self.finish();
+                return Ok(None);
+            }
+            let value = V::decode_val(record.value)?;
+            let typed_key = K::decode_key(record.key)?;
+            self.remaining -= 1;
+            return Ok(Some((typed_key, value)));
         }
     }
+
+    fn retreat_to_prev_leaf(&mut self) -> Result<bool> {
+        let prev_id = match self
+            .current_header
+            .as_ref()
+            .and_then(|header| header.left_sibling)
+        {
+            Some(id) => id,
+            None => {
+                self.finish();
+                return Ok(false);
+            }
+        };
+        let (page, header) = self.tree.load_leaf_page(self.tx, prev_id)?;
+        self.set_current_page(page, header)?;
+        Ok(true)
+    }
+
+    fn finish(&mut self) {
+        self.done = true;
+        self.current_page = None;
+        self.current_header = None;
+        self.slot_extents = None;
+        self.remaining = 0;
+    }
+
+    fn set_current_page(&mut self, page: PageRef, header: page::Header) -> Result<()> {
+        let payload = page::payload(page.data())?;
+        let slots = header.slot_directory(page.data())?;
+        let extents = page::SlotExtents::build(&header, payload, &slots)?;
+        self.remaining = slots.len();
+        self.current_page = Some(page);
+        self.current_header = Some(header);
+        self.slot_extents = Some(extents);
+        Ok(())
+    }
 }
 
 #[derive(Clone)]
@@ -254,6 +359,35 @@ impl EncodedBound {
         }
     }
 
+    /// Returns whether `key` lies on the allowed side of this lower bound.
+    fn allows_from_below<K: KeyCodec>(&self, key: &[u8]) -> bool {
+        match self {
+            EncodedBound::Unbounded => true,
+            EncodedBound::Included(bound) => {
+                matches!(
+                    K::compare_encoded(key, bound),
+                    Ordering::Equal | Ordering::Greater
+                )
+            }
+            EncodedBound::Excluded(bound) => {
+                matches!(K::compare_encoded(key, bound), Ordering::Greater)
+            }
+        }
+    }
+
+    /// Returns whether `key` lies beyond this upper bound.
+    fn exceeded_by<K: KeyCodec>(&self, key: &[u8]) -> bool {
+        match self {
+            EncodedBound::Unbounded => false,
+            EncodedBound::Included(bound) => {
+                matches!(K::compare_encoded(key, bound), Ordering::Greater)
+            }
+            EncodedBound::Excluded(bound) => {
+                !matches!(K::compare_encoded(key, bound), Ordering::Less)
+            }
+        }
+    }
+
     fn key_bytes(&self) -> Option<&[u8]> {
         match self {
             EncodedBound::Unbounded => None,
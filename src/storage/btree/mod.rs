@@ -9,10 +9,11 @@ pub mod page;
 pub mod codecs;
 mod cursor;
 mod key_cursor;
+pub(crate) mod relocate;
 mod stats;
 mod tree;
 
-pub use cursor::Cursor;
+pub use cursor::{Cursor, RevCursor};
 pub(crate) use key_cursor::KeyCursor;
 pub use stats::BTreeStats;
 pub use tree::{BTree, BTreeOptions, KeyCodec, PutItem, ValCodec};
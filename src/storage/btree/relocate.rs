@@ -0,0 +1,156 @@
+//! Moves non-root B+ tree pages to a different page id.
+//!
+//! Relocation copies the page image and rewrites every pointer that names the
+//! page: the parent's child record, the sibling links, and (for internal
+//! pages) the parent pointer of each child. Planning verifies that all of
+//! those back-references agree before anything is written, so a page whose
+//! links look inconsistent is simply left where it is.
+
+use crate::primitives::pager::{PageStore, WriteGuard};
+use crate::storage::btree::page::{self, BTreePageKind};
+use crate::types::page::{header, PageHeader, PageKind, PAGE_HDR_LEN};
+use crate::types::{PageId, Result, SombraError};
+
+/// Pointer updates required to move a single B+ tree page.
+#[derive(Debug)]
+pub(crate) struct Relocation {
+    page: PageId,
+    parent: PageId,
+    parent_slot: usize,
+    left_sibling: Option<PageId>,
+    right_sibling: Option<PageId>,
+    children: Vec<PageId>,
+}
+
+impl Relocation {
+    /// Plans the relocation of `page_id`.
+    ///
+    /// Returns `None` when the page is not a B+ tree page, is a tree root
+    /// (roots are owned by catalog metadata), or any neighbour disagrees about
+    /// its links.
+    pub(crate) fn plan(
+        store: &dyn PageStore,
+        tx: &mut WriteGuard<'_>,
+        page_id: PageId,
+    ) -> Result<Option<Self>> {
+        let page = store.get_page_with_write(tx, page_id)?;
+        let data = page.data();
+        let stage1 = PageHeader::decode(&data[..PAGE_HDR_LEN])?;
+        if !matches!(stage1.kind, PageKind::BTreeLeaf | PageKind::BTreeInternal) {
+            return Ok(None);
+        }
+        let header = page::Header::parse(data)?;
+        let Some(parent) = header.parent else {
+            return Ok(None);
+        };
+        let children = match header.kind {
+            BTreePageKind::Leaf => Vec::new(),
+            BTreePageKind::Internal => internal_children(&header, data)?,
+        };
+        drop(page);
+
+        let parent_page = store.get_page_with_write(tx, parent)?;
+        let parent_data = parent_page.data();
+        let parent_header = page::Header::parse(parent_data)?;
+        if parent_header.kind != BTreePageKind::Internal {
+            return Ok(None);
+        }
+        let Some(parent_slot) = internal_children(&parent_header, parent_data)?
+            .iter()
+            .position(|child| *child == page_id)
+        else {
+            return Ok(None);
+        };
+        drop(parent_page);
+
+        if let Some(left) = header.left_sibling {
+            let left_page = store.get_page_with_write(tx, left)?;
+            if page::Header::parse(left_page.data())?.right_sibling != Some(page_id) {
+                return Ok(None);
+            }
+        }
+        if let Some(right) = header.right_sibling {
+            let right_page = store.get_page_with_write(tx, right)?;
+            if page::Header::parse(right_page.data())?.left_sibling != Some(page_id) {
+                return Ok(None);
+            }
+        }
+        for child in &children {
+            let child_page = store.get_page_with_write(tx, *child)?;
+            if page::Header::parse(child_page.data())?.parent != Some(page_id) {
+                return Ok(None);
+            }
+        }
+
+        Ok(Some(Self {
+            page: page_id,
+            parent,
+            parent_slot,
+            left_sibling: header.left_sibling,
+            right_sibling: header.right_sibling,
+            children,
+        }))
+    }
+
+    /// Copies the page into `target` and repoints all neighbours at it.
+    ///
+    /// The original page is left untouched; callers free it once the move
+    /// succeeds.
+    pub(crate) fn apply(
+        &self,
+        store: &dyn PageStore,
+        tx: &mut WriteGuard<'_>,
+        target: PageId,
+    ) -> Result<()> {
+        let image = store.get_page_with_write(tx, self.page)?.data().to_vec();
+        {
+            let mut page = tx.page_mut(target)?;
+            let buf = page.data_mut();
+            if buf.len() != image.len() {
+                return Err(SombraError::Invalid("relocation target size mismatch"));
+            }
+            buf.copy_from_slice(&image);
+            buf[header::PAGE_NO].copy_from_slice(&target.0.to_be_bytes());
+        }
+        {
+            let mut parent = tx.page_mut(self.parent)?;
+            let header = page::Header::parse(parent.data())?;
+            let offset = record_offset(&header, parent.data(), self.parent_slot)?;
+            let payload = page::payload_mut(parent.data_mut())?;
+            payload[offset..offset + 8].copy_from_slice(&target.0.to_be_bytes());
+        }
+        if let Some(left) = self.left_sibling {
+            let mut sibling = tx.page_mut(left)?;
+            let payload = page::payload_mut(sibling.data_mut())?;
+            page::set_right_sibling(payload, Some(target));
+        }
+        if let Some(right) = self.right_sibling {
+            let mut sibling = tx.page_mut(right)?;
+            let payload = page::payload_mut(sibling.data_mut())?;
+            page::set_left_sibling(payload, Some(target));
+        }
+        for child in &self.children {
+            let mut page = tx.page_mut(*child)?;
+            let payload = page::payload_mut(page.data_mut())?;
+            page::set_parent(payload, Some(target));
+        }
+        Ok(())
+    }
+}
+
+fn internal_children(header: &page::Header, data: &[u8]) -> Result<Vec<PageId>> {
+    (0..header.slot_count as usize)
+        .map(|slot| {
+            let record = page::record_slice(header, data, slot)?;
+            Ok(page::decode_internal_record(record)?.child)
+        })
+        .collect()
+}
+
+fn record_offset(header: &page::Header, data: &[u8], slot: usize) -> Result<usize> {
+    let offset = header.slot_directory(data)?.get(slot)? as usize;
+    if offset + page::INTERNAL_RECORD_HEADER_LEN > page::payload(data)?.len() {
+        return Err(SombraError::Corruption("internal record beyond payload"));
+    }
+    Ok(offset)
+}
@@ -477,6 +477,53 @@ fn range_iterates_with_bounds() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn range_rev_walks_leaves_from_the_top() -> Result<()> {
+    let dir = tempdir().map_err(SombraError::Io)?;
+    let path = dir.path().join("btree_range_rev.db");
+    let mut options = PagerOptions::default();
+    options.page_size = 512;
+    let pager = Arc::new(Pager::create(&path, options)?);
+    let store: Arc<dyn PageStore> = pager.clone();
+    let tree = BTree::<u64, u64>::open_or_create(&store, BTreeOptions::default())?;
+
+    // Filling even keys first and odd keys second splits leaves in the
+    // middle of the tree as well as at its right edge.
+    let mut write = pager.begin_write()?;
+    for key in (0u64..400).step_by(2).chain((1u64..400).step_by(2)) {
+        tree.put(&mut write, &key, &(key + 10_000))?;
+    }
+    pager.commit(write)?;
+
+    let read = pager.begin_read()?;
+    let bounds = [
+        (Bound::Unbounded, Bound::Unbounded),
+        (Bound::Included(50), Bound::Excluded(300)),
+        (Bound::Excluded(99), Bound::Included(220)),
+        (Bound::Included(150), Bound::Included(180)),
+        (Bound::Excluded(30), Bound::Included(30)),
+    ];
+    for (lo, hi) in bounds {
+        let mut forward = Vec::new();
+        let mut cursor = tree.range(&read, lo, hi)?;
+        while let Some(entry) = cursor.next()? {
+            forward.push(entry);
+        }
+        let mut backward = Vec::new();
+        let mut cursor = tree.range_rev(&read, lo, hi)?;
+        while let Some(entry) = cursor.next()? {
+            backward.push(entry);
+        }
+        backward.reverse();
+        assert_eq!(backward, forward, "bounds {lo:?}..{hi:?}");
+    }
+
+    let mut top = tree.range_rev(&read, Bound::Unbounded, Bound::Included(399))?;
+    assert_eq!(top.next()?, Some((399, 10_399)));
+    assert_eq!(top.next()?, Some((398, 10_398)));
+    Ok(())
+}
+
 #[test]
 fn root_split_creates_new_internal() -> Result<()> {
     let dir = tempdir().map_err(SombraError::Io)?;
@@ -560,6 +607,37 @@ fn delete_existing_key_removes_entry() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn delete_first_key_of_root_leaf_with_shorter_successor() -> Result<()> {
+    let dir = tempdir().map_err(SombraError::Io)?;
+    let path = dir.path().join("btree_delete_root_first.db");
+    let pager = Arc::new(Pager::create(&path, PagerOptions::default())?);
+    let store: Arc<dyn PageStore> = pager.clone();
+    let tree = BTree::<Vec<u8>, u64>::open_or_create(&store, BTreeOptions::default())?;
+
+    let keys: Vec<Vec<u8>> = vec![b"Alice".to_vec(), b"Bob".to_vec(), b"Carol".to_vec()];
+    {
+        let mut write = pager.begin_write()?;
+        for (idx, key) in keys.iter().enumerate() {
+            tree.put(&mut write, key, &(idx as u64))?;
+        }
+        pager.commit(write)?;
+    }
+    {
+        let mut write = pager.begin_write()?;
+        for key in &keys[..2] {
+            assert!(tree.delete(&mut write, key)?);
+        }
+        pager.commit(write)?;
+    }
+
+    let read = pager.begin_read()?;
+    assert_eq!(tree.get(&read, &keys[0])?, None);
+    assert_eq!(tree.get(&read, &keys[1])?, None);
+    assert_eq!(tree.get(&read, &keys[2])?, Some(2));
+    Ok(())
+}
+
 #[test]
 fn delete_rebalances_via_left_sibling_borrow() -> Result<()> {
     let dir = tempdir().map_err(SombraError::Io)?;
@@ -956,6 +1034,38 @@ fn delete_sequence_preserves_map_equivalence() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn delete_many_in_one_transaction_empties_leaves() -> Result<()> {
+    let dir = tempdir().map_err(SombraError::Io)?;
+    let path = dir.path().join("btree_delete_bulk.db");
+    let mut options = PagerOptions::default();
+    options.page_size = 512;
+    let pager = Arc::new(Pager::create(&path, options)?);
+    let store: Arc<dyn PageStore> = pager.clone();
+    let tree = BTree::<u64, u64>::open_or_create(&store, BTreeOptions::default())?;
+
+    let mut write = pager.begin_write()?;
+    for key in 0u64..200 {
+        tree.put(&mut write, &key, &(key + 7))?;
+    }
+    pager.commit(write)?;
+
+    // Leaves drained slot by slot in one transaction must not reuse cached
+    // allocator layouts once the final record is removed.
+    let mut write = pager.begin_write()?;
+    for key in 0u64..150 {
+        assert!(tree.delete(&mut write, &key)?);
+    }
+    pager.commit(write)?;
+
+    let read = pager.begin_read()?;
+    for key in 0u64..200 {
+        let expected = (key >= 150).then_some(key + 7);
+        assert_eq!(tree.get(&read, &key)?, expected);
+    }
+    Ok(())
+}
+
 #[test]
 fn delete_collapse_root_to_leaf() -> Result<()> {
     let dir = tempdir().map_err(SombraError::Io)?;
@@ -80,6 +80,52 @@ impl<K: KeyCodec, V: ValCodec> BTree<K, V> {
         Ok(())
     }
 
+    /// Visits the key-value pairs with keys in `lo..=hi` using a write transaction.
+    pub fn range_with_write<F>(
+        &self,
+        tx: &mut WriteGuard<'_>,
+        lo: &K,
+        hi: &K,
+        mut visit: F,
+    ) -> Result<()>
+    where
+        F: FnMut(K, V) -> Result<()>,
+    {
+        if self.root.load(AtomicOrdering::SeqCst) == 0 {
+            return Ok(());
+        }
+        let mut lo_key = Vec::new();
+        K::encode_key(lo, &mut lo_key);
+        let mut hi_key = Vec::new();
+        K::encode_key(hi, &mut hi_key);
+        let (mut current, _, _) = self.find_leaf_mut(tx, &lo_key)?;
+        loop {
+            let page = tx.page_mut(current)?;
+            let header = page::Header::parse(page.data())?;
+            let slot_view = SlotView::new(&header, page.data())?;
+            for idx in 0..slot_view.len() {
+                let rec_slice = slot_view.slice(idx)?;
+                record_btree_leaf_key_decodes(1);
+                let record = page::decode_leaf_record(rec_slice)?;
+                if K::compare_encoded(record.key, &lo_key) == Ordering::Less {
+                    continue;
+                }
+                if K::compare_encoded(record.key, &hi_key) == Ordering::Greater {
+                    return Ok(());
+                }
+                let value = V::decode_val(record.value)?;
+                let key = K::decode_key(record.key)?;
+                visit(key, value)?;
+            }
+            let next = header.right_sibling;
+            drop(page);
+            match next {
+                Some(id) => current = id,
+                None => return Ok(()),
+            }
+        }
+    }
+
     /// Retrieves the value associated with the given key, if it exists, using a read transaction.
     pub fn get(&self, tx: &ReadGuard, key: &K) -> Result<Option<V>> {
         let mut encoded_key = Vec::new();
@@ -217,6 +263,8 @@ impl<K: KeyCodec, V: ValCodec> BTree<K, V> {
         let needs_rebalance = if entries.is_empty() {
             let mut page = tx.page_mut(leaf_id)?;
             self.write_leaf_empty(&mut page, &header, &[], high_fence.as_slice())?;
+            // The rewrite bypasses the allocator, so any cached layout is stale.
+            self.leaf_allocator_cache(tx).take(leaf_id);
             self.stats.inc_leaf_rebuilds();
             drop(page);
             true
@@ -259,9 +307,19 @@ impl<K: KeyCodec, V: ValCodec> BTree<K, V> {
 
             if !applied_in_place {
                 if !has_parent {
-                    return Err(SombraError::Invalid(
-                        "leaf layout after delete exceeds capacity",
-                    ));
+                    // A root leaf has no separator to fix up, so rewrite it
+                    // with the remaining entries and the new low fence.
+                    let mut page = tx.page_mut(leaf_id)?;
+                    self.rebuild_leaf_payload(
+                        tx,
+                        &mut page,
+                        &header,
+                        new_low.as_slice(),
+                        high_fence.as_slice(),
+                        &entries,
+                    )?;
+                    self.stats.inc_leaf_rebuilds();
+                    return Ok(true);
                 }
                 rebalance_snapshot = Some(LeafSnapshot {
                     entries: entries.clone(),
@@ -312,6 +370,16 @@ impl<K: KeyCodec, V: ValCodec> BTree<K, V> {
         Cursor::new(self, tx, lo, hi)
     }
 
+    /// Returns a cursor that walks the specified range from the highest key down.
+    pub fn range_rev<'a>(
+        &'a self,
+        tx: &'a ReadGuard,
+        lo: Bound<K>,
+        hi: Bound<K>,
+    ) -> Result<RevCursor<'a, K, V>> {
+        RevCursor::new(self, tx, lo, hi)
+    }
+
     pub(crate) fn find_leaf(&self, tx: &ReadGuard, key: &[u8]) -> Result<(PageRef, page::Header)> {
         let mut current = PageId(self.root.load(AtomicOrdering::SeqCst));
         loop {
@@ -382,6 +450,34 @@ impl<K: KeyCodec, V: ValCodec> BTree<K, V> {
         }
     }
 
+    pub(crate) fn find_rightmost_leaf(&self, tx: &ReadGuard) -> Result<(PageRef, page::Header)> {
+        let mut current = PageId(self.root.load(AtomicOrdering::SeqCst));
+        loop {
+            let page = self.store.get_page(tx, current)?;
+            let header = page::Header::parse(page.data())?;
+            match header.kind {
+                page::BTreePageKind::Leaf => {
+                    self.stats.inc_leaf_searches();
+                    return Ok((page, header));
+                }
+                page::BTreePageKind::Internal => {
+                    self.stats.inc_internal_searches();
+                    let slot_view = SlotView::new(&header, page.data())?;
+                    if slot_view.len() == 0 {
+                        return Err(SombraError::Corruption("internal node without slots"));
+                    }
+                    let child = {
+                        let rec_slice = slot_view.slice(slot_view.len() - 1)?;
+                        let record = page::decode_internal_record(rec_slice)?;
+                        record.child
+                    };
+                    drop(page);
+                    current = child;
+                }
+            }
+        }
+    }
+
     pub(crate) fn load_leaf_page(
         &self,
         tx: &ReadGuard,
@@ -4,7 +4,7 @@ use std::ops::Bound;
 use std::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
 use std::sync::Arc;
 
-use super::super::cursor::Cursor;
+use super::super::cursor::{Cursor, RevCursor};
 use super::super::page;
 use super::super::stats::{BTreeStats, BTreeStatsSnapshot};
 use crate::primitives::pager::{PageMut, PageRef, PageStore, ReadGuard, WriteGuard};
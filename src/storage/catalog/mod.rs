@@ -1,5 +1,7 @@
 #![forbid(unsafe_code)]
 
+use std::collections::HashMap;
+use std::ops::Bound;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
@@ -34,6 +36,56 @@ impl Default for DictOptions {
     }
 }
 
+/// Schema role a dictionary name is interned for.
+///
+/// The dictionary is a single namespace, so the same string can serve as a
+/// label, an edge type and a property key. Each role keeps its own count of
+/// distinct names for [`Dict::intern_as`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum NameKind {
+    /// A node label.
+    Label,
+    /// An edge type.
+    EdgeType,
+    /// A property key.
+    PropKey,
+}
+
+impl NameKind {
+    /// Every role, in the order their records are stored.
+    pub const ALL: [NameKind; 3] = [NameKind::Label, NameKind::EdgeType, NameKind::PropKey];
+
+    fn tag(self) -> u64 {
+        match self {
+            NameKind::Label => 1,
+            NameKind::EdgeType => 2,
+            NameKind::PropKey => 3,
+        }
+    }
+
+    /// Key of the record marking `id` as a name of this role.
+    fn member_key(self, id: StrId) -> u64 {
+        (self.tag() << 32) | u64::from(id.0)
+    }
+
+    /// Key of the record holding this role's distinct name count. String ids
+    /// start at 1, so id 0 never collides with a member.
+    fn count_key(self) -> u64 {
+        self.tag() << 32
+    }
+
+    fn limit_error(self) -> &'static str {
+        match self {
+            NameKind::Label => "distinct label limit exceeded",
+            NameKind::EdgeType => "distinct edge type limit exceeded",
+            NameKind::PropKey => "distinct property key limit exceeded",
+        }
+    }
+}
+
+/// Key of the record noting that every existing name has a recorded role.
+const NAME_KINDS_COMPLETE_KEY: u64 = 0;
+
 /// Represents how a string is stored in the dictionary.
 ///
 /// Strings can be stored in two ways:
@@ -231,10 +283,14 @@ impl ValCodec for StrEntry {
 /// Short strings (up to `inline_limit` bytes) are stored inline in the B-Tree,
 /// while longer strings are stored in the variable-length store (VStore) to avoid
 /// excessive B-Tree node bloat.
+///
+/// A third B-Tree records the [`NameKind`] roles each name was interned for,
+/// along with a per-role count of distinct names.
 pub struct Dict {
     store: Arc<dyn PageStore>,
     s2i: BTree<Vec<u8>, u64>,
     i2s: BTree<u64, StrEntry>,
+    kinds: BTree<u64, u64>,
     vstore: VStore,
     opts: DictOptions,
     metrics: Arc<DictMetrics>,
@@ -266,10 +322,17 @@ impl Dict {
         i2s_opts.root_page = (meta.dict_id_to_str_root.0 != 0).then_some(meta.dict_id_to_str_root);
         let i2s = BTree::<u64, StrEntry>::open_or_create(&store, i2s_opts)?;
 
+        let mut kinds_opts = BTreeOptions::default();
+        kinds_opts.checksum_verify_on_read = opts.checksum_verify_on_read;
+        kinds_opts.root_page =
+            (meta.dict_name_kind_root.0 != 0).then_some(meta.dict_name_kind_root);
+        let kinds = BTree::<u64, u64>::open_or_create(&store, kinds_opts)?;
+
         let dict = Dict {
             store,
             s2i,
             i2s,
+            kinds,
             vstore,
             opts,
             metrics: Arc::new(DictMetrics::default()),
@@ -321,6 +384,19 @@ impl Dict {
         }
     }
 
+    /// Looks up a string through a write transaction, so names it interned
+    /// are found before it commits.
+    pub fn lookup_with_write(&self, tx: &mut WriteGuard<'_>, s: &str) -> Result<Option<StrId>> {
+        let key = encode_string_key(s)?;
+        match self.s2i.get_with_write(tx, &key)? {
+            Some(id) if id > u32::MAX as u64 => {
+                Err(SombraError::Corruption("string id exceeds u32 range"))
+            }
+            Some(id) => Ok(Some(StrId(id as u32))),
+            None => Ok(None),
+        }
+    }
+
     /// Interns a string, returning its unique identifier.
     ///
     /// If the string already exists in the dictionary, returns its existing identifier.
@@ -359,6 +435,101 @@ impl Dict {
         Ok(id)
     }
 
+    /// Interns a string as a name of the given schema role.
+    ///
+    /// The first time a string is interned for `kind` it counts as a new
+    /// distinct name of that role; interning it again never counts. Counts are
+    /// stored with the dictionary, so they survive reopening.
+    ///
+    /// # Parameters
+    /// * `tx` - The write transaction to use for modifications.
+    /// * `s` - The string to intern.
+    /// * `kind` - The role the name plays.
+    /// * `limit` - Maximum number of distinct names of this role, if any.
+    ///
+    /// # Errors
+    /// Returns [`SombraError::Invalid`] if the name is new to `kind` and the
+    /// role already holds `limit` distinct names, plus any error from
+    /// [`Dict::intern`].
+    pub fn intern_as(
+        &self,
+        tx: &mut WriteGuard<'_>,
+        s: &str,
+        kind: NameKind,
+        limit: Option<usize>,
+    ) -> Result<StrId> {
+        let id = self.intern(tx, s)?;
+        if self
+            .kinds
+            .get_with_write(tx, &kind.member_key(id))?
+            .is_some()
+        {
+            return Ok(id);
+        }
+        let count = self
+            .kinds
+            .get_with_write(tx, &kind.count_key())?
+            .unwrap_or(0);
+        if limit.is_some_and(|limit| count >= limit as u64) {
+            return Err(SombraError::Invalid(kind.limit_error()));
+        }
+        self.kinds.put(tx, &kind.member_key(id), &0)?;
+        self.kinds.put(tx, &kind.count_key(), &(count + 1))?;
+        self.sync_roots(tx)?;
+        trace!(
+            id = id.0,
+            ?kind,
+            count = count + 1,
+            "dict.intern_as.new_name"
+        );
+        Ok(id)
+    }
+
+    /// Returns how many distinct names have been interned for `kind`.
+    pub fn name_count(&self, tx: &ReadGuard, kind: NameKind) -> Result<u64> {
+        Ok(self.kinds.get(tx, &kind.count_key())?.unwrap_or(0))
+    }
+
+    /// Returns whether every name in the dictionary has its roles recorded.
+    ///
+    /// Dictionaries written before roles were tracked report `false` until
+    /// [`Dict::backfill_name_kinds`] runs.
+    pub fn name_kinds_complete(&self) -> Result<bool> {
+        let read = self.store.begin_latest_committed_read()?;
+        Ok(self.kinds.get(&read, &NAME_KINDS_COMPLETE_KEY)?.is_some())
+    }
+
+    /// Records the roles of names stored before roles were tracked.
+    ///
+    /// `names` lists the string ids found in use for each role. Ids already
+    /// recorded are skipped, so names interned through [`Dict::intern_as`]
+    /// before the backfill are not counted twice. Limits are not applied.
+    pub fn backfill_name_kinds(
+        &self,
+        tx: &mut WriteGuard<'_>,
+        names: &[(NameKind, Vec<StrId>)],
+    ) -> Result<()> {
+        for (kind, ids) in names {
+            let mut count = self
+                .kinds
+                .get_with_write(tx, &kind.count_key())?
+                .unwrap_or(0);
+            for id in ids {
+                if self
+                    .kinds
+                    .get_with_write(tx, &kind.member_key(*id))?
+                    .is_none()
+                {
+                    self.kinds.put(tx, &kind.member_key(*id), &0)?;
+                    count += 1;
+                }
+            }
+            self.kinds.put(tx, &kind.count_key(), &count)?;
+        }
+        self.kinds.put(tx, &NAME_KINDS_COMPLETE_KEY, &1)?;
+        self.sync_roots(tx)
+    }
+
     /// Resolves a string identifier back to its original string.
     ///
     /// # Parameters
@@ -395,12 +566,95 @@ impl Dict {
         }
     }
 
+    /// Resolves a string identifier inside a write transaction, including
+    /// strings interned by that transaction and not yet committed.
+    pub fn resolve_with_write(&self, tx: &mut WriteGuard<'_>, id: StrId) -> Result<String> {
+        self.metrics.resolve_call();
+        let bytes = match self.i2s.get_with_write(tx, &u64::from(id.0))? {
+            Some(StrEntry::Inline(bytes)) => bytes,
+            Some(StrEntry::VRef(vref)) => self.vstore.read_with_write(tx, vref)?,
+            None => {
+                self.metrics.resolve_miss();
+                return Err(SombraError::NotFound);
+            }
+        };
+        String::from_utf8(bytes)
+            .map_err(|_| SombraError::Corruption("dictionary entry not valid UTF-8"))
+    }
+
     /// Resolves a string identifier using a fresh read guard.
     pub fn resolve_str(&self, id: StrId) -> Result<String> {
         let read = self.store.begin_latest_committed_read()?;
         self.resolve(&read, id)
     }
 
+    /// Resolves many string identifiers under a single read guard.
+    ///
+    /// # Parameters
+    /// * `ids` - The identifiers to resolve; duplicates are looked up once.
+    ///
+    /// # Returns
+    /// One entry per input id, in input order. Identifiers missing from the
+    /// dictionary yield `None` instead of failing the whole batch.
+    ///
+    /// # Errors
+    /// Returns an error if a stored entry is not valid UTF-8 or a storage
+    /// operation fails.
+    pub fn resolve_many(&self, ids: &[StrId]) -> Result<Vec<Option<String>>> {
+        let read = self.store.begin_latest_committed_read()?;
+        let mut cache: HashMap<StrId, Option<String>> = HashMap::with_capacity(ids.len());
+        let mut out = Vec::with_capacity(ids.len());
+        for &id in ids {
+            if let Some(hit) = cache.get(&id) {
+                out.push(hit.clone());
+                continue;
+            }
+            let resolved = match self.resolve(&read, id) {
+                Ok(name) => Some(name),
+                Err(SombraError::NotFound) => None,
+                Err(err) => return Err(err),
+            };
+            cache.insert(id, resolved.clone());
+            out.push(resolved);
+        }
+        Ok(out)
+    }
+
+    /// Collects every string-to-id entry for integrity checks.
+    ///
+    /// Keys that do not decode to a string yield `Err` in place of the
+    /// string, so one damaged entry does not hide the rest.
+    pub fn debug_collect_str_to_id(&self, tx: &ReadGuard) -> Result<Vec<(Result<String>, u64)>> {
+        let mut cursor = self.s2i.range(tx, Bound::Unbounded, Bound::Unbounded)?;
+        let mut entries = Vec::new();
+        while let Some((key, id)) = cursor.next()? {
+            entries.push((decode_string_key(&key), id));
+        }
+        Ok(entries)
+    }
+
+    /// Collects every id-to-string entry for integrity checks, reading
+    /// spilled strings from the value store.
+    ///
+    /// Entries whose string cannot be loaded yield `Err` in place of the
+    /// string.
+    pub fn debug_collect_id_to_str(&self, tx: &ReadGuard) -> Result<Vec<(u64, Result<String>)>> {
+        let mut cursor = self.i2s.range(tx, Bound::Unbounded, Bound::Unbounded)?;
+        let mut entries = Vec::new();
+        while let Some((id, entry)) = cursor.next()? {
+            let bytes = match entry {
+                StrEntry::Inline(bytes) => Ok(bytes),
+                StrEntry::VRef(vref) => self.vstore.read(tx, vref),
+            };
+            let resolved = bytes.and_then(|bytes| {
+                String::from_utf8(bytes)
+                    .map_err(|_| SombraError::Corruption("dictionary entry not valid UTF-8"))
+            });
+            entries.push((id, resolved));
+        }
+        Ok(entries)
+    }
+
     fn reserve_str_id(&self, tx: &mut WriteGuard<'_>) -> Result<StrId> {
         let mut allocated: Option<u32> = None;
         tx.update_meta(|meta| {
@@ -418,29 +672,44 @@ impl Dict {
     fn sync_roots(&self, tx: &mut WriteGuard<'_>) -> Result<()> {
         let s2i_root = self.s2i.root_page();
         let i2s_root = self.i2s.root_page();
+        let kinds_root = self.kinds.root_page();
         let meta = self.store.meta()?;
-        if meta.dict_str_to_id_root == s2i_root && meta.dict_id_to_str_root == i2s_root {
+        if meta.dict_str_to_id_root == s2i_root
+            && meta.dict_id_to_str_root == i2s_root
+            && meta.dict_name_kind_root == kinds_root
+        {
             return Ok(());
         }
         tx.update_meta(|meta| {
             meta.dict_str_to_id_root = s2i_root;
             meta.dict_id_to_str_root = i2s_root;
+            meta.dict_name_kind_root = kinds_root;
         })
     }
 
     fn initialize_meta(&self, meta: &crate::primitives::pager::Meta) -> Result<()> {
         let s2i_root = self.s2i.root_page();
         let i2s_root = self.i2s.root_page();
+        let kinds_root = self.kinds.root_page();
+        // A dictionary with no names yet has nothing to backfill.
+        let mark_complete = meta.dict_name_kind_root.0 == 0 && meta.dict_next_str_id <= 1;
         let needs_update = meta.dict_str_to_id_root != s2i_root
             || meta.dict_id_to_str_root != i2s_root
-            || meta.dict_next_str_id == 0;
+            || meta.dict_name_kind_root != kinds_root
+            || meta.dict_next_str_id == 0
+            || mark_complete;
         if !needs_update {
             return Ok(());
         }
         let mut write = self.store.begin_write()?;
+        if mark_complete {
+            self.kinds.put(&mut write, &NAME_KINDS_COMPLETE_KEY, &1)?;
+        }
+        let kinds_root = self.kinds.root_page();
         write.update_meta(|meta| {
             meta.dict_str_to_id_root = s2i_root;
             meta.dict_id_to_str_root = i2s_root;
+            meta.dict_name_kind_root = kinds_root;
             if meta.dict_next_str_id == 0 {
                 meta.dict_next_str_id = 1;
             }
@@ -455,3 +724,11 @@ fn encode_string_key(s: &str) -> Result<Vec<u8>> {
     ord::put_str_key(&mut buf, s)?;
     Ok(buf)
 }
+
+fn decode_string_key(src: &[u8]) -> Result<String> {
+    let (s, consumed) = ord::split_str_key(src)?;
+    if consumed != src.len() {
+        return Err(SombraError::Corruption("string key has trailing bytes"));
+    }
+    Ok(s.to_owned())
+}
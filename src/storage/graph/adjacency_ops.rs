@@ -5,10 +5,13 @@ use std::collections::{BTreeMap, HashSet, VecDeque};
 use std::collections::HashMap;
 use std::ops::Bound;
 
+use tracing::warn;
+
 use crate::primitives::pager::{ReadGuard, WriteGuard};
 use crate::storage::btree::{BTree, PutItem};
 use crate::storage::mvcc::{CommitId, VersionedValue, COMMIT_MAX};
 use crate::storage::options::AdjacencyBackend;
+use crate::storage::EdgeData;
 use crate::storage::{
     profile_timer, record_flush_adj_entries, record_flush_adj_fwd_put, record_flush_adj_fwd_sort,
     record_flush_adj_key_encode, record_flush_adj_rev_put, record_flush_adj_rev_sort,
@@ -28,8 +31,8 @@ use super::node::{
 #[cfg(feature = "degree-cache")]
 use super::adjacency::DegreeDir;
 
-use super::adjacency::{self, Dir, ExpandOpts, Neighbor, NeighborCursor};
-use super::edge::PropStorage as EdgePropStorage;
+use super::adjacency::{self, Dir, ExpandOpts, Neighbor, NeighborCursor, NeighborScan};
+use super::edge::{self, PropStorage as EdgePropStorage};
 use super::graph_types::{BfsOptions, BfsVisit, RootKind};
 use super::{Graph, UnitValue};
 
@@ -63,6 +66,86 @@ impl Graph {
         Ok(NeighborCursor::new(neighbors))
     }
 
+    /// Starts a resumable walk over the neighbors of `id`.
+    ///
+    /// Nothing is read until [`Self::next_neighbors`] is called, and each call
+    /// reads at most the requested number of entries.
+    pub fn neighbor_scan(
+        &self,
+        id: NodeId,
+        dir: Dir,
+        ty: Option<TypeId>,
+        opts: ExpandOpts,
+    ) -> NeighborScan {
+        let enable_distinct = opts.distinct_nodes || self.distinct_neighbors_default;
+        NeighborScan {
+            node: id,
+            ty,
+            out_pending: dir.includes_out(),
+            in_pending: dir.includes_in(),
+            resume: None,
+            seen: enable_distinct.then(HashSet::new),
+        }
+    }
+
+    /// Reads up to `limit` further neighbors of `scan`, returning an empty
+    /// batch once the walk is done.
+    ///
+    /// The B-tree backend resumes each call just past the last adjacency key
+    /// it returned. The IFA backend has no resumable key order, so its first
+    /// call returns the whole list.
+    pub fn next_neighbors(
+        &self,
+        tx: &ReadGuard,
+        scan: &mut NeighborScan,
+        limit: usize,
+    ) -> Result<Vec<Neighbor>> {
+        let mut out = Vec::new();
+        if scan.is_done() || limit == 0 {
+            return Ok(out);
+        }
+        if self.adjacency_backend == AdjacencyBackend::IfaOnly {
+            let dir = match (scan.out_pending, scan.in_pending) {
+                (true, true) => Dir::Both,
+                (true, false) => Dir::Out,
+                _ => Dir::In,
+            };
+            let opts = ExpandOpts {
+                distinct_nodes: scan.seen.is_some(),
+            };
+            out.extend(self.neighbors_ifa(tx, scan.node, dir, scan.ty, opts)?);
+            scan.out_pending = false;
+            scan.in_pending = false;
+            return Ok(out);
+        }
+        while out.len() < limit && !scan.is_done() {
+            let forward = scan.out_pending;
+            if scan.resume.is_none() {
+                self.metrics
+                    .adjacency_scan(if forward { "out" } else { "in" });
+            }
+            let remaining = limit - out.len();
+            scan.resume = self.collect_neighbors_from(
+                tx,
+                scan.node,
+                scan.ty,
+                forward,
+                scan.resume.take(),
+                remaining,
+                scan.seen.as_mut(),
+                &mut out,
+            )?;
+            if scan.resume.is_none() {
+                if forward {
+                    scan.out_pending = false;
+                } else {
+                    scan.in_pending = false;
+                }
+            }
+        }
+        Ok(out)
+    }
+
     /// IFA-based neighbor lookup for IfaOnly mode.
     ///
     /// This method supports two paths:
@@ -279,13 +362,21 @@ impl Graph {
         mut seen: Option<&mut HashSet<NodeId>>,
         out: &mut Vec<Neighbor>,
     ) -> Result<()> {
+        let endpoints = |neighbor: NodeId| {
+            if dir == Dir::In {
+                (neighbor, node)
+            } else {
+                (node, neighbor)
+            }
+        };
         match ty {
             Some(type_id) => {
                 // Query specific type
                 let entries = ifa.get_neighbors_read(tx, node, dir, type_id, snapshot)?;
                 for (neighbor, edge) in entries {
                     // Verify edge is visible
-                    if self.visible_edge(tx, edge)?.is_none() {
+                    let (src, dst) = endpoints(neighbor);
+                    if !self.adjacency_edge_visible(tx, src, type_id, dst, edge)? {
                         continue;
                     }
                     if let Some(set) = seen.as_deref_mut() {
@@ -305,7 +396,8 @@ impl Graph {
                 let entries = ifa.get_all_neighbors_read(tx, node, dir, snapshot)?;
                 for (neighbor, edge, type_id) in entries {
                     // Verify edge is visible
-                    if self.visible_edge(tx, edge)?.is_none() {
+                    let (src, dst) = endpoints(neighbor);
+                    if !self.adjacency_edge_visible(tx, src, type_id, dst, edge)? {
                         continue;
                     }
                     if let Some(set) = seen.as_deref_mut() {
@@ -382,6 +474,74 @@ impl Graph {
         Ok(visits)
     }
 
+    /// Finds a shortest path from `start` to `end`, pairing each node with the
+    /// edge used to reach it (`None` for `start`).
+    ///
+    /// Only edges whose type is listed in `edge_types` are followed when the
+    /// filter is non-empty. Among parallel edges joining two consecutive
+    /// nodes, the first one in adjacency order is reported. Returns `None`
+    /// when `end` is unreachable.
+    pub fn shortest_path_with_edges(
+        &self,
+        tx: &ReadGuard,
+        start: NodeId,
+        end: NodeId,
+        dir: Dir,
+        edge_types: Option<&[TypeId]>,
+    ) -> Result<Option<Vec<(NodeId, Option<EdgeId>)>>> {
+        if !self.node_exists(tx, start)? || !self.node_exists(tx, end)? {
+            return Err(SombraError::NotFound);
+        }
+        if start == end {
+            return Ok(Some(vec![(start, None)]));
+        }
+        let filters: Vec<Option<TypeId>> = match edge_types {
+            Some(types) if !types.is_empty() => types.iter().copied().map(Some).collect(),
+            _ => vec![None],
+        };
+        let mut parents: BTreeMap<NodeId, (NodeId, EdgeId)> = BTreeMap::new();
+        let mut queue: VecDeque<NodeId> = VecDeque::from([start]);
+        let mut found = false;
+        'search: while let Some(node) = queue.pop_front() {
+            for ty in &filters {
+                let cursor = self.neighbors(
+                    tx,
+                    node,
+                    dir,
+                    *ty,
+                    ExpandOpts {
+                        distinct_nodes: false,
+                    },
+                )?;
+                for entry in cursor {
+                    if entry.neighbor == start || parents.contains_key(&entry.neighbor) {
+                        continue;
+                    }
+                    parents.insert(entry.neighbor, (node, entry.edge));
+                    if entry.neighbor == end {
+                        found = true;
+                        break 'search;
+                    }
+                    queue.push_back(entry.neighbor);
+                }
+            }
+        }
+        if !found {
+            return Ok(None);
+        }
+        let mut path = vec![(end, None)];
+        let mut current = end;
+        while let Some(&(prev, edge)) = parents.get(&current) {
+            if let Some(last) = path.last_mut() {
+                last.1 = Some(edge);
+            }
+            path.push((prev, None));
+            current = prev;
+        }
+        path.reverse();
+        Ok(Some(path))
+    }
+
     /// Optimized BFS for IFA mode with adj_page caching.
     /// 
     /// Caches adj_page lookups to avoid redundant node B-tree reads during traversal.
@@ -521,7 +681,7 @@ impl Graph {
                 let mut inn = 0u64;
                 let mut loops = 0u64;
                 for entry in &inline_adj.entries {
-                    let matches_ty = ty.map_or(true, |t| t.0 == entry.type_id);
+                    let matches_ty = ty.is_none_or(|t| t.0 == entry.type_id);
                     if !matches_ty {
                         continue;
                     }
@@ -1317,105 +1477,100 @@ impl Graph {
         Ok(())
     }
 
-    pub(crate) fn collect_incident_edges(
+    /// Lists the edges touching `node` as the write transaction sees them,
+    /// including edges it created or deleted itself, ordered by edge id.
+    pub fn incident_edges_with_write(
         &self,
-        read: &ReadGuard,
+        tx: &mut WriteGuard<'_>,
         node: NodeId,
-    ) -> Result<HashSet<EdgeId>> {
-        // For IfaOnly mode, use IFA path
-        if self.adjacency_backend == AdjacencyBackend::IfaOnly {
-            return self.collect_incident_edges_ifa(read, node);
+    ) -> Result<Vec<(EdgeId, EdgeData)>> {
+        let ends = self.incident_edge_ends_with_write(tx, node)?;
+        let mut edges = Vec::with_capacity(ends.len());
+        for id in ends.into_keys() {
+            if let Some(data) = self.get_edge_in_write(tx, id)? {
+                edges.push((id, data));
+            }
         }
-
-        // B-tree path
-        let mut edges = HashSet::new();
-        self.collect_adjacent_edges(read, node, true, &mut edges)?;
-        self.collect_adjacent_edges(read, node, false, &mut edges)?;
         Ok(edges)
     }
 
-    /// Collects incident edges using IFA path.
-    fn collect_incident_edges_ifa(
+    /// Maps each edge touching `node`, as the write transaction sees it, to
+    /// its `(src, dst)` endpoints without decoding properties.
+    ///
+    /// Deferred adjacency writes are flushed first. Under
+    /// [`AdjacencyBackend::IfaOnly`] there is no B-tree adjacency to read
+    /// through the transaction, so every edge row is scanned instead.
+    pub(crate) fn incident_edge_ends_with_write(
         &self,
-        read: &ReadGuard,
+        tx: &mut WriteGuard<'_>,
         node: NodeId,
-    ) -> Result<HashSet<EdgeId>> {
-        let ifa = self.ifa.as_ref().ok_or(SombraError::Invalid(
-            "IFA not initialized but IfaOnly mode selected",
-        ))?;
-
-        let snapshot = Self::reader_snapshot_commit(read);
-        let mut edges = HashSet::new();
-
-        // Get node row to inspect inline and external adjacency
-        let versioned = match self.visible_node(read, node)? {
-            Some(v) => v,
-            None => return Ok(edges),
-        };
-
-        // Collect edges from inline adjacency, if present
-        if let Some(inline_adj) = &versioned.row.inline_adj {
-            for entry in &inline_adj.entries {
-                edges.insert(entry.edge);
-            }
-        }
-
-        // Collect edges from external adj_page, if present
-        if let Some(adj_page_id) = versioned.row.adj_page {
-            for dir in [Dir::Out, Dir::In] {
-                let neighbors = ifa.get_all_neighbors_true_ifa(read, adj_page_id, dir, snapshot)?;
-                for (_neighbor, edge_id, _type_id) in neighbors {
-                    edges.insert(edge_id);
+    ) -> Result<BTreeMap<EdgeId, (NodeId, NodeId)>> {
+        self.flush_deferred_writes(tx)?;
+        let mut ends = BTreeMap::new();
+        if self.adjacency_backend == AdjacencyBackend::IfaOnly {
+            self.edges.for_each_with_write(tx, |id, bytes| {
+                let versioned = edge::decode(&bytes)?;
+                let row = versioned.row;
+                if !versioned.header.is_tombstone() && (row.src == node || row.dst == node) {
+                    ends.insert(EdgeId(id), (row.src, row.dst));
                 }
+                Ok(())
+            })?;
+        } else {
+            let (lo, hi) = adjacency_bounds_for_node(node);
+            for forward in [true, false] {
+                let tree = if forward {
+                    &self.adj_fwd
+                } else {
+                    &self.adj_rev
+                };
+                tree.range_with_write(tx, &lo, &hi, |key, value| {
+                    if value.header.end != COMMIT_MAX || value.header.is_tombstone() {
+                        return Ok(());
+                    }
+                    let (src, dst, edge) = if forward {
+                        adjacency::decode_fwd_key(&key).map(|(src, _, dst, edge)| (src, dst, edge))
+                    } else {
+                        adjacency::decode_rev_key(&key).map(|(dst, _, src, edge)| (src, dst, edge))
+                    }
+                    .ok_or(SombraError::Corruption("adjacency key decode failed"))?;
+                    ends.insert(edge, (src, dst));
+                    Ok(())
+                })?;
             }
         }
-
-        Ok(edges)
+        Ok(ends)
     }
 
-    fn collect_adjacent_edges(
+    fn collect_neighbors(
         &self,
-        read: &ReadGuard,
+        tx: &ReadGuard,
         node: NodeId,
+        ty_filter: Option<TypeId>,
         forward: bool,
-        edges: &mut HashSet<EdgeId>,
+        seen: Option<&mut HashSet<NodeId>>,
+        out: &mut Vec<Neighbor>,
     ) -> Result<()> {
-        let (lo, hi) = adjacency_bounds_for_node(node);
-        let tree = if forward {
-            &self.adj_fwd
-        } else {
-            &self.adj_rev
-        };
-        let snapshot = Self::reader_snapshot_commit(read);
-        let mut cursor = tree.range(read, Bound::Included(lo), Bound::Included(hi))?;
-        while let Some((key, value)) = cursor.next()? {
-            if !Self::version_visible(&value.header, snapshot) {
-                continue;
-            }
-            let decoded = if forward {
-                adjacency::decode_fwd_key(&key)
-            } else {
-                adjacency::decode_rev_key(&key)
-            }
-            .ok_or(SombraError::Corruption("adjacency key decode failed"))?;
-            let edge_id = decoded.3;
-            if self.visible_edge(read, edge_id)?.is_none() {
-                continue;
-            }
-            edges.insert(edge_id);
-        }
+        self.collect_neighbors_from(tx, node, ty_filter, forward, None, usize::MAX, seen, out)?;
         Ok(())
     }
 
-    fn collect_neighbors(
+    /// Collects the adjacency entries of `node` that sort after `after`.
+    ///
+    /// Stops once `limit` neighbors were pushed and returns the key of the
+    /// last entry read so the walk can resume after it; returns `None` when
+    /// the node's range is exhausted.
+    fn collect_neighbors_from(
         &self,
         tx: &ReadGuard,
         node: NodeId,
         ty_filter: Option<TypeId>,
         forward: bool,
+        after: Option<Vec<u8>>,
+        limit: usize,
         seen: Option<&mut HashSet<NodeId>>,
         out: &mut Vec<Neighbor>,
-    ) -> Result<()> {
+    ) -> Result<Option<Vec<u8>>> {
         let (lo, hi) = if forward {
             adjacency::fwd_bounds(node, ty_filter)
         } else {
@@ -1426,9 +1581,14 @@ impl Graph {
         } else {
             &self.adj_rev
         };
+        let lower = match after {
+            Some(key) => Bound::Excluded(key),
+            None => Bound::Included(lo),
+        };
         let snapshot = Self::reader_snapshot_commit(tx);
-        let mut cursor = tree.range(tx, Bound::Included(lo), Bound::Included(hi))?;
+        let mut cursor = tree.range(tx, lower, Bound::Included(hi))?;
         let mut seen = seen;
+        let mut pushed = 0usize;
         while let Some((key, value)) = cursor.next()? {
             if !Self::version_visible(&value.header, snapshot) {
                 continue;
@@ -1447,7 +1607,7 @@ impl Graph {
                         continue;
                     }
                 }
-                if self.visible_edge(tx, edge)?.is_none() {
+                if !self.adjacency_edge_visible(tx, src, ty, dst, edge)? {
                     continue;
                 }
                 if let Some(set) = seen.as_deref_mut() {
@@ -1474,7 +1634,7 @@ impl Graph {
                         continue;
                     }
                 }
-                if self.visible_edge(tx, edge)?.is_none() {
+                if !self.adjacency_edge_visible(tx, src, ty, dst, edge)? {
                     continue;
                 }
                 if let Some(set) = seen.as_deref_mut() {
@@ -1488,8 +1648,135 @@ impl Graph {
                     ty,
                 });
             }
+            pushed += 1;
+            if pushed >= limit {
+                return Ok(Some(key));
+            }
         }
-        Ok(())
+        Ok(None)
+    }
+
+    /// Checks whether the edge an adjacency entry points at is visible to `tx`.
+    ///
+    /// An entry whose edge has no row at all is dangling. Strict graphs fail
+    /// the read; graphs opened with [`GraphOptions::heal_on_read`] log the
+    /// entry, queue it for [`Self::repair_dangling_adjacency`], and skip it.
+    ///
+    /// [`GraphOptions::heal_on_read`]: crate::storage::GraphOptions::heal_on_read
+    fn adjacency_edge_visible(
+        &self,
+        tx: &ReadGuard,
+        src: NodeId,
+        ty: TypeId,
+        dst: NodeId,
+        edge: EdgeId,
+    ) -> Result<bool> {
+        let Some(bytes) = self.edges.get(tx, &edge.0)? else {
+            if !self.heal_on_read {
+                return Err(SombraError::Corruption(
+                    "adjacency entry references missing edge",
+                ));
+            }
+            warn!(
+                src = src.0,
+                dst = dst.0,
+                ty = ty.0,
+                edge = edge.0,
+                "graph.adjacency.dangling"
+            );
+            self.dangling_adjacency.lock().insert((src, ty, dst, edge));
+            return Ok(false);
+        };
+        Ok(self.visible_edge_from_bytes(tx, edge, &bytes)?.is_some())
+    }
+
+    /// Removes the dangling adjacency entries that lenient reads have queued.
+    ///
+    /// Entries whose edge row exists again are left in place. Only B-tree
+    /// adjacency is rewritten, so under [`AdjacencyBackend::IfaOnly`] the
+    /// queue is drained without changes. Returns the number of entries removed.
+    pub fn repair_dangling_adjacency(&self, tx: &mut WriteGuard<'_>) -> Result<usize> {
+        let queued: Vec<_> = self.dangling_adjacency.lock().drain().collect();
+        if self.adjacency_backend == AdjacencyBackend::IfaOnly {
+            return Ok(0);
+        }
+        let mut repaired = 0;
+        for (src, ty, dst, edge) in queued {
+            if self.edges.get_with_write(tx, &edge.0)?.is_some() {
+                continue;
+            }
+            if self.delete_adjacency_pair(tx, src, ty, dst, edge)? {
+                repaired += 1;
+            }
+        }
+        if repaired > 0 {
+            self.persist_tree_root(tx, RootKind::AdjFwd)?;
+            self.persist_tree_root(tx, RootKind::AdjRev)?;
+        }
+        Ok(repaired)
+    }
+
+    /// Removes adjacency entries that no live edge row backs.
+    ///
+    /// Each `(src, ty, dst, edge)` entry is rechecked against the edge row in
+    /// `tx`, so entries gathered from an older snapshot are safe to pass: an
+    /// entry is kept when its edge exists, is not deleted, and agrees on
+    /// endpoints and type. As with [`Graph::repair_dangling_adjacency`], IFA-only
+    /// adjacency is left alone. Returns the entries that were removed.
+    pub fn repair_orphaned_adjacency(
+        &self,
+        tx: &mut WriteGuard<'_>,
+        entries: &[(NodeId, TypeId, NodeId, EdgeId)],
+    ) -> Result<Vec<(NodeId, TypeId, NodeId, EdgeId)>> {
+        if self.adjacency_backend == AdjacencyBackend::IfaOnly {
+            return Ok(Vec::new());
+        }
+        let mut removed = Vec::new();
+        for &(src, ty, dst, edge) in entries {
+            if let Some(bytes) = self.edges.get_with_write(tx, &edge.0)? {
+                let versioned = edge::decode(&bytes)?;
+                let row = &versioned.row;
+                if !versioned.header.is_tombstone()
+                    && row.src == src
+                    && row.dst == dst
+                    && row.ty == ty
+                {
+                    continue;
+                }
+            }
+            if self.delete_adjacency_pair(tx, src, ty, dst, edge)? {
+                removed.push((src, ty, dst, edge));
+            }
+        }
+        if !removed.is_empty() {
+            self.persist_tree_root(tx, RootKind::AdjFwd)?;
+            self.persist_tree_root(tx, RootKind::AdjRev)?;
+        }
+        Ok(removed)
+    }
+
+    fn delete_adjacency_pair(
+        &self,
+        tx: &mut WriteGuard<'_>,
+        src: NodeId,
+        ty: TypeId,
+        dst: NodeId,
+        edge: EdgeId,
+    ) -> Result<bool> {
+        let fwd = adjacency::encode_fwd_key(src, ty, dst, edge);
+        let rev = adjacency::encode_rev_key(dst, ty, src, edge);
+        let removed_fwd = self.adj_fwd.delete(tx, &fwd)?;
+        let removed_rev = self.adj_rev.delete(tx, &rev)?;
+        #[cfg(feature = "degree-cache")]
+        {
+            if removed_fwd {
+                self.bump_degree(tx, src, DegreeDir::Out, ty, -1)?;
+            }
+            if removed_rev {
+                self.bump_degree(tx, dst, DegreeDir::In, ty, -1)?;
+            }
+        }
+        Ok(removed_fwd || removed_rev)
     }
 
     fn enqueue_bfs_neighbors(
@@ -0,0 +1,230 @@
+use std::collections::{HashMap, VecDeque};
+use std::ops::Bound;
+
+use crate::primitives::pager::ReadGuard;
+use crate::storage::adjacency::{Dir, ExpandOpts};
+use crate::types::{NodeId, Result, SombraError, TypeId};
+
+use super::{ComponentMode, Graph, PageRankOpts};
+
+impl Graph {
+    /// Scores every visible node by PageRank, returned in node id order.
+    ///
+    /// Ranks are refined by power iteration over outgoing edges until the L1
+    /// change between rounds drops below `opts.tolerance` or `opts.max_iters`
+    /// rounds have run. Rank held by nodes without outgoing edges is spread
+    /// uniformly across the graph, so the scores always sum to one.
+    pub fn page_rank(&self, tx: &ReadGuard, opts: PageRankOpts) -> Result<Vec<(NodeId, f64)>> {
+        if !(0.0..=1.0).contains(&opts.damping) {
+            return Err(SombraError::Invalid(
+                "page rank damping must be within [0, 1]",
+            ));
+        }
+        if opts.tolerance.is_nan() || opts.tolerance < 0.0 {
+            return Err(SombraError::Invalid(
+                "page rank tolerance must be non-negative",
+            ));
+        }
+        let ids = self.visible_node_ids(tx)?;
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let out_links = self.adjacency_indices(tx, &ids, Dir::Out, opts.edge_type)?;
+
+        let n = ids.len() as f64;
+        let mut ranks = vec![1.0 / n; ids.len()];
+        let mut next = vec![0.0; ids.len()];
+        for _ in 0..opts.max_iters {
+            let dangling: f64 = out_links
+                .iter()
+                .zip(&ranks)
+                .filter(|(links, _)| links.is_empty())
+                .map(|(_, rank)| *rank)
+                .sum();
+            let base = (1.0 - opts.damping) / n + opts.damping * dangling / n;
+            next.iter_mut().for_each(|slot| *slot = base);
+            for (links, rank) in out_links.iter().zip(&ranks) {
+                if links.is_empty() {
+                    continue;
+                }
+                let share = opts.damping * rank / links.len() as f64;
+                for &target in links {
+                    next[target] += share;
+                }
+            }
+            let delta: f64 = next
+                .iter()
+                .zip(&ranks)
+                .map(|(new, old)| (new - old).abs())
+                .sum();
+            std::mem::swap(&mut ranks, &mut next);
+            if delta < opts.tolerance {
+                break;
+            }
+        }
+        Ok(ids.into_iter().zip(ranks).collect())
+    }
+
+    /// Labels every visible node with the id of its connected component.
+    ///
+    /// [`ComponentMode::Weak`] ignores edge direction; [`ComponentMode::Strong`]
+    /// only groups nodes that reach each other along outgoing edges. Component
+    /// ids are dense, numbered from zero in order of each component's lowest
+    /// node id, and results are returned in node id order. Both modes walk the
+    /// graph iteratively, so deep chains cannot overflow the stack.
+    pub fn connected_components(
+        &self,
+        tx: &ReadGuard,
+        mode: ComponentMode,
+    ) -> Result<Vec<(NodeId, u64)>> {
+        let ids = self.visible_node_ids(tx)?;
+        let components = match mode {
+            ComponentMode::Weak => {
+                let links = self.adjacency_indices(tx, &ids, Dir::Both, None)?;
+                weak_components(&links)
+            }
+            ComponentMode::Strong => {
+                let links = self.adjacency_indices(tx, &ids, Dir::Out, None)?;
+                strong_components(&links)
+            }
+        };
+        Ok(ids.into_iter().zip(components).collect())
+    }
+
+    fn visible_node_ids(&self, tx: &ReadGuard) -> Result<Vec<NodeId>> {
+        let mut ids = Vec::new();
+        let mut cursor = self.nodes.range(tx, Bound::Unbounded, Bound::Unbounded)?;
+        while let Some((key, bytes)) = cursor.next()? {
+            if self
+                .visible_node_from_bytes(tx, NodeId(key), &bytes)?
+                .is_some()
+            {
+                ids.push(NodeId(key));
+            }
+        }
+        Ok(ids)
+    }
+
+    /// Resolves each node's neighbors to positions within `ids`.
+    fn adjacency_indices(
+        &self,
+        tx: &ReadGuard,
+        ids: &[NodeId],
+        dir: Dir,
+        ty: Option<TypeId>,
+    ) -> Result<Vec<Vec<usize>>> {
+        let index: HashMap<NodeId, usize> =
+            ids.iter().enumerate().map(|(idx, id)| (*id, idx)).collect();
+        let mut links = Vec::with_capacity(ids.len());
+        for id in ids {
+            let neighbors = self.neighbors(
+                tx,
+                *id,
+                dir,
+                ty,
+                ExpandOpts {
+                    distinct_nodes: false,
+                },
+            )?;
+            links.push(
+                neighbors
+                    .filter_map(|neighbor| index.get(&neighbor.neighbor).copied())
+                    .collect(),
+            );
+        }
+        Ok(links)
+    }
+}
+
+/// Assigns component ids by breadth-first flooding from each unvisited node.
+fn weak_components(links: &[Vec<usize>]) -> Vec<u64> {
+    let mut components = vec![u64::MAX; links.len()];
+    let mut next_component = 0u64;
+    let mut queue = VecDeque::new();
+    for root in 0..links.len() {
+        if components[root] != u64::MAX {
+            continue;
+        }
+        components[root] = next_component;
+        queue.push_back(root);
+        while let Some(node) = queue.pop_front() {
+            for &neighbor in &links[node] {
+                if components[neighbor] == u64::MAX {
+                    components[neighbor] = next_component;
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        next_component += 1;
+    }
+    components
+}
+
+/// Tarjan's algorithm with an explicit call stack, renumbered so component
+/// ids follow the order in which their lowest member appears.
+fn strong_components(links: &[Vec<usize>]) -> Vec<u64> {
+    const UNVISITED: usize = usize::MAX;
+    let n = links.len();
+    let mut order = vec![UNVISITED; n];
+    let mut lowlink = vec![0usize; n];
+    let mut on_stack = vec![false; n];
+    let mut scc_stack = Vec::new();
+    let mut raw = vec![0usize; n];
+    let mut raw_count = 0usize;
+    let mut next_order = 0usize;
+    let mut calls: Vec<(usize, usize)> = Vec::new();
+    for root in 0..n {
+        if order[root] != UNVISITED {
+            continue;
+        }
+        order[root] = next_order;
+        lowlink[root] = next_order;
+        next_order += 1;
+        scc_stack.push(root);
+        on_stack[root] = true;
+        calls.push((root, 0));
+        while let Some(&(node, edge)) = calls.last() {
+            if let Some(&next) = links[node].get(edge) {
+                if let Some(frame) = calls.last_mut() {
+                    frame.1 += 1;
+                }
+                if order[next] == UNVISITED {
+                    order[next] = next_order;
+                    lowlink[next] = next_order;
+                    next_order += 1;
+                    scc_stack.push(next);
+                    on_stack[next] = true;
+                    calls.push((next, 0));
+                } else if on_stack[next] {
+                    lowlink[node] = lowlink[node].min(order[next]);
+                }
+                continue;
+            }
+            calls.pop();
+            if let Some(&(parent, _)) = calls.last() {
+                lowlink[parent] = lowlink[parent].min(lowlink[node]);
+            }
+            if lowlink[node] == order[node] {
+                while let Some(member) = scc_stack.pop() {
+                    on_stack[member] = false;
+                    raw[member] = raw_count;
+                    if member == node {
+                        break;
+                    }
+                }
+                raw_count += 1;
+            }
+        }
+    }
+    let mut renumbered = vec![u64::MAX; raw_count];
+    let mut next_component = 0u64;
+    raw.into_iter()
+        .map(|component| {
+            if renumbered[component] == u64::MAX {
+                renumbered[component] = next_component;
+                next_component += 1;
+            }
+            renumbered[component]
+        })
+        .collect()
+}
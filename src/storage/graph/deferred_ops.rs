@@ -152,6 +152,7 @@ impl Graph {
         commit: CommitId,
         insert: bool,
     ) -> Result<()> {
+        self.metrics.property_index_write();
         if !self.defer_index_flush {
             if insert {
                 self.indexes.insert_property_value_with_commit(
@@ -223,11 +224,11 @@ impl Graph {
             for (def, key, node, commit) in buffer.prop_inserts.drain(..) {
                 match def.kind {
                     IndexKind::BTree => {
-                        let prefix = IndexStore::btree_prefix(def.label, def.prop, &key);
+                        let prefix = IndexStore::btree_prefix(def.label, def.prop(), &key);
                         btree_items.push((prefix, node, Some(commit)));
                     }
                     IndexKind::Chunked => {
-                        let prefix = IndexStore::chunked_prefix(def.label, def.prop, &key);
+                        let prefix = IndexStore::chunked_prefix(def.label, def.prop(), &key);
                         chunked_groups
                             .entry(prefix)
                             .or_insert_with(|| (Vec::new(), Some(commit)))
@@ -9,7 +9,7 @@ use crate::storage::profile::{
 };
 use crate::storage::{props, EdgeData, EdgeSpec};
 use crate::storage::{VersionPtr, VersionSpace};
-use crate::types::{EdgeId, Result, SombraError, VRef};
+use crate::types::{EdgeId, NodeId, Result, SombraError, TypeId, VRef};
 
 use super::edge::{
     self, EncodeOpts as EdgeEncodeOpts, PropPayload as EdgePropPayload,
@@ -55,6 +55,7 @@ impl Graph {
             map_vref = Some(vref);
             EdgePropPayload::VRef(vref)
         };
+        self.record_prop_placement(spec.props.len(), spill_vrefs.len(), map_vref.is_some());
         // When using deferred index flush, skip the pending flag since visibility
         // is already controlled by commit boundaries.
         let (commit_id, version) = if self.defer_index_flush {
@@ -121,6 +122,9 @@ impl Graph {
         if !self.defer_index_flush {
             self.finalize_edge_head(tx, edge_id)?;
         }
+        tx.update_meta(|meta| {
+            meta.storage_edge_count = meta.storage_edge_count.saturating_add(1);
+        })?;
         self.metrics.edge_created();
         Ok(edge_id)
     }
@@ -148,6 +152,49 @@ impl Graph {
         }))
     }
 
+    /// Retrieves edge data using an active write transaction.
+    ///
+    /// Like [`Graph::get_node_in_write`], this surfaces versions the current
+    /// writer has not committed yet.
+    pub fn get_edge_in_write(
+        &self,
+        tx: &mut WriteGuard<'_>,
+        id: EdgeId,
+    ) -> Result<Option<EdgeData>> {
+        let Some(bytes) = self.edges.get_with_write(tx, &id.0)? else {
+            return Ok(None);
+        };
+        let versioned = edge::decode(&bytes)?;
+        if versioned.header.is_tombstone() {
+            return Ok(None);
+        }
+        let row = versioned.row;
+        let prop_bytes = self.read_edge_prop_bytes_with_write(tx, &row.props)?;
+        let props = self.materialize_props_owned_with_write(tx, &prop_bytes)?;
+        Ok(Some(EdgeData {
+            src: row.src,
+            dst: row.dst,
+            ty: row.ty,
+            props,
+        }))
+    }
+
+    /// Reads the type ids of many edges, preserving the order of `ids`.
+    ///
+    /// Properties are not decoded. Missing or invisible edges yield `None`.
+    pub fn edge_type_ids(&self, tx: &ReadGuard, ids: &[EdgeId]) -> Result<Vec<Option<TypeId>>> {
+        let mut types = Vec::with_capacity(ids.len());
+        for &id in ids {
+            let Some(bytes) = self.edges.get(tx, &id.0)? else {
+                types.push(None);
+                continue;
+            };
+            let visible = self.visible_edge_from_bytes(tx, id, &bytes)?;
+            types.push(visible.map(|versioned| versioned.row.ty));
+        }
+        Ok(types)
+    }
+
     /// Scans and returns all edges in the graph.
     pub fn scan_all_edges(&self, tx: &ReadGuard) -> Result<Vec<(EdgeId, EdgeData)>> {
         let mut cursor =
@@ -214,6 +261,11 @@ impl Graph {
             map_vref = Some(vref);
             EdgePropPayload::VRef(vref)
         };
+        self.record_prop_placement(
+            delta.new_map.len(),
+            delta.encoded.spill_vrefs.len(),
+            map_vref.is_some(),
+        );
         let mut old_header = versioned.header;
         old_header.end = commit_id;
         let mut log_bytes = existing_bytes.clone();
@@ -275,6 +327,85 @@ impl Graph {
         Ok(())
     }
 
+    /// Moves an edge onto new endpoints, keeping its id, type, and properties.
+    ///
+    /// The old row is logged as a prior version and its adjacency entries are
+    /// retired as [`Graph::delete_edge`] retires them, so earlier snapshots
+    /// still see the edge where it was.
+    pub(crate) fn repoint_edge(
+        &self,
+        tx: &mut WriteGuard<'_>,
+        id: EdgeId,
+        src: NodeId,
+        dst: NodeId,
+    ) -> Result<()> {
+        let Some(existing_bytes) = self.edges.get_with_write(tx, &id.0)? else {
+            return Err(SombraError::NotFound);
+        };
+        let versioned = edge::decode(&existing_bytes)?;
+        if versioned.header.is_tombstone() {
+            return Err(SombraError::NotFound);
+        }
+        let row = versioned.row;
+        if row.src == src && row.dst == dst {
+            return Ok(());
+        }
+        // The logged version keeps its own property storage, which vacuum
+        // frees once the version retires, so the moved row gets a fresh copy.
+        let prop_bytes = self.read_edge_prop_bytes_with_write(tx, &row.props)?;
+        let current = self.materialize_props_owned_with_write(tx, &prop_bytes)?;
+        let encoded =
+            props::encode_props_owned(&current, self.inline_value_opts(), &self.vstore, tx)?;
+        let (commit_id, mut new_header) = self.tx_pending_version_header(tx);
+        let mut map_vref: Option<VRef> = None;
+        let payload = if encoded.bytes.len() <= self.inline_prop_blob {
+            EdgePropPayload::Inline(&encoded.bytes)
+        } else {
+            let vref = self.vstore.write(tx, &encoded.bytes)?;
+            map_vref = Some(vref);
+            EdgePropPayload::VRef(vref)
+        };
+        self.record_prop_placement(current.len(), encoded.spill_vrefs.len(), map_vref.is_some());
+        let mut old_header = versioned.header;
+        old_header.end = commit_id;
+        let mut log_bytes = existing_bytes.clone();
+        Self::overwrite_encoded_header(&mut log_bytes, &old_header);
+        let prev_ptr = self.log_version_entry(
+            tx,
+            VersionSpace::Edge,
+            id.0,
+            old_header,
+            versioned.prev_ptr,
+            log_bytes.clone(),
+        )?;
+        let inline_history = self.maybe_inline_history(&log_bytes);
+        if inline_history.is_some() {
+            new_header.flags |= mvcc_flags::INLINE_HISTORY;
+        }
+        let written = edge::encode(
+            src,
+            dst,
+            row.ty,
+            payload,
+            EdgeEncodeOpts::new(self.row_hash_header),
+            new_header,
+            prev_ptr,
+            inline_history.as_deref(),
+        )
+        .and_then(|encoded_row| self.edges.put(tx, &id.0, &encoded_row.bytes));
+        if let Err(err) = written {
+            if let Some(vref) = map_vref.take() {
+                let _ = self.vstore.free(tx, vref);
+            }
+            props::free_vrefs(&self.vstore, tx, &encoded.spill_vrefs);
+            return Err(err);
+        }
+        self.persist_tree_root(tx, RootKind::Edges)?;
+        self.stage_adjacency_removals(tx, &[(row.src, row.dst, row.ty, id)], commit_id)?;
+        self.stage_adjacency_inserts(tx, &[(src, dst, row.ty, id)], commit_id)?;
+        self.finalize_edge_head(tx, id)
+    }
+
     /// Deletes an edge from the graph by ID.
     pub fn delete_edge(&self, tx: &mut WriteGuard<'_>, id: EdgeId) -> Result<()> {
         let Some(bytes) = self.edges.get_with_write(tx, &id.0)? else {
@@ -317,6 +448,9 @@ impl Graph {
         self.edges.put(tx, &id.0, &encoded.bytes)?;
         self.persist_tree_root(tx, RootKind::Edges)?;
         self.finalize_edge_head(tx, id)?;
+        tx.update_meta(|meta| {
+            meta.storage_edge_count = meta.storage_edge_count.saturating_sub(1);
+        })?;
         Ok(())
     }
 
@@ -1,11 +1,14 @@
+use std::collections::BTreeMap;
+use std::ops::Bound;
 use std::time::{Duration, SystemTime};
 
 use crate::primitives::pager::AsyncFsyncBacklog;
 use crate::primitives::wal::{WalAllocatorStats, WalCommitBacklog};
 use crate::storage::adjacency::Dir;
+use crate::storage::index::IndexDef;
 use crate::storage::mvcc::{CommitId, CommitTableSnapshot};
 use crate::storage::types::PropValueOwned;
-use crate::types::{Lsn, NodeId, TypeId};
+use crate::types::{EdgeId, LabelId, Lsn, NodeId, PropId, TypeId};
 
 /// Default maximum size for inline property blob storage in bytes.
 pub const DEFAULT_INLINE_PROP_BLOB: u32 = 128;
@@ -13,6 +16,11 @@ pub const DEFAULT_INLINE_PROP_BLOB: u32 = 128;
 pub const DEFAULT_INLINE_PROP_VALUE: u32 = 48;
 /// Storage flag indicating that degree caching is enabled.
 pub const STORAGE_FLAG_DEGREE_CACHE: u32 = 0x01;
+/// Storage flag indicating that node ids are allocated in per-label runs.
+pub const STORAGE_FLAG_CLUSTER_BY_LABEL: u32 = 0x02;
+/// Storage flag indicating that the meta page's live node and edge counts
+/// cover every row, including rows written before the counters existed.
+pub const STORAGE_FLAG_LIVE_COUNTS: u32 = 0x04;
 /// MVCC metrics publish interval.
 pub const MVCC_METRICS_PUBLISH_INTERVAL: Duration = Duration::from_millis(500);
 
@@ -49,6 +57,76 @@ pub struct BfsVisit {
     pub depth: u32,
 }
 
+/// Edge semantics used by [`Graph::connected_components`](super::Graph::connected_components).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ComponentMode {
+    /// Nodes joined by an edge in either direction share a component.
+    Weak,
+    /// Nodes share a component only when each reaches the other.
+    Strong,
+}
+
+/// Options for [`Graph::page_rank`](super::Graph::page_rank).
+#[derive(Clone, Copy, Debug)]
+pub struct PageRankOpts {
+    /// Probability of following an edge rather than jumping to a random node.
+    pub damping: f64,
+    /// Maximum number of power iterations.
+    pub max_iters: u32,
+    /// Stops once the L1 change between iterations falls below this value.
+    pub tolerance: f64,
+    /// Only follows edges of this type when set.
+    pub edge_type: Option<TypeId>,
+}
+
+impl Default for PageRankOpts {
+    fn default() -> Self {
+        Self {
+            damping: 0.85,
+            max_iters: 100,
+            tolerance: 1e-6,
+            edge_type: None,
+        }
+    }
+}
+
+/// Index-backed selection of nodes targeted by bulk writes such as
+/// [`Graph::add_label_where`](super::Graph::add_label_where).
+#[derive(Clone, Debug)]
+pub enum NodeFilter {
+    /// Every node carrying the label.
+    Label(LabelId),
+    /// Nodes carrying the label whose ids fall within the bounds.
+    IdRange {
+        /// Label whose index drives the scan.
+        label: LabelId,
+        /// Lower id bound.
+        start: Bound<NodeId>,
+        /// Upper id bound.
+        end: Bound<NodeId>,
+    },
+    /// Nodes carrying the label whose indexed property equals `value`.
+    PropEq {
+        /// Label owning the property index.
+        label: LabelId,
+        /// Indexed property.
+        prop: PropId,
+        /// Value to match.
+        value: PropValueOwned,
+    },
+    /// Nodes carrying the label whose indexed property falls within the bounds.
+    PropRange {
+        /// Label owning the property index.
+        label: LabelId,
+        /// Indexed property.
+        prop: PropId,
+        /// Lower value bound.
+        start: Bound<PropValueOwned>,
+        /// Upper value bound.
+        end: Bound<PropValueOwned>,
+    },
+}
+
 /// Statistics describing a version-log vacuum run.
 #[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
 pub struct VersionVacuumStats {
@@ -239,6 +317,64 @@ pub struct PropStats {
     pub max: Option<PropValueOwned>,
 }
 
+/// Visible entity counts and id ranges for a snapshot, from [`Graph::summary`].
+///
+/// [`Graph::summary`]: super::Graph::summary
+#[derive(Clone, Debug, Default)]
+pub struct GraphSummary {
+    /// Number of visible nodes.
+    pub node_count: u64,
+    /// Number of visible edges.
+    pub edge_count: u64,
+    /// Visible nodes carrying each label.
+    pub label_counts: BTreeMap<LabelId, u64>,
+    /// Visible edges of each type.
+    pub type_counts: BTreeMap<TypeId, u64>,
+    /// Lowest and highest visible node id, if any node exists.
+    pub node_id_range: Option<(NodeId, NodeId)>,
+    /// Lowest and highest visible edge id, if any edge exists.
+    pub edge_id_range: Option<(EdgeId, EdgeId)>,
+}
+
+/// Indexes switched to building by [`Graph::suspend_indexes`], to be
+/// backfilled by [`Graph::restore_indexes`].
+///
+/// [`Graph::suspend_indexes`]: super::Graph::suspend_indexes
+/// [`Graph::restore_indexes`]: super::Graph::restore_indexes
+#[derive(Clone, Debug, Default)]
+pub struct SuspendedIndexes {
+    /// Property index definitions that were suspended.
+    pub properties: Vec<IndexDef>,
+}
+
+impl SuspendedIndexes {
+    /// Returns true when nothing was suspended.
+    pub fn is_empty(&self) -> bool {
+        self.properties.is_empty()
+    }
+
+    /// Appends another suspension's indexes to this one.
+    pub fn extend(&mut self, other: SuspendedIndexes) {
+        self.properties.extend(other.properties);
+    }
+}
+
+/// Disagreement between one index and the node rows, from
+/// [`Graph::index_drift`].
+///
+/// [`Graph::index_drift`]: super::Graph::index_drift
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IndexDrift {
+    /// Label the index covers.
+    pub label: LabelId,
+    /// Indexed property, or `None` for a label index.
+    pub prop: Option<PropId>,
+    /// Nodes listed by the index that their rows no longer place there.
+    pub stale: Vec<NodeId>,
+    /// Nodes whose rows belong in the index but are not listed.
+    pub missing: Vec<NodeId>,
+}
+
 /// Approximate space usage for core graph B-trees.
 #[derive(Clone, Copy, Debug, Default)]
 pub struct GraphSpaceUsage {
@@ -265,3 +401,59 @@ pub struct GraphSpaceUsage {
     /// B-tree pages used by the version log heap.
     pub version_log_pages: u64,
 }
+
+/// Outcome of [`Graph::merge_nodes`](super::Graph::merge_nodes).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MergeReport {
+    /// Edges re-pointed onto the surviving node.
+    pub edges_moved: usize,
+    /// Edges deleted as duplicates under
+    /// [`MergeNodeOpts::dedup_edges`](crate::storage::MergeNodeOpts::dedup_edges).
+    pub edges_dropped: usize,
+    /// Properties copied onto the surviving node, including overwrites.
+    pub props_copied: usize,
+    /// Properties both nodes defined with different values.
+    pub prop_conflicts: usize,
+}
+
+/// Where property values written since the graph opened were placed, from
+/// [`Graph::prop_placement`].
+///
+/// Values of a property map too large for its record count as overflow along
+/// with individually spilled values.
+///
+/// [`Graph::prop_placement`]: super::Graph::prop_placement
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PropPlacementStats {
+    /// Property values written inline in their owning record.
+    pub inline_writes: u64,
+    /// Property values written to VStore overflow pages.
+    pub overflow_writes: u64,
+}
+
+impl PropPlacementStats {
+    /// Fraction of values that went to overflow, or 0.0 when none were written.
+    pub fn overflow_ratio(&self) -> f64 {
+        let total = self.inline_writes.saturating_add(self.overflow_writes);
+        if total == 0 {
+            0.0
+        } else {
+            self.overflow_writes as f64 / total as f64
+        }
+    }
+}
+
+/// Page-level breakdown of free versus live space in the database file.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SpaceReport {
+    /// Page size in bytes.
+    pub page_size: u32,
+    /// Pages allocated in the file, including the meta page.
+    pub total_pages: u64,
+    /// Pages on the freelist awaiting reuse.
+    pub free_pages: u64,
+    /// Pages holding metadata, tree nodes, or overflow data.
+    pub live_pages: u64,
+    /// Fraction of `total_pages` that is free, in `[0, 1]`.
+    pub fragmentation: f64,
+}
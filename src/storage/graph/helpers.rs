@@ -1,8 +1,10 @@
+use std::ops::Bound;
 use std::sync::Arc;
 use std::time::SystemTime;
 
 use crate::primitives::pager::PageStore;
 use crate::storage::btree::{BTree, BTreeOptions};
+use crate::storage::mvcc::VersionHeader;
 use crate::storage::VersionedValue;
 use crate::types::{LabelId, PageId, Result, SombraError};
 
@@ -24,6 +26,23 @@ pub(crate) fn open_u64_vec_tree(
     BTree::open_or_create(store, opts)
 }
 
+/// Counts the rows of a node or edge tree whose latest version is not a
+/// tombstone.
+pub(crate) fn count_live_rows(
+    store: &Arc<dyn PageStore>,
+    tree: &BTree<u64, Vec<u8>>,
+) -> Result<u64> {
+    let read = store.begin_latest_committed_read()?;
+    let mut cursor = tree.range(&read, Bound::Unbounded, Bound::Unbounded)?;
+    let mut live = 0u64;
+    while let Some((_, bytes)) = cursor.next()? {
+        if !VersionHeader::decode(&bytes)?.is_tombstone() {
+            live += 1;
+        }
+    }
+    Ok(live)
+}
+
 pub(crate) fn open_unit_tree(
     store: &Arc<dyn PageStore>,
     root: PageId,
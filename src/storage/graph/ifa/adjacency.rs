@@ -1000,7 +1000,7 @@ impl IfaAdjacency {
         if let Some(entries) = header.lookup_inline_entries(type_id) {
             // Check if edge exists in inline entries
             let target_entry = InlineAdjEntry::new(neighbor, edge_id);
-            if entries.iter().any(|e| *e == target_entry) {
+            if entries.contains(&target_entry) {
                 // Remove inline entry
                 header.remove_inline_entry(type_id, neighbor, edge_id);
                 self.write_adj_page(tx, adj_page_id, &adj_page)?;
@@ -1215,7 +1215,7 @@ impl IfaAdjacency {
     ///
     /// The number of pre-allocated pages consumed.
     #[allow(dead_code)]
-    pub fn insert_edges_batch_true_ifa_preallocated<'a, I>(
+    pub fn insert_edges_batch_true_ifa_preallocated<I>(
         &self,
         tx: &mut WriteGuard<'_>,
         adj_page_id: PageId,
@@ -1268,7 +1268,7 @@ impl IfaAdjacency {
             };
 
             // Check if we already have an external segment
-            let has_external = old_ptr.map_or(false, |p| !p.is_null());
+            let has_external = old_ptr.is_some_and(|p| !p.is_null());
 
             // Calculate total edges after insertion
             let existing_count = existing_inline.as_ref().map_or(0, |e| e.len());
@@ -1313,7 +1313,7 @@ impl IfaAdjacency {
 
             // Get a pre-allocated page for this segment
             let page_id = preallocated_pages.next()
-                .ok_or_else(|| SombraError::Invalid("ran out of pre-allocated pages"))?;
+                .ok_or(SombraError::Invalid("ran out of pre-allocated pages"))?;
             pages_used += 1;
 
             // Re-read current inline entries (may have changed from partial insertion above)
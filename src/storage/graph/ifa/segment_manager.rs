@@ -338,7 +338,7 @@ impl SegmentManager {
         let extent_pages_needed = if remaining_after_first == 0 {
             0
         } else {
-            (remaining_after_first + max_entries_extent - 1) / max_entries_extent
+            remaining_after_first.div_ceil(max_entries_extent)
         };
         
         // Allocate primary page
@@ -1081,6 +1081,6 @@ mod segment_manager_tests {
         let (_pager, manager) = create_test_manager();
         let max = manager.max_entries_per_page();
         // With 8KB pages and 32-byte entries: (8192 - PAGE_HDR_LEN - 50) / 32 ≈ 250+
-        assert!(max > 200, "max_entries={} should be > 200", max);
+        assert!(max > 200, "max_entries={max} should be > 200");
     }
 }
@@ -699,11 +699,7 @@ impl IfaStore {
         let mut chain_depth = 0;
         const MAX_CHAIN_DEPTH: u32 = 100; // Prevent infinite loops
         
-        loop {
-            let block = match self.overflow.get_with_write(tx, &current_key)? {
-                Some(b) => b,
-                None => break,
-            };
+        while let Some(block) = self.overflow.get_with_write(tx, &current_key)? {
             
             for (type_id, ptr) in block.iter() {
                 result.push((type_id, ptr));
@@ -738,11 +734,7 @@ impl IfaStore {
         let mut chain_depth = 0;
         const MAX_CHAIN_DEPTH: u32 = 100;
 
-        loop {
-            let block = match self.overflow.get_with_write(tx, &current_key)? {
-                Some(b) => b,
-                None => break,
-            };
+        while let Some(block) = self.overflow.get_with_write(tx, &current_key)? {
 
             for (type_id, ptr) in block.iter() {
                 result.push((type_id, ptr));
@@ -776,11 +768,7 @@ impl IfaStore {
         let mut chain_depth = 0;
         const MAX_CHAIN_DEPTH: u32 = 100;
         
-        loop {
-            let block = match self.overflow.get(tx, &current_key)? {
-                Some(b) => b,
-                None => break,
-            };
+        while let Some(block) = self.overflow.get(tx, &current_key)? {
             
             for (type_id, ptr) in block.iter() {
                 result.push((type_id, ptr));
@@ -1013,6 +1001,7 @@ mod store_tests {
     }
 
     #[test]
+    #[allow(clippy::double_comparisons)]
     fn test_store_creation() {
         let (_pager, store) = create_test_store();
         let roots = store.roots();
@@ -1096,7 +1085,7 @@ mod store_tests {
             let result = store.lookup_type_mut(&mut tx, node, Dir::Out, TypeId(i)).unwrap();
             match result {
                 TypeLookupResult::External(ptr) => assert_eq!(ptr.0, i as u64 * 100),
-                _ => panic!("Expected Found for type {}", i),
+                _ => panic!("Expected Found for type {i}"),
             }
         }
     }
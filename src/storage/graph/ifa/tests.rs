@@ -260,6 +260,7 @@ fn overflow_block_roundtrip() {
 }
 
 #[test]
+#[allow(clippy::assertions_on_constants)]
 fn constants_sanity() {
     // Verify our constants make sense
     assert_eq!(SEGMENT_PTR_LEN, 8);
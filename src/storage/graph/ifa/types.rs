@@ -830,9 +830,14 @@ impl NodeAdjHeader {
                 if is_legacy {
                     // Legacy format - decode from fixed positions
                     buckets[bucket_idx] = bucket;
-                    for j in bucket_idx + 1..INLINE_BUCKET_COUNT - 1 {
+                    for (j, slot) in buckets
+                        .iter_mut()
+                        .enumerate()
+                        .take(INLINE_BUCKET_COUNT - 1)
+                        .skip(bucket_idx + 1)
+                    {
                         let pos = j * TYPE_BUCKET_LEN;
-                        buckets[j] = TypeBucket::decode(&bytes[pos..pos + TYPE_BUCKET_LEN])?;
+                        *slot = TypeBucket::decode(&bytes[pos..pos + TYPE_BUCKET_LEN])?;
                     }
                     return Ok(Self { buckets, inline_entries });
                 } else {
@@ -1071,9 +1076,9 @@ impl OverflowBlock {
             return Err(SombraError::Corruption("overflow block entry count too high"));
         }
         let mut entries = [TypeBucket::empty(); OVERFLOW_BLOCK_ENTRIES];
-        for i in 0..OVERFLOW_BLOCK_ENTRIES {
+        for (i, entry) in entries.iter_mut().enumerate() {
             let start = 10 + i * TYPE_BUCKET_LEN;
-            entries[i] = TypeBucket::decode(&bytes[start..start + TYPE_BUCKET_LEN])?;
+            *entry = TypeBucket::decode(&bytes[start..start + TYPE_BUCKET_LEN])?;
         }
         Ok(Self {
             next,
@@ -1,13 +1,13 @@
 use std::cmp::{Ordering as CmpOrdering, Ordering};
 use std::collections::{BTreeMap, HashSet, VecDeque};
-use std::ops::Bound;
+use std::ops::{Bound, ControlFlow};
 use std::sync::atomic::Ordering as AtomicOrdering;
 use std::sync::Arc;
 
 use crate::primitives::pager::{ReadGuard, WriteGuard};
 
 use crate::storage::index::{
-    collect_all, GraphIndexCacheStats, IndexDef, LabelScan, PostingStream,
+    collect_all, GraphIndexCacheStats, IndexDef, IndexState, LabelScan, PostingStream, TypeTag,
 };
 use crate::storage::mvcc::CommitId;
 
@@ -16,10 +16,10 @@ use crate::storage::PropValueOwned;
 use crate::types::{EdgeId, LabelId, NodeId, PageId, PropId, Result, SombraError, TypeId};
 
 
-use super::graph_types::PropStats;
+use super::graph_types::{GraphSummary, IndexDrift, PropStats, SuspendedIndexes};
 use super::prop_ops::{
-    clone_owned_bound, encode_range_bound, encode_value_key_owned, prop_stats_key,
-    update_min_max,
+    clone_owned_bound, encode_composite_column, encode_composite_prefix, encode_index_key,
+    encode_range_bound, index_key_for, prefix_successor, prop_stats_key, update_min_max,
 };
 use super::Graph;
 
@@ -61,20 +61,38 @@ impl Graph {
         self.bump_ddl_epoch(tx)
     }
 
+    /// Returns whether writers should create label indexes on first use.
+    ///
+    /// Mirrors [`GraphOptions::auto_label_index`](crate::storage::GraphOptions::auto_label_index).
+    pub fn auto_label_index(&self) -> bool {
+        self.auto_label_index
+    }
+
     /// Checks if a label index exists for the given label.
     pub fn has_label_index(&self, label: LabelId) -> Result<bool> {
         self.indexes.has_label_index(label)
     }
 
     /// Creates a property index for fast property-based lookups.
+    ///
+    /// The index is backfilled in `tx`, so it is stored as
+    /// [`IndexState::Ready`] whatever state `def` carries. Creating an index
+    /// that already exists is a no-op; a different index on the same label
+    /// and leading property, such as `(a)` against `(a, b)`, is rejected.
     pub fn create_property_index(&self, tx: &mut WriteGuard<'_>, def: IndexDef) -> Result<()> {
+        def.validate()?;
         let existing = self
             .indexes
             .property_indexes_for_label_with_write(tx, def.label)?;
-        if existing.iter().any(|entry| entry.prop == def.prop) {
+        if index_exists(&existing, &def)? {
             return Ok(());
         }
+        let def = IndexDef {
+            state: IndexState::Ready,
+            ..def
+        };
         let mut entries: Vec<(Vec<u8>, NodeId)> = Vec::new();
+        let mut unique_values: BTreeMap<NodeId, Vec<PropValueOwned>> = BTreeMap::new();
         self.nodes.for_each_with_write(tx, |id_raw, bytes| {
             let versioned = crate::storage::node::decode(&bytes)?;
             if versioned.header.is_tombstone() {
@@ -86,9 +104,14 @@ impl Graph {
             let prop_bytes = self.read_node_prop_bytes(&versioned.row.props)?;
             let props = self.materialize_props_owned(&prop_bytes)?;
             let map: BTreeMap<PropId, PropValueOwned> = props.into_iter().collect();
-            if let Some(value) = map.get(&def.prop) {
-                let key = encode_value_key_owned(def.ty, value)?;
-                entries.push((key, NodeId(id_raw)));
+            let Some(key) = index_key_for(&def, &map)? else {
+                return Ok(());
+            };
+            entries.push((key, NodeId(id_raw)));
+            if def.unique {
+                if let Some(values) = unique_index_values(&def, &map) {
+                    unique_values.insert(NodeId(id_raw), values.into_iter().cloned().collect());
+                }
             }
             Ok(())
         })?;
@@ -96,11 +119,81 @@ impl Graph {
             CmpOrdering::Equal => node_a.0.cmp(&node_b.0),
             other => other,
         });
+        if def.unique {
+            // Nodes sharing a key are compared by value, as truncated keys can
+            // also be shared by distinct long values.
+            for group in entries.chunk_by(|(a, _), (b, _)| a == b) {
+                for (idx, (_, first)) in group.iter().enumerate() {
+                    let Some(value) = unique_values.get(first) else {
+                        continue;
+                    };
+                    if let Some((_, second)) = group[idx + 1..]
+                        .iter()
+                        .find(|(_, other)| unique_values.get(other) == Some(value))
+                    {
+                        return Err(SombraError::ConstraintViolation(format!(
+                            "nodes {} and {} share a value for unique property {} on label {}",
+                            first.0,
+                            second.0,
+                            def.prop().0,
+                            def.label.0
+                        )));
+                    }
+                }
+            }
+        }
         self.indexes.create_property_index(tx, def, &entries)?;
         self.sync_index_roots(tx)?;
         self.bump_ddl_epoch(tx)
     }
 
+    /// Registers a property index in [`IndexState::Building`] without
+    /// backfilling existing nodes.
+    ///
+    /// Writes skip a building index, so the planner keeps scanning until
+    /// [`Graph::finish_property_index_build`] backfills it and marks it ready.
+    /// Existing indexes are handled as in [`Graph::create_property_index`].
+    pub fn begin_property_index_build(&self, tx: &mut WriteGuard<'_>, def: IndexDef) -> Result<()> {
+        def.validate()?;
+        let existing = self
+            .indexes
+            .property_indexes_for_label_with_write(tx, def.label)?;
+        if index_exists(&existing, &def)? {
+            return Ok(());
+        }
+        let def = IndexDef {
+            state: IndexState::Building,
+            ..def
+        };
+        self.indexes.create_property_index(tx, def, &[])?;
+        self.sync_index_roots(tx)?;
+        self.bump_ddl_epoch(tx)
+    }
+
+    /// Backfills an index registered by [`Graph::begin_property_index_build`]
+    /// and marks it [`IndexState::Ready`].
+    ///
+    /// Returns `false` when no such index exists or it is already ready.
+    pub fn finish_property_index_build(
+        &self,
+        tx: &mut WriteGuard<'_>,
+        label: LabelId,
+        prop: PropId,
+    ) -> Result<bool> {
+        let defs = self
+            .indexes
+            .property_indexes_for_label_with_write(tx, label)?;
+        let Some(def) = defs.into_iter().find(|d| d.prop() == prop) else {
+            return Ok(false);
+        };
+        if def.state == IndexState::Ready {
+            return Ok(false);
+        }
+        self.indexes.drop_property_index(tx, def.clone())?;
+        self.create_property_index(tx, def)?;
+        Ok(true)
+    }
+
     /// Drops a property index for the given label and property.
     pub fn drop_property_index(
         &self,
@@ -111,7 +204,7 @@ impl Graph {
         let defs = self
             .indexes
             .property_indexes_for_label_with_write(tx, label)?;
-        let Some(def) = defs.into_iter().find(|d| d.prop == prop) else {
+        let Some(def) = defs.into_iter().find(|d| d.prop() == prop) else {
             return Ok(());
         };
         self.indexes.drop_property_index(tx, def)?;
@@ -119,6 +212,171 @@ impl Graph {
         self.bump_ddl_epoch(tx)
     }
 
+    /// Backfills every property index still in [`IndexState::Building`],
+    /// such as those left behind by a load that suspended indexes and never
+    /// restored them.
+    ///
+    /// Returns how many indexes were rebuilt.
+    pub fn finish_index_builds(&self, tx: &mut WriteGuard<'_>) -> Result<usize> {
+        let mut finished = 0;
+        for def in self.indexes.all_property_indexes_with_write(tx)? {
+            if self.finish_property_index_build(tx, def.label, def.prop())? {
+                finished += 1;
+            }
+        }
+        Ok(finished)
+    }
+
+    /// Switches every ready property index on `labels` to
+    /// [`IndexState::Building`] with no entries so bulk writes skip index
+    /// maintenance.
+    ///
+    /// Unique indexes are left ready, so the load still checks and maintains
+    /// them per node. Pass the result to [`Graph::restore_indexes`] once the
+    /// load is done.
+    /// Commit the suspension on its own: the definitions stay in the catalog,
+    /// so if the load never restores them [`Graph::finish_index_builds`]
+    /// rebuilds them later.
+    pub fn suspend_indexes(
+        &self,
+        tx: &mut WriteGuard<'_>,
+        labels: &[LabelId],
+    ) -> Result<SuspendedIndexes> {
+        let mut suspended = SuspendedIndexes::default();
+        for &label in labels {
+            for def in self
+                .indexes
+                .property_indexes_for_label_with_write(tx, label)?
+            {
+                if def.state == IndexState::Building || def.unique {
+                    continue;
+                }
+                self.indexes.drop_property_index(tx, def.clone())?;
+                let building = IndexDef {
+                    state: IndexState::Building,
+                    ..def.clone()
+                };
+                self.indexes.create_property_index(tx, building, &[])?;
+                suspended.properties.push(def);
+            }
+        }
+        if suspended.is_empty() {
+            return Ok(suspended);
+        }
+        self.sync_index_roots(tx)?;
+        self.bump_ddl_epoch(tx)?;
+        Ok(suspended)
+    }
+
+    /// Backfills the indexes suspended by [`Graph::suspend_indexes`] from the
+    /// current node rows.
+    pub fn restore_indexes(
+        &self,
+        tx: &mut WriteGuard<'_>,
+        suspended: SuspendedIndexes,
+    ) -> Result<()> {
+        for def in suspended.properties {
+            self.finish_property_index_build(tx, def.label, def.prop())?;
+        }
+        Ok(())
+    }
+
+    /// Compares indexes against the node rows visible to `tx`.
+    ///
+    /// Every ready property index is checked. Label indexes are checked for
+    /// the labels visible nodes carry, since the label index keeps no list of
+    /// its labels. Only indexes with stale or missing entries are returned.
+    pub fn index_drift(&self, tx: &ReadGuard) -> Result<Vec<IndexDrift>> {
+        let defs: Vec<IndexDef> = self
+            .indexes
+            .all_property_indexes(tx)?
+            .into_iter()
+            .filter(|def| def.state == IndexState::Ready)
+            .collect();
+        let mut expected_props: Vec<HashSet<(Vec<u8>, NodeId)>> = vec![HashSet::new(); defs.len()];
+        let mut expected_labels: BTreeMap<LabelId, HashSet<NodeId>> = BTreeMap::new();
+        let mut cursor = self.nodes.range(tx, Bound::Unbounded, Bound::Unbounded)?;
+        while let Some((key, bytes)) = cursor.next()? {
+            let node = NodeId(key);
+            let Some(versioned) = self.visible_node_from_bytes(tx, node, &bytes)? else {
+                continue;
+            };
+            let labels = versioned.row.labels;
+            for label in &labels {
+                expected_labels.entry(*label).or_default().insert(node);
+            }
+            if !defs
+                .iter()
+                .any(|def| labels.binary_search(&def.label).is_ok())
+            {
+                continue;
+            }
+            let Some(data) = self.get_node(tx, node)? else {
+                continue;
+            };
+            let props: BTreeMap<PropId, PropValueOwned> = data.props.into_iter().collect();
+            for (def, expected) in defs.iter().zip(expected_props.iter_mut()) {
+                if labels.binary_search(&def.label).is_err() {
+                    continue;
+                }
+                if let Some(key) = index_key_for(def, &props)? {
+                    expected.insert((key, node));
+                }
+            }
+        }
+
+        let mut drift = Vec::new();
+        for (def, expected) in defs.iter().zip(expected_props) {
+            let actual: HashSet<(Vec<u8>, NodeId)> = self
+                .indexes
+                .scan_property_range(tx, def, Bound::Unbounded, Bound::Unbounded)?
+                .into_iter()
+                .collect();
+            let stale = actual.difference(&expected).map(|(_, node)| *node);
+            let missing = expected.difference(&actual).map(|(_, node)| *node);
+            push_drift(&mut drift, def.label, Some(def.prop()), stale, missing);
+        }
+        for (label, expected) in expected_labels {
+            let Some(mut scan) = self.indexes.label_scan(tx, label)? else {
+                continue;
+            };
+            let actual: HashSet<NodeId> = collect_posting_stream(&mut scan)?.into_iter().collect();
+            let stale = actual.difference(&expected).copied();
+            let missing = expected.difference(&actual).copied();
+            push_drift(&mut drift, label, None, stale, missing);
+        }
+        Ok(drift)
+    }
+
+    /// Drops and rebuilds one index from the current node rows.
+    ///
+    /// `prop` selects the property index on `label`, or the label index when
+    /// `None`. Returns false when no such index exists.
+    pub fn rebuild_index(
+        &self,
+        tx: &mut WriteGuard<'_>,
+        label: LabelId,
+        prop: Option<PropId>,
+    ) -> Result<bool> {
+        let Some(prop) = prop else {
+            if !self.indexes.has_label_index_with_write(tx, label)? {
+                return Ok(false);
+            }
+            self.indexes.drop_label_index(tx, label)?;
+            self.create_label_index(tx, label)?;
+            return Ok(true);
+        };
+        let defs = self
+            .indexes
+            .property_indexes_for_label_with_write(tx, label)?;
+        let Some(def) = defs.into_iter().find(|d| d.prop() == prop) else {
+            return Ok(false);
+        };
+        self.indexes.drop_property_index(tx, def.clone())?;
+        self.create_property_index(tx, def)?;
+        Ok(true)
+    }
+
     /// Checks if a property index exists for the given label and property.
     pub fn has_property_index(&self, label: LabelId, prop: PropId) -> Result<bool> {
         let read = self.lease_latest_snapshot()?;
@@ -155,6 +413,34 @@ impl Graph {
             .scan_property_range(tx, &def, Bound::Unbounded, Bound::Unbounded)
     }
 
+    /// Walks the property index on `(label, prop)` in key order.
+    ///
+    /// Calls `f` with each encoded value key and node ID visible to `tx` until
+    /// it returns [`ControlFlow::Break`]. Keys of indexes with
+    /// [`IndexDef::max_key_bytes`] set are reported truncated, as stored.
+    /// Fails when no such index exists.
+    pub fn scan_index(
+        &self,
+        tx: &ReadGuard,
+        label: LabelId,
+        prop: PropId,
+        mut f: impl FnMut(&[u8], NodeId) -> ControlFlow<()>,
+    ) -> Result<()> {
+        let def = self
+            .indexes
+            .get_property_index(tx, label, prop)?
+            .ok_or(SombraError::Invalid("property index not found"))?;
+        let entries =
+            self.indexes
+                .scan_property_range(tx, &def, Bound::Unbounded, Bound::Unbounded)?;
+        for (key, node) in &entries {
+            if f(key, *node).is_break() {
+                break;
+            }
+        }
+        Ok(())
+    }
+
     /// Returns all property index definitions currently registered.
     pub fn all_property_indexes(&self) -> Result<Vec<IndexDef>> {
         let read = self.lease_latest_snapshot()?;
@@ -187,9 +473,12 @@ impl Graph {
             .get_property_index(tx, label, prop)?
             .ok_or(SombraError::Invalid("property index not found"))?;
         record_storage_profile_timer(StorageProfileKind::PropIndexLookup, lookup_timer);
+        if def.is_composite() {
+            return self.composite_def_scan_stream(tx, &def, std::slice::from_ref(value), None);
+        }
 
         let encode_timer = storage_profile_timer();
-        let key = encode_value_key_owned(def.ty, value)?;
+        let key = encode_index_key(&def, value)?;
         record_storage_profile_timer(StorageProfileKind::PropIndexKeyEncode, encode_timer);
 
         let stream_timer = storage_profile_timer();
@@ -253,10 +542,13 @@ impl Graph {
             .get_property_index(tx, label, prop)?
             .ok_or(SombraError::Invalid("property index not found"))?;
         record_storage_profile_timer(StorageProfileKind::PropIndexLookup, lookup_timer);
+        if def.is_composite() {
+            return self.composite_def_scan_stream(tx, &def, &[], Some((start, end)));
+        }
 
         let encode_timer = storage_profile_timer();
-        let start_key = encode_range_bound(def.ty, start)?;
-        let end_key = encode_range_bound(def.ty, end)?;
+        let start_key = encode_range_bound(&def, start, false)?;
+        let end_key = encode_range_bound(&def, end, true)?;
         record_storage_profile_timer(StorageProfileKind::PropIndexKeyEncode, encode_timer);
 
         let stream_timer = storage_profile_timer();
@@ -276,6 +568,200 @@ impl Graph {
         Ok(instrument_posting_stream(filtered))
     }
 
+    /// Scans for nodes whose string property starts with `prefix`.
+    pub fn property_scan_prefix(
+        &self,
+        tx: &ReadGuard,
+        label: LabelId,
+        prop: PropId,
+        prefix: &str,
+    ) -> Result<Vec<NodeId>> {
+        let mut stream = self.property_scan_prefix_stream(tx, label, prop, prefix)?;
+        collect_posting_stream(&mut *stream)
+    }
+
+    /// Returns a stream of node IDs whose string property starts with `prefix`.
+    ///
+    /// String keys lead with their length, so the keys sharing a prefix form
+    /// one contiguous run per value length. The scan seeks to the prefix
+    /// within each length present in the index and reads until keys stop
+    /// matching, then jumps straight to the next length that holds keys.
+    pub fn property_scan_prefix_stream<'a>(
+        &'a self,
+        tx: &'a ReadGuard,
+        label: LabelId,
+        prop: PropId,
+        prefix: &str,
+    ) -> Result<Box<dyn PostingStream + 'a>> {
+        let lookup_timer = storage_profile_timer();
+        let def = self
+            .indexes
+            .get_property_index(tx, label, prop)?
+            .ok_or(SombraError::Invalid("property index not found"))?;
+        record_storage_profile_timer(StorageProfileKind::PropIndexLookup, lookup_timer);
+        if def.ty() != TypeTag::String {
+            return Err(SombraError::Invalid(
+                "prefix scans require a string property index",
+            ));
+        }
+
+        // Truncated keys only keep the leading payload bytes, so longer
+        // prefixes seek on those and leave the rest to the filter.
+        let mut seek = prefix.as_bytes();
+        if let Some(max) = def.max_key_bytes {
+            seek = &seek[..seek.len().min(usize::from(max))];
+        }
+        let mut len = u32::try_from(prefix.len())
+            .map_err(|_| SombraError::Invalid("property value exceeds maximum length"))?;
+
+        let stream_timer = storage_profile_timer();
+        let mut nodes = Vec::new();
+        loop {
+            let mut start = len.to_be_bytes().to_vec();
+            start.extend_from_slice(seek);
+            let end = prefix_successor(&start).map_or(Bound::Unbounded, Bound::Excluded);
+            nodes.extend(
+                self.indexes
+                    .scan_property_range(tx, &def, Bound::Included(start), end)?
+                    .into_iter()
+                    .map(|(_, node)| node),
+            );
+            let Some(next) = len.checked_add(1) else {
+                break;
+            };
+            let next_key = self.indexes.first_property_key(
+                tx,
+                &def,
+                Bound::Included(next.to_be_bytes().to_vec()),
+            )?;
+            let Some(key) = next_key else {
+                break;
+            };
+            let header = key
+                .get(..4)
+                .ok_or(SombraError::Corruption("string index key missing length"))?;
+            len = u32::from_be_bytes([header[0], header[1], header[2], header[3]]);
+        }
+        nodes.sort_unstable_by_key(|node| node.0);
+        nodes.dedup();
+        let stream: Box<dyn PostingStream + 'a> = Box::new(SortedNodeStream { nodes, pos: 0 });
+        record_storage_profile_timer(StorageProfileKind::PropIndexStreamBuild, stream_timer);
+        let filtered =
+            PropertyFilterStream::new_prefix(self, tx, stream, label, prop, prefix.to_owned());
+        Ok(instrument_posting_stream(filtered))
+    }
+
+    /// Scans the composite index led by `prop` for nodes whose leading
+    /// properties equal `eq` in order, optionally bounding the property after
+    /// them by `range`.
+    pub fn composite_scan(
+        &self,
+        tx: &ReadGuard,
+        label: LabelId,
+        prop: PropId,
+        eq: &[PropValueOwned],
+        range: Option<(Bound<&PropValueOwned>, Bound<&PropValueOwned>)>,
+    ) -> Result<Vec<NodeId>> {
+        let mut stream = self.composite_scan_stream(tx, label, prop, eq, range)?;
+        collect_posting_stream(&mut *stream)
+    }
+
+    /// Returns a stream of node IDs matching a [`Graph::composite_scan`].
+    pub fn composite_scan_stream<'a>(
+        &'a self,
+        tx: &'a ReadGuard,
+        label: LabelId,
+        prop: PropId,
+        eq: &[PropValueOwned],
+        range: Option<(Bound<&PropValueOwned>, Bound<&PropValueOwned>)>,
+    ) -> Result<Box<dyn PostingStream + 'a>> {
+        let lookup_timer = storage_profile_timer();
+        let def = self
+            .indexes
+            .get_property_index(tx, label, prop)?
+            .ok_or(SombraError::Invalid("property index not found"))?;
+        record_storage_profile_timer(StorageProfileKind::PropIndexLookup, lookup_timer);
+        self.composite_def_scan_stream(tx, &def, eq, range)
+    }
+
+    /// Scans the key range shared by every node whose leading properties
+    /// equal `eq`, narrowed by `range` on the next property, and confirms
+    /// each candidate against its stored values.
+    fn composite_def_scan_stream<'a>(
+        &'a self,
+        tx: &'a ReadGuard,
+        def: &IndexDef,
+        eq: &[PropValueOwned],
+        range: Option<(Bound<&PropValueOwned>, Bound<&PropValueOwned>)>,
+    ) -> Result<Box<dyn PostingStream + 'a>> {
+        let covered = eq.len() + usize::from(range.is_some());
+        if covered == 0 || covered > def.props.len() {
+            return Err(SombraError::Invalid(
+                "composite index lookup must cover between one and all properties",
+            ));
+        }
+        if !def.is_composite() {
+            return match range {
+                Some((start, end)) => {
+                    self.property_scan_range_stream(tx, def.label, def.prop(), start, end)
+                }
+                None => self.property_scan_eq_stream(tx, def.label, def.prop(), &eq[0]),
+            };
+        }
+
+        let encode_timer = storage_profile_timer();
+        let columns = encode_composite_prefix(def, eq, false)?;
+        let prefix = encode_composite_prefix(def, eq, range.is_some())?;
+        let (start, end) = range.unwrap_or((Bound::Unbounded, Bound::Unbounded));
+        let bounded_key = |bound: Bound<&PropValueOwned>| -> Result<Option<Vec<u8>>> {
+            match bound {
+                Bound::Unbounded => Ok(None),
+                Bound::Included(value) | Bound::Excluded(value) => {
+                    let mut key = columns.clone();
+                    key.extend_from_slice(&encode_composite_column(def, eq.len(), value)?);
+                    Ok(Some(key))
+                }
+            }
+        };
+        // Keys extend past the covered properties, so bounds are widened to
+        // whole key prefixes and exclusive bounds are enforced by the filter.
+        let start_key = match bounded_key(start)? {
+            Some(key) => Bound::Included(key),
+            None if prefix.is_empty() => Bound::Unbounded,
+            None => Bound::Included(prefix.clone()),
+        };
+        let end_key = bounded_key(end)?
+            .or_else(|| (!prefix.is_empty()).then(|| prefix.clone()))
+            .and_then(|key| prefix_successor(&key))
+            .map_or(Bound::Unbounded, Bound::Excluded);
+        record_storage_profile_timer(StorageProfileKind::PropIndexKeyEncode, encode_timer);
+
+        let stream_timer = storage_profile_timer();
+        // Each key holds its own ascending postings, so candidates spanning
+        // several keys are sorted here to keep the stream ordered.
+        let mut nodes: Vec<NodeId> = self
+            .indexes
+            .scan_property_range(tx, def, start_key, end_key)?
+            .into_iter()
+            .map(|(_, node)| node)
+            .collect();
+        nodes.sort_unstable_by_key(|node| node.0);
+        nodes.dedup();
+        let stream: Box<dyn PostingStream + 'a> = Box::new(SortedNodeStream { nodes, pos: 0 });
+        record_storage_profile_timer(StorageProfileKind::PropIndexStreamBuild, stream_timer);
+        let filtered = PropertyFilterStream::new_composite(
+            self,
+            tx,
+            stream,
+            def.label,
+            def.props[..covered].to_vec(),
+            eq.to_vec(),
+            clone_owned_bound(start),
+            clone_owned_bound(end),
+        );
+        Ok(instrument_posting_stream(filtered))
+    }
+
     /// Returns a label scan iterator; this prefers real indexes.
     pub fn label_scan<'a>(
         &'a self,
@@ -340,6 +826,76 @@ impl Graph {
         Ok(nodes)
     }
 
+    /// Returns the nodes carrying `label` as the write transaction sees them,
+    /// including nodes it created or deleted itself.
+    ///
+    /// Deferred index writes are flushed first. Labels without an index fall
+    /// back to a scan of the node tree.
+    pub fn nodes_with_label_with_write(
+        &self,
+        tx: &mut WriteGuard<'_>,
+        label: LabelId,
+    ) -> Result<Vec<NodeId>> {
+        self.flush_deferred_writes(tx)?;
+        if let Some(nodes) = self.indexes.label_nodes_with_write(tx, label)? {
+            return Ok(nodes);
+        }
+        let mut nodes = Vec::new();
+        self.nodes.for_each_with_write(tx, |id_raw, bytes| {
+            let versioned = crate::storage::node::decode(&bytes)?;
+            if !versioned.header.is_tombstone()
+                && versioned.row.labels.binary_search(&label).is_ok()
+            {
+                nodes.push(NodeId(id_raw));
+            }
+            Ok(())
+        })?;
+        Ok(nodes)
+    }
+
+    /// Returns up to `limit` node identifiers carrying `label`, highest id first.
+    ///
+    /// The label index is walked from its highest entry down and the walk
+    /// stops after `limit` matches. Labels without an index fall back to a
+    /// descending scan of the node tree.
+    pub fn last_nodes_with_label(
+        &self,
+        tx: &ReadGuard,
+        label: LabelId,
+        limit: usize,
+    ) -> Result<Vec<NodeId>> {
+        let mut nodes = Vec::new();
+        if limit == 0 {
+            return Ok(nodes);
+        }
+        if let Some(mut scan) = self.indexes.label_scan_rev(tx, label)? {
+            while let Some(node) = scan.next()? {
+                if self.node_has_label(tx, node, label)? {
+                    nodes.push(node);
+                    if nodes.len() == limit {
+                        break;
+                    }
+                }
+            }
+            return Ok(nodes);
+        }
+        let mut cursor = self
+            .nodes
+            .range_rev(tx, Bound::Unbounded, Bound::Unbounded)?;
+        while let Some((key, bytes)) = cursor.next()? {
+            let Some(versioned) = self.visible_node_from_bytes(tx, NodeId(key), &bytes)? else {
+                continue;
+            };
+            if versioned.row.labels.binary_search(&label).is_ok() {
+                nodes.push(NodeId(key));
+                if nodes.len() == limit {
+                    break;
+                }
+            }
+        }
+        Ok(nodes)
+    }
+
     /// Counts the number of edges that have the provided type.
     pub fn count_edges_with_type(&self, tx: &ReadGuard, ty: TypeId) -> Result<u64> {
         let mut cursor = self.edges.range(tx, Bound::Unbounded, Bound::Unbounded)?;
@@ -356,6 +912,38 @@ impl Graph {
         Ok(count)
     }
 
+    /// Counts visible nodes and edges per label and type in one pass each.
+    ///
+    /// Both trees are keyed by id, so the id ranges fall out of the same scan.
+    pub fn summary(&self, tx: &ReadGuard) -> Result<GraphSummary> {
+        let mut summary = GraphSummary::default();
+        let mut cursor = self.nodes.range(tx, Bound::Unbounded, Bound::Unbounded)?;
+        while let Some((key, bytes)) = cursor.next()? {
+            let id = NodeId(key);
+            let Some(versioned) = self.visible_node_from_bytes(tx, id, &bytes)? else {
+                continue;
+            };
+            summary.node_count += 1;
+            for label in versioned.row.labels {
+                *summary.label_counts.entry(label).or_insert(0) += 1;
+            }
+            let (first, _) = summary.node_id_range.get_or_insert((id, id));
+            summary.node_id_range = Some((*first, id));
+        }
+        let mut cursor = self.edges.range(tx, Bound::Unbounded, Bound::Unbounded)?;
+        while let Some((key, bytes)) = cursor.next()? {
+            let id = EdgeId(key);
+            let Some(versioned) = self.visible_edge_from_bytes(tx, id, &bytes)? else {
+                continue;
+            };
+            summary.edge_count += 1;
+            *summary.type_counts.entry(versioned.row.ty).or_insert(0) += 1;
+            let (first, _) = summary.edge_id_range.get_or_insert((id, id));
+            summary.edge_id_range = Some((*first, id));
+        }
+        Ok(summary)
+    }
+
     /// Samples up to `limit` nodes from the B-Tree and returns their label lists.
     pub fn sample_node_labels(&self, tx: &ReadGuard, limit: usize) -> Result<Vec<Vec<LabelId>>> {
         if limit == 0 {
@@ -378,7 +966,7 @@ impl Graph {
         &self,
         tx: &ReadGuard,
         label: LabelId,
-    ) -> Result<FallbackLabelScan> {
+    ) -> Result<SortedNodeStream> {
         let mut cursor = self.nodes.range(tx, Bound::Unbounded, Bound::Unbounded)?;
         let mut nodes = Vec::new();
         while let Some((key, bytes)) = cursor.next()? {
@@ -390,7 +978,7 @@ impl Graph {
                 nodes.push(NodeId(key));
             }
         }
-        Ok(FallbackLabelScan { nodes, pos: 0 })
+        Ok(SortedNodeStream { nodes, pos: 0 })
     }
 
     pub(crate) fn insert_indexed_props(
@@ -404,9 +992,8 @@ impl Graph {
         for label in labels {
             let defs = self.index_defs_for_label(tx, *label)?;
             for def in defs.iter() {
-                if let Some(value) = props.get(&def.prop) {
-                    let key = encode_value_key_owned(def.ty, value)?;
-                    self.stage_prop_index_op(tx, *def, key, node, commit, true)?;
+                if let Some(key) = index_key_for(def, props)? {
+                    self.stage_prop_index_op(tx, def.clone(), key, node, commit, true)?;
                 }
             }
         }
@@ -426,16 +1013,14 @@ impl Graph {
             let defs = self.index_defs_for_label(tx, *label)?;
             if self.defer_index_flush {
                 for def in defs.iter() {
-                    let old = old_props.get(&def.prop);
-                    let new = new_props.get(&def.prop);
+                    let old = index_key_for(def, old_props)?;
+                    let new = index_key_for(def, new_props)?;
                     match (old, new) {
-                        (_, Some(value)) => {
-                            let key = encode_value_key_owned(def.ty, value)?;
-                            self.stage_prop_index_op(tx, *def, key, node, commit, true)?;
+                        (_, Some(key)) => {
+                            self.stage_prop_index_op(tx, def.clone(), key, node, commit, true)?;
                         }
-                        (Some(prev), None) => {
-                            let key = encode_value_key_owned(def.ty, prev)?;
-                            self.stage_prop_index_op(tx, *def, key, node, commit, false)?;
+                        (Some(key), None) => {
+                            self.stage_prop_index_op(tx, def.clone(), key, node, commit, false)?;
                         }
                         _ => {}
                     };
@@ -443,11 +1028,10 @@ impl Graph {
                 continue;
             }
             for def in defs.iter() {
-                let old = old_props.get(&def.prop);
-                let new = new_props.get(&def.prop);
+                let old = index_key_for(def, old_props)?;
+                let new = index_key_for(def, new_props)?;
                 match (old, new) {
-                    (_, Some(value)) => {
-                        let key = encode_value_key_owned(def.ty, value)?;
+                    (_, Some(key)) => {
                         self.indexes.insert_property_value_with_commit(
                             tx,
                             def,
@@ -456,8 +1040,7 @@ impl Graph {
                             Some(commit),
                         )?;
                     }
-                    (Some(prev), None) => {
-                        let key = encode_value_key_owned(def.ty, prev)?;
+                    (Some(key), None) => {
                         self.indexes.remove_property_value_with_commit(
                             tx,
                             def,
@@ -473,6 +1056,103 @@ impl Graph {
         Ok(())
     }
 
+    /// Rejects a node write that would give a unique index a value another
+    /// node already holds.
+    ///
+    /// `new_props` is the node's property map after the write and `old_props`
+    /// the map before it, so values the node already holds are not rechecked.
+    /// Index hits are confirmed against each candidate's current properties,
+    /// since updates leave postings for superseded values behind and long
+    /// values may share a truncated key.
+    pub(crate) fn check_unique_props(
+        &self,
+        tx: &mut WriteGuard<'_>,
+        node: Option<NodeId>,
+        labels: &[LabelId],
+        old_props: &BTreeMap<PropId, PropValueOwned>,
+        new_props: &BTreeMap<PropId, PropValueOwned>,
+    ) -> Result<()> {
+        for label in labels {
+            let defs = self.index_defs_for_label(tx, *label)?;
+            for def in defs.iter().filter(|def| def.unique) {
+                let Some(values) = unique_index_values(def, new_props) else {
+                    continue;
+                };
+                if def
+                    .props
+                    .iter()
+                    .zip(&values)
+                    .all(|(prop, value)| old_props.get(prop) == Some(*value))
+                {
+                    continue;
+                }
+                let Some(key) = index_key_for(def, new_props)? else {
+                    continue;
+                };
+                let mut candidates = self.indexes.scan_property_eq_with_write(tx, def, &key)?;
+                let state = self.take_txn_state(tx);
+                if let Some(buffer) = state.deferred_index.as_ref() {
+                    candidates.extend(
+                        buffer
+                            .prop_inserts
+                            .iter()
+                            .filter(|(staged, staged_key, _, _)| {
+                                staged.label == def.label
+                                    && staged.prop() == def.prop()
+                                    && *staged_key == key
+                            })
+                            .map(|(_, _, id, _)| *id),
+                    );
+                }
+                self.store_txn_state(tx, state);
+                for other in candidates {
+                    if Some(other) == node {
+                        continue;
+                    }
+                    let Some(other_props) =
+                        self.labeled_node_props_with_write(tx, other, def.label)?
+                    else {
+                        continue;
+                    };
+                    if def
+                        .props
+                        .iter()
+                        .zip(&values)
+                        .all(|(prop, value)| other_props.get(prop) == Some(*value))
+                    {
+                        return Err(SombraError::ConstraintViolation(format!(
+                            "node {} already holds this value for unique property {} on label {}",
+                            other.0,
+                            def.prop().0,
+                            def.label.0
+                        )));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads the properties of `node` as the writer sees it, provided the
+    /// node is live and carries `label`.
+    fn labeled_node_props_with_write(
+        &self,
+        tx: &mut WriteGuard<'_>,
+        node: NodeId,
+        label: LabelId,
+    ) -> Result<Option<BTreeMap<PropId, PropValueOwned>>> {
+        let Some(bytes) = self.nodes.get_with_write(tx, &node.0)? else {
+            return Ok(None);
+        };
+        let versioned = crate::storage::node::decode(&bytes)?;
+        if versioned.header.is_tombstone() || versioned.row.labels.binary_search(&label).is_err() {
+            return Ok(None);
+        }
+        let prop_bytes = self.read_node_prop_bytes_with_write(tx, &versioned.row.props)?;
+        let props = self.materialize_props_owned_with_write(tx, &prop_bytes)?;
+        Ok(Some(props.into_iter().collect()))
+    }
+
     fn index_defs_for_label(
         &self,
         tx: &mut WriteGuard<'_>,
@@ -481,20 +1161,23 @@ impl Graph {
         let mut state = self.take_txn_state(tx);
         state.index_cache.sync_epoch(self.catalog_epoch.current());
         let result = state.index_cache.get_or_load(label, |label| {
-            self.indexes
-                .property_indexes_for_label_with_write(tx, label)
+            let mut defs = self
+                .indexes
+                .property_indexes_for_label_with_write(tx, label)?;
+            defs.retain(|def| def.state == IndexState::Ready);
+            Ok(defs)
         });
         self.store_txn_state(tx, state);
         result
     }
 
-    fn bump_ddl_epoch(&self, tx: &mut WriteGuard<'_>) -> Result<()> {
+    pub(crate) fn bump_ddl_epoch(&self, tx: &mut WriteGuard<'_>) -> Result<()> {
         self.catalog_epoch.bump_in_txn(tx)?;
         self.invalidate_txn_cache(tx);
         Ok(())
     }
 
-    fn sync_index_roots(&self, tx: &mut WriteGuard<'_>) -> Result<()> {
+    pub(crate) fn sync_index_roots(&self, tx: &mut WriteGuard<'_>) -> Result<()> {
         let roots = self.indexes.roots();
         tx.update_meta(|meta| {
             meta.storage_index_catalog_root = roots.catalog;
@@ -541,6 +1224,68 @@ impl Graph {
     }
 }
 
+/// Returns whether `existing` already holds an index shaped like `def`.
+///
+/// The catalog keys indexes by label and leading property, so any other
+/// index sharing that key is reported as a conflict rather than ignored.
+fn index_exists(existing: &[IndexDef], def: &IndexDef) -> Result<bool> {
+    let Some(entry) = existing.iter().find(|entry| entry.prop() == def.prop()) else {
+        return Ok(false);
+    };
+    if entry.props == def.props
+        && entry.types == def.types
+        && entry.kind == def.kind
+        && entry.unique == def.unique
+        && entry.max_key_bytes == def.max_key_bytes
+    {
+        return Ok(true);
+    }
+    Err(SombraError::Invalid(
+        "a different index already exists on this label and leading property",
+    ))
+}
+
+/// Returns the values `props` holds for each property of the unique index
+/// `def`, or `None` when one is missing or null and so exempt from the
+/// constraint.
+fn unique_index_values<'p>(
+    def: &IndexDef,
+    props: &'p BTreeMap<PropId, PropValueOwned>,
+) -> Option<Vec<&'p PropValueOwned>> {
+    def.props
+        .iter()
+        .map(|prop| {
+            props
+                .get(prop)
+                .filter(|value| !matches!(value, PropValueOwned::Null))
+        })
+        .collect()
+}
+
+fn push_drift(
+    drift: &mut Vec<IndexDrift>,
+    label: LabelId,
+    prop: Option<PropId>,
+    stale: impl Iterator<Item = NodeId>,
+    missing: impl Iterator<Item = NodeId>,
+) {
+    let mut stale: Vec<NodeId> = stale.collect();
+    let mut missing: Vec<NodeId> = missing.collect();
+    if stale.is_empty() && missing.is_empty() {
+        return;
+    }
+    stale.sort_by_key(|node| node.0);
+    stale.dedup();
+    missing.sort_by_key(|node| node.0);
+    missing.dedup();
+    drift.push(IndexDrift {
+        label,
+        prop,
+        stale,
+        missing,
+    });
+}
+
 fn collect_posting_stream(stream: &mut dyn PostingStream) -> Result<Vec<NodeId>> {
     let mut nodes = Vec::new();
     collect_all(stream, &mut nodes)?;
@@ -578,6 +1323,13 @@ enum PropertyPredicate {
         start: Bound<PropValueOwned>,
         end: Bound<PropValueOwned>,
     },
+    Composite {
+        props: Vec<PropId>,
+        eq: Vec<PropValueOwned>,
+        start: Bound<PropValueOwned>,
+        end: Bound<PropValueOwned>,
+    },
+    Prefix(String),
 }
 
 struct PropertyFilterStream<'a> {
@@ -636,6 +1388,55 @@ impl<'a> PropertyFilterStream<'a> {
         })
     }
 
+    fn new_prefix(
+        graph: &'a Graph,
+        tx: &'a ReadGuard,
+        inner: Box<dyn PostingStream + 'a>,
+        label: LabelId,
+        prop: PropId,
+        prefix: String,
+    ) -> Box<dyn PostingStream + 'a> {
+        Box::new(Self {
+            graph,
+            tx,
+            inner,
+            label,
+            prop,
+            predicate: PropertyPredicate::Prefix(prefix),
+            pending: VecDeque::new(),
+            scratch: Vec::new(),
+            inner_exhausted: false,
+        })
+    }
+
+    fn new_composite(
+        graph: &'a Graph,
+        tx: &'a ReadGuard,
+        inner: Box<dyn PostingStream + 'a>,
+        label: LabelId,
+        props: Vec<PropId>,
+        eq: Vec<PropValueOwned>,
+        start: Bound<PropValueOwned>,
+        end: Bound<PropValueOwned>,
+    ) -> Box<dyn PostingStream + 'a> {
+        Box::new(Self {
+            graph,
+            tx,
+            inner,
+            label,
+            prop: props[0],
+            predicate: PropertyPredicate::Composite {
+                props,
+                eq,
+                start,
+                end,
+            },
+            pending: VecDeque::new(),
+            scratch: Vec::new(),
+            inner_exhausted: false,
+        })
+    }
+
     fn fill_pending(&mut self, max: usize) -> Result<()> {
         self.scratch.clear();
         let has_more = self.inner.next_batch(&mut self.scratch, max)?;
@@ -656,6 +1457,17 @@ impl<'a> PropertyFilterStream<'a> {
             PropertyPredicate::Range { start, end } => self
                 .graph
                 .node_matches_property_range(self.tx, node, self.label, self.prop, start, end),
+            PropertyPredicate::Composite {
+                props,
+                eq,
+                start,
+                end,
+            } => self
+                .graph
+                .node_matches_composite(self.tx, node, self.label, props, eq, start, end),
+            PropertyPredicate::Prefix(prefix) => self
+                .graph
+                .node_matches_property_prefix(self.tx, node, self.label, self.prop, prefix),
         }
     }
 }
@@ -689,12 +1501,13 @@ impl PostingStream for PropertyFilterStream<'_> {
     }
 }
 
-struct FallbackLabelScan {
+/// Posting stream over an owned list of sorted node identifiers.
+struct SortedNodeStream {
     nodes: Vec<NodeId>,
     pos: usize,
 }
 
-impl PostingStream for FallbackLabelScan {
+impl PostingStream for SortedNodeStream {
     fn next_batch(&mut self, out: &mut Vec<NodeId>, max: usize) -> Result<bool> {
         if max == 0 {
             // Return true if more data remains, false if exhausted
@@ -1,6 +1,6 @@
 use std::cell::{Cell, RefCell};
 
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::convert::TryFrom;
 
 use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering as AtomicOrdering};
@@ -24,7 +24,7 @@ use crate::storage::vstore::VStore;
 
 use crate::storage::PropValueOwned;
 use crate::types::page::PAGE_HDR_LEN;
-use crate::types::{EdgeId, LabelId, NodeId, PageId, PropId, Result, SombraError, TypeId};
+use crate::types::{EdgeId, LabelId, Lsn, NodeId, PageId, PropId, Result, SombraError, TypeId};
 
 
 use super::adjacency;
@@ -41,6 +41,7 @@ use super::options::{AdjacencyBackend, GraphOptions, VacuumCfg};
 use super::props;
 
 mod adjacency_ops;
+mod analytics_ops;
 mod deferred_ops;
 mod edge_ops;
 mod graph_types;
@@ -50,6 +51,7 @@ mod index_ops;
 mod mvcc_ops;
 mod node_ops;
 mod prop_ops;
+mod schema_ops;
 mod snapshot;
 mod tests;
 mod vacuum;
@@ -60,17 +62,20 @@ pub use writer::{BulkEdgeValidator, CreateEdgeOptions, GraphWriter, GraphWriterS
 
 #[allow(unused_imports)]
 pub use graph_types::{
-    AdjacencyVacuumStats, BfsOptions, BfsVisit, GraphMvccStatus, GraphSpaceUsage,
-    GraphVacuumStats, PropStats, SnapshotPoolStatus, VacuumBudget, VacuumMode, VacuumTrigger,
-    VersionVacuumStats, DEFAULT_INLINE_PROP_BLOB, DEFAULT_INLINE_PROP_VALUE,
-    MVCC_METRICS_PUBLISH_INTERVAL, STORAGE_FLAG_DEGREE_CACHE,
+    AdjacencyVacuumStats, BfsOptions, BfsVisit, ComponentMode, GraphMvccStatus, GraphSpaceUsage,
+    GraphSummary, GraphVacuumStats, IndexDrift, MergeReport, NodeFilter, PageRankOpts, PropStats,
+    PropPlacementStats, SnapshotPoolStatus, SpaceReport, SuspendedIndexes, VacuumBudget, VacuumMode, VacuumTrigger,
+    VersionVacuumStats,
+    DEFAULT_INLINE_PROP_BLOB, DEFAULT_INLINE_PROP_VALUE, MVCC_METRICS_PUBLISH_INTERVAL,
+    STORAGE_FLAG_CLUSTER_BY_LABEL, STORAGE_FLAG_DEGREE_CACHE, STORAGE_FLAG_LIVE_COUNTS,
 };
 
 use graph_types::RootKind;
 #[cfg(feature = "degree-cache")]
 use helpers::open_degree_tree;
-use helpers::{open_u64_vec_tree, open_unit_tree};
+use helpers::{count_live_rows, open_u64_vec_tree, open_unit_tree};
 
+use schema_ops::SchemaLimits;
 use snapshot::{SnapshotLease, SnapshotPool};
 use vacuum::MicroGcTrigger;
 use version_cache::VersionCache;
@@ -107,6 +112,10 @@ pub struct Graph {
     catalog_epoch: CatalogEpoch,
     inline_prop_blob: usize,
     inline_prop_value: usize,
+    compress_inline_props: Option<usize>,
+    auto_label_index: bool,
+    /// Per-label id runs `(next, end)` when nodes are clustered by label.
+    label_clusters: Option<Mutex<HashMap<LabelId, (u64, u64)>>>,
     #[cfg(feature = "degree-cache")]
     degree_cache_enabled: bool,
     nodes_root: AtomicU64,
@@ -141,6 +150,8 @@ pub struct Graph {
     version_cache_misses: AtomicU64,
     version_codec_raw_bytes: AtomicU64,
     version_codec_encoded_bytes: AtomicU64,
+    prop_inline_writes: AtomicU64,
+    prop_overflow_writes: AtomicU64,
     micro_gc_last_ms: AtomicU64,
     micro_gc_budget_hint: AtomicUsize,
     micro_gc_running: AtomicBool,
@@ -154,6 +165,12 @@ pub struct Graph {
     ifa_overflow_root: AtomicU64,
     /// Selected adjacency backend.
     adjacency_backend: AdjacencyBackend,
+    /// Configured caps on distinct labels, edge types, and property keys.
+    schema_limits: SchemaLimits,
+    /// Whether reads skip dangling adjacency entries instead of failing.
+    heal_on_read: bool,
+    /// Dangling `(src, ty, dst, edge)` entries seen by lenient reads, awaiting repair.
+    dangling_adjacency: Mutex<HashSet<(NodeId, TypeId, NodeId, EdgeId)>>,
 }
 
 struct VacuumSched {
@@ -301,6 +318,26 @@ impl Graph {
             storage_flags &= !STORAGE_FLAG_DEGREE_CACHE;
         }
 
+        // Clustering changes how ids are laid out, so it can only be chosen
+        // before the first node is written.
+        let cluster_by_label = (meta.storage_flags & STORAGE_FLAG_CLUSTER_BY_LABEL) != 0
+            || (opts.cluster_by_label && meta.storage_next_node_id <= 1);
+        if cluster_by_label {
+            storage_flags |= STORAGE_FLAG_CLUSTER_BY_LABEL;
+        }
+
+        // Files written before the live counters existed start them at zero,
+        // so count their rows once and record that the counters are exact.
+        let live_counts = if meta.storage_flags & STORAGE_FLAG_LIVE_COUNTS == 0 {
+            storage_flags |= STORAGE_FLAG_LIVE_COUNTS;
+            Some((
+                count_live_rows(&store, &nodes)?,
+                count_live_rows(&store, &edges)?,
+            ))
+        } else {
+            None
+        };
+
         let inline_blob_meta = u32::try_from(inline_prop_blob)
             .map_err(|_| SombraError::Invalid("inline_prop_blob exceeds u32::MAX"))?;
         let inline_value_meta = u32::try_from(inline_prop_value)
@@ -376,11 +413,25 @@ impl Graph {
                 meta.storage_next_version_ptr = next_version_ptr_init;
                 meta.storage_inline_prop_blob = inline_blob_meta;
                 meta.storage_inline_prop_value = inline_value_meta;
+                if let Some((node_count, edge_count)) = live_counts {
+                    meta.storage_node_count = node_count;
+                    meta.storage_edge_count = edge_count;
+                }
             })?;
             store.commit(write)?;
         }
 
-        let vstore = VStore::open(Arc::clone(&store))?;
+        #[cfg(feature = "compression")]
+        let vstore =
+            VStore::open(Arc::clone(&store))?.compress_values(opts.compress_overflow_values);
+        #[cfg(not(feature = "compression"))]
+        let vstore = if opts.compress_overflow_values {
+            return Err(SombraError::Invalid(
+                "compress_overflow_values requires the `compression` feature",
+            ));
+        } else {
+            VStore::open(Arc::clone(&store))?
+        };
         let catalog_epoch = CatalogEpoch::new(DdlEpoch(meta.storage_ddl_epoch));
         let next_node_id = AtomicU64::new(next_node_id_init);
         let next_edge_id = AtomicU64::new(next_edge_id_init);
@@ -453,6 +504,9 @@ impl Graph {
             catalog_epoch,
             inline_prop_blob,
             inline_prop_value,
+            compress_inline_props: opts.compress_inline_props.map(|len| len as usize),
+            auto_label_index: opts.auto_label_index,
+            label_clusters: cluster_by_label.then(|| Mutex::new(HashMap::new())),
             #[cfg(feature = "degree-cache")]
             degree_cache_enabled,
             nodes_root: AtomicU64::new(nodes_root_id),
@@ -491,6 +545,8 @@ impl Graph {
             version_cache_misses: AtomicU64::new(0),
             version_codec_raw_bytes: AtomicU64::new(0),
             version_codec_encoded_bytes: AtomicU64::new(0),
+            prop_inline_writes: AtomicU64::new(0),
+            prop_overflow_writes: AtomicU64::new(0),
             micro_gc_last_ms: AtomicU64::new(0),
             micro_gc_budget_hint: AtomicUsize::new(0),
             micro_gc_running: AtomicBool::new(false),
@@ -499,6 +555,13 @@ impl Graph {
             ifa_adj_in_root: AtomicU64::new(ifa_adj_in_root_id),
             ifa_overflow_root: AtomicU64::new(ifa_overflow_root_id),
             adjacency_backend,
+            schema_limits: SchemaLimits {
+                labels: opts.max_distinct_labels,
+                types: opts.max_distinct_types,
+                props: opts.max_distinct_props,
+            },
+            heal_on_read: opts.heal_on_read,
+            dangling_adjacency: Mutex::new(HashSet::new()),
         });
         graph.recompute_version_log_bytes()?;
         graph.register_vacuum_hook();
@@ -564,6 +627,97 @@ impl Graph {
 
         Ok(usage)
     }
+
+    /// Returns the number of pages the allocator can hand out again without
+    /// growing the file.
+    pub fn free_page_count(&self) -> u64 {
+        self.store.free_page_count().unwrap_or(0)
+    }
+
+    /// Returns the number of live nodes recorded in the meta page.
+    ///
+    /// The counter is maintained by the create and delete paths inside each
+    /// write transaction, so it is available after reopen without a scan.
+    /// Files written before the counter existed are counted once on open.
+    /// Like [`Self::space_report`], it reflects allocator-side meta and thus
+    /// includes changes staged by a write transaction that has not committed.
+    pub fn node_count(&self) -> Result<u64> {
+        Ok(self.store.meta()?.storage_node_count)
+    }
+
+    /// Returns the number of live edges recorded in the meta page.
+    ///
+    /// See [`Self::node_count`] for how the counter is maintained.
+    pub fn edge_count(&self) -> Result<u64> {
+        Ok(self.store.meta()?.storage_edge_count)
+    }
+
+    /// Returns how many property values written since the graph opened stayed
+    /// inline versus went to overflow.
+    pub fn prop_placement(&self) -> graph_types::PropPlacementStats {
+        graph_types::PropPlacementStats {
+            inline_writes: self.prop_inline_writes.load(AtomicOrdering::Relaxed),
+            overflow_writes: self.prop_overflow_writes.load(AtomicOrdering::Relaxed),
+        }
+    }
+
+    /// Summarizes how much of the database file is free versus holding live data.
+    ///
+    /// Figures come from allocator state rather than a read snapshot, so they
+    /// include pages released by commits that readers may still observe.
+    pub fn space_report(&self) -> Result<graph_types::SpaceReport> {
+        let total_pages = self.store.meta()?.next_page.0;
+        let free_pages = self.free_page_count().min(total_pages);
+        let fragmentation = if total_pages == 0 {
+            0.0
+        } else {
+            free_pages as f64 / total_pages as f64
+        };
+        Ok(graph_types::SpaceReport {
+            page_size: self.store.page_size(),
+            total_pages,
+            free_pages,
+            live_pages: total_pages - free_pages,
+            fragmentation,
+        })
+    }
+
+    /// Returns the ids of nodes whose records may have changed after `lsn`.
+    ///
+    /// The WAL frames written after `lsn` are mapped back to the leaf pages of
+    /// the node B-tree and every node stored on one of those pages is returned.
+    /// Tracking happens at page granularity, so the result is an
+    /// over-approximation: untouched nodes sharing a leaf with a modified node
+    /// are included as well. Nodes whose leaf page was rewritten (including
+    /// tombstoned records awaiting vacuum) are reported; nodes already purged
+    /// from the tree are not.
+    ///
+    /// Returns [`SombraError::SnapshotTooOld`] when a checkpoint has truncated
+    /// the WAL past `lsn`, since the history needed to answer is gone.
+    pub fn nodes_changed_since(&self, lsn: Lsn) -> Result<Vec<NodeId>> {
+        use std::sync::atomic::Ordering as AtomicOrdering;
+
+        let Some(pages) = self.store.wal_pages_since(lsn)? else {
+            return Err(SombraError::SnapshotTooOld(format!(
+                "WAL history before lsn {} is no longer retained",
+                lsn.0
+            )));
+        };
+        if pages.is_empty() {
+            return Ok(Vec::new());
+        }
+        let changed: HashSet<u64> = pages.into_iter().map(|page| page.0).collect();
+        let nodes_root = PageId(self.nodes_root.load(AtomicOrdering::SeqCst));
+        let mut ids = Vec::new();
+        self.for_each_leaf_key(nodes_root, |leaf, key| {
+            if changed.contains(&leaf.0) {
+                ids.push(NodeId(key));
+            }
+        })?;
+        ids.sort_unstable();
+        ids.dedup();
+        Ok(ids)
+    }
 }
 
 impl Drop for Graph {
@@ -614,7 +768,7 @@ impl Graph {
                     stats.internal_pages = stats.internal_pages.saturating_add(1);
                 }
             }
-            let payload_len = bytes.len().saturating_sub(PAGE_HDR_LEN as usize);
+            let payload_len = bytes.len().saturating_sub(PAGE_HDR_LEN);
             let free_bytes = header
                 .free_end
                 .saturating_sub(header.free_start) as usize;
@@ -625,7 +779,7 @@ impl Graph {
             }
 
             if let BTreePageKind::Internal = header.kind {
-                let payload = &bytes[PAGE_HDR_LEN as usize..];
+                let payload = &bytes[PAGE_HDR_LEN..];
                 let slots = header.slot_directory(bytes)?;
                 for idx in 0..slots.len() {
                     let (start, len) = slots.extent(idx)?;
@@ -643,6 +797,58 @@ impl Graph {
         Ok(stats)
     }
 
+    /// Visits every `u64` key stored in the leaves of the tree rooted at `root`.
+    fn for_each_leaf_key<F>(&self, root: PageId, mut visit: F) -> Result<()>
+    where
+        F: FnMut(PageId, u64),
+    {
+        use crate::storage::btree::page::BTreePageKind;
+
+        if root.0 == 0 {
+            return Ok(());
+        }
+        let read = self.store.begin_latest_committed_read()?;
+        let mut stack = vec![root];
+        let mut visited = HashSet::new();
+        while let Some(id) = stack.pop() {
+            if !visited.insert(id.0) {
+                continue;
+            }
+            let page = self.store.get_page(&read, id)?;
+            let bytes = page.data();
+            let header = btree_page::Header::parse(bytes)?;
+            let payload = btree_page::payload(bytes)?;
+            let slots = header.slot_directory(bytes)?;
+            match header.kind {
+                BTreePageKind::Leaf => {
+                    let extents = btree_page::SlotExtents::build(&header, payload, &slots)?;
+                    for idx in 0..slots.len() {
+                        let rec_slice = extents.record_slice(payload, idx)?;
+                        let record = btree_page::decode_leaf_record(rec_slice)?;
+                        let key: [u8; 8] = record
+                            .key
+                            .try_into()
+                            .map_err(|_| SombraError::Corruption("u64 key length mismatch"))?;
+                        visit(id, u64::from_be_bytes(key));
+                    }
+                }
+                BTreePageKind::Internal => {
+                    for idx in 0..slots.len() {
+                        let (start, len) = slots.extent(idx)?;
+                        let start = start as usize;
+                        let end = start + len as usize;
+                        if end > payload.len() {
+                            continue;
+                        }
+                        let rec = btree_page::decode_internal_record(&payload[start..end])?;
+                        stack.push(rec.child);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Evicts readers that have exceeded the configured timeout and emits warnings when appropriate.
     fn enforce_reader_timeouts(&self) {
         let timeout = self.vacuum_cfg.reader_timeout;
@@ -678,6 +884,7 @@ const TRUST_BATCH_REQUIRED: &str = "trusted endpoints batch must be validated";
 
 struct GraphTxnState {
     index_cache: GraphIndexCache,
+    required_props: BTreeMap<LabelId, Arc<Vec<PropId>>>,
     deferred_adj: Option<AdjacencyBuffer>,
     deferred_index: Option<IndexBuffer>,
 }
@@ -686,6 +893,7 @@ impl GraphTxnState {
     fn new(epoch: DdlEpoch) -> Self {
         Self {
             index_cache: GraphIndexCache::new(epoch),
+            required_props: BTreeMap::new(),
             deferred_adj: None,
             deferred_index: None,
         }
@@ -721,6 +929,13 @@ impl Graph {
                 .fetch_add(stats.hits, AtomicOrdering::Relaxed);
             self.idx_cache_misses
                 .fetch_add(stats.misses, AtomicOrdering::Relaxed);
+            // Only the cached catalog entries are stale; writes staged earlier
+            // in the transaction must survive until the deferred flush.
+            if state.deferred_adj.is_some() || state.deferred_index.is_some() {
+                state.index_cache = GraphIndexCache::new(self.catalog_epoch.current());
+                state.required_props.clear();
+                tx.store_extension(state);
+            }
         }
     }
 
@@ -847,6 +1062,23 @@ impl Graph {
         }
         Ok(())
     }
+
+    /// Marks `page` dirty without changing its bytes so the next commit
+    /// rewrites it (with a fresh checksum) through the WAL.
+    ///
+    /// Only available to tests and to harnesses built with `test-hooks`.
+    #[cfg(any(test, feature = "test-hooks"))]
+    pub fn touch_page(&self, tx: &mut WriteGuard<'_>, page: PageId) -> Result<()> {
+        if page.0 == 0 {
+            return Err(SombraError::Invalid("cannot touch the meta page"));
+        }
+        if page.0 >= self.store.meta()?.next_page.0 {
+            return Err(SombraError::Invalid("cannot touch an unallocated page"));
+        }
+        tx.page_mut(page)?;
+        Ok(())
+    }
+
     /// Returns the current catalog epoch used for DDL invalidation.
     pub fn catalog_epoch(&self) -> u64 {
         self.catalog_epoch.current().0
@@ -472,17 +472,6 @@ impl Graph {
         self.visible_node_from_bytes(tx, id, &bytes)
     }
 
-    pub(crate) fn visible_edge(
-        &self,
-        tx: &ReadGuard,
-        id: EdgeId,
-    ) -> Result<Option<edge::VersionedEdgeRow>> {
-        let Some(bytes) = self.edges.get(tx, &id.0)? else {
-            return Ok(None);
-        };
-        self.visible_edge_from_bytes(tx, id, &bytes)
-    }
-
     pub(crate) fn recompute_version_log_bytes(&self) -> Result<()> {
         let read = self.begin_read_guard()?;
         let mut cursor = self.version_log.range(
@@ -1,33 +1,42 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::ops::RangeBounds;
 
 use super::node::{
     self, EncodeOpts as NodeEncodeOpts, PropPayload as NodePropPayload,
     PropStorage as NodePropStorage,
 };
-use super::Graph;
+use super::{Graph, MergeReport, NodeFilter};
 use crate::primitives::pager::{ReadGuard, WriteGuard};
 use crate::storage::graph::RootKind;
 use crate::storage::mvcc::{VersionHeader, VERSION_HEADER_LEN};
 use crate::storage::patch;
-use crate::storage::{props, DeleteMode, DeleteNodeOpts, NodeData, NodeSpec, PropValueOwned};
+use crate::storage::{
+    props, ConflictPolicy, DeleteMode, DeleteNodeOpts, MergeNodeOpts, NodeData, NodeSpec,
+    PropValueOwned,
+};
 use crate::storage::{VersionPtr, VersionSpace};
-use crate::types::{EdgeId, LabelId, NodeId, PropId, Result, SombraError, VRef};
+use crate::types::{EdgeId, LabelId, NodeId, PropId, Result, SombraError, TypeId, VRef};
 
 use crate::storage::profile::{
     profile_timer as storage_profile_timer, record_profile_timer as record_storage_profile_timer,
     StorageProfileKind,
 };
 
+/// Number of node ids reserved at a time for one label when clustering by label.
+const LABEL_CLUSTER_BLOCK: u64 = 256;
+
 impl Graph {
     /// Creates a new node in the graph with the given specification.
     pub fn create_node(&self, tx: &mut WriteGuard<'_>, spec: NodeSpec<'_>) -> Result<NodeId> {
         let total_start = storage_profile_timer();
         let labels = super::helpers::normalize_labels(spec.labels)?;
+        self.check_required_node_props(tx, &labels, spec.props)?;
         let mut prop_owned: BTreeMap<PropId, PropValueOwned> = BTreeMap::new();
         for entry in spec.props {
             let owned = super::prop_ops::prop_value_to_owned(entry.value.clone());
             prop_owned.insert(entry.prop, owned);
         }
+        self.check_unique_props(tx, None, &labels, &BTreeMap::new(), &prop_owned)?;
         // Encode properties (profiled)
         let encode_start = storage_profile_timer();
         let (prop_bytes, spill_vrefs) = self.encode_property_map(tx, spec.props)?;
@@ -39,6 +48,7 @@ impl Graph {
             map_vref = Some(vref);
             NodePropPayload::VRef(vref)
         };
+        self.record_prop_placement(spec.props.len(), spill_vrefs.len(), map_vref.is_some());
         let root = self.nodes.root_page();
         debug_assert!(root.0 != 0, "nodes root page not initialized");
         // When using deferred index flush, skip the pending flag since visibility
@@ -67,9 +77,7 @@ impl Graph {
             }
         };
         record_storage_profile_timer(StorageProfileKind::CreateNodeEncodeProps, encode_start);
-        let id_raw = self
-            .next_node_id
-            .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        let id_raw = self.allocate_node_id(spec.labels.first().copied());
         let node_id = NodeId(id_raw);
         let next_id = node_id.0.saturating_add(1);
         tx.update_meta(|meta| {
@@ -117,11 +125,40 @@ impl Graph {
         if !self.defer_index_flush {
             self.finalize_node_head(tx, node_id)?;
         }
+        tx.update_meta(|meta| {
+            meta.storage_node_count = meta.storage_node_count.saturating_add(1);
+        })?;
         self.metrics.node_created();
         record_storage_profile_timer(StorageProfileKind::CreateNode, total_start);
         Ok(node_id)
     }
 
+    /// Returns whether node ids are allocated in per-label runs.
+    ///
+    /// Mirrors [`GraphOptions::cluster_by_label`](crate::storage::GraphOptions::cluster_by_label)
+    /// as recorded when the graph was created.
+    pub fn cluster_by_label(&self) -> bool {
+        self.label_clusters.is_some()
+    }
+
+    /// Hands out the next node id, drawing from the primary label's run when
+    /// clustering is enabled so same-label rows stay adjacent in the nodes tree.
+    fn allocate_node_id(&self, primary: Option<LabelId>) -> u64 {
+        let next = &self.next_node_id;
+        let (Some(clusters), Some(label)) = (self.label_clusters.as_ref(), primary) else {
+            return next.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        };
+        let mut clusters = clusters.lock();
+        let run = clusters.entry(label).or_insert((0, 0));
+        if run.0 >= run.1 {
+            let start = next.fetch_add(LABEL_CLUSTER_BLOCK, std::sync::atomic::Ordering::SeqCst);
+            *run = (start, start.saturating_add(LABEL_CLUSTER_BLOCK));
+        }
+        let id = run.0;
+        run.0 += 1;
+        id
+    }
+
     /// Retrieves node data by ID.
     pub fn get_node(&self, tx: &ReadGuard, id: NodeId) -> Result<Option<NodeData>> {
         let Some(bytes) = self.nodes.get(tx, &id.0)? else {
@@ -164,6 +201,60 @@ impl Graph {
         Ok(Some(count))
     }
 
+    /// Fetches a single property for many nodes, preserving the order of `ids`.
+    ///
+    /// Only `prop` is decoded from each record; other values are skipped
+    /// without being materialized. Missing or invisible nodes and nodes
+    /// without the property yield `None`.
+    pub fn get_prop_for_nodes(
+        &self,
+        tx: &ReadGuard,
+        ids: &[NodeId],
+        prop: PropId,
+    ) -> Result<Vec<Option<PropValueOwned>>> {
+        let mut values = Vec::with_capacity(ids.len());
+        for &id in ids {
+            let Some(bytes) = self.nodes.get(tx, &id.0)? else {
+                values.push(None);
+                continue;
+            };
+            let Some(versioned) = self.visible_node_from_bytes(tx, id, &bytes)? else {
+                values.push(None);
+                continue;
+            };
+            let prop_bytes = match versioned.row.props {
+                NodePropStorage::Inline(bytes) => bytes,
+                NodePropStorage::VRef(vref) => self.vstore.read(tx, vref)?,
+            };
+            let value = match props::decode_raw_prop(&prop_bytes, prop)? {
+                Some(raw) => Some(props::materialize_value(&raw, &self.vstore, tx)?),
+                None => None,
+            };
+            values.push(value);
+        }
+        Ok(values)
+    }
+
+    /// Reads the label ids of many nodes, preserving the order of `ids`.
+    ///
+    /// Properties are not decoded. Missing or invisible nodes yield `None`.
+    pub fn node_label_ids(
+        &self,
+        tx: &ReadGuard,
+        ids: &[NodeId],
+    ) -> Result<Vec<Option<Vec<LabelId>>>> {
+        let mut labels = Vec::with_capacity(ids.len());
+        for &id in ids {
+            let Some(bytes) = self.nodes.get(tx, &id.0)? else {
+                labels.push(None);
+                continue;
+            };
+            let visible = self.visible_node_from_bytes(tx, id, &bytes)?;
+            labels.push(visible.map(|versioned| versioned.row.labels));
+        }
+        Ok(labels)
+    }
+
     /// Retrieves node data using an active write transaction.
     ///
     /// This surfaces pending versions created by the current writer so that
@@ -222,6 +313,25 @@ impl Graph {
         tx: &mut WriteGuard<'_>,
         id: NodeId,
         opts: DeleteNodeOpts,
+    ) -> Result<()> {
+        let mut deleted_edges = HashSet::new();
+        if opts.mode == DeleteMode::CascadeOrphans {
+            let doomed = self.plan_orphan_cascade(tx, id, opts.max_cascade_depth)?;
+            for node in doomed {
+                self.delete_node_inner(tx, node, DeleteMode::Cascade, &mut deleted_edges)?;
+            }
+            return Ok(());
+        }
+        self.delete_node_inner(tx, id, opts.mode, &mut deleted_edges)
+    }
+
+    /// Deletes one node, skipping cascaded edges already in `deleted_edges`.
+    fn delete_node_inner(
+        &self,
+        tx: &mut WriteGuard<'_>,
+        id: NodeId,
+        mode: DeleteMode,
+        deleted_edges: &mut HashSet<EdgeId>,
     ) -> Result<()> {
         let Some(bytes) = self.nodes.get_with_write(tx, &id.0)? else {
             return Err(SombraError::NotFound);
@@ -234,21 +344,19 @@ impl Graph {
         let prop_bytes = self.read_node_prop_bytes_with_write(tx, &row.props)?;
         let old_props_vec = self.materialize_props_owned_with_write(tx, &prop_bytes)?;
         let old_props: BTreeMap<PropId, PropValueOwned> = old_props_vec.into_iter().collect();
-        let read = self.lease_latest_snapshot()?;
-        let incident = self.collect_incident_edges(&read, id)?;
-        drop(read);
+        let incident = self.incident_edge_ends_with_write(tx, id)?;
 
-        match opts.mode {
+        match mode {
             DeleteMode::Restrict => {
                 if !incident.is_empty() {
                     return Err(SombraError::Invalid("node has incident edges"));
                 }
             }
-            DeleteMode::Cascade => {
-                let mut edges: Vec<EdgeId> = incident.into_iter().collect();
-                edges.sort_by_key(|edge| edge.0);
-                for edge_id in edges {
-                    self.delete_edge(tx, edge_id)?;
+            DeleteMode::Cascade | DeleteMode::CascadeOrphans => {
+                for edge_id in incident.into_keys() {
+                    if deleted_edges.insert(edge_id) {
+                        self.delete_edge(tx, edge_id)?;
+                    }
                 }
             }
         }
@@ -292,10 +400,147 @@ impl Graph {
         self.nodes.put(tx, &id.0, &encoded.bytes)?;
         self.persist_tree_root(tx, RootKind::Nodes)?;
         self.finalize_node_head(tx, id)?;
+        tx.update_meta(|meta| {
+            meta.storage_node_count = meta.storage_node_count.saturating_sub(1);
+        })?;
         self.metrics.node_deleted();
         Ok(())
     }
 
+    /// Lists `root` followed by the nodes [`DeleteMode::CascadeOrphans`]
+    /// removes with it, in breadth-first order.
+    ///
+    /// Reads edges through `tx`, so edges the transaction created or removed
+    /// before the delete decide which children are orphaned. Walks an
+    /// explicit queue rather than recursing, so deep chains cannot exhaust
+    /// the stack.
+    fn plan_orphan_cascade(
+        &self,
+        tx: &mut WriteGuard<'_>,
+        root: NodeId,
+        max_depth: Option<u32>,
+    ) -> Result<Vec<NodeId>> {
+        if self.get_node_in_write(tx, root)?.is_none() {
+            return Err(SombraError::NotFound);
+        }
+        let mut doomed = vec![root];
+        let mut seen: HashSet<NodeId> = HashSet::from([root]);
+        let mut queue: VecDeque<(NodeId, u32)> = VecDeque::from([(root, 0)]);
+        while let Some((node, depth)) = queue.pop_front() {
+            for (src, child) in self.incident_edge_ends_with_write(tx, node)?.into_values() {
+                if src != node || seen.contains(&child) {
+                    continue;
+                }
+                let orphaned = self
+                    .incident_edge_ends_with_write(tx, child)?
+                    .into_values()
+                    .all(|(parent, dst)| dst != child || parent == child || seen.contains(&parent));
+                if !orphaned {
+                    continue;
+                }
+                let child_depth = depth + 1;
+                if max_depth.is_some_and(|max| child_depth > max) {
+                    return Err(SombraError::Invalid("cascade exceeds max_cascade_depth"));
+                }
+                seen.insert(child);
+                doomed.push(child);
+                queue.push_back((child, child_depth));
+            }
+        }
+        Ok(doomed)
+    }
+
+    /// Folds `remove` into `keep`: re-points its edges onto `keep`, copies its
+    /// properties per [`MergeNodeOpts::prop_conflict`], then deletes `remove`.
+    ///
+    /// Moved edges keep their ids, types, and properties, and an edge joining
+    /// the two nodes becomes a self-loop on `keep` unless
+    /// [`MergeNodeOpts::dedup_edges`] drops it. Edges are read through `tx`,
+    /// so ones the transaction added earlier move too. `keep` retains its own
+    /// labels.
+    pub fn merge_nodes(
+        &self,
+        tx: &mut WriteGuard<'_>,
+        keep: NodeId,
+        remove: NodeId,
+        opts: MergeNodeOpts,
+    ) -> Result<MergeReport> {
+        if keep == remove {
+            return Err(SombraError::Invalid("cannot merge a node into itself"));
+        }
+        let keep_data = self
+            .get_node_in_write(tx, keep)?
+            .ok_or(SombraError::NotFound)?;
+        let remove_data = self
+            .get_node_in_write(tx, remove)?
+            .ok_or(SombraError::NotFound)?;
+        let mut report = MergeReport::default();
+
+        let existing: BTreeMap<PropId, PropValueOwned> = keep_data.props.into_iter().collect();
+        let mut incoming = Vec::new();
+        for (prop, value) in remove_data.props {
+            match existing.get(&prop) {
+                None => incoming.push((prop, value)),
+                Some(current) if *current == value => {}
+                Some(_) => {
+                    report.prop_conflicts += 1;
+                    match opts.prop_conflict {
+                        ConflictPolicy::KeepExisting => {}
+                        ConflictPolicy::TakeIncoming => incoming.push((prop, value)),
+                        ConflictPolicy::Fail => {
+                            return Err(SombraError::Conflict(format!(
+                                "property {} differs between nodes {} and {}",
+                                prop.0, keep.0, remove.0
+                            )));
+                        }
+                    }
+                }
+            }
+        }
+
+        // Property lists of the edges `keep` ends up with, by endpoints and type.
+        let mut present: HashMap<(NodeId, NodeId, TypeId), Vec<Vec<(PropId, PropValueOwned)>>> =
+            HashMap::new();
+        if opts.dedup_edges {
+            for (_, edge) in self.incident_edges_with_write(tx, keep)? {
+                present
+                    .entry((edge.src, edge.dst, edge.ty))
+                    .or_default()
+                    .push(edge.props);
+            }
+        }
+        let repoint = |node: NodeId| if node == remove { keep } else { node };
+        for (edge_id, edge) in self.incident_edges_with_write(tx, remove)? {
+            let (src, dst) = (repoint(edge.src), repoint(edge.dst));
+            if opts.dedup_edges {
+                let joins_pair = (edge.src == keep && edge.dst == remove)
+                    || (edge.src == remove && edge.dst == keep);
+                let twins = present.entry((src, dst, edge.ty)).or_default();
+                if joins_pair || twins.contains(&edge.props) {
+                    self.delete_edge(tx, edge_id)?;
+                    report.edges_dropped += 1;
+                    continue;
+                }
+                twins.push(edge.props);
+            }
+            self.repoint_edge(tx, edge_id, src, dst)?;
+            report.edges_moved += 1;
+        }
+        self.delete_node_inner(tx, remove, DeleteMode::Restrict, &mut HashSet::new())?;
+
+        if !incoming.is_empty() {
+            let ops = incoming
+                .iter()
+                .map(|(prop, value)| {
+                    patch::PropPatchOp::Set(*prop, super::prop_ops::prop_value_ref(value))
+                })
+                .collect();
+            self.update_node(tx, keep, patch::PropPatch::new(ops))?;
+            report.props_copied = incoming.len();
+        }
+        Ok(report)
+    }
+
     /// Updates the properties of an existing node by applying the given patch.
     pub fn update_node(
         &self,
@@ -324,6 +569,15 @@ impl Graph {
         let Some(delta) = self.build_prop_delta(tx, &prop_bytes, &patch)? else {
             return Ok(());
         };
+        if let Err(err) = self
+            .check_required_prop_map(tx, &labels, &delta.new_map)
+            .and_then(|()| {
+                self.check_unique_props(tx, Some(id), &labels, &delta.old_map, &delta.new_map)
+            })
+        {
+            props::free_vrefs(&self.vstore, tx, &delta.encoded.spill_vrefs);
+            return Err(err);
+        }
         let (commit_id, new_header) = self.tx_pending_version_header(tx);
         let mut map_vref: Option<VRef> = None;
         let payload = if delta.encoded.bytes.len() <= self.inline_prop_blob {
@@ -333,6 +587,11 @@ impl Graph {
             map_vref = Some(vref);
             NodePropPayload::VRef(vref)
         };
+        self.record_prop_placement(
+            delta.new_map.len(),
+            delta.encoded.spill_vrefs.len(),
+            map_vref.is_some(),
+        );
         let mut old_header = versioned.header;
         old_header.end = commit_id;
         let mut log_bytes = existing_bytes.clone();
@@ -405,6 +664,138 @@ impl Graph {
         Ok(())
     }
 
+    /// Adds `label` to every node selected by `filter`, returning how many nodes changed.
+    ///
+    /// Matches are resolved against the latest committed snapshot, so nodes
+    /// created earlier in the same transaction are not considered. Nodes that
+    /// already carry the label are skipped and not counted.
+    pub fn add_label_where(
+        &self,
+        tx: &mut WriteGuard<'_>,
+        filter: &NodeFilter,
+        label: LabelId,
+    ) -> Result<u64> {
+        let matches = {
+            let read = self.lease_latest_snapshot()?;
+            self.filter_nodes(&read, filter)?
+        };
+        if matches.is_empty() {
+            return Ok(0);
+        }
+        let mut added = 0u64;
+        for id in matches {
+            if self.add_node_label(tx, id, label)? {
+                added += 1;
+            }
+        }
+        Ok(added)
+    }
+
+    fn filter_nodes(&self, tx: &ReadGuard, filter: &NodeFilter) -> Result<Vec<NodeId>> {
+        match filter {
+            NodeFilter::Label(label) => self.nodes_with_label(tx, *label),
+            NodeFilter::IdRange { label, start, end } => {
+                let mut nodes = self.nodes_with_label(tx, *label)?;
+                nodes.retain(|id| (*start, *end).contains(id));
+                Ok(nodes)
+            }
+            NodeFilter::PropEq { label, prop, value } => {
+                self.property_scan_eq(tx, *label, *prop, value)
+            }
+            NodeFilter::PropRange {
+                label,
+                prop,
+                start,
+                end,
+            } => self.property_scan_range_bounds(tx, *label, *prop, start.as_ref(), end.as_ref()),
+        }
+    }
+
+    /// Rewrites a node head with `label` appended, logging the prior version.
+    ///
+    /// Returns `false` when the node is missing, deleted, or already labeled.
+    fn add_node_label(&self, tx: &mut WriteGuard<'_>, id: NodeId, label: LabelId) -> Result<bool> {
+        let Some(existing_bytes) = self.nodes.get_with_write(tx, &id.0)? else {
+            return Ok(false);
+        };
+        let versioned = node::decode(&existing_bytes)?;
+        if versioned.header.is_tombstone() || versioned.row.labels.contains(&label) {
+            return Ok(false);
+        }
+        let node::NodeRow {
+            labels,
+            props: storage,
+            adj_page,
+            inline_adj,
+            ..
+        } = versioned.row;
+        let mut new_labels = labels;
+        new_labels.push(label);
+        let new_labels = super::helpers::normalize_labels(&new_labels)?;
+        // The prior version keeps its own property payload, so the new head
+        // gets a fresh copy rather than sharing any out-of-line storage.
+        let prop_bytes = self.read_node_prop_bytes_with_write(tx, &storage)?;
+        let current = self.materialize_props_owned_with_write(tx, &prop_bytes)?;
+        let prop_map: BTreeMap<PropId, PropValueOwned> = current.iter().cloned().collect();
+        self.check_required_prop_map(tx, &[label], &prop_map)?;
+        self.check_unique_props(tx, Some(id), &[label], &BTreeMap::new(), &prop_map)?;
+        let encoded =
+            props::encode_props_owned(&current, self.inline_value_opts(), &self.vstore, tx)?;
+        let mut map_vref: Option<VRef> = None;
+        let payload = if encoded.bytes.len() <= self.inline_prop_blob {
+            NodePropPayload::Inline(&encoded.bytes)
+        } else {
+            let vref = self.vstore.write(tx, &encoded.bytes)?;
+            map_vref = Some(vref);
+            NodePropPayload::VRef(vref)
+        };
+        let (commit_id, mut new_header) = self.tx_pending_version_header(tx);
+        let mut old_header = versioned.header;
+        old_header.end = commit_id;
+        let mut log_bytes = existing_bytes.clone();
+        Self::overwrite_encoded_header(&mut log_bytes, &old_header);
+        let prev_ptr = self.log_version_entry(
+            tx,
+            VersionSpace::Node,
+            id.0,
+            old_header,
+            versioned.prev_ptr,
+            log_bytes.clone(),
+        )?;
+        let inline_history = self.maybe_inline_history(&log_bytes);
+        if inline_history.is_some() {
+            new_header.flags |= crate::storage::mvcc_flags::INLINE_HISTORY;
+        }
+        let mut encode_opts = NodeEncodeOpts::new(self.row_hash_header);
+        if let Some(adj) = adj_page {
+            encode_opts = encode_opts.with_adj_page(adj);
+        }
+        if let Some(inline) = inline_adj.as_ref() {
+            encode_opts = encode_opts.with_inline_adj(inline);
+        }
+        let written = node::encode(
+            &new_labels,
+            payload,
+            encode_opts,
+            new_header,
+            prev_ptr,
+            inline_history.as_deref(),
+        )
+        .and_then(|encoded_row| self.nodes.put(tx, &id.0, &encoded_row.bytes));
+        if let Err(err) = written {
+            if let Some(vref) = map_vref.take() {
+                let _ = self.vstore.free(tx, vref);
+            }
+            props::free_vrefs(&self.vstore, tx, &encoded.spill_vrefs);
+            return Err(err);
+        }
+        self.persist_tree_root(tx, RootKind::Nodes)?;
+        self.stage_label_inserts(tx, id, &[label], commit_id)?;
+        self.insert_indexed_props(tx, id, &[label], &prop_map, commit_id)?;
+        self.finalize_node_head(tx, id)?;
+        Ok(true)
+    }
+
     fn finalize_node_head(&self, tx: &mut WriteGuard<'_>, id: NodeId) -> Result<()> {
         let Some(mut bytes) = self.nodes.get_with_write(tx, &id.0)? else {
             return Err(SombraError::Corruption("node head missing during finalize"));
@@ -2,10 +2,13 @@ use std::cmp::Ordering;
 use std::cmp::Ordering::{Equal, Greater, Less};
 use std::collections::BTreeMap;
 use std::ops::Bound;
+use std::sync::atomic::Ordering as AtomicOrdering;
+
+use xxhash_rust::xxh64::xxh64;
 
 use crate::primitives::pager::{ReadGuard, WriteGuard};
 use crate::storage::edge::PropStorage as EdgePropStorage;
-use crate::storage::index::TypeTag;
+use crate::storage::index::{IndexDef, TypeTag};
 use crate::storage::node::{self, PropStorage as NodePropStorage};
 use crate::storage::patch::{PropPatch, PropPatchOp};
 use crate::storage::props::{self, RawPropValue};
@@ -30,15 +33,159 @@ pub(crate) fn encode_value_key_owned(ty: TypeTag, value: &PropValueOwned) -> Res
     }
 }
 
+/// Length of the digest that replaces the tail of a truncated index key.
+const TRUNCATED_KEY_DIGEST_LEN: usize = 8;
+
+/// Encodes `value` as a key for the index described by `def`.
+///
+/// Payloads longer than [`IndexDef::max_key_bytes`] keep their length header
+/// and leading bytes, followed by an xxh64 digest of the full payload. The
+/// length header differs from every untruncated key, and readers confirm
+/// candidates against stored values, so digest collisions stay harmless.
+pub(crate) fn encode_index_key(def: &IndexDef, value: &PropValueOwned) -> Result<Vec<u8>> {
+    let key = encode_value_key_owned(def.ty(), value)?;
+    let Some(cut) = truncation_point(def, &key) else {
+        return Ok(key);
+    };
+    let digest = xxh64(&key[4..], 0);
+    let mut out = Vec::with_capacity(cut + TRUNCATED_KEY_DIGEST_LEN);
+    out.extend_from_slice(&key[..cut]);
+    out.extend_from_slice(&digest.to_be_bytes());
+    Ok(out)
+}
+
+/// Marks a composite key column whose property is missing or null.
+const COMPOSITE_ABSENT: u8 = 0x00;
+/// Marks a composite key column followed by its encoded value.
+const COMPOSITE_PRESENT: u8 = 0x01;
+
+/// Encodes the key `props` gives the index described by `def`.
+///
+/// Composite keys concatenate the encoded value of each indexed property.
+/// Every encoding is fixed width or length prefixed, so the parts of one key
+/// never run into each other. Properties after the leading one are preceded
+/// by a presence byte, so a node is indexed whenever it holds the leading
+/// property, as with single-property indexes, and a scan on the leading
+/// property alone still finds every match. Returns `None` when the leading
+/// property is missing.
+pub(crate) fn index_key_for(
+    def: &IndexDef,
+    props: &BTreeMap<PropId, PropValueOwned>,
+) -> Result<Option<Vec<u8>>> {
+    let Some(leading) = props.get(&def.prop()) else {
+        return Ok(None);
+    };
+    if !def.is_composite() {
+        return encode_index_key(def, leading).map(Some);
+    }
+    let mut key = encode_value_key_owned(def.ty(), leading)?;
+    for (prop, ty) in def.props.iter().zip(&def.types).skip(1) {
+        match props.get(prop) {
+            None | Some(PropValueOwned::Null) => key.push(COMPOSITE_ABSENT),
+            Some(value) => {
+                key.push(COMPOSITE_PRESENT);
+                key.extend_from_slice(&encode_value_key_owned(*ty, value)?);
+            }
+        }
+    }
+    Ok(Some(key))
+}
+
+/// Encodes `value` as column `idx` of a key for the composite index `def`.
+pub(crate) fn encode_composite_column(
+    def: &IndexDef,
+    idx: usize,
+    value: &PropValueOwned,
+) -> Result<Vec<u8>> {
+    let ty = *def.types.get(idx).ok_or(SombraError::Invalid(
+        "composite index lookup names too many properties",
+    ))?;
+    let mut out = Vec::new();
+    if idx > 0 {
+        out.push(COMPOSITE_PRESENT);
+    }
+    out.extend_from_slice(&encode_value_key_owned(ty, value)?);
+    Ok(out)
+}
+
+/// Encodes `values` as the key prefix they give the leading properties of
+/// the composite index `def`. With `present_next`, the prefix also covers
+/// the presence byte of the following property.
+pub(crate) fn encode_composite_prefix(
+    def: &IndexDef,
+    values: &[PropValueOwned],
+    present_next: bool,
+) -> Result<Vec<u8>> {
+    let mut key = Vec::new();
+    for (idx, value) in values.iter().enumerate() {
+        key.extend_from_slice(&encode_composite_column(def, idx, value)?);
+    }
+    if present_next && !values.is_empty() {
+        key.push(COMPOSITE_PRESENT);
+    }
+    Ok(key)
+}
+
+/// Returns the smallest key greater than every key starting with `prefix`,
+/// or `None` when no such key exists.
+pub(crate) fn prefix_successor(prefix: &[u8]) -> Option<Vec<u8>> {
+    let mut out = prefix.to_vec();
+    while let Some(last) = out.pop() {
+        if last != u8::MAX {
+            out.push(last + 1);
+            return Some(out);
+        }
+    }
+    None
+}
+
+/// Returns whether `value` is a string starting with `prefix`.
+///
+/// Matching compares bytes, so it is case-sensitive. Case-insensitive
+/// matching would fold both sides here and index folded keys to match.
+pub(crate) fn value_has_prefix(value: &PropValueOwned, prefix: &str) -> bool {
+    matches!(value, PropValueOwned::Str(s) if s.starts_with(prefix))
+}
+
+/// Encodes a range bound for `def`.
+///
+/// A truncated bound becomes an inclusive bound covering every digest under
+/// its prefix, since digests do not preserve value order. Callers re-check
+/// candidates against the exact bound.
 pub(crate) fn encode_range_bound(
-    ty: TypeTag,
+    def: &IndexDef,
     bound: Bound<&PropValueOwned>,
+    upper: bool,
 ) -> Result<Bound<Vec<u8>>> {
-    match bound {
-        Bound::Unbounded => Ok(Bound::Unbounded),
-        Bound::Included(value) => encode_value_key_owned(ty, value).map(Bound::Included),
-        Bound::Excluded(value) => encode_value_key_owned(ty, value).map(Bound::Excluded),
+    let (value, inclusive) = match bound {
+        Bound::Unbounded => return Ok(Bound::Unbounded),
+        Bound::Included(value) => (value, true),
+        Bound::Excluded(value) => (value, false),
+    };
+    let key = encode_value_key_owned(def.ty(), value)?;
+    let Some(cut) = truncation_point(def, &key) else {
+        return Ok(if inclusive {
+            Bound::Included(key)
+        } else {
+            Bound::Excluded(key)
+        });
+    };
+    let fill = if upper { 0xFF } else { 0x00 };
+    let mut out = Vec::with_capacity(cut + TRUNCATED_KEY_DIGEST_LEN);
+    out.extend_from_slice(&key[..cut]);
+    out.extend_from_slice(&[fill; TRUNCATED_KEY_DIGEST_LEN]);
+    Ok(Bound::Included(out))
+}
+
+/// Returns where to cut `key` when `def` truncates it, skipping the 4-byte
+/// length header of string and bytes keys.
+fn truncation_point(def: &IndexDef, key: &[u8]) -> Option<usize> {
+    let max = usize::from(def.max_key_bytes?);
+    if !matches!(def.ty(), TypeTag::String | TypeTag::Bytes) {
+        return None;
     }
+    let cut = 4 + max;
+    (key.len() > cut).then_some(cut)
 }
 
 pub(crate) fn clone_owned_bound(bound: Bound<&PropValueOwned>) -> Bound<PropValueOwned> {
@@ -62,6 +209,19 @@ pub(crate) fn prop_value_to_owned(value: PropValue<'_>) -> PropValueOwned {
     }
 }
 
+pub(crate) fn prop_value_ref(value: &PropValueOwned) -> PropValue<'_> {
+    match value {
+        PropValueOwned::Null => PropValue::Null,
+        PropValueOwned::Bool(v) => PropValue::Bool(*v),
+        PropValueOwned::Int(v) => PropValue::Int(*v),
+        PropValueOwned::Float(v) => PropValue::Float(*v),
+        PropValueOwned::Str(v) => PropValue::Str(v.as_str()),
+        PropValueOwned::Bytes(v) => PropValue::Bytes(v.as_slice()),
+        PropValueOwned::Date(v) => PropValue::Date(*v),
+        PropValueOwned::DateTime(v) => PropValue::DateTime(*v),
+    }
+}
+
 pub(crate) fn compare_prop_values(a: &PropValueOwned, b: &PropValueOwned) -> Result<Ordering> {
     use PropValueOwned::*;
     Ok(match (a, b) {
@@ -286,6 +446,66 @@ impl Graph {
         Ok(true)
     }
 
+    pub(crate) fn node_matches_property_prefix(
+        &self,
+        tx: &ReadGuard,
+        node: NodeId,
+        label: LabelId,
+        prop: PropId,
+        prefix: &str,
+    ) -> Result<bool> {
+        let Some(versioned) = self.visible_node(tx, node)? else {
+            return Ok(false);
+        };
+        if versioned.row.labels.binary_search(&label).is_err() {
+            return Ok(false);
+        }
+        let Some(value) = self.node_property_value(tx, &versioned, prop)? else {
+            return Ok(false);
+        };
+        Ok(value_has_prefix(&value, prefix))
+    }
+
+    /// Checks that `node` is visible, carries `label`, holds `eq[i]` for
+    /// each `props[i]` and, when `props` is one longer than `eq`, holds a
+    /// value for the last property within `start..end`.
+    pub(crate) fn node_matches_composite(
+        &self,
+        tx: &ReadGuard,
+        node: NodeId,
+        label: LabelId,
+        props: &[PropId],
+        eq: &[PropValueOwned],
+        start: &Bound<PropValueOwned>,
+        end: &Bound<PropValueOwned>,
+    ) -> Result<bool> {
+        let Some(versioned) = self.visible_node(tx, node)? else {
+            return Ok(false);
+        };
+        if versioned.row.labels.binary_search(&label).is_err() {
+            return Ok(false);
+        }
+        let bytes = self.read_node_prop_bytes(&versioned.row.props)?;
+        let raw = props::decode_raw(&bytes)?;
+        for (idx, prop) in props.iter().enumerate() {
+            let Some(entry) = raw.iter().find(|entry| entry.prop == *prop) else {
+                return Ok(false);
+            };
+            let value = self.materialize_raw_prop_value(tx, &entry.value)?;
+            let matches = match eq.get(idx) {
+                Some(expected) => value == *expected,
+                None => {
+                    Self::bound_allows(&value, start, true)?
+                        && Self::bound_allows(&value, end, false)?
+                }
+            };
+            if !matches {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
     pub(crate) fn bound_allows(
         value: &PropValueOwned,
         bound: &Bound<PropValueOwned>,
@@ -300,22 +520,50 @@ impl Graph {
             },
             Bound::Excluded(b) => match compare_prop_values(value, b)? {
                 Less if is_lower => Ok(false),
-                Equal => Ok(!is_lower),
+                Equal => Ok(false),
                 Greater if !is_lower => Ok(false),
                 _ => Ok(true),
             },
         }
     }
 
+    pub(crate) fn inline_value_opts(&self) -> props::InlineValueOpts {
+        props::InlineValueOpts {
+            limit: self.inline_prop_value,
+            compress_min_len: self.compress_inline_props,
+        }
+    }
+
     pub(crate) fn encode_property_map(
         &self,
         tx: &mut WriteGuard<'_>,
         props: &[PropEntry<'_>],
     ) -> Result<(Vec<u8>, Vec<VRef>)> {
-        let result = props::encode_props(props, self.inline_prop_value, &self.vstore, tx)?;
+        let result = props::encode_props(props, self.inline_value_opts(), &self.vstore, tx)?;
         Ok((result.bytes, result.spill_vrefs))
     }
 
+    /// Tallies where the values of a property map written to a record landed.
+    ///
+    /// `spilled` values went to overflow individually; when the whole map
+    /// moved to the VStore (`map_spilled`) every value counts as overflow.
+    pub(crate) fn record_prop_placement(&self, total: usize, spilled: usize, map_spilled: bool) {
+        if total == 0 {
+            return;
+        }
+        let overflow = if map_spilled {
+            total
+        } else {
+            spilled.min(total)
+        };
+        let inline = total - overflow;
+        self.prop_inline_writes
+            .fetch_add(inline as u64, AtomicOrdering::Relaxed);
+        self.prop_overflow_writes
+            .fetch_add(overflow as u64, AtomicOrdering::Relaxed);
+        self.metrics.property_placement(inline, overflow);
+    }
+
     pub(crate) fn free_prop_values_from_bytes(
         &self,
         tx: &mut WriteGuard<'_>,
@@ -420,7 +668,7 @@ impl Graph {
             .map(|(prop, value)| (*prop, value.clone()))
             .collect::<Vec<_>>();
         let encoded =
-            props::encode_props_owned(&ordered, self.inline_prop_value, &self.vstore, tx)?;
+            props::encode_props_owned(&ordered, self.inline_value_opts(), &self.vstore, tx)?;
         Ok(Some(PropDelta {
             old_map,
             new_map,
@@ -0,0 +1,217 @@
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
+
+use crate::primitives::pager::WriteGuard;
+use crate::storage::catalog::NameKind;
+use crate::storage::edge::{self, PropStorage as EdgePropStorage};
+use crate::storage::node::{self, PropStorage as NodePropStorage};
+use crate::storage::props::{self, RawPropValue};
+use crate::storage::{PropEntry, PropValue, PropValueOwned};
+use crate::types::{LabelId, NodeId, PropId, Result, SombraError, StrId};
+
+use super::Graph;
+
+/// Caps on the number of distinct schema names a graph accepts.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct SchemaLimits {
+    pub(crate) labels: Option<usize>,
+    pub(crate) types: Option<usize>,
+    pub(crate) props: Option<usize>,
+}
+
+impl Graph {
+    /// Returns the configured cap on distinct names of `kind`.
+    ///
+    /// Callers pass it to [`Dict::intern_as`](crate::storage::catalog::Dict::intern_as)
+    /// when turning names into ids, which is where the limit is enforced.
+    pub fn name_limit(&self, kind: NameKind) -> Option<usize> {
+        match kind {
+            NameKind::Label => self.schema_limits.labels,
+            NameKind::EdgeType => self.schema_limits.types,
+            NameKind::PropKey => self.schema_limits.props,
+        }
+    }
+
+    /// Scans stored nodes and edges for the label, type and property ids in use.
+    ///
+    /// Feeds [`Dict::backfill_name_kinds`](crate::storage::catalog::Dict::backfill_name_kinds)
+    /// once for dictionaries written before name roles were tracked.
+    pub fn schema_name_ids(&self, tx: &mut WriteGuard<'_>) -> Result<Vec<(NameKind, Vec<StrId>)>> {
+        let mut labels = HashSet::new();
+        let mut types = HashSet::new();
+        let mut props = HashSet::new();
+        let mut node_props: Vec<NodePropStorage> = Vec::new();
+        self.nodes.for_each_with_write(tx, |_id, bytes| {
+            let versioned = node::decode(&bytes)?;
+            labels.extend(versioned.row.labels.iter().map(|label| label.0));
+            node_props.push(versioned.row.props);
+            Ok(())
+        })?;
+        let mut edge_props: Vec<EdgePropStorage> = Vec::new();
+        self.edges.for_each_with_write(tx, |_id, bytes| {
+            let versioned = edge::decode(&bytes)?;
+            types.insert(versioned.row.ty.0);
+            edge_props.push(versioned.row.props);
+            Ok(())
+        })?;
+        for storage in &node_props {
+            let bytes = self.read_node_prop_bytes_with_write(tx, storage)?;
+            props.extend(props::decode_raw(&bytes)?.iter().map(|raw| raw.prop.0));
+        }
+        for storage in &edge_props {
+            let bytes = self.read_edge_prop_bytes_with_write(tx, storage)?;
+            props.extend(props::decode_raw(&bytes)?.iter().map(|raw| raw.prop.0));
+        }
+        let sorted = |ids: HashSet<u32>| {
+            let mut ids: Vec<StrId> = ids.into_iter().map(StrId).collect();
+            ids.sort_unstable();
+            ids
+        };
+        Ok(vec![
+            (NameKind::Label, sorted(labels)),
+            (NameKind::EdgeType, sorted(types)),
+            (NameKind::PropKey, sorted(props)),
+        ])
+    }
+
+    /// Requires every node carrying `label` to set `prop` to a non-null value.
+    ///
+    /// Existing nodes are validated before the constraint is stored, so it is
+    /// rejected while any labeled node lacks the property. Returns `false` if
+    /// the constraint was already registered.
+    pub fn create_required_prop(
+        &self,
+        tx: &mut WriteGuard<'_>,
+        label: LabelId,
+        prop: PropId,
+    ) -> Result<bool> {
+        let mut labeled: Vec<(NodeId, NodePropStorage)> = Vec::new();
+        self.nodes.for_each_with_write(tx, |id_raw, bytes| {
+            let versioned = node::decode(&bytes)?;
+            if versioned.header.is_tombstone()
+                || versioned.row.labels.binary_search(&label).is_err()
+            {
+                return Ok(());
+            }
+            labeled.push((NodeId(id_raw), versioned.row.props));
+            Ok(())
+        })?;
+        for (id, storage) in &labeled {
+            let bytes = self.read_node_prop_bytes_with_write(tx, storage)?;
+            let value = props::decode_raw_prop(&bytes, prop)?;
+            if matches!(value, None | Some(RawPropValue::Null)) {
+                return Err(SombraError::InvalidOwned(format!(
+                    "node {} with label {} is missing required property {}",
+                    id.0, label.0, prop.0
+                )));
+            }
+        }
+        if !self
+            .indexes
+            .catalog()
+            .insert_required_prop(tx, label, prop)?
+        {
+            return Ok(false);
+        }
+        self.sync_index_roots(tx)?;
+        self.bump_ddl_epoch(tx)?;
+        Ok(true)
+    }
+
+    /// Removes the requirement that nodes carrying `label` set `prop`.
+    ///
+    /// Returns `false` if no such constraint was registered.
+    pub fn drop_required_prop(
+        &self,
+        tx: &mut WriteGuard<'_>,
+        label: LabelId,
+        prop: PropId,
+    ) -> Result<bool> {
+        if !self
+            .indexes
+            .catalog()
+            .remove_required_prop(tx, label, prop)?
+        {
+            return Ok(false);
+        }
+        self.sync_index_roots(tx)?;
+        self.bump_ddl_epoch(tx)?;
+        Ok(true)
+    }
+
+    /// Returns every `(label, prop)` required-property constraint.
+    pub fn required_props(&self) -> Result<Vec<(LabelId, PropId)>> {
+        let read = self.lease_latest_snapshot()?;
+        self.indexes.catalog().iter_required_props(&read)
+    }
+
+    /// Rejects a node create unless `props` sets every property its labels require.
+    pub(crate) fn check_required_node_props(
+        &self,
+        tx: &mut WriteGuard<'_>,
+        labels: &[LabelId],
+        props: &[PropEntry<'_>],
+    ) -> Result<()> {
+        self.check_required_props(tx, labels, |prop| {
+            props
+                .iter()
+                .any(|entry| entry.prop == prop && !matches!(entry.value, PropValue::Null))
+        })
+    }
+
+    /// Rejects a node write whose resulting properties omit one its labels require.
+    pub(crate) fn check_required_prop_map(
+        &self,
+        tx: &mut WriteGuard<'_>,
+        labels: &[LabelId],
+        props: &BTreeMap<PropId, PropValueOwned>,
+    ) -> Result<()> {
+        self.check_required_props(tx, labels, |prop| {
+            props
+                .get(&prop)
+                .is_some_and(|value| !matches!(value, PropValueOwned::Null))
+        })
+    }
+
+    fn check_required_props(
+        &self,
+        tx: &mut WriteGuard<'_>,
+        labels: &[LabelId],
+        is_set: impl Fn(PropId) -> bool,
+    ) -> Result<()> {
+        for label in labels {
+            for prop in self.required_props_for_label(tx, *label)?.iter() {
+                if !is_set(*prop) {
+                    return Err(SombraError::InvalidOwned(format!(
+                        "nodes with label {} require property {}",
+                        label.0, prop.0
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Loads the properties required on `label`, caching them for the transaction.
+    fn required_props_for_label(
+        &self,
+        tx: &mut WriteGuard<'_>,
+        label: LabelId,
+    ) -> Result<Arc<Vec<PropId>>> {
+        let mut state = self.take_txn_state(tx);
+        let result = match state.required_props.get(&label) {
+            Some(props) => Ok(Arc::clone(props)),
+            None => self
+                .indexes
+                .catalog()
+                .required_props_with_write(tx, label)
+                .map(|props| {
+                    let props = Arc::new(props);
+                    state.required_props.insert(label, Arc::clone(&props));
+                    props
+                }),
+        };
+        self.store_txn_state(tx, state);
+        result
+    }
+}
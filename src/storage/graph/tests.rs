@@ -1,25 +1,62 @@
 #![cfg(test)]
 
 use super::*;
+use crate::primitives::pager::{PageStore, Pager, PagerOptions};
 use std::ops::Bound;
+use std::path::Path;
+use std::sync::Arc;
+use tempfile::{tempdir, TempDir};
+
+/// File name fixture graphs use inside their temp dir.
+const GRAPH_FILE: &str = "graph.db";
+
+/// Creates a graph in a fresh temp dir, letting `opts` adjust its options.
+fn open_graph(opts: impl FnOnce(&mut GraphOptions)) -> (TempDir, Arc<Pager>, Arc<Graph>) {
+    open_graph_with(PagerOptions::default(), opts)
+}
+
+/// [`open_graph`] with custom pager options.
+fn open_graph_with(
+    pager_opts: PagerOptions,
+    opts: impl FnOnce(&mut GraphOptions),
+) -> (TempDir, Arc<Pager>, Arc<Graph>) {
+    let dir = tempdir().expect("tempdir");
+    let (pager, graph) = open_graph_at(&dir.path().join(GRAPH_FILE), pager_opts, opts);
+    (dir, pager, graph)
+}
+
+/// Opens the graph at `path`, creating the file if it does not exist yet.
+fn open_graph_at(
+    path: &Path,
+    pager_opts: PagerOptions,
+    opts: impl FnOnce(&mut GraphOptions),
+) -> (Arc<Pager>, Arc<Graph>) {
+    let pager = if path.exists() {
+        Arc::new(Pager::open(path, pager_opts).unwrap())
+    } else {
+        Arc::new(Pager::create(path, pager_opts).unwrap())
+    };
+    let store: Arc<dyn PageStore> = pager.clone();
+    let mut options = GraphOptions::new(store);
+    opts(&mut options);
+    (pager, Graph::open(options).unwrap())
+}
 
 mod vacuum_background_tests {
     use super::*;
-    use crate::primitives::pager::{PageStore, Pager, PagerOptions};
-    use crate::storage::{DeleteNodeOpts, GraphOptions, NodeSpec, PropEntry, PropValue};
-    use crate::types::{LabelId, PropId, Result};
-    use std::sync::Arc;
+    use crate::primitives::pager::CheckpointMode;
+    use crate::storage::btree::KeyCodec;
+    use crate::storage::{
+        CounterMetrics, DeleteNodeOpts, NodeData, NodeFilter, NodeSpec, PropEntry, PropValue,
+        PropValueOwned,
+    };
+    use crate::types::{LabelId, NodeId, PropId, Result};
+    use std::collections::HashSet;
+    use std::sync::atomic::Ordering;
     use std::time::Duration;
-    use tempfile::tempdir;
 
-    fn setup_graph(cfg: VacuumCfg) -> (tempfile::TempDir, Arc<Pager>, Arc<Graph>) {
-        let dir = tempdir().expect("tempdir");
-        let path = dir.path().join("vacuum-bg.db");
-        let pager = Arc::new(Pager::create(&path, PagerOptions::default()).unwrap());
-        let store: Arc<dyn PageStore> = pager.clone();
-        let graph = Graph::open(GraphOptions::new(store).vacuum(cfg)).unwrap();
-        (dir, pager, graph)
-    }
+    const PERSON: LabelId = LabelId(1);
+    const COMPANY: LabelId = LabelId(2);
 
     fn create_and_delete_node(pager: &Pager, graph: &Graph) -> Result<()> {
         let mut write = pager.begin_write()?;
@@ -51,7 +88,7 @@ mod vacuum_background_tests {
             reader_timeout: Duration::MAX,
             reader_timeout_warn_threshold_pct: 0,
         };
-        let (_tmpdir, pager, graph) = setup_graph(cfg.clone());
+        let (_tmpdir, pager, graph) = open_graph(|opts| opts.vacuum = cfg);
         create_and_delete_node(&pager, &graph)?;
         graph.vacuum_sched.last_stats.borrow_mut().take();
         graph.vacuum_sched.running.set(true);
@@ -78,7 +115,7 @@ mod vacuum_background_tests {
             reader_timeout: Duration::MAX,
             reader_timeout_warn_threshold_pct: 0,
         };
-        let (_tmpdir, pager, graph) = setup_graph(cfg.clone());
+        let (_tmpdir, pager, graph) = open_graph(|opts| opts.vacuum = cfg.clone());
         let mut write = pager.begin_write()?;
         let node = graph.create_node(
             &mut write,
@@ -122,29 +159,349 @@ mod vacuum_background_tests {
             reader_timeout: Duration::MAX,
             reader_timeout_warn_threshold_pct: 0,
         };
-        let (_tmpdir, _pager, graph) = setup_graph(cfg);
+        let (_tmpdir, _pager, graph) = open_graph(|opts| opts.vacuum = cfg);
         assert_eq!(graph.vacuum_retention_window(), retention);
         Ok(())
     }
+
+    #[test]
+    fn free_pages_track_deletes_and_vacuum() -> Result<()> {
+        let (_tmpdir, pager, graph) = open_graph(|opts| {
+            opts.vacuum = VacuumCfg {
+                enabled: false,
+                retention_window: Duration::from_millis(0),
+                ..VacuumCfg::default()
+            }
+        });
+
+        let name = "x".repeat(40);
+        let mut write = pager.begin_write()?;
+        let mut nodes = Vec::new();
+        for i in 0..2_000 {
+            let props = [
+                PropEntry::new(PropId(1), PropValue::Int(i)),
+                PropEntry::new(PropId(2), PropValue::Str(&name)),
+            ];
+            nodes.push(graph.create_node(
+                &mut write,
+                NodeSpec {
+                    labels: &[LabelId(1)],
+                    props: &props,
+                },
+            )?);
+        }
+        pager.commit(write)?;
+        pager.checkpoint(CheckpointMode::Force)?;
+        let populated = graph.space_report()?;
+        assert_eq!(populated.free_pages, 0);
+        assert_eq!(populated.live_pages, populated.total_pages);
+        assert_eq!(graph.free_page_count(), populated.free_pages);
+
+        let mut write = pager.begin_write()?;
+        for node in &nodes[..1_800] {
+            graph.delete_node(&mut write, *node, DeleteNodeOpts::default())?;
+        }
+        pager.commit(write)?;
+        let horizon = graph.compute_vacuum_horizon().expect("vacuum horizon");
+        graph.vacuum_mvcc(horizon, None, VacuumTrigger::Manual, None)?;
+        let fragmented = graph.space_report()?;
+        assert!(fragmented.free_pages > populated.free_pages);
+        assert!(fragmented.fragmentation > populated.fragmentation);
+        assert_eq!(
+            fragmented.free_pages + fragmented.live_pages,
+            fragmented.total_pages
+        );
+
+        // Checkpointing folds the reclaimed pages into the freelist and
+        // truncates the free tail of the file.
+        pager.checkpoint(CheckpointMode::Force)?;
+        let compacted = graph.space_report()?;
+        assert!(compacted.free_pages < fragmented.free_pages);
+        assert!(compacted.fragmentation < fragmented.fragmentation);
+        assert!(compacted.total_pages < fragmented.total_pages);
+        Ok(())
+    }
+
+    #[test]
+    fn prop_column_matches_node_reads() -> Result<()> {
+        let (_tmpdir, pager, graph) = open_graph(|_| {});
+
+        let long_name = "n".repeat(4_096);
+        let mut write = pager.begin_write()?;
+        let mut ids = Vec::new();
+        for i in 0..40_i64 {
+            let name = if i % 7 == 0 {
+                long_name.clone()
+            } else {
+                format!("user-{i}")
+            };
+            let mut props = vec![
+                PropEntry::new(PropId(1), PropValue::Int(i)),
+                PropEntry::new(PropId(3), PropValue::Bool(i % 2 == 0)),
+            ];
+            if i % 5 != 0 {
+                props.push(PropEntry::new(PropId(2), PropValue::Str(&name)));
+            }
+            ids.push(graph.create_node(
+                &mut write,
+                NodeSpec {
+                    labels: &[LabelId(1)],
+                    props: &props,
+                },
+            )?);
+        }
+        graph.delete_node(&mut write, ids[3], DeleteNodeOpts::default())?;
+        pager.commit(write)?;
+
+        let mut query: Vec<NodeId> = ids.iter().rev().copied().collect();
+        query.push(NodeId(u64::MAX));
+        query.push(ids[8]);
+        let read = pager.begin_latest_committed_read()?;
+        for prop in [PropId(1), PropId(2), PropId(3), PropId(9)] {
+            let column = graph.get_prop_for_nodes(&read, &query, prop)?;
+            assert_eq!(column.len(), query.len());
+            for (id, value) in query.iter().zip(&column) {
+                let expected: Option<PropValueOwned> =
+                    graph.get_node(&read, *id)?.and_then(|node| {
+                        node.props
+                            .into_iter()
+                            .find(|(key, _)| *key == prop)
+                            .map(|(_, value)| value)
+                    });
+                assert_eq!(value, &expected, "node {id:?} prop {prop:?}");
+            }
+        }
+        let names = graph.get_prop_for_nodes(&read, &[ids[7], ids[5], ids[3]], PropId(2))?;
+        assert_eq!(
+            names,
+            vec![Some(PropValueOwned::Str(long_name.clone())), None, None]
+        );
+        Ok(())
+    }
+
+    fn load_strings(compress: bool, values: &[String]) -> Result<(GraphSpaceUsage, Vec<NodeData>)> {
+        let (_tmpdir, pager, graph) = open_graph(|opts| {
+            if compress {
+                opts.compress_inline_props = Some(16);
+            }
+        });
+
+        let mut write = pager.begin_write()?;
+        let mut ids: Vec<NodeId> = Vec::new();
+        for value in values {
+            ids.push(graph.create_node(
+                &mut write,
+                NodeSpec {
+                    labels: &[],
+                    props: &[
+                        PropEntry::new(PropId(1), PropValue::Str(value)),
+                        PropEntry::new(PropId(2), PropValue::Bytes(value.as_bytes())),
+                    ],
+                },
+            )?);
+        }
+        pager.commit(write)?;
+
+        let read = pager.begin_latest_committed_read()?;
+        let nodes = ids
+            .iter()
+            .map(|id| Ok(graph.get_node(&read, *id)?.expect("node")))
+            .collect::<Result<Vec<_>>>()?;
+        Ok((graph.space_usage()?, nodes))
+    }
+
+    #[test]
+    fn compressed_inline_strings_pack_tighter_and_read_back() -> Result<()> {
+        let values: Vec<String> = (0..400)
+            .map(|i| match i % 4 {
+                // Repetitive values, one of them longer than the inline limit.
+                0 => format!("{i:0>40}"),
+                1 => "status=active;".repeat(3),
+                2 => "x".repeat(200),
+                // Too short to compress.
+                _ => format!("id-{i}"),
+            })
+            .collect();
+        let (plain_usage, plain) = load_strings(false, &values)?;
+        let (packed_usage, packed) = load_strings(true, &values)?;
+
+        assert!(
+            packed_usage.nodes_bytes < plain_usage.nodes_bytes,
+            "{} >= {}",
+            packed_usage.nodes_bytes,
+            plain_usage.nodes_bytes
+        );
+        assert!(packed_usage.nodes_pages <= plain_usage.nodes_pages);
+        for ((value, plain), packed) in values.iter().zip(&plain).zip(&packed) {
+            let expected = vec![
+                (PropId(1), PropValueOwned::Str(value.clone())),
+                (PropId(2), PropValueOwned::Bytes(value.as_bytes().to_vec())),
+            ];
+            assert_eq!(plain.props, expected);
+            assert_eq!(packed.props, expected);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn overflow_ratio_tracks_spilled_values() -> Result<()> {
+        let metrics = Arc::new(CounterMetrics::default());
+        let (_tmpdir, pager, graph) = open_graph(|opts| {
+            opts.inline_prop_blob = Some(128);
+            opts.inline_prop_value = Some(64);
+            opts.metrics = Some(metrics.clone());
+        });
+
+        let small = "s".repeat(16);
+        let large = "L".repeat(512);
+        let mut write = pager.begin_write()?;
+        for i in 0..8 {
+            // One int plus one string per node; every fourth string spills.
+            let text = if i % 4 == 0 { &large } else { &small };
+            graph.create_node(
+                &mut write,
+                NodeSpec {
+                    labels: &[LabelId(1)],
+                    props: &[
+                        PropEntry::new(PropId(1), PropValue::Int(i)),
+                        PropEntry::new(PropId(2), PropValue::Str(text)),
+                    ],
+                },
+            )?;
+        }
+        // Each value fits inline, but together they outgrow the record, so
+        // the whole map moves to the VStore.
+        let medium = "m".repeat(48);
+        let wide: Vec<PropEntry<'_>> = (1..=4)
+            .map(|prop| PropEntry::new(PropId(prop), PropValue::Str(&medium)))
+            .collect();
+        graph.create_node(
+            &mut write,
+            NodeSpec {
+                labels: &[],
+                props: &wide,
+            },
+        )?;
+        pager.commit(write)?;
+
+        // Re-encoding a node's map to add a label is not a property write.
+        let mut write = pager.begin_write()?;
+        let added =
+            graph.add_label_where(&mut write, &NodeFilter::Label(LabelId(1)), LabelId(2))?;
+        assert_eq!(added, 8);
+        pager.commit(write)?;
+
+        assert_eq!(metrics.prop_inline_writes.load(Ordering::Relaxed), 14);
+        assert_eq!(metrics.prop_overflow_writes.load(Ordering::Relaxed), 6);
+        let placement = graph.prop_placement();
+        assert_eq!(placement.inline_writes, 14);
+        assert_eq!(placement.overflow_writes, 6);
+        assert!((placement.overflow_ratio() - 0.3).abs() < f64::EPSILON);
+        Ok(())
+    }
+
+    fn build_mixed(path: &Path, cluster_by_label: bool) -> Result<()> {
+        let (pager, graph) = open_graph_at(path, PagerOptions::default(), |opts| {
+            opts.cluster_by_label = cluster_by_label
+        });
+        let name = "x".repeat(40);
+        let mut write = pager.begin_write()?;
+        for i in 0..2_000 {
+            let label = if i % 2 == 0 { PERSON } else { COMPANY };
+            let props = [
+                PropEntry::new(PropId(1), PropValue::Int(i)),
+                PropEntry::new(PropId(2), PropValue::Str(&name)),
+            ];
+            graph.create_node(
+                &mut write,
+                NodeSpec {
+                    labels: &[label],
+                    props: &props,
+                },
+            )?;
+        }
+        pager.commit(write)?;
+        pager.checkpoint(CheckpointMode::Force)?;
+        Ok(())
+    }
+
+    /// Returns the distinct nodes-tree leaf pages holding the rows a label
+    /// scan resolves, along with the number of rows.
+    fn label_scan_pages(path: &Path) -> Result<(usize, usize)> {
+        let (pager, graph) = open_graph_at(path, PagerOptions::default(), |_| {});
+        let read = pager.begin_latest_committed_read()?;
+        let nodes = graph.nodes_with_label(&read, PERSON)?;
+        let mut pages = HashSet::new();
+        let mut key = Vec::new();
+        for node in &nodes {
+            assert!(graph.get_node(&read, *node)?.is_some());
+            key.clear();
+            <u64 as KeyCodec>::encode_key(&node.0, &mut key);
+            let (leaf, _) = graph.nodes.find_leaf(&read, &key)?;
+            pages.insert(leaf.id);
+        }
+        Ok((pages.len(), nodes.len()))
+    }
+
+    #[test]
+    fn clustered_label_scan_touches_fewer_pages() -> Result<()> {
+        let dir = tempdir().expect("tempdir");
+        let interleaved = dir.path().join("interleaved.db");
+        let clustered = dir.path().join("clustered.db");
+        build_mixed(&interleaved, false)?;
+        build_mixed(&clustered, true)?;
+
+        let (interleaved_pages, interleaved_rows) = label_scan_pages(&interleaved)?;
+        let (clustered_pages, clustered_rows) = label_scan_pages(&clustered)?;
+        assert_eq!(interleaved_rows, 1_000);
+        assert_eq!(clustered_rows, 1_000);
+        assert!(
+            clustered_pages * 4 < interleaved_pages * 3,
+            "clustered scan pages {clustered_pages} vs interleaved {interleaved_pages}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn clustering_is_fixed_at_creation() -> Result<()> {
+        let dir = tempdir().expect("tempdir");
+        let clustered = dir.path().join("clustered.db");
+        build_mixed(&clustered, true)?;
+        let (_, graph) = open_graph_at(&clustered, PagerOptions::default(), |_| {});
+        assert!(graph.cluster_by_label());
+
+        let plain = dir.path().join("plain.db");
+        build_mixed(&plain, false)?;
+        let (pager, graph) = open_graph_at(&plain, PagerOptions::default(), |opts| {
+            opts.cluster_by_label = true
+        });
+        assert!(!graph.cluster_by_label());
+        let read = pager.begin_latest_committed_read()?;
+        let ids = graph.nodes_with_label(&read, PERSON)?;
+        assert!(ids.windows(2).all(|pair| pair[1].0 == pair[0].0 + 2));
+        Ok(())
+    }
 }
 
 mod adjacency_commit_tests {
     use super::*;
-    use crate::primitives::pager::{PageStore, Pager, PagerOptions, ReadGuard};
-    use crate::storage::{adjacency, EdgeSpec, GraphOptions, NodeSpec};
-    use crate::types::{Result, TypeId};
-    use std::ops::Bound;
-    use std::sync::Arc;
-    use tempfile::tempdir;
-
-    fn setup_graph(name: &str) -> (tempfile::TempDir, Arc<Pager>, Arc<Graph>) {
-        let dir = tempdir().expect("tempdir");
-        let path = dir.path().join(name);
-        let pager = Arc::new(Pager::create(&path, PagerOptions::default()).unwrap());
-        let store: Arc<dyn PageStore> = pager.clone();
-        let graph = Graph::open(GraphOptions::new(store)).unwrap();
-        (dir, pager, graph)
-    }
+    use crate::primitives::pager::{ReadGuard, WriteGuard};
+    use crate::storage::patch::{PropPatch, PropPatchOp};
+    use crate::storage::{
+        adjacency, ConflictPolicy, CounterMetrics, DeleteNodeOpts, Dir, EdgeSpec, ExpandOpts,
+        IndexDef, IndexKind, IndexState, MergeNodeOpts, NodeFilter, NodeSpec, PropEntry, PropValue,
+        PropValueOwned, TypeTag,
+    };
+    use crate::types::{EdgeId, LabelId, NodeId, PropId, Result, SombraError, TypeId};
+    use std::ops::ControlFlow;
+    use std::sync::atomic::Ordering;
+
+    const USER: LabelId = LabelId(1);
+    const GUEST: LabelId = LabelId(2);
+    const PERSON: LabelId = LabelId(1);
+    const NAME: PropId = PropId(1);
+    const EMAIL: PropId = PropId(2);
+    const AGE: PropId = PropId(2);
 
     fn collect_edges(
         graph: &Graph,
@@ -172,7 +529,7 @@ mod adjacency_commit_tests {
 
     #[test]
     fn adjacency_batch_flushes_on_commit() -> Result<()> {
-        let (dir, pager, graph) = setup_graph("adjacency_batch_flush.db");
+        let (dir, pager, graph) = open_graph(|_| {});
 
         let mut write = pager.begin_write()?;
         let a = graph.create_node(
@@ -221,92 +578,2348 @@ mod adjacency_commit_tests {
         drop(dir);
         Ok(())
     }
-}
 
-mod wal_recovery_tests {
-    use super::*;
-    use crate::primitives::pager::{CheckpointMode, PageStore, Pager, PagerOptions};
-    use crate::storage::mvcc::VersionLogEntry;
-    use crate::storage::node;
-    use crate::storage::patch::{PropPatch, PropPatchOp};
-    use crate::storage::props;
-    use crate::storage::props::RawPropValue;
-    use crate::storage::{NodeSpec, PropEntry, PropValue};
-    use std::sync::Arc;
-    use tempfile::tempdir;
+    /// Builds `a -> b` and `a -> c`, then drops the `a -> b` edge row while
+    /// leaving its adjacency entries behind.
+    fn broken_graph(
+        heal_on_read: bool,
+    ) -> Result<(TempDir, Arc<Pager>, Arc<Graph>, NodeId, NodeId)> {
+        let (dir, pager, graph) = open_graph(|opts| opts.heal_on_read = heal_on_read);
+        let mut write = pager.begin_write()?;
+        let node = NodeSpec {
+            labels: &[LabelId(1)],
+            props: &[],
+        };
+        let a = graph.create_node(&mut write, node.clone())?;
+        let b = graph.create_node(&mut write, node.clone())?;
+        let c = graph.create_node(&mut write, node)?;
+        let mut edges = Vec::new();
+        for dst in [b, c] {
+            edges.push(graph.create_edge(
+                &mut write,
+                EdgeSpec {
+                    src: a,
+                    dst,
+                    ty: TypeId(1),
+                    props: &[],
+                },
+            )?);
+        }
+        pager.commit(write)?;
+
+        let mut write = pager.begin_write()?;
+        assert!(graph.edges.delete(&mut write, &edges[0].0)?);
+        graph.persist_tree_root(&mut write, RootKind::Edges)?;
+        pager.commit(write)?;
+        Ok((dir, pager, graph, a, c))
+    }
+
+    fn out_neighbors(pager: &Pager, graph: &Graph, node: NodeId) -> Result<Vec<NodeId>> {
+        let read = pager.begin_latest_committed_read()?;
+        let cursor = graph.neighbors(&read, node, Dir::Out, None, ExpandOpts::default())?;
+        Ok(cursor.map(|neighbor| neighbor.neighbor).collect())
+    }
 
     #[test]
-    fn wal_recovery_restores_version_log_entries() -> Result<()> {
-        let dir = tempdir().expect("tempdir");
-        let path = dir.path().join("mvcc_wal_recovery.db");
-        let pager = Arc::new(Pager::create(&path, PagerOptions::default()).unwrap());
-        let store: Arc<dyn PageStore> = pager.clone();
-        let graph = Graph::open(GraphOptions::new(store)).unwrap();
+    fn strict_reads_fail_on_dangling_adjacency() -> Result<()> {
+        let (_tmpdir, pager, graph, a, _) = broken_graph(false)?;
+        match out_neighbors(&pager, &graph, a) {
+            Err(SombraError::Corruption(msg)) => {
+                assert_eq!(msg, "adjacency entry references missing edge")
+            }
+            other => panic!("expected corruption error, got {other:?}"),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn lenient_reads_skip_and_repair_dangling_adjacency() -> Result<()> {
+        let (_tmpdir, pager, graph, a, c) = broken_graph(true)?;
+        assert_eq!(out_neighbors(&pager, &graph, a)?, vec![c]);
 
         let mut write = pager.begin_write()?;
-        let node = graph.create_node(
-            &mut write,
-            NodeSpec {
-                labels: &[],
-                props: &[PropEntry::new(PropId(1), PropValue::Int(1))],
-            },
-        )?;
+        assert_eq!(graph.repair_dangling_adjacency(&mut write)?, 1);
         pager.commit(write)?;
+        let read = pager.begin_latest_committed_read()?;
+        assert_eq!(graph.debug_collect_adj_fwd(&read)?.len(), 1);
+        assert_eq!(graph.debug_collect_adj_rev(&read)?.len(), 1);
+        drop(read);
 
+        // Nothing is left to queue once the chain is repaired.
+        assert_eq!(out_neighbors(&pager, &graph, a)?, vec![c]);
         let mut write = pager.begin_write()?;
-        graph.update_node(
-            &mut write,
-            node,
-            PropPatch::new(vec![PropPatchOp::Set(PropId(1), PropValue::Int(2))]),
-        )?;
+        assert_eq!(graph.repair_dangling_adjacency(&mut write)?, 0);
         pager.commit(write)?;
-        eprintln!("node updated");
-        pager.checkpoint(CheckpointMode::Force)?;
-        eprintln!("checkpoint after update");
+        Ok(())
+    }
 
-        drop(graph);
-        drop(pager);
+    const CHAIN_LEN: usize = 512;
 
-        let reopened_store: Arc<dyn PageStore> =
-            Arc::new(Pager::open(&path, PagerOptions::default()).unwrap());
-        let reopened_graph = Graph::open(GraphOptions::new(Arc::clone(&reopened_store))).unwrap();
-        let read = reopened_store.begin_latest_committed_read()?;
+    /// Builds `root -> n1 -> ... -> n(CHAIN_LEN - 1)` plus `keeper -> n(CHAIN_LEN / 2)`.
+    fn chain(graph: &Graph, pager: &Pager) -> Result<(Vec<NodeId>, NodeId)> {
+        let mut write = pager.begin_write()?;
+        let spec = NodeSpec {
+            labels: &[],
+            props: &[],
+        };
+        let nodes = (0..CHAIN_LEN)
+            .map(|_| graph.create_node(&mut write, spec.clone()))
+            .collect::<Result<Vec<_>>>()?;
+        let keeper = graph.create_node(&mut write, spec)?;
+        let mut links: Vec<(NodeId, NodeId)> = nodes.windows(2).map(|w| (w[0], w[1])).collect();
+        links.push((keeper, nodes[CHAIN_LEN / 2]));
+        for (src, dst) in links {
+            graph.create_edge(
+                &mut write,
+                EdgeSpec {
+                    src,
+                    dst,
+                    ty: TypeId(1),
+                    props: &[],
+                },
+            )?;
+        }
+        pager.commit(write)?;
+        Ok((nodes, keeper))
+    }
 
-        let mut cursor = reopened_graph
-            .nodes
-            .range(&read, Bound::Unbounded, Bound::Unbounded)?;
-        let (_node_key, head_bytes) = cursor.next()?.expect("node present after crash recovery");
-        let head = node::decode(&head_bytes)?;
-        let head_props = reopened_graph.read_node_prop_bytes(&head.row.props)?;
-        let head_raw = props::decode_raw(&head_props)?;
-        assert_eq!(head_raw.len(), 1);
-        match head_raw[0].value {
-            RawPropValue::Int(value) => assert_eq!(value, 2),
-            _ => panic!("unexpected property value {:?}", head_raw[0].value),
+    #[test]
+    fn orphan_cascade_walks_deep_chain_iteratively() -> Result<()> {
+        let (_tmpdir, pager, graph) = open_graph(|_| {});
+        let (nodes, keeper) = chain(&graph, &pager)?;
+
+        // The keeper's edge stops the cascade halfway down the chain.
+        let too_shallow = DeleteNodeOpts::cascade_orphans().max_cascade_depth(10);
+        let mut write = pager.begin_write()?;
+        let err = graph
+            .delete_node(&mut write, nodes[0], too_shallow)
+            .expect_err("depth bound enforced");
+        assert!(matches!(err, SombraError::Invalid(msg) if msg.contains("max_cascade_depth")));
+        drop(write);
+        let read = pager.begin_latest_committed_read()?;
+        assert!(graph.get_node(&read, nodes[0])?.is_some());
+        drop(read);
+
+        let bounded = DeleteNodeOpts::cascade_orphans().max_cascade_depth(CHAIN_LEN as u32);
+        let mut write = pager.begin_write()?;
+        graph.delete_node(&mut write, nodes[0], bounded)?;
+        pager.commit(write)?;
+
+        let read = pager.begin_latest_committed_read()?;
+        for (idx, node) in nodes.iter().enumerate() {
+            let alive = graph.get_node(&read, *node)?.is_some();
+            assert_eq!(alive, idx >= CHAIN_LEN / 2, "node {idx}");
         }
+        assert!(graph.get_node(&read, keeper)?.is_some());
+        Ok(())
+    }
 
-        let prev_ptr = head.prev_ptr;
-        assert!(
-            !prev_ptr.is_null(),
-            "node update should log previous version"
-        );
-        let log_bytes = reopened_graph
-            .version_log
-            .get(&read, &prev_ptr.raw())?
-            .expect("version log entry missing after recovery");
-        let entry = VersionLogEntry::decode(&log_bytes)?;
-        let old_version = node::decode(&entry.bytes)?;
-        let old_props_bytes = reopened_graph.read_node_prop_bytes(&old_version.row.props)?;
-        let old_raw = props::decode_raw(&old_props_bytes)?;
-        assert_eq!(old_raw.len(), 1);
-        match old_raw[0].value {
-            RawPropValue::Int(value) => assert_eq!(value, 1),
-            _ => panic!("unexpected historical property {:?}", old_raw[0].value),
+    #[test]
+    fn orphan_cascade_sees_edges_from_the_same_transaction() -> Result<()> {
+        let (_tmpdir, pager, graph) = open_graph(|_| {});
+        let spec = NodeSpec {
+            labels: &[],
+            props: &[],
+        };
+        let link = |write: &mut _, src, dst| {
+            graph.create_edge(
+                write,
+                EdgeSpec {
+                    src,
+                    dst,
+                    ty: TypeId(1),
+                    props: &[],
+                },
+            )
+        };
+
+        let mut write = pager.begin_write()?;
+        let root = graph.create_node(&mut write, spec.clone())?;
+        let adopted = graph.create_node(&mut write, spec.clone())?;
+        let orphan = graph.create_node(&mut write, spec.clone())?;
+        link(&mut write, root, adopted)?;
+        link(&mut write, root, orphan)?;
+        pager.commit(write)?;
+
+        // A second parent and a fresh child, both added right before the delete.
+        let mut write = pager.begin_write()?;
+        let parent = graph.create_node(&mut write, spec.clone())?;
+        let adoption = link(&mut write, parent, adopted)?;
+        let fresh = graph.create_node(&mut write, spec)?;
+        link(&mut write, root, fresh)?;
+        graph.delete_node(&mut write, root, DeleteNodeOpts::cascade_orphans())?;
+        pager.commit(write)?;
+
+        let read = pager.begin_latest_committed_read()?;
+        for (node, alive) in [
+            (root, false),
+            (orphan, false),
+            (fresh, false),
+            (adopted, true),
+            (parent, true),
+        ] {
+            assert_eq!(graph.get_node(&read, node)?.is_some(), alive, "{node:?}");
+        }
+        assert!(graph.get_edge(&read, adoption)?.is_some());
+        assert_eq!(graph.degree(&read, adopted, Dir::In, None)?, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn shortest_path_reports_edges_between_consecutive_nodes() -> Result<()> {
+        let (_tmpdir, pager, graph) = open_graph(|_| {});
+        let (road, rail) = (TypeId(1), TypeId(2));
+
+        let mut write = pager.begin_write()?;
+        let mut nodes = Vec::new();
+        for _ in 0..5 {
+            nodes.push(graph.create_node(
+                &mut write,
+                NodeSpec {
+                    labels: &[],
+                    props: &[],
+                },
+            )?);
+        }
+        let (a, b, c, d, isolated) = (nodes[0], nodes[1], nodes[2], nodes[3], nodes[4]);
+        // Parallel roads a->b, a single road on to d, and a rail shortcut a->c.
+        for (src, dst, ty) in [
+            (a, b, road),
+            (a, b, road),
+            (b, c, road),
+            (c, d, road),
+            (a, c, rail),
+        ] {
+            graph.create_edge(
+                &mut write,
+                EdgeSpec {
+                    src,
+                    dst,
+                    ty,
+                    props: &[],
+                },
+            )?;
         }
+        pager.commit(write)?;
 
-        drop(read);
-        drop(reopened_graph);
-        drop(reopened_store);
+        let read = pager.begin_latest_committed_read()?;
+        let assert_connected = |hops: &[(NodeId, Option<EdgeId>)]| -> Result<()> {
+            assert_eq!(hops[0].1, None);
+            for pair in hops.windows(2) {
+                let edge_id = pair[1].1.expect("edge for every hop after start");
+                let edge = graph.get_edge(&read, edge_id)?.expect("edge exists");
+                assert_eq!((edge.src, edge.dst), (pair[0].0, pair[1].0));
+            }
+            Ok(())
+        };
+
+        let by_road = graph
+            .shortest_path_with_edges(&read, a, d, Dir::Out, Some(&[road]))?
+            .expect("road path");
+        let node_ids: Vec<NodeId> = by_road.iter().map(|(node, _)| *node).collect();
+        assert_eq!(node_ids, vec![a, b, c, d]);
+        assert_connected(&by_road)?;
+
+        let any = graph
+            .shortest_path_with_edges(&read, a, d, Dir::Out, None)?
+            .expect("any path");
+        let node_ids: Vec<NodeId> = any.iter().map(|(node, _)| *node).collect();
+        assert_eq!(node_ids, vec![a, c, d]);
+        assert_connected(&any)?;
+        let rail_edge = graph.get_edge(&read, any[1].1.expect("rail edge"))?;
+        assert_eq!(rail_edge.map(|edge| edge.ty), Some(rail));
+
+        assert!(graph
+            .shortest_path_with_edges(&read, d, a, Dir::Out, None)?
+            .is_none());
+        assert!(graph
+            .shortest_path_with_edges(&read, a, isolated, Dir::Out, None)?
+            .is_none());
+        assert_eq!(
+            graph.shortest_path_with_edges(&read, a, a, Dir::Out, None)?,
+            Some(vec![(a, None)])
+        );
+        Ok(())
+    }
+
+    const WEIGHT: PropId = PropId(3);
+    const KNOWS: TypeId = TypeId(1);
+    const FOLLOWS: TypeId = TypeId(2);
+
+    struct MergeFixture {
+        _dir: TempDir,
+        pager: Arc<Pager>,
+        graph: Arc<Graph>,
+        keep: NodeId,
+        remove: NodeId,
+        a: NodeId,
+        b: NodeId,
+    }
+
+    /// `keep -KNOWS-> a`, `keep -KNOWS-> remove`, and `remove` linked to
+    /// `a` (duplicate of keep's edge) and `b` in both directions.
+    fn merge_fixture() -> Result<MergeFixture> {
+        let (dir, pager, graph) = open_graph(|_| {});
+
+        let mut write = pager.begin_write()?;
+        graph.create_label_index(&mut write, PERSON)?;
+        graph.create_property_index(
+            &mut write,
+            IndexDef {
+                label: PERSON,
+                props: vec![EMAIL],
+                kind: IndexKind::BTree,
+                types: vec![TypeTag::String],
+                max_key_bytes: None,
+                unique: false,
+                state: IndexState::Ready,
+            },
+        )?;
+        let keep = graph.create_node(
+            &mut write,
+            NodeSpec {
+                labels: &[PERSON],
+                props: &[PropEntry::new(NAME, PropValue::Str("Ada"))],
+            },
+        )?;
+        let remove = graph.create_node(
+            &mut write,
+            NodeSpec {
+                labels: &[PERSON],
+                props: &[
+                    PropEntry::new(NAME, PropValue::Str("A. Lovelace")),
+                    PropEntry::new(EMAIL, PropValue::Str("ada@example.com")),
+                ],
+            },
+        )?;
+        let a = graph.create_node(
+            &mut write,
+            NodeSpec {
+                labels: &[PERSON],
+                props: &[],
+            },
+        )?;
+        let b = graph.create_node(
+            &mut write,
+            NodeSpec {
+                labels: &[PERSON],
+                props: &[],
+            },
+        )?;
+        for (src, dst, ty) in [
+            (keep, a, KNOWS),
+            (keep, remove, KNOWS),
+            (remove, a, KNOWS),
+            (remove, b, KNOWS),
+            (b, remove, FOLLOWS),
+        ] {
+            graph.create_edge(
+                &mut write,
+                EdgeSpec {
+                    src,
+                    dst,
+                    ty,
+                    props: &[],
+                },
+            )?;
+        }
+        pager.commit(write)?;
+        Ok(MergeFixture {
+            _dir: dir,
+            pager,
+            graph,
+            keep,
+            remove,
+            a,
+            b,
+        })
+    }
+
+    fn neighbor_ids(graph: &Graph, read: &ReadGuard, node: NodeId, dir: Dir) -> Vec<NodeId> {
+        let mut ids: Vec<NodeId> = graph
+            .neighbors(read, node, dir, None, ExpandOpts::default())
+            .expect("neighbors")
+            .map(|n| n.neighbor)
+            .collect();
+        ids.sort_by_key(|id| id.0);
+        ids
+    }
+
+    /// Adds two `remove -KNOWS-> a` edges told apart only by their weight.
+    fn parallel_edges(f: &MergeFixture, write: &mut WriteGuard<'_>) -> Result<[EdgeId; 2]> {
+        let mut ids = [EdgeId(0); 2];
+        for (id, weight) in ids.iter_mut().zip([1, 2]) {
+            *id = f.graph.create_edge(
+                write,
+                EdgeSpec {
+                    src: f.remove,
+                    dst: f.a,
+                    ty: KNOWS,
+                    props: &[PropEntry::new(WEIGHT, PropValue::Int(weight))],
+                },
+            )?;
+        }
+        Ok(ids)
+    }
+
+    #[test]
+    fn merge_unions_edges_without_duplicates() -> Result<()> {
+        let f = merge_fixture()?;
+        let mut write = f.pager.begin_write()?;
+        let opts = MergeNodeOpts::new(ConflictPolicy::KeepExisting).dedup_edges();
+        let report = f.graph.merge_nodes(&mut write, f.keep, f.remove, opts)?;
+        f.pager.commit(write)?;
+
+        assert_eq!(report.edges_moved, 2);
+        assert_eq!(report.edges_dropped, 2);
+        assert_eq!(report.props_copied, 1);
+        assert_eq!(report.prop_conflicts, 1);
+
+        let read = f.pager.begin_latest_committed_read()?;
+        assert!(f.graph.get_node(&read, f.remove)?.is_none());
+        assert_eq!(
+            neighbor_ids(&f.graph, &read, f.keep, Dir::Out),
+            vec![f.a, f.b]
+        );
+        assert_eq!(neighbor_ids(&f.graph, &read, f.keep, Dir::In), vec![f.b]);
+        assert_eq!(f.graph.degree(&read, f.keep, Dir::Out, None)?, 2);
+        assert_eq!(f.graph.degree(&read, f.a, Dir::In, None)?, 1);
+        assert_eq!(f.graph.edge_count()?, 3);
+        assert_eq!(f.graph.node_count()?, 3);
+
+        let kept = f.graph.get_node(&read, f.keep)?.expect("kept node");
+        assert_eq!(
+            kept.props,
+            vec![
+                (NAME, PropValueOwned::Str("Ada".into())),
+                (EMAIL, PropValueOwned::Str("ada@example.com".into())),
+            ]
+        );
+        let by_email = f.graph.property_scan_eq(
+            &read,
+            PERSON,
+            EMAIL,
+            &PropValueOwned::Str("ada@example.com".into()),
+        )?;
+        assert_eq!(by_email, vec![f.keep]);
+        Ok(())
+    }
+
+    #[test]
+    fn conflict_policy_controls_property_resolution() -> Result<()> {
+        let f = merge_fixture()?;
+        let mut write = f.pager.begin_write()?;
+        let opts = MergeNodeOpts::new(ConflictPolicy::TakeIncoming);
+        f.graph.merge_nodes(&mut write, f.keep, f.remove, opts)?;
+        f.pager.commit(write)?;
+        let read = f.pager.begin_latest_committed_read()?;
+        let kept = f.graph.get_node(&read, f.keep)?.expect("kept node");
+        assert_eq!(
+            kept.props[0],
+            (NAME, PropValueOwned::Str("A. Lovelace".into()))
+        );
+        drop(read);
+
+        let f = merge_fixture()?;
+        let mut write = f.pager.begin_write()?;
+        let err = f
+            .graph
+            .merge_nodes(
+                &mut write,
+                f.keep,
+                f.remove,
+                MergeNodeOpts::new(ConflictPolicy::Fail),
+            )
+            .expect_err("conflicting name");
+        assert!(matches!(err, SombraError::Conflict(_)));
+        drop(write);
+        let read = f.pager.begin_latest_committed_read()?;
+        assert!(f.graph.get_node(&read, f.remove)?.is_some());
+        assert_eq!(f.graph.edge_count()?, 5);
+        Ok(())
+    }
+
+    #[test]
+    fn merge_rejects_self_and_missing_nodes() -> Result<()> {
+        let f = merge_fixture()?;
+        let mut write = f.pager.begin_write()?;
+        assert!(matches!(
+            f.graph
+                .merge_nodes(&mut write, f.keep, f.keep, MergeNodeOpts::default()),
+            Err(SombraError::Invalid(_))
+        ));
+        assert!(matches!(
+            f.graph
+                .merge_nodes(&mut write, f.keep, NodeId(999), MergeNodeOpts::default()),
+            Err(SombraError::NotFound)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn merge_repoints_edges_in_place_by_default() -> Result<()> {
+        let f = merge_fixture()?;
+        let mut write = f.pager.begin_write()?;
+        let parallel = parallel_edges(&f, &mut write)?;
+        let report = f
+            .graph
+            .merge_nodes(&mut write, f.keep, f.remove, MergeNodeOpts::default())?;
+        f.pager.commit(write)?;
+
+        assert_eq!(report.edges_moved, 6);
+        assert_eq!(report.edges_dropped, 0);
+        let read = f.pager.begin_latest_committed_read()?;
+        for (id, weight) in parallel.into_iter().zip([1, 2]) {
+            let edge = f
+                .graph
+                .get_edge(&read, id)?
+                .expect("moved edge keeps its id");
+            assert_eq!((edge.src, edge.dst, edge.ty), (f.keep, f.a, KNOWS));
+            assert_eq!(edge.props, vec![(WEIGHT, PropValueOwned::Int(weight))]);
+        }
+        assert_eq!(f.graph.edge_count()?, 7);
+        // keep -> a three times over, keep -> b, and the former keep -> remove
+        // edge now looping back onto keep.
+        assert_eq!(f.graph.degree(&read, f.keep, Dir::Out, None)?, 6);
+        assert_eq!(f.graph.degree(&read, f.a, Dir::In, None)?, 4);
+        assert_eq!(
+            neighbor_ids(&f.graph, &read, f.keep, Dir::In),
+            vec![f.keep, f.b]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn dedup_keeps_parallel_edges_with_distinct_props() -> Result<()> {
+        let f = merge_fixture()?;
+        let mut write = f.pager.begin_write()?;
+        let parallel = parallel_edges(&f, &mut write)?;
+        let opts = MergeNodeOpts::default().dedup_edges();
+        let report = f.graph.merge_nodes(&mut write, f.keep, f.remove, opts)?;
+        f.pager.commit(write)?;
+
+        // Only the prop-less `remove -> a` twin and `keep -> remove` go.
+        assert_eq!(report.edges_moved, 4);
+        assert_eq!(report.edges_dropped, 2);
+        let read = f.pager.begin_latest_committed_read()?;
+        for id in parallel {
+            let edge = f.graph.get_edge(&read, id)?.expect("distinct edge kept");
+            assert_eq!((edge.src, edge.dst), (f.keep, f.a));
+        }
+        assert_eq!(f.graph.edge_count()?, 5);
+        assert_eq!(f.graph.degree(&read, f.a, Dir::In, None)?, 3);
+        Ok(())
+    }
+
+    #[test]
+    fn page_rank_favors_hub_and_redistributes_dangling_rank() -> Result<()> {
+        let (_tmpdir, pager, graph) = open_graph(|_| {});
+
+        let mut write = pager.begin_write()?;
+        let mut nodes = Vec::new();
+        for _ in 0..4 {
+            nodes.push(graph.create_node(
+                &mut write,
+                NodeSpec {
+                    labels: &[],
+                    props: &[],
+                },
+            )?);
+        }
+        let (hub, leaves) = (nodes[0], &nodes[1..]);
+        for (idx, leaf) in leaves.iter().enumerate() {
+            graph.create_edge(
+                &mut write,
+                EdgeSpec {
+                    src: *leaf,
+                    dst: hub,
+                    ty: TypeId(1),
+                    props: &[],
+                },
+            )?;
+            if idx == 0 {
+                graph.create_edge(
+                    &mut write,
+                    EdgeSpec {
+                        src: hub,
+                        dst: *leaf,
+                        ty: TypeId(2),
+                        props: &[],
+                    },
+                )?;
+            }
+        }
+        pager.commit(write)?;
+
+        let read = pager.begin_latest_committed_read()?;
+        let ranks = graph.page_rank(&read, PageRankOpts::default())?;
+        assert_eq!(ranks.iter().map(|(id, _)| *id).collect::<Vec<_>>(), nodes);
+        let total: f64 = ranks.iter().map(|(_, score)| score).sum();
+        assert!((total - 1.0).abs() < 1e-6);
+        let hub_score = ranks[0].1;
+        assert!(ranks[1..].iter().all(|(_, score)| *score < hub_score));
+        assert!(ranks[1].1 > ranks[2].1);
+        assert!((ranks[2].1 - ranks[3].1).abs() < 1e-9);
+
+        let typed = graph.page_rank(
+            &read,
+            PageRankOpts {
+                edge_type: Some(TypeId(1)),
+                ..PageRankOpts::default()
+            },
+        )?;
+        let typed_total: f64 = typed.iter().map(|(_, score)| score).sum();
+        assert!((typed_total - 1.0).abs() < 1e-6);
+        assert!((typed[1].1 - typed[2].1).abs() < 1e-9);
+
+        let invalid = graph.page_rank(
+            &read,
+            PageRankOpts {
+                damping: 1.5,
+                ..PageRankOpts::default()
+            },
+        );
+        assert!(matches!(invalid, Err(SombraError::Invalid(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn connected_components_label_weak_and_strong_groups() -> Result<()> {
+        let (_tmpdir, pager, graph) = open_graph(|_| {});
+
+        const CYCLE: usize = 256;
+        let mut write = pager.begin_write()?;
+        let mut nodes = Vec::new();
+        for _ in 0..CYCLE + 3 {
+            nodes.push(graph.create_node(
+                &mut write,
+                NodeSpec {
+                    labels: &[],
+                    props: &[],
+                },
+            )?);
+        }
+        let mut link = |src: NodeId, dst: NodeId| {
+            graph.create_edge(
+                &mut write,
+                EdgeSpec {
+                    src,
+                    dst,
+                    ty: TypeId(1),
+                    props: &[],
+                },
+            )
+        };
+        for idx in 0..CYCLE {
+            link(nodes[idx], nodes[(idx + 1) % CYCLE])?;
+        }
+        link(nodes[CYCLE + 1], nodes[0])?;
+        link(nodes[CYCLE + 1], nodes[CYCLE + 2])?;
+        pager.commit(write)?;
+
+        let read = pager.begin_latest_committed_read()?;
+        let weak = graph.connected_components(&read, ComponentMode::Weak)?;
+        assert_eq!(weak.len(), nodes.len());
+        assert!(weak.iter().zip(&nodes).all(|((id, _), node)| id == node));
+        assert!(weak[..CYCLE].iter().all(|(_, c)| *c == 0));
+        assert_eq!(weak[CYCLE].1, 1);
+        assert_eq!(weak[CYCLE + 1].1, 0);
+        assert_eq!(weak[CYCLE + 2].1, 0);
+
+        let strong = graph.connected_components(&read, ComponentMode::Strong)?;
+        assert!(strong[..CYCLE].iter().all(|(_, c)| *c == 0));
+        let tail: Vec<u64> = strong[CYCLE..].iter().map(|(_, c)| *c).collect();
+        assert_eq!(tail, vec![1, 2, 3]);
+        Ok(())
+    }
+
+    const LEGACY: LabelId = LabelId(2);
+    const TIER: PropId = PropId(1);
+
+    fn backfill_graph(count: i64) -> (TempDir, Arc<Pager>, Arc<Graph>, Vec<NodeId>) {
+        let (dir, pager, graph) = open_graph(|_| {});
+        let mut write = pager.begin_write().unwrap();
+        graph.create_label_index(&mut write, USER).unwrap();
+        graph.create_label_index(&mut write, LEGACY).unwrap();
+        graph
+            .create_property_index(
+                &mut write,
+                IndexDef {
+                    label: LEGACY,
+                    props: vec![TIER],
+                    kind: IndexKind::BTree,
+                    types: vec![TypeTag::Int],
+                    max_key_bytes: None,
+                    unique: false,
+                    state: IndexState::Ready,
+                },
+            )
+            .unwrap();
+        let mut nodes = Vec::new();
+        for i in 0..count {
+            let props = [PropEntry::new(TIER, PropValue::Int(i % 2))];
+            let node = graph
+                .create_node(
+                    &mut write,
+                    NodeSpec {
+                        labels: &[USER],
+                        props: &props,
+                    },
+                )
+                .unwrap();
+            nodes.push(node);
+        }
+        pager.commit(write).unwrap();
+        (dir, pager, graph, nodes)
+    }
+
+    #[test]
+    fn add_label_where_tags_nodes_before_cutoff() -> Result<()> {
+        let (_dir, pager, graph, nodes) = backfill_graph(6);
+        let cutoff = nodes[3];
+        let filter = NodeFilter::IdRange {
+            label: USER,
+            start: Bound::Unbounded,
+            end: Bound::Excluded(cutoff),
+        };
+
+        let mut write = pager.begin_write()?;
+        assert_eq!(graph.add_label_where(&mut write, &filter, LEGACY)?, 3);
+        pager.commit(write)?;
+
+        let read = pager.begin_latest_committed_read()?;
+        assert_eq!(graph.nodes_with_label(&read, LEGACY)?, nodes[..3].to_vec());
+        assert_eq!(graph.count_nodes_with_label(&read, USER)?, 6);
+        let tagged = graph.get_node(&read, nodes[0])?.expect("node");
+        assert_eq!(tagged.labels, vec![USER, LEGACY]);
+        assert_eq!(tagged.props, vec![(TIER, PropValueOwned::Int(0))]);
+        // Property indexes declared on the new label pick up the relabeled nodes.
+        let odd = graph.property_scan_eq(&read, LEGACY, TIER, &PropValueOwned::Int(1))?;
+        assert_eq!(odd, vec![nodes[1]]);
+        drop(read);
+
+        let mut write = pager.begin_write()?;
+        assert_eq!(graph.add_label_where(&mut write, &filter, LEGACY)?, 0);
+        pager.commit(write)?;
+        Ok(())
+    }
+
+    #[test]
+    fn add_label_where_leaves_snapshot_readers_unchanged() -> Result<()> {
+        let (_dir, pager, graph, nodes) = backfill_graph(2);
+        let before = pager.begin_latest_committed_read()?;
+
+        let mut write = pager.begin_write()?;
+        let added = graph.add_label_where(&mut write, &NodeFilter::Label(USER), LEGACY)?;
+        assert_eq!(added, 2);
+        pager.commit(write)?;
+
+        let old = graph.get_node(&before, nodes[1])?.expect("node");
+        assert_eq!(old.labels, vec![USER]);
+        let read = pager.begin_latest_committed_read()?;
+        assert_eq!(graph.nodes_with_label(&read, LEGACY)?, nodes);
+        Ok(())
+    }
+
+    const ITEM: LabelId = LabelId(1);
+    const SCORE: PropId = PropId(1);
+
+    fn decode_int_key(key: &[u8]) -> i64 {
+        let bytes: [u8; 8] = key.try_into().expect("int key");
+        (u64::from_be_bytes(bytes) ^ 0x8000_0000_0000_0000) as i64
+    }
+
+    fn scan_all(kind: IndexKind) -> Result<()> {
+        let (_tmpdir, pager, graph) = open_graph(|_| {});
+        let mut write = pager.begin_write()?;
+        graph.create_property_index(
+            &mut write,
+            IndexDef {
+                label: ITEM,
+                props: vec![SCORE],
+                kind,
+                types: vec![TypeTag::Int],
+                max_key_bytes: None,
+                unique: false,
+                state: IndexState::Ready,
+            },
+        )?;
+        let mut expected: Vec<(i64, NodeId)> = Vec::new();
+        for score in [30, -5, 12, 7, 12, 0] {
+            let node = graph.create_node(
+                &mut write,
+                NodeSpec {
+                    labels: &[ITEM],
+                    props: &[PropEntry::new(SCORE, PropValue::Int(score))],
+                },
+            )?;
+            expected.push((score, node));
+        }
+        pager.commit(write)?;
+        expected.sort();
+
+        let read = pager.begin_latest_committed_read()?;
+        let mut seen = Vec::new();
+        graph.scan_index(&read, ITEM, SCORE, |key, node| {
+            seen.push((decode_int_key(key), node));
+            ControlFlow::Continue(())
+        })?;
+        assert_eq!(seen, expected);
+
+        let mut first_two = Vec::new();
+        graph.scan_index(&read, ITEM, SCORE, |key, node| {
+            first_two.push((decode_int_key(key), node));
+            if first_two.len() == 2 {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        })?;
+        assert_eq!(first_two, expected[..2]);
+        assert!(graph
+            .scan_index(&read, ITEM, PropId(2), |_, _| ControlFlow::Continue(()))
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn scan_index_yields_sorted_btree_entries() -> Result<()> {
+        scan_all(IndexKind::BTree)
+    }
+
+    #[test]
+    fn scan_index_yields_sorted_chunked_entries() -> Result<()> {
+        scan_all(IndexKind::Chunked)
+    }
+
+    const DOC: LabelId = LabelId(1);
+    const BODY: PropId = PropId(1);
+    const MAX_KEY_BYTES: u16 = 16;
+
+    fn long(suffix: &str) -> String {
+        format!("{}{suffix}", "shared-prefix-".repeat(4))
+    }
+
+    fn check_truncated(kind: IndexKind) -> Result<()> {
+        let (_tmpdir, pager, graph) = open_graph(|_| {});
+        let mut write = pager.begin_write()?;
+        graph.create_property_index(
+            &mut write,
+            IndexDef {
+                label: DOC,
+                props: vec![BODY],
+                kind,
+                types: vec![TypeTag::String],
+                max_key_bytes: Some(MAX_KEY_BYTES),
+                unique: false,
+                state: IndexState::Ready,
+            },
+        )?;
+        let values = [
+            long("alpha"),
+            long("bravo"),
+            "short".to_string(),
+            long("alpha"),
+        ];
+        let mut nodes: Vec<NodeId> = Vec::new();
+        for value in &values {
+            nodes.push(graph.create_node(
+                &mut write,
+                NodeSpec {
+                    labels: &[DOC],
+                    props: &[PropEntry::new(BODY, PropValue::Str(value))],
+                },
+            )?);
+        }
+        pager.commit(write)?;
+
+        let read = pager.begin_latest_committed_read()?;
+        let eq = |value: &str| {
+            graph.property_scan_eq(&read, DOC, BODY, &PropValueOwned::Str(value.into()))
+        };
+        assert_eq!(eq(&long("alpha"))?, vec![nodes[0], nodes[3]]);
+        assert_eq!(eq(&long("bravo"))?, vec![nodes[1]]);
+        assert_eq!(eq("short")?, vec![nodes[2]]);
+        assert!(eq(&long("delta"))?.is_empty());
+
+        let mut in_range = graph.property_scan_range(
+            &read,
+            DOC,
+            BODY,
+            &PropValueOwned::Str(long("alpha")),
+            &PropValueOwned::Str(long("alpha")),
+        )?;
+        in_range.sort();
+        assert_eq!(in_range, vec![nodes[0], nodes[3]]);
+
+        let mut key_lens = Vec::new();
+        graph.scan_index(&read, DOC, BODY, |key, _| {
+            key_lens.push(key.len());
+            ControlFlow::Continue(())
+        })?;
+        assert_eq!(key_lens.len(), values.len());
+        assert!(key_lens
+            .iter()
+            .all(|len| *len <= 4 + usize::from(MAX_KEY_BYTES) + 8));
+        drop(read);
+
+        let def = graph.property_index(DOC, BODY)?.expect("index definition");
+        assert_eq!(def.max_key_bytes, Some(MAX_KEY_BYTES));
+        Ok(())
+    }
+
+    #[test]
+    fn truncated_btree_keys_keep_equality_exact() -> Result<()> {
+        check_truncated(IndexKind::BTree)
+    }
+
+    #[test]
+    fn truncated_chunked_keys_keep_equality_exact() -> Result<()> {
+        check_truncated(IndexKind::Chunked)
+    }
+
+    struct LoadedUsers {
+        by_age: Vec<Vec<NodeId>>,
+        users: Vec<NodeId>,
+        index_writes: u64,
+    }
+
+    fn load_users(suspend: bool) -> Result<LoadedUsers> {
+        let metrics = Arc::new(CounterMetrics::default());
+        let (_tmpdir, pager, graph) = open_graph(|opts| opts.metrics = Some(metrics.clone()));
+
+        let mut write = pager.begin_write()?;
+        graph.create_label_index(&mut write, USER)?;
+        graph.create_property_index(
+            &mut write,
+            IndexDef {
+                label: USER,
+                props: vec![AGE],
+                kind: IndexKind::BTree,
+                types: vec![TypeTag::Int],
+                max_key_bytes: None,
+                unique: false,
+                state: IndexState::Ready,
+            },
+        )?;
+        pager.commit(write)?;
+
+        let mut write = pager.begin_write()?;
+        let suspended = if suspend {
+            graph.suspend_indexes(&mut write, &[USER])?
+        } else {
+            Default::default()
+        };
+        pager.commit(write)?;
+
+        let mut write = pager.begin_write()?;
+        for i in 0..30i64 {
+            graph.create_node(
+                &mut write,
+                NodeSpec {
+                    labels: &[USER],
+                    props: &[PropEntry::new(AGE, PropValue::Int(i % 4))],
+                },
+            )?;
+        }
+        pager.commit(write)?;
+        let index_writes = metrics.property_index_writes.load(Ordering::Relaxed);
+
+        let mut write = pager.begin_write()?;
+        graph.restore_indexes(&mut write, suspended)?;
+        pager.commit(write)?;
+
+        let read = pager.begin_latest_committed_read()?;
+        let mut by_age = Vec::new();
+        for age in 0..4 {
+            by_age.push(graph.property_scan_eq(&read, USER, AGE, &PropValueOwned::Int(age))?);
+        }
+        let users = graph.nodes_with_label(&read, USER)?;
+        Ok(LoadedUsers {
+            by_age,
+            users,
+            index_writes,
+        })
+    }
+
+    #[test]
+    fn suspended_load_matches_maintained_load() -> Result<()> {
+        let maintained = load_users(false)?;
+        let suspended = load_users(true)?;
+        assert_eq!(suspended.by_age, maintained.by_age);
+        assert_eq!(suspended.users, maintained.users);
+        assert_eq!(suspended.users.len(), 30);
+        assert_eq!(maintained.index_writes, 30);
+        assert_eq!(suspended.index_writes, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn unrestored_suspension_is_rebuilt_by_finish_index_builds() -> Result<()> {
+        let (_tmpdir, pager, graph) = open_graph(|_| {});
+        let def = IndexDef {
+            label: USER,
+            props: vec![AGE],
+            kind: IndexKind::BTree,
+            types: vec![TypeTag::Int],
+            max_key_bytes: None,
+            unique: false,
+            state: IndexState::Ready,
+        };
+        let mut write = pager.begin_write()?;
+        graph.create_property_index(&mut write, def)?;
+        let suspended = graph.suspend_indexes(&mut write, &[USER])?;
+        assert_eq!(suspended.properties.len(), 1);
+        for i in 0..6i64 {
+            graph.create_node(
+                &mut write,
+                NodeSpec {
+                    labels: &[USER],
+                    props: &[PropEntry::new(AGE, PropValue::Int(i % 2))],
+                },
+            )?;
+        }
+        pager.commit(write)?;
+        let def = graph.property_index(USER, AGE)?.expect("definition kept");
+        assert_eq!(def.state, IndexState::Building);
+
+        let mut write = pager.begin_write()?;
+        assert_eq!(graph.finish_index_builds(&mut write)?, 1);
+        pager.commit(write)?;
+        let def = graph.property_index(USER, AGE)?.expect("definition kept");
+        assert_eq!(def.state, IndexState::Ready);
+        let read = pager.begin_latest_committed_read()?;
+        let odd = graph.property_scan_eq(&read, USER, AGE, &PropValueOwned::Int(1))?;
+        assert_eq!(odd.len(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn unique_indexes_stay_live_while_suspended() -> Result<()> {
+        let (_tmpdir, pager, graph) = open_graph(|_| {});
+        let def = IndexDef {
+            label: USER,
+            props: vec![AGE],
+            kind: IndexKind::BTree,
+            types: vec![TypeTag::Int],
+            max_key_bytes: None,
+            unique: true,
+            state: IndexState::Ready,
+        };
+        let mut write = pager.begin_write()?;
+        graph.create_property_index(&mut write, def)?;
+        let suspended = graph.suspend_indexes(&mut write, &[USER])?;
+        assert!(suspended.is_empty());
+        let spec = NodeSpec {
+            labels: &[USER],
+            props: &[PropEntry::new(AGE, PropValue::Int(7))],
+        };
+        graph.create_node(&mut write, spec.clone())?;
+        let err = graph.create_node(&mut write, spec).unwrap_err();
+        assert!(matches!(err, SombraError::ConstraintViolation(_)));
+        graph.restore_indexes(&mut write, suspended)?;
+        pager.commit(write)?;
+
+        let def = graph.property_index(USER, AGE)?.expect("definition kept");
+        assert_eq!(def.state, IndexState::Ready);
+        assert!(def.unique);
+        let read = pager.begin_latest_committed_read()?;
+        let hits = graph.property_scan_eq(&read, USER, AGE, &PropValueOwned::Int(7))?;
+        assert_eq!(hits.len(), 1);
+        Ok(())
+    }
+
+    fn create_node_with(
+        pager: &Pager,
+        graph: &Graph,
+        labels: &[LabelId],
+        props: &[PropEntry<'_>],
+    ) -> Result<NodeId> {
+        let mut write = pager.begin_write()?;
+        let node = graph.create_node(&mut write, NodeSpec { labels, props })?;
+        pager.commit(write)?;
+        Ok(node)
+    }
+
+    fn expect_missing<T: std::fmt::Debug>(result: Result<T>) {
+        match result {
+            Err(SombraError::InvalidOwned(msg)) => {
+                assert!(msg.contains("require"), "unexpected message {msg:?}")
+            }
+            other => panic!("expected a missing required property error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn required_prop_rejects_nodes_without_it() -> Result<()> {
+        let (_dir, pager, graph) = open_graph(|_| {});
+        let mut write = pager.begin_write()?;
+        assert!(graph.create_required_prop(&mut write, USER, EMAIL)?);
+        assert!(!graph.create_required_prop(&mut write, USER, EMAIL)?);
+        pager.commit(write)?;
+        assert_eq!(graph.required_props()?, vec![(USER, EMAIL)]);
+
+        let name = [PropEntry::new(NAME, PropValue::Str("Ada"))];
+        expect_missing(create_node_with(&pager, &graph, &[USER], &name));
+        let null_email = [PropEntry::new(EMAIL, PropValue::Null)];
+        expect_missing(create_node_with(&pager, &graph, &[USER], &null_email));
+        create_node_with(&pager, &graph, &[GUEST], &name)?;
+
+        let email = [PropEntry::new(EMAIL, PropValue::Str("ada@example.com"))];
+        let user = create_node_with(&pager, &graph, &[USER], &email)?;
+        let read = pager.begin_latest_committed_read()?;
+        assert_eq!(graph.nodes_with_label(&read, USER)?, vec![user]);
+        Ok(())
+    }
+
+    #[test]
+    fn required_prop_blocks_removal_and_relabeling() -> Result<()> {
+        let (_dir, pager, graph) = open_graph(|_| {});
+        let email = [PropEntry::new(EMAIL, PropValue::Str("ada@example.com"))];
+        let user = create_node_with(&pager, &graph, &[USER], &email)?;
+        let guest = create_node_with(&pager, &graph, &[GUEST], &[])?;
+        let mut write = pager.begin_write()?;
+        graph.create_required_prop(&mut write, USER, EMAIL)?;
+        pager.commit(write)?;
+
+        let mut write = pager.begin_write()?;
+        expect_missing(graph.update_node(
+            &mut write,
+            user,
+            PropPatch::new(vec![PropPatchOp::Delete(EMAIL)]),
+        ));
+        expect_missing(graph.update_node(
+            &mut write,
+            user,
+            PropPatch::new(vec![PropPatchOp::Set(EMAIL, PropValue::Null)]),
+        ));
+        expect_missing(graph.add_label_where(&mut write, &NodeFilter::Label(GUEST), USER));
+        graph.update_node(
+            &mut write,
+            user,
+            PropPatch::new(vec![PropPatchOp::Set(NAME, PropValue::Str("Ada"))]),
+        )?;
+        pager.commit(write)?;
+
+        let mut write = pager.begin_write()?;
+        assert!(graph.drop_required_prop(&mut write, USER, EMAIL)?);
+        graph.update_node(
+            &mut write,
+            user,
+            PropPatch::new(vec![PropPatchOp::Delete(EMAIL)]),
+        )?;
+        assert_eq!(
+            graph.add_label_where(&mut write, &NodeFilter::Label(GUEST), USER)?,
+            1
+        );
+        pager.commit(write)?;
+        assert!(graph.required_props()?.is_empty());
+        let read = pager.begin_latest_committed_read()?;
+        assert_eq!(graph.nodes_with_label(&read, USER)?, vec![user, guest]);
+        Ok(())
+    }
+
+    #[test]
+    fn required_prop_validates_existing_nodes() -> Result<()> {
+        let (_dir, pager, graph) = open_graph(|_| {});
+        create_node_with(&pager, &graph, &[USER], &[])?;
+        let mut write = pager.begin_write()?;
+        graph.create_property_index(
+            &mut write,
+            IndexDef {
+                label: USER,
+                props: vec![NAME],
+                kind: IndexKind::BTree,
+                types: vec![TypeTag::String],
+                max_key_bytes: None,
+                unique: false,
+                state: IndexState::Ready,
+            },
+        )?;
+        expect_missing(graph.create_required_prop(&mut write, USER, EMAIL));
+        graph.create_required_prop(&mut write, GUEST, NAME)?;
+        pager.commit(write)?;
+        // Constraints share the catalog with index definitions without
+        // showing up as indexes.
+        assert_eq!(graph.all_property_indexes()?.len(), 1);
+        assert_eq!(graph.required_props()?, vec![(GUEST, NAME)]);
+        Ok(())
+    }
+
+    fn unique_def(kind: IndexKind) -> IndexDef {
+        IndexDef {
+            label: USER,
+            props: vec![EMAIL],
+            kind,
+            types: vec![TypeTag::String],
+            max_key_bytes: None,
+            unique: true,
+            state: IndexState::Ready,
+        }
+    }
+
+    fn flush_and_commit(pager: &Pager, graph: &Graph, mut write: WriteGuard<'_>) -> Result<()> {
+        graph.flush_deferred_writes(&mut write)?;
+        pager.commit(write)?;
+        Ok(())
+    }
+
+    fn create_user(
+        pager: &Pager,
+        graph: &Graph,
+        labels: &[LabelId],
+        email: &str,
+    ) -> Result<NodeId> {
+        let mut write = pager.begin_write()?;
+        let props = [PropEntry::new(EMAIL, PropValue::Str(email))];
+        let node = graph.create_node(
+            &mut write,
+            NodeSpec {
+                labels,
+                props: &props,
+            },
+        )?;
+        flush_and_commit(pager, graph, write)?;
+        Ok(node)
+    }
+
+    fn set_email(pager: &Pager, graph: &Graph, node: NodeId, email: &str) -> Result<()> {
+        let mut write = pager.begin_write()?;
+        graph.update_node(
+            &mut write,
+            node,
+            PropPatch::new(vec![PropPatchOp::Set(EMAIL, PropValue::Str(email))]),
+        )?;
+        flush_and_commit(pager, graph, write)?;
+        Ok(())
+    }
+
+    fn expect_violation<T: std::fmt::Debug>(result: Result<T>) {
+        match result {
+            Err(SombraError::ConstraintViolation(msg)) => {
+                assert!(msg.contains("unique"), "unexpected message {msg:?}")
+            }
+            other => panic!("expected a constraint violation, got {other:?}"),
+        }
+    }
+
+    fn holders(pager: &Pager, graph: &Graph, email: &str) -> Result<Vec<NodeId>> {
+        let read = pager.begin_latest_committed_read()?;
+        let value = PropValueOwned::Str(email.to_owned());
+        let mut nodes = Vec::new();
+        for node in graph.property_scan_eq(&read, USER, EMAIL, &value)? {
+            let current = graph
+                .get_node(&read, node)?
+                .and_then(|data| data.props.into_iter().find(|(prop, _)| *prop == EMAIL))
+                .map(|(_, value)| value);
+            if current.as_ref() == Some(&value) {
+                nodes.push(node);
+            }
+        }
+        Ok(nodes)
+    }
+
+    fn unique_writes_reject_duplicates(kind: IndexKind, defer_index_flush: bool) -> Result<()> {
+        let (_dir, pager, graph) = open_graph(|opts| opts.defer_index_flush = defer_index_flush);
+        let mut write = pager.begin_write()?;
+        graph.create_property_index(&mut write, unique_def(kind))?;
+        flush_and_commit(&pager, &graph, write)?;
+        assert!(graph.property_index(USER, EMAIL)?.unwrap().unique);
+
+        let ada = create_user(&pager, &graph, &[USER], "ada@example.com")?;
+        let grace = create_user(&pager, &graph, &[USER], "grace@example.com")?;
+        expect_violation(create_user(&pager, &graph, &[USER], "ada@example.com"));
+        expect_violation(set_email(&pager, &graph, grace, "ada@example.com"));
+        // The constraint is scoped to the indexed label.
+        create_user(&pager, &graph, &[GUEST], "ada@example.com")?;
+
+        // A rejected insert leaves nothing behind once its transaction drops.
+        let read = pager.begin_latest_committed_read()?;
+        assert_eq!(graph.nodes_with_label(&read, USER)?, vec![ada, grace]);
+        drop(read);
+        assert_eq!(holders(&pager, &graph, "ada@example.com")?, vec![ada]);
+        assert_eq!(holders(&pager, &graph, "grace@example.com")?, vec![grace]);
+
+        // Values freed earlier in the same transaction can be reused.
+        let mut write = pager.begin_write()?;
+        graph.update_node(
+            &mut write,
+            ada,
+            PropPatch::new(vec![PropPatchOp::Set(EMAIL, PropValue::Str("ada@new.org"))]),
+        )?;
+        graph.update_node(
+            &mut write,
+            grace,
+            PropPatch::new(vec![PropPatchOp::Set(
+                EMAIL,
+                PropValue::Str("ada@example.com"),
+            )]),
+        )?;
+        expect_violation(graph.create_node(
+            &mut write,
+            NodeSpec {
+                labels: &[USER],
+                props: &[PropEntry::new(EMAIL, PropValue::Str("ada@new.org"))],
+            },
+        ));
+        flush_and_commit(&pager, &graph, write)?;
+        assert_eq!(holders(&pager, &graph, "ada@example.com")?, vec![grace]);
+        assert_eq!(holders(&pager, &graph, "ada@new.org")?, vec![ada]);
+
+        // Rewriting a node's own value and omitting the property are fine.
+        set_email(&pager, &graph, ada, "ada@new.org")?;
+        let mut write = pager.begin_write()?;
+        graph.create_node(
+            &mut write,
+            NodeSpec {
+                labels: &[USER],
+                props: &[],
+            },
+        )?;
+        flush_and_commit(&pager, &graph, write)?;
+        Ok(())
+    }
+
+    #[test]
+    fn unique_btree_index_rejects_duplicates() -> Result<()> {
+        unique_writes_reject_duplicates(IndexKind::BTree, false)
+    }
+
+    #[test]
+    fn unique_chunked_index_rejects_duplicates() -> Result<()> {
+        unique_writes_reject_duplicates(IndexKind::Chunked, false)
+    }
+
+    #[test]
+    fn unique_index_checks_deferred_postings() -> Result<()> {
+        unique_writes_reject_duplicates(IndexKind::BTree, true)
+    }
+
+    #[test]
+    fn unique_index_rejects_existing_duplicates() -> Result<()> {
+        let (_dir, pager, graph) = open_graph(|_| {});
+        create_user(&pager, &graph, &[USER], "ada@example.com")?;
+        create_user(&pager, &graph, &[USER], "ada@example.com")?;
+        let mut write = pager.begin_write()?;
+        expect_violation(graph.create_property_index(&mut write, unique_def(IndexKind::BTree)));
+        drop(write);
+        assert!(graph.property_index(USER, EMAIL)?.is_none());
+
+        let mut write = pager.begin_write()?;
+        let mut def = unique_def(IndexKind::BTree);
+        def.unique = false;
+        graph.create_property_index(&mut write, def)?;
+        flush_and_commit(&pager, &graph, write)?;
+        assert!(!graph.property_index(USER, EMAIL)?.unwrap().unique);
+        Ok(())
+    }
+
+    const CITY: PropId = PropId(1);
+
+    fn composite_def(kind: IndexKind) -> IndexDef {
+        IndexDef {
+            label: PERSON,
+            props: vec![CITY, AGE],
+            kind,
+            types: vec![TypeTag::String, TypeTag::Int],
+            max_key_bytes: None,
+            unique: false,
+            state: IndexState::Ready,
+        }
+    }
+
+    fn city(name: &str) -> PropValueOwned {
+        PropValueOwned::Str(name.to_owned())
+    }
+
+    fn create_person(pager: &Pager, graph: &Graph, name: &str, age: Option<i64>) -> Result<NodeId> {
+        let mut write = pager.begin_write()?;
+        let mut props = vec![PropEntry::new(CITY, PropValue::Str(name))];
+        if let Some(age) = age {
+            props.push(PropEntry::new(AGE, PropValue::Int(age)));
+        }
+        let node = graph.create_node(
+            &mut write,
+            NodeSpec {
+                labels: &[PERSON],
+                props: &props,
+            },
+        )?;
+        pager.commit(write)?;
+        Ok(node)
+    }
+
+    fn composite_scan_matches_prefixes(kind: IndexKind) -> Result<()> {
+        let (dir, pager, graph) = open_graph(|_| {});
+
+        let paris_30 = create_person(&pager, &graph, "paris", Some(30))?;
+        let mut write = pager.begin_write()?;
+        graph.create_property_index(&mut write, composite_def(kind))?;
+        pager.commit(write)?;
+        let paris_40 = create_person(&pager, &graph, "paris", Some(40))?;
+        let paris_50 = create_person(&pager, &graph, "paris", Some(50))?;
+        let rome_30 = create_person(&pager, &graph, "rome", Some(30))?;
+        let paris_unknown = create_person(&pager, &graph, "paris", None)?;
+
+        let read = pager.begin_latest_committed_read()?;
+        let eq = [city("paris"), PropValueOwned::Int(40)];
+        assert_eq!(
+            graph.composite_scan(&read, PERSON, CITY, &eq, None)?,
+            vec![paris_40]
+        );
+        assert_eq!(
+            graph.composite_scan(&read, PERSON, CITY, &[city("paris")], None)?,
+            vec![paris_30, paris_40, paris_50, paris_unknown]
+        );
+        let (low, high) = (PropValueOwned::Int(30), PropValueOwned::Int(50));
+        assert_eq!(
+            graph.composite_scan(
+                &read,
+                PERSON,
+                CITY,
+                &[city("paris")],
+                Some((Bound::Excluded(&low), Bound::Included(&high))),
+            )?,
+            vec![paris_40, paris_50]
+        );
+        assert_eq!(
+            graph.composite_scan(
+                &read,
+                PERSON,
+                CITY,
+                &[city("paris")],
+                Some((Bound::Unbounded, Bound::Excluded(&high))),
+            )?,
+            vec![paris_30, paris_40]
+        );
+        // Single-property lookups on the leading property use the same index.
+        assert_eq!(
+            graph.property_scan_eq(&read, PERSON, CITY, &city("rome"))?,
+            vec![rome_30]
+        );
+        assert!(graph
+            .composite_scan(
+                &read,
+                PERSON,
+                CITY,
+                &eq,
+                Some((Bound::Unbounded, Bound::Unbounded))
+            )
+            .is_err());
+        drop(read);
+
+        let mut write = pager.begin_write()?;
+        graph.update_node(
+            &mut write,
+            paris_40,
+            PropPatch::new(vec![PropPatchOp::Set(AGE, PropValue::Int(30))]),
+        )?;
+        graph.update_node(
+            &mut write,
+            paris_unknown,
+            PropPatch::new(vec![PropPatchOp::Set(AGE, PropValue::Int(40))]),
+        )?;
+        pager.commit(write)?;
+
+        let read = pager.begin_latest_committed_read()?;
+        assert_eq!(
+            graph.composite_scan(&read, PERSON, CITY, &eq, None)?,
+            vec![paris_unknown]
+        );
+        let eq_30 = [city("paris"), PropValueOwned::Int(30)];
+        assert_eq!(
+            graph.composite_scan(&read, PERSON, CITY, &eq_30, None)?,
+            vec![paris_30, paris_40]
+        );
+        drop(read);
+        drop(graph);
+        drop(pager);
+
+        let (_pager, graph) = open_graph_at(
+            &dir.path().join(GRAPH_FILE),
+            PagerOptions::default(),
+            |_| {},
+        );
+        assert_eq!(
+            graph.property_index(PERSON, CITY)?,
+            Some(composite_def(kind))
+        );
+        assert!(graph.property_index(PERSON, AGE)?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn composite_btree_index_scans_prefixes() -> Result<()> {
+        composite_scan_matches_prefixes(IndexKind::BTree)
+    }
+
+    #[test]
+    fn composite_chunked_index_scans_prefixes() -> Result<()> {
+        composite_scan_matches_prefixes(IndexKind::Chunked)
+    }
+
+    #[test]
+    fn unique_composite_index_compares_every_property() -> Result<()> {
+        let (_dir, pager, graph) = open_graph(|_| {});
+        let mut write = pager.begin_write()?;
+        let mut def = composite_def(IndexKind::BTree);
+        def.unique = true;
+        graph.create_property_index(&mut write, def)?;
+        pager.commit(write)?;
+
+        create_person(&pager, &graph, "paris", Some(30))?;
+        create_person(&pager, &graph, "paris", Some(40))?;
+        create_person(&pager, &graph, "rome", Some(30))?;
+        // Nodes missing a property are exempt, like null values.
+        create_person(&pager, &graph, "paris", None)?;
+        create_person(&pager, &graph, "paris", None)?;
+        match create_person(&pager, &graph, "paris", Some(30)) {
+            Err(SombraError::ConstraintViolation(_)) => {}
+            other => panic!("expected a constraint violation, got {other:?}"),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn composite_index_definitions_are_validated() -> Result<()> {
+        let (_dir, pager, graph) = open_graph(|_| {});
+        let mut write = pager.begin_write()?;
+        let mut repeated = composite_def(IndexKind::BTree);
+        repeated.props = vec![CITY, CITY];
+        assert!(graph.create_property_index(&mut write, repeated).is_err());
+        let mut untyped = composite_def(IndexKind::BTree);
+        untyped.types.pop();
+        assert!(graph.create_property_index(&mut write, untyped).is_err());
+        let mut truncated = composite_def(IndexKind::BTree);
+        truncated.max_key_bytes = Some(16);
+        assert!(graph.create_property_index(&mut write, truncated).is_err());
+        drop(write);
+        assert!(graph.property_index(PERSON, CITY)?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn conflicting_index_on_leading_property_is_rejected() -> Result<()> {
+        let (_dir, pager, graph) = open_graph(|_| {});
+        let mut write = pager.begin_write()?;
+        graph.create_property_index(&mut write, composite_def(IndexKind::BTree))?;
+        // Recreating the same index stays a no-op.
+        graph.create_property_index(&mut write, composite_def(IndexKind::BTree))?;
+        let mut single = composite_def(IndexKind::BTree);
+        single.props.truncate(1);
+        single.types.truncate(1);
+        assert!(matches!(
+            graph.create_property_index(&mut write, single.clone()),
+            Err(SombraError::Invalid(_))
+        ));
+        assert!(matches!(
+            graph.begin_property_index_build(&mut write, single),
+            Err(SombraError::Invalid(_))
+        ));
+        pager.commit(write)?;
+        let def = graph.property_index(PERSON, CITY)?.expect("index kept");
+        assert_eq!(def.props, vec![CITY, AGE]);
+        Ok(())
+    }
+
+    #[test]
+    fn excluded_bounds_reject_equal_values() -> Result<()> {
+        let five = PropValueOwned::Int(5);
+        let bound = Bound::Excluded(PropValueOwned::Int(5));
+        assert!(!Graph::bound_allows(&five, &bound, true)?);
+        assert!(!Graph::bound_allows(&five, &bound, false)?);
+        let inside = PropValueOwned::Int(4);
+        assert!(Graph::bound_allows(&inside, &bound, false)?);
+        assert!(!Graph::bound_allows(&inside, &bound, true)?);
+        Ok(())
+    }
+
+    const WORD: LabelId = LabelId(1);
+    const TEXT: PropId = PropId(1);
+    const COUNT: PropId = PropId(2);
+
+    fn string_def(kind: IndexKind, max_key_bytes: Option<u16>) -> IndexDef {
+        IndexDef {
+            label: WORD,
+            props: vec![TEXT],
+            kind,
+            types: vec![TypeTag::String],
+            max_key_bytes,
+            unique: false,
+            state: IndexState::Ready,
+        }
+    }
+
+    fn create_word(pager: &Pager, graph: &Graph, text: &str) -> Result<NodeId> {
+        let mut write = pager.begin_write()?;
+        let node = graph.create_node(
+            &mut write,
+            NodeSpec {
+                labels: &[WORD],
+                props: &[
+                    PropEntry::new(TEXT, PropValue::Str(text)),
+                    PropEntry::new(COUNT, PropValue::Int(1)),
+                ],
+            },
+        )?;
+        pager.commit(write)?;
+        Ok(node)
+    }
+
+    fn prefix_scan_spans_value_lengths(kind: IndexKind, max_key_bytes: Option<u16>) -> Result<()> {
+        let (_dir, pager, graph) = open_graph(|_| {});
+        let mut write = pager.begin_write()?;
+        graph.create_property_index(&mut write, string_def(kind, max_key_bytes))?;
+        pager.commit(write)?;
+
+        let long = "app".to_owned() + &"x".repeat(64);
+        let app = create_word(&pager, &graph, "app")?;
+        let apple = create_word(&pager, &graph, "apple")?;
+        let ap = create_word(&pager, &graph, "ap")?;
+        let application = create_word(&pager, &graph, "application")?;
+        let banana = create_word(&pager, &graph, "banana")?;
+        let long_app = create_word(&pager, &graph, &long)?;
+        let upper = create_word(&pager, &graph, "Apple")?;
+
+        let read = pager.begin_latest_committed_read()?;
+        assert_eq!(
+            graph.property_scan_prefix(&read, WORD, TEXT, "app")?,
+            vec![app, apple, application, long_app]
+        );
+        assert_eq!(
+            graph.property_scan_prefix(&read, WORD, TEXT, "appli")?,
+            vec![application]
+        );
+        assert_eq!(
+            graph.property_scan_prefix(&read, WORD, TEXT, &long)?,
+            vec![long_app]
+        );
+        assert_eq!(
+            graph.property_scan_prefix(&read, WORD, TEXT, "")?,
+            vec![app, apple, ap, application, banana, long_app, upper]
+        );
+        assert!(graph
+            .property_scan_prefix(&read, WORD, TEXT, "cherry")?
+            .is_empty());
+        drop(read);
+
+        let mut write = pager.begin_write()?;
+        graph.update_node(
+            &mut write,
+            apple,
+            PropPatch::new(vec![PropPatchOp::Set(TEXT, PropValue::Str("berry"))]),
+        )?;
+        pager.commit(write)?;
+        let read = pager.begin_latest_committed_read()?;
+        assert_eq!(
+            graph.property_scan_prefix(&read, WORD, TEXT, "app")?,
+            vec![app, application, long_app]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn btree_prefix_scan_spans_value_lengths() -> Result<()> {
+        prefix_scan_spans_value_lengths(IndexKind::BTree, None)
+    }
+
+    #[test]
+    fn chunked_prefix_scan_spans_value_lengths() -> Result<()> {
+        prefix_scan_spans_value_lengths(IndexKind::Chunked, None)
+    }
+
+    #[test]
+    fn truncated_prefix_scan_confirms_long_prefixes() -> Result<()> {
+        prefix_scan_spans_value_lengths(IndexKind::BTree, Some(4))
+    }
+
+    #[test]
+    fn prefix_scan_requires_string_index() -> Result<()> {
+        let (_dir, pager, graph) = open_graph(|_| {});
+        let mut write = pager.begin_write()?;
+        let mut def = string_def(IndexKind::BTree, None);
+        def.props = vec![COUNT];
+        def.types = vec![TypeTag::Int];
+        graph.create_property_index(&mut write, def)?;
+        pager.commit(write)?;
+        create_word(&pager, &graph, "app")?;
+
+        let read = pager.begin_latest_committed_read()?;
+        assert!(graph.property_scan_prefix(&read, WORD, COUNT, "1").is_err());
+        assert!(graph.property_scan_prefix(&read, WORD, TEXT, "a").is_err());
+        Ok(())
+    }
+}
+
+mod wal_recovery_tests {
+    use super::*;
+    use crate::primitives::pager::CheckpointMode;
+    use crate::storage::catalog::{Dict, DictOptions, NameKind};
+    use crate::storage::mvcc::VersionLogEntry;
+    use crate::storage::node;
+    use crate::storage::patch::{PropPatch, PropPatchOp};
+    use crate::storage::props;
+    use crate::storage::props::RawPropValue;
+    use crate::storage::{DeleteNodeOpts, EdgeSpec, NodeSpec, PropEntry, PropValue};
+    use crate::types::StrId;
+
+    #[test]
+    fn wal_recovery_restores_version_log_entries() -> Result<()> {
+        let (dir, pager, graph) = open_graph(|_| {});
+        let path = dir.path().join(GRAPH_FILE);
+
+        let mut write = pager.begin_write()?;
+        let node = graph.create_node(
+            &mut write,
+            NodeSpec {
+                labels: &[],
+                props: &[PropEntry::new(PropId(1), PropValue::Int(1))],
+            },
+        )?;
+        pager.commit(write)?;
+
+        let mut write = pager.begin_write()?;
+        graph.update_node(
+            &mut write,
+            node,
+            PropPatch::new(vec![PropPatchOp::Set(PropId(1), PropValue::Int(2))]),
+        )?;
+        pager.commit(write)?;
+        eprintln!("node updated");
+        pager.checkpoint(CheckpointMode::Force)?;
+        eprintln!("checkpoint after update");
+
+        drop(graph);
+        drop(pager);
+
+        let reopened_store: Arc<dyn PageStore> =
+            Arc::new(Pager::open(&path, PagerOptions::default()).unwrap());
+        let reopened_graph = Graph::open(GraphOptions::new(Arc::clone(&reopened_store))).unwrap();
+        let read = reopened_store.begin_latest_committed_read()?;
+
+        let mut cursor = reopened_graph
+            .nodes
+            .range(&read, Bound::Unbounded, Bound::Unbounded)?;
+        let (_node_key, head_bytes) = cursor.next()?.expect("node present after crash recovery");
+        let head = node::decode(&head_bytes)?;
+        let head_props = reopened_graph.read_node_prop_bytes(&head.row.props)?;
+        let head_raw = props::decode_raw(&head_props)?;
+        assert_eq!(head_raw.len(), 1);
+        match head_raw[0].value {
+            RawPropValue::Int(value) => assert_eq!(value, 2),
+            _ => panic!("unexpected property value {:?}", head_raw[0].value),
+        }
+
+        let prev_ptr = head.prev_ptr;
+        assert!(
+            !prev_ptr.is_null(),
+            "node update should log previous version"
+        );
+        let log_bytes = reopened_graph
+            .version_log
+            .get(&read, &prev_ptr.raw())?
+            .expect("version log entry missing after recovery");
+        let entry = VersionLogEntry::decode(&log_bytes)?;
+        let old_version = node::decode(&entry.bytes)?;
+        let old_props_bytes = reopened_graph.read_node_prop_bytes(&old_version.row.props)?;
+        let old_raw = props::decode_raw(&old_props_bytes)?;
+        assert_eq!(old_raw.len(), 1);
+        match old_raw[0].value {
+            RawPropValue::Int(value) => assert_eq!(value, 1),
+            _ => panic!("unexpected historical property {:?}", old_raw[0].value),
+        }
+
+        drop(read);
+        drop(reopened_graph);
+        drop(reopened_store);
+        Ok(())
+    }
+
+    fn wal_dir(path: &Path) -> std::path::PathBuf {
+        let mut name = path.as_os_str().to_owned();
+        name.push("-wal");
+        name.into()
+    }
+
+    fn copy_tree(src: &Path, dst: &Path) {
+        if src.is_dir() {
+            std::fs::create_dir_all(dst).unwrap();
+            for entry in std::fs::read_dir(src).unwrap() {
+                let entry = entry.unwrap();
+                copy_tree(&entry.path(), &dst.join(entry.file_name()));
+            }
+        } else {
+            std::fs::copy(src, dst).unwrap();
+        }
+    }
+
+    /// Flips a payload byte of the newest frame in the active WAL segment.
+    fn tear_last_wal_frame(wal: &Path, page_size: usize) {
+        let segment = std::fs::read_dir(wal.join("active"))
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .max()
+            .expect("active wal segment");
+        let mut bytes = std::fs::read(&segment).unwrap();
+        let frame_len = 32 + page_size;
+        let mut offset = 32;
+        let mut last = None;
+        while offset + frame_len <= bytes.len() {
+            let lsn = u64::from_be_bytes(bytes[offset..offset + 8].try_into().unwrap());
+            if lsn == 0 {
+                break;
+            }
+            last = Some(offset);
+            offset += frame_len;
+        }
+        let last = last.expect("wal holds frames");
+        bytes[last + 32 + page_size / 2] ^= 0xFF;
+        std::fs::write(&segment, bytes).unwrap();
+    }
+
+    #[test]
+    fn touched_page_replays_from_wal_after_torn_tail() -> Result<()> {
+        // Keep post-commit micro GC from logging extra pages between the touches.
+        let (dir, pager, graph) = open_graph(|opts| {
+            opts.vacuum = VacuumCfg {
+                enabled: false,
+                ..VacuumCfg::default()
+            }
+        });
+        let path = dir.path().join(GRAPH_FILE);
+        let crashed = dir.path().join("crashed.db");
+
+        let mut write = pager.begin_write()?;
+        let node = graph.create_node(
+            &mut write,
+            NodeSpec {
+                labels: &[],
+                props: &[PropEntry::new(PropId(1), PropValue::Int(7))],
+            },
+        )?;
+        pager.commit(write)?;
+        pager.checkpoint(CheckpointMode::Force)?;
+        let meta = pager.meta()?;
+        let page = meta.storage_nodes_root;
+        let original = {
+            let read = pager.begin_latest_committed_read()?;
+            let bytes = pager.get_page(&read, page)?.data().to_vec();
+            drop(read);
+            bytes
+        };
+
+        let mut write = pager.begin_write()?;
+        assert!(graph.touch_page(&mut write, PageId(0)).is_err());
+        assert!(graph.touch_page(&mut write, meta.next_page).is_err());
+        graph.touch_page(&mut write, page)?;
+        pager.commit(write)?;
+        let logged = pager
+            .wal_pages_since(meta.last_checkpoint_lsn)?
+            .expect("wal still covers the checkpoint");
+        assert_eq!(logged, vec![page]);
+
+        // The second touch is the commit the simulated crash tears.
+        let mut write = pager.begin_write()?;
+        graph.touch_page(&mut write, page)?;
+        pager.commit(write)?;
+        copy_tree(&path, &crashed);
+        copy_tree(&wal_dir(&path), &wal_dir(&crashed));
+        drop(graph);
+        drop(pager);
+        tear_last_wal_frame(&wal_dir(&crashed), meta.page_size as usize);
+
+        let reopened = Arc::new(Pager::open(&crashed, PagerOptions::default())?);
+        let read = reopened.begin_latest_committed_read()?;
+        assert_eq!(reopened.get_page(&read, page)?.data(), &original[..]);
+        drop(read);
+        let store: Arc<dyn PageStore> = reopened.clone();
+        let graph = Graph::open(GraphOptions::new(store)).unwrap();
+        let read = reopened.begin_latest_committed_read()?;
+        assert!(graph.get_node(&read, node)?.is_some());
+        Ok(())
+    }
+
+    const NODE_COUNT: i64 = 2_000;
+
+    fn checkpointed_nodes() -> (TempDir, Arc<Pager>, Arc<Graph>, Vec<NodeId>) {
+        let (dir, pager, graph) = open_graph(|_| {});
+        let mut write = pager.begin_write().unwrap();
+        let mut nodes = Vec::new();
+        for i in 0..NODE_COUNT {
+            let node = graph
+                .create_node(
+                    &mut write,
+                    NodeSpec {
+                        labels: &[LabelId(1)],
+                        props: &[PropEntry::new(PropId(1), PropValue::Int(i))],
+                    },
+                )
+                .unwrap();
+            nodes.push(node);
+        }
+        pager.commit(write).unwrap();
+        pager.checkpoint(CheckpointMode::Force).unwrap();
+        (dir, pager, graph, nodes)
+    }
+
+    fn bump(pager: &Pager, graph: &Graph, node: NodeId) -> Result<Lsn> {
+        let mut write = pager.begin_write()?;
+        graph.update_node(
+            &mut write,
+            node,
+            PropPatch::new(vec![PropPatchOp::Set(PropId(1), PropValue::Int(-1))]),
+        )?;
+        pager.commit(write)
+    }
+
+    #[test]
+    fn nodes_changed_since_reports_mutated_nodes() -> Result<()> {
+        let (_tmpdir, pager, graph, nodes) = checkpointed_nodes();
+        let since = pager.last_checkpoint_lsn();
+        assert!(graph.nodes_changed_since(since)?.is_empty());
+
+        let touched = [nodes[3], nodes[1_000]];
+        for node in touched {
+            bump(&pager, &graph, node)?;
+        }
+
+        let changed = graph.nodes_changed_since(since)?;
+        for node in touched {
+            assert!(changed.contains(&node), "missing mutated node {node:?}");
+        }
+        assert!(!changed.contains(nodes.last().unwrap()));
+        assert!(changed.len() < nodes.len());
+        Ok(())
+    }
+
+    #[test]
+    fn nodes_changed_since_only_sees_later_commits() -> Result<()> {
+        let (_tmpdir, pager, graph, nodes) = checkpointed_nodes();
+        let first_lsn = bump(&pager, &graph, nodes[0])?;
+        bump(&pager, &graph, nodes[1_999])?;
+
+        let changed = graph.nodes_changed_since(first_lsn)?;
+        assert!(changed.contains(&nodes[1_999]));
+        assert!(!changed.contains(&nodes[0]));
+        Ok(())
+    }
+
+    #[test]
+    fn nodes_changed_since_rejects_checkpointed_history() -> Result<()> {
+        let (_tmpdir, pager, graph, nodes) = checkpointed_nodes();
+        let before = pager.last_checkpoint_lsn();
+        bump(&pager, &graph, nodes[0])?;
+        pager.checkpoint(CheckpointMode::Force)?;
+        assert!(pager.last_checkpoint_lsn().0 > before.0);
+
+        match graph.nodes_changed_since(before) {
+            Err(SombraError::SnapshotTooOld(_)) => {}
+            other => panic!("expected SnapshotTooOld, got {other:?}"),
+        }
+        Ok(())
+    }
+
+    fn open_with_dict(
+        path: &Path,
+        opts: impl FnOnce(&mut GraphOptions),
+    ) -> (Arc<Pager>, Arc<Graph>, Dict) {
+        let (pager, graph) = open_graph_at(path, PagerOptions::default(), opts);
+        let store: Arc<dyn PageStore> = pager.clone();
+        let dict = Dict::open(store, DictOptions::default()).unwrap();
+        (pager, graph, dict)
+    }
+
+    fn intern(
+        pager: &Pager,
+        graph: &Graph,
+        dict: &Dict,
+        name: &str,
+        kind: NameKind,
+    ) -> Result<StrId> {
+        let mut write = pager.begin_write()?;
+        let id = dict.intern_as(&mut write, name, kind, graph.name_limit(kind))?;
+        pager.commit(write)?;
+        Ok(id)
+    }
+
+    fn expect_invalid<T: std::fmt::Debug>(result: Result<T>, expected: &str) {
+        match result {
+            Err(SombraError::Invalid(msg)) => assert_eq!(msg, expected),
+            other => panic!("expected Invalid({expected:?}), got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn label_limit_counts_distinct_labels_only() -> Result<()> {
+        let dir = tempdir().expect("tempdir");
+        let (pager, graph, dict) = open_with_dict(&dir.path().join(GRAPH_FILE), |opts| {
+            opts.max_distinct_labels = Some(2)
+        });
+
+        let person = intern(&pager, &graph, &dict, "Person", NameKind::Label)?;
+        intern(&pager, &graph, &dict, "Company", NameKind::Label)?;
+        for _ in 0..3 {
+            assert_eq!(
+                intern(&pager, &graph, &dict, "Person", NameKind::Label)?,
+                person
+            );
+        }
+        expect_invalid(
+            intern(&pager, &graph, &dict, "City", NameKind::Label),
+            "distinct label limit exceeded",
+        );
+        let read = pager.begin_latest_committed_read()?;
+        assert_eq!(dict.name_count(&read, NameKind::Label)?, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn roles_are_counted_separately() -> Result<()> {
+        let dir = tempdir().expect("tempdir");
+        let (pager, graph, dict) = open_with_dict(&dir.path().join(GRAPH_FILE), |opts| {
+            opts.max_distinct_types = Some(1);
+            opts.max_distinct_props = Some(1);
+        });
+
+        // The same string may serve as a label, a type, and a property key.
+        intern(&pager, &graph, &dict, "name", NameKind::Label)?;
+        intern(&pager, &graph, &dict, "name", NameKind::EdgeType)?;
+        intern(&pager, &graph, &dict, "name", NameKind::PropKey)?;
+        expect_invalid(
+            intern(&pager, &graph, &dict, "KNOWS", NameKind::EdgeType),
+            "distinct edge type limit exceeded",
+        );
+        expect_invalid(
+            intern(&pager, &graph, &dict, "age", NameKind::PropKey),
+            "distinct property key limit exceeded",
+        );
+        // Names rejected for one role stay usable for roles without a limit.
+        intern(&pager, &graph, &dict, "age", NameKind::Label)?;
+        Ok(())
+    }
+
+    #[test]
+    fn counts_persist_across_reopen() -> Result<()> {
+        let dir = tempdir().expect("tempdir");
+        let path = dir.path().join(GRAPH_FILE);
+        {
+            let (pager, graph, dict) = open_with_dict(&path, |_| {});
+            intern(&pager, &graph, &dict, "Person", NameKind::Label)?;
+            intern(&pager, &graph, &dict, "Company", NameKind::Label)?;
+            pager.checkpoint(CheckpointMode::Force)?;
+        }
+        let (pager, graph, dict) = open_with_dict(&path, |opts| opts.max_distinct_labels = Some(2));
+        intern(&pager, &graph, &dict, "Company", NameKind::Label)?;
+        expect_invalid(
+            intern(&pager, &graph, &dict, "City", NameKind::Label),
+            "distinct label limit exceeded",
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn backfill_counts_names_already_in_use() -> Result<()> {
+        let dir = tempdir().expect("tempdir");
+        let path = dir.path().join(GRAPH_FILE);
+        {
+            let (pager, graph, dict) = open_with_dict(&path, |_| {});
+            let mut write = pager.begin_write()?;
+            let person = dict.intern(&mut write, "Person")?;
+            let company = dict.intern(&mut write, "Company")?;
+            for label in [person, company] {
+                graph.create_node(
+                    &mut write,
+                    NodeSpec {
+                        labels: &[LabelId(label.0)],
+                        props: &[],
+                    },
+                )?;
+            }
+            // Forget the role records, as in a file written before they existed.
+            write.update_meta(|meta| meta.dict_name_kind_root = PageId(0))?;
+            pager.commit(write)?;
+            pager.checkpoint(CheckpointMode::Force)?;
+        }
+        let (pager, graph, dict) = open_with_dict(&path, |opts| opts.max_distinct_labels = Some(2));
+        assert!(!dict.name_kinds_complete()?);
+        let mut write = pager.begin_write()?;
+        let names = graph.schema_name_ids(&mut write)?;
+        dict.backfill_name_kinds(&mut write, &names)?;
+        pager.commit(write)?;
+        assert!(dict.name_kinds_complete()?);
+
+        let read = pager.begin_latest_committed_read()?;
+        assert_eq!(dict.name_count(&read, NameKind::Label)?, 2);
+        drop(read);
+        intern(&pager, &graph, &dict, "Person", NameKind::Label)?;
+        expect_invalid(
+            intern(&pager, &graph, &dict, "City", NameKind::Label),
+            "distinct label limit exceeded",
+        );
+        Ok(())
+    }
+
+    fn preimage_graph(retain: bool) -> (TempDir, Arc<Pager>, Arc<Graph>) {
+        let options = PagerOptions {
+            retain_page_preimages: retain,
+            ..PagerOptions::default()
+        };
+        open_graph_with(options, |_| {})
+    }
+
+    fn insert_node(graph: &Graph, write: &mut WriteGuard<'_>) -> Result<NodeId> {
+        graph.create_node(
+            write,
+            NodeSpec {
+                labels: &[LabelId(1)],
+                props: &[PropEntry::new(PropId(1), PropValue::Int(42))],
+            },
+        )
+    }
+
+    #[test]
+    fn changed_pages_reflect_node_insert() -> Result<()> {
+        let (_tmpdir, pager, graph) = preimage_graph(true);
+        let mut write = pager.begin_write()?;
+        assert!(write.changed_pages()?.is_empty());
+        insert_node(&graph, &mut write)?;
+
+        let changed = write.changed_pages()?;
+        assert!(!changed.is_empty());
+        assert!(changed.windows(2).all(|pair| pair[0].0 .0 < pair[1].0 .0));
+        let root = graph.nodes.root_page();
+        let (_, before, after) = changed
+            .iter()
+            .find(|(page, _, _)| *page == root)
+            .expect("nodes root modified by insert");
+        assert_ne!(before, after);
+        for (_, before, after) in &changed {
+            assert_eq!(before.len(), after.len());
+        }
+        pager.commit(write)?;
+
+        let write = pager.begin_write()?;
+        assert!(write.changed_pages()?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn changed_pages_include_unwritten_allocations() -> Result<()> {
+        let (_tmpdir, pager, _graph) = preimage_graph(true);
+        let mut write = pager.begin_write()?;
+        let page = write.allocate_page()?;
+        let changed = write.changed_pages()?;
+        let (_, before, after) = changed
+            .iter()
+            .find(|(id, _, _)| *id == page)
+            .expect("allocated page reported");
+        assert!(before.iter().all(|byte| *byte == 0));
+        assert_eq!(before.len(), after.len());
+
+        write.free_page(page)?;
+        assert!(write.changed_pages()?.iter().all(|(id, _, _)| *id != page));
+        Ok(())
+    }
+
+    #[test]
+    fn changed_pages_empty_without_retention() -> Result<()> {
+        let (_tmpdir, pager, graph) = preimage_graph(false);
+        let mut write = pager.begin_write()?;
+        insert_node(&graph, &mut write)?;
+        assert!(write.changed_pages()?.is_empty());
+        Ok(())
+    }
+
+    fn readahead_graph(path: &Path, readahead_pages: usize) -> (Arc<Pager>, Arc<Graph>) {
+        let options = PagerOptions {
+            readahead_pages,
+            ..PagerOptions::default()
+        };
+        open_graph_at(path, options, |_| {})
+    }
+
+    fn scan_misses(path: &Path, readahead_pages: usize) -> Result<(u64, u64, usize)> {
+        let (pager, graph) = readahead_graph(path, readahead_pages);
+        let read = pager.begin_latest_committed_read()?;
+        let before = pager.stats();
+        let rows = graph.scan_all_nodes(&read)?;
+        let after = pager.stats();
+        Ok((
+            after.misses - before.misses,
+            after.readahead_pages - before.readahead_pages,
+            rows.len(),
+        ))
+    }
+
+    #[test]
+    fn full_scan_misses_drop_with_readahead() -> Result<()> {
+        let dir = tempdir().expect("tempdir");
+        let path = dir.path().join(GRAPH_FILE);
+        {
+            let (pager, graph) = readahead_graph(&path, 0);
+            let mut write = pager.begin_write()?;
+            let name = "x".repeat(40);
+            for i in 0..2_000 {
+                let props = [
+                    PropEntry::new(PropId(1), PropValue::Int(i)),
+                    PropEntry::new(PropId(2), PropValue::Str(&name)),
+                ];
+                graph.create_node(
+                    &mut write,
+                    NodeSpec {
+                        labels: &[LabelId(1)],
+                        props: &props,
+                    },
+                )?;
+            }
+            pager.commit(write)?;
+            pager.checkpoint(CheckpointMode::Force)?;
+            drop(graph);
+        }
+
+        let (cold_misses, cold_prefetched, cold_rows) = scan_misses(&path, 0)?;
+        let (warm_misses, warm_prefetched, warm_rows) = scan_misses(&path, 16)?;
+        assert_eq!(cold_rows, 2_000);
+        assert_eq!(warm_rows, 2_000);
+        assert_eq!(cold_prefetched, 0);
+        assert!(warm_prefetched > 0);
+        assert!(
+            warm_misses * 2 < cold_misses,
+            "read-ahead misses {warm_misses} vs baseline {cold_misses}"
+        );
+        Ok(())
+    }
+
+    fn node_spec() -> NodeSpec<'static> {
+        NodeSpec {
+            labels: &[LabelId(1)],
+            props: &[],
+        }
+    }
+
+    #[test]
+    fn counts_survive_reopen_and_track_deletes() -> Result<()> {
+        let dir = tempdir().expect("tempdir");
+        let path = dir.path().join(GRAPH_FILE);
+        let (a, b, first_edge) = {
+            let (pager, graph) = open_graph_at(&path, PagerOptions::default(), |_| {});
+            assert_eq!((graph.node_count()?, graph.edge_count()?), (0, 0));
+            let mut write = pager.begin_write()?;
+            let mut nodes = Vec::new();
+            for _ in 0..5 {
+                nodes.push(graph.create_node(&mut write, node_spec())?);
+            }
+            let mut edges = Vec::new();
+            for pair in nodes.windows(2) {
+                edges.push(graph.create_edge(
+                    &mut write,
+                    EdgeSpec {
+                        src: pair[0],
+                        dst: pair[1],
+                        ty: TypeId(1),
+                        props: &[],
+                    },
+                )?);
+            }
+            pager.commit(write)?;
+            assert_eq!((graph.node_count()?, graph.edge_count()?), (5, 4));
+            pager.checkpoint(CheckpointMode::Force)?;
+            (nodes[3], nodes[4], edges[0])
+        };
+
+        let (pager, graph) = open_graph_at(&path, PagerOptions::default(), |_| {});
+        assert_eq!((graph.node_count()?, graph.edge_count()?), (5, 4));
+
+        let mut write = pager.begin_write()?;
+        graph.delete_edge(&mut write, first_edge)?;
+        // Cascading deletes also drop the edges incident to `a` and `b`.
+        graph.delete_node(&mut write, b, DeleteNodeOpts::cascade())?;
+        pager.commit(write)?;
+        let mut write = pager.begin_write()?;
+        graph.delete_node(&mut write, a, DeleteNodeOpts::cascade())?;
+        pager.commit(write)?;
+        assert_eq!((graph.node_count()?, graph.edge_count()?), (3, 1));
+        pager.checkpoint(CheckpointMode::Force)?;
+        drop(graph);
+        drop(pager);
+
+        let (_pager, graph) = open_graph_at(&path, PagerOptions::default(), |_| {});
+        assert_eq!((graph.node_count()?, graph.edge_count()?), (3, 1));
+        Ok(())
+    }
+
+    #[test]
+    fn rolled_back_writes_leave_counts_unchanged() -> Result<()> {
+        let (dir, pager, graph) = open_graph(|_| {});
+        let mut write = pager.begin_write()?;
+        let src = graph.create_node(&mut write, node_spec())?;
+        let dst = graph.create_node(&mut write, node_spec())?;
+        graph.create_edge(
+            &mut write,
+            EdgeSpec {
+                src,
+                dst,
+                ty: TypeId(1),
+                props: &[],
+            },
+        )?;
+        pager.commit(write)?;
+
+        let mut write = pager.begin_write()?;
+        graph.create_node(&mut write, node_spec())?;
+        graph.delete_node(&mut write, src, DeleteNodeOpts::cascade())?;
+        assert_eq!((graph.node_count()?, graph.edge_count()?), (2, 0));
+        drop(write);
+        assert_eq!((graph.node_count()?, graph.edge_count()?), (2, 1));
+
+        pager.checkpoint(CheckpointMode::Force)?;
+        drop(graph);
+        drop(pager);
+        let (_pager, graph) = open_graph_at(
+            &dir.path().join(GRAPH_FILE),
+            PagerOptions::default(),
+            |_| {},
+        );
+        assert_eq!((graph.node_count()?, graph.edge_count()?), (2, 1));
+        Ok(())
+    }
+
+    #[test]
+    fn counts_are_backfilled_once_for_older_files() -> Result<()> {
+        let dir = tempdir().expect("tempdir");
+        let path = dir.path().join(GRAPH_FILE);
+        {
+            let (pager, graph) = open_graph_at(&path, PagerOptions::default(), |_| {});
+            let mut write = pager.begin_write()?;
+            let src = graph.create_node(&mut write, node_spec())?;
+            let dst = graph.create_node(&mut write, node_spec())?;
+            let gone = graph.create_node(&mut write, node_spec())?;
+            graph.create_edge(
+                &mut write,
+                EdgeSpec {
+                    src,
+                    dst,
+                    ty: TypeId(1),
+                    props: &[],
+                },
+            )?;
+            pager.commit(write)?;
+            let mut write = pager.begin_write()?;
+            graph.delete_node(&mut write, gone, DeleteNodeOpts::cascade())?;
+            // Rewind the meta page to how a file from before the counters
+            // looks.
+            write.update_meta(|meta| {
+                meta.storage_flags &= !STORAGE_FLAG_LIVE_COUNTS;
+                meta.storage_node_count = 0;
+                meta.storage_edge_count = 0;
+            })?;
+            pager.commit(write)?;
+            pager.checkpoint(CheckpointMode::Force)?;
+        }
+
+        let (pager, graph) = open_graph_at(&path, PagerOptions::default(), |_| {});
+        assert_eq!((graph.node_count()?, graph.edge_count()?), (2, 1));
+        assert_ne!(pager.meta()?.storage_flags & STORAGE_FLAG_LIVE_COUNTS, 0);
         Ok(())
     }
 }
@@ -143,6 +143,32 @@ impl BTreePostings {
         Ok(out)
     }
 
+    /// Lists nodes posted under `prefix` as the writer currently sees them.
+    pub fn scan_eq_with_write(
+        &self,
+        tx: &mut WriteGuard<'_>,
+        prefix: &[u8],
+    ) -> Result<Vec<NodeId>> {
+        if self.root_page().0 == 0 {
+            return Ok(Vec::new());
+        }
+        self.ensure_tree_with_write(tx)?;
+        let tree_ref = self.tree.borrow();
+        let Some(tree) = tree_ref.as_ref() else {
+            return Err(SombraError::Corruption("btree postings tree missing"));
+        };
+        let lower = Self::make_key(prefix, NodeId(0));
+        let upper = Self::make_key(prefix, NodeId(u64::MAX));
+        let mut out = Vec::new();
+        tree.range_with_write(tx, &lower, &upper, |key, value| {
+            if (value.header.flags & mvcc_flags::TOMBSTONE) == 0 {
+                out.push(Self::parse_node_id(&key)?);
+            }
+            Ok(())
+        })?;
+        Ok(out)
+    }
+
     pub fn scan_range_bounds(
         &self,
         tx: &ReadGuard,
@@ -170,7 +196,7 @@ impl BTreePostings {
             {
                 continue;
             }
-            if key.len() < 8 {
+            if key.len() < 16 {
                 return Err(SombraError::Corruption("btree postings key too short"));
             }
             let mut key_label_bytes = [0u8; 4];
@@ -185,13 +211,47 @@ impl BTreePostings {
                 }
                 continue;
             }
-            let value_prefix = key[..key.len() - 8].to_vec();
+            let value_key = key[8..key.len() - 8].to_vec();
             let node = Self::parse_node_id(&key)?;
-            out.push((value_prefix, node));
+            out.push((value_key, node));
         }
         Ok(out)
     }
 
+    /// Returns the first value key at or after `start` with a visible posting.
+    pub fn first_value_key(
+        &self,
+        tx: &ReadGuard,
+        label: LabelId,
+        prop: PropId,
+        start: Bound<Vec<u8>>,
+    ) -> Result<Option<Vec<u8>>> {
+        if self.root_page().0 == 0 {
+            return Ok(None);
+        }
+        self.ensure_tree_read()?;
+        let tree_ref = self.tree.borrow();
+        let Some(tree) = tree_ref.as_ref() else {
+            return Err(SombraError::Corruption("btree postings tree missing"));
+        };
+        let lower = make_btree_lower_bound(label, prop, start);
+        let upper = make_btree_upper_bound(label, prop, Bound::Unbounded);
+        let snapshot = snapshot_commit(tx);
+        let mut cursor = tree.range(tx, lower, upper)?;
+        while let Some((key, value)) = cursor.next()? {
+            if !value.header.visible_at(snapshot)
+                || (value.header.flags & mvcc_flags::TOMBSTONE) != 0
+            {
+                continue;
+            }
+            if key.len() < 16 {
+                return Err(SombraError::Corruption("btree postings key too short"));
+            }
+            return Ok(Some(key[8..key.len() - 8].to_vec()));
+        }
+        Ok(None)
+    }
+
     pub fn stream_eq<'a>(
         &'a self,
         tx: &'a ReadGuard,
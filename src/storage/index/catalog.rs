@@ -5,9 +5,25 @@ use crate::primitives::pager::{PageStore, ReadGuard, WriteGuard};
 use crate::storage::btree::{BTree, BTreeOptions};
 use crate::types::{LabelId, PageId, PropId, Result, SombraError};
 
-use super::types::{IndexDef, IndexKind, TypeTag};
+use super::types::{IndexDef, IndexKind, IndexState, TypeTag, MAX_INDEX_PROPS};
 
-/// Persistent catalog of property index definitions.
+/// Trailing key byte marking a required-property constraint entry.
+///
+/// Constraint keys extend the 8-byte index key so both kinds of entry share
+/// the catalog tree without colliding.
+const REQUIRED_PROP_TAG: u8 = 1;
+const INDEX_KEY_LEN: usize = 8;
+const REQUIRED_KEY_LEN: usize = INDEX_KEY_LEN + 1;
+/// Length of the flagged payload: kind, type, key limit, state and flags.
+const FLAGGED_VALUE_LEN: usize = 6;
+/// Length of each trailing composite column: property id and type.
+const COLUMN_LEN: usize = 5;
+const FLAG_UNIQUE: u8 = 0x01;
+const FLAG_MAX_KEY_BYTES: u8 = 0x02;
+const FLAG_COMPOSITE: u8 = 0x04;
+
+/// Persistent catalog of property index definitions and required-property
+/// constraints.
 pub struct IndexCatalog {
     store: Arc<dyn PageStore>,
     tree: BTree<Vec<u8>, Vec<u8>>,
@@ -44,8 +60,56 @@ impl IndexCatalog {
         buf
     }
 
-    fn encode_value(kind: IndexKind, ty: TypeTag) -> Vec<u8> {
-        vec![Self::encode_kind(kind), Self::encode_type(ty)]
+    fn encode_required_key(label: LabelId, prop: PropId) -> Vec<u8> {
+        let mut buf = Self::encode_key(label, prop);
+        buf.push(REQUIRED_PROP_TAG);
+        buf
+    }
+
+    fn decode_key(key: &[u8]) -> (LabelId, PropId) {
+        let mut label_bytes = [0u8; 4];
+        label_bytes.copy_from_slice(&key[..4]);
+        let mut prop_bytes = [0u8; 4];
+        prop_bytes.copy_from_slice(&key[4..8]);
+        (
+            LabelId(u32::from_be_bytes(label_bytes)),
+            PropId(u32::from_be_bytes(prop_bytes)),
+        )
+    }
+
+    fn encode_value(def: &IndexDef) -> Vec<u8> {
+        let mut value = vec![Self::encode_kind(def.kind), Self::encode_type(def.ty())];
+        // Unique and composite indexes use a flagged payload, followed by the
+        // properties after the leading one; other indexes keep the shorter
+        // forms older catalogs understand.
+        if def.unique || def.is_composite() {
+            let mut flags = 0;
+            if def.unique {
+                flags |= FLAG_UNIQUE;
+            }
+            if def.max_key_bytes.is_some() {
+                flags |= FLAG_MAX_KEY_BYTES;
+            }
+            if def.is_composite() {
+                flags |= FLAG_COMPOSITE;
+            }
+            value.extend_from_slice(&def.max_key_bytes.unwrap_or(0).to_be_bytes());
+            value.push(u8::from(def.state == IndexState::Building));
+            value.push(flags);
+            for (prop, ty) in def.props.iter().zip(&def.types).skip(1) {
+                value.extend_from_slice(&prop.0.to_be_bytes());
+                value.push(Self::encode_type(*ty));
+            }
+            return value;
+        }
+        if let Some(max) = def.max_key_bytes {
+            value.extend_from_slice(&max.to_be_bytes());
+        }
+        // Ready indexes omit the state byte so older catalogs decode unchanged.
+        if def.state == IndexState::Building {
+            value.push(1);
+        }
+        value
     }
 
     fn encode_kind(kind: IndexKind) -> u8 {
@@ -90,15 +154,69 @@ impl IndexCatalog {
         }
     }
 
-    fn decode_value(bytes: &[u8]) -> Result<(IndexKind, TypeTag)> {
-        if bytes.len() != 2 {
+    fn decode_def(label: LabelId, prop: PropId, bytes: &[u8]) -> Result<IndexDef> {
+        if bytes.len() < 2 {
             return Err(SombraError::Corruption(
                 "property catalog payload length invalid",
             ));
         }
-        let kind = Self::decode_kind(bytes[0])?;
-        let ty = Self::decode_type(bytes[1])?;
-        Ok((kind, ty))
+        let mut props = vec![prop];
+        let mut types = vec![Self::decode_type(bytes[1])?];
+        let (max_key_bytes, state_byte, unique) = match bytes.len() {
+            2 => (None, None, false),
+            3 => (None, Some(bytes[2]), false),
+            4 => (Some(u16::from_be_bytes([bytes[2], bytes[3]])), None, false),
+            5 => (
+                Some(u16::from_be_bytes([bytes[2], bytes[3]])),
+                Some(bytes[4]),
+                false,
+            ),
+            len if len >= FLAGGED_VALUE_LEN => {
+                let flags = bytes[5];
+                if flags & !(FLAG_UNIQUE | FLAG_MAX_KEY_BYTES | FLAG_COMPOSITE) != 0 {
+                    return Err(SombraError::Corruption("unknown property index flags"));
+                }
+                let columns = &bytes[FLAGGED_VALUE_LEN..];
+                let composite = flags & FLAG_COMPOSITE != 0;
+                if composite == columns.is_empty()
+                    || columns.len() % COLUMN_LEN != 0
+                    || columns.len() / COLUMN_LEN >= MAX_INDEX_PROPS
+                {
+                    return Err(SombraError::Corruption(
+                        "property catalog payload length invalid",
+                    ));
+                }
+                for column in columns.chunks_exact(COLUMN_LEN) {
+                    props.push(PropId(u32::from_be_bytes([
+                        column[0], column[1], column[2], column[3],
+                    ])));
+                    types.push(Self::decode_type(column[4])?);
+                }
+                let max = (flags & FLAG_MAX_KEY_BYTES != 0)
+                    .then(|| u16::from_be_bytes([bytes[2], bytes[3]]));
+                let state = (bytes[4] != 0).then_some(bytes[4]);
+                (max, state, flags & FLAG_UNIQUE != 0)
+            }
+            _ => {
+                return Err(SombraError::Corruption(
+                    "property catalog payload length invalid",
+                ))
+            }
+        };
+        let state = match state_byte {
+            None => IndexState::Ready,
+            Some(1) => IndexState::Building,
+            Some(_) => return Err(SombraError::Corruption("unknown property index state")),
+        };
+        Ok(IndexDef {
+            label,
+            props,
+            kind: Self::decode_kind(bytes[0])?,
+            types,
+            max_key_bytes,
+            unique,
+            state,
+        })
     }
 
     /// Checks if a property index exists for the given label and property.
@@ -113,23 +231,17 @@ impl IndexCatalog {
         let Some(value) = self.tree.get(tx, &key)? else {
             return Ok(None);
         };
-        let (kind, ty) = Self::decode_value(&value)?;
-        Ok(Some(IndexDef {
-            label,
-            prop,
-            kind,
-            ty,
-        }))
+        Self::decode_def(label, prop, &value).map(Some)
     }
 
     /// Inserts a new property index definition into the catalog.
     /// Returns an error if an index for this label-property pair already exists.
     pub fn insert(&self, tx: &mut WriteGuard<'_>, def: IndexDef) -> Result<()> {
-        let key = Self::encode_key(def.label, def.prop);
+        let key = Self::encode_key(def.label, def.prop());
         if self.tree.get_with_write(tx, &key)?.is_some() {
             return Err(SombraError::Invalid("property index already exists"));
         }
-        let value = Self::encode_value(def.kind, def.ty);
+        let value = Self::encode_value(&def);
         self.tree.put(tx, &key, &value)
     }
 
@@ -153,19 +265,16 @@ impl IndexCatalog {
             .tree
             .range(tx, Bound::Included(lower), Bound::Included(upper))?;
         while let Some((key, value)) = cursor.next()? {
-            if key.len() != 8 {
+            if key.len() == REQUIRED_KEY_LEN {
+                continue;
+            }
+            if key.len() != INDEX_KEY_LEN {
                 return Err(SombraError::Corruption("catalog key length invalid"));
             }
             let mut prop_bytes = [0u8; 4];
             prop_bytes.copy_from_slice(&key[4..8]);
             let prop = PropId(u32::from_be_bytes(prop_bytes));
-            let (kind, ty) = Self::decode_value(&value)?;
-            results.push(IndexDef {
-                label,
-                prop,
-                kind,
-                ty,
-            });
+            results.push(Self::decode_def(label, prop, &value)?);
         }
         Ok(results)
     }
@@ -178,7 +287,10 @@ impl IndexCatalog {
     ) -> Result<Vec<IndexDef>> {
         let mut results = Vec::new();
         self.tree.for_each_with_write(tx, |key, value| {
-            if key.len() != 8 {
+            if key.len() == REQUIRED_KEY_LEN {
+                return Ok(());
+            }
+            if key.len() != INDEX_KEY_LEN {
                 return Err(SombraError::Corruption("catalog key length invalid"));
             }
             let mut label_bytes = [0u8; 4];
@@ -190,13 +302,29 @@ impl IndexCatalog {
             let mut prop_bytes = [0u8; 4];
             prop_bytes.copy_from_slice(&key[4..8]);
             let prop = PropId(u32::from_be_bytes(prop_bytes));
-            let (kind, ty) = Self::decode_value(&value)?;
-            results.push(IndexDef {
-                label,
-                prop,
-                kind,
-                ty,
-            });
+            results.push(Self::decode_def(label, prop, &value)?);
+            Ok(())
+        })?;
+        Ok(results)
+    }
+
+    /// Iterates over every property index definition using a write transaction.
+    pub fn iter_all_with_write(&self, tx: &mut WriteGuard<'_>) -> Result<Vec<IndexDef>> {
+        let mut results = Vec::new();
+        self.tree.for_each_with_write(tx, |key, value| {
+            if key.len() == REQUIRED_KEY_LEN {
+                return Ok(());
+            }
+            if key.len() != INDEX_KEY_LEN {
+                return Err(SombraError::Corruption("catalog key length invalid"));
+            }
+            let mut label_bytes = [0u8; 4];
+            label_bytes.copy_from_slice(&key[..4]);
+            let label = LabelId(u32::from_be_bytes(label_bytes));
+            let mut prop_bytes = [0u8; 4];
+            prop_bytes.copy_from_slice(&key[4..8]);
+            let prop = PropId(u32::from_be_bytes(prop_bytes));
+            results.push(Self::decode_def(label, prop, &value)?);
             Ok(())
         })?;
         Ok(results)
@@ -207,7 +335,10 @@ impl IndexCatalog {
         let mut cursor = self.tree.range(tx, Bound::Unbounded, Bound::Unbounded)?;
         let mut results = Vec::new();
         while let Some((key, value)) = cursor.next()? {
-            if key.len() != 8 {
+            if key.len() == REQUIRED_KEY_LEN {
+                continue;
+            }
+            if key.len() != INDEX_KEY_LEN {
                 return Err(SombraError::Corruption("catalog key length invalid"));
             }
             let mut label_bytes = [0u8; 4];
@@ -216,14 +347,83 @@ impl IndexCatalog {
             let mut prop_bytes = [0u8; 4];
             prop_bytes.copy_from_slice(&key[4..8]);
             let prop = PropId(u32::from_be_bytes(prop_bytes));
-            let (kind, ty) = Self::decode_value(&value)?;
-            results.push(IndexDef {
-                label,
-                prop,
-                kind,
-                ty,
-            });
+            results.push(Self::decode_def(label, prop, &value)?);
         }
         Ok(results)
     }
+
+    /// Records that nodes carrying `label` must set `prop`.
+    /// Returns false if the constraint was already registered.
+    pub fn insert_required_prop(
+        &self,
+        tx: &mut WriteGuard<'_>,
+        label: LabelId,
+        prop: PropId,
+    ) -> Result<bool> {
+        let key = Self::encode_required_key(label, prop);
+        if self.tree.get_with_write(tx, &key)?.is_some() {
+            return Ok(false);
+        }
+        self.tree.put(tx, &key, &Vec::new())?;
+        Ok(true)
+    }
+
+    /// Removes a required-property constraint.
+    /// Returns true if the constraint was found and removed, false otherwise.
+    pub fn remove_required_prop(
+        &self,
+        tx: &mut WriteGuard<'_>,
+        label: LabelId,
+        prop: PropId,
+    ) -> Result<bool> {
+        let key = Self::encode_required_key(label, prop);
+        self.tree.delete(tx, &key)
+    }
+
+    /// Lists the properties required on `label` using a write transaction.
+    pub fn required_props_with_write(
+        &self,
+        tx: &mut WriteGuard<'_>,
+        label: LabelId,
+    ) -> Result<Vec<PropId>> {
+        let mut results = Vec::new();
+        self.tree.for_each_with_write(tx, |key, _value| {
+            if key.len() != REQUIRED_KEY_LEN {
+                return Ok(());
+            }
+            let (entry_label, prop) = Self::decode_key(&key);
+            if entry_label == label {
+                results.push(prop);
+            }
+            Ok(())
+        })?;
+        Ok(results)
+    }
+
+    /// Iterates over every required-property constraint in the catalog.
+    pub fn iter_required_props(&self, tx: &ReadGuard) -> Result<Vec<(LabelId, PropId)>> {
+        let mut cursor = self.tree.range(tx, Bound::Unbounded, Bound::Unbounded)?;
+        let mut results = Vec::new();
+        while let Some((key, _value)) = cursor.next()? {
+            if key.len() == REQUIRED_KEY_LEN {
+                results.push(Self::decode_key(&key));
+            }
+        }
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_payload_is_corruption() {
+        for bytes in [&[][..], &[2][..]] {
+            assert!(matches!(
+                IndexCatalog::decode_def(LabelId(1), PropId(1), bytes),
+                Err(SombraError::Corruption(_))
+            ));
+        }
+    }
 }
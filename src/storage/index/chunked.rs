@@ -186,6 +186,30 @@ impl ChunkedIndex {
         Ok(out)
     }
 
+    /// Lists nodes posted under `prefix` as the writer currently sees them.
+    pub fn scan_with_write(&self, tx: &mut WriteGuard<'_>, prefix: &[u8]) -> Result<Vec<NodeId>> {
+        if self.root_page().0 == 0 {
+            return Ok(Vec::new());
+        }
+        self.ensure_tree_with_write(tx)?;
+        let tree_ref = self.tree.borrow();
+        let Some(tree) = tree_ref.as_ref() else {
+            return Err(SombraError::Corruption("chunked postings tree missing"));
+        };
+        let lower = Self::make_key(prefix, SEGMENT_PRIMARY);
+        let upper = Self::make_key(prefix, u32::MAX);
+        let mut out = Vec::new();
+        tree.range_with_write(tx, &lower, &upper, |_, bytes| {
+            if (bytes.header.flags & mvcc_flags::TOMBSTONE) == 0 {
+                out.extend(Segment::decode(&bytes.value)?.nodes.iter().copied());
+            }
+            Ok(())
+        })?;
+        out.sort_by_key(|node| node.0);
+        out.dedup_by_key(|node| node.0);
+        Ok(out)
+    }
+
     pub fn scan_range_bounds(
         &self,
         tx: &ReadGuard,
@@ -248,6 +272,40 @@ impl ChunkedIndex {
         Ok(out)
     }
 
+    /// Returns the first value key at or after `start` with a visible segment.
+    pub fn first_value_key(
+        &self,
+        tx: &ReadGuard,
+        label: LabelId,
+        prop: PropId,
+        start: Bound<Vec<u8>>,
+    ) -> Result<Option<Vec<u8>>> {
+        if self.root_page().0 == 0 {
+            return Ok(None);
+        }
+        self.ensure_tree_read()?;
+        let tree_ref = self.tree.borrow();
+        let Some(tree) = tree_ref.as_ref() else {
+            return Err(SombraError::Corruption("chunked postings tree missing"));
+        };
+        let lower = make_chunk_lower_bound(label, prop, start);
+        let upper = make_chunk_upper_bound(label, prop, Bound::Unbounded);
+        let snapshot = snapshot_commit(tx);
+        let mut cursor = tree.range(tx, lower, upper)?;
+        while let Some((key, bytes)) = cursor.next()? {
+            if !bytes.header.visible_at(snapshot)
+                || (bytes.header.flags & mvcc_flags::TOMBSTONE) != 0
+            {
+                continue;
+            }
+            if key.len() < 12 {
+                return Err(SombraError::Corruption("chunked postings key too short"));
+            }
+            return Ok(Some(key[8..key.len() - 4].to_vec()));
+        }
+        Ok(None)
+    }
+
     pub fn stream_eq<'a>(
         &'a self,
         tx: &'a ReadGuard,
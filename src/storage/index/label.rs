@@ -5,7 +5,7 @@ use std::sync::Arc;
 use crate::primitives::pager::{PageStore, ReadGuard, WriteGuard};
 use crate::storage::btree::{
     page::{self, BTreePageKind},
-    BTree, BTreeOptions, Cursor, PutItem, ValCodec,
+    BTree, BTreeOptions, Cursor, PutItem, RevCursor, ValCodec,
 };
 use crate::storage::{mvcc_flags, CommitId, VersionHeader, VersionedValue, COMMIT_MAX};
 use crate::types::{
@@ -336,7 +336,41 @@ impl LabelIndex {
             .range(tx, Bound::Included(lower), Bound::Included(upper))?;
         Ok(LabelScan {
             target_label: label,
-            cursor,
+            cursor: LabelCursor::Ascending(cursor),
+            snapshot,
+        })
+    }
+
+    /// Lists the nodes carrying `label` as the writer currently sees them.
+    pub fn scan_with_write(&self, tx: &mut WriteGuard<'_>, label: LabelId) -> Result<Vec<NodeId>> {
+        let (lower, upper) = label_bounds(label);
+        let mut nodes = Vec::new();
+        self.tree
+            .range_with_write(tx, &lower, &upper, |key, value| {
+                if value.header.end != COMMIT_MAX
+                    || (value.header.flags & mvcc_flags::TOMBSTONE) != 0
+                {
+                    return Ok(());
+                }
+                let (entry_label, node) = decode_key(&key)?;
+                if entry_label == label && node != LABEL_SENTINEL_NODE {
+                    nodes.push(node);
+                }
+                Ok(())
+            })?;
+        Ok(nodes)
+    }
+
+    /// Scans the nodes carrying `label` from the highest node id down.
+    pub fn scan_rev<'a>(&'a self, tx: &'a ReadGuard, label: LabelId) -> Result<LabelScan<'a>> {
+        let (lower, upper) = label_bounds(label);
+        let snapshot = snapshot_commit(tx);
+        let cursor = self
+            .tree
+            .range_rev(tx, Bound::Included(lower), Bound::Included(upper))?;
+        Ok(LabelScan {
+            target_label: label,
+            cursor: LabelCursor::Descending(cursor),
             snapshot,
         })
     }
@@ -402,10 +436,24 @@ fn prune_versioned_tree<V: ValCodec>(
 /// Iterator for scanning nodes with a specific label.
 pub struct LabelScan<'a> {
     target_label: LabelId,
-    cursor: Cursor<'a, Vec<u8>, VersionedValue<EmptyValue>>,
+    cursor: LabelCursor<'a>,
     snapshot: CommitId,
 }
 
+enum LabelCursor<'a> {
+    Ascending(Cursor<'a, Vec<u8>, VersionedValue<EmptyValue>>),
+    Descending(RevCursor<'a, Vec<u8>, VersionedValue<EmptyValue>>),
+}
+
+impl LabelCursor<'_> {
+    fn next(&mut self) -> Result<Option<(Vec<u8>, VersionedValue<EmptyValue>)>> {
+        match self {
+            LabelCursor::Ascending(cursor) => cursor.next(),
+            LabelCursor::Descending(cursor) => cursor.next(),
+        }
+    }
+}
+
 impl<'a> LabelScan<'a> {
     /// Retrieves the next node ID matching the target label, if available.
     pub fn next(&mut self) -> Result<Option<NodeId>> {
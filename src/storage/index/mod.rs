@@ -15,6 +15,6 @@ pub use epoch::{CatalogEpoch, DdlEpoch};
 pub use label::{LabelScan, LABEL_SENTINEL_NODE};
 pub use store::{IndexRoots, IndexStore, IndexVacuumStats};
 pub use types::{
-    collect_all, intersect_k, intersect_sorted, IndexDef, IndexKind, PostingStream, TypeTag,
-    VecPostingStream,
+    collect_all, intersect_k, intersect_sorted, IndexDef, IndexKind, IndexState, PostingStream,
+    TypeTag, VecPostingStream,
 };
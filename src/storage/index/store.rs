@@ -115,6 +115,14 @@ impl IndexStore {
         self.catalog.iter_all(tx)
     }
 
+    /// Returns every property index definition using a write transaction.
+    pub fn all_property_indexes_with_write(
+        &self,
+        tx: &mut WriteGuard<'_>,
+    ) -> Result<Vec<IndexDef>> {
+        self.catalog.iter_all_with_write(tx)
+    }
+
     /// Checks if a label index exists for the given label.
     pub fn has_label_index(&self, label: LabelId) -> Result<bool> {
         self.label_index.is_indexed_read(label)
@@ -221,6 +229,33 @@ impl IndexStore {
         Ok(Some(scan))
     }
 
+    /// Lists the nodes carrying `label` as the writer currently sees them, or
+    /// `None` when `label` has no label index.
+    pub fn label_nodes_with_write(
+        &self,
+        tx: &mut WriteGuard<'_>,
+        label: LabelId,
+    ) -> Result<Option<Vec<NodeId>>> {
+        if !self.label_index.is_indexed_with_write(tx, label)? {
+            return Ok(None);
+        }
+        self.label_index.scan_with_write(tx, label).map(Some)
+    }
+
+    /// Returns a label scan that yields node ids from highest to lowest, or
+    /// `None` when `label` has no label index.
+    pub fn label_scan_rev<'a>(
+        &'a self,
+        tx: &'a ReadGuard,
+        label: LabelId,
+    ) -> Result<Option<LabelScan<'a>>> {
+        if !self.label_index.is_indexed_read(label)? {
+            return Ok(None);
+        }
+        let scan = self.label_index.scan_rev(tx, label)?;
+        Ok(Some(scan))
+    }
+
     /// Retrieves the property index definition for a label and property.
     pub fn get_property_index(
         &self,
@@ -256,7 +291,7 @@ impl IndexStore {
         def: IndexDef,
         existing: &[(Vec<u8>, NodeId)],
     ) -> Result<()> {
-        self.catalog.insert(tx, def)?;
+        self.catalog.insert(tx, def.clone())?;
         match def.kind {
             IndexKind::Chunked => {
                 for (value_key, node) in existing {
@@ -281,7 +316,7 @@ impl IndexStore {
         }
         let mut keys: Vec<Vec<u8>> = Vec::with_capacity(existing.len());
         for (value_key, node) in existing {
-            let prefix = BTreePostings::make_prefix(def.label, def.prop, value_key);
+            let prefix = BTreePostings::make_prefix(def.label, def.prop(), value_key);
             keys.push(BTreePostings::make_key(&prefix, *node));
         }
         keys.sort();
@@ -294,7 +329,7 @@ impl IndexStore {
     /// Drops an existing property index and removes all entries.
     pub fn drop_property_index(&self, tx: &mut WriteGuard<'_>, def: IndexDef) -> Result<()> {
         self.drop_property_entries(tx, &def)?;
-        let removed = self.catalog.remove(tx, def.label, def.prop)?;
+        let removed = self.catalog.remove(tx, def.label, def.prop())?;
         if !removed {
             return Err(SombraError::Invalid("property index not found"));
         }
@@ -323,11 +358,11 @@ impl IndexStore {
     ) -> Result<()> {
         match def.kind {
             IndexKind::Chunked => {
-                let prefix = ChunkedIndex::make_prefix(def.label, def.prop, value_key);
+                let prefix = ChunkedIndex::make_prefix(def.label, def.prop(), value_key);
                 self.chunked.put_with_commit(tx, &prefix, node, commit)
             }
             IndexKind::BTree => {
-                let prefix = BTreePostings::make_prefix(def.label, def.prop, value_key);
+                let prefix = BTreePostings::make_prefix(def.label, def.prop(), value_key);
                 self.btree.put_with_commit(tx, &prefix, node, commit)
             }
         }
@@ -355,11 +390,11 @@ impl IndexStore {
     ) -> Result<()> {
         match def.kind {
             IndexKind::Chunked => {
-                let prefix = ChunkedIndex::make_prefix(def.label, def.prop, value_key);
+                let prefix = ChunkedIndex::make_prefix(def.label, def.prop(), value_key);
                 self.chunked.remove_with_commit(tx, &prefix, node, commit)
             }
             IndexKind::BTree => {
-                let prefix = BTreePostings::make_prefix(def.label, def.prop, value_key);
+                let prefix = BTreePostings::make_prefix(def.label, def.prop(), value_key);
                 self.btree.remove_with_commit(tx, &prefix, node, commit)
             }
         }
@@ -420,16 +455,36 @@ impl IndexStore {
     ) -> Result<Vec<NodeId>> {
         match def.kind {
             IndexKind::Chunked => {
-                let prefix = ChunkedIndex::make_prefix(def.label, def.prop, value_key);
+                let prefix = ChunkedIndex::make_prefix(def.label, def.prop(), value_key);
                 self.chunked.scan(tx, &prefix)
             }
             IndexKind::BTree => {
-                let prefix = BTreePostings::make_prefix(def.label, def.prop, value_key);
+                let prefix = BTreePostings::make_prefix(def.label, def.prop(), value_key);
                 self.btree.scan_eq(tx, &prefix)
             }
         }
     }
 
+    /// Scans for nodes with a specific property value, including writes
+    /// made earlier in `tx`.
+    pub fn scan_property_eq_with_write(
+        &self,
+        tx: &mut WriteGuard<'_>,
+        def: &IndexDef,
+        value_key: &[u8],
+    ) -> Result<Vec<NodeId>> {
+        match def.kind {
+            IndexKind::Chunked => {
+                let prefix = ChunkedIndex::make_prefix(def.label, def.prop(), value_key);
+                self.chunked.scan_with_write(tx, &prefix)
+            }
+            IndexKind::BTree => {
+                let prefix = BTreePostings::make_prefix(def.label, def.prop(), value_key);
+                self.btree.scan_eq_with_write(tx, &prefix)
+            }
+        }
+    }
+
     /// Scans for all nodes with property values in a given range.
     pub fn scan_property_range(
         &self,
@@ -439,12 +494,28 @@ impl IndexStore {
         end: Bound<Vec<u8>>,
     ) -> Result<Vec<(Vec<u8>, NodeId)>> {
         match def.kind {
-            IndexKind::Chunked => self
-                .chunked
-                .scan_range_bounds(tx, def.label, def.prop, start, end),
+            IndexKind::Chunked => {
+                self.chunked
+                    .scan_range_bounds(tx, def.label, def.prop(), start, end)
+            }
             IndexKind::BTree => self
                 .btree
-                .scan_range_bounds(tx, def.label, def.prop, start, end),
+                .scan_range_bounds(tx, def.label, def.prop(), start, end),
+        }
+    }
+
+    /// Returns the first value key at or after `start` that has a visible posting.
+    pub fn first_property_key(
+        &self,
+        tx: &ReadGuard,
+        def: &IndexDef,
+        start: Bound<Vec<u8>>,
+    ) -> Result<Option<Vec<u8>>> {
+        match def.kind {
+            IndexKind::Chunked => self
+                .chunked
+                .first_value_key(tx, def.label, def.prop(), start),
+            IndexKind::BTree => self.btree.first_value_key(tx, def.label, def.prop(), start),
         }
     }
 
@@ -456,8 +527,8 @@ impl IndexStore {
         value_key: &[u8],
     ) -> Result<Box<dyn PostingStream + 'a>> {
         match def.kind {
-            IndexKind::Chunked => self.chunked.stream_eq(tx, def.label, def.prop, value_key),
-            IndexKind::BTree => self.btree.stream_eq(tx, def.label, def.prop, value_key),
+            IndexKind::Chunked => self.chunked.stream_eq(tx, def.label, def.prop(), value_key),
+            IndexKind::BTree => self.btree.stream_eq(tx, def.label, def.prop(), value_key),
         }
     }
 
@@ -470,19 +541,21 @@ impl IndexStore {
         end: Bound<Vec<u8>>,
     ) -> Result<Box<dyn PostingStream + 'a>> {
         match def.kind {
-            IndexKind::Chunked => self
-                .chunked
-                .stream_range_bounds(tx, def.label, def.prop, start, end),
-            IndexKind::BTree => self
-                .btree
-                .stream_range_bounds(tx, def.label, def.prop, start, end),
+            IndexKind::Chunked => {
+                self.chunked
+                    .stream_range_bounds(tx, def.label, def.prop(), start, end)
+            }
+            IndexKind::BTree => {
+                self.btree
+                    .stream_range_bounds(tx, def.label, def.prop(), start, end)
+            }
         }
     }
 
     fn drop_property_entries(&self, tx: &mut WriteGuard<'_>, def: &IndexDef) -> Result<()> {
         match def.kind {
-            IndexKind::Chunked => self.drop_chunked_entries(tx, def.label, def.prop),
-            IndexKind::BTree => self.drop_btree_entries(tx, def.label, def.prop),
+            IndexKind::Chunked => self.drop_chunked_entries(tx, def.label, def.prop()),
+            IndexKind::BTree => self.drop_btree_entries(tx, def.label, def.prop()),
         }
     }
 
@@ -30,19 +30,100 @@ pub enum TypeTag {
     DateTime,
 }
 
+/// Build progress of a property index.
+///
+/// Writes only maintain a `Ready` index. A `Building` index misses nodes
+/// until it is backfilled, so the planner scans instead of consulting it.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum IndexState {
+    /// Backfill finished; the index covers every node.
+    #[default]
+    Ready,
+    /// Registered but not yet backfilled; entries may be missing.
+    Building,
+}
+
 /// Definition supplied when creating a property index.
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+///
+/// Composite indexes list several properties. Their keys concatenate the
+/// encoded value of each property in order, so equality on the leading
+/// properties narrows a lookup to one key prefix. A label holds at most one
+/// index per leading property, which identifies the index in the catalog.
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct IndexDef {
     /// The label this index applies to
     pub label: LabelId,
-    /// The property being indexed
-    pub prop: PropId,
+    /// The indexed properties in key order; single-property indexes hold one
+    pub props: Vec<PropId>,
     /// The storage backend implementation to use
     pub kind: IndexKind,
-    /// The expected type of property values
-    pub ty: TypeTag,
+    /// The expected type of each property in `props`, in the same order
+    pub types: Vec<TypeTag>,
+    /// Longest string or bytes payload stored verbatim in index keys.
+    ///
+    /// Longer values keep their first `max_key_bytes` bytes followed by a
+    /// digest of the full payload, so equality lookups still hit a single key
+    /// and are confirmed against the stored value. Range scans widen their
+    /// bounds to every key sharing a truncated prefix and filter candidates,
+    /// so they read more entries when many long values share a prefix.
+    /// `None` stores full keys. Composite indexes always store full keys.
+    pub max_key_bytes: Option<u16>,
+    /// Rejects writes that would give two nodes carrying `label` the same
+    /// non-null values for every property in `props`.
+    pub unique: bool,
+    /// Whether the index is complete enough to answer queries.
+    pub state: IndexState,
+}
+
+impl IndexDef {
+    /// Returns the leading property, which identifies the index on its label.
+    pub fn prop(&self) -> PropId {
+        self.props[0]
+    }
+
+    /// Returns the expected type of the leading property.
+    pub fn ty(&self) -> TypeTag {
+        self.types[0]
+    }
+
+    /// Returns whether the index covers more than one property.
+    pub fn is_composite(&self) -> bool {
+        self.props.len() > 1
+    }
+
+    /// Checks that the definition names each property once, with one type
+    /// per property.
+    pub fn validate(&self) -> Result<()> {
+        if self.props.is_empty() {
+            return Err(SombraError::Invalid("property index needs a property"));
+        }
+        if self.props.len() != self.types.len() {
+            return Err(SombraError::Invalid(
+                "property index needs one type per property",
+            ));
+        }
+        if self.props.len() > MAX_INDEX_PROPS {
+            return Err(SombraError::Invalid(
+                "composite index has too many properties",
+            ));
+        }
+        for (idx, prop) in self.props.iter().enumerate() {
+            if self.props[..idx].contains(prop) {
+                return Err(SombraError::Invalid("composite index repeats a property"));
+            }
+        }
+        if self.is_composite() && self.max_key_bytes.is_some() {
+            return Err(SombraError::Invalid(
+                "composite indexes cannot truncate keys",
+            ));
+        }
+        Ok(())
+    }
 }
 
+/// Most properties a single composite index may cover.
+pub(crate) const MAX_INDEX_PROPS: usize = 8;
+
 /// Streaming interface over sorted, unique `NodeId`s.
 pub trait PostingStream {
     /// Pushes up to `max` identifiers into `out`, returning `true` when additional
@@ -0,0 +1,209 @@
+//! Parsing of textual property literals, such as CSV cells, into typed
+//! property values.
+//!
+//! Shared by the CLI importer and [`crate::ffi::Database::import_csv`] so both
+//! coerce cells the same way.
+
+use thiserror::Error;
+use time::format_description::{well_known::Rfc3339, FormatItem};
+use time::macros::format_description;
+use time::{Date, Month, OffsetDateTime, PrimitiveDateTime};
+
+use super::types::PropValueOwned;
+
+const DATE_FMT: &[FormatItem<'static>] = format_description!("[year]-[month]-[day]");
+const DATETIME_FMT_T: &[FormatItem<'static>] =
+    format_description!("[year]-[month]-[day]T[hour]:[minute]:[second]");
+const DATETIME_FMT_T_FRAC: &[FormatItem<'static>] =
+    format_description!("[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond]");
+const DATETIME_FMT_SPACE: &[FormatItem<'static>] =
+    format_description!("[year]-[month]-[day] [hour]:[minute]:[second]");
+const DATETIME_FMT_SPACE_FRAC: &[FormatItem<'static>] =
+    format_description!("[year]-[month]-[day] [hour]:[minute]:[second].[subsecond]");
+
+/// Supported property type coercions for CSV cells.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PropertyType {
+    /// Let the importer infer the type from literals (default).
+    Auto,
+    /// Boolean literal (`true`/`false`).
+    Bool,
+    /// 64-bit signed integer.
+    Int,
+    /// 64-bit floating point value.
+    Float,
+    /// UTF-8 string literal (disables heuristics).
+    String,
+    /// Calendar date (epoch days or `YYYY-MM-DD`).
+    Date,
+    /// Timestamp (epoch milliseconds or RFC3339).
+    DateTime,
+    /// `0x`-prefixed hex blob.
+    Bytes,
+}
+
+impl Default for PropertyType {
+    fn default() -> Self {
+        Self::Auto
+    }
+}
+
+/// A literal that does not parse as the requested [`PropertyType`].
+#[derive(Debug, Error)]
+#[error("{0}")]
+pub struct LiteralError(String);
+
+/// Parses one CSV cell with the given coercion; `null` (except for strings)
+/// yields `None`.
+pub fn parse_literal(raw: &str, ty: PropertyType) -> Result<Option<PropValueOwned>, LiteralError> {
+    if !matches!(ty, PropertyType::String) && raw.eq_ignore_ascii_case("null") {
+        return Ok(None);
+    }
+    let value = match ty {
+        PropertyType::Auto => return parse_auto_literal(raw),
+        PropertyType::String => PropValueOwned::Str(raw.to_string()),
+        PropertyType::Bool => {
+            let parsed = parse_bool_literal(raw)
+                .ok_or_else(|| LiteralError(format!("invalid boolean literal '{raw}'")))?;
+            PropValueOwned::Bool(parsed)
+        }
+        PropertyType::Int => {
+            let parsed = raw
+                .parse::<i64>()
+                .map_err(|_| LiteralError(format!("invalid integer literal '{raw}'")))?;
+            PropValueOwned::Int(parsed)
+        }
+        PropertyType::Float => {
+            let parsed = raw
+                .parse::<f64>()
+                .map_err(|_| LiteralError(format!("invalid float literal '{raw}'")))?;
+            PropValueOwned::Float(parsed)
+        }
+        PropertyType::Date => {
+            if let Ok(days) = raw.parse::<i64>() {
+                PropValueOwned::Date(days)
+            } else {
+                PropValueOwned::Date(parse_iso_date(raw)?)
+            }
+        }
+        PropertyType::DateTime => {
+            if let Ok(ms) = raw.parse::<i64>() {
+                PropValueOwned::DateTime(ms)
+            } else {
+                PropValueOwned::DateTime(parse_iso_datetime(raw)?)
+            }
+        }
+        PropertyType::Bytes => {
+            let bytes = parse_bytes_literal(raw)?;
+            PropValueOwned::Bytes(bytes)
+        }
+    };
+    Ok(Some(value))
+}
+
+fn parse_auto_literal(raw: &str) -> Result<Option<PropValueOwned>, LiteralError> {
+    if raw.eq_ignore_ascii_case("null") {
+        return Ok(None);
+    }
+    if let Some(val) = parse_bool_literal(raw) {
+        return Ok(Some(PropValueOwned::Bool(val)));
+    }
+    if let Ok(int_val) = raw.parse::<i64>() {
+        return Ok(Some(PropValueOwned::Int(int_val)));
+    }
+    if let Ok(float_val) = raw.parse::<f64>() {
+        return Ok(Some(PropValueOwned::Float(float_val)));
+    }
+    if raw.starts_with("0x") || raw.starts_with("0X") {
+        let bytes = parse_bytes_literal(raw)?;
+        return Ok(Some(PropValueOwned::Bytes(bytes)));
+    }
+    if looks_like_datetime(raw) {
+        let millis = parse_iso_datetime(raw)?;
+        return Ok(Some(PropValueOwned::DateTime(millis)));
+    }
+    if looks_like_date(raw) {
+        let days = parse_iso_date(raw)?;
+        return Ok(Some(PropValueOwned::Date(days)));
+    }
+    Ok(Some(PropValueOwned::Str(raw.to_string())))
+}
+
+fn parse_bool_literal(raw: &str) -> Option<bool> {
+    match raw.to_ascii_lowercase().as_str() {
+        "true" => Some(true),
+        "false" => Some(false),
+        _ => None,
+    }
+}
+
+fn parse_bytes_literal(raw: &str) -> Result<Vec<u8>, LiteralError> {
+    let body = raw
+        .strip_prefix("0x")
+        .or_else(|| raw.strip_prefix("0X"))
+        .ok_or_else(|| LiteralError("byte literal must start with 0x".into()))?;
+    if body.len() % 2 != 0 {
+        return Err(LiteralError(
+            "byte literal must contain an even number of hex digits".into(),
+        ));
+    }
+    hex::decode(body)
+        .map_err(|_| LiteralError(format!("byte literal '{raw}' contains non-hex characters")))
+}
+
+fn looks_like_date(raw: &str) -> bool {
+    let bytes = raw.as_bytes();
+    bytes.len() >= 10
+        && bytes[4] == b'-'
+        && bytes[7] == b'-'
+        && bytes[..4].iter().all(u8::is_ascii_digit)
+        && bytes[5..7].iter().all(u8::is_ascii_digit)
+        && bytes[8..10].iter().all(u8::is_ascii_digit)
+}
+
+fn looks_like_datetime(raw: &str) -> bool {
+    let bytes = raw.as_bytes();
+    if bytes.len() < 16 || !looks_like_date(raw) {
+        return false;
+    }
+    matches!(bytes[10], b'T' | b' ')
+}
+
+fn parse_iso_date(raw: &str) -> Result<i64, LiteralError> {
+    let date = Date::parse(raw, DATE_FMT)
+        .map_err(|_| LiteralError(format!("invalid date literal '{raw}'")))?;
+    let epoch = Date::from_calendar_date(1970, Month::January, 1)
+        .expect("1970-01-01 is a valid calendar date");
+    Ok((date - epoch).whole_days())
+}
+
+fn parse_iso_datetime(raw: &str) -> Result<i64, LiteralError> {
+    if let Ok(dt) = OffsetDateTime::parse(raw, &Rfc3339) {
+        return nanos_to_millis(dt.unix_timestamp_nanos());
+    }
+    if let Ok(dt) = PrimitiveDateTime::parse(raw, DATETIME_FMT_T) {
+        return nanos_to_millis(dt.assume_utc().unix_timestamp_nanos());
+    }
+    if let Ok(dt) = PrimitiveDateTime::parse(raw, DATETIME_FMT_T_FRAC) {
+        return nanos_to_millis(dt.assume_utc().unix_timestamp_nanos());
+    }
+    if let Ok(dt) = PrimitiveDateTime::parse(raw, DATETIME_FMT_SPACE) {
+        return nanos_to_millis(dt.assume_utc().unix_timestamp_nanos());
+    }
+    if let Ok(dt) = PrimitiveDateTime::parse(raw, DATETIME_FMT_SPACE_FRAC) {
+        return nanos_to_millis(dt.assume_utc().unix_timestamp_nanos());
+    }
+    Err(LiteralError(format!(
+        "invalid datetime literal '{raw}', expected RFC3339 or 'YYYY-MM-DD HH:MM:SS'"
+    )))
+}
+
+fn nanos_to_millis(nanos: i128) -> Result<i64, LiteralError> {
+    let millis = nanos / 1_000_000;
+    if millis < i64::MIN as i128 || millis > i64::MAX as i128 {
+        return Err(LiteralError(
+            "datetime literal is outside the supported range".into(),
+        ));
+    }
+    Ok(millis as i64)
+}
@@ -101,6 +101,13 @@ pub trait StorageMetrics: Send + Sync {
     /// Records a bulk adjacency flush.
     fn adjacency_bulk_flush(&self, _inserts: usize, _removals: usize) {}
 
+    /// Records a property index entry inserted or removed by a node write.
+    fn property_index_write(&self) {}
+
+    /// Records where the values of a written property map landed: `inline`
+    /// stayed in the record, `overflow` went to the VStore.
+    fn property_placement(&self, _inline: usize, _overflow: usize) {}
+
     /// Records latency (nanoseconds) to begin a read (snapshot acquisition + MVCC registration).
     fn mvcc_read_latency_ns(&self, _nanos: u64) {}
 
@@ -280,6 +287,15 @@ pub struct CounterMetrics {
     /// Bulk adjacency removals flushed.
     pub adjacency_bulk_removals: AtomicU64,
 
+    /// Property index entries inserted or removed by node writes.
+    pub property_index_writes: AtomicU64,
+
+    /// Property values written inline in their owning record.
+    pub prop_inline_writes: AtomicU64,
+
+    /// Property values spilled to VStore overflow pages.
+    pub prop_overflow_writes: AtomicU64,
+
     /// Total nanoseconds spent beginning reads.
     pub mvcc_read_latency_ns: AtomicU64,
     /// Number of recorded read-begin latencies.
@@ -491,6 +507,17 @@ impl StorageMetrics for CounterMetrics {
         }
     }
 
+    fn property_index_write(&self) {
+        self.property_index_writes.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn property_placement(&self, inline: usize, overflow: usize) {
+        self.prop_inline_writes
+            .fetch_add(inline as u64, Ordering::Relaxed);
+        self.prop_overflow_writes
+            .fetch_add(overflow as u64, Ordering::Relaxed);
+    }
+
     fn mvcc_read_latency_ns(&self, nanos: u64) {
         self.mvcc_read_latency_ns
             .fetch_add(nanos, Ordering::Relaxed);
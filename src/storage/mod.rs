@@ -31,6 +31,7 @@ pub mod vstore;
 mod adjacency;
 mod edge;
 mod graph;
+mod literal;
 mod metrics;
 mod mvcc;
 mod node;
@@ -52,19 +53,20 @@ pub use core::Db;
 pub use adjacency::DegreeDir;
 
 /// Graph traversal and adjacency operations.
-pub use adjacency::{Dir, ExpandOpts, Neighbor, NeighborCursor};
+pub use adjacency::{Dir, ExpandOpts, Neighbor, NeighborCursor, NeighborScan};
 
 /// Core graph storage implementation.
 pub use graph::{
-     AdjacencyVacuumStats, BfsOptions, BfsVisit, BulkEdgeValidator, CreateEdgeOptions, Graph,
-     GraphMvccStatus, GraphSpaceUsage, GraphVacuumStats, GraphWriter, GraphWriterStats, PropStats,
-     VacuumMode, VacuumTrigger, VersionVacuumStats, DEFAULT_INLINE_PROP_BLOB,
-     DEFAULT_INLINE_PROP_VALUE, STORAGE_FLAG_DEGREE_CACHE,
+     AdjacencyVacuumStats, BfsOptions, BfsVisit, BulkEdgeValidator, ComponentMode, CreateEdgeOptions, Graph,
+     GraphMvccStatus, GraphSpaceUsage, GraphSummary, GraphVacuumStats, GraphWriter,
+     GraphWriterStats, IndexDrift, MergeReport, NodeFilter, PageRankOpts, PropPlacementStats, PropStats, SpaceReport, SuspendedIndexes, VacuumMode, VacuumTrigger, VersionVacuumStats,
+     DEFAULT_INLINE_PROP_BLOB, DEFAULT_INLINE_PROP_VALUE, STORAGE_FLAG_CLUSTER_BY_LABEL,
+     STORAGE_FLAG_DEGREE_CACHE, STORAGE_FLAG_LIVE_COUNTS,
  };
 
 
 /// Index definitions and label scan operations.
-pub use index::{IndexDef, IndexKind, LabelScan, TypeTag};
+pub use index::{IndexDef, IndexKind, IndexState, LabelScan, TypeTag};
 pub use mvcc::{
     flags as mvcc_flags, CommitEntrySnapshot, CommitId, CommitReader, CommitStatus, CommitTable,
     CommitTableSnapshot, IntentId, ReaderSnapshot, ReaderSnapshotEntry, VersionCodecConfig,
@@ -72,6 +74,9 @@ pub use mvcc::{
     COMMIT_MAX, VERSION_HEADER_LEN, VERSION_PTR_LEN,
 };
 
+/// Typed parsing of textual property literals.
+pub use literal::{parse_literal, LiteralError, PropertyType};
+
 /// Metrics and profiling.
 pub use metrics::{default_metrics, CounterMetrics, NoopMetrics, StorageMetrics};
 
@@ -99,6 +104,6 @@ pub use profile::{
 
 /// Node, edge, and property data types and operations.
 pub use types::{
-    DeleteMode, DeleteNodeOpts, EdgeData, EdgeSpec, NodeData, NodeSpec, PropEntry, PropValue,
-    PropValueOwned,
+    ConflictPolicy, DeleteMode, DeleteNodeOpts, EdgeData, EdgeSpec, MergeNodeOpts, NodeData,
+    NodeSpec, PropEntry, PropValue, PropValueOwned,
 };
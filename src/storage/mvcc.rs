@@ -507,6 +507,20 @@ impl CommitTable {
         Ok(())
     }
 
+    /// Forgets a reserved commit whose transaction rolled back.
+    ///
+    /// Only pending entries are removed, so later commits are not held back
+    /// waiting for a commit that will never finish.
+    pub fn abort(&mut self, id: CommitId) {
+        if let Some(pos) = self
+            .entries
+            .iter()
+            .position(|entry| entry.id == id && entry.status == CommitStatus::Pending)
+        {
+            self.entries.remove(pos);
+        }
+    }
+
     /// Marks all commits up to and including `upto_id` as durable.
     pub fn mark_durable_up_to(&mut self, upto_id: CommitId) -> Result<()> {
         if upto_id <= self.released_up_to {
@@ -827,6 +841,21 @@ mod commit_table_tests {
         assert_eq!(table.oldest_visible(), 2);
     }
 
+    #[test]
+    fn aborted_commit_does_not_block_durability() {
+        let mut table = CommitTable::new(0);
+        let intent1 = table.reserve_intent();
+        let intent2 = table.reserve_intent();
+        table.promote_intent(intent1, 1).unwrap();
+        table.promote_intent(intent2, 2).unwrap();
+        table.mark_committed(2).unwrap();
+        assert!(table.mark_durable_up_to(2).is_err());
+        table.abort(1);
+        table.mark_durable_up_to(2).unwrap();
+        table.release_committed(2);
+        assert_eq!(table.oldest_visible(), 2);
+    }
+
     #[test]
     fn reject_unknown_ids() {
         let mut table = CommitTable::new(10);
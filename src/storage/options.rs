@@ -26,6 +26,12 @@ pub struct GraphOptions {
     pub inline_prop_blob: Option<u32>,
     /// Maximum size in bytes for inlining property values
     pub inline_prop_value: Option<u32>,
+    /// Minimum length in bytes at which string and bytes property values are
+    /// Snappy-compressed before being stored inline (`None` = never).
+    pub compress_inline_props: Option<u32>,
+    /// Whether values spilled to overflow pages are zstd-compressed
+    /// (requires the `compression` feature).
+    pub compress_overflow_values: bool,
     /// Whether to enable degree caching for nodes
     pub degree_cache: bool,
     /// Default behavior for distinct neighbors traversal
@@ -62,6 +68,24 @@ pub struct GraphOptions {
     pub snapshot_pool_max_age_ms: u64,
     /// Adjacency storage backend selection.
     pub adjacency_backend: AdjacencyBackend,
+    /// Whether writers create a label index the first time they see a label.
+    /// When disabled, label indexes exist only where explicitly requested and
+    /// label scans over unindexed labels fall back to a record scan.
+    pub auto_label_index: bool,
+    /// Whether node ids are handed out in per-label runs so that nodes sharing
+    /// a primary label land on the same record pages. Only honoured when the
+    /// graph is created; reopening keeps whatever policy the file was built with.
+    pub cluster_by_label: bool,
+    /// Maximum number of distinct label names interned through
+    /// [`Graph::name_limit`](crate::storage::Graph::name_limit) (`None` = unlimited).
+    pub max_distinct_labels: Option<usize>,
+    /// Maximum number of distinct edge type names interned (`None` = unlimited).
+    pub max_distinct_types: Option<usize>,
+    /// Maximum number of distinct property key names interned (`None` = unlimited).
+    pub max_distinct_props: Option<usize>,
+    /// Whether read traversals skip adjacency entries whose edge row is
+    /// missing instead of failing with a corruption error.
+    pub heal_on_read: bool,
 }
 
 impl GraphOptions {
@@ -71,6 +95,8 @@ impl GraphOptions {
             store,
             inline_prop_blob: None,
             inline_prop_value: None,
+            compress_inline_props: None,
+            compress_overflow_values: false,
             degree_cache: cfg!(feature = "degree-cache"),
             distinct_neighbors_default: false,
             metrics: None,
@@ -89,6 +115,12 @@ impl GraphOptions {
             snapshot_pool_size: 0,
             snapshot_pool_max_age_ms: 200,
             adjacency_backend: AdjacencyBackend::default(),
+            auto_label_index: true,
+            cluster_by_label: false,
+            max_distinct_labels: None,
+            max_distinct_types: None,
+            max_distinct_props: None,
+            heal_on_read: false,
         }
     }
 
@@ -104,6 +136,25 @@ impl GraphOptions {
         self
     }
 
+    /// Compresses string and bytes property values of at least `min_len` bytes.
+    ///
+    /// Compressed values that fit within the inline limit stay in the record
+    /// instead of spilling to the value store; reads decompress transparently.
+    pub fn compress_inline_props(mut self, min_len: u32) -> Self {
+        self.compress_inline_props = Some(min_len);
+        self
+    }
+
+    /// Compresses property blobs stored on overflow pages.
+    ///
+    /// Values that do not shrink are stored raw, and compressed values stay
+    /// readable after this is turned off. Needs the `compression` cargo
+    /// feature; opening a graph with this set fails without it.
+    pub fn compress_overflow_values(mut self, enabled: bool) -> Self {
+        self.compress_overflow_values = enabled;
+        self
+    }
+
     /// Enables or disables degree caching.
     pub fn degree_cache(mut self, enabled: bool) -> Self {
         self.degree_cache = enabled;
@@ -116,6 +167,18 @@ impl GraphOptions {
         self
     }
 
+    /// Enables or disables automatic label index creation on insert.
+    pub fn auto_label_index(mut self, enabled: bool) -> Self {
+        self.auto_label_index = enabled;
+        self
+    }
+
+    /// Enables or disables clustering node records by their primary label.
+    pub fn cluster_by_label(mut self, enabled: bool) -> Self {
+        self.cluster_by_label = enabled;
+        self
+    }
+
     /// Sets the metrics collection implementation.
     pub fn metrics(mut self, metrics: Arc<dyn super::metrics::StorageMetrics>) -> Self {
         self.metrics = Some(metrics);
@@ -215,6 +278,34 @@ impl GraphOptions {
         self.adjacency_backend = backend;
         self
     }
+
+    /// Caps the number of distinct label names interned for the graph.
+    pub fn max_distinct_labels(mut self, limit: usize) -> Self {
+        self.max_distinct_labels = Some(limit);
+        self
+    }
+
+    /// Caps the number of distinct edge type names interned for the graph.
+    pub fn max_distinct_types(mut self, limit: usize) -> Self {
+        self.max_distinct_types = Some(limit);
+        self
+    }
+
+    /// Caps the number of distinct property key names interned for the graph.
+    pub fn max_distinct_props(mut self, limit: usize) -> Self {
+        self.max_distinct_props = Some(limit);
+        self
+    }
+
+    /// Lets read traversals step over dangling adjacency entries.
+    ///
+    /// An adjacency entry is dangling when the edge it names has no row at
+    /// all. Strict graphs (the default) fail the read; lenient graphs log the
+    /// entry, skip it, and queue it for [`super::Graph::repair_dangling_adjacency`].
+    pub fn heal_on_read(mut self, enabled: bool) -> Self {
+        self.heal_on_read = enabled;
+        self
+    }
 }
 
 /// Configuration for background MVCC cleanup.
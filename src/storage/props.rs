@@ -1,3 +1,7 @@
+use std::cmp::Ordering;
+
+use snap::raw::{decompress_len, Decoder, Encoder};
+
 use crate::primitives::pager::{ReadGuard, WriteGuard};
 use crate::storage::vstore::VStore;
 use crate::types::{PropId, Result, SombraError, VRef};
@@ -13,6 +17,21 @@ pub const TYPE_BYTES: u8 = 5;
 pub const TYPE_DATETIME: u8 = 6;
 pub const TYPE_DATE: u8 = 7;
 
+const REPR_INLINE: u8 = 0;
+const REPR_VREF: u8 = 1;
+/// Inline payload compressed with Snappy (raw block format).
+const REPR_INLINE_SNAPPY: u8 = 2;
+
+/// Placement rules for string and bytes values.
+#[derive(Clone, Copy, Debug)]
+pub struct InlineValueOpts {
+    /// Longest value stored inside the record; longer values spill to the VStore.
+    pub limit: usize,
+    /// Minimum length at which values are Snappy-compressed before placement;
+    /// `None` disables compression.
+    pub compress_min_len: Option<usize>,
+}
+
 pub struct PropEncodeResult {
     pub bytes: Vec<u8>,
     pub spill_vrefs: Vec<VRef>,
@@ -40,7 +59,7 @@ pub enum RawPropValue {
 
 pub fn encode_props<'a>(
     entries: &[PropEntry<'a>],
-    inline_value_limit: usize,
+    inline: InlineValueOpts,
     vstore: &VStore,
     tx: &mut WriteGuard<'_>,
 ) -> Result<PropEncodeResult> {
@@ -81,7 +100,7 @@ pub fn encode_props<'a>(
                     &mut bytes,
                     TYPE_STR,
                     s.as_bytes(),
-                    inline_value_limit,
+                    inline,
                     vstore,
                     tx,
                     &mut spill_vrefs,
@@ -92,7 +111,7 @@ pub fn encode_props<'a>(
                     &mut bytes,
                     TYPE_BYTES,
                     b,
-                    inline_value_limit,
+                    inline,
                     vstore,
                     tx,
                     &mut spill_vrefs,
@@ -113,7 +132,7 @@ pub fn encode_props<'a>(
 
 pub fn encode_props_owned(
     entries: &[(PropId, PropValueOwned)],
-    inline_value_limit: usize,
+    inline: InlineValueOpts,
     vstore: &VStore,
     tx: &mut WriteGuard<'_>,
 ) -> Result<PropEncodeResult> {
@@ -144,7 +163,7 @@ pub fn encode_props_owned(
         };
         temp_entries.push(PropEntry::new(*prop, prop_value));
     }
-    encode_props(&temp_entries, inline_value_limit, vstore, tx)
+    encode_props(&temp_entries, inline, vstore, tx)
 }
 
 pub fn decode_raw(buf: &[u8]) -> Result<Vec<RawProp>> {
@@ -162,47 +181,108 @@ pub fn decode_raw(buf: &[u8]) -> Result<Vec<RawProp>> {
         }
         let type_tag = buf[cursor];
         cursor += 1;
-        let value = match type_tag {
-            TYPE_NULL => RawPropValue::Null,
-            TYPE_BOOL => {
-                if cursor >= buf.len() {
-                    return Err(SombraError::Corruption("bool payload truncated"));
-                }
-                let byte = buf[cursor];
-                cursor += 1;
-                if byte > 1 {
-                    return Err(SombraError::Corruption("bool payload invalid"));
-                }
-                RawPropValue::Bool(byte == 1)
+        let value = decode_value(buf, &mut cursor, type_tag)?;
+        props.push(RawProp { prop, value });
+    }
+    Ok(props)
+}
+
+/// Decodes only the value stored for `target`, skipping over the other entries.
+///
+/// Entries are encoded in ascending property id order, so the walk stops as
+/// soon as it passes `target`.
+pub fn decode_raw_prop(buf: &[u8], target: PropId) -> Result<Option<RawPropValue>> {
+    let mut cursor = 0usize;
+    let entry_count = read_var_u64(buf, &mut cursor)?;
+    for _ in 0..entry_count {
+        let prop_raw = read_var_u64(buf, &mut cursor)?;
+        if prop_raw > u32::MAX as u64 {
+            return Err(SombraError::Corruption("property id overflow"));
+        }
+        if cursor >= buf.len() {
+            return Err(SombraError::Corruption("property record truncated"));
+        }
+        let type_tag = buf[cursor];
+        cursor += 1;
+        match (prop_raw as u32).cmp(&target.0) {
+            Ordering::Less => skip_value(buf, &mut cursor, type_tag)?,
+            Ordering::Equal => return decode_value(buf, &mut cursor, type_tag).map(Some),
+            Ordering::Greater => break,
+        }
+    }
+    Ok(None)
+}
+
+fn decode_value(buf: &[u8], cursor: &mut usize, type_tag: u8) -> Result<RawPropValue> {
+    Ok(match type_tag {
+        TYPE_NULL => RawPropValue::Null,
+        TYPE_BOOL => {
+            if *cursor >= buf.len() {
+                return Err(SombraError::Corruption("bool payload truncated"));
             }
-            TYPE_INT => {
-                let value = read_var_i64(buf, &mut cursor)?;
-                RawPropValue::Int(value)
+            let byte = buf[*cursor];
+            *cursor += 1;
+            if byte > 1 {
+                return Err(SombraError::Corruption("bool payload invalid"));
             }
-            TYPE_FLOAT => {
-                if cursor + 8 > buf.len() {
-                    return Err(SombraError::Corruption("float payload truncated"));
-                }
-                let mut arr = [0u8; 8];
-                arr.copy_from_slice(&buf[cursor..cursor + 8]);
-                cursor += 8;
-                RawPropValue::Float(f64::from_le_bytes(arr))
+            RawPropValue::Bool(byte == 1)
+        }
+        TYPE_INT => {
+            let value = read_var_i64(buf, cursor)?;
+            RawPropValue::Int(value)
+        }
+        TYPE_FLOAT => {
+            if *cursor + 8 > buf.len() {
+                return Err(SombraError::Corruption("float payload truncated"));
             }
-            TYPE_STR => decode_bytes_like(buf, &mut cursor, true)?,
-            TYPE_BYTES => decode_bytes_like(buf, &mut cursor, false)?,
-            TYPE_DATE => {
-                let value = read_var_i64(buf, &mut cursor)?;
-                RawPropValue::Date(value)
+            let mut arr = [0u8; 8];
+            arr.copy_from_slice(&buf[*cursor..*cursor + 8]);
+            *cursor += 8;
+            RawPropValue::Float(f64::from_le_bytes(arr))
+        }
+        TYPE_STR => decode_bytes_like(buf, cursor, true)?,
+        TYPE_BYTES => decode_bytes_like(buf, cursor, false)?,
+        TYPE_DATE => {
+            let value = read_var_i64(buf, cursor)?;
+            RawPropValue::Date(value)
+        }
+        TYPE_DATETIME => {
+            let value = read_var_i64(buf, cursor)?;
+            RawPropValue::DateTime(value)
+        }
+        _ => return Err(SombraError::Corruption("unknown property type tag")),
+    })
+}
+
+/// Advances `cursor` past one encoded value without copying its payload.
+fn skip_value(buf: &[u8], cursor: &mut usize, type_tag: u8) -> Result<()> {
+    let width = match type_tag {
+        TYPE_NULL => 0,
+        TYPE_BOOL => 1,
+        TYPE_FLOAT => 8,
+        TYPE_INT | TYPE_DATE | TYPE_DATETIME => {
+            read_var_u64(buf, cursor)?;
+            0
+        }
+        TYPE_STR | TYPE_BYTES => {
+            if *cursor >= buf.len() {
+                return Err(SombraError::Corruption("bytes payload truncated"));
             }
-            TYPE_DATETIME => {
-                let value = read_var_i64(buf, &mut cursor)?;
-                RawPropValue::DateTime(value)
+            let repr = buf[*cursor];
+            *cursor += 1;
+            match repr {
+                REPR_INLINE | REPR_INLINE_SNAPPY => read_var_u64(buf, cursor)? as usize,
+                REPR_VREF => 28,
+                _ => return Err(SombraError::Corruption("unknown string/bytes repr tag")),
             }
-            _ => return Err(SombraError::Corruption("unknown property type tag")),
-        };
-        props.push(RawProp { prop, value });
+        }
+        _ => return Err(SombraError::Corruption("unknown property type tag")),
+    };
+    if *cursor + width > buf.len() {
+        return Err(SombraError::Corruption("property record truncated"));
     }
-    Ok(props)
+    *cursor += width;
+    Ok(())
 }
 
 /// Returns the number of properties encoded in the buffer without materializing values.
@@ -219,35 +299,43 @@ pub fn materialize_props(
 ) -> Result<Vec<(PropId, PropValueOwned)>> {
     let mut result = Vec::with_capacity(raw.len());
     for prop in raw {
-        let value = match &prop.value {
-            RawPropValue::Null => PropValueOwned::Null,
-            RawPropValue::Bool(v) => PropValueOwned::Bool(*v),
-            RawPropValue::Int(v) => PropValueOwned::Int(*v),
-            RawPropValue::Float(v) => PropValueOwned::Float(*v),
-            RawPropValue::StrInline(bytes) => {
-                let s = std::str::from_utf8(bytes)
-                    .map_err(|_| SombraError::Corruption("stored string not utf8"))?;
-                PropValueOwned::Str(s.to_owned())
-            }
-            RawPropValue::StrVRef(vref) => {
-                let bytes = vstore.read(tx, *vref)?;
-                let s = String::from_utf8(bytes)
-                    .map_err(|_| SombraError::Corruption("stored string not utf8"))?;
-                PropValueOwned::Str(s)
-            }
-            RawPropValue::BytesInline(bytes) => PropValueOwned::Bytes(bytes.clone()),
-            RawPropValue::BytesVRef(vref) => {
-                let bytes = vstore.read(tx, *vref)?;
-                PropValueOwned::Bytes(bytes)
-            }
-            RawPropValue::Date(v) => PropValueOwned::Date(*v),
-            RawPropValue::DateTime(v) => PropValueOwned::DateTime(*v),
-        };
-        result.push((prop.prop, value));
+        result.push((prop.prop, materialize_value(&prop.value, vstore, tx)?));
     }
     Ok(result)
 }
 
+/// Resolves a single raw value, reading spilled strings and bytes from `vstore`.
+pub fn materialize_value(
+    value: &RawPropValue,
+    vstore: &VStore,
+    tx: &ReadGuard,
+) -> Result<PropValueOwned> {
+    Ok(match value {
+        RawPropValue::Null => PropValueOwned::Null,
+        RawPropValue::Bool(v) => PropValueOwned::Bool(*v),
+        RawPropValue::Int(v) => PropValueOwned::Int(*v),
+        RawPropValue::Float(v) => PropValueOwned::Float(*v),
+        RawPropValue::StrInline(bytes) => {
+            let s = std::str::from_utf8(bytes)
+                .map_err(|_| SombraError::Corruption("stored string not utf8"))?;
+            PropValueOwned::Str(s.to_owned())
+        }
+        RawPropValue::StrVRef(vref) => {
+            let bytes = vstore.read(tx, *vref)?;
+            let s = String::from_utf8(bytes)
+                .map_err(|_| SombraError::Corruption("stored string not utf8"))?;
+            PropValueOwned::Str(s)
+        }
+        RawPropValue::BytesInline(bytes) => PropValueOwned::Bytes(bytes.clone()),
+        RawPropValue::BytesVRef(vref) => {
+            let bytes = vstore.read(tx, *vref)?;
+            PropValueOwned::Bytes(bytes)
+        }
+        RawPropValue::Date(v) => PropValueOwned::Date(*v),
+        RawPropValue::DateTime(v) => PropValueOwned::DateTime(*v),
+    })
+}
+
 pub fn materialize_props_with_write(
     raw: &[RawProp],
     vstore: &VStore,
@@ -294,19 +382,34 @@ fn encode_bytes_like(
     dst: &mut Vec<u8>,
     type_tag: u8,
     bytes: &[u8],
-    inline_limit: usize,
+    inline: InlineValueOpts,
     vstore: &VStore,
     tx: &mut WriteGuard<'_>,
     spill_vrefs: &mut Vec<VRef>,
 ) -> Result<()> {
     dst.push(type_tag);
-    if bytes.len() <= inline_limit {
-        dst.push(0);
+    if let Some(min_len) = inline.compress_min_len {
+        if bytes.len() >= min_len {
+            let compressed = Encoder::new()
+                .compress_vec(bytes)
+                .map_err(|_| SombraError::Invalid("property value compression failed"))?;
+            // Keep the compressed form only when it is smaller and fits inline;
+            // otherwise fall through to the plain inline or VStore encoding.
+            if compressed.len() < bytes.len() && compressed.len() <= inline.limit {
+                dst.push(REPR_INLINE_SNAPPY);
+                write_var_u64(compressed.len() as u64, dst);
+                dst.extend_from_slice(&compressed);
+                return Ok(());
+            }
+        }
+    }
+    if bytes.len() <= inline.limit {
+        dst.push(REPR_INLINE);
         write_var_u64(bytes.len() as u64, dst);
         dst.extend_from_slice(bytes);
         return Ok(());
     }
-    dst.push(1);
+    dst.push(REPR_VREF);
     let vref = vstore.write(tx, bytes)?;
     spill_vrefs.push(vref);
     encode_vref(dst, vref);
@@ -320,20 +423,25 @@ fn decode_bytes_like(buf: &[u8], cursor: &mut usize, is_string: bool) -> Result<
     let repr = buf[*cursor];
     *cursor += 1;
     match repr {
-        0 => {
+        REPR_INLINE | REPR_INLINE_SNAPPY => {
             let len = read_var_u64(buf, cursor)? as usize;
             if *cursor + len > buf.len() {
                 return Err(SombraError::Corruption("inline bytes truncated"));
             }
-            let data = buf[*cursor..*cursor + len].to_vec();
+            let stored = &buf[*cursor..*cursor + len];
             *cursor += len;
+            let data = if repr == REPR_INLINE_SNAPPY {
+                decompress_inline(stored)?
+            } else {
+                stored.to_vec()
+            };
             Ok(if is_string {
                 RawPropValue::StrInline(data)
             } else {
                 RawPropValue::BytesInline(data)
             })
         }
-        1 => {
+        REPR_VREF => {
             if *cursor + 28 > buf.len() {
                 return Err(SombraError::Corruption("vref payload truncated"));
             }
@@ -364,6 +472,16 @@ fn decode_bytes_like(buf: &[u8], cursor: &mut usize, is_string: bool) -> Result<
     }
 }
 
+fn decompress_inline(stored: &[u8]) -> Result<Vec<u8>> {
+    let len = decompress_len(stored)
+        .map_err(|_| SombraError::Corruption("compressed property header invalid"))?;
+    let mut out = vec![0u8; len];
+    Decoder::new()
+        .decompress(stored, &mut out)
+        .map_err(|_| SombraError::Corruption("compressed property payload invalid"))?;
+    Ok(out)
+}
+
 fn encode_vref(dst: &mut Vec<u8>, vref: VRef) {
     dst.extend_from_slice(&vref.start_page.0.to_be_bytes());
     dst.extend_from_slice(&vref.n_pages.to_be_bytes());
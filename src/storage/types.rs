@@ -126,6 +126,12 @@ pub enum DeleteMode {
     Restrict,
     /// Deletes the node and all connected edges.
     Cascade,
+    /// Cascades like [`DeleteMode::Cascade`], then deletes every node left
+    /// without incoming edges, repeating down the outgoing edges.
+    ///
+    /// A node is deleted only once all of its incoming edges come from nodes
+    /// being deleted; nodes kept alive by a cycle among themselves are left.
+    CascadeOrphans,
 }
 
 /// Options for deleting a node from the graph.
@@ -133,6 +139,11 @@ pub enum DeleteMode {
 pub struct DeleteNodeOpts {
     /// The deletion mode (restrict or cascade).
     pub mode: DeleteMode,
+    /// Deepest orphan level [`DeleteMode::CascadeOrphans`] may reach, counting
+    /// the deleted node as level 0 (`None` = unbounded).
+    ///
+    /// Exceeding it fails the delete before anything is removed.
+    pub max_cascade_depth: Option<u32>,
 }
 
 impl DeleteNodeOpts {
@@ -140,6 +151,7 @@ impl DeleteNodeOpts {
     pub fn restrict() -> Self {
         Self {
             mode: DeleteMode::Restrict,
+            max_cascade_depth: None,
         }
     }
 
@@ -147,8 +159,23 @@ impl DeleteNodeOpts {
     pub fn cascade() -> Self {
         Self {
             mode: DeleteMode::Cascade,
+            max_cascade_depth: None,
         }
     }
+
+    /// Creates delete options that also remove orphaned descendants.
+    pub fn cascade_orphans() -> Self {
+        Self {
+            mode: DeleteMode::CascadeOrphans,
+            max_cascade_depth: None,
+        }
+    }
+
+    /// Bounds how many orphan levels a cascade may delete.
+    pub fn max_cascade_depth(mut self, depth: u32) -> Self {
+        self.max_cascade_depth = Some(depth);
+        self
+    }
 }
 
 impl Default for DeleteNodeOpts {
@@ -156,3 +183,43 @@ impl Default for DeleteNodeOpts {
         Self::restrict()
     }
 }
+
+/// How [`Graph::merge_nodes`](crate::storage::Graph::merge_nodes) resolves a
+/// property both nodes define with different values.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ConflictPolicy {
+    /// Keeps the surviving node's value (default).
+    #[default]
+    KeepExisting,
+    /// Takes the value from the node being merged away.
+    TakeIncoming,
+    /// Fails the merge with [`SombraError::Conflict`](crate::types::SombraError::Conflict).
+    Fail,
+}
+
+/// Options for [`Graph::merge_nodes`](crate::storage::Graph::merge_nodes).
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct MergeNodeOpts {
+    /// How a property both nodes define with different values is resolved.
+    pub prop_conflict: ConflictPolicy,
+    /// Deletes edges joining the two nodes, and edges that would duplicate
+    /// another edge of the surviving node in endpoints, type, and properties,
+    /// instead of moving them.
+    pub dedup_edges: bool,
+}
+
+impl MergeNodeOpts {
+    /// Creates merge options resolving property conflicts with `policy`.
+    pub fn new(policy: ConflictPolicy) -> Self {
+        Self {
+            prop_conflict: policy,
+            dedup_edges: false,
+        }
+    }
+
+    /// Drops duplicate edges instead of moving them.
+    pub fn dedup_edges(mut self) -> Self {
+        self.dedup_edges = true;
+        self
+    }
+}
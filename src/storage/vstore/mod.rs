@@ -3,6 +3,8 @@
 use std::cmp::min;
 use std::collections::VecDeque;
 use std::convert::TryInto;
+use std::io;
+
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 
@@ -15,6 +17,10 @@ use tracing::debug;
 use tracing::trace;
 
 const OVERFLOW_HEADER_LEN: usize = 16;
+/// Offset of the reserved word in an overflow payload header. On the first
+/// page of a chain it holds the uncompressed length of a zstd-compressed
+/// value; zero marks the value as stored raw.
+const OVERFLOW_RAW_LEN_OFFSET: usize = 12;
 
 /// Metrics tracking for variable-length value storage operations.
 #[derive(Default)]
@@ -187,6 +193,8 @@ pub struct VStore {
     metrics: Arc<VStoreMetrics>,
     oldest_reader_commit: AtomicU64,
     deferred: Mutex<VecDeque<VRef>>,
+    #[cfg(feature = "compression")]
+    compress: bool,
 }
 
 impl VStore {
@@ -211,9 +219,21 @@ impl VStore {
             metrics: Arc::new(VStoreMetrics::default()),
             oldest_reader_commit: AtomicU64::new(0),
             deferred: Mutex::new(VecDeque::new()),
+            #[cfg(feature = "compression")]
+            compress: false,
         })
     }
 
+    /// Zstd-compresses values before they are chunked across overflow pages.
+    ///
+    /// Values that do not shrink are stored raw. Compressed values are read
+    /// back transparently whether or not this is enabled.
+    #[cfg(feature = "compression")]
+    pub fn compress_values(mut self, enabled: bool) -> Self {
+        self.compress = enabled;
+        self
+    }
+
     /// Returns a reference to the VStore metrics.
     pub fn metrics(&self) -> Arc<VStoreMetrics> {
         Arc::clone(&self.metrics)
@@ -239,7 +259,76 @@ impl VStore {
         if bytes.len() > u32::MAX as usize {
             return Err(SombraError::Invalid("value larger than 4GB not supported"));
         }
+        let compressed = self.compress_value(bytes)?;
+        let raw_len = compressed.as_ref().map(|_| bytes.len() as u32);
+        self.write_stored(tx, compressed.as_deref().unwrap_or(bytes), raw_len)
+    }
+
+    /// Writes `stored` to a fresh chain, marking it as the compressed form of
+    /// a `raw_len`-byte value when given.
+    fn write_stored(
+        &self,
+        tx: &mut WriteGuard<'_>,
+        stored: &[u8],
+        raw_len: Option<u32>,
+    ) -> Result<VRef> {
         let owner_commit = tx.reserve_commit_id().0;
+        let mut checksum = Crc32Fast::default();
+        let pages = self.write_chain(tx, stored, &mut checksum)?;
+        if let Some(raw_len) = raw_len {
+            self.mark_compressed(tx, pages[0], raw_len)?;
+        }
+        self.metrics.add_bytes_written(stored.len() as u64);
+        trace!(
+            pages = pages.len(),
+            len = stored.len(),
+            ?raw_len,
+            "vstore.write"
+        );
+        Ok(VRef {
+            start_page: pages[0],
+            n_pages: pages.len() as u32,
+            len: stored.len() as u32,
+            checksum: checksum.finalize(),
+            owner_commit,
+        })
+    }
+
+    /// Returns the zstd encoding of `bytes` when compression is enabled and
+    /// it saves space.
+    #[cfg(feature = "compression")]
+    fn compress_value(&self, bytes: &[u8]) -> Result<Option<Vec<u8>>> {
+        if !self.compress || bytes.is_empty() {
+            return Ok(None);
+        }
+        let compressed = zstd::bulk::compress(bytes, 0)
+            .map_err(|_| SombraError::Invalid("overflow value compression failed"))?;
+        Ok((compressed.len() < bytes.len()).then_some(compressed))
+    }
+
+    #[cfg(not(feature = "compression"))]
+    fn compress_value(&self, _bytes: &[u8]) -> Result<Option<Vec<u8>>> {
+        Ok(None)
+    }
+
+    /// Records on a chain's first page that it holds `raw_len` bytes
+    /// compressed.
+    fn mark_compressed(&self, tx: &mut WriteGuard<'_>, first: PageId, raw_len: u32) -> Result<()> {
+        let mut page = tx.page_mut(first)?;
+        let buf = page.data_mut();
+        let at = PAGE_HDR_LEN + OVERFLOW_RAW_LEN_OFFSET;
+        buf[at..at + 4].copy_from_slice(&raw_len.to_be_bytes());
+        Ok(())
+    }
+
+    /// Allocates and fills a fresh overflow chain holding `bytes`, feeding
+    /// them into `checksum`, and returns its pages in chain order.
+    fn write_chain(
+        &self,
+        tx: &mut WriteGuard<'_>,
+        bytes: &[u8],
+        checksum: &mut Crc32Fast,
+    ) -> Result<Vec<PageId>> {
         let needed_pages = if bytes.is_empty() {
             1
         } else {
@@ -266,7 +355,6 @@ impl VStore {
             pages.extend(extent.iter_pages());
         }
         debug_assert_eq!(pages.len(), needed_pages);
-        let mut checksum = Crc32Fast::default();
         let mut offset = 0usize;
         for (idx, page_id) in pages.iter().enumerate() {
             let next = if idx + 1 < pages.len() {
@@ -286,15 +374,7 @@ impl VStore {
         self.metrics
             .add_extent_stats(extents.len() as u64, pages.len() as u64);
         self.metrics.add_pages_allocated(pages.len() as u64);
-        self.metrics.add_bytes_written(bytes.len() as u64);
-        trace!(pages = pages.len(), len = bytes.len(), "vstore.write");
-        Ok(VRef {
-            start_page: pages[0],
-            n_pages: pages.len() as u32,
-            len: bytes.len() as u32,
-            checksum: checksum.finalize(),
-            owner_commit,
-        })
+        Ok(pages)
     }
 
     /// Reads variable-length data from a VRef into a new vector.
@@ -316,12 +396,16 @@ impl VStore {
         dst.clear();
         dst.reserve(remaining);
         let mut checksum = Crc32Fast::default();
+        let mut raw_len = 0;
         while pages_left > 0 {
             if current.0 == 0 {
                 return Err(SombraError::Corruption("overflow chain terminated early"));
             }
             let page = self.store.get_page(tx, current)?;
             let (next, used, data) = self.decode_page(page.data())?;
+            if pages_left == vref.n_pages {
+                raw_len = self.raw_len(page.data());
+            }
             let used_usize = used as usize;
             if used_usize > remaining {
                 return Err(SombraError::Corruption(
@@ -348,11 +432,73 @@ impl VStore {
         if computed != vref.checksum {
             return Err(SombraError::Corruption("overflow checksum mismatch"));
         }
+        self.decompress_in_place(dst, raw_len)?;
         self.metrics.add_bytes_read(vref.len as u64);
         trace!(pages = vref.n_pages, len = vref.len, "vstore.read");
         Ok(())
     }
 
+    /// Replaces the stored bytes in `dst` with the value they encode.
+    ///
+    /// Builds without the `compression` feature cannot decode compressed
+    /// values and report them as invalid.
+    fn decompress_in_place(&self, dst: &mut Vec<u8>, raw_len: u32) -> Result<()> {
+        if raw_len == 0 {
+            return Ok(());
+        }
+        #[cfg(feature = "compression")]
+        {
+            let out = zstd::bulk::decompress(dst, raw_len as usize)
+                .map_err(|_| SombraError::Corruption("compressed overflow payload invalid"))?;
+            if out.len() != raw_len as usize {
+                return Err(SombraError::Corruption(
+                    "compressed overflow length mismatch",
+                ));
+            }
+            *dst = out;
+            Ok(())
+        }
+        #[cfg(not(feature = "compression"))]
+        {
+            let _ = dst;
+            Err(SombraError::Invalid(
+                "compressed overflow value requires the `compression` feature",
+            ))
+        }
+    }
+
+    /// Returns the uncompressed length recorded on a chain's first page, or
+    /// zero when the value is stored raw.
+    fn raw_len(&self, data: &[u8]) -> u32 {
+        let at = PAGE_HDR_LEN + OVERFLOW_RAW_LEN_OFFSET;
+        u32::from_be_bytes(data[at..at + 4].try_into().expect("four bytes"))
+    }
+
+    /// Returns a reader that yields a value one overflow page at a time.
+    ///
+    /// The chain is validated as pages are pulled and the checksum once the
+    /// last byte has been read; a mismatch surfaces as an
+    /// [`io::ErrorKind::InvalidData`] error wrapping the [`SombraError`] after
+    /// every byte has been returned, so callers must treat earlier output as
+    /// provisional until the reader reports end of stream.
+    ///
+    /// Compressed values cannot be decoded piecemeal, so they are read and
+    /// decompressed in full when the first page is pulled.
+    pub fn read_stream<'a>(&'a self, tx: &'a ReadGuard, vref: VRef) -> VStoreReader<'a> {
+        VStoreReader {
+            vstore: self,
+            tx,
+            vref,
+            current: vref.start_page,
+            pages_left: vref.n_pages,
+            remaining: vref.len as usize,
+            page: Vec::with_capacity(self.data_capacity),
+            pos: 0,
+            checksum: Crc32Fast::default(),
+            done: vref.n_pages == 0,
+        }
+    }
+
     /// Reads variable-length data using a write transaction without opening a read guard.
     pub fn read_with_write(&self, tx: &mut WriteGuard<'_>, vref: VRef) -> Result<Vec<u8>> {
         let mut dst = Vec::with_capacity(vref.len as usize);
@@ -377,12 +523,16 @@ impl VStore {
         dst.clear();
         dst.reserve(remaining);
         let mut checksum = Crc32Fast::default();
+        let mut raw_len = 0;
         while pages_left > 0 {
             if current.0 == 0 {
                 return Err(SombraError::Corruption("overflow chain terminated early"));
             }
             let page = self.store.get_page_with_write(tx, current)?;
             let (next, used, data) = self.decode_page(page.data())?;
+            if pages_left == vref.n_pages {
+                raw_len = self.raw_len(page.data());
+            }
             let used_usize = used as usize;
             if used_usize > remaining {
                 return Err(SombraError::Corruption(
@@ -409,6 +559,7 @@ impl VStore {
         if computed != vref.checksum {
             return Err(SombraError::Corruption("overflow checksum mismatch"));
         }
+        self.decompress_in_place(dst, raw_len)?;
         self.metrics.add_bytes_read(vref.len as u64);
         trace!(
             pages = vref.n_pages,
@@ -529,10 +680,15 @@ impl VStore {
     }
 
     /// Updates variable-length data in place if possible, otherwise reallocates.
+    ///
+    /// The new value is compressed under the same rules as [`VStore::write`].
     pub fn update(&self, tx: &mut WriteGuard<'_>, vref: &mut VRef, new: &[u8]) -> Result<()> {
         if new.len() > u32::MAX as usize {
             return Err(SombraError::Invalid("value larger than 4GB not supported"));
         }
+        let compressed = self.compress_value(new)?;
+        let raw_len = compressed.as_ref().map(|_| new.len() as u32);
+        let new = compressed.as_deref().unwrap_or(new);
         let total_capacity = self.data_capacity * vref.n_pages as usize;
         if new.len() <= total_capacity {
             let mut current = vref.start_page;
@@ -558,6 +714,9 @@ impl VStore {
                     "overflow chain shorter than expected during update",
                 ));
             }
+            if let Some(raw_len) = raw_len {
+                self.mark_compressed(tx, vref.start_page, raw_len)?;
+            }
             vref.len = new.len() as u32;
             vref.checksum = checksum.finalize();
             self.metrics.add_bytes_written(new.len() as u64);
@@ -565,7 +724,7 @@ impl VStore {
             return Ok(());
         }
 
-        let replacement = self.write(tx, new)?;
+        let replacement = self.write_stored(tx, new, raw_len)?;
         self.free(tx, *vref)?;
         *vref = replacement;
         trace!(
@@ -576,6 +735,185 @@ impl VStore {
         Ok(())
     }
 
+    /// Appends `extra` to the value in place.
+    ///
+    /// Only the page holding the current end of the value and any trailing
+    /// pages are rewritten; bytes that do not fit go to newly allocated pages
+    /// linked onto the chain. The checksum is extended from the stored one, so
+    /// existing data is never re-read. Appended bytes are stored raw; a value
+    /// that was written compressed is instead decompressed, extended and
+    /// rewritten through [`VStore::update`].
+    pub fn append(&self, tx: &mut WriteGuard<'_>, vref: &mut VRef, extra: &[u8]) -> Result<()> {
+        if extra.is_empty() {
+            return Ok(());
+        }
+        if self.is_compressed(tx, *vref)? {
+            let mut bytes = self.read_with_write(tx, *vref)?;
+            bytes.extend_from_slice(extra);
+            return self.update(tx, vref, &bytes);
+        }
+        let new_len = vref.len as usize + extra.len();
+        if new_len > u32::MAX as usize {
+            return Err(SombraError::Invalid("value larger than 4GB not supported"));
+        }
+        if vref.n_pages == 0 {
+            *vref = self.write(tx, extra)?;
+            return Ok(());
+        }
+        let chain = self.chain_layout(tx, *vref)?;
+        let mut checksum = Crc32Fast::resume(vref.checksum);
+        checksum.update(extra);
+        // Pages before the last non-empty one are full, so new bytes start
+        // there and spill into any empty pages after it.
+        let first_open = chain.iter().rposition(|(_, used)| *used > 0).unwrap_or(0);
+        let last_idx = chain.len() - 1;
+        let mut rest = extra;
+        for &(page_id, used) in &chain[first_open..last_idx] {
+            if rest.is_empty() {
+                break;
+            }
+            let take = min(self.data_capacity - used as usize, rest.len());
+            if take == 0 {
+                continue;
+            }
+            let mut page = tx.page_mut(page_id)?;
+            let (next, _, data) = self.decode_page(page.data())?;
+            let mut payload = data.to_vec();
+            payload.extend_from_slice(&rest[..take]);
+            self.write_payload(&mut page, page_id, next, &payload)?;
+            rest = &rest[take..];
+        }
+        if !rest.is_empty() {
+            let (last_id, used) = chain[last_idx];
+            let take = min(self.data_capacity - used as usize, rest.len());
+            let (tail, overflow) = rest.split_at(take);
+            let next = if overflow.is_empty() {
+                PageId(0)
+            } else {
+                let mut unused = Crc32Fast::default();
+                let pages = self.write_chain(tx, overflow, &mut unused)?;
+                vref.n_pages = vref
+                    .n_pages
+                    .checked_add(pages.len() as u32)
+                    .ok_or(SombraError::Invalid("page count exceeds u32::MAX"))?;
+                pages[0]
+            };
+            let mut page = tx.page_mut(last_id)?;
+            let (_, _, data) = self.decode_page(page.data())?;
+            let mut payload = data.to_vec();
+            payload.extend_from_slice(tail);
+            self.write_payload(&mut page, last_id, next, &payload)?;
+        }
+        vref.len = new_len as u32;
+        vref.checksum = checksum.finalize();
+        vref.owner_commit = tx.reserve_commit_id().0;
+        self.metrics.add_bytes_written(extra.len() as u64);
+        trace!(pages = vref.n_pages, len = vref.len, "vstore.append");
+        Ok(())
+    }
+
+    /// Shortens the value to its first `new_len` bytes, freeing pages past
+    /// the new end.
+    ///
+    /// A CRC cannot be un-extended, so the checksum is recomputed from the
+    /// retained bytes; dropped pages are not read beyond their chain links.
+    /// Freed pages respect the reader horizon like [`VStore::free`]. A
+    /// compressed value is decompressed, cut and rewritten instead.
+    pub fn truncate(&self, tx: &mut WriteGuard<'_>, vref: &mut VRef, new_len: u32) -> Result<()> {
+        if self.is_compressed(tx, *vref)? {
+            let mut bytes = self.read_with_write(tx, *vref)?;
+            if new_len as usize > bytes.len() {
+                return Err(SombraError::Invalid("truncate length exceeds value length"));
+            }
+            bytes.truncate(new_len as usize);
+            return self.update(tx, vref, &bytes);
+        }
+        if new_len > vref.len {
+            return Err(SombraError::Invalid("truncate length exceeds value length"));
+        }
+        if new_len == vref.len || vref.n_pages == 0 {
+            return Ok(());
+        }
+        let commit = tx.reserve_commit_id().0;
+        let chain = self.chain_layout(tx, *vref)?;
+        let target = new_len as usize;
+        let mut checksum = Crc32Fast::default();
+        let mut kept = 0usize;
+        let mut keep_pages = 0usize;
+        let mut tail = Vec::new();
+        for &(page_id, used) in &chain {
+            let take = min(used as usize, target - kept);
+            let page = self.store.get_page_with_write(tx, page_id)?;
+            let (_, _, data) = self.decode_page(page.data())?;
+            checksum.update(&data[..take]);
+            keep_pages += 1;
+            kept += take;
+            if kept == target {
+                tail = data[..take].to_vec();
+                break;
+            }
+        }
+        let last_id = chain[keep_pages - 1].0;
+        let mut page = tx.page_mut(last_id)?;
+        self.write_payload(&mut page, last_id, PageId(0), &tail)?;
+        drop(page);
+        if keep_pages < chain.len() {
+            let dropped = VRef {
+                start_page: chain[keep_pages].0,
+                n_pages: (chain.len() - keep_pages) as u32,
+                len: 0,
+                checksum: 0,
+                // Readers that still see the longer value may walk these
+                // pages until this commit falls behind the horizon.
+                owner_commit: commit,
+            };
+            self.free(tx, dropped)?;
+            vref.n_pages = keep_pages as u32;
+        }
+        vref.len = new_len;
+        vref.checksum = checksum.finalize();
+        vref.owner_commit = commit;
+        trace!(pages = vref.n_pages, len = vref.len, "vstore.truncate");
+        Ok(())
+    }
+
+    fn is_compressed(&self, tx: &mut WriteGuard<'_>, vref: VRef) -> Result<bool> {
+        if vref.n_pages == 0 {
+            return Ok(false);
+        }
+        let page = self.store.get_page_with_write(tx, vref.start_page)?;
+        Ok(self.raw_len(page.data()) != 0)
+    }
+
+    /// Walks a value's overflow chain, returning each page and the bytes it
+    /// holds.
+    fn chain_layout(&self, tx: &mut WriteGuard<'_>, vref: VRef) -> Result<Vec<(PageId, u32)>> {
+        let mut chain = Vec::with_capacity(vref.n_pages as usize);
+        let mut current = vref.start_page;
+        let mut total = 0u64;
+        for _ in 0..vref.n_pages {
+            if current.0 == 0 {
+                return Err(SombraError::Corruption("overflow chain terminated early"));
+            }
+            let page = self.store.get_page_with_write(tx, current)?;
+            let (next, used, _) = self.decode_page(page.data())?;
+            chain.push((current, used));
+            total += used as u64;
+            current = next;
+        }
+        if current.0 != 0 {
+            return Err(SombraError::Corruption(
+                "overflow chain longer than n_pages",
+            ));
+        }
+        if total != vref.len as u64 {
+            return Err(SombraError::Corruption(
+                "overflow chain shorter than reported length",
+            ));
+        }
+        Ok(chain)
+    }
+
     fn init_overflow_page(
         &self,
         page: &mut PageMut<'_>,
@@ -675,9 +1013,6 @@ impl VStore {
                 .try_into()
                 .map_err(|_| SombraError::Corruption("overflow used bytes truncated"))?,
         );
-        if payload[12..16] != [0; 4] {
-            return Err(SombraError::Corruption("overflow reserved bytes not zero"));
-        }
         if used as usize > self.data_capacity {
             return Err(SombraError::Corruption(
                 "overflow used bytes exceed capacity",
@@ -691,6 +1026,96 @@ impl VStore {
     }
 }
 
+/// Streaming reader over a value's overflow chain, created by
+/// [`VStore::read_stream`].
+pub struct VStoreReader<'a> {
+    vstore: &'a VStore,
+    tx: &'a ReadGuard,
+    vref: VRef,
+    current: PageId,
+    pages_left: u32,
+    remaining: usize,
+    page: Vec<u8>,
+    pos: usize,
+    checksum: Crc32Fast,
+    done: bool,
+}
+
+impl VStoreReader<'_> {
+    /// Loads the next overflow page into the buffer, or finishes the chain.
+    fn advance(&mut self) -> Result<()> {
+        if self.pages_left == 0 {
+            self.done = true;
+            if self.current.0 != 0 {
+                return Err(SombraError::Corruption(
+                    "overflow chain longer than n_pages",
+                ));
+            }
+            if self.remaining != 0 {
+                return Err(SombraError::Corruption(
+                    "overflow chain shorter than reported length",
+                ));
+            }
+            if self.checksum.finalize() != self.vref.checksum {
+                return Err(SombraError::Corruption("overflow checksum mismatch"));
+            }
+            self.vstore.metrics.add_bytes_read(self.vref.len as u64);
+            trace!(
+                pages = self.vref.n_pages,
+                len = self.vref.len,
+                "vstore.read_stream"
+            );
+            return Ok(());
+        }
+        if self.current.0 == 0 {
+            self.done = true;
+            return Err(SombraError::Corruption("overflow chain terminated early"));
+        }
+        let page = self.vstore.store.get_page(self.tx, self.current)?;
+        if self.pages_left == self.vref.n_pages && self.vstore.raw_len(page.data()) != 0 {
+            drop(page);
+            self.done = true;
+            self.page = self.vstore.read(self.tx, self.vref)?;
+            self.pos = 0;
+            return Ok(());
+        }
+        let (next, used, data) = self.vstore.decode_page(page.data())?;
+        if used as usize > self.remaining {
+            self.done = true;
+            return Err(SombraError::Corruption(
+                "overflow chain exceeded reported length",
+            ));
+        }
+        self.page.clear();
+        self.page.extend_from_slice(data);
+        self.pos = 0;
+        self.checksum.update(data);
+        self.remaining -= used as usize;
+        self.pages_left -= 1;
+        self.current = next;
+        Ok(())
+    }
+}
+
+impl io::Read for VStoreReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.pos == self.page.len() {
+            if self.done || buf.is_empty() {
+                return Ok(0);
+            }
+            self.advance().map_err(|err| match err {
+                SombraError::Io(io_err) => io_err,
+                SombraError::Corruption(_) => io::Error::new(io::ErrorKind::InvalidData, err),
+                other => io::Error::other(other),
+            })?;
+        }
+        let n = min(buf.len(), self.page.len() - self.pos);
+        buf[..n].copy_from_slice(&self.page[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -714,4 +1139,167 @@ mod tests {
         assert_eq!(snapshot.extent_pages, vref.n_pages as u64);
         Ok(())
     }
+
+    #[test]
+    fn vstore_read_stream_matches_eager_read_and_verifies_checksum() -> Result<()> {
+        use std::io::Read;
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("vstore_stream.db");
+        let pager: Arc<dyn PageStore> = Arc::new(Pager::create(&path, PagerOptions::default())?);
+        let vstore = VStore::open(Arc::clone(&pager))?;
+        let mut write = pager.begin_write()?;
+        let payload: Vec<u8> = (0..pager.page_size() as usize * 5 + 123)
+            .map(|i| (i % 251) as u8)
+            .collect();
+        let vref = vstore.write(&mut write, &payload)?;
+        let empty = vstore.write(&mut write, &[])?;
+        pager.commit(write)?;
+
+        let read = pager.begin_latest_committed_read()?;
+        let mut streamed = Vec::new();
+        let mut reader = vstore.read_stream(&read, vref);
+        let mut chunk = [0u8; 1000];
+        loop {
+            let n = reader.read(&mut chunk)?;
+            if n == 0 {
+                break;
+            }
+            streamed.extend_from_slice(&chunk[..n]);
+        }
+        assert_eq!(streamed, vstore.read(&read, vref)?);
+        assert_eq!(streamed, payload);
+
+        let mut out = Vec::new();
+        vstore.read_stream(&read, empty).read_to_end(&mut out)?;
+        assert!(out.is_empty());
+
+        let tampered = VRef {
+            checksum: vref.checksum ^ 1,
+            ..vref
+        };
+        let mut out = Vec::new();
+        let err = vstore
+            .read_stream(&read, tampered)
+            .read_to_end(&mut out)
+            .expect_err("checksum mismatch must fail");
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        assert!(matches!(
+            err.get_ref().and_then(|e| e.downcast_ref::<SombraError>()),
+            Some(SombraError::Corruption("overflow checksum mismatch"))
+        ));
+        assert_eq!(out.len(), payload.len());
+        Ok(())
+    }
+
+    #[test]
+    fn vstore_append_and_truncate_keep_value_and_checksum_consistent() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("vstore_append.db");
+        let pager: Arc<dyn PageStore> = Arc::new(Pager::create(&path, PagerOptions::default())?);
+        let vstore = VStore::open(Arc::clone(&pager))?;
+        vstore.set_oldest_reader_commit(COMMIT_MAX);
+        let cap = vstore.data_capacity;
+        let mut expected: Vec<u8> = (0..cap + 10).map(|i| (i % 241) as u8).collect();
+        let mut write = pager.begin_write()?;
+        let mut vref = vstore.write(&mut write, &expected)?;
+        assert_eq!(vref.n_pages, 2);
+
+        // A small append fits in the tail page.
+        let small = vec![0xAB; 20];
+        vstore.append(&mut write, &mut vref, &small)?;
+        expected.extend_from_slice(&small);
+        assert_eq!(vref.n_pages, 2);
+        assert_eq!(vstore.read_with_write(&mut write, vref)?, expected);
+
+        // A large append fills the tail page and links new ones.
+        let large: Vec<u8> = (0..cap * 2).map(|i| (i % 7) as u8).collect();
+        vstore.append(&mut write, &mut vref, &large)?;
+        expected.extend_from_slice(&large);
+        assert_eq!(vref.n_pages, 4);
+        assert_eq!(vref.len as usize, expected.len());
+        assert_eq!(vstore.read_with_write(&mut write, vref)?, expected);
+
+        // Truncating frees the pages past the new end.
+        let freed_before = vstore.metrics_snapshot().pages_freed;
+        vstore.truncate(&mut write, &mut vref, (cap + 5) as u32)?;
+        expected.truncate(cap + 5);
+        assert_eq!(vref.n_pages, 2);
+        assert_eq!(vstore.read_with_write(&mut write, vref)?, expected);
+        vstore.flush_deferred(&mut write)?;
+        assert_eq!(vstore.metrics_snapshot().pages_freed, freed_before + 2);
+        assert!(matches!(
+            vstore.truncate(&mut write, &mut vref, (cap + 6) as u32),
+            Err(SombraError::Invalid(_))
+        ));
+
+        // Shrinking in place leaves empty trailing pages that appends reuse.
+        let mut short = vstore.write(&mut write, &vec![1u8; cap * 3])?;
+        vstore.update(&mut write, &mut short, b"head")?;
+        vstore.append(&mut write, &mut short, &vec![2u8; cap + 1])?;
+        let mut want = b"head".to_vec();
+        want.extend(vec![2u8; cap + 1]);
+        assert_eq!(short.n_pages, 3);
+        assert_eq!(vstore.read_with_write(&mut write, short)?, want);
+
+        vstore.truncate(&mut write, &mut short, 0)?;
+        assert_eq!(short.n_pages, 1);
+        assert!(vstore.read_with_write(&mut write, short)?.is_empty());
+        pager.commit(write)?;
+
+        let read = pager.begin_latest_committed_read()?;
+        assert_eq!(vstore.read(&read, vref)?, expected);
+        Ok(())
+    }
+
+    #[cfg(feature = "compression")]
+    #[test]
+    fn vstore_compresses_values_that_shrink() -> Result<()> {
+        use std::io::Read;
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("vstore_compress.db");
+        let pager: Arc<dyn PageStore> = Arc::new(Pager::create(&path, PagerOptions::default())?);
+        let vstore = VStore::open(Arc::clone(&pager))?.compress_values(true);
+        let page_size = pager.page_size() as usize;
+        let json = r#"{"kind":"event","payload":"aaaaaaaaaaaaaaaa"},"#.repeat(page_size / 8);
+        let noise: Vec<u8> = (0..page_size * 2)
+            .scan(0x2545_F491u32, |state, _| {
+                *state ^= *state << 13;
+                *state ^= *state >> 17;
+                *state ^= *state << 5;
+                Some(*state as u8)
+            })
+            .collect();
+        let mut write = pager.begin_write()?;
+        let packed = vstore.write(&mut write, json.as_bytes())?;
+        let raw = vstore.write(&mut write, &noise)?;
+        assert!((packed.len as usize) < json.len());
+        assert!(packed.n_pages < json.len().div_ceil(vstore.data_capacity) as u32);
+        assert_eq!(raw.len as usize, noise.len());
+
+        let mut appended = vstore.write(&mut write, json.as_bytes())?;
+        vstore.append(&mut write, &mut appended, b"tail")?;
+        let mut cut = vstore.write(&mut write, json.as_bytes())?;
+        vstore.truncate(&mut write, &mut cut, 10)?;
+        pager.commit(write)?;
+
+        let read = pager.begin_latest_committed_read()?;
+        assert_eq!(vstore.read(&read, packed)?, json.as_bytes());
+        assert_eq!(vstore.read(&read, raw)?, noise);
+        let mut streamed = Vec::new();
+        vstore
+            .read_stream(&read, packed)
+            .read_to_end(&mut streamed)?;
+        assert_eq!(streamed, json.as_bytes());
+        let mut want = json.clone().into_bytes();
+        want.extend_from_slice(b"tail");
+        assert_eq!(vstore.read(&read, appended)?, want);
+        assert_eq!(vstore.read(&read, cut)?, &json.as_bytes()[..10]);
+
+        // Compression is a write-side setting; reads decode either way.
+        let plain = VStore::open(Arc::clone(&pager))?;
+        assert_eq!(plain.read(&read, packed)?, json.as_bytes());
+        Ok(())
+    }
 }
@@ -23,6 +23,16 @@ impl Default for Crc32Fast {
     }
 }
 
+impl Crc32Fast {
+    /// Resumes from a previously finalized checksum, so further updates yield
+    /// the checksum of the original bytes followed by the new ones.
+    pub fn resume(crc: u32) -> Self {
+        Self {
+            inner: crc32fast::Hasher::new_with_initial(crc),
+        }
+    }
+}
+
 impl Checksum for Crc32Fast {
     fn reset(&mut self) {
         self.inner.reset();
@@ -37,6 +47,198 @@ impl Checksum for Crc32Fast {
     }
 }
 
+/// CRC32C (Castagnoli) checksum computed with slicing-by-8 lookup tables.
+pub struct Crc32c {
+    state: u32,
+}
+
+impl Default for Crc32c {
+    fn default() -> Self {
+        Self { state: !0 }
+    }
+}
+
+/// Reflected CRC32C polynomial.
+const CRC32C_POLY: u32 = 0x82F6_3B78;
+
+const CRC32C_TABLES: [[u32; 256]; 8] = crc32c_tables();
+
+const fn crc32c_tables() -> [[u32; 256]; 8] {
+    let mut tables = [[0u32; 256]; 8];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ CRC32C_POLY
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        tables[0][i] = crc;
+        i += 1;
+    }
+    let mut t = 1;
+    while t < 8 {
+        let mut i = 0;
+        while i < 256 {
+            let prev = tables[t - 1][i];
+            tables[t][i] = (prev >> 8) ^ tables[0][(prev & 0xFF) as usize];
+            i += 1;
+        }
+        t += 1;
+    }
+    tables
+}
+
+impl Checksum for Crc32c {
+    fn reset(&mut self) {
+        self.state = !0;
+    }
+
+    fn update(&mut self, bytes: &[u8]) {
+        let t = &CRC32C_TABLES;
+        let mut crc = self.state;
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in &mut chunks {
+            let lo = crc ^ u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+            crc = t[7][(lo & 0xFF) as usize]
+                ^ t[6][((lo >> 8) & 0xFF) as usize]
+                ^ t[5][((lo >> 16) & 0xFF) as usize]
+                ^ t[4][(lo >> 24) as usize]
+                ^ t[3][chunk[4] as usize]
+                ^ t[2][chunk[5] as usize]
+                ^ t[1][chunk[6] as usize]
+                ^ t[0][chunk[7] as usize];
+        }
+        for &byte in chunks.remainder() {
+            crc = (crc >> 8) ^ t[0][((crc ^ u32::from(byte)) & 0xFF) as usize];
+        }
+        self.state = crc;
+    }
+
+    fn finalize(&self) -> u32 {
+        !self.state
+    }
+}
+
+/// xxHash64 digest folded to 32 bits for fields sized for a CRC32.
+pub struct XxHash64 {
+    inner: xxhash_rust::xxh64::Xxh64,
+}
+
+impl Default for XxHash64 {
+    fn default() -> Self {
+        Self {
+            inner: xxhash_rust::xxh64::Xxh64::new(0),
+        }
+    }
+}
+
+impl Checksum for XxHash64 {
+    fn reset(&mut self) {
+        self.inner.reset(0);
+    }
+
+    fn update(&mut self, bytes: &[u8]) {
+        self.inner.update(bytes);
+    }
+
+    fn finalize(&self) -> u32 {
+        let digest = self.inner.digest();
+        (digest ^ (digest >> 32)) as u32
+    }
+}
+
+/// Checksum algorithm selectable for on-disk structures that record it.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub enum ChecksumKind {
+    /// CRC32 (IEEE), the original algorithm.
+    #[default]
+    Crc32,
+    /// CRC32C (Castagnoli).
+    Crc32c,
+    /// xxHash64, folded to 32 bits.
+    XxHash64,
+}
+
+impl ChecksumKind {
+    /// Returns the code stored on disk for this algorithm.
+    pub fn code(self) -> u16 {
+        match self {
+            ChecksumKind::Crc32 => 0,
+            ChecksumKind::Crc32c => 1,
+            ChecksumKind::XxHash64 => 2,
+        }
+    }
+
+    /// Returns the algorithm stored as `code`, if this build supports it.
+    pub fn from_code(code: u16) -> Option<Self> {
+        match code {
+            0 => Some(ChecksumKind::Crc32),
+            1 => Some(ChecksumKind::Crc32c),
+            2 => Some(ChecksumKind::XxHash64),
+            _ => None,
+        }
+    }
+
+    /// Creates a fresh hasher for this algorithm.
+    pub fn hasher(self) -> AnyChecksum {
+        match self {
+            ChecksumKind::Crc32 => AnyChecksum::Crc32(Crc32Fast::default()),
+            ChecksumKind::Crc32c => AnyChecksum::Crc32c(Crc32c::default()),
+            ChecksumKind::XxHash64 => AnyChecksum::XxHash64(XxHash64::default()),
+        }
+    }
+
+    /// Checksums the concatenation of `chunks`.
+    pub fn checksum(self, chunks: &[&[u8]]) -> u32 {
+        let mut hasher = self.hasher();
+        for chunk in chunks {
+            hasher.update(chunk);
+        }
+        hasher.finalize()
+    }
+}
+
+/// Hasher for whichever algorithm a [`ChecksumKind`] names.
+pub enum AnyChecksum {
+    /// CRC32 (IEEE).
+    Crc32(Crc32Fast),
+    /// CRC32C (Castagnoli).
+    Crc32c(Crc32c),
+    /// xxHash64, folded to 32 bits.
+    XxHash64(XxHash64),
+}
+
+impl Checksum for AnyChecksum {
+    fn reset(&mut self) {
+        match self {
+            AnyChecksum::Crc32(h) => h.reset(),
+            AnyChecksum::Crc32c(h) => h.reset(),
+            AnyChecksum::XxHash64(h) => h.reset(),
+        }
+    }
+
+    fn update(&mut self, bytes: &[u8]) {
+        match self {
+            AnyChecksum::Crc32(h) => h.update(bytes),
+            AnyChecksum::Crc32c(h) => h.update(bytes),
+            AnyChecksum::XxHash64(h) => h.update(bytes),
+        }
+    }
+
+    fn finalize(&self) -> u32 {
+        match self {
+            AnyChecksum::Crc32(h) => h.finalize(),
+            AnyChecksum::Crc32c(h) => h.finalize(),
+            AnyChecksum::XxHash64(h) => h.finalize(),
+        }
+    }
+}
+
 /// Computes a CRC32 checksum for a page, incorporating the page number and salt.
 pub fn page_crc32(page_no: u64, salt: u64, payload: &[u8]) -> u32 {
     let mut hasher = crc32fast::Hasher::new();
@@ -63,6 +265,48 @@ mod tests {
         assert_eq!(c.finalize(), second);
     }
 
+    #[test]
+    fn crc32_resume_extends_finalized_checksum() {
+        let mut resumed = Crc32Fast::resume(crc32fast::hash(b"hello"));
+        resumed.update(b" world");
+        assert_eq!(resumed.finalize(), crc32fast::hash(b"hello world"));
+    }
+
+    #[test]
+    fn crc32c_matches_reference_vectors() {
+        assert_eq!(ChecksumKind::Crc32c.checksum(&[b"123456789"]), 0xE306_9283);
+        assert_eq!(ChecksumKind::Crc32c.checksum(&[&[0u8; 32]]), 0x8A91_36AA);
+        let data: Vec<u8> = (0..=255u8).cycle().take(1000).collect();
+        let whole = ChecksumKind::Crc32c.checksum(&[&data]);
+        assert_eq!(
+            ChecksumKind::Crc32c.checksum(&[&data[..3], &data[3..517], &data[517..]]),
+            whole
+        );
+    }
+
+    #[test]
+    fn checksum_kinds_roundtrip_codes_and_differ() {
+        let kinds = [
+            ChecksumKind::Crc32,
+            ChecksumKind::Crc32c,
+            ChecksumKind::XxHash64,
+        ];
+        for kind in kinds {
+            assert_eq!(ChecksumKind::from_code(kind.code()), Some(kind));
+        }
+        assert_eq!(ChecksumKind::from_code(7), None);
+        assert_eq!(
+            ChecksumKind::Crc32.checksum(&[b"hello world"]),
+            crc32fast::hash(b"hello world")
+        );
+        let digests: Vec<u32> = kinds
+            .iter()
+            .map(|kind| kind.checksum(&[b"hello world"]))
+            .collect();
+        assert_ne!(digests[0], digests[1]);
+        assert_ne!(digests[1], digests[2]);
+    }
+
     #[test]
     fn page_crc32_changes_with_components() {
         let payload = vec![0u8; 16];
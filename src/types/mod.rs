@@ -7,7 +7,7 @@
 
 /// Checksum computation for data integrity.
 pub mod checksum;
-pub use checksum::{page_crc32, Checksum, Crc32Fast};
+pub use checksum::{page_crc32, AnyChecksum, Checksum, ChecksumKind, Crc32Fast, Crc32c, XxHash64};
 
 use std::fmt;
 
@@ -85,6 +85,12 @@ pub enum SombraError {
     /// Write-write conflict detected during commit.
     #[error("write conflict: {0}")]
     Conflict(String),
+    /// Work exceeded its time budget and was aborted.
+    #[error("timeout: {0}")]
+    Timeout(String),
+    /// Write rejected because it would break a schema constraint.
+    #[error("constraint violation: {0}")]
+    ConstraintViolation(String),
 }
 
 /// Result type for Sombra operations using [`SombraError`].
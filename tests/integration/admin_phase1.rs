@@ -7,12 +7,18 @@ use std::sync::Arc;
 
 use sombra::{
     admin::{
-        checkpoint, promote_vacuumed_copy, stats, vacuum_into, verify, AdminOpenOptions,
-        CheckpointMode, VacuumOptions, VerifyLevel,
+        backup, checkpoint, incremental_vacuum, open_graph, promote_vacuumed_copy, repair, stats,
+        vacuum_into, verify, AdminOpenOptions, CheckpointMode, IncrementalVacuumOptions,
+        RepairOptions, RepairTarget, VacuumOptions, VerifyLevel,
     },
     primitives::pager::{PageStore, Pager, PagerOptions},
-    storage::{EdgeSpec, Graph, GraphOptions, NodeSpec, PropEntry, PropValue},
-    types::{LabelId, PropId, TypeId},
+    storage::{
+        btree::{BTree, BTreeOptions},
+        catalog::{Dict, DictOptions},
+        EdgeSpec, Graph, GraphOptions, IndexDef, IndexKind, IndexState, NodeSpec, PropEntry,
+        PropValue, TypeTag,
+    },
+    types::{LabelId, NodeId, PropId, TypeId},
 };
 use tempfile::TempDir;
 
@@ -128,6 +134,195 @@ fn vacuum_replace_promotes_copy_and_backs_up() {
     );
 }
 
+#[test]
+fn incremental_vacuum_shrinks_file_in_bounded_passes() {
+    let (_dir, db_path) = setup_db("admin-incremental-vacuum");
+    let handle = open_graph(&db_path, &admin_opts()).expect("open graph");
+    let store: Arc<dyn PageStore> = handle.pager.clone();
+    let tree =
+        BTree::<u64, Vec<u8>>::open_or_create(&store, BTreeOptions::default()).expect("open tree");
+    let value = |key: u64| vec![(key % 251) as u8; 200];
+
+    let mut write = handle.pager.begin_write().expect("begin write");
+    for key in 0..2_000u64 {
+        tree.put(&mut write, &key, &value(key)).expect("put");
+    }
+    handle.pager.commit(write).expect("commit inserts");
+    let mut write = handle.pager.begin_write().expect("begin write");
+    for key in 0..1_500u64 {
+        tree.delete(&mut write, &key).expect("delete");
+    }
+    handle.pager.commit(write).expect("commit deletes");
+    handle
+        .pager
+        .checkpoint(CheckpointMode::Force)
+        .expect("checkpoint");
+    let pages_before = handle.pager.meta().expect("meta").next_page.0;
+
+    let opts = IncrementalVacuumOptions { max_pages: 4 };
+    let mut reclaimed = 0;
+    let mut passes = 0;
+    loop {
+        let report = incremental_vacuum(&handle, &opts).expect("incremental vacuum");
+        assert!(report.pages_relocated <= opts.max_pages);
+        reclaimed += report.pages_reclaimed;
+        passes += 1;
+        assert!(passes < 1_000, "incremental vacuum never settled");
+        if !report.more_work {
+            break;
+        }
+    }
+    let pages_after = handle.pager.meta().expect("meta").next_page.0;
+    assert!(passes > 1, "expected several bounded passes");
+    assert!(pages_after < pages_before);
+    assert_eq!(pages_before - pages_after, reclaimed);
+
+    let read = handle.pager.begin_latest_committed_read().expect("read");
+    for key in 0..2_000u64 {
+        let expected = (key >= 1_500).then(|| value(key));
+        assert_eq!(tree.get(&read, &key).expect("get"), expected, "key {key}");
+    }
+    drop(read);
+    drop(tree);
+    drop(store);
+    drop(handle);
+
+    let report = verify(&db_path, &admin_opts(), VerifyLevel::Full).expect("verify");
+    assert!(
+        report.success,
+        "verification findings: {:?}",
+        report.findings
+    );
+}
+
+#[test]
+fn incremental_vacuum_skips_unmovable_tail_pages() {
+    let (_dir, db_path) = setup_db("admin-incremental-vacuum-blocked");
+    let handle = open_graph(&db_path, &admin_opts()).expect("open graph");
+    let store: Arc<dyn PageStore> = handle.pager.clone();
+    let tree =
+        BTree::<u64, Vec<u8>>::open_or_create(&store, BTreeOptions::default()).expect("open tree");
+    let value = |key: u64| vec![(key % 251) as u8; 200];
+
+    let mut write = handle.pager.begin_write().expect("begin write");
+    for key in 0..2_000u64 {
+        tree.put(&mut write, &key, &value(key)).expect("put");
+    }
+    handle.pager.commit(write).expect("commit inserts");
+    // A second tree's root lands at the end of the file and cannot move.
+    let pinned =
+        BTree::<u64, Vec<u8>>::open_or_create(&store, BTreeOptions::default()).expect("open tree");
+    let mut write = handle.pager.begin_write().expect("begin write");
+    pinned.put(&mut write, &1, &value(1)).expect("put");
+    for key in 0..1_500u64 {
+        tree.delete(&mut write, &key).expect("delete");
+    }
+    handle.pager.commit(write).expect("commit deletes");
+    handle
+        .pager
+        .checkpoint(CheckpointMode::Force)
+        .expect("checkpoint");
+    let root = pinned.root_page().0;
+    assert_eq!(handle.pager.meta().expect("meta").next_page.0, root + 1);
+
+    let opts = IncrementalVacuumOptions { max_pages: 4 };
+    let mut relocated = 0;
+    let mut passes = 0;
+    loop {
+        let report = incremental_vacuum(&handle, &opts).expect("incremental vacuum");
+        assert_eq!(report.blocked_at, Some(root));
+        assert_eq!(report.pages_after, root + 1);
+        relocated += report.pages_relocated;
+        passes += 1;
+        assert!(passes < 1_000, "incremental vacuum never settled");
+        if !report.more_work {
+            break;
+        }
+    }
+    assert!(relocated > 0, "pages below the root should still move");
+
+    let read = handle.pager.begin_latest_committed_read().expect("read");
+    for key in 0..2_000u64 {
+        let expected = (key >= 1_500).then(|| value(key));
+        assert_eq!(tree.get(&read, &key).expect("get"), expected, "key {key}");
+    }
+    assert_eq!(pinned.get(&read, &1).expect("get"), Some(value(1)));
+    drop(read);
+    drop((tree, pinned, store, handle));
+
+    let report = verify(&db_path, &admin_opts(), VerifyLevel::Full).expect("verify");
+    assert!(
+        report.success,
+        "verification findings: {:?}",
+        report.findings
+    );
+}
+
+#[test]
+fn backup_copies_snapshot_of_open_database() {
+    let (dir, db_path) = setup_db("admin-backup");
+    let handle = open_graph(&db_path, &admin_opts()).expect("open graph");
+    let store: Arc<dyn PageStore> = handle.pager.clone();
+    let tree =
+        BTree::<u64, Vec<u8>>::open_or_create(&store, BTreeOptions::default()).expect("open tree");
+    let mut write = handle.pager.begin_write().expect("begin write");
+    for key in 0..500u64 {
+        tree.put(&mut write, &key, &vec![7u8; 200]).expect("put");
+    }
+    for key in 0..400u64 {
+        tree.delete(&mut write, &key).expect("delete");
+    }
+    for idx in 0..10 {
+        handle
+            .graph
+            .create_node(
+                &mut write,
+                NodeSpec {
+                    labels: &[LabelId(1)],
+                    props: &[PropEntry::new(PropId(1), PropValue::Int(idx))],
+                },
+            )
+            .expect("create node");
+    }
+    handle.pager.commit(write).expect("commit");
+
+    let dest = dir.path().join("backup.sombra");
+    let report = backup(&handle, &dest).expect("backup");
+    assert!(report.pages_copied > 0);
+    assert!(report.snapshot_lsn >= report.checkpoint_lsn);
+    assert_eq!(
+        report.backup_bytes,
+        fs::metadata(&dest).expect("meta").len()
+    );
+    assert!(backup(&handle, &dest).is_err(), "existing destination");
+
+    let mut write = handle.pager.begin_write().expect("begin write");
+    handle
+        .graph
+        .create_node(
+            &mut write,
+            NodeSpec {
+                labels: &[LabelId(1)],
+                props: &[],
+            },
+        )
+        .expect("create node");
+    handle.pager.commit(write).expect("commit after backup");
+    drop(tree);
+    drop(store);
+    drop(handle);
+
+    let report = verify(&dest, &admin_opts(), VerifyLevel::Full).expect("verify backup");
+    assert!(
+        report.success,
+        "verification findings: {:?}",
+        report.findings
+    );
+    assert_eq!(report.counts.nodes_found, 12);
+    let report = verify(&db_path, &admin_opts(), VerifyLevel::Full).expect("verify source");
+    assert_eq!(report.counts.nodes_found, 13);
+}
+
 #[test]
 fn verify_full_reports_counts() {
     let (_dir, db_path) = setup_db("admin-verify");
@@ -142,3 +337,268 @@ fn verify_full_reports_counts() {
     assert_eq!(report.counts.edges_found, 1);
     assert!(report.counts.adjacency_entries >= 2);
 }
+
+/// Seeds three indexed `User` nodes and two `alice -> bob` edges, then drops
+/// the row of carol (who has no edges) and of the second edge, leaving their
+/// index and adjacency entries behind.
+fn seed_orphans(path: &Path) -> sombra::types::Result<()> {
+    let pager = Arc::new(Pager::create(path, PagerOptions::default())?);
+    let store: Arc<dyn PageStore> = pager.clone();
+    let graph = Graph::open(GraphOptions::new(store.clone()))?;
+
+    let mut write = pager.begin_write()?;
+    let mut users = Vec::new();
+    for name in ["Alice", "Bob", "Carol"] {
+        users.push(graph.create_node(
+            &mut write,
+            NodeSpec {
+                labels: &[LabelId(1)],
+                props: &[PropEntry::new(PropId(1), PropValue::Str(name))],
+            },
+        )?);
+    }
+    let mut edges = Vec::new();
+    for _ in 0..2 {
+        edges.push(graph.create_edge(
+            &mut write,
+            EdgeSpec {
+                src: users[0],
+                dst: users[1],
+                ty: TypeId(1),
+                props: &[],
+            },
+        )?);
+    }
+    pager.commit(write)?;
+
+    let mut write = pager.begin_write()?;
+    graph.create_label_index(&mut write, LabelId(1))?;
+    graph.create_property_index(
+        &mut write,
+        IndexDef {
+            label: LabelId(1),
+            props: vec![PropId(1)],
+            kind: IndexKind::BTree,
+            types: vec![TypeTag::String],
+            max_key_bytes: None,
+            unique: false,
+            state: IndexState::Ready,
+        },
+    )?;
+    pager.commit(write)?;
+    pager.checkpoint(CheckpointMode::Force)?;
+    drop(graph);
+
+    let meta = store.meta()?;
+    let open_rows = |root| {
+        let mut opts = BTreeOptions::default();
+        opts.root_page = Some(root);
+        BTree::<u64, Vec<u8>>::open_or_create(&store, opts)
+    };
+    let nodes = open_rows(meta.storage_nodes_root)?;
+    let edge_rows = open_rows(meta.storage_edges_root)?;
+    let mut write = pager.begin_write()?;
+    assert!(nodes.delete(&mut write, &users[2].0)?);
+    assert!(edge_rows.delete(&mut write, &edges[1].0)?);
+    let (nodes_root, edges_root) = (nodes.root_page(), edge_rows.root_page());
+    write.update_meta(|meta| {
+        meta.storage_nodes_root = nodes_root;
+        meta.storage_edges_root = edges_root;
+    })?;
+    pager.commit(write)?;
+    pager.checkpoint(CheckpointMode::Force)?;
+    Ok(())
+}
+
+#[test]
+fn repair_rebuilds_adjacency_and_indexes_from_records() {
+    let dir = TempDir::new().expect("tempdir");
+    let db_path = dir.path().join("admin-repair.sombra");
+    seed_orphans(&db_path).expect("seed orphaned entries");
+    let opts = admin_opts();
+
+    let report = verify(&db_path, &opts, VerifyLevel::Full).expect("verify");
+    assert!(!report.success);
+    assert!(report.repairs.is_empty());
+    let messages: Vec<&str> = report
+        .findings
+        .iter()
+        .map(|finding| finding.message.as_str())
+        .collect();
+    for expected in [
+        "adjacency references missing edge 2",
+        "label index on label 1 lists 1 stale and misses 0 nodes",
+        "property index on label 1 property 1 lists 1 stale and misses 0 nodes",
+    ] {
+        assert!(messages.contains(&expected), "{messages:?}");
+    }
+
+    let report = repair(
+        &db_path,
+        &opts,
+        VerifyLevel::Full,
+        &RepairOptions::default(),
+    )
+    .expect("repair");
+    assert!(
+        report.success,
+        "verification findings: {:?}",
+        report.findings
+    );
+    assert_eq!(report.counts.nodes_found, 2);
+    assert_eq!(report.counts.edges_found, 1);
+    let targets: Vec<_> = report
+        .repairs
+        .iter()
+        .map(|repair| format!("{:?}: {}", repair.target, repair.message))
+        .collect();
+    assert_eq!(
+        targets,
+        [
+            "Adjacency: removed adjacency entries for edge 2 (1 -> 2 type 1)",
+            "PropertyIndex: rebuilt property index on label 1 property 1, dropping 1 stale and adding 0 missing nodes",
+            "LabelIndex: rebuilt label index on label 1, dropping 1 stale and adding 0 missing nodes",
+        ]
+    );
+    assert!(matches!(report.repairs[0].target, RepairTarget::Adjacency));
+
+    let handle = open_graph(&db_path, &opts).expect("open repaired graph");
+    let read = handle.pager.begin_latest_committed_read().expect("read");
+    assert_eq!(
+        handle
+            .graph
+            .nodes_with_label(&read, LabelId(1))
+            .expect("scan"),
+        vec![NodeId(1), NodeId(2)]
+    );
+    drop(read);
+    drop(handle);
+    let report = repair(
+        &db_path,
+        &opts,
+        VerifyLevel::Full,
+        &RepairOptions::default(),
+    )
+    .expect("repair clean database");
+    assert!(report.success && report.repairs.is_empty());
+}
+
+fn seed_interned(path: &Path) -> sombra::types::Result<()> {
+    let pager = Arc::new(Pager::create(path, PagerOptions::default())?);
+    let store: Arc<dyn PageStore> = pager.clone();
+    let graph = Graph::open(GraphOptions::new(store.clone()))?;
+    let dict = Dict::open(store, DictOptions::default())?;
+
+    let mut write = pager.begin_write()?;
+    let user = LabelId(dict.intern(&mut write, "User")?.0);
+    let name = PropId(dict.intern(&mut write, "name")?.0);
+    let knows = TypeId(dict.intern(&mut write, "KNOWS")?.0);
+    let alice = graph.create_node(
+        &mut write,
+        NodeSpec {
+            labels: &[user],
+            props: &[PropEntry::new(name, PropValue::Str("Alice"))],
+        },
+    )?;
+    let bob = graph.create_node(
+        &mut write,
+        NodeSpec {
+            labels: &[user],
+            props: &[PropEntry::new(name, PropValue::Str("Bob"))],
+        },
+    )?;
+    graph.create_edge(
+        &mut write,
+        EdgeSpec {
+            src: alice,
+            dst: bob,
+            ty: knows,
+            props: &[],
+        },
+    )?;
+    pager.commit(write)?;
+    pager.checkpoint(CheckpointMode::Force)?;
+    Ok(())
+}
+
+/// Rewrites the dictionary's string-to-id index behind its back: `name` loses
+/// its entry and `Ghost` claims the id already owned by `User`.
+fn corrupt_string_index(path: &Path) -> sombra::types::Result<()> {
+    let pager = Arc::new(Pager::open(path, PagerOptions::default())?);
+    let store: Arc<dyn PageStore> = pager.clone();
+    let mut opts = BTreeOptions::default();
+    opts.root_page = Some(store.meta()?.dict_str_to_id_root);
+    let s2i = BTree::<Vec<u8>, u64>::open_or_create(&store, opts)?;
+    let key = |s: &str| {
+        let mut key = (s.len() as u32).to_be_bytes().to_vec();
+        key.extend_from_slice(s.as_bytes());
+        key
+    };
+
+    let mut write = pager.begin_write()?;
+    let user_id = s2i.get_with_write(&mut write, &key("User"))?.expect("User");
+    assert!(s2i.delete(&mut write, &key("name"))?);
+    s2i.put(&mut write, &key("Ghost"), &user_id)?;
+    let root = s2i.root_page();
+    write.update_meta(|meta| meta.dict_str_to_id_root = root)?;
+    pager.commit(write)?;
+    pager.checkpoint(CheckpointMode::Force)?;
+    Ok(())
+}
+
+#[test]
+fn verify_catalog_reports_dictionary_corruption() {
+    let dir = TempDir::new().expect("tempdir");
+    let db_path = dir.path().join("admin-verify-catalog.sombra");
+    seed_interned(&db_path).expect("seed interned data");
+    let opts = admin_opts();
+
+    let report = verify(&db_path, &opts, VerifyLevel::Catalog).expect("verify");
+    assert!(
+        report.success,
+        "verification findings: {:?}",
+        report.findings
+    );
+    assert_eq!(report.counts.dictionary_entries, 3);
+
+    corrupt_string_index(&db_path).expect("corrupt dictionary");
+    let full = verify(&db_path, &opts, VerifyLevel::Full).expect("verify");
+    assert!(full.success, "full level skips the dictionary");
+
+    let report = verify(&db_path, &opts, VerifyLevel::Catalog).expect("verify");
+    assert!(!report.success);
+    let messages: Vec<&str> = report
+        .findings
+        .iter()
+        .map(|finding| finding.message.as_str())
+        .collect();
+    assert!(
+        messages
+            .iter()
+            .any(|msg| msg.contains("\"name\"") && msg.contains("missing from string index")),
+        "{messages:?}"
+    );
+    assert!(
+        messages
+            .iter()
+            .any(|msg| msg.contains("claimed by both \"User\" and \"Ghost\"")),
+        "{messages:?}"
+    );
+}
+
+#[test]
+fn verify_catalog_reports_unresolved_ids() {
+    let (_dir, db_path) = setup_db("admin-verify-unresolved");
+    let opts = admin_opts();
+    let report = verify(&db_path, &opts, VerifyLevel::Catalog).expect("verify");
+    assert!(!report.success);
+    assert!(
+        report
+            .findings
+            .iter()
+            .any(|finding| finding.message
+                == "node 1 label references dictionary id 1 with no entry"),
+        "{:?}",
+        report.findings
+    );
+}
@@ -1,6 +1,7 @@
 #![allow(missing_docs)]
 #![allow(clippy::arc_with_non_send_sync, clippy::field_reassign_with_default)]
 
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -11,8 +12,8 @@ use sombra::{
     primitives::pager::{CheckpointMode, PageStore, Pager, PagerOptions},
     storage::{
         catalog::{Dict, DictOptions},
-        EdgeSpec, Graph, GraphOptions, IndexDef, IndexKind, NodeSpec, PropEntry, PropValue,
-        PropValueOwned, TypeTag,
+        EdgeSpec, Graph, GraphOptions, IndexDef, IndexKind, IndexState, NodeSpec, PropEntry,
+        PropValue, PropValueOwned, TypeTag,
     },
     types::{LabelId, PropId, TypeId},
 };
@@ -83,9 +84,12 @@ fn create_age_index(path: &Path) {
         .expect("create label index");
     let def = IndexDef {
         label: LabelId(label_id.0),
-        prop: PropId(prop_age.0),
+        props: vec![PropId(prop_age.0)],
         kind: IndexKind::BTree,
-        ty: TypeTag::Int,
+        types: vec![TypeTag::Int],
+        max_key_bytes: None,
+        unique: false,
+        state: IndexState::Ready,
     };
     graph
         .create_property_index(&mut write, def)
@@ -244,6 +248,26 @@ fn vacuum_analyze_reports_summary() {
     assert!(dst.exists());
 }
 
+#[test]
+fn backup_reports_snapshot_lsn() {
+    let (dir, db_path) = setup_db("backup");
+    let dst = dir.path().join("backup-copy.sombra");
+    let output = cargo_bin_cmd!("cli")
+        .args(["--format", "json", "backup"])
+        .arg(&db_path)
+        .arg("--into")
+        .arg(&dst)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let json: Value = serde_json::from_slice(&output).expect("valid json");
+    assert!(json["snapshot_lsn"].as_u64().is_some());
+    assert!(json["pages_copied"].as_u64().unwrap_or(0) > 0);
+    assert!(dst.exists(), "backup destination file should be created");
+}
+
 #[test]
 fn import_and_export_round_trip() {
     let dir = TempDir::new().expect("tempdir");
@@ -378,3 +402,122 @@ fn import_and_export_round_trip() {
 
     assert_property_index_has_values(&db_path, &[41, 36]);
 }
+
+#[test]
+fn export_import_offsets_merge_graphs_without_collisions() {
+    let (dir, graph_a) = setup_db("graph_a");
+    let graph_b = dir.path().join("graph_b.sombra");
+    let merged = dir.path().join("merged.sombra");
+    let fixtures = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/import");
+    cargo_bin_cmd!("cli")
+        .args([
+            "import",
+            graph_b.to_str().unwrap(),
+            "--nodes",
+            fixtures.join("people_nodes.csv").to_str().unwrap(),
+            "--node-props",
+            "name",
+            "--node-label-column",
+            "labels",
+            "--edges",
+            fixtures.join("follows_edges.csv").to_str().unwrap(),
+            "--edge-type-column",
+            "type",
+            "--edge-props",
+            "weight",
+            "--create",
+        ])
+        .assert()
+        .success();
+
+    let csv_rows = |path: &Path| -> Vec<Vec<String>> {
+        ReaderBuilder::new()
+            .from_path(path)
+            .unwrap()
+            .records()
+            .map(|rec| rec.expect("valid row").iter().map(str::to_string).collect())
+            .collect()
+    };
+    let export = |db: &Path, name: &str, extra: &[&str]| {
+        let nodes = dir.path().join(format!("{name}_nodes.csv"));
+        let edges = dir.path().join(format!("{name}_edges.csv"));
+        cargo_bin_cmd!("cli")
+            .args([
+                "export",
+                db.to_str().unwrap(),
+                "--nodes",
+                nodes.to_str().unwrap(),
+                "--edges",
+                edges.to_str().unwrap(),
+            ])
+            .args(extra)
+            .assert()
+            .success();
+        (nodes, edges)
+    };
+    let (a_nodes, a_edges) = export(&graph_a, "a", &["--id-offset", "1000"]);
+    let (b_nodes, b_edges) = export(&graph_b, "b", &["--id-prefix", "b:"]);
+    assert!(csv_rows(&a_nodes)
+        .iter()
+        .all(|row| row[0].parse::<u64>().unwrap() > 1000));
+    assert!(csv_rows(&b_nodes)
+        .iter()
+        .all(|row| row[0].starts_with("b:")));
+
+    let import = |nodes: &Path, edges: &Path, offset: &str| {
+        cargo_bin_cmd!("cli")
+            .args([
+                "import",
+                merged.to_str().unwrap(),
+                "--nodes",
+                nodes.to_str().unwrap(),
+                "--node-label-column",
+                "labels",
+                "--node-id-property",
+                "origin_id",
+                "--edges",
+                edges.to_str().unwrap(),
+                "--edge-type-column",
+                "type",
+                "--id-offset",
+                offset,
+                "--create",
+            ])
+            .assert()
+            .success();
+    };
+    import(&a_nodes, &a_edges, "0");
+    import(&b_nodes, &b_edges, "5000");
+
+    let (merged_nodes, merged_edges) = export(&merged, "merged", &["--node-props", "origin_id"]);
+    let origin: HashMap<String, String> = csv_rows(&merged_nodes)
+        .into_iter()
+        .map(|row| (row[0].clone(), row[2].clone()))
+        .collect();
+    let mut origin_ids: Vec<&String> = origin.values().collect();
+    origin_ids.sort();
+    origin_ids.dedup();
+    assert_eq!(origin_ids.len(), 4, "origin ids collided: {origin:?}");
+
+    let shift_b = |id: &str| {
+        let raw: u64 = id.trim_start_matches("b:").parse().unwrap();
+        format!("b:{}", raw + 5000)
+    };
+    let mut expected: Vec<(String, String)> = csv_rows(&a_edges)
+        .into_iter()
+        .map(|row| (row[0].clone(), row[1].clone()))
+        .chain(
+            csv_rows(&b_edges)
+                .into_iter()
+                .map(|row| (shift_b(&row[0]), shift_b(&row[1]))),
+        )
+        .collect();
+    expected.sort();
+    let mut actual: Vec<(String, String)> = csv_rows(&merged_edges)
+        .into_iter()
+        .map(|row| (origin[&row[0]].clone(), origin[&row[1]].clone()))
+        .collect();
+    actual.sort();
+    assert_eq!(actual, expected);
+    assert_eq!(actual.len(), 2);
+}
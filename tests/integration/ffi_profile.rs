@@ -0,0 +1,43 @@
+#![allow(missing_docs)]
+
+//! Kept in its own test binary so no other test moves the process-wide
+//! profile counters while this one compares them.
+
+use serde_json::json;
+use sombra::ffi::{profile_snapshot, Database, DatabaseOptions, Result};
+
+#[test]
+fn profiled_query_reports_local_timings_only() -> Result<()> {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let db = Database::open(dir.path().join("profiled.db"), DatabaseOptions::default())?;
+    db.seed_demo()?;
+    let spec = json!({
+        "$schemaVersion": 1,
+        "matches": [{ "var": "a", "label": "User" }]
+    });
+    let expected = db.execute_json(&spec)?["rows"]
+        .as_array()
+        .expect("rows")
+        .clone();
+
+    let before = profile_snapshot(false);
+    let (rows, profile) = db.execute_json_profiled(&spec)?;
+    let after = profile_snapshot(false);
+
+    assert_eq!(rows, expected);
+    assert_eq!(profile.plan_count, 1);
+    assert_eq!(profile.exec_count, 1);
+    assert_eq!(profile.serde_count, 1);
+    assert!(profile.plan_ns > 0);
+    assert!(profile.exec_ns > 0);
+    assert!(profile.serde_ns > 0);
+    assert_eq!(
+        before.map(|s| (s.plan_count, s.exec_count, s.serde_count)),
+        after.map(|s| (s.plan_count, s.exec_count, s.serde_count))
+    );
+
+    // Each profiled call starts from a fresh local counter set.
+    let (_, again) = db.execute_json_profiled(&spec)?;
+    assert_eq!(again.plan_count, 1);
+    Ok(())
+}
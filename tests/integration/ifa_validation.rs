@@ -10,7 +10,12 @@
 //! - Segment version chain correctness
 
 #![allow(missing_docs)]
-#![allow(clippy::arc_with_non_send_sync, clippy::field_reassign_with_default)]
+#![allow(
+    clippy::arc_with_non_send_sync,
+    clippy::field_reassign_with_default,
+    clippy::uninlined_format_args,
+    clippy::unnecessary_cast
+)]
 
 use std::collections::HashSet;
 use std::sync::Arc;
@@ -0,0 +1,105 @@
+#![allow(missing_docs)]
+// The counting allocator below is the only way to observe allocations.
+#![allow(unsafe_code)]
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+use sombra::primitives::pager::{CheckpointMode, PageStore, Pager, PagerOptions};
+use sombra::types::page::{PageHeader, PageKind, PAGE_HDR_LEN};
+use sombra::types::{PageId, Result};
+use tempfile::tempdir;
+
+thread_local! {
+    static ALLOCATIONS: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Forwards to the system allocator, counting allocations per thread.
+struct CountingAlloc;
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+fn allocations() -> u64 {
+    ALLOCATIONS.with(Cell::get)
+}
+
+fn create_pages(pager: &Pager, count: u32) -> Result<Vec<PageId>> {
+    let meta = pager.meta()?;
+    let mut write = pager.begin_write()?;
+    let mut pages = Vec::new();
+    for i in 0..count {
+        let page = write.allocate_page()?;
+        let mut frame = write.page_mut(page)?;
+        let buf = frame.data_mut();
+        let header =
+            PageHeader::new(page, PageKind::BTreeLeaf, meta.page_size, meta.salt)?.with_crc32(0);
+        header.encode(&mut buf[..PAGE_HDR_LEN])?;
+        buf[PAGE_HDR_LEN..PAGE_HDR_LEN + 4].copy_from_slice(&i.to_be_bytes());
+        drop(frame);
+        pages.push(page);
+    }
+    pager.commit(write)?;
+    pager.checkpoint(CheckpointMode::Force)?;
+    Ok(pages)
+}
+
+#[test]
+fn page_slice_scans_stop_allocating_once_buffers_recycle() -> Result<()> {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("page_slice_allocs.db");
+    let options = PagerOptions {
+        page_size: 4096,
+        ..PagerOptions::default()
+    };
+    let pager = Pager::create(&path, options)?;
+    let pages = create_pages(&pager, 64)?;
+    let (warmup, scan) = pages.split_at(16);
+
+    let read = pager.begin_latest_committed_read()?;
+    let before = allocations();
+    let mut copied = 0u64;
+    for &id in scan {
+        let page = pager.get_page(&read, id)?;
+        copied += u64::from(page.data()[PAGE_HDR_LEN + 3]);
+    }
+    let copy_allocations = allocations() - before;
+    drop(read);
+
+    let mut read = pager.begin_latest_committed_read()?;
+    for &id in warmup {
+        read.page_slice(&pager, id)?;
+    }
+    let before = allocations();
+    let mut sliced = 0u64;
+    for &id in scan {
+        sliced += u64::from(read.page_slice(&pager, id)?[PAGE_HDR_LEN + 3]);
+    }
+    let slice_allocations = allocations() - before;
+
+    assert_eq!(copied, sliced);
+    assert!(copy_allocations >= scan.len() as u64);
+    assert_eq!(slice_allocations, 0);
+    Ok(())
+}
@@ -8,8 +8,8 @@ use sombra::primitives::pager::{PageStore, Pager, PagerOptions};
 use sombra::storage::index::{collect_all, intersect_k, intersect_sorted, PostingStream};
 use sombra::storage::{
     BulkEdgeValidator, CreateEdgeOptions, DeleteNodeOpts, EdgeSpec, Graph, GraphOptions,
-    GraphWriter, IndexDef, IndexKind, LabelScan, NodeSpec, PropEntry, PropPatch, PropPatchOp,
-    PropValue, PropValueOwned, TypeTag,
+    GraphWriter, IndexDef, IndexKind, IndexState, LabelScan, NodeSpec, PropEntry, PropPatch,
+    PropPatchOp, PropValue, PropValueOwned, TypeTag,
 };
 use sombra::types::{LabelId, PropId, Result, SombraError, TypeId};
 use tempfile::tempdir;
@@ -201,9 +201,12 @@ fn property_index_chunked_eq_updates() -> Result<()> {
         &mut write,
         IndexDef {
             label: LabelId(1),
-            prop: PropId(1),
+            props: vec![PropId(1)],
             kind: IndexKind::Chunked,
-            ty: TypeTag::Int,
+            types: vec![TypeTag::Int],
+            max_key_bytes: None,
+            unique: false,
+            state: IndexState::Ready,
         },
     )?;
     pager.commit(write)?;
@@ -332,9 +335,12 @@ fn property_index_btree_eq() -> Result<()> {
         &mut write,
         IndexDef {
             label: LabelId(2),
-            prop: PropId(2),
+            props: vec![PropId(2)],
             kind: IndexKind::BTree,
-            ty: TypeTag::String,
+            types: vec![TypeTag::String],
+            max_key_bytes: None,
+            unique: false,
+            state: IndexState::Ready,
         },
     )?;
     pager.commit(write)?;
@@ -394,9 +400,12 @@ fn property_index_chunked_range() -> Result<()> {
         &mut write,
         IndexDef {
             label: LabelId(3),
-            prop: PropId(1),
+            props: vec![PropId(1)],
             kind: IndexKind::Chunked,
-            ty: TypeTag::Int,
+            types: vec![TypeTag::Int],
+            max_key_bytes: None,
+            unique: false,
+            state: IndexState::Ready,
         },
     )?;
     pager.commit(write)?;
@@ -554,9 +563,12 @@ fn property_index_btree_range() -> Result<()> {
         &mut write,
         IndexDef {
             label: LabelId(4),
-            prop: PropId(2),
+            props: vec![PropId(2)],
             kind: IndexKind::BTree,
-            ty: TypeTag::String,
+            types: vec![TypeTag::String],
+            max_key_bytes: None,
+            unique: false,
+            state: IndexState::Ready,
         },
     )?;
     pager.commit(write)?;
@@ -662,9 +674,12 @@ fn posting_stream_intersection() -> Result<()> {
         &mut write,
         IndexDef {
             label: LabelId(5),
-            prop: PropId(1),
+            props: vec![PropId(1)],
             kind: IndexKind::Chunked,
-            ty: TypeTag::Int,
+            types: vec![TypeTag::Int],
+            max_key_bytes: None,
+            unique: false,
+            state: IndexState::Ready,
         },
     )?;
     pager.commit(write)?;
@@ -753,9 +768,12 @@ fn posting_stream_intersection_btree() -> Result<()> {
         &mut write,
         IndexDef {
             label: LabelId(7),
-            prop: PropId(2),
+            props: vec![PropId(2)],
             kind: IndexKind::BTree,
-            ty: TypeTag::String,
+            types: vec![TypeTag::String],
+            max_key_bytes: None,
+            unique: false,
+            state: IndexState::Ready,
         },
     )?;
     pager.commit(write)?;